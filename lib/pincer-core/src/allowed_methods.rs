@@ -0,0 +1,194 @@
+//! Capabilities advertised by `OPTIONS` responses.
+//!
+//! Supported headers:
+//! - `Allow` (RFC 9110)
+//! - `Access-Control-Allow-Origin`, `Access-Control-Allow-Methods`,
+//!   `Access-Control-Allow-Headers`, `Access-Control-Expose-Headers`,
+//!   `Access-Control-Allow-Credentials`, `Access-Control-Max-Age` (Fetch)
+//!
+//! Malformed values are ignored rather than reported.
+
+use std::time::Duration;
+
+use http::HeaderMap;
+
+use crate::{Method, Response};
+
+/// Methods and CORS policy advertised by the server for a resource.
+///
+/// Returned by `#[options]` methods declared with `pincer::Result<AllowedMethods>`.
+///
+/// # Example
+///
+/// ```
+/// use http::HeaderMap;
+/// use pincer_core::{AllowedMethods, Method};
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("allow", "GET, HEAD, OPTIONS".parse().expect("value"));
+/// headers.insert("access-control-allow-methods", "GET, PUT".parse().expect("value"));
+///
+/// let allowed = AllowedMethods::from_headers(&headers);
+/// assert!(allowed.allows(Method::Put));
+/// assert!(!allowed.allows(Method::Delete));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AllowedMethods {
+    /// Methods from the `Allow` header, uppercased.
+    pub allow: Vec<String>,
+    /// Origin from `Access-Control-Allow-Origin` (`*` for any origin).
+    pub allow_origin: Option<String>,
+    /// Methods from `Access-Control-Allow-Methods`, uppercased.
+    pub allow_methods: Vec<String>,
+    /// Request headers from `Access-Control-Allow-Headers`, lowercased.
+    pub allow_headers: Vec<String>,
+    /// Response headers from `Access-Control-Expose-Headers`, lowercased.
+    pub expose_headers: Vec<String>,
+    /// Whether `Access-Control-Allow-Credentials` is `true`.
+    pub allow_credentials: bool,
+    /// How long the preflight result may be cached, from `Access-Control-Max-Age`.
+    pub max_age: Option<Duration>,
+}
+
+impl AllowedMethods {
+    /// Read the capabilities from `Allow` and `Access-Control-*` headers.
+    ///
+    /// Repeated headers are merged.
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self {
+            allow: tokens(headers, "allow", str::to_ascii_uppercase),
+            allow_origin: find(headers, "access-control-allow-origin").map(str::to_string),
+            allow_methods: tokens(
+                headers,
+                "access-control-allow-methods",
+                str::to_ascii_uppercase,
+            ),
+            allow_headers: tokens(
+                headers,
+                "access-control-allow-headers",
+                str::to_ascii_lowercase,
+            ),
+            expose_headers: tokens(
+                headers,
+                "access-control-expose-headers",
+                str::to_ascii_lowercase,
+            ),
+            allow_credentials: find(headers, "access-control-allow-credentials")
+                .is_some_and(|value| value.eq_ignore_ascii_case("true")),
+            max_age: find(headers, "access-control-max-age")
+                .and_then(|value| value.parse().ok())
+                .map(Duration::from_secs),
+        }
+    }
+
+    /// Returns `true` if the method is listed in `Allow` or
+    /// `Access-Control-Allow-Methods` (a `*` wildcard allows any method).
+    #[must_use]
+    pub fn allows(&self, method: Method) -> bool {
+        let method = method.to_string();
+        self.allow.contains(&method)
+            || self
+                .allow_methods
+                .iter()
+                .any(|allowed| *allowed == method || allowed == "*")
+    }
+
+    /// Returns `true` if the response carried any CORS header.
+    #[must_use]
+    pub fn has_cors(&self) -> bool {
+        self.allow_origin.is_some()
+            || !self.allow_methods.is_empty()
+            || !self.allow_headers.is_empty()
+            || !self.expose_headers.is_empty()
+            || self.allow_credentials
+            || self.max_age.is_some()
+    }
+}
+
+impl<B> Response<B> {
+    /// Get the methods and CORS policy advertised by the response headers.
+    #[must_use]
+    pub fn allowed_methods(&self) -> AllowedMethods {
+        AllowedMethods::from_headers(self.headers())
+    }
+}
+
+fn find<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)?
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Split all values of a comma-separated header into normalized tokens.
+fn tokens(headers: &HeaderMap, name: &str, normalize: fn(&str) -> String) -> Vec<String> {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(normalize)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            let name: http::HeaderName = name.parse().expect("header name");
+            headers.append(name, value.parse().expect("header value"));
+        }
+        headers
+    }
+
+    #[test]
+    fn parses_allow_header() {
+        let allowed = AllowedMethods::from_headers(&headers(&[
+            ("Allow", "get, HEAD"),
+            ("Allow", " options ,"),
+        ]));
+        assert_eq!(allowed.allow, ["GET", "HEAD", "OPTIONS"]);
+        assert!(allowed.allows(Method::Head));
+        assert!(!allowed.allows(Method::Post));
+        assert!(!allowed.has_cors());
+    }
+
+    #[test]
+    fn parses_cors_headers() {
+        let allowed = AllowedMethods::from_headers(&headers(&[
+            ("Access-Control-Allow-Origin", "https://app.example.com"),
+            ("Access-Control-Allow-Methods", "*"),
+            ("Access-Control-Allow-Headers", "Content-Type, X-Request-Id"),
+            ("Access-Control-Expose-Headers", "ETag"),
+            ("Access-Control-Allow-Credentials", "TRUE"),
+            ("Access-Control-Max-Age", "7200"),
+        ]));
+        assert_eq!(
+            allowed.allow_origin.as_deref(),
+            Some("https://app.example.com")
+        );
+        assert_eq!(allowed.allow_headers, ["content-type", "x-request-id"]);
+        assert_eq!(allowed.expose_headers, ["etag"]);
+        assert!(allowed.allow_credentials);
+        assert_eq!(allowed.max_age, Some(Duration::from_hours(2)));
+        assert!(allowed.allows(Method::Delete));
+        assert!(allowed.has_cors());
+    }
+
+    #[test]
+    fn ignores_malformed_values() {
+        let allowed = AllowedMethods::from_headers(&headers(&[
+            ("Access-Control-Max-Age", "soon"),
+            ("Access-Control-Allow-Origin", " "),
+        ]));
+        assert_eq!(allowed, AllowedMethods::default());
+    }
+}
@@ -15,6 +15,10 @@ pub enum ContentType {
     PlainText,
     /// Binary content type (`application/octet-stream`).
     OctetStream,
+    /// XML content type (`application/xml`).
+    Xml,
+    /// CBOR content type (`application/cbor`).
+    Cbor,
 }
 
 impl ContentType {
@@ -26,6 +30,8 @@ impl ContentType {
             Self::FormUrlEncoded => "application/x-www-form-urlencoded",
             Self::PlainText => "text/plain",
             Self::OctetStream => "application/octet-stream",
+            Self::Xml => "application/xml",
+            Self::Cbor => "application/cbor",
         }
     }
 }
@@ -61,6 +67,50 @@ pub fn to_json<T: serde::Serialize>(value: &T) -> Result<Bytes> {
         .map_err(Into::into)
 }
 
+/// Serialize a value to canonical JSON bytes.
+///
+/// Object keys are sorted (recursively) and no insignificant whitespace is
+/// emitted, so the same value always produces the same bytes. This is the
+/// form expected by signing schemes (HMAC, JWS) where the signed payload must
+/// be reproducible.
+///
+/// # Errors
+///
+/// Returns an error if JSON serialization fails.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::to_json_canonical;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Transfer { to: String, amount: u64 }
+///
+/// let transfer = Transfer { to: "alice".to_string(), amount: 10 };
+/// let bytes = to_json_canonical(&transfer).expect("serialize");
+/// assert_eq!(bytes.as_ref(), br#"{"amount":10,"to":"alice"}"#);
+/// ```
+pub fn to_json_canonical<T: serde::Serialize>(value: &T) -> Result<Bytes> {
+    let mut value = serde_json::to_value(value)?;
+    sort_keys(&mut value);
+    serde_json::to_vec(&value)
+        .map(Bytes::from)
+        .map_err(Into::into)
+}
+
+/// Recursively sort object keys (a no-op unless `serde_json/preserve_order` is enabled).
+fn sort_keys(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.sort_keys();
+            map.values_mut().for_each(sort_keys);
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(sort_keys),
+        _ => {}
+    }
+}
+
 /// Serialize a value to form URL-encoded bytes.
 ///
 /// Uses `serde_html_form` which supports `Vec<T>` for repeated form fields
@@ -149,6 +199,590 @@ pub fn from_json<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T> {
     })
 }
 
+/// Deserialize the value at a JSON pointer (RFC 6901, e.g. `/data/items`)
+/// of a JSON document, without defining the structs wrapping it.
+///
+/// The error path starts with the pointer.
+///
+/// # Errors
+///
+/// Returns an error if the document is not valid JSON, it has no value at
+/// `pointer`, or deserialization fails.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::from_json_at;
+///
+/// let bytes = br#"{"data":{"items":[1,2,3],"total":3}}"#;
+/// let items: Vec<u32> = from_json_at(bytes, "/data/items").expect("deserialize");
+/// assert_eq!(items, [1, 2, 3]);
+/// ```
+pub fn from_json_at<T: serde::de::DeserializeOwned>(bytes: &[u8], pointer: &str) -> Result<T> {
+    json_at(bytes, pointer, crate::strict::Options::default())
+}
+
+/// Deserialize YAML bytes to a value.
+///
+/// # Errors
+///
+/// Returns an [`Error::Deserialization`](crate::Error::Deserialization) if
+/// YAML deserialization fails.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::from_yaml;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Config { replicas: u32 }
+///
+/// let config: Config = from_yaml(b"replicas: 3\n").expect("deserialize");
+/// assert_eq!(config, Config { replicas: 3 });
+/// ```
+#[cfg(feature = "yaml")]
+pub fn from_yaml<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    serde_yaml::from_slice(bytes)
+        .map_err(|err| crate::Error::deserialization("YAML", err.to_string()))
+}
+
+/// Deserialize TOML bytes to a value.
+///
+/// # Errors
+///
+/// Returns an [`Error::Deserialization`](crate::Error::Deserialization) if
+/// the bytes are not valid UTF-8 or TOML deserialization fails.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::from_toml;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Manifest { name: String }
+///
+/// let manifest: Manifest = from_toml(b"name = \"pincer\"").expect("deserialize");
+/// assert_eq!(manifest, Manifest { name: "pincer".to_string() });
+/// ```
+#[cfg(feature = "toml")]
+pub fn from_toml<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    let text = std::str::from_utf8(bytes)
+        .map_err(|err| crate::Error::deserialization("TOML", err.to_string()))?;
+    toml::from_str(text).map_err(|err| crate::Error::deserialization("TOML", err.to_string()))
+}
+
+/// Deserialize the value at `pointer` with the decoding `options`.
+pub(crate) fn json_at<T: serde::de::DeserializeOwned>(
+    bytes: &[u8],
+    pointer: &str,
+    options: crate::strict::Options,
+) -> Result<T> {
+    let mut document: serde_json::Value = from_json(bytes)?;
+    let value = document
+        .pointer_mut(pointer)
+        .map(serde_json::Value::take)
+        .ok_or_else(|| crate::Error::json_deserialization(pointer, "no value at JSON pointer"))?;
+    let result = if options == crate::strict::Options::default() {
+        serde_path_to_error::deserialize(value)
+    } else {
+        serde_path_to_error::deserialize(crate::strict::Strict(value, options))
+    };
+    result.map_err(|e| {
+        let path = match e.path().to_string() {
+            root if root == "." => pointer.to_string(),
+            index if index.starts_with('[') => format!("{pointer}{index}"),
+            path => format!("{pointer}.{path}"),
+        };
+        crate::Error::json_deserialization(path, e.inner().to_string())
+    })
+}
+
+/// Serializer and deserializer of request and response bodies in one format.
+///
+/// A client has a codec per format, selected for a whole API with
+/// `#[pincer(json = "...", xml = "...", cbor = "...")]`, the path of a
+/// constant or unit struct implementing the trait:
+/// - JSON: [`SerdeJson`] by default; implement the trait to use another JSON
+///   library on hot paths (e.g. `simd-json` or `sonic-rs`)
+/// - XML: `QuickXml` with the `xml` feature, selected with `xml = "quick_xml"`
+/// - CBOR: `Ciborium` with the `cbor` feature, selected with `cbor = "ciborium"`
+///
+/// Methods send XML or CBOR with `#[body(format = "xml")]` or
+/// `#[body(format = "cbor")]`, and read them with a matching `#[accept(...)]`
+/// or with `#[response(xml)]` or `#[response(cbor)]`.
+///
+/// # Example
+///
+/// ```
+/// use bytes::Bytes;
+/// use pincer_core::{BodyCodec, ContentType, Result, SerdeJson};
+///
+/// /// Codec counting the bodies it encodes.
+/// #[derive(Default)]
+/// struct CountingJson(std::sync::atomic::AtomicUsize);
+///
+/// impl BodyCodec for CountingJson {
+///     fn content_type(&self) -> ContentType {
+///         ContentType::Json
+///     }
+///
+///     fn encode<T: serde::Serialize>(&self, value: &T) -> Result<Bytes> {
+///         self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+///         SerdeJson::new().encode(value)
+///     }
+///
+///     fn decode<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+///         SerdeJson::new().decode(bytes)
+///     }
+/// }
+///
+/// let codec = CountingJson::default();
+/// let body = codec.encode(&[1, 2]).expect("encode");
+/// assert_eq!(body.as_ref(), b"[1,2]");
+/// ```
+pub trait BodyCodec {
+    /// Format of the bodies, e.g. to decode error bodies of that type with
+    /// [`Error::decode_body_with`](crate::Error::decode_body_with).
+    fn content_type(&self) -> ContentType;
+
+    /// Serialize a value to bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    fn encode<T: serde::Serialize>(&self, value: &T) -> Result<Bytes>;
+
+    /// Deserialize a value from bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if deserialization fails.
+    fn decode<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T>;
+}
+
+/// XML codec using `quick-xml` (requires `xml` feature).
+///
+/// The root element of a serialized value is named after its type, e.g.
+/// `<User>` for a `struct User`; rename it with `#[serde(rename = "user")]`.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::{BodyCodec, QuickXml};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// #[serde(rename = "user")]
+/// struct User { name: String }
+///
+/// let user = User { name: "alice".to_string() };
+/// let xml = QuickXml.encode(&user).expect("encode");
+/// assert_eq!(xml.as_ref(), b"<user><name>alice</name></user>");
+/// assert_eq!(QuickXml.decode::<User>(&xml).expect("decode"), user);
+/// ```
+#[cfg(feature = "xml")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuickXml;
+
+#[cfg(feature = "xml")]
+impl BodyCodec for QuickXml {
+    fn content_type(&self) -> ContentType {
+        ContentType::Xml
+    }
+
+    fn encode<T: serde::Serialize>(&self, value: &T) -> Result<Bytes> {
+        quick_xml::se::to_string(value)
+            .map(Bytes::from)
+            .map_err(|err| crate::Error::invalid_request(format!("XML serialization error: {err}")))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        quick_xml::de::from_reader(bytes)
+            .map_err(|err| crate::Error::deserialization("XML", err.to_string()))
+    }
+}
+
+/// CBOR (RFC 8949) codec using `ciborium` (requires `cbor` feature).
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::{BodyCodec, Ciborium};
+///
+/// let cbor = Ciborium.encode(&("alice", 42)).expect("encode");
+/// assert_eq!(cbor.as_ref(), b"\x82\x65alice\x18\x2a");
+/// assert_eq!(Ciborium.decode::<(String, u8)>(&cbor).expect("decode").1, 42);
+/// ```
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ciborium;
+
+#[cfg(feature = "cbor")]
+impl BodyCodec for Ciborium {
+    fn content_type(&self) -> ContentType {
+        ContentType::Cbor
+    }
+
+    fn encode<T: serde::Serialize>(&self, value: &T) -> Result<Bytes> {
+        let mut buffer = Vec::new();
+        ciborium::into_writer(value, &mut buffer).map_err(|err| {
+            crate::Error::invalid_request(format!("CBOR serialization error: {err}"))
+        })?;
+        Ok(Bytes::from(buffer))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        ciborium::from_reader(bytes)
+            .map_err(|err| crate::Error::deserialization("CBOR", err.to_string()))
+    }
+}
+
+/// JSON codec using `serde_json`, the default JSON [`BodyCodec`].
+///
+/// With the default options this is the same as [`to_json`] and
+/// [`from_json`]. The options are:
+///
+/// - [`pretty`](Self::pretty) - indent the output, for debugging
+/// - [`sort_keys`](Self::sort_keys) - sort object keys, as [`to_json_canonical`]
+/// - [`big_integers_as_strings`](Self::big_integers_as_strings) - write
+///   integers outside of ±2^53 (not exactly representable by JavaScript
+///   numbers) as strings, and read numbers written as strings
+/// - [`floats_as_strings`](Self::floats_as_strings) - write floating point
+///   numbers as strings, for APIs expecting decimals as strings, and read
+///   numbers written as strings
+/// - [`deny_unknown_fields`](Self::deny_unknown_fields) - fail to read
+///   unknown fields, as [`from_json_strict`](crate::from_json_strict)
+///
+/// The codec of a whole API is selected with `#[pincer(json = "...")]`, and
+/// the codec of a client with `HyperClient::builder().json_codec(...)`: the
+/// client decodes responses with it, and re-encodes JSON request bodies with
+/// its encoding options, see [`transcode`](Self::transcode).
+///
+/// Struct fields are written in declaration order. Keeping the order of
+/// objects read into `serde_json::Value` and arbitrary precision numbers are
+/// `serde_json` features (`preserve_order`, `arbitrary_precision`) enabled in
+/// your own `Cargo.toml`.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::{BodyCodec, SerdeJson};
+///
+/// const STRICT_JSON: SerdeJson = SerdeJson::new().big_integers_as_strings(true);
+///
+/// let body = STRICT_JSON.encode(&[1, u64::MAX]).expect("encode");
+/// assert_eq!(body.as_ref(), br#"[1,"18446744073709551615"]"#);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct SerdeJson {
+    pretty: bool,
+    sort_keys: bool,
+    big_integers_as_strings: bool,
+    floats_as_strings: bool,
+    deny_unknown_fields: bool,
+}
+
+impl SerdeJson {
+    /// Create a codec with the default `serde_json` behavior.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            pretty: false,
+            sort_keys: false,
+            big_integers_as_strings: false,
+            floats_as_strings: false,
+            deny_unknown_fields: false,
+        }
+    }
+
+    /// Indent encoded bodies.
+    #[must_use]
+    pub const fn pretty(mut self, enabled: bool) -> Self {
+        self.pretty = enabled;
+        self
+    }
+
+    /// Sort object keys (recursively) in encoded bodies.
+    #[must_use]
+    pub const fn sort_keys(mut self, enabled: bool) -> Self {
+        self.sort_keys = enabled;
+        self
+    }
+
+    /// Write integers outside of ±2^53 as strings, and read numbers written
+    /// as strings.
+    #[must_use]
+    pub const fn big_integers_as_strings(mut self, enabled: bool) -> Self {
+        self.big_integers_as_strings = enabled;
+        self
+    }
+
+    /// Write floating point numbers as strings, and read numbers written as
+    /// strings.
+    #[must_use]
+    pub const fn floats_as_strings(mut self, enabled: bool) -> Self {
+        self.floats_as_strings = enabled;
+        self
+    }
+
+    /// Fail to decode bodies with a field unknown to the decoded type.
+    #[must_use]
+    pub const fn deny_unknown_fields(mut self, enabled: bool) -> Self {
+        self.deny_unknown_fields = enabled;
+        self
+    }
+
+    /// Check whether the encoding options are the `serde_json` defaults.
+    #[must_use]
+    pub const fn is_default_encoding(&self) -> bool {
+        !(self.pretty || self.sort_keys || self.big_integers_as_strings || self.floats_as_strings)
+    }
+
+    /// Re-encode a JSON document with the encoding options.
+    ///
+    /// The document is read as a `serde_json::Value`, so numbers beyond
+    /// `u64`/`i64` lose precision unless the `serde_json/arbitrary_precision`
+    /// feature is enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `json` is not valid JSON.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use pincer_core::SerdeJson;
+    ///
+    /// let body = SerdeJson::new().sort_keys(true).transcode(br#"{"b":1,"a":2}"#).expect("transcode");
+    /// assert_eq!(body.as_ref(), br#"{"a":2,"b":1}"#);
+    /// ```
+    pub fn transcode(&self, json: &[u8]) -> Result<Bytes> {
+        let value: serde_json::Value = serde_json::from_slice(json)?;
+        self.encode(&value)
+    }
+
+    /// Decoding options of the [`Strict`](crate::strict::Strict) deserializer.
+    pub(crate) const fn decode_options(self) -> crate::strict::Options {
+        crate::strict::Options {
+            deny_unknown_fields: self.deny_unknown_fields,
+            numbers_from_strings: self.big_integers_as_strings || self.floats_as_strings,
+        }
+    }
+
+    fn write<T: serde::Serialize + ?Sized>(self, out: &mut Vec<u8>, value: &T) -> Result<()> {
+        if self.pretty {
+            let formatter = NumberFormatter {
+                inner: serde_json::ser::PrettyFormatter::new(),
+                codec: self,
+                in_key: false,
+            };
+            value.serialize(&mut serde_json::Serializer::with_formatter(out, formatter))?;
+        } else {
+            let formatter = NumberFormatter {
+                inner: serde_json::ser::CompactFormatter,
+                codec: self,
+                in_key: false,
+            };
+            value.serialize(&mut serde_json::Serializer::with_formatter(out, formatter))?;
+        }
+        Ok(())
+    }
+}
+
+impl BodyCodec for SerdeJson {
+    fn content_type(&self) -> ContentType {
+        ContentType::Json
+    }
+
+    fn encode<T: serde::Serialize>(&self, value: &T) -> Result<Bytes> {
+        if self.is_default_encoding() {
+            return to_json(value);
+        }
+
+        let mut out = Vec::new();
+        if self.sort_keys {
+            let mut value = serde_json::to_value(value)?;
+            sort_keys(&mut value);
+            self.write(&mut out, &value)?;
+        } else {
+            self.write(&mut out, value)?;
+        }
+        Ok(Bytes::from(out))
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+        let options = self.decode_options();
+        if options == crate::strict::Options::default() {
+            return from_json(bytes);
+        }
+        crate::strict::from_json_with(bytes, options)
+    }
+}
+
+/// Largest integer exactly representable by an `f64` (2^53).
+const MAX_SAFE_INTEGER: u64 = 1 << 53;
+
+/// Formatter applying the [`SerdeJson`] number options around `inner`.
+struct NumberFormatter<F> {
+    inner: F,
+    codec: SerdeJson,
+    /// Integer map keys are already written inside quotes
+    in_key: bool,
+}
+
+impl<F: serde_json::ser::Formatter> NumberFormatter<F> {
+    fn quoted<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        quote: bool,
+        write: impl FnOnce(&mut F, &mut W) -> std::io::Result<()>,
+    ) -> std::io::Result<()> {
+        if quote && !self.in_key {
+            writer.write_all(b"\"")?;
+            write(&mut self.inner, writer)?;
+            writer.write_all(b"\"")
+        } else {
+            write(&mut self.inner, writer)
+        }
+    }
+}
+
+impl<F: serde_json::ser::Formatter> serde_json::ser::Formatter for NumberFormatter<F> {
+    fn write_i64<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: i64,
+    ) -> std::io::Result<()> {
+        let quote = self.codec.big_integers_as_strings && value.unsigned_abs() > MAX_SAFE_INTEGER;
+        self.quoted(writer, quote, |inner, writer| {
+            inner.write_i64(writer, value)
+        })
+    }
+
+    fn write_i128<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: i128,
+    ) -> std::io::Result<()> {
+        let quote = self.codec.big_integers_as_strings
+            && value.unsigned_abs() > u128::from(MAX_SAFE_INTEGER);
+        self.quoted(writer, quote, |inner, writer| {
+            inner.write_i128(writer, value)
+        })
+    }
+
+    fn write_u64<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: u64,
+    ) -> std::io::Result<()> {
+        let quote = self.codec.big_integers_as_strings && value > MAX_SAFE_INTEGER;
+        self.quoted(writer, quote, |inner, writer| {
+            inner.write_u64(writer, value)
+        })
+    }
+
+    fn write_u128<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: u128,
+    ) -> std::io::Result<()> {
+        let quote = self.codec.big_integers_as_strings && value > u128::from(MAX_SAFE_INTEGER);
+        self.quoted(writer, quote, |inner, writer| {
+            inner.write_u128(writer, value)
+        })
+    }
+
+    fn write_f32<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: f32,
+    ) -> std::io::Result<()> {
+        let quote = self.codec.floats_as_strings;
+        self.quoted(writer, quote, |inner, writer| {
+            inner.write_f32(writer, value)
+        })
+    }
+
+    fn write_f64<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        value: f64,
+    ) -> std::io::Result<()> {
+        let quote = self.codec.floats_as_strings;
+        self.quoted(writer, quote, |inner, writer| {
+            inner.write_f64(writer, value)
+        })
+    }
+
+    // Layout is delegated so that `inner` can indent
+
+    fn begin_array<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.inner.begin_array(writer)
+    }
+
+    fn end_array<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.inner.end_array(writer)
+    }
+
+    fn begin_array_value<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        self.inner.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        self.inner.end_array_value(writer)
+    }
+
+    fn begin_object<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.inner.begin_object(writer)
+    }
+
+    fn end_object<W: ?Sized + std::io::Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        self.inner.end_object(writer)
+    }
+
+    fn begin_object_key<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> std::io::Result<()> {
+        self.in_key = true;
+        self.inner.begin_object_key(writer, first)
+    }
+
+    fn end_object_key<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        self.in_key = false;
+        self.inner.end_object_key(writer)
+    }
+
+    fn begin_object_value<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        self.inner.begin_object_value(writer)
+    }
+
+    fn end_object_value<W: ?Sized + std::io::Write>(
+        &mut self,
+        writer: &mut W,
+    ) -> std::io::Result<()> {
+        self.inner.end_object_value(writer)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,6 +799,8 @@ mod tests {
             ContentType::OctetStream.as_str(),
             "application/octet-stream"
         );
+        assert_eq!(ContentType::Xml.as_str(), "application/xml");
+        assert_eq!(ContentType::Cbor.as_str(), "application/cbor");
     }
 
     #[test]
@@ -189,6 +825,20 @@ mod tests {
         assert_eq!(bytes.as_ref(), br#"{"name":"Alice","age":30}"#);
     }
 
+    #[test]
+    fn to_json_canonical_sorts_nested_keys() {
+        let value = serde_json::json!({
+            "z": 1,
+            "a": { "y": [ { "b": true, "a": null } ], "x": "s" },
+        });
+
+        let bytes = to_json_canonical(&value).expect("serialize");
+        assert_eq!(
+            bytes.as_ref(),
+            br#"{"a":{"x":"s","y":[{"a":null,"b":true}]},"z":1}"#
+        );
+    }
+
     #[test]
     fn to_form_serialize() {
         #[derive(serde::Serialize)]
@@ -338,4 +988,142 @@ mod tests {
             "Expected field 'city' mentioned in error: {msg}"
         );
     }
+
+    #[test]
+    fn from_json_at_pointer() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Item {
+            id: u32,
+        }
+
+        let bytes = br#"{"data":{"items":[{"id":1},{"id":2,"extra":true}],"total":2}}"#;
+        let items: Vec<Item> = from_json_at(bytes, "/data/items").expect("items");
+        assert_eq!(items, [Item { id: 1 }, Item { id: 2 }]);
+        let total: u32 = from_json_at(bytes, "/data/total").expect("total");
+        assert_eq!(total, 2);
+        let second: Item = from_json_at(bytes, "/data/items/1").expect("item");
+        assert_eq!(second, Item { id: 2 });
+
+        let err = from_json_at::<u32>(bytes, "/data/missing").expect_err("missing");
+        assert_eq!(
+            err.to_string(),
+            "JSON deserialization error at '/data/missing': no value at JSON pointer"
+        );
+        let err =
+            from_json_at::<Vec<Item>>(br#"{"data":[{"id":"x"}]}"#, "/data").expect_err("type");
+        assert!(
+            err.to_string()
+                .starts_with("JSON deserialization error at '/data[0].id'")
+        );
+        let err = json_at::<Vec<Item>>(bytes, "/data/items", crate::strict::Options::STRICT)
+            .expect_err("strict");
+        assert!(
+            err.to_string()
+                .starts_with("JSON deserialization error at '/data/items[1].extra': unknown field")
+        );
+    }
+
+    #[test]
+    fn serde_json_default_matches_to_json() {
+        let value = serde_json::json!({"b": [1, 2.5], "a": u64::MAX});
+        assert_eq!(
+            SerdeJson::new().encode(&value).expect("json"),
+            to_json(&value).expect("json")
+        );
+    }
+
+    #[test]
+    fn serde_json_pretty_and_sorted() {
+        #[derive(serde::Serialize)]
+        struct Point {
+            y: i32,
+            x: i32,
+        }
+
+        let codec = SerdeJson::new().pretty(true).sort_keys(true);
+        let body = codec.encode(&Point { y: 2, x: 1 }).expect("json");
+        assert_eq!(body.as_ref(), b"{\n  \"x\": 1,\n  \"y\": 2\n}");
+
+        let body = SerdeJson::new()
+            .pretty(true)
+            .encode(&Point { y: 2, x: 1 })
+            .expect("json");
+        assert_eq!(body.as_ref(), b"{\n  \"y\": 2,\n  \"x\": 1\n}");
+    }
+
+    #[test]
+    fn serde_json_numbers_as_strings() {
+        let codec = SerdeJson::new().big_integers_as_strings(true);
+        let body = codec
+            .encode(&(
+                9_007_199_254_740_992_u64,
+                9_007_199_254_740_993_u64,
+                -9_007_199_254_740_993_i64,
+            ))
+            .expect("json");
+        assert_eq!(
+            body.as_ref(),
+            br#"[9007199254740992,"9007199254740993","-9007199254740993"]"#
+        );
+
+        let map = std::collections::BTreeMap::from([(u64::MAX, 1.5)]);
+        let body = codec.encode(&map).expect("json");
+        assert_eq!(body.as_ref(), br#"{"18446744073709551615":1.5}"#);
+
+        let body = SerdeJson::new()
+            .floats_as_strings(true)
+            .encode(&map)
+            .expect("json");
+        assert_eq!(body.as_ref(), br#"{"18446744073709551615":"1.5"}"#);
+    }
+
+    #[test]
+    fn serde_json_decode_options() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Account {
+            balance: u64,
+            rate: f64,
+            name: String,
+        }
+
+        let json = br#"{"balance":"18446744073709551615","rate":"0.5","name":"42"}"#;
+        assert!(SerdeJson::new().decode::<Account>(json).is_err());
+        let account: Account = SerdeJson::new()
+            .big_integers_as_strings(true)
+            .decode(json)
+            .expect("numbers as strings");
+        assert_eq!(
+            account,
+            Account {
+                balance: u64::MAX,
+                rate: 0.5,
+                name: "42".to_string(),
+            }
+        );
+
+        let json = br#"{"balance":1,"rate":0.5,"name":"a","extra":true}"#;
+        assert!(SerdeJson::new().decode::<Account>(json).is_ok());
+        let err = SerdeJson::new()
+            .deny_unknown_fields(true)
+            .decode::<Account>(json)
+            .expect_err("unknown field");
+        assert!(
+            err.to_string()
+                .starts_with("JSON deserialization error at 'extra': unknown field"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn serde_json_transcode() {
+        let codec = SerdeJson::new().floats_as_strings(true);
+        let body = codec.transcode(br#"{"rates":[1.5,2]}"#).expect("transcode");
+        assert_eq!(body.as_ref(), br#"{"rates":["1.5",2]}"#);
+        assert!(codec.transcode(b"{").is_err());
+        assert!(
+            SerdeJson::new()
+                .deny_unknown_fields(true)
+                .is_default_encoding()
+        );
+    }
 }
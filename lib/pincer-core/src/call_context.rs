@@ -0,0 +1,117 @@
+//! Call context for middleware access.
+
+use std::collections::BTreeSet;
+
+/// Who a request is made for: tenant, user, locale and feature flags.
+///
+/// Stored in request extensions by callers (`RequestBuilder::extension`) or
+/// by the `CallContextLayer` middleware from the task-local context, so that
+/// auth, header and logging layers can stamp multi-tenant requests without
+/// every service writing its own layer.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::CallContext;
+///
+/// let context = CallContext::new()
+///     .with_tenant_id("acme")
+///     .with_user_id("u-42")
+///     .with_locale("fr-FR")
+///     .with_feature_flag("new-billing");
+///
+/// assert_eq!(context.tenant_id(), Some("acme"));
+/// assert!(context.has_feature_flag("new-billing"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallContext {
+    tenant_id: Option<String>,
+    user_id: Option<String>,
+    locale: Option<String>,
+    feature_flags: BTreeSet<String>,
+}
+
+impl CallContext {
+    /// Create an empty context.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the tenant id.
+    #[must_use]
+    pub fn with_tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Set the user id.
+    #[must_use]
+    pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Set the locale, as a language tag (e.g. `fr-FR`).
+    #[must_use]
+    pub fn with_locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Enable a feature flag.
+    #[must_use]
+    pub fn with_feature_flag(mut self, flag: impl Into<String>) -> Self {
+        self.feature_flags.insert(flag.into());
+        self
+    }
+
+    /// Get the tenant id.
+    #[must_use]
+    pub fn tenant_id(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+
+    /// Get the user id.
+    #[must_use]
+    pub fn user_id(&self) -> Option<&str> {
+        self.user_id.as_deref()
+    }
+
+    /// Get the locale.
+    #[must_use]
+    pub fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+
+    /// Get the enabled feature flags, in lexicographic order.
+    pub fn feature_flags(&self) -> impl Iterator<Item = &str> {
+        self.feature_flags.iter().map(String::as_str)
+    }
+
+    /// Whether the feature flag `flag` is enabled.
+    #[must_use]
+    pub fn has_feature_flag(&self, flag: &str) -> bool {
+        self.feature_flags.contains(flag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn call_context_accessors() {
+        let context = CallContext::new()
+            .with_tenant_id("acme")
+            .with_feature_flag("b")
+            .with_feature_flag("a")
+            .with_feature_flag("b");
+
+        assert_eq!(context.tenant_id(), Some("acme"));
+        assert_eq!(context.user_id(), None);
+        assert_eq!(context.locale(), None);
+        assert_eq!(context.feature_flags().collect::<Vec<_>>(), vec!["a", "b"]);
+        assert!(!context.has_feature_flag("c"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Response body checksum verification (requires `checksum` feature).
+//!
+//! Supported headers:
+//! - `Content-MD5` (RFC 1864)
+//! - `x-amz-checksum-crc32`, `x-amz-checksum-sha1`, `x-amz-checksum-sha256`
+//! - `Digest` (RFC 3230) with `md5`, `sha`, `sha-256` and `sha-512`
+//! - `Content-Digest` (RFC 9530) with `sha-256` and `sha-512`
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use bytes::Bytes;
+use sha1::Digest;
+
+use crate::{Error, Response, Result};
+
+/// Checksum algorithms that can be verified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Algorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+    Crc32,
+}
+
+impl Algorithm {
+    /// Parse an algorithm token from `Digest`/`Content-Digest` headers.
+    fn from_digest_token(token: &str) -> Option<Self> {
+        match token.trim().to_ascii_lowercase().as_str() {
+            "md5" => Some(Self::Md5),
+            "sha" => Some(Self::Sha1),
+            "sha-256" => Some(Self::Sha256),
+            "sha-512" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Md5 => "md5",
+            Self::Sha1 => "sha-1",
+            Self::Sha256 => "sha-256",
+            Self::Sha512 => "sha-512",
+            Self::Crc32 => "crc32",
+        }
+    }
+
+    /// Compute the base64-encoded checksum of `data`.
+    fn compute(self, data: &[u8]) -> String {
+        match self {
+            Self::Md5 => STANDARD.encode(md5::Md5::digest(data)),
+            Self::Sha1 => STANDARD.encode(sha1::Sha1::digest(data)),
+            Self::Sha256 => STANDARD.encode(sha2::Sha256::digest(data)),
+            Self::Sha512 => STANDARD.encode(sha2::Sha512::digest(data)),
+            Self::Crc32 => STANDARD.encode(crc32fast::hash(data).to_be_bytes()),
+        }
+    }
+}
+
+/// Collect the `(algorithm, expected base64 value)` pairs advertised by headers.
+fn advertised_checksums<'a>(
+    headers: impl Iterator<Item = (&'a str, &'a str)>,
+) -> Vec<(Algorithm, String)> {
+    let mut checksums = Vec::new();
+
+    for (name, value) in headers {
+        match name.to_ascii_lowercase().as_str() {
+            "content-md5" => checksums.push((Algorithm::Md5, value.trim().to_string())),
+            "x-amz-checksum-crc32" => checksums.push((Algorithm::Crc32, value.trim().to_string())),
+            "x-amz-checksum-sha1" => checksums.push((Algorithm::Sha1, value.trim().to_string())),
+            "x-amz-checksum-sha256" => {
+                checksums.push((Algorithm::Sha256, value.trim().to_string()));
+            }
+            "digest" | "content-digest" => {
+                for entry in value.split(',') {
+                    let Some((token, encoded)) = entry.split_once('=') else {
+                        continue;
+                    };
+                    if let Some(algorithm) = Algorithm::from_digest_token(token) {
+                        // RFC 9530 wraps values in colons (structured field byte sequence)
+                        let encoded = encoded.trim().trim_matches(':');
+                        checksums.push((algorithm, encoded.to_string()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    checksums
+}
+
+impl Response<Bytes> {
+    /// Verify the body against any checksum headers sent by the server.
+    ///
+    /// Returns `Ok(true)` if at least one supported checksum was verified,
+    /// and `Ok(false)` if the response carries no supported checksum header.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Integrity`] if any advertised checksum does not
+    /// match the received body.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let response = client.execute(request).await?;
+    /// if !response.verify_checksum()? {
+    ///     tracing::warn!("artifact served without checksum");
+    /// }
+    /// ```
+    pub fn verify_checksum(&self) -> Result<bool> {
+        let headers = self
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+        let checksums = advertised_checksums(headers);
+
+        for (algorithm, expected) in &checksums {
+            let actual = algorithm.compute(self.body());
+            if actual != *expected {
+                return Err(Error::Integrity {
+                    algorithm: algorithm.name().to_string(),
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        Ok(!checksums.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{HeaderMap, HeaderName, HeaderValue};
+
+    use super::*;
+
+    fn response(header: &'static str, value: &'static str, body: &'static str) -> Response<Bytes> {
+        let mut headers = HeaderMap::new();
+        let name: HeaderName = header.parse().expect("header name");
+        headers.insert(name, HeaderValue::from_static(value));
+        Response::new(200, headers, Bytes::from_static(body.as_bytes()))
+    }
+
+    #[test]
+    fn verify_content_md5() {
+        // md5("hello") = 5d41402abc4b2a76b9719d911017c592
+        let response = response("content-md5", "XUFAKrxLKna5cZ2REBfFkg==", "hello");
+        assert!(response.verify_checksum().expect("valid checksum"));
+    }
+
+    #[test]
+    fn verify_amz_crc32() {
+        // crc32("hello") = 0x3610a686
+        let response = response("x-amz-checksum-crc32", "NhCmhg==", "hello");
+        assert!(response.verify_checksum().expect("valid checksum"));
+    }
+
+    #[test]
+    fn verify_digest_sha256() {
+        let response = response(
+            "Digest",
+            "SHA-256=LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=",
+            "hello",
+        );
+        assert!(response.verify_checksum().expect("valid checksum"));
+    }
+
+    #[test]
+    fn verify_content_digest_sha256() {
+        let response = response(
+            "content-digest",
+            "sha-256=:LPJNul+wow4m6DsqxbninhsWHlwfp0JecwQzYpOLmCQ=:",
+            "hello",
+        );
+        assert!(response.verify_checksum().expect("valid checksum"));
+    }
+
+    #[test]
+    fn verify_mismatch() {
+        let response = response("content-md5", "XUFAKrxLKna5cZ2REBfFkg==", "tampered");
+        let err = response.verify_checksum().expect_err("mismatch");
+        assert!(err.is_integrity());
+        assert!(err.to_string().contains("md5"));
+    }
+
+    #[test]
+    fn verify_without_checksum() {
+        let response = response("content-type", "application/octet-stream", "hello");
+        assert!(!response.verify_checksum().expect("no checksum"));
+    }
+
+    #[test]
+    fn verify_ignores_unknown_digest_algorithms() {
+        let response = response("digest", "unixsum=30637", "hello");
+        assert!(!response.verify_checksum().expect("no supported checksum"));
+    }
+}
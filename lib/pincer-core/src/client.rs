@@ -2,16 +2,19 @@
 //!
 //! - [`HttpClient`] - Low-level HTTP execution
 //! - [`PincerClient`] - High-level client with base URL (for `#[pincer]` macro)
+//! - [`DynPincerClient`] - Object-safe [`PincerClient`], for `Box<dyn _>` and `Arc<dyn _>`
 //!
 //! Most users should use the `#[pincer]` macro which generates clients automatically.
 //! Implement [`PincerClient`] directly for custom auth or testing.
 
 use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 
 use bytes::Bytes;
 use url::Url;
 
-use crate::{Request, Response, Result};
+use crate::{Request, Response, Result, Runtime};
 
 /// Core HTTP client trait.
 ///
@@ -31,6 +34,14 @@ pub trait HttpClient: Send + Sync {
         &self,
         request: Request<Bytes>,
     ) -> impl Future<Output = Result<Response<Bytes>>> + Send;
+
+    /// Runtime used for timers, such as per-method timeouts.
+    ///
+    /// `None` (the default) uses the Tokio runtime of the `pincer` crate.
+    /// Override it for clients built on another runtime.
+    fn runtime(&self) -> Option<&dyn Runtime> {
+        None
+    }
 }
 
 /// Extension trait for [`HttpClient`] with convenience methods.
@@ -173,6 +184,118 @@ pub trait PincerClient: Clone + Send + Sync {
     ///
     /// All API paths will be resolved relative to this URL.
     fn base_url(&self) -> &Url;
+
+    /// Runtime used for timers, see [`HttpClient::runtime`].
+    fn runtime(&self) -> Option<&dyn Runtime> {
+        None
+    }
+}
+
+// ============================================================================
+// Dyn-Compatible Client Trait
+// ============================================================================
+
+/// Boxed future returned by [`DynPincerClient::execute_dyn`].
+pub type BoxResponseFuture<'a> = Pin<Box<dyn Future<Output = Result<Response<Bytes>>> + Send + 'a>>;
+
+/// Object-safe version of [`PincerClient`].
+///
+/// [`PincerClient`] returns `impl Future` and requires `Clone`, so it cannot
+/// be used as a trait object. This trait boxes the future instead, and is
+/// implemented for every [`PincerClient`].
+///
+/// Both `Box<dyn DynPincerClient>` and `Arc<dyn DynPincerClient>` implement
+/// [`PincerClient`], so they work with `mode = "impl_only"` traits and
+/// `mode = "wrapper"` structs, and the implementation can be chosen at
+/// runtime (real, mock, recording).
+///
+/// # Example
+///
+/// ```ignore
+/// use std::sync::Arc;
+/// use pincer::{DynPincerClient, HyperClient, ApiClient};
+///
+/// let client: Arc<dyn DynPincerClient> = if use_mock {
+///     Arc::new(MockClient::new())
+/// } else {
+///     Arc::new(ApiClient::new(HyperClient::new(), "https://api.example.com")?)
+/// };
+/// let user = client.get_user(42).await?;
+/// ```
+pub trait DynPincerClient: Send + Sync {
+    /// Execute an HTTP request and return the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, see [`PincerClient::execute`].
+    fn execute_dyn(&self, request: Request<Bytes>) -> BoxResponseFuture<'_>;
+
+    /// Get the base URL for this client.
+    fn base_url_dyn(&self) -> &Url;
+
+    /// Get the runtime used for timers.
+    fn runtime_dyn(&self) -> Option<&dyn Runtime>;
+
+    /// Clone this client into a new box.
+    fn clone_box(&self) -> Box<dyn DynPincerClient>;
+}
+
+impl<T: PincerClient + 'static> DynPincerClient for T {
+    fn execute_dyn(&self, request: Request<Bytes>) -> BoxResponseFuture<'_> {
+        Box::pin(self.execute(request))
+    }
+
+    fn base_url_dyn(&self) -> &Url {
+        self.base_url()
+    }
+
+    fn runtime_dyn(&self) -> Option<&dyn Runtime> {
+        self.runtime()
+    }
+
+    fn clone_box(&self) -> Box<dyn DynPincerClient> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn DynPincerClient> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+impl PincerClient for Box<dyn DynPincerClient> {
+    fn execute(
+        &self,
+        request: Request<Bytes>,
+    ) -> impl Future<Output = Result<Response<Bytes>>> + Send {
+        (**self).execute_dyn(request)
+    }
+
+    fn base_url(&self) -> &Url {
+        (**self).base_url_dyn()
+    }
+
+    fn runtime(&self) -> Option<&dyn Runtime> {
+        (**self).runtime_dyn()
+    }
+}
+
+impl PincerClient for Arc<dyn DynPincerClient> {
+    fn execute(
+        &self,
+        request: Request<Bytes>,
+    ) -> impl Future<Output = Result<Response<Bytes>>> + Send {
+        (**self).execute_dyn(request)
+    }
+
+    fn base_url(&self) -> &Url {
+        (**self).base_url_dyn()
+    }
+
+    fn runtime(&self) -> Option<&dyn Runtime> {
+        (**self).runtime_dyn()
+    }
 }
 
 // ============================================================================
@@ -201,4 +324,34 @@ pub trait HttpClientStreaming: HttpClient {
         &self,
         request: Request<Bytes>,
     ) -> impl Future<Output = Result<crate::response::streaming::StreamingResponse>> + Send;
+
+    /// Execute an HTTP request with a streaming body.
+    ///
+    /// The request body, if any, is replaced by `body`. The default
+    /// implementation buffers the whole body and calls [`HttpClient::execute`];
+    /// clients able to send chunks as they are produced should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading the body or the request fails.
+    fn execute_upload(
+        &self,
+        request: Request<Bytes>,
+        body: crate::response::streaming::StreamingBody,
+    ) -> impl Future<Output = Result<Response<Bytes>>> + Send {
+        use futures_util::TryStreamExt;
+
+        async move {
+            let buffered = body
+                .try_fold(bytes::BytesMut::new(), |mut acc, chunk| async move {
+                    acc.extend_from_slice(&chunk);
+                    Ok(acc)
+                })
+                .await?;
+
+            let mut request = request;
+            *request.body_mut() = Some(buffered.freeze());
+            self.execute(request).await
+        }
+    }
 }
@@ -0,0 +1,56 @@
+//! Client name for middleware access.
+
+/// The name of the generated client that sent a request.
+///
+/// Set with `#[pincer(name = "github")]` and stored in request extensions,
+/// so logging, metrics and access logs can tell apart the telemetry of
+/// several clients in one process.
+///
+/// # Example
+///
+/// ```ignore
+/// // In middleware
+/// if let Some(client) = request.extensions().get::<ClientName>() {
+///     println!("Client: {}", client.as_str());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClientName(&'static str);
+
+impl ClientName {
+    /// Create a new client name.
+    #[must_use]
+    pub const fn new(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    /// Get the name.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl std::fmt::Display for ClientName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl AsRef<str> for ClientName {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_name_as_str() {
+        let name = ClientName::new("github");
+        assert_eq!(name.as_str(), "github");
+        assert_eq!(name.to_string(), "github");
+    }
+}
@@ -0,0 +1,437 @@
+//! Cookie storage for session-based APIs.
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use http::HeaderMap;
+use http::header::SET_COOKIE;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::request::is_cookie_name;
+
+/// Cookies received in `Set-Cookie` response headers, replayed on the
+/// requests they match.
+///
+/// Cookies are scoped as in RFC 6265: by domain (the exact host, or its
+/// subdomains with a `Domain` attribute), by path, by `Secure` (HTTPS only),
+/// and dropped once expired (`Max-Age` or `Expires`). The public suffix list
+/// is not checked, so a server can set a cookie for its parent domain.
+///
+/// The store is shared behind an `Arc`, e.g. between a `CookieJarLayer` and
+/// the code logging in.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::CookieStore;
+/// use url::Url;
+///
+/// let store = CookieStore::new();
+/// let login: Url = "https://api.example.com/login".parse().expect("url");
+/// store.insert(&login, "session=abc123; Path=/; Secure; HttpOnly");
+///
+/// let orders: Url = "https://api.example.com/orders".parse().expect("url");
+/// assert_eq!(store.cookie_header(&orders).as_deref(), Some("session=abc123"));
+///
+/// let insecure: Url = "http://api.example.com/orders".parse().expect("url");
+/// assert_eq!(store.cookie_header(&insecure), None);
+/// ```
+#[derive(Debug, Default)]
+pub struct CookieStore {
+    cookies: Mutex<Vec<StoredCookie>>,
+}
+
+/// A cookie with its scope.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct StoredCookie {
+    name: String,
+    value: String,
+    domain: String,
+    host_only: bool,
+    path: String,
+    secure: bool,
+    expires: Option<SystemTime>,
+}
+
+impl StoredCookie {
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    fn matches(&self, url: &Url, host: &str) -> bool {
+        let domain_match = if self.host_only {
+            host == self.domain
+        } else {
+            domain_matches(host, &self.domain)
+        };
+        domain_match && path_matches(url.path(), &self.path) && (!self.secure || is_secure(url))
+    }
+}
+
+impl CookieStore {
+    /// Create an empty store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store the cookies of every `Set-Cookie` header of a response to `url`.
+    pub fn store(&self, url: &Url, headers: &HeaderMap) {
+        let now = SystemTime::now();
+        for value in headers.get_all(SET_COOKIE) {
+            if let Ok(value) = value.to_str() {
+                self.insert_at(url, value, now);
+            }
+        }
+    }
+
+    /// Store the cookie of one `Set-Cookie` header value, received from `url`.
+    ///
+    /// A cookie replaces the stored cookie with the same name, domain and
+    /// path; an expired cookie removes it.
+    ///
+    /// Returns `false` if the cookie is invalid, expired, or its `Domain` does
+    /// not cover the host of `url`.
+    pub fn insert(&self, url: &Url, set_cookie: &str) -> bool {
+        self.insert_at(url, set_cookie, SystemTime::now())
+    }
+
+    fn insert_at(&self, url: &Url, set_cookie: &str, now: SystemTime) -> bool {
+        let Some(cookie) = parse_set_cookie(url, set_cookie, now) else {
+            return false;
+        };
+        let mut cookies = self.lock();
+        cookies.retain(|stored| {
+            (&stored.name, &stored.domain, &stored.path)
+                != (&cookie.name, &cookie.domain, &cookie.path)
+        });
+        if cookie.is_expired(now) {
+            return false;
+        }
+        cookies.push(cookie);
+        true
+    }
+
+    /// Get the `(name, value)` of the cookies to send to `url`, the most
+    /// specific paths first.
+    #[must_use]
+    pub fn cookies(&self, url: &Url) -> Vec<(String, String)> {
+        self.cookies_at(url, SystemTime::now())
+    }
+
+    fn cookies_at(&self, url: &Url, now: SystemTime) -> Vec<(String, String)> {
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+            return Vec::new();
+        };
+        let mut cookies = self.lock();
+        cookies.retain(|cookie| !cookie.is_expired(now));
+        let mut matching: Vec<_> = cookies
+            .iter()
+            .filter(|cookie| cookie.matches(url, &host))
+            .collect();
+        matching.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+        matching
+            .into_iter()
+            .map(|cookie| (cookie.name.clone(), cookie.value.clone()))
+            .collect()
+    }
+
+    /// Get the `Cookie` header value to send to `url`, if any cookie matches.
+    #[must_use]
+    pub fn cookie_header(&self, url: &Url) -> Option<String> {
+        let cookies = self.cookies(url);
+        (!cookies.is_empty()).then(|| {
+            cookies
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+    }
+
+    /// Get the value of the cookie named `name` sent to `url`.
+    #[must_use]
+    pub fn get(&self, url: &Url, name: &str) -> Option<String> {
+        self.cookies(url)
+            .into_iter()
+            .find_map(|(cookie, value)| (cookie == name).then_some(value))
+    }
+
+    /// Serialize the cookies to JSON, e.g. to keep a session between runs.
+    ///
+    /// Expired cookies are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> crate::Result<Vec<u8>> {
+        let now = SystemTime::now();
+        let mut cookies = self.lock();
+        cookies.retain(|cookie| !cookie.is_expired(now));
+        Ok(serde_json::to_vec(&*cookies)?)
+    }
+
+    /// Add the cookies serialized by [`to_json`](Self::to_json), replacing
+    /// the stored cookies with the same name, domain and path.
+    ///
+    /// Returns the number of cookies added, the expired ones being skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `json` is not a serialized cookie store.
+    pub fn load_json(&self, json: &[u8]) -> crate::Result<usize> {
+        let loaded: Vec<StoredCookie> = crate::from_json(json)?;
+        let now = SystemTime::now();
+        let mut cookies = self.lock();
+        let mut count = 0;
+        for cookie in loaded.into_iter().filter(|cookie| !cookie.is_expired(now)) {
+            cookies.retain(|stored| {
+                (&stored.name, &stored.domain, &stored.path)
+                    != (&cookie.name, &cookie.domain, &cookie.path)
+            });
+            cookies.push(cookie);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Remove every cookie, e.g. on logout.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Number of stored cookies, including the expired ones not purged yet.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if no cookie is stored.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<StoredCookie>> {
+        self.cookies.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Parse a `Set-Cookie` header value received from `url`.
+fn parse_set_cookie(url: &Url, set_cookie: &str, now: SystemTime) -> Option<StoredCookie> {
+    let host = url.host_str()?.to_ascii_lowercase();
+    let mut parts = set_cookie.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if !is_cookie_name(name) {
+        return None;
+    }
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value);
+
+    let mut cookie = StoredCookie {
+        name: name.to_string(),
+        value: value.to_string(),
+        domain: host.clone(),
+        host_only: true,
+        path: default_path(url),
+        secure: false,
+        expires: None,
+    };
+    let mut max_age = None;
+    for attribute in parts {
+        let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+        let value = value.trim();
+        match key.trim().to_ascii_lowercase().as_str() {
+            "expires" => cookie.expires = parse_expires(value).or(cookie.expires),
+            "max-age" => {
+                max_age = value
+                    .parse::<i64>()
+                    .ok()
+                    .or_else(|| value.parse::<u64>().ok().map(|_| i64::MAX))
+                    .or(max_age);
+            }
+            "domain" => {
+                let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                if !domain.is_empty() {
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+            }
+            "path" if value.starts_with('/') => cookie.path = value.to_string(),
+            "secure" => cookie.secure = true,
+            _ => {}
+        }
+    }
+    // A Max-Age past the representable dates makes a session cookie
+    if let Some(max_age) = max_age {
+        cookie.expires = match u64::try_from(max_age) {
+            Ok(seconds) if seconds > 0 => now.checked_add(Duration::from_secs(seconds)),
+            _ => Some(UNIX_EPOCH),
+        };
+    }
+
+    let is_ip = url
+        .host()
+        .is_some_and(|host| !matches!(host, url::Host::Domain(_)));
+    if !cookie.host_only && (is_ip || !domain_matches(&host, &cookie.domain)) {
+        return None;
+    }
+    Some(cookie)
+}
+
+/// Parse an `Expires` date, also accepting the `21-Oct-2015` day format.
+fn parse_expires(value: &str) -> Option<SystemTime> {
+    httpdate::parse_http_date(value)
+        .or_else(|_| httpdate::parse_http_date(&value.replace('-', " ")))
+        .ok()
+}
+
+/// Default path of a cookie: the directory of the request path.
+fn default_path(url: &Url) -> String {
+    match url.path().rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(end) => url.path()[..end].to_string(),
+    }
+}
+
+/// Returns `true` if `host` is `domain` or one of its subdomains.
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain
+        || host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+/// Returns `true` if `path` is `cookie_path` or below it.
+fn path_matches(path: &str, cookie_path: &str) -> bool {
+    path.strip_prefix(cookie_path)
+        .is_some_and(|rest| rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'))
+}
+
+fn is_secure(url: &Url) -> bool {
+    matches!(url.scheme(), "https" | "wss")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        url.parse().expect("url")
+    }
+
+    #[test]
+    fn scopes_cookies_by_domain_and_path() {
+        let store = CookieStore::new();
+        let origin = url("https://api.example.com/v1/login");
+        assert!(store.insert(&origin, "session=abc; Domain=.example.com; Path=/"));
+        assert!(store.insert(&origin, "scoped=1"));
+        assert!(store.insert(&origin, "admin=2; Path=/admin"));
+        assert!(!store.insert(&origin, "other=3; Domain=other.com"));
+        assert!(!store.insert(&origin, "bad name=4"));
+
+        assert_eq!(
+            store.cookie_header(&url("https://api.example.com/v1/users")),
+            Some("scoped=1; session=abc".to_string())
+        );
+        assert_eq!(
+            store.cookie_header(&url("https://auth.example.com/admin/x")),
+            Some("session=abc".to_string())
+        );
+        assert_eq!(
+            store.cookie_header(&url("https://api.example.com/administrators")),
+            Some("session=abc".to_string())
+        );
+        assert_eq!(store.cookie_header(&url("https://example.org/")), None);
+        assert_eq!(
+            store.get(&url("https://api.example.com/admin"), "admin"),
+            Some("2".to_string())
+        );
+    }
+
+    #[test]
+    fn replaces_and_expires_cookies() {
+        let store = CookieStore::new();
+        let origin = url("http://localhost:8080/");
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+        assert!(store.insert_at(&origin, "token=\"old\"", now));
+        assert!(store.insert_at(&origin, "token=new; Max-Age=60", now));
+        assert!(store.insert_at(&origin, "secure=1; Secure", now));
+        assert_eq!(
+            store.cookies_at(&origin, now),
+            [("token".to_string(), "new".to_string())]
+        );
+        assert_eq!(store.cookies_at(&origin, now + Duration::from_mins(1)), []);
+        assert_eq!(store.len(), 1);
+
+        assert!(store.insert_at(
+            &origin,
+            "token=x; Expires=Wed, 21-Oct-2099 07:28:00 GMT",
+            now
+        ));
+        assert!(!store.insert_at(&origin, "token=x; Max-Age=0", now));
+        assert_eq!(store.len(), 1);
+        store.clear();
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn max_age_overflow_is_a_session_cookie() {
+        let store = CookieStore::new();
+        let origin = url("http://localhost:8080/");
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let later = now + Duration::from_hours(24 * 365 * 100);
+
+        for max_age in ["9223372036854775807", "18446744073709551615"] {
+            let header =
+                format!("token=x; Max-Age={max_age}; Expires=Wed, 21-Oct-2015 07:28:00 GMT");
+            assert!(store.insert_at(&origin, &header, now));
+            assert_eq!(
+                store.cookies_at(&origin, later),
+                [("token".to_string(), "x".to_string())]
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips_cookies_through_json() {
+        let store = CookieStore::new();
+        let origin = url("https://api.example.com/");
+        store.insert(&origin, "session=abc; Secure; Max-Age=3600");
+        store.insert(&origin, "theme=dark; Domain=example.com; Path=/app");
+
+        let json = store.to_json().expect("json");
+        let loaded = CookieStore::new();
+        loaded.insert(&origin, "session=old; Secure");
+        assert_eq!(loaded.load_json(&json).expect("load"), 2);
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.get(&origin, "session"), Some("abc".to_string()));
+        assert_eq!(
+            loaded.cookie_header(&url("https://www.example.com/app/x")),
+            Some("theme=dark".to_string())
+        );
+        assert!(loaded.load_json(b"{}").is_err());
+    }
+
+    #[test]
+    fn stores_every_set_cookie_header() {
+        let store = CookieStore::new();
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, "a=1".parse().expect("value"));
+        headers.append(SET_COOKIE, "b=2".parse().expect("value"));
+
+        store.store(&url("https://example.com/"), &headers);
+
+        assert_eq!(
+            store.cookie_header(&url("https://example.com/")),
+            Some("a=1; b=2".to_string())
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! CSV body decoding.
+//!
+//! Records are read with the `csv` crate (RFC 4180): quoted fields may
+//! contain delimiters, line breaks and doubled quotes (`""`). The first
+//! record is the header, and every following record is deserialized by
+//! header name.
+
+use crate::{Error, Result};
+
+/// Deserialize CSV records, using the first record as the header.
+///
+/// Empty fields deserialize to `None` for `Option` fields. Blank lines are
+/// skipped and a leading byte order mark is ignored.
+///
+/// # Errors
+///
+/// Returns an error if `delimiter` is not an ASCII character, if the body is
+/// not valid CSV, if a record does not have as many fields as the header, or
+/// if a record cannot be deserialized.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::from_csv;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, PartialEq, Deserialize)]
+/// struct Visit { page: String, count: u64 }
+///
+/// let body = b"page;count\n/home;42\n\"/a;b\";7\n";
+/// let visits: Vec<Visit> = from_csv(body, ';').expect("deserialize");
+/// assert_eq!(visits[1], Visit { page: "/a;b".to_string(), count: 7 });
+/// ```
+pub fn from_csv<T: serde::de::DeserializeOwned>(bytes: &[u8], delimiter: char) -> Result<Vec<T>> {
+    ::csv::ReaderBuilder::new()
+        .delimiter(delimiter_byte(delimiter)?)
+        .from_reader(bytes)
+        .deserialize()
+        .collect::<std::result::Result<Vec<T>, _>>()
+        .map_err(|err| Error::deserialization("CSV", err.to_string()))
+}
+
+/// Check that `delimiter` is an ASCII character.
+pub(crate) fn delimiter_byte(delimiter: char) -> Result<u8> {
+    u8::try_from(delimiter)
+        .ok()
+        .filter(u8::is_ascii)
+        .ok_or_else(|| {
+            Error::deserialization(
+                "CSV",
+                format!("delimiter {delimiter:?} is not an ASCII character"),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Plan {
+        Free,
+        Pro,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Account {
+        id: u64,
+        name: String,
+        plan: Plan,
+        active: bool,
+        score: Option<f64>,
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Note {
+        title: String,
+        body: String,
+    }
+
+    #[test]
+    fn from_csv_quoted_fields() {
+        let body = "title,body\r\n\"x, \"\"y\"\"\",\"multi\nline\"\n\n\"\",\"\"\n";
+        let notes: Vec<Note> = from_csv(body.as_bytes(), ',').expect("csv");
+        assert_eq!(
+            notes,
+            vec![
+                Note {
+                    title: "x, \"y\"".to_string(),
+                    body: "multi\nline".to_string(),
+                },
+                Note {
+                    title: String::new(),
+                    body: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn from_csv_empty_quoted_field() {
+        let body = "id,name,plan,active,score\n1,\"\",free,true,\"\"\n";
+        let accounts: Vec<Account> = from_csv(body.as_bytes(), ',').expect("csv");
+        assert_eq!(
+            accounts,
+            vec![Account {
+                id: 1,
+                name: String::new(),
+                plan: Plan::Free,
+                active: true,
+                score: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn from_csv_typed_records() {
+        let body =
+            "\u{feff}id,name,plan,active,score\n1,Alice,pro,true,9.5\n2,\"Bob, Jr\",free,false,\n";
+        let accounts: Vec<Account> = from_csv(body.as_bytes(), ',').expect("csv");
+        assert_eq!(
+            accounts,
+            vec![
+                Account {
+                    id: 1,
+                    name: "Alice".to_string(),
+                    plan: Plan::Pro,
+                    active: true,
+                    score: Some(9.5),
+                },
+                Account {
+                    id: 2,
+                    name: "Bob, Jr".to_string(),
+                    plan: Plan::Free,
+                    active: false,
+                    score: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn from_csv_errors() {
+        let err = from_csv::<Note>(b"title,body\n1", ',').expect_err("field count");
+        assert!(err.to_string().contains("record 1"), "{err}");
+
+        let err = from_csv::<Note>(b"title\n1", ',').expect_err("missing field");
+        assert!(err.to_string().contains("missing field `body`"), "{err}");
+
+        let err = from_csv::<Account>(b"id,name,plan,active,score\nx,a,pro,true,1", ',')
+            .expect_err("invalid id");
+        assert!(err.to_string().contains("field 0"), "{err}");
+
+        assert!(from_csv::<Note>(b"title,body\n", '→').is_err());
+        assert!(from_csv::<Account>(b"", ',').expect("empty").is_empty());
+    }
+}
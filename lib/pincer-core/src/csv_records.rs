@@ -0,0 +1,314 @@
+//! Streams of CSV records.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use bytes::{BufMut, BytesMut};
+use csv::StringRecord;
+use futures_core::Stream;
+use serde::de::DeserializeOwned;
+
+use crate::csv::delimiter_byte;
+use crate::text_lines::LineSplitter;
+use crate::{Error, Result, StreamingBody};
+
+/// Stream of the records of a CSV body, deserialized one record at a time as
+/// the chunks arrive, e.g. for a large analytics export.
+///
+/// The first record is the header, and every following record is
+/// deserialized by header name, as with [`from_csv`](crate::from_csv).
+/// Quoted fields may span lines; their line breaks are read as `\n`. Blank
+/// lines are skipped and a leading byte order mark is ignored.
+///
+/// The stream ends after an error reading the body; a record failing to
+/// deserialize, or longer than the [maximum length](Self::max_record_length),
+/// yields an error and the stream goes on with the next record.
+///
+/// # Example
+///
+/// ```ignore
+/// use futures_util::StreamExt;
+///
+/// let mut visits = client.execute_streaming(request).await?.csv_records::<Visit>();
+/// while let Some(visit) = visits.next().await {
+///     process(visit?);
+/// }
+/// ```
+pub struct CsvRecords<T> {
+    lines: LineSplitter,
+    delimiter: char,
+    headers: Option<StringRecord>,
+    /// Lines of a record with a quoted field still open.
+    pending: BytesMut,
+    /// A quoted field of `pending` is still open.
+    quoted: bool,
+    /// Skipping the rest of a record too long, up to its end.
+    discarding: bool,
+    /// Index of the next record, the header being record 0.
+    index: u64,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for CsvRecords<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CsvRecords")
+            .field("buffered", &(self.lines.buffered() + self.pending.len()))
+            .field("max_record_length", &self.lines.max_line_length)
+            .field("headers", &self.headers)
+            .field("done", &self.lines.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> CsvRecords<T> {
+    /// Create a stream of the comma-separated records of `body`.
+    #[must_use]
+    pub fn new(body: StreamingBody) -> Self {
+        Self {
+            lines: LineSplitter::new(body, "CSV"),
+            delimiter: ',',
+            headers: None,
+            pending: BytesMut::new(),
+            quoted: false,
+            discarding: false,
+            index: 0,
+            _item: PhantomData,
+        }
+    }
+
+    /// Set the field delimiter (default: `,`).
+    ///
+    /// A delimiter that is not an ASCII character makes the stream yield an
+    /// error and end.
+    #[must_use]
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the maximum length of a record, without its line ending (default:
+    /// [`DEFAULT_MAX_LINE_LENGTH`](crate::DEFAULT_MAX_LINE_LENGTH)).
+    #[must_use]
+    pub const fn max_record_length(mut self, max_record_length: usize) -> Self {
+        self.lines.max_line_length = max_record_length;
+        self
+    }
+
+    /// Add a line to the pending record, returning the record once complete.
+    fn push_line(&mut self, line: &[u8]) -> Option<Result<BytesMut>> {
+        if self.pending.is_empty() && !self.discarding && line.is_empty() {
+            return None;
+        }
+        for &byte in line {
+            if byte == b'"' {
+                self.quoted = !self.quoted;
+            }
+        }
+        let complete = !self.quoted;
+        let too_long = self.pending.len() + usize::from(!self.pending.is_empty()) + line.len()
+            > self.lines.max_line_length;
+
+        if self.discarding || too_long {
+            self.pending.clear();
+            let reported = self.discarding;
+            self.discarding = !complete;
+            return (!reported).then(|| {
+                Err(Error::deserialization(
+                    "CSV",
+                    format!("record longer than {} bytes", self.lines.max_line_length),
+                ))
+            });
+        }
+
+        if !self.pending.is_empty() {
+            self.pending.put_u8(b'\n');
+        }
+        self.pending.extend_from_slice(line);
+        complete.then(|| Ok(self.pending.split()))
+    }
+
+    /// Parse a complete record, keeping the first one as the header.
+    fn record(&mut self, bytes: &[u8], delimiter: u8) -> Option<Result<T>>
+    where
+        T: DeserializeOwned,
+    {
+        let index = self.index;
+        self.index += 1;
+        let record = match parse_record(bytes, delimiter) {
+            Ok(record) => record,
+            Err(err) => return Some(Err(err)),
+        };
+        let Some(headers) = &self.headers else {
+            self.headers = Some(record);
+            return None;
+        };
+        if record.len() != headers.len() {
+            return Some(Err(Error::deserialization(
+                "CSV",
+                format!(
+                    "record {index} has {} fields, but the header has {}",
+                    record.len(),
+                    headers.len()
+                ),
+            )));
+        }
+        Some(
+            record
+                .deserialize(Some(headers))
+                .map_err(|err| Error::deserialization("CSV", format!("record {index}: {err}"))),
+        )
+    }
+}
+
+impl<T: DeserializeOwned> Stream for CsvRecords<T> {
+    type Item = Result<T>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let delimiter = match delimiter_byte(this.delimiter) {
+            Ok(delimiter) => delimiter,
+            Err(_) if this.lines.done => return Poll::Ready(None),
+            Err(err) => {
+                this.lines.done = true;
+                return Poll::Ready(Some(Err(err)));
+            }
+        };
+
+        loop {
+            let record = match ready!(this.lines.poll_line(cx)) {
+                Some(Ok(line)) => this.push_line(&line),
+                Some(Err(err)) => {
+                    // The record around a line too long cannot be recovered
+                    this.pending.clear();
+                    this.quoted = false;
+                    this.discarding = false;
+                    return Poll::Ready(Some(Err(err)));
+                }
+                // A quoted field left open at the end of the body
+                None if !this.pending.is_empty() && !this.discarding => {
+                    this.quoted = false;
+                    Some(Ok(this.pending.split()))
+                }
+                None => return Poll::Ready(None),
+            };
+            match record {
+                Some(Ok(record)) => {
+                    if let Some(item) = this.record(&record, delimiter) {
+                        return Poll::Ready(Some(item));
+                    }
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => {}
+            }
+        }
+    }
+}
+
+/// Parse a single record, without any header.
+fn parse_record(bytes: &[u8], delimiter: u8) -> Result<StringRecord> {
+    let mut record = StringRecord::new();
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(bytes)
+        .read_record(&mut record)
+        .map_err(|err| Error::deserialization("CSV", err.to_string()))?;
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures_util::{StreamExt, stream};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Visit {
+        page: String,
+        count: u64,
+    }
+
+    fn records<T: DeserializeOwned>(chunks: Vec<Result<&'static str>>) -> CsvRecords<T> {
+        let chunks = chunks
+            .into_iter()
+            .map(|chunk| chunk.map(|chunk| Bytes::from_static(chunk.as_bytes())));
+        CsvRecords::new(Box::pin(stream::iter(chunks)))
+    }
+
+    fn visit(page: &str, count: u64) -> Visit {
+        Visit {
+            page: page.to_string(),
+            count,
+        }
+    }
+
+    #[tokio::test]
+    async fn splits_records_across_chunks() {
+        let visits: Vec<Visit> = records(vec![
+            Ok("\u{feff}count,pa"),
+            Ok("ge\r\n42,/home\n\n7,\"/a,\n"),
+            Ok("b\"\"c\"\"\"\n1,/last"),
+        ])
+        .map(|visit| visit.expect("visit"))
+        .collect()
+        .await;
+        assert_eq!(
+            visits,
+            [
+                visit("/home", 42),
+                visit("/a,\nb\"c\"", 7),
+                visit("/last", 1)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_invalid_records_and_body_errors() {
+        let results: Vec<Result<Visit>> = records(vec![
+            Ok("page;count\n/a;1\n/b;x\n/c\n/d;4\n"),
+            Err(Error::connection("reset")),
+            Ok("/e;5\n"),
+        ])
+        .delimiter(';')
+        .collect()
+        .await;
+        let counts: Vec<_> = results
+            .iter()
+            .map(|result| result.as_ref().ok().map(|visit| visit.count))
+            .collect();
+        assert_eq!(counts, [Some(1), None, None, Some(4), None]);
+        let err = results.get(2).and_then(|result| result.as_ref().err());
+        assert_eq!(
+            err.map(ToString::to_string).as_deref(),
+            Some("CSV deserialization error: record 3 has 1 fields, but the header has 2")
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_records_too_long() {
+        let results: Vec<Result<Visit>> =
+            records(vec![Ok("page,count\n/a,1\n\"/too\nlong\",2\n/b,3\n")])
+                .max_record_length(10)
+                .collect()
+                .await;
+        let counts: Vec<_> = results
+            .iter()
+            .map(|result| result.as_ref().ok().map(|visit| visit.count))
+            .collect();
+        assert_eq!(counts, [Some(1), None, Some(3)]);
+    }
+
+    #[tokio::test]
+    async fn rejects_non_ascii_delimiter() {
+        let results: Vec<Result<Visit>> = records(vec![Ok("page→count\n/a→1\n")])
+            .delimiter('→')
+            .collect()
+            .await;
+        assert_eq!(results.len(), 1);
+        assert!(results.first().is_some_and(Result::is_err));
+    }
+}
@@ -0,0 +1,41 @@
+//! `deepObject` style of query parameters.
+//!
+//! The `deepObject` style of `OpenAPI` encodes the fields of an object parameter
+//! with bracketed keys, e.g. `filter[status]=active&filter[type]=user`, as
+//! used by Stripe-like APIs.
+
+/// Nest query `pairs` under the parameter `name`, in the `deepObject` style.
+///
+/// Each key `key` becomes `name[key]`, and an already nested key `a[b]`
+/// becomes `name[a][b]`, so nested objects keep their structure.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::deep_object_pairs;
+///
+/// let pairs = vec![
+///     ("status".to_string(), "active".to_string()),
+///     ("created[gte]".to_string(), "2024".to_string()),
+/// ];
+/// assert_eq!(
+///     deep_object_pairs("filter", pairs),
+///     [
+///         ("filter[status]".to_string(), "active".to_string()),
+///         ("filter[created][gte]".to_string(), "2024".to_string()),
+///     ]
+/// );
+/// ```
+#[must_use]
+pub fn deep_object_pairs(name: &str, pairs: Vec<(String, String)>) -> Vec<(String, String)> {
+    pairs
+        .into_iter()
+        .map(|(key, value)| {
+            let key = match key.split_once('[') {
+                Some((head, rest)) => format!("{name}[{head}][{rest}"),
+                None => format!("{name}[{key}]"),
+            };
+            (key, value)
+        })
+        .collect()
+}
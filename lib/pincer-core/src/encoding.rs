@@ -0,0 +1,161 @@
+//! Response body content decoding.
+//!
+//! Codecs are enabled by features:
+//! - `gzip` - `gzip` and `deflate`
+//! - `brotli` - `br`
+//! - `zstd` - `zstd`
+//!
+//! [`Response::body_decoded`] uses them to decode a body according to its
+//! `Content-Encoding` header.
+
+#[cfg(any(feature = "gzip", feature = "brotli"))]
+use std::io::Read;
+
+use bytes::Bytes;
+
+use crate::{Error, Response, Result};
+
+/// Returns `true` if bodies with the given content coding can be decoded.
+///
+/// `identity` (or an empty coding) is always supported.
+#[must_use]
+pub fn is_supported(encoding: &str) -> bool {
+    let encoding = encoding.trim().to_ascii_lowercase();
+    let encoding = encoding.as_str();
+
+    matches!(encoding, "identity" | "")
+        || (cfg!(feature = "gzip") && matches!(encoding, "gzip" | "x-gzip" | "deflate"))
+        || (cfg!(feature = "brotli") && encoding == "br")
+        || (cfg!(feature = "zstd") && encoding == "zstd")
+}
+
+/// Decode a body compressed with a single content coding.
+///
+/// # Errors
+///
+/// Returns an error if the coding is not supported (see [`is_supported`]) or
+/// the body is not valid for it.
+pub fn decode(encoding: &str, body: Bytes) -> Result<Bytes> {
+    match encoding.trim().to_ascii_lowercase().as_str() {
+        "identity" | "" => Ok(body),
+        #[cfg(feature = "gzip")]
+        "gzip" | "x-gzip" => read_all("gzip", flate2::read::GzDecoder::new(body.as_ref())),
+        #[cfg(feature = "gzip")]
+        "deflate" => read_all("deflate", flate2::read::DeflateDecoder::new(body.as_ref())),
+        #[cfg(feature = "brotli")]
+        "br" => read_all("brotli", brotli::Decompressor::new(body.as_ref(), 4096)),
+        #[cfg(feature = "zstd")]
+        "zstd" => {
+            let decompressed = zstd::decode_all(body.as_ref())
+                .map_err(|e| Error::InvalidRequest(format!("zstd decompression failed: {e}")))?;
+            Ok(Bytes::from(decompressed))
+        }
+        other => Err(Error::InvalidRequest(format!(
+            "unsupported content encoding: {other}"
+        ))),
+    }
+}
+
+#[cfg(any(feature = "gzip", feature = "brotli"))]
+fn read_all(name: &str, mut decoder: impl Read) -> Result<Bytes> {
+    let mut decompressed = Vec::new();
+    decoder
+        .read_to_end(&mut decompressed)
+        .map_err(|e| Error::InvalidRequest(format!("{name} decompression failed: {e}")))?;
+    Ok(Bytes::from(decompressed))
+}
+
+impl Response<Bytes> {
+    /// Get the body decoded according to its `Content-Encoding` header.
+    ///
+    /// The body is returned as-is when there is no `Content-Encoding` (e.g.,
+    /// it was already decompressed by the decompression middleware). Several
+    /// codings (`gzip, br`) are undone in reverse order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a coding is not supported, usually because its
+    /// codec feature (`gzip`, `brotli`, `zstd`) is disabled, or if the body is
+    /// not valid for it.
+    pub fn body_decoded(&self) -> Result<Bytes> {
+        let Some(encoding) = self.header("content-encoding") else {
+            return Ok(self.body().clone());
+        };
+
+        encoding
+            .rsplit(',')
+            .try_fold(self.body().clone(), |body, coding| decode(coding, body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header::{CONTENT_ENCODING, HeaderMap, HeaderValue};
+
+    use super::*;
+
+    fn response(encoding: Option<&'static str>, body: impl Into<Bytes>) -> Response<Bytes> {
+        let headers: HeaderMap = encoding
+            .map(|encoding| (CONTENT_ENCODING, HeaderValue::from_static(encoding)))
+            .into_iter()
+            .collect();
+        Response::new(200, headers, body.into())
+    }
+
+    #[test]
+    fn decode_identity() {
+        let body = Bytes::from("hello world");
+        assert_eq!(decode("identity", body.clone()).expect("decode"), body);
+        assert_eq!(decode("", body.clone()).expect("decode"), body);
+    }
+
+    #[test]
+    fn decode_unknown_encoding() {
+        assert!(!is_supported("compress"));
+        let err = decode("compress", Bytes::new()).expect_err("unsupported");
+        assert!(err.to_string().contains("compress"));
+    }
+
+    #[test]
+    fn body_decoded_without_encoding() {
+        let response = response(None, "plain");
+        assert_eq!(response.body_decoded().expect("body").as_ref(), b"plain");
+    }
+
+    #[cfg(feature = "gzip")]
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).expect("write");
+        encoder.finish().expect("finish")
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn decode_gzip() {
+        let result = decode("gzip", Bytes::from(gzip(b"hello world"))).expect("decode");
+        assert_eq!(result.as_ref(), b"hello world");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn json_decodes_compressed_body() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct User {
+            id: u64,
+        }
+
+        let response = response(Some("GZIP"), gzip(br#"{"id": 1}"#));
+        let user: User = response.json().expect("json");
+        assert_eq!(user, User { id: 1 });
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn body_decoded_several_codings() {
+        let twice = gzip(&gzip(b"nested"));
+        let response = response(Some("gzip, gzip"), twice);
+        assert_eq!(response.body_decoded().expect("body").as_ref(), b"nested");
+    }
+}
@@ -0,0 +1,287 @@
+//! Endpoints known at runtime.
+
+use percent_encoding::{AsciiSet, CONTROLS, NON_ALPHANUMERIC, utf8_percent_encode};
+
+use crate::{Error, Method, Request, RequestBuilder, Result, ToQueryPairs};
+
+/// Characters percent-encoded in path parameters.
+///
+/// Everything but unreserved characters and sub-delimiters is encoded,
+/// including `/`, so a value always stays within a single path segment.
+pub const PATH_SEGMENT_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`')
+    .add(b'?')
+    .add(b'{')
+    .add(b'}')
+    .add(b'/')
+    .add(b'\\')
+    .add(b'%');
+
+/// Characters percent-encoded in the query values of path templates
+/// (`{?q,page}`).
+///
+/// Everything but unreserved characters is encoded, so a value can hold `&`,
+/// `=` or `+`.
+pub const QUERY_COMPONENT_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Characters percent-encoded in the reserved expansions of path templates
+/// (`{+path}`).
+///
+/// Reserved characters such as `/` and `?` are kept, so a value can span
+/// several path segments.
+pub const RESERVED_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}')
+    .add(b'^')
+    .add(b'|')
+    .add(b'\\')
+    .add(b'%');
+
+/// An endpoint described at runtime, for APIs only known once configured
+/// (e.g., user-configured integrations).
+///
+/// Path parameters are percent-encoded like the ones of `#[pincer]` methods.
+/// Building the request fails if a placeholder of the template has no value,
+/// or a value has no placeholder.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::Endpoint;
+///
+/// let base_url = "https://api.example.com".parse().expect("url");
+/// let request = Endpoint::get("/users/{id}")
+///     .path("id", 5)
+///     .query("fields", "name")
+///     .request::<bytes::Bytes>(&base_url)
+///     .expect("request")
+///     .build();
+/// assert_eq!(request.url().as_str(), "https://api.example.com/users/5?fields=name");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    method: Method,
+    template: String,
+    path_params: Vec<(String, String)>,
+    query: Vec<(String, String)>,
+}
+
+impl Endpoint {
+    /// Create an endpoint from a method and a path template (e.g., `/users/{id}`).
+    #[must_use]
+    pub fn new(method: Method, template: impl Into<String>) -> Self {
+        Self {
+            method,
+            template: template.into(),
+            path_params: Vec::new(),
+            query: Vec::new(),
+        }
+    }
+
+    /// Create a `GET` endpoint.
+    #[must_use]
+    pub fn get(template: impl Into<String>) -> Self {
+        Self::new(Method::Get, template)
+    }
+
+    /// Create a `POST` endpoint.
+    #[must_use]
+    pub fn post(template: impl Into<String>) -> Self {
+        Self::new(Method::Post, template)
+    }
+
+    /// Create a `PUT` endpoint.
+    #[must_use]
+    pub fn put(template: impl Into<String>) -> Self {
+        Self::new(Method::Put, template)
+    }
+
+    /// Create a `DELETE` endpoint.
+    #[must_use]
+    pub fn delete(template: impl Into<String>) -> Self {
+        Self::new(Method::Delete, template)
+    }
+
+    /// Create a `PATCH` endpoint.
+    #[must_use]
+    pub fn patch(template: impl Into<String>) -> Self {
+        Self::new(Method::Patch, template)
+    }
+
+    /// Create a `HEAD` endpoint.
+    #[must_use]
+    pub fn head(template: impl Into<String>) -> Self {
+        Self::new(Method::Head, template)
+    }
+
+    /// Create an `OPTIONS` endpoint.
+    #[must_use]
+    pub fn options(template: impl Into<String>) -> Self {
+        Self::new(Method::Options, template)
+    }
+
+    /// Sets the value of the `{name}` placeholder, replacing any previous value.
+    #[must_use]
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn path(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        let name = name.into();
+        self.path_params.retain(|(key, _)| *key != name);
+        self.path_params.push((name, value.to_string()));
+        self
+    }
+
+    /// Appends a query parameter. Repeat it for multi-valued parameters.
+    #[must_use]
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn query(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.query.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Appends the query parameters of a `#[derive(Query)]` struct.
+    #[must_use]
+    pub fn query_pairs(mut self, params: &impl ToQueryPairs) -> Self {
+        self.query.extend(params.to_query_pairs());
+        self
+    }
+
+    /// HTTP method.
+    #[must_use]
+    pub const fn method(&self) -> Method {
+        self.method
+    }
+
+    /// Path template.
+    #[must_use]
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Build the URL of the endpoint, relative to `base_url`.
+    pub fn url(&self, base_url: &url::Url) -> Result<url::Url> {
+        let path = self.expand_path()?;
+        let mut url = base_url.join(&path).map_err(Error::InvalidUrl)?;
+        if !self.query.is_empty() {
+            url.query_pairs_mut().extend_pairs(&self.query);
+        }
+        Ok(url)
+    }
+
+    /// Start a request to the endpoint, relative to `base_url`.
+    ///
+    /// Headers and body are then set on the returned builder.
+    pub fn request<B>(&self, base_url: &url::Url) -> Result<RequestBuilder<B>> {
+        Ok(Request::builder(self.method, self.url(base_url)?))
+    }
+
+    /// Substitute the encoded path parameters into the template.
+    fn expand_path(&self) -> Result<String> {
+        let mut path = String::with_capacity(self.template.len());
+        let mut rest = self.template.as_str();
+
+        while let Some(open) = rest.find('{') {
+            let (literal, placeholder) = rest.split_at(open);
+            let close = placeholder.find('}').ok_or_else(|| {
+                Error::invalid_request(format!("unclosed placeholder in `{}`", self.template))
+            })?;
+            let name = placeholder.get(1..close).unwrap_or_default();
+            let (_, value) = self
+                .path_params
+                .iter()
+                .find(|(key, _)| key == name)
+                .ok_or_else(|| {
+                    Error::invalid_request(format!(
+                        "missing path parameter `{name}` for `{}`",
+                        self.template
+                    ))
+                })?;
+            path.push_str(literal);
+            path.extend(utf8_percent_encode(value, PATH_SEGMENT_ENCODE_SET));
+            rest = placeholder.get(close + 1..).unwrap_or_default();
+        }
+        path.push_str(rest);
+
+        if let Some((name, _)) = self
+            .path_params
+            .iter()
+            .find(|(key, _)| !self.template.contains(&format!("{{{key}}}")))
+        {
+            return Err(Error::invalid_request(format!(
+                "unknown path parameter `{name}` for `{}`",
+                self.template
+            )));
+        }
+        Ok(path)
+    }
+}
+
+/// Names of the placeholders of a path template.
+pub(crate) fn placeholders(template: &str) -> Vec<&str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn base_url() -> url::Url {
+        "https://api.example.com".parse().expect("url")
+    }
+
+    #[test]
+    fn expands_and_encodes_path_parameters() {
+        let url = Endpoint::get("/repos/{owner}/{repo}")
+            .path("owner", "a b")
+            .path("repo", "x/y")
+            .url(&base_url())
+            .expect("url");
+        assert_eq!(url.as_str(), "https://api.example.com/repos/a%20b/x%2Fy");
+    }
+
+    #[test]
+    fn appends_query_parameters() {
+        let request: Request<Bytes> = Endpoint::delete("/users")
+            .query("id", 1)
+            .query("id", 2)
+            .request(&base_url())
+            .expect("request")
+            .build();
+        assert_eq!(request.method(), Method::Delete);
+        assert_eq!(request.url().query(), Some("id=1&id=2"));
+    }
+
+    #[test]
+    fn rejects_missing_unknown_and_unclosed_placeholders() {
+        let missing = Endpoint::get("/users/{id}").url(&base_url());
+        assert!(missing.is_err());
+
+        let unknown = Endpoint::get("/users/{id}")
+            .path("id", 1)
+            .path("name", "x")
+            .url(&base_url());
+        assert!(unknown.is_err());
+
+        let unclosed = Endpoint::get("/users/{id").path("id", 1).url(&base_url());
+        assert!(unclosed.is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! Entity tags for conditional requests.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Error, RequestBuilder, Response};
+
+/// An entity tag (RFC 9110 §8.8.3), as sent in `ETag` and `If-Match` headers.
+///
+/// Used for optimistic concurrency: read a resource and its `ETag`, then
+/// update it with `If-Match`. If someone else updated it in between, the
+/// server answers `412 Precondition Failed`, surfaced as
+/// [`Error::PreconditionFailed`].
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::EntityTag;
+///
+/// let etag: EntityTag = "W/\"v42\"".parse().expect("entity tag");
+/// assert!(etag.is_weak());
+/// assert_eq!(etag.tag(), "v42");
+/// assert_eq!(EntityTag::strong("v42").to_string(), "\"v42\"");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntityTag {
+    tag: String,
+    weak: bool,
+}
+
+impl EntityTag {
+    /// Create a strong entity tag from its opaque value (without quotes).
+    #[must_use]
+    pub fn strong(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            weak: false,
+        }
+    }
+
+    /// Create a weak entity tag from its opaque value (without quotes).
+    #[must_use]
+    pub fn weak(tag: impl Into<String>) -> Self {
+        Self {
+            tag: tag.into(),
+            weak: true,
+        }
+    }
+
+    /// Get the opaque value, without quotes.
+    #[must_use]
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns `true` if this is a weak entity tag (`W/"..."`).
+    #[must_use]
+    pub const fn is_weak(&self) -> bool {
+        self.weak
+    }
+}
+
+impl FromStr for EntityTag {
+    type Err = Error;
+
+    /// Parse a quoted entity tag, optionally prefixed by `W/`.
+    fn from_str(value: &str) -> Result<Self, Error> {
+        let value = value.trim();
+        let (weak, quoted) = value
+            .strip_prefix("W/")
+            .map_or((false, value), |quoted| (true, quoted));
+        quoted
+            .strip_prefix('"')
+            .and_then(|quoted| quoted.strip_suffix('"'))
+            .filter(|tag| !tag.contains('"'))
+            .map(|tag| Self {
+                tag: tag.to_string(),
+                weak,
+            })
+            .ok_or_else(|| Error::invalid_request(format!("invalid entity tag: {value}")))
+    }
+}
+
+impl fmt::Display for EntityTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weak {
+            f.write_str("W/")?;
+        }
+        write!(f, "\"{}\"", self.tag)
+    }
+}
+
+impl<B> RequestBuilder<B> {
+    /// Sets the `If-Match` header, so the request only applies if the
+    /// resource still has this entity tag.
+    #[must_use]
+    pub fn if_match(self, etag: &EntityTag) -> Self {
+        self.header(http::header::IF_MATCH, etag.to_string())
+    }
+}
+
+impl<B> Response<B> {
+    /// Get the entity tag of the response from its `ETag` header.
+    ///
+    /// Returns `None` if the header is missing or invalid.
+    #[must_use]
+    pub fn etag(&self) -> Option<EntityTag> {
+        self.header("etag")?.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use http::HeaderMap;
+
+    use super::*;
+    use crate::{Method, Request};
+
+    #[test]
+    fn entity_tag_parse_and_display() {
+        let strong: EntityTag = "\"abc\"".parse().expect("strong");
+        assert_eq!(strong, EntityTag::strong("abc"));
+        assert_eq!(strong.to_string(), "\"abc\"");
+
+        let weak: EntityTag = " W/\"abc\" ".parse().expect("weak");
+        assert_eq!(weak, EntityTag::weak("abc"));
+        assert_eq!(weak.to_string(), "W/\"abc\"");
+
+        assert!("abc".parse::<EntityTag>().is_err());
+        assert!("\"a\"b\"".parse::<EntityTag>().is_err());
+        assert!("w/\"abc\"".parse::<EntityTag>().is_err());
+    }
+
+    #[test]
+    fn if_match_and_etag_headers() {
+        let request: Request<Bytes> = Request::builder(
+            Method::Put,
+            "https://api.example.com/".parse().expect("url"),
+        )
+        .if_match(&EntityTag::weak("v1"))
+        .build();
+        assert_eq!(request.header("if-match"), Some("W/\"v1\""));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("etag", "\"v2\"".parse().expect("value"));
+        let response = Response::new(200, headers, Bytes::new());
+        assert_eq!(response.etag(), Some(EntityTag::strong("v2")));
+    }
+}
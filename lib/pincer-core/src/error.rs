@@ -1,6 +1,23 @@
 //! Error types for pincer.
 
+use std::time::Duration;
+
 use derive_more::{Display, Error, From};
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+
+use crate::rate_limit::{self, RateLimitInfo};
+
+/// Default number of body bytes previewed in the `Display` of [`Error::Http`].
+pub const DEFAULT_BODY_PREVIEW_LEN: usize = 256;
+
+/// Response extension setting how many body bytes are previewed by the
+/// `Display` of errors built with [`Error::from_response`].
+///
+/// Clients set it on every response when the preview length is configured,
+/// e.g. to `0` when error bodies may contain sensitive data. Without it,
+/// [`DEFAULT_BODY_PREVIEW_LEN`] bytes are previewed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyPreviewLen(pub usize);
 
 // ============================================================================
 // Error Decoder Trait
@@ -71,7 +88,10 @@ impl ErrorDecoder for DefaultErrorDecoder {
 #[derive(Debug, Display, Error, From)]
 pub enum Error {
     /// HTTP-level errors (non-2xx status codes).
-    #[display("HTTP error {status}: {message}")]
+    ///
+    /// The `Display` output ends with a preview of the body, see
+    /// [`BodyPreviewLen`].
+    #[display("HTTP error {status}: {message}{}", preview_suffix(body.as_ref(), *preview_len))]
     #[from(skip)]
     Http {
         /// HTTP status code.
@@ -81,6 +101,43 @@ pub enum Error {
         /// Response body, if available.
         #[error(not(source))]
         body: Option<bytes::Bytes>,
+        /// Response headers (empty if the error was not built from a response).
+        #[error(not(source))]
+        headers: Box<HeaderMap>,
+        /// Number of body bytes previewed by `Display` (`0` for none).
+        preview_len: usize,
+    },
+
+    /// Rate limited by the server (`429 Too Many Requests`).
+    #[display("rate limited{}", retry_after_suffix(*retry_after))]
+    #[from(skip)]
+    RateLimited {
+        /// Delay requested by the `Retry-After` header, if valid.
+        retry_after: Option<Duration>,
+        /// Quota advertised by the rate limit headers (boxed to keep `Error` small).
+        limit_info: Box<RateLimitInfo>,
+        /// Response body, if available.
+        #[error(not(source))]
+        body: Option<bytes::Bytes>,
+        /// Response headers.
+        #[error(not(source))]
+        headers: Box<HeaderMap>,
+    },
+
+    /// Precondition of a conditional request failed (`412 Precondition Failed`).
+    ///
+    /// With `If-Match`, the resource was modified since its entity tag was read.
+    #[display("precondition failed{}", preview_suffix(body.as_ref(), *preview_len))]
+    #[from(skip)]
+    PreconditionFailed {
+        /// Response body, if available.
+        #[error(not(source))]
+        body: Option<bytes::Bytes>,
+        /// Response headers.
+        #[error(not(source))]
+        headers: Box<HeaderMap>,
+        /// Number of body bytes previewed by `Display` (`0` for none).
+        preview_len: usize,
     },
 
     /// Network/connection errors.
@@ -98,6 +155,16 @@ pub enum Error {
     #[from(skip)]
     Timeout,
 
+    /// Request rejected because the client is at capacity (load shedding).
+    #[display("service overloaded")]
+    #[from(skip)]
+    Overloaded,
+
+    /// Request aborted because the scope it was attached to ended.
+    #[display("request cancelled")]
+    #[from(skip)]
+    Cancelled,
+
     /// Invalid request configuration.
     #[display("invalid request: {_0}")]
     #[from(skip)]
@@ -118,6 +185,16 @@ pub enum Error {
         message: String,
     },
 
+    /// Deserialization error of a non-JSON body (YAML, TOML, ...).
+    #[display("{format} deserialization error: {message}")]
+    #[from(skip)]
+    Deserialization {
+        /// Body format (e.g., "YAML").
+        format: &'static str,
+        /// Error message.
+        message: String,
+    },
+
     /// Form URL-encoded serialization error.
     #[display("form serialization error: {_0}")]
     #[from]
@@ -143,10 +220,54 @@ pub enum Error {
         max: usize,
     },
 
+    /// Request URL longer than the configured limit, e.g. with a query string
+    /// that a gateway would silently truncate.
+    #[display("URL too long ({length} bytes exceeded max of {max})")]
+    #[from(skip)]
+    UrlTooLong {
+        /// Length of the URL, in bytes.
+        length: usize,
+        /// Maximum allowed length.
+        max: usize,
+    },
+
+    /// Pagination reached a configured limit while the server still had pages.
+    #[display("pagination limit reached: {_0}")]
+    #[from(skip)]
+    PaginationLimit(#[error(not(source))] String),
+
     /// Invalid redirect response.
     #[display("invalid redirect: {_0}")]
     #[from(skip)]
     InvalidRedirect(#[error(not(source))] String),
+
+    /// Response body does not match its advertised checksum.
+    #[display("integrity error: {algorithm} checksum mismatch (expected {expected}, got {actual})")]
+    #[from(skip)]
+    Integrity {
+        /// Checksum algorithm (e.g., "sha-256").
+        algorithm: String,
+        /// Checksum advertised by the server.
+        expected: String,
+        /// Checksum computed from the received body.
+        actual: String,
+    },
+
+    /// Response body shorter than its advertised `Content-Length`, e.g. a
+    /// compressed stream cut off by the connection.
+    #[display("truncated body: expected {expected} bytes, got {actual}")]
+    #[from(skip)]
+    TruncatedBody {
+        /// Length advertised by the `Content-Length` header.
+        expected: u64,
+        /// Length of the received body.
+        actual: u64,
+    },
+
+    /// Signature verification failed (e.g., inbound webhook).
+    #[display("invalid signature: {_0}")]
+    #[from(skip)]
+    Signature(#[error(not(source))] String),
 }
 
 /// Result type alias using [`crate::Error`].
@@ -160,6 +281,8 @@ impl Error {
             status,
             message: message.into(),
             body: None,
+            headers: Box::default(),
+            preview_len: DEFAULT_BODY_PREVIEW_LEN,
         }
     }
 
@@ -170,7 +293,70 @@ impl Error {
             status,
             message: message.into(),
             body: Some(body),
+            headers: Box::default(),
+            preview_len: DEFAULT_BODY_PREVIEW_LEN,
+        }
+    }
+
+    /// Create an HTTP error from a non-2xx response.
+    ///
+    /// Keeps the response body and headers. A `429` response becomes
+    /// [`Error::RateLimited`], with its `Retry-After` and rate limit headers
+    /// parsed, and a `412` response becomes [`Error::PreconditionFailed`].
+    /// The body preview length is taken from the [`BodyPreviewLen`] extension.
+    #[must_use]
+    pub fn from_response(response: crate::Response<bytes::Bytes>) -> Self {
+        let preview_len = response
+            .extensions()
+            .get::<BodyPreviewLen>()
+            .map_or(DEFAULT_BODY_PREVIEW_LEN, |len| len.0);
+        let (status, headers, body) = response.into_parts();
+        if status == 429 {
+            return Self::RateLimited {
+                retry_after: rate_limit::retry_after(&headers),
+                limit_info: Box::new(RateLimitInfo::from_headers(&headers)),
+                body: Some(body),
+                headers: Box::new(headers),
+            };
+        }
+        if status == 412 {
+            return Self::PreconditionFailed {
+                body: Some(body),
+                headers: Box::new(headers),
+                preview_len,
+            };
+        }
+        Self::Http {
+            status,
+            message: format!("HTTP error: {status}"),
+            body: Some(body),
+            headers: Box::new(headers),
+            preview_len,
+        }
+    }
+
+    /// Add a response header to an HTTP error.
+    ///
+    /// Has no effect on other errors, or if the name or value is invalid.
+    #[must_use]
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        if let Self::Http { headers, .. }
+        | Self::RateLimited { headers, .. }
+        | Self::PreconditionFailed { headers, .. } = &mut self
+            && let (Ok(name), Ok(value)) =
+                (HeaderName::try_from(name), HeaderValue::try_from(value))
+        {
+            headers.insert(name, value);
         }
+        self
+    }
+
+    /// Set the `Content-Type` of an HTTP error body.
+    ///
+    /// Has no effect on other errors.
+    #[must_use]
+    pub fn with_content_type(self, value: &str) -> Self {
+        self.with_header("content-type", value)
     }
 
     /// Create a connection error.
@@ -200,23 +386,94 @@ impl Error {
         }
     }
 
+    /// Create a deserialization error for a non-JSON body `format`.
+    #[must_use]
+    pub fn deserialization(format: &'static str, message: impl Into<String>) -> Self {
+        Self::Deserialization {
+            format,
+            message: message.into(),
+        }
+    }
+
     /// Returns `true` if this is a timeout error.
     #[must_use]
     pub const fn is_timeout(&self) -> bool {
         matches!(self, Self::Timeout)
     }
 
+    /// Returns `true` if the request was shed because the client was at capacity.
+    #[must_use]
+    pub const fn is_overloaded(&self) -> bool {
+        matches!(self, Self::Overloaded)
+    }
+
+    /// Returns `true` if the request was aborted because its scope ended.
+    #[must_use]
+    pub const fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled)
+    }
+
+    /// Returns `true` if pagination stopped at a configured limit.
+    #[must_use]
+    pub const fn is_pagination_limit(&self) -> bool {
+        matches!(self, Self::PaginationLimit(_))
+    }
+
     /// Returns `true` if this is a connection error.
     #[must_use]
     pub const fn is_connection(&self) -> bool {
         matches!(self, Self::Connection(_))
     }
 
+    /// Returns `true` if this is a checksum mismatch error.
+    #[must_use]
+    pub const fn is_integrity(&self) -> bool {
+        matches!(self, Self::Integrity { .. })
+    }
+
+    /// Returns `true` if the response body was shorter than advertised.
+    #[must_use]
+    pub const fn is_truncated_body(&self) -> bool {
+        matches!(self, Self::TruncatedBody { .. })
+    }
+
+    /// Returns `true` if this is a signature verification error.
+    #[must_use]
+    pub const fn is_signature(&self) -> bool {
+        matches!(self, Self::Signature(_))
+    }
+
+    /// Returns `true` if this is a rate limit error.
+    #[must_use]
+    pub const fn is_rate_limited(&self) -> bool {
+        matches!(self, Self::RateLimited { .. })
+    }
+
+    /// Returns `true` if the precondition of a conditional request failed.
+    #[must_use]
+    pub const fn is_precondition_failed(&self) -> bool {
+        matches!(self, Self::PreconditionFailed { .. })
+    }
+
+    /// Returns the `Retry-After` delay if this is a rate limit error.
+    #[must_use]
+    pub const fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
     /// Returns the HTTP status code if this is an HTTP error.
+    ///
+    /// [`Error::RateLimited`] has status `429` and
+    /// [`Error::PreconditionFailed`] has status `412`.
     #[must_use]
     pub const fn status(&self) -> Option<u16> {
         match self {
             Self::Http { status, .. } => Some(*status),
+            Self::RateLimited { .. } => Some(429),
+            Self::PreconditionFailed { .. } => Some(412),
             _ => None,
         }
     }
@@ -243,11 +500,61 @@ impl Error {
     #[must_use]
     pub fn body(&self) -> Option<&bytes::Bytes> {
         match self {
-            Self::Http { body, .. } => body.as_ref(),
+            Self::Http { body, .. }
+            | Self::RateLimited { body, .. }
+            | Self::PreconditionFailed { body, .. } => body.as_ref(),
             _ => None,
         }
     }
 
+    /// Returns the response headers if this is an HTTP error.
+    #[must_use]
+    pub fn headers(&self) -> Option<&HeaderMap> {
+        match self {
+            Self::Http { headers, .. }
+            | Self::RateLimited { headers, .. }
+            | Self::PreconditionFailed { headers, .. } => Some(headers),
+            _ => None,
+        }
+    }
+
+    /// Returns a response header of an HTTP error, compared case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Some(retry_after) = err.header("retry-after") {
+    ///     tracing::warn!(retry_after, "rate limited");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers()?.get(name)?.to_str().ok()
+    }
+
+    /// Returns the `Content-Type` of the HTTP error body, if known.
+    #[must_use]
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+    }
+
+    /// Returns a preview of the HTTP error body, as shown by `Display`.
+    ///
+    /// The preview holds the first bytes of the body (see
+    /// [`BodyPreviewLen`]), with invalid UTF-8 replaced and control
+    /// characters turned into spaces. Returns `None` if there is no body, it
+    /// is empty, or previews are disabled.
+    #[must_use]
+    pub fn body_preview(&self) -> Option<String> {
+        let len = match self {
+            Self::Http { preview_len, .. } | Self::PreconditionFailed { preview_len, .. } => {
+                *preview_len
+            }
+            _ => DEFAULT_BODY_PREVIEW_LEN,
+        };
+        preview(self.body()?, len)
+    }
+
     /// Try to decode the HTTP error body as JSON.
     ///
     /// Returns `Some(Ok(value))` if the error has a body and it deserializes successfully,
@@ -277,6 +584,124 @@ impl Error {
     pub fn decode_body<T: serde::de::DeserializeOwned>(&self) -> Option<Result<T>> {
         self.body().map(|body| crate::from_json(body))
     }
+
+    /// Try to decode the HTTP error body according to its `Content-Type`.
+    ///
+    /// - `application/json` and `*/*+json` are decoded as JSON
+    /// - `application/x-www-form-urlencoded` is decoded as a form, like query strings
+    /// - otherwise (including a missing content type), JSON is attempted, then form
+    ///
+    /// XML (`application/xml`, `text/xml` and `*/*+xml`) and CBOR
+    /// (`application/cbor` and `*/*+cbor`) bodies need a codec: they fail
+    /// here, decode them with [`decode_body_with`](Self::decode_body_with)
+    /// instead.
+    ///
+    /// Returns `None` if there is no body or this is not an HTTP error.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Some(Ok(api_error)) = e.decode_body_as::<ApiError>() {
+    ///     println!("API error: {}", api_error.message);
+    /// }
+    /// ```
+    pub fn decode_body_as<T: serde::de::DeserializeOwned>(&self) -> Option<Result<T>> {
+        self.decode_by_content_type(None::<&crate::SerdeJson>)
+    }
+
+    /// Try to decode the HTTP error body according to its `Content-Type`,
+    /// decoding the bodies of the [`content_type`](crate::BodyCodec::content_type)
+    /// of `codec` with it.
+    ///
+    /// Same as [`decode_body_as`](Self::decode_body_as) otherwise. Pass the
+    /// codec of the client that returned the error, e.g. its XML codec.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if let Some(Ok(fault)) = e.decode_body_with::<SoapFault>(&QuickXml) {
+    ///     println!("SOAP fault: {}", fault.reason);
+    /// }
+    /// ```
+    pub fn decode_body_with<T: serde::de::DeserializeOwned>(
+        &self,
+        codec: &impl crate::BodyCodec,
+    ) -> Option<Result<T>> {
+        self.decode_by_content_type(Some(codec))
+    }
+
+    fn decode_by_content_type<T: serde::de::DeserializeOwned>(
+        &self,
+        codec: Option<&impl crate::BodyCodec>,
+    ) -> Option<Result<T>> {
+        use crate::ContentType;
+
+        let body = self.body()?;
+        let media_type = self
+            .content_type()
+            .and_then(|content_type| content_type.split(';').next())
+            .map(|media_type| media_type.trim().to_ascii_lowercase());
+        let format = media_type
+            .as_deref()
+            .and_then(|media_type| match media_type {
+                "application/x-www-form-urlencoded" => Some(ContentType::FormUrlEncoded),
+                "application/xml" | "text/xml" => Some(ContentType::Xml),
+                "application/cbor" => Some(ContentType::Cbor),
+                _ if media_type == "application/json" || media_type.ends_with("+json") => {
+                    Some(ContentType::Json)
+                }
+                _ if media_type.ends_with("+xml") => Some(ContentType::Xml),
+                _ if media_type.ends_with("+cbor") => Some(ContentType::Cbor),
+                _ => None,
+            });
+
+        let result = match (format, codec) {
+            (Some(format), Some(codec)) if codec.content_type() == format => codec.decode(body),
+            (Some(ContentType::Json), _) => crate::from_json(body),
+            (Some(ContentType::FormUrlEncoded), _) => from_form(body),
+            (Some(_), _) => Err(Self::invalid_request(format!(
+                "cannot decode {} error body without its codec, use Error::decode_body_with",
+                media_type.unwrap_or_default()
+            ))),
+            (None, _) => crate::from_json(body).or_else(|err| from_form(body).map_err(|_| err)),
+        };
+        Some(result)
+    }
+}
+
+/// Decode a form URL-encoded body.
+fn from_form<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T> {
+    serde_html_form::from_bytes(body)
+        .map_err(|e| Error::invalid_request(format!("form deserialization error: {e}")))
+}
+
+/// Lossy preview of the first `len` bytes of `body`.
+fn preview(body: &bytes::Bytes, len: usize) -> Option<String> {
+    if len == 0 || body.is_empty() {
+        return None;
+    }
+
+    let head = body.get(..len).unwrap_or(body);
+    let mut preview: String = String::from_utf8_lossy(head)
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+    if head.len() < body.len() {
+        preview.push('…');
+    }
+    Some(preview)
+}
+
+fn retry_after_suffix(retry_after: Option<Duration>) -> String {
+    retry_after
+        .map(|delay| format!(" (retry after {}s)", delay.as_secs()))
+        .unwrap_or_default()
+}
+
+fn preview_suffix(body: Option<&bytes::Bytes>, len: usize) -> String {
+    body.and_then(|body| preview(body, len))
+        .map(|preview| format!(" (body: {preview})"))
+        .unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -331,6 +756,43 @@ mod tests {
         assert!(!Error::Timeout.is_connection());
     }
 
+    #[test]
+    fn error_is_integrity() {
+        let err = Error::Integrity {
+            algorithm: "md5".to_string(),
+            expected: "a".to_string(),
+            actual: "b".to_string(),
+        };
+        assert!(err.is_integrity());
+        assert_eq!(
+            err.to_string(),
+            "integrity error: md5 checksum mismatch (expected a, got b)"
+        );
+        assert!(!Error::Timeout.is_integrity());
+    }
+
+    #[test]
+    fn error_is_truncated_body() {
+        let err = Error::TruncatedBody {
+            expected: 100,
+            actual: 42,
+        };
+        assert!(err.is_truncated_body());
+        assert_eq!(
+            err.to_string(),
+            "truncated body: expected 100 bytes, got 42"
+        );
+        assert!(!Error::Timeout.is_truncated_body());
+    }
+
+    #[test]
+    fn error_is_signature() {
+        let err = Error::Signature("mismatch".to_string());
+        assert!(err.is_signature());
+        assert_eq!(err.to_string(), "invalid signature: mismatch");
+        assert!(!Error::Timeout.is_signature());
+    }
+
     #[test]
     fn error_is_not_found() {
         assert!(Error::http(404, "Not Found").is_not_found());
@@ -350,6 +812,44 @@ mod tests {
         assert!(Error::Timeout.body().is_none());
     }
 
+    #[test]
+    fn error_body_preview() {
+        let body = bytes::Bytes::from("{\"error\":\n\"bad input\"}");
+        let err = Error::http_with_body(400, "Bad Request", body);
+        assert_eq!(
+            err.to_string(),
+            "HTTP error 400: Bad Request (body: {\"error\": \"bad input\"})"
+        );
+
+        let err = Error::http_with_body(500, "oops", bytes::Bytes::new());
+        assert_eq!(err.to_string(), "HTTP error 500: oops");
+    }
+
+    #[test]
+    fn body_preview_len_from_response() {
+        let body = bytes::Bytes::from_static(b"secret token");
+        let mut response = crate::Response::new(401, HeaderMap::new(), body.clone());
+        response.extensions_mut().insert(BodyPreviewLen(0));
+
+        let err = Error::from_response(response);
+        assert_eq!(err.to_string(), "HTTP error 401: HTTP error: 401");
+        assert_eq!(err.body_preview(), None);
+        assert_eq!(err.body(), Some(&body));
+
+        let mut response = crate::Response::new(412, HeaderMap::new(), body);
+        response.extensions_mut().insert(BodyPreviewLen(6));
+        let err = Error::from_response(response);
+        assert_eq!(err.to_string(), "precondition failed (body: secret…)");
+    }
+
+    #[test]
+    fn body_preview_is_bounded_and_lossy() {
+        let body = bytes::Bytes::from_static(b"abc\xffdef");
+        assert_eq!(preview(&body, 5).as_deref(), Some("abc\u{fffd}d…"));
+        assert_eq!(preview(&body, 100).as_deref(), Some("abc\u{fffd}def"));
+        assert_eq!(preview(&body, 0), None);
+    }
+
     #[test]
     fn error_decode_body() {
         #[derive(Debug, PartialEq, serde::Deserialize)]
@@ -379,6 +879,150 @@ mod tests {
         assert!(Error::Timeout.decode_body::<ApiError>().is_none());
     }
 
+    #[test]
+    fn error_decode_body_as() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct ApiError {
+            error: String,
+        }
+        let expected = ApiError {
+            error: "not found".to_string(),
+        };
+
+        let err = Error::http_with_body(404, "Not Found", r#"{"error": "not found"}"#.into())
+            .with_content_type("application/problem+json");
+        assert_eq!(err.content_type(), Some("application/problem+json"));
+        let decoded = err.decode_body_as::<ApiError>().expect("body");
+        assert_eq!(decoded.expect("json"), expected);
+
+        let err = Error::http_with_body(404, "Not Found", "error=not+found".into())
+            .with_content_type("application/x-www-form-urlencoded; charset=utf-8");
+        let decoded = err.decode_body_as::<ApiError>().expect("body");
+        assert_eq!(decoded.expect("form"), expected);
+
+        // Unknown content type: JSON, then form
+        let err = Error::http_with_body(404, "Not Found", "error=not+found".into());
+        let decoded = err.decode_body_as::<ApiError>().expect("body");
+        assert_eq!(decoded.expect("form"), expected);
+
+        let err = Error::http_with_body(404, "Not Found", "<error/>".into())
+            .with_content_type("text/xml");
+        let decoded = err.decode_body_as::<ApiError>().expect("body");
+        assert!(decoded.is_err());
+
+        assert!(Error::Timeout.decode_body_as::<ApiError>().is_none());
+    }
+
+    #[test]
+    fn error_decode_body_with_xml_codec() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct ApiError {
+            error: String,
+        }
+
+        /// Codec reading `<error>message</error>` documents only.
+        struct ErrorTag;
+
+        impl crate::BodyCodec for ErrorTag {
+            fn content_type(&self) -> crate::ContentType {
+                crate::ContentType::Xml
+            }
+
+            fn encode<T: serde::Serialize>(&self, _value: &T) -> Result<bytes::Bytes> {
+                Err(Error::invalid_request("decode only"))
+            }
+
+            fn decode<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> Result<T> {
+                let xml = std::str::from_utf8(bytes)
+                    .map_err(|e| Error::deserialization("XML", e.to_string()))?;
+                let message = xml
+                    .strip_prefix("<error>")
+                    .and_then(|xml| xml.strip_suffix("</error>"))
+                    .ok_or_else(|| Error::deserialization("XML", "not an error element"))?;
+                crate::from_json(
+                    serde_json::json!({ "error": message })
+                        .to_string()
+                        .as_bytes(),
+                )
+            }
+        }
+
+        let err = Error::http_with_body(404, "Not Found", "<error>not found</error>".into())
+            .with_content_type("application/problem+xml");
+        let decoded = err.decode_body_with::<ApiError>(&ErrorTag).expect("body");
+        assert_eq!(
+            decoded.expect("xml"),
+            ApiError {
+                error: "not found".to_string()
+            }
+        );
+
+        // Other content types are decoded as with decode_body_as
+        let err = Error::http_with_body(404, "Not Found", r#"{"error": "gone"}"#.into())
+            .with_content_type("application/json");
+        let decoded = err.decode_body_with::<ApiError>(&ErrorTag).expect("body");
+        assert_eq!(decoded.expect("json").error, "gone");
+    }
+
+    #[test]
+    fn error_from_response() {
+        let headers = HeaderMap::from_iter([
+            (
+                http::header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (http::header::RETRY_AFTER, HeaderValue::from_static("30")),
+        ]);
+        let response = crate::Response::new(503, headers, bytes::Bytes::from("{}"));
+
+        let err = Error::from_response(response);
+        assert_eq!(err.status(), Some(503));
+        assert_eq!(err.content_type(), Some("application/json"));
+        assert_eq!(err.header("Retry-After"), Some("30"));
+        assert_eq!(err.headers().map(HeaderMap::len), Some(2));
+        assert!(Error::Timeout.headers().is_none());
+        assert_eq!(err.body().map(AsRef::as_ref), Some(b"{}".as_ref()));
+    }
+
+    #[test]
+    fn error_rate_limited_from_response() {
+        let headers = HeaderMap::from_iter([
+            (http::header::RETRY_AFTER, HeaderValue::from_static("30")),
+            (
+                HeaderName::from_static("x-ratelimit-remaining"),
+                HeaderValue::from_static("0"),
+            ),
+        ]);
+        let response = crate::Response::new(429, headers, bytes::Bytes::from("slow down"));
+
+        let err = Error::from_response(response);
+        assert!(err.is_rate_limited());
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(30)));
+        assert_eq!(err.status(), Some(429));
+        assert!(err.is_client_error());
+        assert_eq!(err.body().map(AsRef::as_ref), Some(b"slow down".as_ref()));
+        assert_eq!(err.to_string(), "rate limited (retry after 30s)");
+        let Error::RateLimited { limit_info, .. } = err else {
+            unreachable!("rate limited");
+        };
+        assert_eq!(limit_info.remaining, Some(0));
+
+        assert!(!Error::http(429, "Too Many Requests").is_rate_limited());
+    }
+
+    #[test]
+    fn error_precondition_failed_from_response() {
+        let headers =
+            HeaderMap::from_iter([(http::header::ETAG, HeaderValue::from_static("\"v2\""))]);
+        let response = crate::Response::new(412, headers, bytes::Bytes::from("stale"));
+
+        let err = Error::from_response(response);
+        assert!(err.is_precondition_failed());
+        assert_eq!(err.status(), Some(412));
+        assert_eq!(err.header("etag"), Some("\"v2\""));
+        assert_eq!(err.to_string(), "precondition failed (body: stale)");
+    }
+
     #[test]
     fn default_error_decoder() {
         let decoder = DefaultErrorDecoder;
@@ -0,0 +1,140 @@
+//! Newline-delimited JSON (NDJSON) streams.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use futures_core::Stream;
+use serde::de::DeserializeOwned;
+
+use crate::text_lines::LineSplitter;
+use crate::{Result, StreamingBody, from_json};
+
+/// Stream of the values of a newline-delimited JSON body (`application/x-ndjson`,
+/// JSON Lines), deserialized one line at a time as the chunks arrive.
+///
+/// Blank lines are skipped, and a last line without a trailing newline is
+/// decoded at the end of the body. The stream ends after an error reading
+/// the body; a line failing to deserialize, or longer than the
+/// [maximum length](Self::max_line_length), yields an error and the stream
+/// goes on with the next line.
+///
+/// # Example
+///
+/// ```ignore
+/// use futures_util::StreamExt;
+///
+/// let mut events = client.export_events().await?;
+/// while let Some(event) = events.next().await {
+///     process(event?);
+/// }
+/// ```
+pub struct JsonLines<T> {
+    lines: LineSplitter,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for JsonLines<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonLines")
+            .field("buffered", &self.lines.buffered())
+            .field("max_line_length", &self.lines.max_line_length)
+            .field("done", &self.lines.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> JsonLines<T> {
+    /// Create a stream of the lines of `body`.
+    #[must_use]
+    pub fn new(body: StreamingBody) -> Self {
+        Self {
+            lines: LineSplitter::new(body, "JSON"),
+            _item: PhantomData,
+        }
+    }
+
+    /// Set the maximum length of a line, without its line ending (default:
+    /// [`DEFAULT_MAX_LINE_LENGTH`](crate::DEFAULT_MAX_LINE_LENGTH)).
+    #[must_use]
+    pub const fn max_line_length(mut self, max_line_length: usize) -> Self {
+        self.lines.max_line_length = max_line_length;
+        self
+    }
+}
+
+impl<T: DeserializeOwned> Stream for JsonLines<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            match ready!(self.lines.poll_line(cx)) {
+                Some(Ok(line)) => {
+                    let line = line.trim_ascii();
+                    if !line.is_empty() {
+                        return Poll::Ready(Some(from_json(line)));
+                    }
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures_util::{StreamExt, stream};
+
+    use super::*;
+    use crate::Error;
+
+    fn lines<T: DeserializeOwned>(chunks: Vec<Result<&'static str>>) -> JsonLines<T> {
+        let chunks = chunks
+            .into_iter()
+            .map(|chunk| chunk.map(|chunk| Bytes::from_static(chunk.as_bytes())));
+        JsonLines::new(Box::pin(stream::iter(chunks)))
+    }
+
+    #[tokio::test]
+    async fn splits_lines_across_chunks() {
+        let values: Vec<u32> = lines::<u32>(vec![Ok("1\n2"), Ok("3\r\n\n 4 \n"), Ok("5")])
+            .map(|value| value.expect("value"))
+            .collect()
+            .await;
+        assert_eq!(values, [1, 23, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn reports_invalid_lines_and_body_errors() {
+        let results: Vec<Result<u32>> = lines(vec![
+            Ok("1\nnope\n2\n"),
+            Err(Error::connection("reset")),
+            Ok("3\n"),
+        ])
+        .collect()
+        .await;
+        let values: Vec<_> = results.iter().map(|result| result.as_ref().ok()).collect();
+        assert_eq!(values, [Some(&1), None, Some(&2), None]);
+    }
+
+    #[tokio::test]
+    async fn skips_lines_too_long() {
+        let results: Vec<Result<String>> = lines(vec![Ok("\"ok\"\n\"too "), Ok("long\"\n\"ab\"")])
+            .max_line_length(5)
+            .collect()
+            .await;
+        let values: Vec<_> = results
+            .iter()
+            .map(|result| result.as_ref().ok().map(String::as_str))
+            .collect();
+        assert_eq!(values, [Some("ok"), None, Some("ab")]);
+        let err = results.get(1).and_then(|result| result.as_ref().err());
+        assert_eq!(
+            err.map(ToString::to_string).as_deref(),
+            Some("JSON deserialization error: line longer than 5 bytes")
+        );
+    }
+}
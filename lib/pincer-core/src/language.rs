@@ -0,0 +1,224 @@
+//! Language negotiation headers.
+//!
+//! [`AcceptLanguage`] renders a fallback chain of language tags as an
+//! `Accept-Language` value with decreasing quality values (RFC 9110 §12.5.4),
+//! and [`Response::content_language`] reads the languages of a response.
+
+use std::fmt;
+
+use crate::Response;
+
+/// Lowest quality value rendered, in tenths.
+const MIN_QUALITY_TENTHS: usize = 1;
+
+/// Ordered fallback chain of language tags, preferred first.
+///
+/// Rendered with quality values decreasing by `0.1` from `1` (omitted), down
+/// to `0.1` for the tenth language and beyond.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::AcceptLanguage;
+///
+/// let languages = AcceptLanguage::new(["fr-CH", "fr", "en"]);
+/// assert_eq!(languages.to_string(), "fr-CH, fr;q=0.9, en;q=0.8");
+///
+/// // Header values are parsed, ordered by quality
+/// let languages = AcceptLanguage::from("en;q=0.5, de, *;q=0");
+/// assert_eq!(languages.tags().collect::<Vec<_>>(), vec!["de", "en"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AcceptLanguage {
+    tags: Vec<String>,
+}
+
+impl AcceptLanguage {
+    /// Create a fallback chain from language tags, preferred first.
+    ///
+    /// Blank tags are skipped.
+    #[must_use]
+    pub fn new(tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let tags = tags
+            .into_iter()
+            .map(Into::into)
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        Self { tags }
+    }
+
+    /// Parse an `Accept-Language` value, ordering the languages by quality.
+    ///
+    /// Languages with a quality of `0` (not acceptable) are dropped, and
+    /// languages with the same quality keep their order.
+    #[must_use]
+    pub fn parse(value: &str) -> Self {
+        let mut weighted: Vec<(u16, &str)> = value
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .filter_map(|param| param.trim().strip_prefix("q="))
+                    .find_map(parse_quality)
+                    .unwrap_or(1000);
+                (!tag.is_empty() && quality > 0).then_some((quality, tag))
+            })
+            .collect();
+        weighted.sort_by_key(|&(quality, _)| std::cmp::Reverse(quality));
+        Self::new(weighted.into_iter().map(|(_, tag)| tag))
+    }
+
+    /// Get the language tags, preferred first.
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(String::as_str)
+    }
+
+    /// Returns `true` if there is no language.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+/// Parse a quality value (`0` to `1`, up to three decimals) into thousandths.
+fn parse_quality(value: &str) -> Option<u16> {
+    let (integer, decimals) = value.trim().split_once('.').unwrap_or((value.trim(), ""));
+    if decimals.len() > 3 || !decimals.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let integer: u16 = match integer {
+        "0" => 0,
+        "1" => 1000,
+        _ => return None,
+    };
+    let decimals: u16 = format!("{decimals:0<3}").parse().ok()?;
+    let quality = integer + decimals;
+    (quality <= 1000).then_some(quality)
+}
+
+impl fmt::Display for AcceptLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, tag) in self.tags.iter().enumerate() {
+            if index == 0 {
+                f.write_str(tag)?;
+            } else {
+                let tenths = 10usize.saturating_sub(index).max(MIN_QUALITY_TENTHS);
+                write!(f, ", {tag};q=0.{tenths}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for AcceptLanguage {
+    fn from(value: &str) -> Self {
+        Self::parse(value)
+    }
+}
+
+impl From<String> for AcceptLanguage {
+    fn from(value: String) -> Self {
+        Self::parse(&value)
+    }
+}
+
+impl From<&String> for AcceptLanguage {
+    fn from(value: &String) -> Self {
+        Self::parse(value)
+    }
+}
+
+impl<T: Into<String>> From<Vec<T>> for AcceptLanguage {
+    fn from(tags: Vec<T>) -> Self {
+        Self::new(tags)
+    }
+}
+
+impl<T: Into<String>, const N: usize> From<[T; N]> for AcceptLanguage {
+    fn from(tags: [T; N]) -> Self {
+        Self::new(tags)
+    }
+}
+
+impl From<&[&str]> for AcceptLanguage {
+    fn from(tags: &[&str]) -> Self {
+        Self::new(tags.iter().copied())
+    }
+}
+
+impl<T: Into<String>> FromIterator<T> for AcceptLanguage {
+    fn from_iter<I: IntoIterator<Item = T>>(tags: I) -> Self {
+        Self::new(tags)
+    }
+}
+
+impl<B> Response<B> {
+    /// Get the languages of the response from its `Content-Language` headers.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// if response.content_language().contains(&"fr") {
+    ///     // localized content
+    /// }
+    /// ```
+    #[must_use]
+    pub fn content_language(&self) -> Vec<&str> {
+        self.headers()
+            .get_all(http::header::CONTENT_LANGUAGE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use http::{HeaderMap, HeaderValue};
+
+    use super::*;
+
+    #[test]
+    fn accept_language_quality_values() {
+        assert_eq!(AcceptLanguage::new(["en"]).to_string(), "en");
+        assert_eq!(
+            AcceptLanguage::new(["fr-CH", " fr ", "", "en"]).to_string(),
+            "fr-CH, fr;q=0.9, en;q=0.8"
+        );
+
+        let many = AcceptLanguage::new((0..12).map(|i| format!("l{i}"))).to_string();
+        assert!(many.ends_with("l9;q=0.1, l10;q=0.1, l11;q=0.1"), "{many}");
+        assert!(AcceptLanguage::default().to_string().is_empty());
+    }
+
+    #[test]
+    fn accept_language_parse() {
+        let languages = AcceptLanguage::parse("en;q=0.5, fr-CH, de;q=0.800, it;q=0.5, *;q=0");
+        assert_eq!(
+            languages.tags().collect::<Vec<_>>(),
+            vec!["fr-CH", "de", "en", "it"]
+        );
+
+        // Invalid quality values are ignored
+        let languages = AcceptLanguage::parse("en;q=2, fr;q=0.1234");
+        assert_eq!(languages.tags().collect::<Vec<_>>(), vec!["en", "fr"]);
+    }
+
+    #[test]
+    fn content_language_header() {
+        let mut headers = HeaderMap::new();
+        headers.append("content-language", HeaderValue::from_static("fr, en-GB"));
+        headers.append("content-language", HeaderValue::from_static("de"));
+        let response = Response::new(200, headers, Bytes::new());
+        assert_eq!(response.content_language(), vec!["fr", "en-GB", "de"]);
+
+        let response = Response::new(200, HeaderMap::new(), Bytes::new());
+        assert!(response.content_language().is_empty());
+    }
+}
@@ -4,42 +4,146 @@
 //! - [`Method`] - HTTP method enum
 //! - [`Request`] and [`RequestBuilder`] - HTTP request types
 //! - [`Response`] - HTTP response type
+//! - [`ConnectionInfo`] - Addresses of the connection a response was received on
+//! - [`DiscardBody`] - Skip reading the body of successful responses nobody reads
+//! - [`InspectHead`] - Decide from the status and headers how much of a body to read
+//! - [`TruncatedBody`] - Mark a body cut at a limit, e.g. a large error body
 //! - [`Error`] and [`Result`] - Error handling
 //! - [`HttpClient`] - Core client trait for HTTP execution
 //! - [`PincerClient`] - Extended client trait with base URL support
+//! - [`DynPincerClient`] - Object-safe client trait, for runtime-selected clients
 //! - [`StatusCode`] - HTTP status codes (re-exported from `http` crate)
 //! - [`header`] - HTTP header names (re-exported from `http` crate)
 //! - [`ToQueryPairs`] - Trait for converting types to query parameter pairs
+//! - [`deep_object_pairs`] - `deepObject` style of query parameters (`filter[status]=active`)
 //! - [`PathTemplate`] - Original path template for middleware access
+//! - [`Endpoint`] - Endpoint described at runtime, for APIs only known once configured
+//! - [`EndpointRegistry`] and [`DynamicClient`] - Endpoints loaded from configuration, called by name
+//! - [`ClientName`] - Name of the generated client, for middleware access
+//! - [`Page`] - One page of a paginated collection
+//! - [`BufferPool`] - Reusable buffers for encoding request bodies
+//! - [`BodyCodec`] - Pluggable body serializer: [`SerdeJson`] for JSON by
+//!   default, `QuickXml` for XML and `Ciborium` for CBOR with the `xml` and
+//!   `cbor` features
+//! - `from_csv` - Typed records of a CSV body (`csv` feature), streamed by
+//!   `CsvRecords` (with the `streaming` feature)
+//! - `JsonLines` - Stream of the values of an NDJSON body (requires `streaming` feature)
+//! - `TextLines` - Stream of the lines of a text body, e.g. a followed log (requires `streaming` feature)
+//! - [`RateLimitInfo`] - Rate limit quota advertised by response headers
+//! - [`AllowedMethods`] - Methods and CORS policy advertised by `OPTIONS` responses
+//! - [`CookieStore`] - Cookies received from servers, replayed on matching requests
+//! - [`Runtime`] - Timers and task spawning of the async runtime, see [`timeout`]
+//! - [`format_timestamp`] - Timestamp formatting of query and path parameters
+//! - [`check_url_length`] - URL length guard, with a [`PostFallback`] for long queries
+//! - [`Redactor`] - Redaction of sensitive query parameters in rendered URLs
+//! - [`encoding`] - Response body decoding (`gzip`, `brotli` and `zstd` features)
+//! - `webhook` - Inbound webhook signature verification (requires `webhook` feature)
 
+mod allowed_methods;
 mod body;
+mod call_context;
+#[cfg(feature = "checksum")]
+mod checksum;
 mod client;
+mod client_name;
+mod cookie;
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(all(feature = "csv", feature = "streaming"))]
+mod csv_records;
+mod deep_object;
+pub mod encoding;
+mod endpoint;
+mod entity_tag;
 mod error;
+#[cfg(feature = "streaming")]
+mod json_lines;
+mod language;
 mod method;
 mod multipart;
+mod page;
 mod param_meta;
 mod path_template;
+mod pool;
 pub mod prelude;
+mod rate_limit;
+mod redact;
+mod registry;
 mod request;
 mod response;
+mod runtime;
+mod strict;
+#[cfg(feature = "streaming")]
+mod text_lines;
+mod timestamp;
+mod url_length;
+#[cfg(feature = "webhook")]
+pub mod webhook;
 
-pub use body::{ContentType, from_json, to_form, to_json, to_query_string};
-pub use client::{HttpClient, HttpClientExt, PincerClient};
-pub use error::{DefaultErrorDecoder, Error, ErrorDecoder, Result};
+#[cfg(feature = "csv")]
+pub use crate::csv::from_csv;
+pub use allowed_methods::AllowedMethods;
+#[cfg(feature = "cbor")]
+pub use body::Ciborium;
+#[cfg(feature = "xml")]
+pub use body::QuickXml;
+#[cfg(feature = "toml")]
+pub use body::from_toml;
+#[cfg(feature = "yaml")]
+pub use body::from_yaml;
+pub use body::{
+    BodyCodec, ContentType, SerdeJson, from_json, from_json_at, to_form, to_json,
+    to_json_canonical, to_query_string,
+};
+pub use call_context::CallContext;
+pub use client::{BoxResponseFuture, DynPincerClient, HttpClient, HttpClientExt, PincerClient};
+pub use client_name::ClientName;
+pub use cookie::CookieStore;
+pub use deep_object::deep_object_pairs;
+pub use endpoint::{
+    Endpoint, PATH_SEGMENT_ENCODE_SET, QUERY_COMPONENT_ENCODE_SET, RESERVED_ENCODE_SET,
+};
+pub use entity_tag::EntityTag;
+pub use error::{
+    BodyPreviewLen, DEFAULT_BODY_PREVIEW_LEN, DefaultErrorDecoder, Error, ErrorDecoder, Result,
+};
+pub use language::AcceptLanguage;
 pub use method::Method;
 pub use multipart::{Form, Part};
-pub use param_meta::{ParamLocation, ParamMeta, ParameterMetadata};
+pub use page::Page;
+pub use param_meta::{Deprecation, ParamLocation, ParamMeta, ParameterMetadata};
 pub use path_template::PathTemplate;
+pub use pool::BufferPool;
+pub use rate_limit::RateLimitInfo;
+pub use redact::{REDACTED, Redactor};
+pub use registry::{DynamicClient, EndpointRegistry, EndpointSpec};
 pub use request::{Request, RequestBuilder};
-pub use response::Response;
+pub use response::{
+    BodyAction, ConnectionInfo, DEFAULT_ERROR_BODY_LIMIT, DiscardBody, InspectHead, Response,
+    TruncatedBody,
+};
+pub use runtime::{Runtime, Sleep, timeout};
+pub use strict::{StrictJson, from_json_strict};
+pub use timestamp::format_timestamp;
+pub use url_length::{PostFallback, check_url_length};
 
 // Re-export http crate types for status codes and headers
 pub use http::{StatusCode, header};
 
+// Re-export the HTML parser of `Response::html`
+#[cfg(feature = "format-html")]
+pub use scraper;
+
 #[cfg(feature = "streaming")]
 pub use client::HttpClientStreaming;
+#[cfg(all(feature = "csv", feature = "streaming"))]
+pub use csv_records::CsvRecords;
+#[cfg(feature = "streaming")]
+pub use json_lines::JsonLines;
+#[cfg(feature = "streaming")]
+pub use response::streaming::{StreamingBody, StreamingResponse, Trailers, TrailersSender};
 #[cfg(feature = "streaming")]
-pub use response::streaming::{StreamingBody, StreamingResponse};
+pub use text_lines::{DEFAULT_MAX_LINE_LENGTH, TextLines};
 
 /// Trait for types that can be converted to query parameter pairs.
 ///
@@ -65,3 +169,23 @@ pub trait ToQueryPairs {
     /// Convert this type to a vector of key-value pairs for query parameters.
     fn to_query_pairs(&self) -> Vec<(String, String)>;
 }
+
+/// One pair per entry, e.g. for `deepObject` query parameters.
+impl<K: std::fmt::Display, V: std::fmt::Display, S> ToQueryPairs
+    for std::collections::HashMap<K, V, S>
+{
+    fn to_query_pairs(&self) -> Vec<(String, String)> {
+        self.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+}
+
+/// One pair per entry, in key order.
+impl<K: std::fmt::Display, V: std::fmt::Display> ToQueryPairs for std::collections::BTreeMap<K, V> {
+    fn to_query_pairs(&self) -> Vec<(String, String)> {
+        self.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+}
@@ -45,6 +45,26 @@ impl Method {
     }
 }
 
+impl std::str::FromStr for Method {
+    type Err = crate::Error;
+
+    /// Parse a method name, case-insensitively (e.g., `GET` or `get`).
+    fn from_str(method: &str) -> Result<Self, Self::Err> {
+        [
+            Self::Get,
+            Self::Post,
+            Self::Put,
+            Self::Delete,
+            Self::Patch,
+            Self::Head,
+            Self::Options,
+        ]
+        .into_iter()
+        .find(|candidate| method.eq_ignore_ascii_case(&candidate.to_string()))
+        .ok_or_else(|| crate::Error::InvalidRequest(format!("unsupported HTTP method: {method}")))
+    }
+}
+
 impl From<Method> for http::Method {
     fn from(method: Method) -> Self {
         match method {
@@ -115,6 +135,13 @@ mod tests {
         assert!(!Method::Patch.is_idempotent());
     }
 
+    #[test]
+    fn method_from_str() {
+        assert_eq!("GET".parse::<Method>().expect("GET"), Method::Get);
+        assert_eq!("patch".parse::<Method>().expect("patch"), Method::Patch);
+        assert!("TRACE".parse::<Method>().is_err());
+    }
+
     #[test]
     fn method_into_http() {
         assert_eq!(http::Method::from(Method::Get), http::Method::GET);
@@ -12,21 +12,47 @@
 //!     .part(Part::text("name", "John Doe"))
 //!     .part(Part::file("avatar", "photo.jpg", photo_bytes));
 //!
-//! let (content_type, body) = form.into_body();
+//! let (content_type, body) = form.into_body()?;
 //! ```
+//!
+//! With the `streaming` feature, parts can be backed by an `AsyncRead` and the
+//! form emitted as a stream with `Form::into_stream`.
 
 use bytes::{BufMut, Bytes, BytesMut};
 
+/// Size of the chunks read from reader-backed parts.
+#[cfg(feature = "streaming")]
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Reader backing a streamed part, taken when the form is streamed.
+#[cfg(feature = "streaming")]
+type SharedReader = std::sync::Arc<
+    std::sync::Mutex<Option<std::pin::Pin<Box<dyn tokio::io::AsyncRead + Send + 'static>>>>,
+>;
+
 /// A single part in a multipart form.
 ///
 /// Each part can be text, binary data, or a file with optional filename
 /// and content type.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Part {
     name: String,
     filename: Option<String>,
     content_type: Option<String>,
     data: Bytes,
+    #[cfg(feature = "streaming")]
+    reader: Option<SharedReader>,
+}
+
+impl std::fmt::Debug for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Part")
+            .field("name", &self.name)
+            .field("filename", &self.filename)
+            .field("content_type", &self.content_type)
+            .field("data", &self.data)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Part {
@@ -38,6 +64,8 @@ impl Part {
             filename: None,
             content_type: None,
             data: data.into(),
+            #[cfg(feature = "streaming")]
+            reader: None,
         }
     }
 
@@ -51,6 +79,8 @@ impl Part {
             filename: None,
             content_type: Some("text/plain; charset=utf-8".to_string()),
             data: Bytes::from(value.into()),
+            #[cfg(feature = "streaming")]
+            reader: None,
         }
     }
 
@@ -64,6 +94,8 @@ impl Part {
             filename: None,
             content_type: Some("application/octet-stream".to_string()),
             data: data.into(),
+            #[cfg(feature = "streaming")]
+            reader: None,
         }
     }
 
@@ -84,9 +116,42 @@ impl Part {
             filename: Some(filename),
             content_type: Some(content_type),
             data: data.into(),
+            #[cfg(feature = "streaming")]
+            reader: None,
+        }
+    }
+
+    /// Create a part whose content is read from `reader` while streaming.
+    ///
+    /// The content is only emitted by [`Form::into_stream`], read in bounded
+    /// chunks as the body is sent. Clones of the part share the reader, so
+    /// it can only be streamed once.
+    ///
+    /// Sets the content type to `application/octet-stream`.
+    #[cfg(feature = "streaming")]
+    #[must_use]
+    pub fn reader(
+        name: impl Into<String>,
+        reader: impl tokio::io::AsyncRead + Send + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            filename: None,
+            content_type: Some("application/octet-stream".to_string()),
+            data: Bytes::new(),
+            reader: Some(std::sync::Arc::new(std::sync::Mutex::new(Some(Box::pin(
+                reader,
+            ))))),
         }
     }
 
+    /// Set the name for this part, keeping its content.
+    #[must_use]
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
     /// Set the filename for this part.
     #[must_use]
     pub fn with_filename(mut self, filename: impl Into<String>) -> Self {
@@ -120,10 +185,48 @@ impl Part {
     }
 
     /// Get the part data.
+    ///
+    /// Empty for parts backed by a reader.
     #[must_use]
     pub fn data(&self) -> &Bytes {
         &self.data
     }
+
+    /// Returns `true` if the part content comes from a reader.
+    #[cfg(feature = "streaming")]
+    #[must_use]
+    pub fn is_streamed(&self) -> bool {
+        self.reader.is_some()
+    }
+
+    /// Encode the part headers, including the blank line before the data.
+    fn encode_headers(&self, boundary: &str, buf: &mut BytesMut) {
+        // Boundary
+        buf.put_slice(b"--");
+        buf.put_slice(boundary.as_bytes());
+        buf.put_slice(b"\r\n");
+
+        // Content-Disposition
+        buf.put_slice(b"Content-Disposition: form-data; name=\"");
+        buf.put_slice(self.name.as_bytes());
+        buf.put_slice(b"\"");
+        if let Some(filename) = &self.filename {
+            buf.put_slice(b"; filename=\"");
+            buf.put_slice(filename.as_bytes());
+            buf.put_slice(b"\"");
+        }
+        buf.put_slice(b"\r\n");
+
+        // Content-Type (optional)
+        if let Some(content_type) = &self.content_type {
+            buf.put_slice(b"Content-Type: ");
+            buf.put_slice(content_type.as_bytes());
+            buf.put_slice(b"\r\n");
+        }
+
+        // Empty line before data
+        buf.put_slice(b"\r\n");
+    }
 }
 
 /// Guess the content type from a filename extension.
@@ -264,43 +367,136 @@ impl Form {
     /// Convert the form into a body.
     ///
     /// Returns a tuple of (content-type header value, body bytes).
-    #[must_use]
-    pub fn into_body(self) -> (String, Bytes) {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a part is backed by a reader; use
+    /// [`into_stream`](Self::into_stream) for such forms.
+    pub fn into_body(self) -> crate::Result<(String, Bytes)> {
+        self.check_buffered()?;
         let content_type = self.content_type();
         let body = self.encode();
-        (content_type, body)
+        Ok((content_type, body))
     }
 
-    /// Encode the form into bytes.
-    fn encode(&self) -> Bytes {
-        let mut buf = BytesMut::new();
+    /// Convert the form into a body encoded into a buffer from `pool`.
+    ///
+    /// Same as [`into_body`](Self::into_body), without allocating a new
+    /// buffer for every form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a part is backed by a reader.
+    pub fn into_body_pooled(self, pool: &crate::BufferPool) -> crate::Result<(String, Bytes)> {
+        self.check_buffered()?;
+        let content_type = self.content_type();
+        let body = pool.encode(|buf| {
+            self.encode_into(buf);
+            Ok(())
+        })?;
+        Ok((content_type, body))
+    }
+
+    /// Reject parts whose content can only be streamed.
+    #[cfg_attr(
+        not(feature = "streaming"),
+        allow(clippy::unused_self, clippy::unnecessary_wraps)
+    )]
+    fn check_buffered(&self) -> crate::Result<()> {
+        #[cfg(feature = "streaming")]
+        if let Some(part) = self.parts.iter().find(|part| part.is_streamed()) {
+            return Err(crate::Error::invalid_request(format!(
+                "multipart part {} is backed by a reader and must be sent with Form::into_stream",
+                part.name
+            )));
+        }
+        Ok(())
+    }
 
-        for part in &self.parts {
-            // Boundary
-            buf.put_slice(b"--");
-            buf.put_slice(self.boundary.as_bytes());
-            buf.put_slice(b"\r\n");
+    /// Convert the form into a streaming body.
+    ///
+    /// Returns a tuple of (content-type header value, body stream). The body
+    /// is produced lazily: reader-backed parts are read in bounded chunks only
+    /// when the consumer polls for more data, so a slow connection applies
+    /// backpressure to the readers.
+    ///
+    /// A streamed body cannot be replayed: `HyperClient` sends it through
+    /// its connection pool but bypasses the middleware stack (auth, retries,
+    /// logging, ...), so headers added by layers must be set on the request.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::{Form, HttpClientStreaming, Part};
+    ///
+    /// let file = tokio::fs::File::open("backup.tar").await?;
+    /// let form = Form::new().part(Part::reader("archive", file).with_filename("backup.tar"));
+    ///
+    /// let (content_type, body) = form.into_stream();
+    /// let request = Request::builder(Method::Post, url)
+    ///     .header("content-type", content_type)
+    ///     .build();
+    /// let response = client.execute_upload(request, body).await?;
+    /// ```
+    #[cfg(feature = "streaming")]
+    #[must_use]
+    pub fn into_stream(self) -> (String, crate::StreamingBody) {
+        use futures_util::{StreamExt, TryStreamExt, stream};
 
-            // Content-Disposition
-            buf.put_slice(b"Content-Disposition: form-data; name=\"");
-            buf.put_slice(part.name.as_bytes());
-            buf.put_slice(b"\"");
-            if let Some(filename) = &part.filename {
-                buf.put_slice(b"; filename=\"");
-                buf.put_slice(filename.as_bytes());
-                buf.put_slice(b"\"");
-            }
-            buf.put_slice(b"\r\n");
+        let content_type = self.content_type();
+        let mut chunks: Vec<crate::StreamingBody> = Vec::with_capacity(self.parts.len() + 1);
 
-            // Content-Type (optional)
-            if let Some(content_type) = &part.content_type {
-                buf.put_slice(b"Content-Type: ");
-                buf.put_slice(content_type.as_bytes());
-                buf.put_slice(b"\r\n");
+        for part in &self.parts {
+            let mut head = BytesMut::new();
+            part.encode_headers(&self.boundary, &mut head);
+            head.put_slice(&part.data);
+            chunks.push(Box::pin(stream::once(async move { Ok(head.freeze()) })));
+
+            if let Some(reader) = &part.reader {
+                let name = part.name.clone();
+                let reader = reader
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .take();
+                let content: crate::StreamingBody = match reader {
+                    Some(reader) => Box::pin(
+                        tokio_util::io::ReaderStream::with_capacity(reader, READ_CHUNK_SIZE)
+                            .map_err(move |e| {
+                                crate::Error::invalid_request(format!(
+                                    "failed to read multipart part {name}: {e}"
+                                ))
+                            }),
+                    ),
+                    None => Box::pin(stream::once(async move {
+                        Err(crate::Error::invalid_request(format!(
+                            "multipart part {name} was already streamed"
+                        )))
+                    })),
+                };
+                chunks.push(content);
             }
 
-            // Empty line before data
-            buf.put_slice(b"\r\n");
+            chunks.push(Box::pin(stream::once(async {
+                Ok(Bytes::from_static(b"\r\n"))
+            })));
+        }
+
+        let closing = Bytes::from(format!("--{}--\r\n", self.boundary));
+        chunks.push(Box::pin(stream::once(async move { Ok(closing) })));
+
+        (content_type, Box::pin(stream::iter(chunks).flatten()))
+    }
+
+    /// Encode the form into bytes.
+    fn encode(&self) -> Bytes {
+        let mut buf = BytesMut::new();
+        self.encode_into(&mut buf);
+        buf.freeze()
+    }
+
+    fn encode_into(&self, buf: &mut BytesMut) {
+        for part in &self.parts {
+            part.encode_headers(&self.boundary, buf);
 
             // Data
             buf.put_slice(&part.data);
@@ -311,8 +507,6 @@ impl Form {
         buf.put_slice(b"--");
         buf.put_slice(self.boundary.as_bytes());
         buf.put_slice(b"--\r\n");
-
-        buf.freeze()
     }
 }
 
@@ -322,8 +516,7 @@ fn generate_boundary() -> String {
 
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos())
-        .unwrap_or(0);
+        .map_or(0, |d| d.as_nanos());
 
     format!("----PincerBoundary{timestamp:x}")
 }
@@ -332,6 +525,21 @@ fn generate_boundary() -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn form_into_body_pooled() {
+        let form = Form::with_boundary("boundary").text("name", "Alice").file(
+            "avatar",
+            "a.png",
+            vec![1, 2, 3],
+        );
+        let pool = crate::BufferPool::new();
+
+        let (content_type, pooled) = form.clone().into_body_pooled(&pool).expect("body");
+        let (_, body) = form.into_body().expect("body");
+        assert_eq!(content_type, "multipart/form-data; boundary=boundary");
+        assert_eq!(pooled, body);
+    }
+
     #[test]
     fn part_text() {
         let part = Part::text("field", "value");
@@ -399,7 +607,7 @@ mod tests {
     fn form_encode() {
         let form = Form::with_boundary("boundary123").text("field", "value");
 
-        let (content_type, body) = form.into_body();
+        let (content_type, body) = form.into_body().expect("body");
 
         assert_eq!(content_type, "multipart/form-data; boundary=boundary123");
 
@@ -414,7 +622,7 @@ mod tests {
     fn form_encode_with_file() {
         let form = Form::with_boundary("boundary456").file("upload", "test.txt", "file content");
 
-        let (_, body) = form.into_body();
+        let (_, body) = form.into_body().expect("body");
         let body_str = String::from_utf8_lossy(&body);
 
         assert!(body_str.contains("name=\"upload\"; filename=\"test.txt\""));
@@ -422,6 +630,91 @@ mod tests {
         assert!(body_str.contains("file content\r\n"));
     }
 
+    #[cfg(feature = "streaming")]
+    async fn collect(body: crate::StreamingBody) -> crate::Result<Vec<u8>> {
+        use futures_util::TryStreamExt;
+
+        body.try_fold(Vec::new(), |mut acc, chunk| async move {
+            acc.extend_from_slice(&chunk);
+            Ok(acc)
+        })
+        .await
+    }
+
+    #[cfg(feature = "streaming")]
+    #[tokio::test]
+    async fn form_stream_matches_buffered_encoding() {
+        let form = Form::with_boundary("b").text("field", "value").file(
+            "upload",
+            "test.txt",
+            "file content",
+        );
+        let (_, expected) = form.clone().into_body().expect("body");
+
+        let (content_type, body) = form.into_stream();
+
+        assert_eq!(content_type, "multipart/form-data; boundary=b");
+        assert_eq!(collect(body).await.expect("body"), expected.as_ref());
+    }
+
+    #[cfg(feature = "streaming")]
+    #[tokio::test]
+    async fn form_stream_reads_reader_in_chunks() {
+        let content = vec![b'x'; READ_CHUNK_SIZE * 2 + 10];
+        let form = Form::with_boundary("b")
+            .part(Part::reader("data", std::io::Cursor::new(content.clone())));
+
+        let (_, body) = form.into_stream();
+        let chunks: Vec<Bytes> = futures_util::TryStreamExt::try_collect(body)
+            .await
+            .expect("chunks");
+
+        assert!(
+            chunks
+                .iter()
+                .all(|chunk| chunk.len() <= READ_CHUNK_SIZE + 128)
+        );
+        let body: Vec<u8> = chunks.concat();
+        let body_str = String::from_utf8_lossy(&body);
+        assert!(body_str.starts_with("--b\r\nContent-Disposition: form-data; name=\"data\"\r\n"));
+        assert!(body_str.contains(std::str::from_utf8(&content).expect("utf8")));
+        assert!(body_str.ends_with("\r\n--b--\r\n"));
+    }
+
+    #[cfg(feature = "streaming")]
+    #[tokio::test]
+    async fn form_stream_reader_is_taken_once() {
+        let form = Form::with_boundary("b").part(Part::reader("data", std::io::Cursor::new("x")));
+        assert!(form.parts().first().expect("part").is_streamed());
+
+        let (_, first) = form.clone().into_stream();
+        collect(first).await.expect("first stream");
+
+        let (_, second) = form.into_stream();
+        let err = collect(second).await.expect_err("reader consumed");
+        assert!(err.to_string().contains("already streamed"));
+    }
+
+    #[cfg(feature = "streaming")]
+    #[test]
+    fn form_into_body_rejects_reader_parts() {
+        let form = Form::with_boundary("b")
+            .text("field", "value")
+            .part(Part::reader("data", std::io::Cursor::new("x")));
+
+        let err = form.clone().into_body().expect_err("reader part");
+        assert!(err.to_string().contains("multipart part data"));
+        assert!(form.into_body_pooled(&crate::BufferPool::new()).is_err());
+    }
+
+    #[cfg(feature = "streaming")]
+    #[test]
+    fn part_with_name_keeps_reader() {
+        let part = Part::reader("data", std::io::Cursor::new("x")).with_name("file");
+        assert_eq!(part.name(), "file");
+        assert!(part.is_streamed());
+    }
+
     #[test]
     fn guess_content_type_common() {
         assert_eq!(guess_content_type("photo.jpg"), "image/jpeg");
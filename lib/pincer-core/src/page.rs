@@ -0,0 +1,402 @@
+//! Pagination metadata.
+//!
+//! [`Page`] is a single container for one page of results, whatever the
+//! server pagination style:
+//! - [`Response::paginate_link_header`] - JSON array body with an RFC 8288
+//!   `Link` header (GitHub style)
+//! - [`Response::paginate_envelope`] - JSON object wrapping the items with
+//!   cursor and count fields
+
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{Error, Response, Result};
+
+/// Envelope fields holding the next page cursor.
+const NEXT_CURSOR_FIELDS: &[&str] = &[
+    "next_cursor",
+    "nextCursor",
+    "next_page_token",
+    "nextPageToken",
+    "next",
+];
+
+/// Envelope fields holding the total number of items.
+const TOTAL_FIELDS: &[&str] = &["total", "total_count", "totalCount"];
+
+/// Envelope fields holding the page size.
+const PER_PAGE_FIELDS: &[&str] = &["per_page", "perPage", "page_size", "pageSize", "limit"];
+
+/// Nested envelope objects searched for pagination fields.
+const META_FIELDS: &[&str] = &["meta", "pagination", "paging"];
+
+/// One page of a paginated collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    items: Vec<T>,
+    next_cursor: Option<String>,
+    total: Option<u64>,
+    #[allow(clippy::struct_field_names)]
+    per_page: Option<u64>,
+    links: HashMap<String, String>,
+}
+
+impl<T> Page<T> {
+    /// Create a page with the given items and no metadata.
+    #[must_use]
+    pub fn new(items: Vec<T>) -> Self {
+        Self {
+            items,
+            next_cursor: None,
+            total: None,
+            per_page: None,
+            links: HashMap::new(),
+        }
+    }
+
+    /// Set the cursor of the next page.
+    #[must_use]
+    pub fn with_next_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.next_cursor = Some(cursor.into());
+        self
+    }
+
+    /// Set the total number of items.
+    #[must_use]
+    pub const fn with_total(mut self, total: u64) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// Set the page size.
+    #[must_use]
+    pub const fn with_per_page(mut self, per_page: u64) -> Self {
+        self.per_page = Some(per_page);
+        self
+    }
+
+    /// Items of this page.
+    #[must_use]
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Consume the page into its items.
+    #[must_use]
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+
+    /// Cursor of the next page, if any.
+    ///
+    /// For `Link` header pagination, this is the URL of the `next` relation.
+    #[must_use]
+    pub fn next_cursor(&self) -> Option<&str> {
+        self.next_cursor.as_deref()
+    }
+
+    /// Returns `true` if there is a next page.
+    #[must_use]
+    pub const fn has_next(&self) -> bool {
+        self.next_cursor.is_some()
+    }
+
+    /// Total number of items, if reported by the server.
+    #[must_use]
+    pub const fn total(&self) -> Option<u64> {
+        self.total
+    }
+
+    /// Page size, if reported by the server.
+    #[must_use]
+    pub const fn per_page(&self) -> Option<u64> {
+        self.per_page
+    }
+
+    /// Raw `Link` relations (e.g., `next`, `prev`, `last`) and their URLs.
+    #[must_use]
+    pub const fn links(&self) -> &HashMap<String, String> {
+        &self.links
+    }
+}
+
+impl Response<Bytes> {
+    /// Parse a page from a JSON array body and a `Link` header.
+    ///
+    /// The next cursor is the URL of the `next` relation. The total and page
+    /// size come from the `X-Total-Count` and `X-Per-Page` headers, if present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body is not a JSON array of `T`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let page = response.paginate_link_header::<Repo>()?;
+    /// if let Some(next) = page.next_cursor() {
+    ///     // fetch `next`
+    /// }
+    /// ```
+    pub fn paginate_link_header<T: DeserializeOwned>(&self) -> Result<Page<T>> {
+        let items: Vec<T> = crate::from_json(self.body())?;
+        let links = self
+            .header("link")
+            .map(parse_link_header)
+            .unwrap_or_default();
+
+        Ok(Page {
+            items,
+            next_cursor: links.get("next").cloned(),
+            total: self.header("x-total-count").and_then(parse_u64),
+            per_page: self.header("x-per-page").and_then(parse_u64),
+            links,
+        })
+    }
+
+    /// Parse a page from a JSON envelope whose items are at `path`.
+    ///
+    /// `path` is a dot-separated field path (e.g., `data` or `result.items`).
+    /// The next cursor, total and page size are looked up under their common
+    /// names (`next_cursor`, `nextPageToken`, `total_count`, `per_page`, ...)
+    /// at the top level or in a `meta`, `pagination` or `paging` object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body is not JSON, or if `path` is missing or
+    /// does not hold an array of `T`.
+    pub fn paginate_envelope<T: DeserializeOwned>(&self, path: &str) -> Result<Page<T>> {
+        let mut envelope: Value = crate::from_json(self.body())?;
+
+        let items = path
+            .split('.')
+            .try_fold(&mut envelope, |value, field| value.get_mut(field))
+            .map(Value::take)
+            .ok_or_else(|| Error::json_deserialization(path, "missing items field"))?;
+        let items: Vec<T> = serde_path_to_error::deserialize(items).map_err(|e| {
+            Error::json_deserialization(format!("{path}{}", e.path()), e.inner().to_string())
+        })?;
+
+        let next_cursor = find_field(&envelope, NEXT_CURSOR_FIELDS).and_then(|value| match value {
+            Value::String(cursor) if !cursor.is_empty() => Some(cursor.clone()),
+            Value::Number(cursor) => Some(cursor.to_string()),
+            _ => None,
+        });
+
+        Ok(Page {
+            items,
+            next_cursor,
+            total: find_field(&envelope, TOTAL_FIELDS).and_then(Value::as_u64),
+            per_page: find_field(&envelope, PER_PAGE_FIELDS).and_then(Value::as_u64),
+            links: HashMap::new(),
+        })
+    }
+}
+
+/// Parse an RFC 8288 `Link` header into relation → URL.
+///
+/// URLs may contain commas and semicolons, so each link is delimited by its
+/// `<...>` target, and its parameters by separators outside quoted strings.
+fn parse_link_header(header: &str) -> HashMap<String, String> {
+    let mut links = HashMap::new();
+
+    let mut rest = header;
+    while let Some((_, target)) = rest.split_once('<') {
+        let Some((url, after)) = target.split_once('>') else {
+            break;
+        };
+        let (mut params, next) = split_unquoted(after, ',');
+        rest = next;
+
+        while !params.is_empty() {
+            let (param, tail) = split_unquoted(params, ';');
+            params = tail;
+            let Some((key, value)) = param.split_once('=') else {
+                continue;
+            };
+            if key.trim().eq_ignore_ascii_case("rel") {
+                for rel in value.trim().trim_matches('"').split_whitespace() {
+                    links.insert(rel.to_ascii_lowercase(), url.to_string());
+                }
+            }
+        }
+    }
+
+    links
+}
+
+/// Split `value` at the first `delimiter` outside a quoted string.
+fn split_unquoted(value: &str, delimiter: char) -> (&str, &str) {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (index, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => {
+                let next = index + c.len_utf8();
+                return (
+                    value.get(..index).unwrap_or_default(),
+                    value.get(next..).unwrap_or_default(),
+                );
+            }
+            _ => {}
+        }
+    }
+    (value, "")
+}
+
+fn parse_u64(value: &str) -> Option<u64> {
+    value.trim().parse().ok()
+}
+
+/// Find the first present field among `names`, at the top level or in a meta object.
+fn find_field<'a>(envelope: &'a Value, names: &[&str]) -> Option<&'a Value> {
+    let scopes =
+        std::iter::once(envelope).chain(META_FIELDS.iter().filter_map(|meta| envelope.get(meta)));
+
+    scopes
+        .flat_map(|scope| names.iter().filter_map(move |name| scope.get(name)))
+        .find(|value| !value.is_null())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        id: u64,
+    }
+
+    fn response(headers: &[(&'static str, &'static str)], body: &'static str) -> Response<Bytes> {
+        let headers = headers
+            .iter()
+            .map(|(name, value)| {
+                let name: http::HeaderName = name.parse().expect("header name");
+                (name, http::HeaderValue::from_static(value))
+            })
+            .collect();
+        Response::new(200, headers, Bytes::from_static(body.as_bytes()))
+    }
+
+    #[test]
+    fn link_header_pagination() {
+        let response = response(
+            &[
+                (
+                    "link",
+                    r#"<https://api.example.com/items?page=2>; rel="next", <https://api.example.com/items?page=5>; rel="last""#,
+                ),
+                ("X-Total-Count", "42"),
+            ],
+            r#"[{"id": 1}, {"id": 2}]"#,
+        );
+
+        let page = response.paginate_link_header::<Item>().expect("page");
+
+        assert_eq!(page.items(), [Item { id: 1 }, Item { id: 2 }]);
+        assert_eq!(
+            page.next_cursor(),
+            Some("https://api.example.com/items?page=2")
+        );
+        assert_eq!(
+            page.links().get("last").map(String::as_str),
+            Some("https://api.example.com/items?page=5")
+        );
+        assert_eq!(page.total(), Some(42));
+        assert_eq!(page.per_page(), None);
+    }
+
+    #[test]
+    fn link_header_urls_with_separators() {
+        let links = parse_link_header(
+            r#"<https://api.example.com/items?ids=1,2;v=3&page=2>; title="a, b; c"; rel="next", <https://api.example.com/items?ids=1,2>; rel="first prev""#,
+        );
+
+        assert_eq!(
+            links.get("next").map(String::as_str),
+            Some("https://api.example.com/items?ids=1,2;v=3&page=2")
+        );
+        assert_eq!(
+            links.get("first").map(String::as_str),
+            Some("https://api.example.com/items?ids=1,2")
+        );
+        assert_eq!(links.get("prev"), links.get("first"));
+        assert_eq!(links.len(), 3);
+    }
+
+    #[test]
+    fn link_header_last_page() {
+        let response = response(&[], r#"[{"id": 3}]"#);
+        let page = response.paginate_link_header::<Item>().expect("page");
+        assert!(!page.has_next());
+        assert!(page.links().is_empty());
+    }
+
+    #[test]
+    fn envelope_pagination() {
+        let response = response(
+            &[],
+            r#"{"data": [{"id": 1}], "meta": {"next_cursor": "abc", "total_count": 10, "per_page": 1}}"#,
+        );
+
+        let page = response.paginate_envelope::<Item>("data").expect("page");
+
+        assert_eq!(page.next_cursor(), Some("abc"));
+        assert_eq!(page.total(), Some(10));
+        assert_eq!(page.per_page(), Some(1));
+        assert_eq!(page.into_items(), [Item { id: 1 }]);
+    }
+
+    #[test]
+    fn envelope_metadata() {
+        let response = response(
+            &[],
+            r#"{"result": {"items": []}, "nextPageToken": "", "pagination": {"total": 0, "limit": 20}}"#,
+        );
+
+        let page = response
+            .paginate_envelope::<Item>("result.items")
+            .expect("page");
+
+        assert!(page.items().is_empty());
+        assert_eq!(page.next_cursor(), None);
+        assert_eq!(page.total(), Some(0));
+        assert_eq!(page.per_page(), Some(20));
+    }
+
+    #[test]
+    fn envelope_cursor_lookup() {
+        let response = response(
+            &[],
+            r#"{"data": [], "next": 2, "meta": {"next_cursor": "x"}}"#,
+        );
+        let page = response.paginate_envelope::<Item>("data").expect("page");
+        assert_eq!(page.next_cursor(), Some("2"));
+    }
+
+    #[test]
+    fn envelope_missing_items() {
+        let response = response(&[], r#"{"items": []}"#);
+        let err = response
+            .paginate_envelope::<Item>("data")
+            .expect_err("missing");
+        assert!(err.to_string().contains("data"));
+    }
+
+    #[test]
+    fn envelope_invalid_item() {
+        let response = response(&[], r#"{"data": [{"id": "x"}]}"#);
+        let err = response
+            .paginate_envelope::<Item>("data")
+            .expect_err("invalid");
+        assert!(err.to_string().contains("data[0].id"));
+    }
+}
@@ -15,6 +15,8 @@ pub enum ParamLocation {
     Query,
     /// Header parameter
     Header,
+    /// Cookie parameter, sent in the `Cookie` header
+    Cookie,
     /// Request body (JSON)
     Body,
     /// Form data (URL-encoded or multipart)
@@ -27,6 +29,7 @@ impl fmt::Display for ParamLocation {
             Self::Path => write!(f, "path"),
             Self::Query => write!(f, "query"),
             Self::Header => write!(f, "header"),
+            Self::Cookie => write!(f, "cookie"),
             Self::Body => write!(f, "body"),
             Self::Form => write!(f, "form"),
         }
@@ -49,6 +52,15 @@ pub struct ParamMeta {
     pub required: bool,
 }
 
+/// Deprecation of a method, from its `#[deprecated]` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Deprecation {
+    /// The version the method was deprecated in (`since = "..."`).
+    pub since: Option<&'static str>,
+    /// The deprecation message (`note = "..."`).
+    pub note: Option<&'static str>,
+}
+
 /// All parameter metadata for a method call.
 ///
 /// This is stored in request extensions to allow middleware to access
@@ -71,6 +83,9 @@ pub struct ParameterMetadata {
     pub method_name: &'static str,
     /// Metadata for each parameter.
     pub parameters: &'static [ParamMeta],
+    /// Set if the method is `#[deprecated]`, e.g. to flag it in `OpenAPI`
+    /// output.
+    pub deprecated: Option<Deprecation>,
 }
 
 #[cfg(test)]
@@ -82,6 +97,7 @@ mod tests {
         assert_eq!(ParamLocation::Path.to_string(), "path");
         assert_eq!(ParamLocation::Query.to_string(), "query");
         assert_eq!(ParamLocation::Header.to_string(), "header");
+        assert_eq!(ParamLocation::Cookie.to_string(), "cookie");
         assert_eq!(ParamLocation::Body.to_string(), "body");
         assert_eq!(ParamLocation::Form.to_string(), "form");
     }
@@ -120,12 +136,17 @@ mod tests {
         let meta = ParameterMetadata {
             method_name: "get_user",
             parameters: PARAMS,
+            deprecated: Some(Deprecation {
+                since: Some("2.0"),
+                note: None,
+            }),
         };
 
         assert_eq!(meta.method_name, "get_user");
         assert_eq!(meta.parameters.len(), 2);
         assert_eq!(meta.parameters.first().expect("first param").name, "id");
         assert_eq!(meta.parameters.get(1).expect("second param").name, "limit");
+        assert_eq!(meta.deprecated.and_then(|d| d.since), Some("2.0"));
     }
 
     #[test]
@@ -133,5 +154,6 @@ mod tests {
         let meta = ParameterMetadata::default();
         assert_eq!(meta.method_name, "");
         assert!(meta.parameters.is_empty());
+        assert!(meta.deprecated.is_none());
     }
 }
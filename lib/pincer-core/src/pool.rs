@@ -0,0 +1,218 @@
+//! Reusable buffers for request bodies.
+//!
+//! A [`BufferPool`] hands out [`BytesMut`] buffers to body encoders (JSON,
+//! multipart) and takes them back once the encoded [`Bytes`] have been split
+//! off. When every `Bytes` produced from a buffer has been dropped (the
+//! request was sent), the next encode reuses the same allocation instead of
+//! asking the allocator for a new one.
+//!
+//! # Example
+//!
+//! ```
+//! use pincer_core::BufferPool;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize)]
+//! struct User { name: String }
+//!
+//! let pool = BufferPool::new();
+//! let user = User { name: "Alice".to_string() };
+//! let body = pool.to_json(&user).expect("serialize");
+//! assert_eq!(body.as_ref(), br#"{"name":"Alice"}"#);
+//! ```
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::Result;
+
+/// Default number of idle buffers kept by a pool.
+const DEFAULT_MAX_BUFFERS: usize = 32;
+
+/// Default capacity of a new buffer (8 KiB).
+const DEFAULT_BUFFER_CAPACITY: usize = 8 * 1024;
+
+/// Default capacity above which a buffer is dropped instead of kept (1 MiB).
+const DEFAULT_MAX_RETAINED_CAPACITY: usize = 1024 * 1024;
+
+/// Pool of reusable body buffers.
+///
+/// Cloning a pool is cheap and clones share the same buffers, so one pool can
+/// be shared by every task using a client.
+#[derive(Debug, Clone)]
+pub struct BufferPool {
+    buffers: Arc<Mutex<Vec<BytesMut>>>,
+    max_buffers: usize,
+    buffer_capacity: usize,
+    max_retained_capacity: usize,
+}
+
+impl Default for BufferPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BufferPool {
+    /// Create a pool keeping up to 32 idle buffers of 8 KiB.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_limits(DEFAULT_MAX_BUFFERS, DEFAULT_BUFFER_CAPACITY)
+    }
+
+    /// Create a pool keeping up to `max_buffers` idle buffers, each allocated
+    /// with `buffer_capacity` bytes.
+    #[must_use]
+    pub fn with_limits(max_buffers: usize, buffer_capacity: usize) -> Self {
+        Self {
+            buffers: Arc::new(Mutex::new(Vec::with_capacity(max_buffers))),
+            max_buffers,
+            buffer_capacity,
+            max_retained_capacity: DEFAULT_MAX_RETAINED_CAPACITY.max(buffer_capacity),
+        }
+    }
+
+    /// Set the capacity above which a returned buffer is dropped.
+    ///
+    /// Keeps a single oversized body from pinning a large allocation in the
+    /// pool. Defaults to 1 MiB.
+    #[must_use]
+    pub const fn with_max_retained_capacity(mut self, capacity: usize) -> Self {
+        self.max_retained_capacity = capacity;
+        self
+    }
+
+    /// Number of idle buffers currently in the pool.
+    #[must_use]
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Run an encoder on a pooled buffer and return what it wrote.
+    ///
+    /// The buffer goes back to the pool whether the encoder succeeds or not.
+    ///
+    /// # Errors
+    ///
+    /// Returns the encoder's error.
+    pub fn encode<F>(&self, encoder: F) -> Result<Bytes>
+    where
+        F: FnOnce(&mut BytesMut) -> Result<()>,
+    {
+        let mut buf = self.lock().pop().unwrap_or_default();
+        buf.clear();
+        // Reclaims the whole allocation once earlier bodies have been dropped
+        buf.reserve(self.buffer_capacity);
+
+        let result = encoder(&mut buf);
+        let capacity = buf.capacity();
+        let bytes = buf.split().freeze();
+        if capacity <= self.max_retained_capacity {
+            self.release(buf);
+        }
+
+        result.map(|()| bytes)
+    }
+
+    /// Serialize a value to JSON bytes using a pooled buffer.
+    ///
+    /// Produces the same bytes as [`to_json`](crate::to_json).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if JSON serialization fails.
+    pub fn to_json<T: serde::Serialize>(&self, value: &T) -> Result<Bytes> {
+        self.encode(|buf| serde_json::to_writer(buf.writer(), value).map_err(Into::into))
+    }
+
+    fn release(&self, buf: BytesMut) {
+        let mut buffers = self.lock();
+        if buffers.len() < self.max_buffers {
+            buffers.push(buf);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<BytesMut>> {
+        self.buffers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    struct User {
+        id: u64,
+        name: &'static str,
+    }
+
+    #[test]
+    fn to_json_matches_unpooled() {
+        let pool = BufferPool::new();
+        let user = User {
+            id: 1,
+            name: "Alice",
+        };
+        assert_eq!(
+            pool.to_json(&user).expect("json"),
+            crate::to_json(&user).expect("json")
+        );
+        assert_eq!(pool.idle(), 1);
+    }
+
+    #[test]
+    fn buffer_is_reused_after_body_dropped() {
+        let pool = BufferPool::with_limits(1, 64);
+        let first = pool
+            .to_json(&User {
+                id: 1,
+                name: "Alice",
+            })
+            .expect("json");
+        let first_ptr = first.as_ptr();
+        drop(first);
+
+        let second = pool.to_json(&User { id: 2, name: "Bob" }).expect("json");
+        assert_eq!(second.as_ptr(), first_ptr);
+        assert_eq!(second.as_ref(), br#"{"id":2,"name":"Bob"}"#);
+    }
+
+    #[test]
+    fn live_bodies_are_not_overwritten() {
+        let pool = BufferPool::with_limits(1, 64);
+        let first = pool
+            .to_json(&User {
+                id: 1,
+                name: "Alice",
+            })
+            .expect("json");
+        let second = pool.to_json(&User { id: 2, name: "Bob" }).expect("json");
+        assert_eq!(first.as_ref(), br#"{"id":1,"name":"Alice"}"#);
+        assert_eq!(second.as_ref(), br#"{"id":2,"name":"Bob"}"#);
+    }
+
+    #[test]
+    fn limits_are_enforced() {
+        let pool = BufferPool::with_limits(0, 64);
+        pool.to_json(&1).expect("json");
+        assert_eq!(pool.idle(), 0);
+
+        let pool = BufferPool::with_limits(1, 16).with_max_retained_capacity(16);
+        pool.encode(|buf| {
+            buf.put_bytes(b'x', 1024);
+            Ok(())
+        })
+        .expect("encode");
+        assert_eq!(pool.idle(), 0);
+    }
+
+    #[test]
+    fn encode_error_returns_buffer() {
+        let pool = BufferPool::new();
+        let result = pool.encode(|_| Err(crate::Error::invalid_request("boom")));
+        assert!(result.is_err());
+        assert_eq!(pool.idle(), 1);
+    }
+}
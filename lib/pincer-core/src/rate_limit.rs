@@ -0,0 +1,172 @@
+//! Rate limit response headers.
+//!
+//! Supported headers:
+//! - `Retry-After` (RFC 9110), as delay-seconds or an HTTP date
+//! - `RateLimit-Limit`, `RateLimit-Remaining`, `RateLimit-Reset` (IETF draft)
+//! - `X-RateLimit-Limit`, `X-RateLimit-Remaining`, `X-RateLimit-Reset`
+//!
+//! Malformed values are ignored rather than reported.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use http::HeaderMap;
+
+/// Reset values above this are Unix timestamps rather than delays (2001-09-09).
+const RESET_TIMESTAMP_THRESHOLD: u64 = 1_000_000_000;
+
+/// Rate limit quota advertised by the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// Maximum number of requests in the current window.
+    pub limit: Option<u32>,
+    /// Number of requests left in the current window.
+    pub remaining: Option<u32>,
+    /// Time until the window resets.
+    pub reset: Option<Duration>,
+}
+
+impl RateLimitInfo {
+    /// Read the quota from `RateLimit-*` or `X-RateLimit-*` headers.
+    ///
+    /// A reset value that looks like a Unix timestamp (as sent by e.g. GitHub)
+    /// is turned into a delay; a timestamp out of range is ignored.
+    #[must_use]
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let number = |name: &str| {
+            find(headers, &format!("ratelimit-{name}"))
+                .or_else(|| find(headers, &format!("x-ratelimit-{name}")))
+                .and_then(parse_u64)
+        };
+        let count = |name: &str| number(name).map(|n| u32::try_from(n).unwrap_or(u32::MAX));
+
+        Self {
+            limit: count("limit"),
+            remaining: count("remaining"),
+            reset: number("reset").and_then(reset_delay),
+        }
+    }
+
+    /// Returns `true` if no rate limit header was found.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.limit.is_none() && self.remaining.is_none() && self.reset.is_none()
+    }
+}
+
+/// Read the `Retry-After` delay, if present and valid.
+///
+/// An HTTP date in the past is a zero delay.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = find(headers, "retry-after")?;
+    if let Some(seconds) = parse_u64(value) {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = httpdate::parse_http_date(value.trim()).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+fn find<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name)?.to_str().ok()
+}
+
+/// Parse a non-negative integer, ignoring any `;` parameters.
+fn parse_u64(value: &str) -> Option<u64> {
+    value.split(';').next()?.trim().parse().ok()
+}
+
+fn reset_delay(reset: u64) -> Option<Duration> {
+    if reset < RESET_TIMESTAMP_THRESHOLD {
+        return Some(Duration::from_secs(reset));
+    }
+    let reset_at = UNIX_EPOCH.checked_add(Duration::from_secs(reset))?;
+    Some(
+        reset_at
+            .duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        pairs
+            .iter()
+            .map(|(name, value)| {
+                let name: http::HeaderName = name.parse().expect("header name");
+                (name, value.parse().expect("header value"))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        let headers = headers(&[("Retry-After", " 90 ")]);
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn retry_after_http_date() {
+        let past = headers(&[("retry-after", "Wed, 21 Oct 2015 07:28:00 GMT")]);
+        assert_eq!(retry_after(&past), Some(Duration::ZERO));
+
+        let future = SystemTime::now() + Duration::from_hours(1);
+        let future = headers(&[("retry-after", &httpdate::fmt_http_date(future))]);
+        let delay = retry_after(&future).expect("delay");
+        assert!(delay > Duration::from_secs(3500));
+    }
+
+    #[test]
+    fn retry_after_invalid() {
+        assert_eq!(retry_after(&headers(&[("retry-after", "-1")])), None);
+        assert_eq!(retry_after(&headers(&[("retry-after", "soon")])), None);
+        assert_eq!(retry_after(&headers(&[])), None);
+    }
+
+    #[test]
+    fn rate_limit_info_from_headers() {
+        let info = RateLimitInfo::from_headers(&headers(&[
+            ("x-ratelimit-limit", "60"),
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "30"),
+        ]));
+        assert_eq!(
+            info,
+            RateLimitInfo {
+                limit: Some(60),
+                remaining: Some(0),
+                reset: Some(Duration::from_secs(30)),
+            }
+        );
+
+        // IETF draft headers take precedence, parameters are ignored
+        let info = RateLimitInfo::from_headers(&headers(&[
+            ("RateLimit-Limit", "100;w=60"),
+            ("X-RateLimit-Limit", "60"),
+        ]));
+        assert_eq!(info.limit, Some(100));
+        assert!(info.reset.is_none());
+    }
+
+    #[test]
+    fn rate_limit_reset_timestamp() {
+        let info = RateLimitInfo::from_headers(&headers(&[("x-ratelimit-reset", "1444000000")]));
+        assert_eq!(info.reset, Some(Duration::ZERO));
+        assert!(RateLimitInfo::from_headers(&headers(&[])).is_empty());
+    }
+
+    #[test]
+    fn rate_limit_reset_timestamp_overflow() {
+        let info = RateLimitInfo::from_headers(&headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "18446744073709551615"),
+        ]));
+        assert_eq!(info.remaining, Some(0));
+        assert!(info.reset.is_none());
+    }
+}
@@ -0,0 +1,264 @@
+//! Endpoints registered by name at runtime.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use serde::Deserialize;
+
+use crate::endpoint::placeholders;
+use crate::{Endpoint, Error, Method, PincerClient, Response, Result, from_json};
+
+/// Description of a registered endpoint.
+///
+/// Deserialized from `{"method": "GET", "path": "/users/{id}", "required": ["fields"]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct EndpointSpec {
+    /// HTTP method.
+    #[serde(deserialize_with = "deserialize_method")]
+    pub method: Method,
+    /// Path template (e.g., `/users/{id}`).
+    pub path: String,
+    /// Query parameters that must be given, besides the path parameters.
+    #[serde(default)]
+    pub required: Vec<String>,
+}
+
+impl EndpointSpec {
+    /// Create a spec from a method and a path template.
+    #[must_use]
+    pub fn new(method: Method, path: impl Into<String>) -> Self {
+        Self {
+            method,
+            path: path.into(),
+            required: Vec::new(),
+        }
+    }
+
+    /// Add a required query parameter.
+    #[must_use]
+    pub fn required(mut self, name: impl Into<String>) -> Self {
+        self.required.push(name.into());
+        self
+    }
+}
+
+/// Endpoints registered by name, for integrations where endpoints are added
+/// by configuration rather than code.
+///
+/// Parameters matching a placeholder of the path fill it, the others are
+/// query parameters.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::EndpointRegistry;
+///
+/// let registry = EndpointRegistry::from_json(br#"{
+///     "getUser": {"method": "GET", "path": "/users/{id}", "required": ["fields"]}
+/// }"#).expect("registry");
+///
+/// let base_url = "https://api.example.com".parse().expect("url");
+/// let url = registry
+///     .endpoint("getUser", [("id", "5"), ("fields", "name")])
+///     .and_then(|endpoint| endpoint.url(&base_url))
+///     .expect("url");
+/// assert_eq!(url.as_str(), "https://api.example.com/users/5?fields=name");
+/// assert!(registry.endpoint("getUser", [("id", "5")]).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct EndpointRegistry {
+    endpoints: HashMap<String, EndpointSpec>,
+}
+
+impl EndpointRegistry {
+    /// Create an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a registry from a JSON object mapping names to [`EndpointSpec`]s.
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        from_json(bytes)
+    }
+
+    /// Register an endpoint, replacing any endpoint with the same name.
+    pub fn insert(&mut self, name: impl Into<String>, spec: EndpointSpec) {
+        self.endpoints.insert(name.into(), spec);
+    }
+
+    /// Get a registered endpoint.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&EndpointSpec> {
+        self.endpoints.get(name)
+    }
+
+    /// Names of the registered endpoints, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.endpoints.keys().map(String::as_str)
+    }
+
+    /// Build the endpoint `name` with the given parameters.
+    ///
+    /// Fails if the endpoint is unknown, or a path or required parameter is
+    /// missing.
+    pub fn endpoint<K, V>(
+        &self,
+        name: &str,
+        params: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Endpoint>
+    where
+        K: Into<String>,
+        V: ToString,
+    {
+        let spec = self
+            .get(name)
+            .ok_or_else(|| Error::invalid_request(format!("unknown endpoint `{name}`")))?;
+        let path_params = placeholders(&spec.path);
+
+        let mut endpoint = Endpoint::new(spec.method, spec.path.as_str());
+        let mut given = Vec::new();
+        for (key, value) in params {
+            let key = key.into();
+            endpoint = if path_params.contains(&key.as_str()) {
+                endpoint.path(key.as_str(), value.to_string())
+            } else {
+                endpoint.query(key.as_str(), value.to_string())
+            };
+            given.push(key);
+        }
+
+        if let Some(missing) = spec.required.iter().find(|name| !given.contains(name)) {
+            return Err(Error::invalid_request(format!(
+                "missing required parameter `{missing}` for endpoint `{name}`"
+            )));
+        }
+        Ok(endpoint)
+    }
+}
+
+/// A client calling the endpoints of an [`EndpointRegistry`] by name.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::{ApiClient, DynamicClient, EndpointRegistry, HyperClient};
+///
+/// let registry = EndpointRegistry::from_json(&std::fs::read("endpoints.json")?)?;
+/// let api = ApiClient::new(HyperClient::new(), "https://api.example.com")?;
+/// let client = DynamicClient::new(api, registry);
+///
+/// let user: User = client.call("getUser", [("id", "5")]).await?.json()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct DynamicClient<C> {
+    client: C,
+    registry: Arc<EndpointRegistry>,
+}
+
+impl<C: PincerClient> DynamicClient<C> {
+    /// Create a client calling the endpoints of `registry` with `client`.
+    #[must_use]
+    pub fn new(client: C, registry: EndpointRegistry) -> Self {
+        Self {
+            client,
+            registry: Arc::new(registry),
+        }
+    }
+
+    /// Get the registry.
+    #[must_use]
+    pub fn registry(&self) -> &EndpointRegistry {
+        &self.registry
+    }
+
+    /// Get the inner client.
+    #[must_use]
+    pub fn inner(&self) -> &C {
+        &self.client
+    }
+
+    /// Call the endpoint `name` with the given parameters.
+    ///
+    /// Non-2xx responses are errors, as for `#[pincer]` methods.
+    pub async fn call<K, V>(
+        &self,
+        name: &str,
+        params: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Response<Bytes>>
+    where
+        K: Into<String>,
+        V: ToString,
+    {
+        let request = self
+            .registry
+            .endpoint(name, params)?
+            .request(self.client.base_url())?
+            .try_build()?;
+        let response = self.client.execute(request).await?;
+        if !response.is_success() {
+            return Err(Error::from_response(response));
+        }
+        Ok(response)
+    }
+}
+
+fn deserialize_method<'de, D>(deserializer: D) -> std::result::Result<Method, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let method = String::deserialize(deserializer)?;
+    method.parse().map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> EndpointRegistry {
+        let mut registry = EndpointRegistry::new();
+        registry.insert(
+            "searchRepos",
+            EndpointSpec::new(Method::Get, "/orgs/{org}/repos").required("q"),
+        );
+        registry
+    }
+
+    #[test]
+    fn loads_specs_from_json() {
+        let registry = EndpointRegistry::from_json(
+            br#"{"deleteUser": {"method": "delete", "path": "/users/{id}"}}"#,
+        )
+        .expect("registry");
+        assert_eq!(
+            registry.get("deleteUser"),
+            Some(&EndpointSpec::new(Method::Delete, "/users/{id}"))
+        );
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["deleteUser"]);
+
+        let invalid = EndpointRegistry::from_json(br#"{"x": {"method": "TRACE", "path": "/"}}"#);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn splits_path_and_query_parameters() {
+        let base_url = "https://api.example.com".parse().expect("url");
+        let url = registry()
+            .endpoint("searchRepos", [("org", "rust lang"), ("q", "http")])
+            .and_then(|endpoint| endpoint.url(&base_url))
+            .expect("url");
+        assert_eq!(
+            url.as_str(),
+            "https://api.example.com/orgs/rust%20lang/repos?q=http"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_endpoints_and_missing_parameters() {
+        let registry = registry();
+        assert!(registry.endpoint("getUser", [("id", "1")]).is_err());
+        assert!(registry.endpoint("searchRepos", [("org", "rust")]).is_err());
+    }
+}
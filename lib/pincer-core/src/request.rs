@@ -14,19 +14,19 @@
 //!     .build();
 //! ```
 
-use std::collections::HashMap;
-
 use bytes::Bytes;
 use http::Extensions;
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
 
-use crate::Method;
+use crate::{Error, Method};
 
 /// An HTTP request with method, URL, headers, optional body, and extensions.
 #[derive(Debug, Clone)]
 pub struct Request<B = Bytes> {
     method: Method,
     url: url::Url,
-    headers: HashMap<String, String>,
+    headers: HeaderMap,
     body: Option<B>,
     extensions: Extensions,
 }
@@ -52,20 +52,22 @@ impl<B> Request<B> {
 
     /// Request headers.
     #[must_use]
-    pub fn headers(&self) -> &HashMap<String, String> {
+    pub const fn headers(&self) -> &HeaderMap {
         &self.headers
     }
 
     /// Mutable access to headers.
     #[must_use]
-    pub fn headers_mut(&mut self) -> &mut HashMap<String, String> {
+    pub const fn headers_mut(&mut self) -> &mut HeaderMap {
         &mut self.headers
     }
 
-    /// Single header value by name.
+    /// Single header value by name, compared case-insensitively.
+    ///
+    /// Returns the first value, or `None` if it is not visible ASCII.
     #[must_use]
     pub fn header(&self, name: &str) -> Option<&str> {
-        self.headers.get(name).map(String::as_str)
+        self.headers.get(name)?.to_str().ok()
     }
 
     /// Request body.
@@ -74,6 +76,12 @@ impl<B> Request<B> {
         self.body.as_ref()
     }
 
+    /// Mutable access to the body.
+    #[must_use]
+    pub fn body_mut(&mut self) -> &mut Option<B> {
+        &mut self.body
+    }
+
     /// Request extensions.
     ///
     /// Extensions allow middleware to attach arbitrary typed data to requests.
@@ -90,15 +98,7 @@ impl<B> Request<B> {
 
     /// Consume into (method, url, headers, body, extensions).
     #[must_use]
-    pub fn into_parts(
-        self,
-    ) -> (
-        Method,
-        url::Url,
-        HashMap<String, String>,
-        Option<B>,
-        Extensions,
-    ) {
+    pub fn into_parts(self) -> (Method, url::Url, HeaderMap, Option<B>, Extensions) {
         (
             self.method,
             self.url,
@@ -116,7 +116,7 @@ impl<B> Request<B> {
     pub fn from_parts(
         method: Method,
         url: url::Url,
-        headers: HashMap<String, String>,
+        headers: HeaderMap,
         body: Option<B>,
         extensions: Extensions,
     ) -> Self {
@@ -131,13 +131,19 @@ impl<B> Request<B> {
 }
 
 /// Builder for constructing [`Request`] instances.
+///
+/// Invalid header names or values are reported by [`try_build`](Self::try_build),
+/// while [`build`](Self::build) leaves them out of the request. Use
+/// `try_build` whenever a header comes from runtime data: generated clients
+/// and [`DynamicClient`](crate::DynamicClient) do.
 #[derive(Debug, Clone)]
 pub struct RequestBuilder<B = Bytes> {
     method: Method,
     url: url::Url,
-    headers: HashMap<String, String>,
+    headers: HeaderMap,
     body: Option<B>,
     extensions: Extensions,
+    invalid_header: Option<String>,
 }
 
 impl<B> RequestBuilder<B> {
@@ -147,24 +153,92 @@ impl<B> RequestBuilder<B> {
         Self {
             method,
             url,
-            headers: HashMap::new(),
+            headers: HeaderMap::new(),
             body: None,
             extensions: Extensions::new(),
+            invalid_header: None,
         }
     }
 
-    /// Sets a header.
+    /// Sets a header, replacing any previous value.
+    ///
+    /// Accepts strings as well as [`HeaderName`] and [`HeaderValue`], e.g.
+    /// the constants of [`http::header`]. An invalid name or value (e.g. with
+    /// a line break) is not set, and makes [`try_build`](Self::try_build) fail.
     #[must_use]
-    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
-        self.headers.insert(name.into(), value.into());
+    pub fn header<K, V>(mut self, name: K, value: V) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        let name = HeaderName::try_from(name).map_err(Into::into);
+        let value = HeaderValue::try_from(value).map_err(Into::into);
+        match (name, value) {
+            (Ok(name), Ok(value)) => {
+                self.headers.insert(name, value);
+            }
+            (Err(err), _) | (_, Err(err)) => {
+                self.invalid_header.get_or_insert_with(|| err.to_string());
+            }
+        }
         self
     }
 
     /// Sets multiple headers.
     #[must_use]
-    pub fn headers(mut self, headers: impl IntoIterator<Item = (String, String)>) -> Self {
-        self.headers.extend(headers);
-        self
+    pub fn headers<K, V>(self, headers: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        HeaderName: TryFrom<K>,
+        <HeaderName as TryFrom<K>>::Error: Into<http::Error>,
+        HeaderValue: TryFrom<V>,
+        <HeaderValue as TryFrom<V>>::Error: Into<http::Error>,
+    {
+        headers
+            .into_iter()
+            .fold(self, |builder, (name, value)| builder.header(name, value))
+    }
+
+    /// Sets the `Accept-Language` header from a language fallback chain.
+    ///
+    /// Leaves the request unchanged if there is no language.
+    #[must_use]
+    pub fn accept_language(self, languages: impl Into<crate::AcceptLanguage>) -> Self {
+        let languages = languages.into();
+        if languages.is_empty() {
+            return self;
+        }
+        self.header(http::header::ACCEPT_LANGUAGE, languages.to_string())
+    }
+
+    /// Adds a cookie to the `Cookie` header, after the cookies already set.
+    ///
+    /// The value is percent-encoded where RFC 6265 does not allow it
+    /// (controls, whitespace, `"`, `,`, `;`, `\` and `%`). An invalid name
+    /// is reported by [`try_build`](Self::try_build).
+    #[must_use]
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn cookie(mut self, name: &str, value: impl ToString) -> Self {
+        if !is_cookie_name(name) {
+            self.invalid_header
+                .get_or_insert_with(|| format!("invalid cookie name `{name}`"));
+            return self;
+        }
+        let value = value.to_string();
+        let pair = format!(
+            "{name}={}",
+            utf8_percent_encode(&value, COOKIE_VALUE_ENCODE_SET)
+        );
+        let cookie = match self
+            .headers
+            .get(http::header::COOKIE)
+            .and_then(|cookie| cookie.to_str().ok())
+        {
+            Some(cookies) if !cookies.is_empty() => format!("{cookies}; {pair}"),
+            _ => pair,
+        };
+        self.header(http::header::COOKIE, cookie)
     }
 
     /// Appends a query parameter to the URL.
@@ -211,7 +285,12 @@ impl<B> RequestBuilder<B> {
         self
     }
 
-    /// Builds the [`Request`].
+    /// Builds the [`Request`], without checking the headers.
+    ///
+    /// Headers with an invalid name or value are silently left out, so the
+    /// request is sent without them: only use `build` with headers known to
+    /// be valid (e.g. constants), and [`try_build`](Self::try_build) to
+    /// report invalid ones otherwise.
     #[must_use]
     pub fn build(self) -> Request<B> {
         Request {
@@ -222,6 +301,35 @@ impl<B> RequestBuilder<B> {
             extensions: self.extensions,
         }
     }
+
+    /// Builds the [`Request`], checking every header.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a header name or value was invalid.
+    pub fn try_build(mut self) -> crate::Result<Request<B>> {
+        if let Some(err) = self.invalid_header.take() {
+            return Err(Error::invalid_request(format!("invalid header: {err}")));
+        }
+        Ok(self.build())
+    }
+}
+
+/// Characters percent-encoded in cookie values.
+const COOKIE_VALUE_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b',')
+    .add(b';')
+    .add(b'\\')
+    .add(b'%');
+
+/// Returns `true` if `name` is a valid cookie name (an RFC 9110 token).
+pub(crate) fn is_cookie_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|byte| byte.is_ascii_graphic() && !b"()<>@,;:\\\"/[]?={}".contains(&byte))
 }
 
 impl RequestBuilder<Bytes> {
@@ -235,6 +343,75 @@ impl RequestBuilder<Bytes> {
         Ok(self.header("Content-Type", "application/json").body(body))
     }
 
+    /// Set a JSON body serialized with `codec`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn json_with<C: crate::BodyCodec, T: serde::Serialize>(
+        self,
+        codec: &C,
+        value: &T,
+    ) -> crate::Result<Self> {
+        let body = codec.encode(value)?;
+        Ok(self.header("Content-Type", "application/json").body(body))
+    }
+
+    /// Set an XML body serialized with `codec`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn xml_with<C: crate::BodyCodec, T: serde::Serialize>(
+        self,
+        codec: &C,
+        value: &T,
+    ) -> crate::Result<Self> {
+        let body = codec.encode(value)?;
+        Ok(self.header("Content-Type", "application/xml").body(body))
+    }
+
+    /// Set a CBOR body serialized with `codec`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn cbor_with<C: crate::BodyCodec, T: serde::Serialize>(
+        self,
+        codec: &C,
+        value: &T,
+    ) -> crate::Result<Self> {
+        let body = codec.encode(value)?;
+        Ok(self.header("Content-Type", "application/cbor").body(body))
+    }
+
+    /// Set a JSON body serialized into a buffer from `pool`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn json_pooled<T: serde::Serialize>(
+        self,
+        value: &T,
+        pool: &crate::BufferPool,
+    ) -> crate::Result<Self> {
+        let body = pool.to_json(value)?;
+        Ok(self.header("Content-Type", "application/json").body(body))
+    }
+
+    /// Set a canonical JSON body (sorted keys, compact).
+    ///
+    /// Use this when the body is signed and the signed bytes must be
+    /// reproducible. See [`crate::to_json_canonical`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn json_canonical<T: serde::Serialize>(self, value: &T) -> crate::Result<Self> {
+        let body = crate::to_json_canonical(value)?;
+        Ok(self.header("Content-Type", "application/json").body(body))
+    }
+
     /// Set a form-urlencoded body.
     ///
     /// # Errors
@@ -265,6 +442,55 @@ mod tests {
         assert!(request.body().is_none());
     }
 
+    #[test]
+    fn request_headers_ignore_case() {
+        let url = url::Url::parse("https://api.example.com/users").expect("valid URL");
+        let request = Request::<Bytes>::builder(Method::Get, url)
+            .header("X-Trace", "first")
+            .header("x-trace", "second")
+            .header(http::header::ACCEPT, "application/json")
+            .build();
+
+        assert_eq!(request.header("X-TRACE"), Some("second"));
+        assert_eq!(request.headers().len(), 2);
+        assert_eq!(request.header("accept"), Some("application/json"));
+    }
+
+    #[test]
+    fn request_builder_invalid_header() {
+        let url = url::Url::parse("https://api.example.com/users").expect("valid URL");
+        let builder = Request::<Bytes>::builder(Method::Get, url)
+            .header("X-Ok", "yes")
+            .header("X-Bad", "line\nbreak");
+
+        let request = builder.clone().build();
+        assert_eq!(request.header("x-ok"), Some("yes"));
+        assert!(request.header("x-bad").is_none());
+
+        let err = builder.try_build().expect_err("invalid header");
+        assert!(err.to_string().contains("invalid header"));
+    }
+
+    #[test]
+    fn request_builder_cookies() {
+        let url = url::Url::parse("https://api.example.com/users").expect("valid URL");
+        let request = Request::<Bytes>::builder(Method::Get, url.clone())
+            .cookie("session_id", "abc123")
+            .cookie("theme", "dark; light")
+            .cookie("count", 3)
+            .build();
+        assert_eq!(
+            request.header("cookie"),
+            Some("session_id=abc123; theme=dark%3B%20light; count=3")
+        );
+
+        let err = Request::<Bytes>::builder(Method::Get, url)
+            .cookie("bad name", "x")
+            .try_build()
+            .expect_err("invalid cookie name");
+        assert!(err.to_string().contains("bad name"));
+    }
+
     #[test]
     fn request_builder_with_query() {
         let url = url::Url::parse("https://api.example.com/users").expect("valid URL");
@@ -311,6 +537,30 @@ mod tests {
         assert!(request.body().is_some());
     }
 
+    #[test]
+    fn request_builder_json_canonical() {
+        #[derive(serde::Serialize)]
+        struct Payment {
+            to: String,
+            amount: u64,
+        }
+
+        let url = url::Url::parse("https://api.example.com/payments").expect("valid URL");
+        let request = Request::builder(Method::Post, url)
+            .json_canonical(&Payment {
+                to: "bob".to_string(),
+                amount: 5,
+            })
+            .expect("json")
+            .build();
+
+        assert_eq!(request.header("Content-Type"), Some("application/json"));
+        assert_eq!(
+            request.body().map(AsRef::as_ref),
+            Some(br#"{"amount":5,"to":"bob"}"#.as_slice())
+        );
+    }
+
     #[test]
     fn request_extensions() {
         #[derive(Debug, Clone, PartialEq)]
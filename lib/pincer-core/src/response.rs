@@ -10,9 +10,12 @@
 //!
 //! For large responses, enable the `streaming` feature for [`streaming::StreamingResponse`].
 
-use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
 
 use bytes::Bytes;
+use http::{Extensions, HeaderMap};
 
 // ============================================================================
 // Streaming Response (feature-gated)
@@ -21,36 +24,114 @@ use bytes::Bytes;
 /// Streaming response support (requires `streaming` feature).
 #[cfg(feature = "streaming")]
 pub mod streaming {
-    use std::collections::HashMap;
+    use std::future::{Future, IntoFuture};
+    use std::net::SocketAddr;
     use std::pin::Pin;
 
     use bytes::Bytes;
     use futures_core::Stream;
     use futures_util::StreamExt;
+    use http::{Extensions, HeaderMap};
+    use tokio::sync::watch;
 
     /// A streaming body: chunks of bytes arriving over time.
     pub type StreamingBody = Pin<Box<dyn Stream<Item = crate::Result<Bytes>> + Send>>;
 
+    /// Trailers of a streaming response, sent after the body (e.g. the
+    /// `grpc-status` of gRPC-web, or the checksum of an object store).
+    ///
+    /// Await it once the body is read: it resolves to the trailers, or to
+    /// `None` if the response has none, or the body was dropped before its end.
+    /// Awaiting it while holding an unread body never completes.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use futures_util::StreamExt;
+    ///
+    /// let response = client.execute_streaming(request).await?;
+    /// let trailers = response.trailers();
+    /// let mut body = response.into_body();
+    /// while let Some(chunk) = body.next().await {
+    ///     file.write_all(&chunk?).await?;
+    /// }
+    /// let checksum = trailers.await.and_then(|trailers| trailers.get("x-checksum").cloned());
+    /// ```
+    #[derive(Debug, Clone)]
+    pub struct Trailers(watch::Receiver<Option<HeaderMap>>);
+
+    impl Trailers {
+        /// Create trailers sent by the returned [`TrailersSender`], e.g. by
+        /// the stream of the body.
+        #[must_use]
+        pub fn channel() -> (TrailersSender, Self) {
+            let (sender, receiver) = watch::channel(None);
+            (TrailersSender(sender), Self(receiver))
+        }
+
+        /// Trailers of a response without any.
+        fn none() -> Self {
+            Self::channel().1
+        }
+    }
+
+    impl IntoFuture for Trailers {
+        type Output = Option<HeaderMap>;
+        type IntoFuture = Pin<Box<dyn Future<Output = Option<HeaderMap>> + Send>>;
+
+        fn into_future(mut self) -> Self::IntoFuture {
+            Box::pin(async move {
+                // Fails once the sender is dropped without trailers
+                let _ = self.0.wait_for(Option::is_some).await;
+                self.0.borrow().clone()
+            })
+        }
+    }
+
+    /// Sender of the [`Trailers`] of a streaming response.
+    ///
+    /// Dropping it without sending resolves the trailers to `None`.
+    #[derive(Debug)]
+    pub struct TrailersSender(watch::Sender<Option<HeaderMap>>);
+
+    impl TrailersSender {
+        /// Send the trailers.
+        pub fn send(self, trailers: HeaderMap) {
+            self.0.send_replace(Some(trailers));
+        }
+    }
+
     /// HTTP response with streaming body, for large payloads.
     ///
     /// Unlike [`super::Response`], the body is consumed as a stream of chunks.
     pub struct StreamingResponse {
         status: u16,
-        headers: HashMap<String, String>,
+        headers: HeaderMap,
         body: StreamingBody,
+        trailers: Trailers,
+        extensions: Extensions,
     }
 
     impl StreamingResponse {
         /// Creates a new streaming response.
         #[must_use]
-        pub fn new(status: u16, headers: HashMap<String, String>, body: StreamingBody) -> Self {
+        pub fn new(status: u16, headers: HeaderMap, body: StreamingBody) -> Self {
             Self {
                 status,
                 headers,
                 body,
+                trailers: Trailers::none(),
+                extensions: Extensions::new(),
             }
         }
 
+        /// Set the trailers sent after the body.
+        #[must_use]
+        pub fn with_trailers(mut self, trailers: Trailers) -> Self {
+            self.trailers = trailers;
+            self
+        }
+
         /// HTTP status code.
         #[must_use]
         pub const fn status(&self) -> u16 {
@@ -59,14 +140,48 @@ pub mod streaming {
 
         /// Response headers.
         #[must_use]
-        pub fn headers(&self) -> &HashMap<String, String> {
+        pub const fn headers(&self) -> &HeaderMap {
             &self.headers
         }
 
-        /// Single header value by name.
+        /// Single header value by name, compared case-insensitively.
         #[must_use]
         pub fn header(&self, name: &str) -> Option<&str> {
-            self.headers.get(name).map(String::as_str)
+            self.headers.get(name)?.to_str().ok()
+        }
+
+        /// Response extensions.
+        #[must_use]
+        pub fn extensions(&self) -> &Extensions {
+            &self.extensions
+        }
+
+        /// Mutable access to extensions.
+        #[must_use]
+        pub fn extensions_mut(&mut self) -> &mut Extensions {
+            &mut self.extensions
+        }
+
+        /// HTTP version of the response, if recorded by the client.
+        #[must_use]
+        pub fn http_version(&self) -> Option<http::Version> {
+            self.extensions.get::<http::Version>().copied()
+        }
+
+        /// Address of the remote peer, if recorded by the client.
+        #[must_use]
+        pub fn remote_addr(&self) -> Option<SocketAddr> {
+            self.extensions
+                .get::<super::ConnectionInfo>()
+                .map(super::ConnectionInfo::remote_addr)
+        }
+
+        /// Local address of the connection, if recorded by the client.
+        #[must_use]
+        pub fn local_addr(&self) -> Option<SocketAddr> {
+            self.extensions
+                .get::<super::ConnectionInfo>()
+                .map(super::ConnectionInfo::local_addr)
         }
 
         /// Status is 2xx.
@@ -87,12 +202,60 @@ pub mod streaming {
             self.status >= 500 && self.status < 600
         }
 
+        /// Trailers of the response, available once the body is read.
+        ///
+        /// Get them before consuming the body, e.g. with
+        /// [`into_body`](Self::into_body), and await them after reading it.
+        #[must_use]
+        pub fn trailers(&self) -> Trailers {
+            self.trailers.clone()
+        }
+
         /// Consume into the streaming body.
         #[must_use]
         pub fn into_body(self) -> StreamingBody {
             self.body
         }
 
+        /// Deserialize the body as newline-delimited JSON, one value per line.
+        ///
+        /// Lines are limited to [`DEFAULT_MAX_LINE_LENGTH`](crate::DEFAULT_MAX_LINE_LENGTH)
+        /// bytes, see [`JsonLines::max_line_length`](crate::JsonLines::max_line_length).
+        #[must_use]
+        pub fn json_lines<T: serde::de::DeserializeOwned>(self) -> crate::JsonLines<T> {
+            crate::JsonLines::new(self.body)
+        }
+
+        /// Deserialize the body as CSV records, one record at a time.
+        ///
+        /// The first record is the header. Records are limited to
+        /// [`DEFAULT_MAX_LINE_LENGTH`](crate::DEFAULT_MAX_LINE_LENGTH) bytes, see
+        /// [`CsvRecords::max_record_length`](crate::CsvRecords::max_record_length).
+        #[cfg(feature = "csv")]
+        #[must_use]
+        pub fn csv_records<T: serde::de::DeserializeOwned>(self) -> crate::CsvRecords<T> {
+            crate::CsvRecords::new(self.body)
+        }
+
+        /// Deserialize the body as CSV records separated by `delimiter`.
+        #[cfg(feature = "csv")]
+        #[must_use]
+        pub fn csv_records_with_delimiter<T: serde::de::DeserializeOwned>(
+            self,
+            delimiter: char,
+        ) -> crate::CsvRecords<T> {
+            crate::CsvRecords::new(self.body).delimiter(delimiter)
+        }
+
+        /// Stream the body as text, one line at a time, e.g. to tail a log.
+        ///
+        /// Lines are limited to [`DEFAULT_MAX_LINE_LENGTH`](crate::DEFAULT_MAX_LINE_LENGTH)
+        /// bytes, see [`TextLines::max_line_length`](crate::TextLines::max_line_length).
+        #[must_use]
+        pub fn lines(self) -> crate::TextLines {
+            crate::TextLines::new(self.body)
+        }
+
         /// Buffer the entire stream into a [`Response`].
         ///
         /// # Errors
@@ -106,35 +269,210 @@ pub mod streaming {
                 collected.extend_from_slice(&chunk?);
             }
 
-            Ok(super::Response::new(
-                self.status,
-                self.headers,
-                Bytes::from(collected),
-            ))
+            let mut response =
+                super::Response::new(self.status, self.headers, Bytes::from(collected));
+            *response.extensions_mut() = self.extensions;
+            Ok(response)
         }
     }
 }
 
+// ============================================================================
+// Connection Info
+// ============================================================================
+
+/// Addresses of the connection a response was received on.
+///
+/// Inserted in response extensions by the HTTP client, and exposed through
+/// [`Response::remote_addr`] and [`Response::local_addr`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionInfo {
+    remote_addr: SocketAddr,
+    local_addr: SocketAddr,
+}
+
+impl ConnectionInfo {
+    /// Create connection info from the remote and local addresses.
+    #[must_use]
+    pub const fn new(remote_addr: SocketAddr, local_addr: SocketAddr) -> Self {
+        Self {
+            remote_addr,
+            local_addr,
+        }
+    }
+
+    /// Address of the remote peer.
+    #[must_use]
+    pub const fn remote_addr(&self) -> SocketAddr {
+        self.remote_addr
+    }
+
+    /// Local address of the connection.
+    #[must_use]
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+// ============================================================================
+// Discarded Body
+// ============================================================================
+
+/// Request extension discarding the body of a successful response unread.
+///
+/// Set by the generated clients on methods returning `()`, so large bodies
+/// nobody reads are not downloaded: the client returns the response with an
+/// empty body as soon as its status is a success. The body of an error
+/// response is still read, for error decoding. A discarded body with a
+/// `Content-Length` of at most 16 KiB is still read and dropped, so that its
+/// HTTP/1.1 connection is reused; the connection of a larger body, or of a
+/// body without `Content-Length`, is closed instead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiscardBody;
+
+/// What the client does with the body of a response, decided by [`InspectHead`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyAction {
+    /// Read the whole body.
+    Collect,
+    /// Read at most this many bytes of the body, dropping the rest.
+    Limit(usize),
+    /// Drop the body unread, the response has an empty body.
+    Discard,
+}
+
+/// Callback inspecting the head of a response.
+type InspectFn = dyn Fn(u16, &HeaderMap) -> crate::Result<BodyAction> + Send + Sync;
+
+/// Request extension inspecting the status and headers of the response
+/// before its body is downloaded.
+///
+/// The callback decides how much of the body to read, or fails the call
+/// without reading it, e.g. on a `Content-Length` over a budget. As with
+/// [`DiscardBody`], the connection of a dropped body is closed on HTTP/1.1,
+/// unless the body is small enough to be read and dropped.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::{BodyAction, Error, InspectHead, Method, Request};
+///
+/// // Keep the first KiB of error bodies, and refuse bodies over 10 MiB
+/// let inspect = InspectHead::new(|status, headers| {
+///     let length = headers
+///         .get("content-length")
+///         .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+///     match length {
+///         Some(length) if length > 10 << 20 => Err(Error::invalid_request("response too large")),
+///         _ if status >= 400 => Ok(BodyAction::Limit(1024)),
+///         _ => Ok(BodyAction::Collect),
+///     }
+/// });
+/// let url = "https://api.example.com/export".parse().expect("url");
+/// let request = Request::builder(Method::Get, url).extension(inspect).build();
+/// # let _: Request<()> = request;
+/// ```
+#[derive(Clone)]
+pub struct InspectHead(Arc<InspectFn>);
+
+impl fmt::Debug for InspectHead {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("InspectHead").finish_non_exhaustive()
+    }
+}
+
+impl InspectHead {
+    /// Create an extension inspecting response heads with `inspect`.
+    pub fn new(
+        inspect: impl Fn(u16, &HeaderMap) -> crate::Result<BodyAction> + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(inspect))
+    }
+
+    /// Create an extension failing on a successful response whose
+    /// `Content-Length` is over `limit` bytes, before reading its body.
+    ///
+    /// A successful body without `Content-Length` is read up to `limit`
+    /// bytes, and marked as [`TruncatedBody`] if it is cut. Error bodies are
+    /// left to the error body limit of the client.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use http::HeaderMap;
+    /// use pincer_core::{BodyAction, InspectHead};
+    ///
+    /// let inspect = InspectHead::max_body(1024);
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("content-length", "4096".parse().expect("value"));
+    /// assert!(inspect.inspect(200, &headers).is_err());
+    /// assert_eq!(inspect.inspect(500, &headers).ok(), Some(BodyAction::Collect));
+    /// assert_eq!(inspect.inspect(200, &HeaderMap::new()).ok(), Some(BodyAction::Limit(1024)));
+    /// ```
+    #[must_use]
+    pub fn max_body(limit: u64) -> Self {
+        Self::new(move |status, headers| {
+            if !(200..300).contains(&status) {
+                return Ok(BodyAction::Collect);
+            }
+            let length = headers
+                .get(http::header::CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+            match length {
+                Some(length) if length > limit => Err(crate::Error::invalid_request(format!(
+                    "response body of {length} bytes over the limit of {limit} bytes"
+                ))),
+                _ => Ok(BodyAction::Limit(
+                    usize::try_from(limit).unwrap_or(usize::MAX),
+                )),
+            }
+        })
+    }
+
+    /// Decide what to do with the body of a response with `status` and `headers`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the callback, failing the call.
+    pub fn inspect(&self, status: u16, headers: &HeaderMap) -> crate::Result<BodyAction> {
+        (self.0)(status, headers)
+    }
+}
+
+/// Default maximum number of bytes of an error response body read by the
+/// clients, into [`Error::Http`](crate::Error::Http).
+pub const DEFAULT_ERROR_BODY_LIMIT: usize = 64 * 1024;
+
+/// Response extension marking a body cut at a limit by the client, e.g. an
+/// error body over the error body limit, or a [`BodyAction::Limit`].
+///
+/// The rest of the body was dropped unread, so the body may not match its
+/// `Content-Length` or `Content-Encoding` headers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TruncatedBody;
+
 // ============================================================================
 // Buffered Response
 // ============================================================================
 
-/// HTTP response with status, headers, and body.
+/// HTTP response with status, headers, body, and extensions.
 #[derive(Debug, Clone)]
 pub struct Response<B = Bytes> {
     status: u16,
-    headers: HashMap<String, String>,
+    headers: HeaderMap,
     body: B,
+    extensions: Extensions,
 }
 
 impl<B> Response<B> {
     /// Creates a new response.
     #[must_use]
-    pub fn new(status: u16, headers: HashMap<String, String>, body: B) -> Self {
+    pub fn new(status: u16, headers: HeaderMap, body: B) -> Self {
         Self {
             status,
             headers,
             body,
+            extensions: Extensions::new(),
         }
     }
 
@@ -146,14 +484,22 @@ impl<B> Response<B> {
 
     /// Response headers.
     #[must_use]
-    pub fn headers(&self) -> &HashMap<String, String> {
+    pub const fn headers(&self) -> &HeaderMap {
         &self.headers
     }
 
-    /// Single header value by name.
+    /// Mutable access to headers.
+    #[must_use]
+    pub const fn headers_mut(&mut self) -> &mut HeaderMap {
+        &mut self.headers
+    }
+
+    /// Single header value by name, compared case-insensitively.
+    ///
+    /// Returns the first value, or `None` if it is not visible ASCII.
     #[must_use]
     pub fn header(&self, name: &str) -> Option<&str> {
-        self.headers.get(name).map(String::as_str)
+        self.headers.get(name)?.to_str().ok()
     }
 
     /// Response body.
@@ -162,6 +508,46 @@ impl<B> Response<B> {
         &self.body
     }
 
+    /// Response extensions.
+    ///
+    /// Extensions carry typed data attached by the client or middleware
+    /// (e.g., connection or TLS details).
+    #[must_use]
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Mutable access to extensions.
+    #[must_use]
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    /// HTTP version of the response, if recorded by the client.
+    #[must_use]
+    pub fn http_version(&self) -> Option<http::Version> {
+        self.extensions.get::<http::Version>().copied()
+    }
+
+    /// Address of the remote peer, if recorded by the client.
+    ///
+    /// This is the address actually connected to: a proxy or CDN edge rather
+    /// than the origin server when one is in the way.
+    #[must_use]
+    pub fn remote_addr(&self) -> Option<SocketAddr> {
+        self.extensions
+            .get::<ConnectionInfo>()
+            .map(ConnectionInfo::remote_addr)
+    }
+
+    /// Local address of the connection, if recorded by the client.
+    #[must_use]
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.extensions
+            .get::<ConnectionInfo>()
+            .map(ConnectionInfo::local_addr)
+    }
+
     /// Consume into body.
     #[must_use]
     pub fn into_body(self) -> B {
@@ -170,7 +556,7 @@ impl<B> Response<B> {
 
     /// Consume into (status, headers, body).
     #[must_use]
-    pub fn into_parts(self) -> (u16, HashMap<String, String>, B) {
+    pub fn into_parts(self) -> (u16, HeaderMap, B) {
         (self.status, self.headers, self.body)
     }
 
@@ -207,6 +593,7 @@ impl<B> Response<B> {
             status: self.status,
             headers: self.headers,
             body: f(self.body),
+            extensions: self.extensions,
         }
     }
 }
@@ -214,11 +601,222 @@ impl<B> Response<B> {
 impl Response<Bytes> {
     /// Deserialize the response body as JSON.
     ///
+    /// A compressed body is decoded first, see [`body_decoded`](Self::body_decoded).
+    /// It is deserialized with the [`SerdeJson`](crate::SerdeJson) codec of
+    /// the response extensions, set by clients configured with one, or the
+    /// default codec. Unknown fields are ignored, unless the codec denies them
+    /// or the response carries the [`StrictJson`](crate::StrictJson) extension.
+    ///
     /// # Errors
     ///
-    /// Returns an error if deserialization fails.
+    /// Returns an error if decoding or deserialization fails.
     pub fn json<T: serde::de::DeserializeOwned>(self) -> crate::Result<T> {
-        crate::from_json(&self.body)
+        let codec = self.json_codec();
+        crate::BodyCodec::decode(&codec, &self.body_decoded()?)
+    }
+
+    /// The [`SerdeJson`](crate::SerdeJson) codec of the response extensions,
+    /// denying unknown fields with the [`StrictJson`](crate::StrictJson) extension.
+    fn json_codec(&self) -> crate::SerdeJson {
+        let codec = self
+            .extensions()
+            .get::<crate::SerdeJson>()
+            .copied()
+            .unwrap_or_default();
+        if self.extensions().get::<crate::StrictJson>().is_some() {
+            codec.deny_unknown_fields(true)
+        } else {
+            codec
+        }
+    }
+
+    /// Deserialize the response body as JSON, failing on unknown fields, see
+    /// [`from_json_strict`](crate::from_json_strict).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding or deserialization fails, or the body has
+    /// a field unknown to `T`.
+    pub fn json_strict<T: serde::de::DeserializeOwned>(self) -> crate::Result<T> {
+        let codec = self.json_codec().deny_unknown_fields(true);
+        crate::BodyCodec::decode(&codec, &self.body_decoded()?)
+    }
+
+    /// Deserialize the value at a JSON pointer of the response body (e.g.
+    /// `/data/items`), see [`from_json_at`](crate::from_json_at).
+    ///
+    /// The value is deserialized with the decoding options of the response
+    /// codec, as [`json`](Self::json).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding fails, the body has no value at
+    /// `pointer`, or deserialization fails.
+    pub fn json_at<T: serde::de::DeserializeOwned>(self, pointer: &str) -> crate::Result<T> {
+        let options = self.json_codec().decode_options();
+        crate::body::json_at(&self.body_decoded()?, pointer, options)
+    }
+
+    /// Deserialize the value at a JSON pointer of the response body, failing
+    /// on unknown fields.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding fails, the body has no value at
+    /// `pointer`, deserialization fails, or the value has a field unknown to `T`.
+    pub fn json_at_strict<T: serde::de::DeserializeOwned>(self, pointer: &str) -> crate::Result<T> {
+        let options = self.json_codec().deny_unknown_fields(true).decode_options();
+        crate::body::json_at(&self.body_decoded()?, pointer, options)
+    }
+
+    /// Deserialize the response body as JSON with `codec`.
+    ///
+    /// A compressed body is decoded first, see [`body_decoded`](Self::body_decoded).
+    /// Only the options of `codec` apply: the [`StrictJson`](crate::StrictJson)
+    /// and [`SerdeJson`](crate::SerdeJson) extensions of the response are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding or deserialization fails.
+    pub fn json_with<C: crate::BodyCodec, T: serde::de::DeserializeOwned>(
+        self,
+        codec: &C,
+    ) -> crate::Result<T> {
+        codec.decode(&self.body_decoded()?)
+    }
+
+    /// Deserialize the response body as XML with `codec`.
+    ///
+    /// A compressed body is decoded first, see [`body_decoded`](Self::body_decoded).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding or deserialization fails.
+    pub fn xml_with<C: crate::BodyCodec, T: serde::de::DeserializeOwned>(
+        self,
+        codec: &C,
+    ) -> crate::Result<T> {
+        codec.decode(&self.body_decoded()?)
+    }
+
+    /// Deserialize the response body as CBOR with `codec`.
+    ///
+    /// A compressed body is decoded first, see [`body_decoded`](Self::body_decoded).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding or deserialization fails.
+    pub fn cbor_with<C: crate::BodyCodec, T: serde::de::DeserializeOwned>(
+        self,
+        codec: &C,
+    ) -> crate::Result<T> {
+        codec.decode(&self.body_decoded()?)
+    }
+
+    /// Deserialize the response body as CSV records, see [`from_csv`](crate::from_csv).
+    ///
+    /// The first record is the header. A compressed body is decoded first,
+    /// see [`body_decoded`](Self::body_decoded).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding or deserialization fails.
+    #[cfg(feature = "csv")]
+    pub fn csv<T: serde::de::DeserializeOwned>(self) -> crate::Result<Vec<T>> {
+        self.csv_with_delimiter(',')
+    }
+
+    /// Deserialize the response body as CSV records separated by `delimiter`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding or deserialization fails.
+    #[cfg(feature = "csv")]
+    pub fn csv_with_delimiter<T: serde::de::DeserializeOwned>(
+        self,
+        delimiter: char,
+    ) -> crate::Result<Vec<T>> {
+        crate::from_csv(&self.body_decoded()?, delimiter)
+    }
+
+    /// Deserialize the response body as YAML, see [`from_yaml`](crate::from_yaml).
+    ///
+    /// A compressed body is decoded first, see [`body_decoded`](Self::body_decoded).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding or deserialization fails.
+    #[cfg(feature = "yaml")]
+    pub fn yaml<T: serde::de::DeserializeOwned>(self) -> crate::Result<T> {
+        crate::from_yaml(&self.body_decoded()?)
+    }
+
+    /// Deserialize the response body as TOML, see [`from_toml`](crate::from_toml).
+    ///
+    /// A compressed body is decoded first, see [`body_decoded`](Self::body_decoded).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding or deserialization fails.
+    #[cfg(feature = "toml")]
+    pub fn toml<T: serde::de::DeserializeOwned>(self) -> crate::Result<T> {
+        crate::from_toml(&self.body_decoded()?)
+    }
+
+    /// Parse the response body as an HTML document, for endpoints only
+    /// available as web pages.
+    ///
+    /// A compressed body is decoded first, see [`body_decoded`](Self::body_decoded).
+    /// The parser is lenient, as browsers: malformed markup is repaired
+    /// rather than rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding fails or the body is not valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::scraper::Selector;
+    ///
+    /// let document = response.html()?;
+    /// let selector = Selector::parse("h1").expect("selector");
+    /// let title = document.select(&selector).next().map(|h1| h1.text().collect::<String>());
+    /// ```
+    #[cfg(feature = "format-html")]
+    pub fn html(self) -> crate::Result<scraper::Html> {
+        let body = self.body_decoded()?;
+        let text = std::str::from_utf8(&body)
+            .map_err(|err| crate::Error::deserialization("HTML", err.to_string()))?;
+        Ok(scraper::Html::parse_document(text))
+    }
+
+    /// Deserialize the response body with `decode`, for formats without a
+    /// helper (see [`yaml`](Self::yaml) and [`toml`](Self::toml) with the
+    /// `yaml` and `toml` features).
+    ///
+    /// A compressed body is decoded first, see [`body_decoded`](Self::body_decoded).
+    /// Errors of `decode` are reported as [`Error::Deserialization`](crate::Error::Deserialization)
+    /// for `format`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if decoding or deserialization fails.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let config: Config = response.decode_with("JSON5", |bytes| {
+    ///     json5::from_str(&String::from_utf8_lossy(bytes))
+    /// })?;
+    /// ```
+    pub fn decode_with<T, E: std::fmt::Display>(
+        self,
+        format: &'static str,
+        decode: impl FnOnce(&[u8]) -> Result<T, E>,
+    ) -> crate::Result<T> {
+        decode(&self.body_decoded()?)
+            .map_err(|err| crate::Error::deserialization(format, err.to_string()))
     }
 
     /// Get the response body as text.
@@ -237,8 +835,11 @@ mod tests {
 
     #[test]
     fn response_basic() {
-        let mut headers = HashMap::new();
-        headers.insert("Content-Type".to_string(), "application/json".to_string());
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static("application/json"),
+        );
 
         let response = Response::new(200, headers, Bytes::from(r#"{"id":1}"#));
 
@@ -251,13 +852,13 @@ mod tests {
 
     #[test]
     fn response_status_checks() {
-        let response = Response::new(301, HashMap::new(), Bytes::new());
+        let response = Response::new(301, HeaderMap::new(), Bytes::new());
         assert!(response.is_redirection());
 
-        let response = Response::new(404, HashMap::new(), Bytes::new());
+        let response = Response::new(404, HeaderMap::new(), Bytes::new());
         assert!(response.is_client_error());
 
-        let response = Response::new(500, HashMap::new(), Bytes::new());
+        let response = Response::new(500, HeaderMap::new(), Bytes::new());
         assert!(response.is_server_error());
     }
 
@@ -270,7 +871,7 @@ mod tests {
         }
 
         let body = Bytes::from(r#"{"id":1,"name":"test"}"#);
-        let response = Response::new(200, HashMap::new(), body);
+        let response = Response::new(200, HeaderMap::new(), body);
 
         let user: User = response.json().expect("deserialize");
         assert_eq!(
@@ -282,10 +883,129 @@ mod tests {
         );
     }
 
+    #[test]
+    fn response_decode_with() {
+        let response = Response::new(200, HeaderMap::new(), Bytes::from("port = 8080"));
+        let port: u16 = response
+            .decode_with("TOML", |bytes| {
+                std::str::from_utf8(bytes)
+                    .map_err(|err| err.to_string())?
+                    .trim_start_matches("port = ")
+                    .parse::<u16>()
+                    .map_err(|err| err.to_string())
+            })
+            .expect("decode");
+        assert_eq!(port, 8080);
+
+        let response = Response::new(200, HeaderMap::new(), Bytes::from("port: x"));
+        let err = response
+            .decode_with("YAML", |_| Err::<u16, _>("invalid type: string"))
+            .expect_err("error");
+        assert_eq!(
+            err.to_string(),
+            "YAML deserialization error: invalid type: string"
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn response_yaml() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Deployment {
+            name: String,
+            replicas: u32,
+        }
+
+        let body = Bytes::from("name: api\nreplicas: 3\n");
+        let deployment: Deployment = Response::new(200, HeaderMap::new(), body)
+            .yaml()
+            .expect("yaml");
+        assert_eq!(
+            deployment,
+            Deployment {
+                name: "api".to_string(),
+                replicas: 3,
+            }
+        );
+
+        let body = Bytes::from("name: api\nreplicas: many\n");
+        let err = Response::new(200, HeaderMap::new(), body)
+            .yaml::<Deployment>()
+            .expect_err("invalid replicas");
+        assert!(
+            err.to_string()
+                .starts_with("YAML deserialization error: replicas: invalid type"),
+            "{err}"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn response_toml() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Manifest {
+            package: Package,
+        }
+
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Package {
+            name: String,
+            version: String,
+        }
+
+        let body = Bytes::from("[package]\nname = \"pincer\"\nversion = \"0.1.0\"\n");
+        let manifest: Manifest = Response::new(200, HeaderMap::new(), body)
+            .toml()
+            .expect("toml");
+        assert_eq!(manifest.package.name, "pincer");
+        assert_eq!(manifest.package.version, "0.1.0");
+
+        let body = Bytes::from("[package\nname = 1");
+        let err = Response::new(200, HeaderMap::new(), body)
+            .toml::<Manifest>()
+            .expect_err("invalid TOML");
+        assert!(
+            err.to_string().starts_with("TOML deserialization error:"),
+            "{err}"
+        );
+        let body = Bytes::from_static(b"\xff");
+        assert!(
+            Response::new(200, HeaderMap::new(), body)
+                .toml::<Manifest>()
+                .is_err()
+        );
+    }
+
+    #[cfg(feature = "format-html")]
+    #[test]
+    fn response_html() {
+        let body = Bytes::from(
+            "<html><body><h1>Status</h1><ul><li class=up>api<li class=down>db</ul></body></html>",
+        );
+        let document = Response::new(200, HeaderMap::new(), body)
+            .html()
+            .expect("html");
+        let selector = scraper::Selector::parse("li.down").expect("selector");
+        let down: Vec<String> = document
+            .select(&selector)
+            .map(|item| item.text().collect())
+            .collect();
+        assert_eq!(down, ["db"]);
+
+        let body = Bytes::from_static(b"<p>\xff</p>");
+        let err = Response::new(200, HeaderMap::new(), body)
+            .html()
+            .expect_err("invalid UTF-8");
+        assert!(
+            err.to_string().starts_with("HTML deserialization error:"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn response_text() {
         let body = Bytes::from("Hello, World!");
-        let response = Response::new(200, HashMap::new(), body);
+        let response = Response::new(200, HeaderMap::new(), body);
 
         let text = response.text().expect("text");
         assert_eq!(text, "Hello, World!");
@@ -293,10 +1013,61 @@ mod tests {
 
     #[test]
     fn response_map_body() {
-        let response = Response::new(200, HashMap::new(), Bytes::from("test"));
+        let response = Response::new(200, HeaderMap::new(), Bytes::from("test"));
         let mapped = response.map_body(|b| b.len());
 
         assert_eq!(mapped.status(), 200);
         assert_eq!(*mapped.body(), 4);
     }
+
+    #[test]
+    fn response_extensions() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Marker(&'static str);
+
+        let mut response = Response::new(200, HeaderMap::new(), Bytes::new());
+        assert!(response.extensions().get::<Marker>().is_none());
+
+        response.extensions_mut().insert(Marker("tls"));
+        let mapped = response.map_body(|b| b.len());
+        assert_eq!(mapped.extensions().get::<Marker>(), Some(&Marker("tls")));
+    }
+
+    #[test]
+    fn response_connection_details() {
+        let mut response = Response::new(200, HeaderMap::new(), Bytes::new());
+        assert_eq!(response.http_version(), None);
+        assert_eq!(response.remote_addr(), None);
+
+        let remote: SocketAddr = "203.0.113.7:443".parse().expect("addr");
+        let local: SocketAddr = "192.0.2.1:52000".parse().expect("addr");
+        response.extensions_mut().insert(http::Version::HTTP_2);
+        response
+            .extensions_mut()
+            .insert(ConnectionInfo::new(remote, local));
+
+        assert_eq!(response.http_version(), Some(http::Version::HTTP_2));
+        assert_eq!(response.remote_addr(), Some(remote));
+        assert_eq!(response.local_addr(), Some(local));
+    }
+
+    #[cfg(feature = "streaming")]
+    #[tokio::test]
+    async fn streaming_response_trailers() {
+        use streaming::{StreamingResponse, Trailers};
+
+        let body = Box::pin(futures_util::stream::empty());
+        let response = StreamingResponse::new(200, HeaderMap::new(), body);
+        assert_eq!(response.trailers().await, None);
+
+        let (sender, trailers) = Trailers::channel();
+        let mut map = HeaderMap::new();
+        map.insert("grpc-status", http::HeaderValue::from_static("0"));
+        sender.send(map.clone());
+        assert_eq!(trailers.await, Some(map));
+
+        let (sender, trailers) = Trailers::channel();
+        drop(sender);
+        assert_eq!(trailers.await, None);
+    }
 }
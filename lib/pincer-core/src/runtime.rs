@@ -0,0 +1,108 @@
+//! Async runtime abstraction.
+//!
+//! The core crate does not depend on an async runtime: timers and task
+//! spawning go through [`Runtime`], so backends built on smol or async-std can
+//! implement [`HttpClient`](crate::HttpClient) without Tokio. The `pincer`
+//! crate provides a Tokio implementation, used when a client does not name
+//! its own runtime.
+
+use std::future::{Future, poll_fn};
+use std::pin::{Pin, pin};
+use std::task::Poll;
+use std::time::Duration;
+
+use crate::{Error, Result};
+
+/// Boxed future returned by [`Runtime::sleep`].
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Timer and task spawning of an async runtime.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer_core::{Runtime, Sleep};
+///
+/// struct SmolRuntime;
+///
+/// impl Runtime for SmolRuntime {
+///     fn sleep(&self, duration: Duration) -> Sleep {
+///         Box::pin(async move {
+///             smol::Timer::after(duration).await;
+///         })
+///     }
+///
+///     fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) {
+///         smol::spawn(future).detach();
+///     }
+/// }
+/// ```
+pub trait Runtime: Send + Sync {
+    /// Returns a future completing after `duration`.
+    fn sleep(&self, duration: Duration) -> Sleep;
+
+    /// Run `future` in the background.
+    fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>);
+}
+
+/// Run `future`, failing with [`Error::Timeout`] if it does not complete
+/// within `duration` on `runtime`.
+///
+/// # Errors
+///
+/// Returns [`Error::Timeout`] if the duration elapses first.
+pub async fn timeout<F: Future>(
+    runtime: &dyn Runtime,
+    duration: Duration,
+    future: F,
+) -> Result<F::Output> {
+    let mut sleep = runtime.sleep(duration);
+    let mut future = pin!(future);
+    poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        sleep.as_mut().poll(cx).map(|()| Err(Error::Timeout))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runtime whose timers never fire, or fire immediately.
+    struct TestRuntime {
+        expired: bool,
+    }
+
+    impl Runtime for TestRuntime {
+        fn sleep(&self, _duration: Duration) -> Sleep {
+            if self.expired {
+                Box::pin(std::future::ready(()))
+            } else {
+                Box::pin(std::future::pending())
+            }
+        }
+
+        fn spawn(&self, _future: Pin<Box<dyn Future<Output = ()> + Send>>) {}
+    }
+
+    #[tokio::test]
+    async fn completes_before_the_timer() {
+        let runtime = TestRuntime { expired: true };
+        let output = timeout(&runtime, Duration::ZERO, std::future::ready(42)).await;
+        assert_eq!(output.expect("completed"), 42);
+    }
+
+    #[tokio::test]
+    async fn fails_when_the_timer_fires() {
+        let runtime = TestRuntime { expired: true };
+        let output = timeout(&runtime, Duration::ZERO, std::future::pending::<()>()).await;
+        assert!(output.expect_err("timeout").is_timeout());
+
+        let runtime = TestRuntime { expired: false };
+        let output = timeout(&runtime, Duration::ZERO, async { "done" }).await;
+        assert_eq!(output.expect("completed"), "done");
+    }
+}
@@ -0,0 +1,457 @@
+//! Strict JSON decoding, failing on unknown fields.
+//!
+//! Serde derived structs skip the fields they do not know by deserializing
+//! them as [`IgnoredAny`](serde::de::IgnoredAny). The [`Strict`] deserializer
+//! wraps another one and fails instead, at every level of nesting, so the
+//! drift of an API contract shows up in tests.
+//!
+//! It also reads numbers written as strings, e.g. `"18446744073709551615"`,
+//! for the decoding options of [`SerdeJson`](crate::SerdeJson).
+
+use std::fmt;
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+
+use crate::Result;
+
+/// Response extension making [`Response::json`](crate::Response::json) fail
+/// on unknown fields, as [`from_json_strict`].
+///
+/// Clients set it on every response when strict decoding is configured, e.g.
+/// in tests, while production clients stay lenient.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StrictJson;
+
+/// Deserialize JSON bytes like [`from_json`](crate::from_json), failing on
+/// unknown fields.
+///
+/// The error path points to the unknown field. Fields of flattened structs,
+/// and of untagged or internally tagged enums, are not checked.
+///
+/// # Errors
+///
+/// Returns an error if deserialization fails or the JSON has a field unknown
+/// to `T`.
+///
+/// # Example
+///
+/// ```
+/// use pincer_core::from_json_strict;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct User { name: String }
+///
+/// let err = from_json_strict::<User>(br#"{"name":"Alice","age":42}"#).expect_err("unknown field");
+/// assert!(err.to_string().starts_with("JSON deserialization error at 'age': unknown field"));
+/// ```
+pub fn from_json_strict<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    from_json_with(bytes, Options::STRICT)
+}
+
+/// Deserialize JSON bytes like [`from_json`](crate::from_json), with `options`.
+pub(crate) fn from_json_with<T: DeserializeOwned>(bytes: &[u8], options: Options) -> Result<T> {
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    serde_path_to_error::deserialize(Strict(&mut deserializer, options)).map_err(|e| {
+        crate::Error::json_deserialization(e.path().to_string(), e.inner().to_string())
+    })
+}
+
+/// Decoding options of the [`Strict`] deserializer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Options {
+    /// Fail on ignored values, i.e. unknown fields
+    pub(crate) deny_unknown_fields: bool,
+    /// Read numbers written as strings
+    pub(crate) numbers_from_strings: bool,
+}
+
+impl Options {
+    /// Only fail on unknown fields.
+    pub(crate) const STRICT: Self = Self {
+        deny_unknown_fields: true,
+        numbers_from_strings: false,
+    };
+}
+
+/// Deserializer failing on ignored values, i.e. unknown fields.
+pub(crate) struct Strict<D>(pub(crate) D, pub(crate) Options);
+
+/// Visitor deserializing nested values strictly.
+struct StrictVisitor<V>(V, Options);
+
+/// Visitor of a number, also reading a number written as a string.
+struct NumberVisitor<V>(V, Options);
+
+/// Seed deserializing a value strictly.
+struct StrictSeed<S>(S, Options);
+
+/// Sequence, map, enum or variant access deserializing its values strictly.
+struct StrictAccess<A>(A, Options);
+
+/// Forward `deserialize_*` methods to the inner deserializer.
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(
+                self,
+                $($arg: $ty,)*
+                visitor: V,
+            ) -> std::result::Result<V::Value, D::Error> {
+                self.0.$method($($arg,)* StrictVisitor(visitor, self.1))
+            }
+        )*
+    };
+}
+
+/// Forward number `deserialize_*` methods to the inner deserializer, reading
+/// any value to also accept strings if enabled.
+macro_rules! forward_deserialize_number {
+    ($($method:ident;)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> std::result::Result<V::Value, D::Error> {
+                if self.1.numbers_from_strings {
+                    self.0.deserialize_any(NumberVisitor(visitor, self.1))
+                } else {
+                    self.0.$method(StrictVisitor(visitor, self.1))
+                }
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for Strict<D> {
+    type Error = D::Error;
+
+    forward_deserialize! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_option();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+    }
+
+    forward_deserialize_number! {
+        deserialize_i8;
+        deserialize_i16;
+        deserialize_i32;
+        deserialize_i64;
+        deserialize_i128;
+        deserialize_u8;
+        deserialize_u16;
+        deserialize_u32;
+        deserialize_u64;
+        deserialize_u128;
+        deserialize_f32;
+        deserialize_f64;
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(
+        self,
+        visitor: V,
+    ) -> std::result::Result<V::Value, D::Error> {
+        if self.1.deny_unknown_fields {
+            Err(de::Error::custom("unknown field"))
+        } else {
+            self.0.deserialize_ignored_any(visitor)
+        }
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+/// Forward `visit_*` methods of values without nested values to the inner visitor.
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty);)*) => {
+        $(
+            fn $method<E: de::Error>(self, value: $ty) -> std::result::Result<V::Value, E> {
+                self.0.$method(value)
+            }
+        )*
+    };
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for StrictVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    forward_visit! {
+        visit_bool(bool);
+        visit_i8(i8);
+        visit_i16(i16);
+        visit_i32(i32);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u8(u8);
+        visit_u16(u16);
+        visit_u32(u32);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f32(f32);
+        visit_f64(f64);
+        visit_char(char);
+        visit_str(&str);
+        visit_borrowed_str(&'de str);
+        visit_string(String);
+        visit_bytes(&[u8]);
+        visit_borrowed_bytes(&'de [u8]);
+        visit_byte_buf(Vec<u8>);
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<V::Value, E> {
+        self.0.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<V::Value, E> {
+        self.0.visit_unit()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<V::Value, D::Error> {
+        self.0.visit_some(Strict(deserializer, self.1))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<V::Value, D::Error> {
+        self.0.visit_newtype_struct(Strict(deserializer, self.1))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> std::result::Result<V::Value, A::Error> {
+        self.0.visit_seq(StrictAccess(seq, self.1))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> std::result::Result<V::Value, A::Error> {
+        self.0.visit_map(StrictAccess(map, self.1))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> std::result::Result<V::Value, A::Error> {
+        self.0.visit_enum(StrictAccess(data, self.1))
+    }
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for NumberVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    forward_visit! {
+        visit_bool(bool);
+        visit_i64(i64);
+        visit_i128(i128);
+        visit_u64(u64);
+        visit_u128(u128);
+        visit_f64(f64);
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<V::Value, E> {
+        if let Ok(number) = value.parse::<u64>() {
+            self.0.visit_u64(number)
+        } else if let Ok(number) = value.parse::<i64>() {
+            self.0.visit_i64(number)
+        } else if let Ok(number) = value.parse::<u128>() {
+            self.0.visit_u128(number)
+        } else if let Ok(number) = value.parse::<i128>() {
+            self.0.visit_i128(number)
+        } else if let Ok(number) = value.parse::<f64>() {
+            self.0.visit_f64(number)
+        } else {
+            self.0.visit_str(value)
+        }
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<V::Value, E> {
+        self.0.visit_none()
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<V::Value, E> {
+        self.0.visit_unit()
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> std::result::Result<V::Value, A::Error> {
+        StrictVisitor(self.0, self.1).visit_seq(seq)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> std::result::Result<V::Value, A::Error> {
+        StrictVisitor(self.0, self.1).visit_map(map)
+    }
+}
+
+impl<'de, S: DeserializeSeed<'de>> DeserializeSeed<'de> for StrictSeed<S> {
+    type Value = S::Value;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<S::Value, D::Error> {
+        self.0.deserialize(Strict(deserializer, self.1))
+    }
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for StrictAccess<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> std::result::Result<Option<T::Value>, A::Error> {
+        self.0.next_element_seed(StrictSeed(seed, self.1))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for StrictAccess<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> std::result::Result<Option<K::Value>, A::Error> {
+        self.0.next_key_seed(StrictSeed(seed, self.1))
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> std::result::Result<V::Value, A::Error> {
+        self.0.next_value_seed(StrictSeed(seed, self.1))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A: EnumAccess<'de>> EnumAccess<'de> for StrictAccess<A> {
+    type Error = A::Error;
+    type Variant = StrictAccess<A::Variant>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> std::result::Result<(V::Value, Self::Variant), A::Error> {
+        let (value, variant) = self.0.variant_seed(StrictSeed(seed, self.1))?;
+        Ok((value, StrictAccess(variant, self.1)))
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for StrictAccess<A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> std::result::Result<(), A::Error> {
+        self.0.unit_variant()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> std::result::Result<T::Value, A::Error> {
+        self.0.newtype_variant_seed(StrictSeed(seed, self.1))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        len: usize,
+        visitor: V,
+    ) -> std::result::Result<V::Value, A::Error> {
+        self.0.tuple_variant(len, StrictVisitor(visitor, self.1))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> std::result::Result<V::Value, A::Error> {
+        self.0
+            .struct_variant(fields, StrictVisitor(visitor, self.1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::from_json;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct User {
+        name: String,
+        address: Option<Address>,
+        #[serde(default)]
+        roles: Vec<Role>,
+        #[serde(default)]
+        labels: HashMap<String, String>,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Address {
+        city: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Role {
+        Admin { scope: String },
+        Guest,
+    }
+
+    /// Check that `json` is rejected for the unknown field at `path`.
+    fn assert_unknown_field(json: &str, path: &str) {
+        let err = from_json_strict::<User>(json.as_bytes()).expect_err("unknown field");
+        let expected = format!("JSON deserialization error at '{path}': unknown field");
+        assert!(err.to_string().starts_with(&expected), "{err}");
+    }
+
+    #[test]
+    fn decodes_known_fields() {
+        let json = br#"{"name":"Alice","address":{"city":"Paris"},"roles":[{"admin":{"scope":"all"}},"guest"],"labels":{"team":"core"}}"#;
+        let user: User = from_json_strict(json).expect("strict");
+        assert_eq!(user, from_json::<User>(json).expect("lenient"));
+    }
+
+    #[test]
+    fn rejects_unknown_fields_with_their_path() {
+        assert_unknown_field(r#"{"name":"Alice","age":42}"#, "age");
+        assert_unknown_field(
+            r#"{"name":"Alice","address":{"city":"Paris","zip":"75001"}}"#,
+            "address.zip",
+        );
+        assert_unknown_field(
+            r#"{"name":"Alice","roles":["guest",{"admin":{"scope":"all","level":1}}]}"#,
+            "roles[1].admin.level",
+        );
+        assert!(from_json::<User>(br#"{"name":"Alice","age":42}"#).is_ok());
+    }
+}
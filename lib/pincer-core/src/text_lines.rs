@@ -0,0 +1,231 @@
+//! Raw text line streams.
+
+use std::fmt;
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+
+use bytes::BytesMut;
+use futures_core::Stream;
+use futures_util::StreamExt;
+
+use crate::{Error, Result, StreamingBody};
+
+/// Default maximum length of a line of a [`TextLines`] stream, in bytes.
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 64 * 1024;
+
+/// Stream of the lines of a text body, yielded as the chunks arrive, e.g.
+/// to tail a log endpoint (`/logs?follow=true`).
+///
+/// Lines are split on `\n`, without the line ending (`\n` or `\r\n`); blank
+/// lines are yielded as empty strings, and invalid UTF-8 is replaced with
+/// `U+FFFD`. A last line without a trailing newline is yielded at the end of
+/// the body.
+///
+/// A line longer than the [maximum length](Self::max_line_length) yields an
+/// error without buffering the rest of it, and the stream goes on with the
+/// next line. The stream ends after an error reading the body.
+///
+/// # Example
+///
+/// ```ignore
+/// use futures_util::StreamExt;
+///
+/// let mut logs = client.execute_streaming(request).await?.lines();
+/// while let Some(line) = logs.next().await {
+///     println!("{}", line?);
+/// }
+/// ```
+pub struct TextLines {
+    lines: LineSplitter,
+}
+
+impl fmt::Debug for TextLines {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TextLines")
+            .field("buffered", &self.lines.buffer.len())
+            .field("max_line_length", &self.lines.max_line_length)
+            .field("done", &self.lines.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TextLines {
+    /// Create a stream of the lines of `body`.
+    #[must_use]
+    pub fn new(body: StreamingBody) -> Self {
+        Self {
+            lines: LineSplitter::new(body, "text"),
+        }
+    }
+
+    /// Set the maximum length of a line, without its line ending (default:
+    /// [`DEFAULT_MAX_LINE_LENGTH`]).
+    #[must_use]
+    pub const fn max_line_length(mut self, max_line_length: usize) -> Self {
+        self.lines.max_line_length = max_line_length;
+        self
+    }
+}
+
+impl Stream for TextLines {
+    type Item = Result<String>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let line = ready!(self.lines.poll_line(cx));
+        Poll::Ready(line.map(|line| line.map(|line| String::from_utf8_lossy(&line).into_owned())))
+    }
+}
+
+/// Splitter of the lines of a streaming body, shared by [`TextLines`] and
+/// [`JsonLines`](crate::JsonLines).
+pub(crate) struct LineSplitter {
+    body: StreamingBody,
+    buffer: BytesMut,
+    /// Length of the start of `buffer` known to have no newline.
+    scanned: usize,
+    pub(crate) max_line_length: usize,
+    /// Skipping the rest of a line too long, up to its newline.
+    discarding: bool,
+    pub(crate) done: bool,
+    /// Format named in the errors of lines too long.
+    format: &'static str,
+}
+
+impl LineSplitter {
+    pub(crate) fn new(body: StreamingBody, format: &'static str) -> Self {
+        Self {
+            body,
+            buffer: BytesMut::new(),
+            scanned: 0,
+            max_line_length: DEFAULT_MAX_LINE_LENGTH,
+            discarding: false,
+            done: false,
+            format,
+        }
+    }
+
+    /// Length of the buffered bytes not yet split into a line.
+    pub(crate) fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Poll the next line, without its line ending.
+    pub(crate) fn poll_line(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<BytesMut>>> {
+        loop {
+            if let Some(line) = self.next_line() {
+                return Poll::Ready(Some(line));
+            }
+            if self.done {
+                return Poll::Ready(None);
+            }
+            match ready!(self.body.poll_next_unpin(cx)) {
+                Some(Ok(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    self.done = true;
+                    self.buffer.clear();
+                    return Poll::Ready(Some(Err(err)));
+                }
+                None => self.done = true,
+            }
+        }
+    }
+
+    /// Take the next line out of the buffer: a complete line, the rest of
+    /// the buffer once the body is done, or an error once the line is too long.
+    fn next_line(&mut self) -> Option<Result<BytesMut>> {
+        loop {
+            let newline = self
+                .buffer
+                .get(self.scanned..)
+                .and_then(|rest| rest.iter().position(|byte| *byte == b'\n'));
+            let mut line = match newline {
+                Some(position) => {
+                    let mut line = self.buffer.split_to(self.scanned + position + 1);
+                    line.truncate(line.len() - 1);
+                    line
+                }
+                None if self.done && !self.buffer.is_empty() => self.buffer.split(),
+                None => {
+                    self.scanned = self.buffer.len();
+                    if self.discarding {
+                        self.buffer.clear();
+                        self.scanned = 0;
+                    } else if self.buffer.len() > self.max_line_length + 1 {
+                        // One more byte for a `\r` before the newline
+                        self.buffer.clear();
+                        self.scanned = 0;
+                        self.discarding = true;
+                        return Some(Err(self.too_long()));
+                    }
+                    return None;
+                }
+            };
+            self.scanned = 0;
+            if std::mem::take(&mut self.discarding) {
+                continue;
+            }
+            if line.ends_with(b"\r") {
+                line.truncate(line.len() - 1);
+            }
+            if line.len() > self.max_line_length {
+                return Some(Err(self.too_long()));
+            }
+            return Some(Ok(line));
+        }
+    }
+
+    fn too_long(&self) -> Error {
+        Error::deserialization(
+            self.format,
+            format!("line longer than {} bytes", self.max_line_length),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures_util::stream;
+
+    use super::*;
+
+    fn lines(chunks: Vec<Result<&'static str>>) -> TextLines {
+        let chunks = chunks
+            .into_iter()
+            .map(|chunk| chunk.map(|chunk| Bytes::from_static(chunk.as_bytes())));
+        TextLines::new(Box::pin(stream::iter(chunks)))
+    }
+
+    #[tokio::test]
+    async fn splits_lines_across_chunks() {
+        let values: Vec<String> = lines(vec![Ok("one\ntw"), Ok("o\r\n\n three \n"), Ok("four")])
+            .map(|line| line.expect("line"))
+            .collect()
+            .await;
+        assert_eq!(values, ["one", "two", "", " three ", "four"]);
+    }
+
+    #[tokio::test]
+    async fn skips_lines_too_long() {
+        let results: Vec<Result<String>> = lines(vec![
+            Ok("short\r\nlong"),
+            Ok("er line"),
+            Ok(" still going\nok\ntoo long\n"),
+            Err(Error::connection("reset")),
+            Ok("lost\n"),
+        ])
+        .max_line_length(5)
+        .collect()
+        .await;
+        let values: Vec<_> = results
+            .iter()
+            .map(|result| result.as_ref().ok().map(String::as_str))
+            .collect();
+        assert_eq!(values, [Some("short"), None, Some("ok"), None, None]);
+        let err = results.get(1).and_then(|result| result.as_ref().err());
+        assert_eq!(
+            err.map(ToString::to_string).as_deref(),
+            Some("text deserialization error: line longer than 5 bytes")
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Timestamp formatting of query and path parameters.
+//!
+//! Generated clients format the parameters marked with a timestamp format
+//! (e.g. `#[query(format = "rfc3339")]`) with [`format_timestamp`], instead
+//! of their `Display` output.
+
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Format a timestamp, in UTC.
+///
+/// `time` is a [`SystemTime`] or any type convertible into one, such as
+/// `chrono::DateTime<Tz>` or `time::OffsetDateTime`. The `format` is one of:
+///
+/// - `rfc3339`: `2024-05-06T07:08:09Z`, to the second: the fraction of the
+///   second is dropped, use a pattern such as `%Y-%m-%dT%H:%M:%S.%3fZ` to keep it
+/// - `unix`: seconds since the Unix epoch, `1714979289`
+/// - `unix_millis`: milliseconds since the Unix epoch, `1714979289000`
+/// - a `strftime`-like pattern, with `%Y`, `%m`, `%d`, `%H`, `%M`, `%S`,
+///   `%3f`, `%6f` and `%9f` (or `%f`) for the fraction of the second, `%s`
+///   for the Unix seconds, `%z` for the offset (`+0000`) and `%%`; other
+///   characters are copied as-is
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// use pincer_core::format_timestamp;
+///
+/// let time = UNIX_EPOCH + Duration::from_millis(1_714_979_289_250);
+/// assert_eq!(format_timestamp(time, "rfc3339"), "2024-05-06T07:08:09Z");
+/// assert_eq!(format_timestamp(time, "unix_millis"), "1714979289250");
+/// assert_eq!(format_timestamp(time, "%d/%m/%Y %H:%M:%S.%3f"), "06/05/2024 07:08:09.250");
+/// ```
+#[must_use]
+pub fn format_timestamp(time: impl Into<SystemTime>, format: &str) -> String {
+    let (secs, nanos) = unix_parts(time.into());
+    match format {
+        "rfc3339" => strftime(secs, nanos, "%Y-%m-%dT%H:%M:%SZ"),
+        "unix" => secs.to_string(),
+        "unix_millis" => secs
+            .saturating_mul(1000)
+            .saturating_add(i64::from(nanos / 1_000_000))
+            .to_string(),
+        pattern => strftime(secs, nanos, pattern),
+    }
+}
+
+/// Split a time into seconds since the Unix epoch, rounded down, and nanoseconds.
+fn unix_parts(time: SystemTime) -> (i64, u32) {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => (
+            i64::try_from(since.as_secs()).unwrap_or(i64::MAX),
+            since.subsec_nanos(),
+        ),
+        Err(err) => {
+            let before = err.duration();
+            let secs = i64::try_from(before.as_secs()).map_or(i64::MIN, |secs| -secs);
+            match before.subsec_nanos() {
+                0 => (secs, 0),
+                nanos => (secs.saturating_sub(1), 1_000_000_000 - nanos),
+            }
+        }
+    }
+}
+
+/// Civil date `(year, month, day)` of a number of days since the Unix epoch.
+///
+/// See <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Format a time with a `strftime`-like pattern.
+fn strftime(secs: i64, nanos: u32, pattern: &str) -> String {
+    let (year, month, day) = civil_from_days(secs.div_euclid(86_400));
+    let seconds_of_day = secs.rem_euclid(86_400);
+    let (hour, minute, second) = (
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60,
+    );
+
+    let mut out = String::with_capacity(pattern.len() + 16);
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let _ = match chars.next() {
+            Some('Y') => write!(out, "{year:04}"),
+            Some('m') => write!(out, "{month:02}"),
+            Some('d') => write!(out, "{day:02}"),
+            Some('H') => write!(out, "{hour:02}"),
+            Some('M') => write!(out, "{minute:02}"),
+            Some('S') => write!(out, "{second:02}"),
+            Some('s') => write!(out, "{secs}"),
+            Some('z') => write!(out, "+0000"),
+            Some('f') => write!(out, "{nanos:09}"),
+            Some(digit @ ('3' | '6' | '9')) if chars.clone().next() == Some('f') => {
+                chars.next();
+                let fraction = format!("{nanos:09}");
+                let len = match digit {
+                    '3' => 3,
+                    '6' => 6,
+                    _ => 9,
+                };
+                write!(out, "{}", fraction.get(..len).unwrap_or_default())
+            }
+            Some('%') | None => write!(out, "%"),
+            Some(other) => write!(out, "%{other}"),
+        };
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn formats_named_formats() {
+        let time = UNIX_EPOCH + Duration::new(951_782_400, 5_000_000);
+        assert_eq!(format_timestamp(time, "rfc3339"), "2000-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(time, "unix"), "951782400");
+        assert_eq!(format_timestamp(time, "unix_millis"), "951782400005");
+    }
+
+    #[test]
+    fn formats_patterns() {
+        let time = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        assert_eq!(
+            format_timestamp(time, "%Y%m%d-%H%M%S.%6f%z (%s) 100%% %q"),
+            "20231114-221320.123456+0000 (1700000000) 100% %q"
+        );
+        assert_eq!(
+            format_timestamp(time, "%f|%9f|%3"),
+            "123456789|123456789|%3"
+        );
+    }
+
+    #[test]
+    fn formats_times_before_the_epoch() {
+        let time = UNIX_EPOCH - Duration::from_millis(1500);
+        assert_eq!(
+            format_timestamp(time, "%Y-%m-%dT%H:%M:%S.%3f"),
+            "1969-12-31T23:59:58.500"
+        );
+        assert_eq!(format_timestamp(time, "unix_millis"), "-1500");
+    }
+}
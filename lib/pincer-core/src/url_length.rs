@@ -0,0 +1,141 @@
+//! URL length guard.
+//!
+//! Some gateways and proxies silently truncate long query strings, so
+//! clients can refuse URLs over a limit with [`check_url_length`], or send
+//! the query of a [`PostFallback`] request as a form body instead.
+
+use http::HeaderValue;
+use http::header::CONTENT_TYPE;
+
+use crate::{Error, Method, Request, Result};
+
+/// Request extension allowing a `GET` request with a URL over the limit of
+/// [`check_url_length`] to be sent as a `POST` with its query in the body.
+///
+/// The query moves to an `application/x-www-form-urlencoded` body, and the
+/// `X-HTTP-Method-Override: GET` header tells the server the original method.
+/// Set by the generated clients on `#[get]` methods marked `#[post_fallback]`,
+/// for APIs accepting both forms, e.g. search endpoints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PostFallback;
+
+/// Check the URL of `request` is at most `max` bytes long.
+///
+/// A `GET` request without body with the [`PostFallback`] extension becomes
+/// a `POST` with its query in the body, if its URL without the query fits.
+///
+/// # Errors
+///
+/// Returns [`Error::UrlTooLong`] if the URL is over the limit.
+///
+/// # Example
+///
+/// ```
+/// use bytes::Bytes;
+/// use pincer_core::{Error, Method, PostFallback, Request, check_url_length};
+///
+/// let url = format!("https://api.example.com/search?q={}", "a".repeat(100));
+/// let request = Request::<Bytes>::builder(Method::Get, url.parse().expect("url")).build();
+/// let err = check_url_length(request.clone(), 64).expect_err("too long");
+/// assert!(matches!(err, Error::UrlTooLong { length: 133, max: 64 }));
+///
+/// let mut request = request;
+/// request.extensions_mut().insert(PostFallback);
+/// let request = check_url_length(request, 64).expect("fallback");
+/// assert_eq!(request.method(), Method::Post);
+/// assert_eq!(request.url().as_str(), "https://api.example.com/search");
+/// ```
+pub fn check_url_length(request: Request, max: usize) -> Result<Request> {
+    let length = request.url().as_str().len();
+    if length <= max {
+        return Ok(request);
+    }
+    let eligible = request.method() == Method::Get
+        && request.body().is_none()
+        && request.extensions().get::<PostFallback>().is_some();
+    let Some(query) = request.url().query().filter(|_| eligible) else {
+        return Err(Error::UrlTooLong { length, max });
+    };
+
+    let query = query.to_string();
+    let (_, mut url, mut headers, _, extensions) = request.into_parts();
+    url.set_query(None);
+    if url.as_str().len() > max {
+        return Err(Error::UrlTooLong { length, max });
+    }
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/x-www-form-urlencoded"),
+    );
+    headers.insert("x-http-method-override", HeaderValue::from_static("GET"));
+    Ok(Request::from_parts(
+        Method::Post,
+        url,
+        headers,
+        Some(query.into()),
+        extensions,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(query_length: usize) -> Request {
+        let url = format!(
+            "https://api.example.com/search?q={}",
+            "a".repeat(query_length)
+        );
+        Request::builder(Method::Get, url.parse().expect("url")).build()
+    }
+
+    #[test]
+    fn accepts_urls_within_the_limit() {
+        let request = check_url_length(search(10), 64).expect("short");
+        assert_eq!(request.method(), Method::Get);
+        assert_eq!(request.url().query(), Some("q=aaaaaaaaaa"));
+    }
+
+    #[test]
+    fn falls_back_to_post_with_a_form_body() {
+        let mut request = search(100);
+        request.extensions_mut().insert(PostFallback);
+        let request = check_url_length(request, 64).expect("fallback");
+
+        assert_eq!(request.method(), Method::Post);
+        assert_eq!(request.url().query(), None);
+        assert_eq!(
+            request.header("content-type"),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(request.header("x-http-method-override"), Some("GET"));
+        assert_eq!(
+            request.body().map(AsRef::as_ref),
+            Some(format!("q={}", "a".repeat(100)).as_bytes())
+        );
+        assert!(request.extensions().get::<PostFallback>().is_some());
+    }
+
+    #[test]
+    fn rejects_ineligible_requests() {
+        let mut request = search(100);
+        request.extensions_mut().insert(PostFallback);
+        *request.body_mut() = Some("body".into());
+        let err = check_url_length(request, 64).expect_err("with a body");
+        assert_eq!(
+            err.to_string(),
+            "URL too long (133 bytes exceeded max of 64)"
+        );
+
+        let mut request = search(100);
+        request.extensions_mut().insert(PostFallback);
+        let err = check_url_length(request, 16).expect_err("path too long");
+        assert!(matches!(
+            err,
+            Error::UrlTooLong {
+                length: 133,
+                max: 16
+            }
+        ));
+    }
+}
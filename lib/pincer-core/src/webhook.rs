@@ -0,0 +1,260 @@
+//! Inbound webhook signature verification (requires `webhook` feature).
+//!
+//! Supported providers:
+//! - GitHub: `X-Hub-Signature-256` (HMAC-SHA256 of the body)
+//! - Stripe: `Stripe-Signature` (HMAC-SHA256 of `{timestamp}.{body}`, with a
+//!   tolerance window against replays)
+//!
+//! Signatures are compared in constant time. Always verify the raw body as
+//! received, before any deserialization.
+//!
+//! # Example
+//!
+//! ```
+//! use pincer_core::webhook;
+//!
+//! let secret = "It's a Secret to Everybody";
+//! let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+//!
+//! webhook::verify_github(secret, b"Hello, World!", signature).expect("valid signature");
+//! ```
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{Error, Result};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Header carrying GitHub webhook signatures.
+pub const GITHUB_SIGNATURE_HEADER: &str = "X-Hub-Signature-256";
+
+/// Header carrying Stripe webhook signatures.
+pub const STRIPE_SIGNATURE_HEADER: &str = "Stripe-Signature";
+
+/// Default maximum age of a Stripe event, as used by Stripe libraries.
+pub const DEFAULT_STRIPE_TOLERANCE: Duration = Duration::from_mins(5);
+
+/// Verify a GitHub `X-Hub-Signature-256` header (`sha256=<hex>`).
+///
+/// # Errors
+///
+/// Returns [`Error::Signature`] if the header is malformed or the signature
+/// does not match the payload.
+pub fn verify_github(secret: impl AsRef<[u8]>, payload: &[u8], signature: &str) -> Result<()> {
+    let hex = signature
+        .trim()
+        .strip_prefix("sha256=")
+        .ok_or_else(|| Error::Signature("expected sha256=<hex> signature".to_string()))?;
+    let expected = decode_hex(hex)?;
+
+    let mut mac = new_mac(secret.as_ref())?;
+    mac.update(payload);
+    mac.verify_slice(&expected)
+        .map_err(|_| Error::Signature("signature mismatch".to_string()))
+}
+
+/// Verify a Stripe `Stripe-Signature` header (`t=<timestamp>,v1=<hex>,...`).
+///
+/// The event is rejected if its timestamp is older than `tolerance`. Any of
+/// the `v1` signatures may match, which supports secret rotation.
+///
+/// # Errors
+///
+/// Returns [`Error::Signature`] if the header is malformed, the event is too
+/// old, or no signature matches the payload.
+pub fn verify_stripe(
+    secret: impl AsRef<[u8]>,
+    payload: &[u8],
+    header: &str,
+    tolerance: Duration,
+) -> Result<()> {
+    verify_stripe_at(secret, payload, header, tolerance, SystemTime::now())
+}
+
+/// Verify a Stripe signature header against the given current time.
+///
+/// See [`verify_stripe`].
+///
+/// # Errors
+///
+/// Returns [`Error::Signature`] if the header is malformed, the event is too
+/// old, or no signature matches the payload.
+pub fn verify_stripe_at(
+    secret: impl AsRef<[u8]>,
+    payload: &[u8],
+    header: &str,
+    tolerance: Duration,
+    now: SystemTime,
+) -> Result<()> {
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for entry in header.split(',') {
+        match entry.trim().split_once('=') {
+            Some(("t", value)) => timestamp = Some(value),
+            Some(("v1", value)) => signatures.push(value),
+            _ => {}
+        }
+    }
+
+    let timestamp = timestamp.ok_or_else(|| Error::Signature("missing timestamp".to_string()))?;
+    if signatures.is_empty() {
+        return Err(Error::Signature("missing v1 signature".to_string()));
+    }
+
+    let seconds: u64 = timestamp
+        .parse()
+        .map_err(|_| Error::Signature(format!("invalid timestamp {timestamp}")))?;
+    let signed_at = UNIX_EPOCH
+        .checked_add(Duration::from_secs(seconds))
+        .ok_or_else(|| Error::Signature(format!("invalid timestamp {timestamp}")))?;
+    if now
+        .duration_since(signed_at)
+        .is_ok_and(|age| age > tolerance)
+    {
+        return Err(Error::Signature(format!(
+            "timestamp {timestamp} outside tolerance of {}s",
+            tolerance.as_secs()
+        )));
+    }
+
+    let mut mac = new_mac(secret.as_ref())?;
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+
+    let matched = signatures.into_iter().any(|signature| {
+        decode_hex(signature).is_ok_and(|expected| mac.clone().verify_slice(&expected).is_ok())
+    });
+    if matched {
+        Ok(())
+    } else {
+        Err(Error::Signature("signature mismatch".to_string()))
+    }
+}
+
+fn new_mac(secret: &[u8]) -> Result<HmacSha256> {
+    <HmacSha256 as Mac>::new_from_slice(secret)
+        .map_err(|e| Error::Signature(format!("invalid secret: {e}")))
+}
+
+/// Decode a hexadecimal string.
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    let invalid = || Error::Signature("invalid hex signature".to_string());
+    if !hex.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+
+    hex.as_bytes()
+        .chunks_exact(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use super::*;
+
+    const GITHUB_SECRET: &str = "It's a Secret to Everybody";
+    const GITHUB_SIGNATURE: &str =
+        "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+
+    fn stripe_signature(secret: &str, timestamp: u64, payload: &[u8]) -> String {
+        let mut mac = new_mac(secret.as_bytes()).expect("mac");
+        mac.update(format!("{timestamp}.").as_bytes());
+        mac.update(payload);
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .fold(String::new(), |mut hex, byte| {
+                let _ = write!(hex, "{byte:02x}");
+                hex
+            })
+    }
+
+    fn at(seconds: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn github_valid_signature() {
+        assert!(verify_github(GITHUB_SECRET, b"Hello, World!", GITHUB_SIGNATURE).is_ok());
+    }
+
+    #[test]
+    fn github_tampered_payload() {
+        let err =
+            verify_github(GITHUB_SECRET, b"Hello, World?", GITHUB_SIGNATURE).expect_err("mismatch");
+        assert!(err.is_signature());
+    }
+
+    #[test]
+    fn github_malformed_header() {
+        assert!(verify_github(GITHUB_SECRET, b"", "sha1=abcd").is_err());
+        assert!(verify_github(GITHUB_SECRET, b"", "sha256=xyz").is_err());
+    }
+
+    #[test]
+    fn stripe_valid_signature() {
+        let signature = stripe_signature("whsec", 1_000, b"{}");
+        let header = format!("t=1000,v1={signature},v0=ignored");
+
+        let result = verify_stripe_at("whsec", b"{}", &header, DEFAULT_STRIPE_TOLERANCE, at(1_060));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn stripe_rotated_secret() {
+        let signature = stripe_signature("new", 1_000, b"{}");
+        let header = format!("t=1000,v1=00ff,v1={signature}");
+
+        let result = verify_stripe_at("new", b"{}", &header, DEFAULT_STRIPE_TOLERANCE, at(1_000));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn stripe_outside_tolerance() {
+        let signature = stripe_signature("whsec", 1_000, b"{}");
+        let header = format!("t=1000,v1={signature}");
+
+        let err = verify_stripe_at("whsec", b"{}", &header, DEFAULT_STRIPE_TOLERANCE, at(1_301))
+            .expect_err("too old");
+        assert!(err.to_string().contains("tolerance"));
+    }
+
+    #[test]
+    fn stripe_wrong_secret() {
+        let signature = stripe_signature("other", 1_000, b"{}");
+        let header = format!("t=1000,v1={signature}");
+
+        let err = verify_stripe_at("whsec", b"{}", &header, DEFAULT_STRIPE_TOLERANCE, at(1_000))
+            .expect_err("mismatch");
+        assert!(err.is_signature());
+    }
+
+    #[test]
+    fn stripe_missing_parts() {
+        let tolerance = DEFAULT_STRIPE_TOLERANCE;
+        assert!(verify_stripe_at("s", b"", "v1=00", tolerance, at(0)).is_err());
+        assert!(verify_stripe_at("s", b"", "t=0", tolerance, at(0)).is_err());
+    }
+
+    #[test]
+    fn stripe_timestamp_overflow() {
+        let header = "t=18446744073709551615,v1=00";
+
+        let err = verify_stripe_at("s", b"", header, DEFAULT_STRIPE_TOLERANCE, at(0))
+            .expect_err("overflow");
+        assert!(err.is_signature());
+        assert!(err.to_string().contains("invalid timestamp"));
+    }
+}
@@ -1,5 +1,7 @@
 //! Attribute parsing for pincer proc-macros.
 
+use proc_macro2::TokenStream;
+use quote::quote;
 use syn::{Ident, Type};
 
 /// HTTP method for a request.
@@ -67,6 +69,11 @@ pub(crate) enum PincerMode {
     /// Impl-only mode: only generates the trait implementation for any type
     /// implementing `PincerClient`. No structs are generated.
     ImplOnly,
+
+    /// Impl-for mode: like impl-only, but implements the trait only for the
+    /// listed types (`impl_for(MyClient, ApiClient<HyperClient>)`), so several
+    /// such traits with the same method names do not collide.
+    ImplFor,
 }
 
 impl PincerMode {
@@ -110,16 +117,18 @@ pub(crate) enum CollectionFormat {
     Ssv,
     /// Pipe-separated: `?tags=a|b|c`
     Pipes,
+    /// Bracketed object fields: `?filter[status]=active&filter[type]=user`
+    DeepObject,
 }
 
 impl CollectionFormat {
     /// Get the separator string for this format.
     ///
-    /// Returns `None` for `Multi` format (uses repeated parameters).
+    /// Returns `None` for `Multi` and `DeepObject` formats (use repeated parameters).
     #[must_use]
     pub(crate) const fn separator(self) -> Option<&'static str> {
         match self {
-            Self::Multi => None,
+            Self::Multi | Self::DeepObject => None,
             Self::Csv => Some(","),
             Self::Ssv => Some(" "),
             Self::Pipes => Some("|"),
@@ -134,6 +143,19 @@ pub(crate) struct QueryOptions {
     pub(crate) alias: Option<String>,
     /// Collection format for Vec<T> parameters.
     pub(crate) format: CollectionFormat,
+    /// Timestamp format of the value (or items), e.g. `format = "rfc3339"`.
+    pub(crate) time_format: Option<String>,
+}
+
+/// Path parameter options.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct PathOptions {
+    /// Optional alias for the path placeholder name.
+    pub(crate) alias: Option<String>,
+    /// Timestamp format of the value, e.g. `format = "unix"`.
+    pub(crate) time_format: Option<String>,
+    /// Insert the value verbatim, already percent-encoded, from `raw`.
+    pub(crate) raw: bool,
 }
 
 /// Multipart parameter options.
@@ -143,20 +165,50 @@ pub(crate) struct MultipartOptions {
     pub(crate) name: Option<String>,
 }
 
+/// Body parameter options.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct BodyOptions {
+    /// Function serializing the value, as in serde's `serialize_with`.
+    pub(crate) serialize_with: Option<syn::Path>,
+    /// Serialization format, from `format = "..."`.
+    pub(crate) format: BodyFormat,
+}
+
+/// Format of a serialized body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum BodyFormat {
+    /// JSON, with the API's codec.
+    #[default]
+    Json,
+    /// XML, with the API's codec, from `format = "xml"`.
+    Xml,
+    /// CBOR, with the API's codec, from `format = "cbor"`.
+    Cbor,
+}
+
 /// Parameter kind for method arguments.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum ParamKind {
-    /// Path parameter (e.g., `#[path]` or `#[path("user_id")]`).
-    Path(Option<String>),
+    /// Path parameter (e.g., `#[path]`, `#[path("user_id")]` or `#[path(format = "unix")]`).
+    Path(PathOptions),
     /// Query parameter (e.g., `#[query]`, `#[query("page_size")]`, `#[query(format = "csv")]`).
     Query(QueryOptions),
     /// Header parameter (e.g., `#[header("Authorization")]`).
     Header(String),
+    /// Cookie parameter (e.g., `#[cookie("session_id")]`), merged into the `Cookie` header.
+    Cookie(String),
+    /// `Accept-Language` fallback chain (e.g., `#[accept_language]`).
+    /// Expects a type convertible into `AcceptLanguage` (a tag, a header value or a list of tags).
+    AcceptLanguage,
+    /// `If-Match` entity tag (e.g., `#[if_match]`).
+    /// Expects an `EntityTag` or `&EntityTag`.
+    IfMatch,
     /// Header map for dynamic headers (e.g., `#[headers]`).
     /// Expects a type implementing `IntoIterator<Item = (K, V)>` where K and V are string-like.
     Headers,
-    /// JSON body (e.g., `#[body]`).
-    Body,
+    /// JSON, XML or CBOR body (e.g., `#[body]`, `#[body(format = "xml")]` or
+    /// `#[body(serialize_with = "path")]`).
+    Body(BodyOptions),
     /// Form body (e.g., `#[form]`).
     Form,
     /// Multipart form part (e.g., `#[multipart]` or `#[multipart(name = "file")]`).
@@ -179,6 +231,7 @@ pub(crate) struct MethodParam {
 ///
 /// These options modify how the generated method behaves.
 #[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
 pub(crate) struct MethodOptions {
     /// Treat 404 responses as `None` instead of an error.
     ///
@@ -186,17 +239,243 @@ pub(crate) struct MethodOptions {
     /// and a 404 status code will return `Ok(None)` rather than an error.
     pub(crate) not_found_as_none: bool,
 
+    /// Check a resource exists, from `#[exists]` on a `HEAD` method.
+    ///
+    /// The method returns `Result<bool>`: `true` on 2xx, `false` on 404,
+    /// and an error for any other status.
+    pub(crate) exists: bool,
+
+    /// Send the request as a `POST` with its query in a form body when its
+    /// URL is too long, from `#[post_fallback]` on a `GET` method.
+    pub(crate) post_fallback: bool,
+
     /// Per-method timeout override.
     ///
     /// When set, overrides the client's default timeout for this specific method.
     pub(crate) timeout: Option<std::time::Duration>,
+
+    /// Maximum length of a successful response body, in bytes, from
+    /// `#[max_body(1048576)]`.
+    pub(crate) max_body: Option<u64>,
+
+    /// The method's `#[deprecated]` attribute, if any.
+    ///
+    /// Forwarded to the clean trait and recorded in the method metadata.
+    pub(crate) deprecated: Option<Deprecated>,
+
+    /// Format of the response body, from `#[response(...)]`, or XML with an
+    /// XML `#[accept(...)]`.
+    pub(crate) response_format: ResponseFormat,
+
+    /// Fail on unknown fields of a JSON response, from
+    /// `#[response(deny_unknown_fields)]`.
+    pub(crate) deny_unknown_fields: bool,
+
+    /// JSON pointer of the deserialized value in a JSON response, from
+    /// `#[response(pointer = "/data/items")]`.
+    pub(crate) pointer: Option<String>,
+
+    /// `Accept` header, from `#[accept("application/xml")]`.
+    pub(crate) accept: Option<String>,
+}
+
+impl MethodOptions {
+    /// Get the `Accept` header of the requests.
+    pub(crate) fn accept(&self) -> &str {
+        match (&self.accept, self.response_format) {
+            (Some(accept), _) => accept,
+            (None, ResponseFormat::Xml) => "application/xml",
+            (None, ResponseFormat::Cbor) => "application/cbor",
+            (None, ResponseFormat::Yaml) => "application/yaml",
+            (None, ResponseFormat::Toml) => "application/toml",
+            (None, _) => "application/json",
+        }
+    }
+}
+
+/// Format of a deserialized response body.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum ResponseFormat {
+    /// JSON, with the API's codec.
+    #[default]
+    Json,
+    /// CSV records separated by `delimiter`, from `#[response(csv)]` (`csv`
+    /// feature of pincer).
+    Csv {
+        /// Field delimiter (default: `,`).
+        delimiter: char,
+    },
+    /// XML, with the API's codec, from `#[response(xml)]`.
+    Xml,
+    /// CBOR, with the API's codec, from `#[response(cbor)]`.
+    Cbor,
+    /// YAML, from `#[response(yaml)]` (`yaml` feature of pincer).
+    Yaml,
+    /// TOML, from `#[response(toml)]` (`toml` feature of pincer).
+    Toml,
+}
+
+impl MethodOptions {
+    /// Parse `#[response(csv)]`, `#[response(csv, delimiter = ';')]`,
+    /// `#[response(xml)]`, `#[response(cbor)]`, `#[response(yaml)]`,
+    /// `#[response(toml)]`, or JSON options
+    /// `#[response(deny_unknown_fields, pointer = "/data")]`.
+    fn parse_response(&mut self, attr: &syn::Attribute) -> syn::Result<()> {
+        let mut formats = Vec::new();
+        let mut delimiter = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("csv") {
+                formats.push(ResponseFormat::Csv { delimiter: ',' });
+                Ok(())
+            } else if meta.path.is_ident("xml") {
+                formats.push(ResponseFormat::Xml);
+                Ok(())
+            } else if meta.path.is_ident("cbor") {
+                formats.push(ResponseFormat::Cbor);
+                Ok(())
+            } else if meta.path.is_ident("yaml") {
+                formats.push(ResponseFormat::Yaml);
+                Ok(())
+            } else if meta.path.is_ident("toml") {
+                formats.push(ResponseFormat::Toml);
+                Ok(())
+            } else if meta.path.is_ident("json") {
+                Ok(())
+            } else if meta.path.is_ident("deny_unknown_fields") {
+                self.deny_unknown_fields = true;
+                Ok(())
+            } else if meta.path.is_ident("pointer") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                let pointer = value.value();
+                if !pointer.is_empty() && !pointer.starts_with('/') {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "a JSON pointer is empty or starts with `/`, e.g. \"/data/items\"",
+                    ));
+                }
+                self.pointer = Some(pointer);
+                Ok(())
+            } else if meta.path.is_ident("delimiter") {
+                let value: syn::LitChar = meta.value()?.parse()?;
+                delimiter = Some(value.value());
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unknown response option, expected `csv`, `json`, `xml`, `cbor`, `yaml`, `toml`, `delimiter`, `deny_unknown_fields` or `pointer`",
+                ))
+            }
+        })?;
+
+        self.response_format = match (formats.as_slice(), delimiter) {
+            ([_, _, ..], _) => Err(syn::Error::new_spanned(
+                attr,
+                "`csv`, `xml`, `cbor`, `yaml` and `toml` are mutually exclusive",
+            )),
+            ([ResponseFormat::Csv { .. }], delimiter) => Ok(ResponseFormat::Csv {
+                delimiter: delimiter.unwrap_or(','),
+            }),
+            ([format], None) => Ok(*format),
+            ([], None) => Ok(ResponseFormat::Json),
+            (_, Some(_)) => Err(syn::Error::new_spanned(
+                attr,
+                "`delimiter` is only supported with `csv`",
+            )),
+        }?;
+        if (self.deny_unknown_fields || self.pointer.is_some())
+            && self.response_format != ResponseFormat::Json
+        {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "`deny_unknown_fields` and `pointer` are only supported with JSON responses",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A parsed `#[deprecated]` attribute.
+#[derive(Debug, Clone)]
+pub(crate) struct Deprecated {
+    /// The attribute itself, to forward it.
+    pub(crate) attr: syn::Attribute,
+    /// The `since = "..."` value.
+    pub(crate) since: Option<String>,
+    /// The `note = "..."` value, or the value of `#[deprecated = "..."]`.
+    pub(crate) note: Option<String>,
+}
+
+impl Deprecated {
+    /// Parse `#[deprecated]`, `#[deprecated = "note"]` or
+    /// `#[deprecated(since = "...", note = "...")]`.
+    fn parse(attr: &syn::Attribute) -> syn::Result<Self> {
+        let mut deprecated = Self {
+            attr: attr.clone(),
+            since: None,
+            note: None,
+        };
+        match &attr.meta {
+            syn::Meta::Path(_) => {}
+            syn::Meta::NameValue(name_value) => {
+                let syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(note),
+                    ..
+                }) = &name_value.value
+                else {
+                    return Err(syn::Error::new_spanned(
+                        &name_value.value,
+                        "expected a string literal",
+                    ));
+                };
+                deprecated.note = Some(note.value());
+            }
+            syn::Meta::List(_) => attr.parse_nested_meta(|meta| {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if meta.path.is_ident("since") {
+                    deprecated.since = Some(value.value());
+                } else if meta.path.is_ident("note") {
+                    deprecated.note = Some(value.value());
+                } else {
+                    return Err(meta.error("expected `since` or `note`"));
+                }
+                Ok(())
+            })?,
+        }
+        Ok(deprecated)
+    }
+
+    /// Generate the `pincer::Deprecation` value of the method metadata.
+    pub(crate) fn metadata(&self) -> TokenStream {
+        let since = option_tokens(self.since.as_deref());
+        let note = option_tokens(self.note.as_deref());
+        quote! {
+            ::pincer::Deprecation {
+                since: #since,
+                note: #note,
+            }
+        }
+    }
+}
+
+fn option_tokens(value: Option<&str>) -> TokenStream {
+    value.map_or_else(|| quote! { None }, |value| quote! { Some(#value) })
 }
 
 /// Parse method-level options from attributes.
 ///
 /// Recognized attributes:
 /// - `#[not_found_as_none]` - Treat 404 as None
+/// - `#[exists]` - Return whether the resource exists (`HEAD` only)
+/// - `#[post_fallback]` - Send a too long `GET` as a `POST` with a form body (`GET` only)
 /// - `#[timeout("30s")]` or `#[timeout(secs = 30)]` - Per-method timeout
+/// - `#[max_body(1048576)]` - Maximum length of a successful response body, in bytes
+/// - `#[deprecated(...)]` - Standard deprecation, forwarded and recorded
+/// - `#[response(csv)]` or `#[response(csv, delimiter = ';')]` - CSV records
+/// - `#[response(xml)]` - XML response, with the API's XML codec
+/// - `#[response(cbor)]` - CBOR response, with the API's CBOR codec
+/// - `#[response(deny_unknown_fields)]` - JSON response failing on unknown fields
+/// - `#[response(pointer = "/data/items")]` - Value at a JSON pointer of the response
+/// - `#[accept("application/xml")]` - `Accept` header, reading XML (or CBOR)
+///   responses if it is an XML (or CBOR) media type
 pub(crate) fn parse_method_options(attrs: &[syn::Attribute]) -> syn::Result<MethodOptions> {
     let mut options = MethodOptions::default();
 
@@ -207,11 +486,47 @@ pub(crate) fn parse_method_options(attrs: &[syn::Attribute]) -> syn::Result<Meth
             options.not_found_as_none = true;
         }
 
+        if path.is_ident("exists") {
+            options.exists = true;
+        }
+
+        if path.is_ident("post_fallback") {
+            options.post_fallback = true;
+        }
+
         if path.is_ident("timeout")
             && let Some(duration) = parse_duration_attr(attr)?
         {
             options.timeout = Some(duration);
         }
+
+        if path.is_ident("max_body") {
+            let limit: syn::LitInt = attr.parse_args()?;
+            options.max_body = Some(limit.base10_parse()?);
+        }
+
+        if path.is_ident("deprecated") {
+            options.deprecated = Some(Deprecated::parse(attr)?);
+        }
+
+        if path.is_ident("response") {
+            options.parse_response(attr)?;
+        }
+
+        if path.is_ident("accept") {
+            let accept: syn::LitStr = attr.parse_args()?;
+            options.accept = Some(accept.value());
+        }
+    }
+
+    if let Some(accept) = options.accept.as_deref()
+        && options.response_format == ResponseFormat::Json
+    {
+        if accept.contains("/xml") || accept.contains("+xml") {
+            options.response_format = ResponseFormat::Xml;
+        } else if accept.contains("/cbor") || accept.contains("+cbor") {
+            options.response_format = ResponseFormat::Cbor;
+        }
     }
 
     Ok(options)
@@ -288,42 +603,97 @@ fn parse_duration_string(s: &str) -> Option<std::time::Duration> {
 }
 
 /// Parse a parameter attribute and return its kind.
-pub(crate) fn parse_param_attr(attr: &syn::Attribute) -> Option<ParamKind> {
+///
+/// Returns `Ok(None)` if the attribute is not a parameter attribute.
+pub(crate) fn parse_param_attr(attr: &syn::Attribute) -> syn::Result<Option<ParamKind>> {
     let path = attr.path();
 
     if path.is_ident("path") {
-        let name = parse_optional_string_arg(attr);
-        return Some(ParamKind::Path(name));
+        let options = parse_path_options(attr)?;
+        return Ok(Some(ParamKind::Path(options)));
     }
 
     if path.is_ident("query") {
-        let options = parse_query_options(attr);
-        return Some(ParamKind::Query(options));
+        let options = parse_query_options(attr)?;
+        return Ok(Some(ParamKind::Query(options)));
     }
 
     if path.is_ident("header") {
-        let name = parse_required_string_arg(attr)?;
-        return Some(ParamKind::Header(name));
+        let Some(name) = parse_required_string_arg(attr) else {
+            return Ok(None);
+        };
+        return Ok(Some(ParamKind::Header(name)));
+    }
+
+    if path.is_ident("cookie") {
+        let Some(name) = parse_required_string_arg(attr) else {
+            return Ok(None);
+        };
+        return Ok(Some(ParamKind::Cookie(name)));
     }
 
     if path.is_ident("body") {
-        return Some(ParamKind::Body);
+        let options = parse_body_options(attr)?;
+        return Ok(Some(ParamKind::Body(options)));
     }
 
     if path.is_ident("form") {
-        return Some(ParamKind::Form);
+        return Ok(Some(ParamKind::Form));
     }
 
     if path.is_ident("headers") {
-        return Some(ParamKind::Headers);
+        return Ok(Some(ParamKind::Headers));
+    }
+
+    if path.is_ident("accept_language") {
+        return Ok(Some(ParamKind::AcceptLanguage));
+    }
+
+    if path.is_ident("if_match") {
+        return Ok(Some(ParamKind::IfMatch));
     }
 
     if path.is_ident("multipart") {
         let options = parse_multipart_options(attr);
-        return Some(ParamKind::Multipart(options));
+        return Ok(Some(ParamKind::Multipart(options)));
     }
 
-    None
+    Ok(None)
+}
+
+/// Parse body parameter options from `#[body]`, `#[body(format = "xml")]`,
+/// `#[body(format = "cbor")]` or `#[body(serialize_with = "path")]`.
+fn parse_body_options(attr: &syn::Attribute) -> syn::Result<BodyOptions> {
+    let mut options = BodyOptions::default();
+
+    if let syn::Meta::List(_) = &attr.meta {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("serialize_with") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                options.serialize_with = Some(value.parse()?);
+            } else if meta.path.is_ident("format") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                options.format = match value.value().as_str() {
+                    "json" => BodyFormat::Json,
+                    "xml" => BodyFormat::Xml,
+                    "cbor" => BodyFormat::Cbor,
+                    other => {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            format!(
+                                "unknown body format: \"{other}\". Expected: \"json\", \"xml\", or \"cbor\""
+                            ),
+                        ));
+                    }
+                };
+            } else {
+                return Err(meta.error("unknown body option. Expected: `format` or `serialize_with`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
 }
 
 /// Parse multipart parameter options from `#[multipart]` or `#[multipart(name = "file")]`.
@@ -351,41 +721,109 @@ fn parse_multipart_options(attr: &syn::Attribute) -> MultipartOptions {
     options
 }
 
+/// Parse path parameter options from `#[path]`, `#[path("alias")]`,
+/// `#[path(format = "unix")]`, `#[path(raw)]`, or an alias followed by
+/// options: `#[path("object_id", raw)]`.
+fn parse_path_options(attr: &syn::Attribute) -> syn::Result<PathOptions> {
+    let mut options = PathOptions::default();
+    if !matches!(attr.meta, syn::Meta::List(_)) {
+        return Ok(options);
+    }
+
+    attr.parse_args_with(|input: syn::parse::ParseStream<'_>| {
+        if input.peek(syn::LitStr) {
+            let alias: syn::LitStr = input.parse()?;
+            options.alias = Some(alias.value());
+            if input.is_empty() {
+                return Ok(());
+            }
+            input.parse::<syn::Token![,]>()?;
+        }
+        let parser = syn::meta::parser(|meta| {
+            if meta.path.is_ident("format") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if !is_timestamp_format(&value.value()) {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        format!(
+                            "unknown path format \"{}\": expected rfc3339, unix, unix_millis \
+                             or a strftime-like pattern",
+                            value.value()
+                        ),
+                    ));
+                }
+                options.time_format = Some(value.value());
+                Ok(())
+            } else if meta.path.is_ident("raw") {
+                options.raw = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown path option: expected `format = \"...\"` or `raw`"))
+            }
+        });
+        syn::parse::Parser::parse2(parser, input.parse()?)
+    })?;
+
+    Ok(options)
+}
+
 /// Parse query parameter options from `#[query]`, `#[query("alias")]`, or `#[query(format = "csv")]`.
-fn parse_query_options(attr: &syn::Attribute) -> QueryOptions {
+fn parse_query_options(attr: &syn::Attribute) -> syn::Result<QueryOptions> {
     let mut options = QueryOptions::default();
 
     if let syn::Meta::List(meta_list) = &attr.meta {
         // Try parsing as a simple string literal first (alias)
         if let Ok(str_lit) = syn::parse2::<syn::LitStr>(meta_list.tokens.clone()) {
             options.alias = Some(str_lit.value());
-            return options;
+            return Ok(options);
         }
 
         // Try parsing as key-value pairs (format = "csv", etc.)
-        let _ = attr.parse_nested_meta(|meta| {
+        attr.parse_nested_meta(|meta| {
             if meta.path.is_ident("format") {
                 let value: syn::LitStr = meta.value()?.parse()?;
-                options.format = parse_collection_format(&value.value());
+                let format = value.value();
+                if is_timestamp_format(&format) {
+                    options.time_format = Some(format);
+                } else {
+                    options.format = parse_collection_format(&format).ok_or_else(|| {
+                        syn::Error::new(
+                            value.span(),
+                            format!(
+                                "unknown query format \"{format}\": expected csv, ssv, pipes, \
+                                 multi, deep_object, rfc3339, unix, unix_millis \
+                                 or a strftime-like pattern"
+                            ),
+                        )
+                    })?;
+                }
             } else if let Some(ident) = meta.path.get_ident() {
                 // Could be an alias specified as an identifier
                 options.alias = Some(ident.to_string());
             }
             Ok(())
-        });
+        })?;
     }
     // Other cases (Path, NameValue) - no options to parse
 
-    options
+    Ok(options)
 }
 
-/// Parse a collection format string.
-fn parse_collection_format(s: &str) -> CollectionFormat {
+/// Check if a `format` value is a timestamp format of `pincer::format_timestamp`:
+/// `rfc3339`, `unix`, `unix_millis` or a `strftime`-like pattern.
+pub(crate) fn is_timestamp_format(format: &str) -> bool {
+    matches!(format, "rfc3339" | "unix" | "unix_millis") || format.contains('%')
+}
+
+/// Parse a collection format string, `None` if unknown.
+fn parse_collection_format(s: &str) -> Option<CollectionFormat> {
     match s.to_lowercase().as_str() {
-        "csv" | "comma" => CollectionFormat::Csv,
-        "ssv" | "space" => CollectionFormat::Ssv,
-        "pipes" | "pipe" => CollectionFormat::Pipes,
-        _ => CollectionFormat::Multi, // default
+        "csv" | "comma" => Some(CollectionFormat::Csv),
+        "ssv" | "space" => Some(CollectionFormat::Ssv),
+        "pipes" | "pipe" => Some(CollectionFormat::Pipes),
+        "multi" => Some(CollectionFormat::Multi),
+        "deep_object" | "deepobject" => Some(CollectionFormat::DeepObject),
+        _ => None,
     }
 }
 
@@ -409,27 +847,29 @@ fn parse_required_string_arg(attr: &syn::Attribute) -> Option<String> {
 
 /// Extract placeholder names from a URL path template.
 ///
-/// E.g., `/users/{id}/posts/{post_id}` returns `["id", "post_id"]`
+/// E.g., `/users/{id}/posts/{post_id}` returns `["id", "post_id"]`, and the
+/// variables of URI template expressions are included: `/files{/segments*}{?q}`
+/// returns `["segments", "q"]`.
 #[must_use]
 pub(crate) fn extract_path_placeholders(path: &str) -> Vec<String> {
-    let mut placeholders = Vec::new();
-    let mut chars = path.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '{' {
-            let mut name = String::new();
-            for next in chars.by_ref() {
-                if next == '}' {
-                    break;
-                }
-                name.push(next);
-            }
-            if !name.is_empty() {
-                placeholders.push(name);
-            }
+    crate::uri_template::variable_names(path)
+}
+
+/// Parse `#[pincer_client_attrs(...)]` on a trait.
+///
+/// Each item is an attribute applied to the generated client and builder
+/// structs, e.g. `#[pincer_client_attrs(derive(Debug), must_use)]`.
+pub(crate) fn parse_client_attrs(attrs: &[syn::Attribute]) -> syn::Result<Vec<syn::Meta>> {
+    let mut metas = Vec::new();
+    for attr in attrs {
+        if attr.path().is_ident("pincer_client_attrs") {
+            let parsed = attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+            )?;
+            metas.extend(parsed);
         }
     }
-    placeholders
+    Ok(metas)
 }
 
 /// Parse trait-level headers from a `#[headers(...)]` attribute.
@@ -483,6 +923,301 @@ mod tests {
         assert_eq!(HttpMethod::Delete.as_str(), "Delete");
     }
 
+    #[test]
+    fn parse_client_attrs_list() {
+        let attrs: Vec<syn::Attribute> = vec![
+            syn::parse_quote! { #[doc = " API"] },
+            syn::parse_quote! { #[pincer_client_attrs(derive(Debug), must_use)] },
+            syn::parse_quote! { #[pincer_client_attrs(non_exhaustive)] },
+        ];
+        let metas = parse_client_attrs(&attrs).expect("parse");
+        assert_eq!(metas.len(), 3);
+        assert!(
+            metas
+                .get(2)
+                .is_some_and(|meta| meta.path().is_ident("non_exhaustive"))
+        );
+
+        let invalid: syn::Attribute = syn::parse_quote! { #[pincer_client_attrs(= 1)] };
+        assert!(parse_client_attrs(&[invalid]).is_err());
+    }
+
+    #[test]
+    fn parse_deprecated_forms() {
+        let parse = |attr: syn::Attribute| {
+            parse_method_options(&[attr])
+                .expect("parse")
+                .deprecated
+                .expect("deprecated")
+        };
+
+        let bare = parse(syn::parse_quote! { #[deprecated] });
+        assert_eq!((bare.since, bare.note), (None, None));
+
+        let note = parse(syn::parse_quote! { #[deprecated = "use v2"] });
+        assert_eq!(note.note.as_deref(), Some("use v2"));
+
+        let full = parse(syn::parse_quote! { #[deprecated(since = "1.2", note = "use v2")] });
+        assert_eq!(full.since.as_deref(), Some("1.2"));
+        assert_eq!(full.note.as_deref(), Some("use v2"));
+
+        let attr: syn::Attribute = syn::parse_quote! { #[deprecated(reason = "x")] };
+        assert!(parse_method_options(&[attr]).is_err());
+    }
+
+    #[test]
+    fn parse_response_format() {
+        let parse = |attr: syn::Attribute| parse_method_options(&[attr]).map(|o| o.response_format);
+
+        assert_eq!(
+            parse(syn::parse_quote! { #[response(csv)] }).expect("csv"),
+            ResponseFormat::Csv { delimiter: ',' }
+        );
+        assert_eq!(
+            parse(syn::parse_quote! { #[response(csv, delimiter = ';')] }).expect("csv"),
+            ResponseFormat::Csv { delimiter: ';' }
+        );
+        assert_eq!(
+            parse(syn::parse_quote! { #[response(json)] }).expect("json"),
+            ResponseFormat::Json
+        );
+        assert!(parse(syn::parse_quote! { #[response(json, delimiter = ';')] }).is_err());
+        assert_eq!(
+            parse(syn::parse_quote! { #[response(xml)] }).expect("xml"),
+            ResponseFormat::Xml
+        );
+        assert_eq!(
+            parse(syn::parse_quote! { #[accept("application/soap+xml")] }).expect("xml"),
+            ResponseFormat::Xml
+        );
+        assert_eq!(
+            parse(syn::parse_quote! { #[response(cbor)] }).expect("cbor"),
+            ResponseFormat::Cbor
+        );
+        assert_eq!(
+            parse(syn::parse_quote! { #[accept("application/cbor")] }).expect("cbor"),
+            ResponseFormat::Cbor
+        );
+        assert!(parse(syn::parse_quote! { #[response(csv, xml)] }).is_err());
+        assert!(parse(syn::parse_quote! { #[response(xml, cbor)] }).is_err());
+        assert!(parse(syn::parse_quote! { #[response(cbor, delimiter = ';')] }).is_err());
+        assert!(parse(syn::parse_quote! { #[response(protobuf)] }).is_err());
+        assert!(parse(syn::parse_quote! { #[response(xml, deny_unknown_fields)] }).is_err());
+    }
+
+    #[test]
+    fn parse_deny_unknown_fields() {
+        let options =
+            parse_method_options(&[syn::parse_quote! { #[response(json, deny_unknown_fields)] }])
+                .expect("strict");
+        assert!(options.deny_unknown_fields);
+        assert_eq!(options.response_format, ResponseFormat::Json);
+        let options =
+            parse_method_options(&[syn::parse_quote! { #[response(json)] }]).expect("lenient");
+        assert!(!options.deny_unknown_fields);
+    }
+
+    #[test]
+    fn parse_json_pointer() {
+        let options =
+            parse_method_options(&[syn::parse_quote! { #[response(pointer = "/data/items")] }])
+                .expect("pointer");
+        assert_eq!(options.pointer.as_deref(), Some("/data/items"));
+        assert!(
+            parse_method_options(&[syn::parse_quote! { #[response(pointer = "data")] }]).is_err()
+        );
+        assert!(
+            parse_method_options(&[syn::parse_quote! { #[response(csv, pointer = "/data")] }])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_accept_and_xml_body() {
+        let options =
+            parse_method_options(&[syn::parse_quote! { #[accept("text/xml")] }]).expect("accept");
+        assert_eq!(options.accept(), "text/xml");
+        let options = parse_method_options(&[syn::parse_quote! { #[response(xml)] }]).expect("xml");
+        assert_eq!(options.accept(), "application/xml");
+
+        let attr: syn::Attribute = syn::parse_quote! { #[body(format = "xml")] };
+        assert_eq!(
+            parse_param_attr(&attr).expect("parse"),
+            Some(ParamKind::Body(BodyOptions {
+                serialize_with: None,
+                format: BodyFormat::Xml,
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_cbor_accept_and_body() {
+        let options =
+            parse_method_options(&[syn::parse_quote! { #[response(cbor)] }]).expect("cbor");
+        assert_eq!(options.accept(), "application/cbor");
+
+        let attr: syn::Attribute = syn::parse_quote! { #[body(format = "cbor")] };
+        assert_eq!(
+            parse_param_attr(&attr).expect("parse"),
+            Some(ParamKind::Body(BodyOptions {
+                serialize_with: None,
+                format: BodyFormat::Cbor,
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_yaml_and_toml_responses() {
+        let options =
+            parse_method_options(&[syn::parse_quote! { #[response(yaml)] }]).expect("yaml");
+        assert_eq!(options.response_format, ResponseFormat::Yaml);
+        assert_eq!(options.accept(), "application/yaml");
+        let options =
+            parse_method_options(&[syn::parse_quote! { #[response(toml)] }]).expect("toml");
+        assert_eq!(options.response_format, ResponseFormat::Toml);
+        assert_eq!(options.accept(), "application/toml");
+
+        assert!(parse_method_options(&[syn::parse_quote! { #[response(yaml, toml)] }]).is_err());
+        assert!(
+            parse_method_options(&[syn::parse_quote! { #[response(yaml, deny_unknown_fields)] }])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_timestamp_formats() {
+        let attr: syn::Attribute = syn::parse_quote! { #[query(format = "rfc3339")] };
+        assert_eq!(
+            parse_param_attr(&attr).expect("parse"),
+            Some(ParamKind::Query(QueryOptions {
+                alias: None,
+                format: CollectionFormat::Multi,
+                time_format: Some("rfc3339".to_string()),
+            }))
+        );
+
+        let attr: syn::Attribute = syn::parse_quote! { #[path(format = "%Y-%m-%d")] };
+        assert_eq!(
+            parse_param_attr(&attr).expect("parse"),
+            Some(ParamKind::Path(PathOptions {
+                alias: None,
+                time_format: Some("%Y-%m-%d".to_string()),
+                raw: false,
+            }))
+        );
+
+        let attr: syn::Attribute = syn::parse_quote! { #[path("day")] };
+        assert_eq!(
+            parse_param_attr(&attr).expect("parse"),
+            Some(ParamKind::Path(PathOptions {
+                alias: Some("day".to_string()),
+                time_format: None,
+                raw: false,
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_unknown_formats() {
+        let attr: syn::Attribute = syn::parse_quote! { #[query(format = "cvs")] };
+        let err = parse_param_attr(&attr).expect_err("unknown query format");
+        assert!(
+            err.to_string().contains("unknown query format \"cvs\""),
+            "{err}"
+        );
+
+        let attr: syn::Attribute = syn::parse_quote! { #[path(format = "rfc3999")] };
+        let err = parse_param_attr(&attr).expect_err("unknown path format");
+        assert!(
+            err.to_string().contains("unknown path format \"rfc3999\""),
+            "{err}"
+        );
+
+        let attr: syn::Attribute = syn::parse_quote! { #[query(format = "multi")] };
+        assert!(parse_param_attr(&attr).is_ok());
+    }
+
+    #[test]
+    fn parse_raw_path_param() {
+        let attr: syn::Attribute = syn::parse_quote! { #[path(raw)] };
+        assert_eq!(
+            parse_param_attr(&attr).expect("parse"),
+            Some(ParamKind::Path(PathOptions {
+                alias: None,
+                time_format: None,
+                raw: true,
+            }))
+        );
+
+        let attr: syn::Attribute = syn::parse_quote! { #[path("object_id", raw)] };
+        assert_eq!(
+            parse_param_attr(&attr).expect("parse"),
+            Some(ParamKind::Path(PathOptions {
+                alias: Some("object_id".to_string()),
+                time_format: None,
+                raw: true,
+            }))
+        );
+
+        let attr: syn::Attribute = syn::parse_quote! { #[path(rwa)] };
+        let err = parse_param_attr(&attr).expect_err("typo");
+        assert!(err.to_string().contains("unknown path option"), "{err}");
+    }
+
+    #[test]
+    fn parse_exists_option() {
+        let attr: syn::Attribute = syn::parse_quote! { #[exists] };
+        assert!(parse_method_options(&[attr]).expect("parse").exists);
+        assert!(!parse_method_options(&[]).expect("parse").exists);
+    }
+
+    #[test]
+    fn parse_max_body_option() {
+        let attr: syn::Attribute = syn::parse_quote! { #[max_body(1_048_576)] };
+        assert_eq!(
+            parse_method_options(&[attr]).expect("parse").max_body,
+            Some(1_048_576)
+        );
+        assert_eq!(parse_method_options(&[]).expect("parse").max_body, None);
+
+        let attr: syn::Attribute = syn::parse_quote! { #[max_body("1MiB")] };
+        assert!(parse_method_options(&[attr]).is_err());
+    }
+
+    #[test]
+    fn parse_post_fallback_option() {
+        let attr: syn::Attribute = syn::parse_quote! { #[post_fallback] };
+        assert!(parse_method_options(&[attr]).expect("parse").post_fallback);
+        assert!(!parse_method_options(&[]).expect("parse").post_fallback);
+    }
+
+    #[test]
+    fn parse_accept_language_param() {
+        let attr: syn::Attribute = syn::parse_quote! { #[accept_language] };
+        assert_eq!(
+            parse_param_attr(&attr).expect("parse"),
+            Some(ParamKind::AcceptLanguage)
+        );
+    }
+
+    #[test]
+    fn parse_cookie_param() {
+        let attr: syn::Attribute = syn::parse_quote! { #[cookie("session_id")] };
+        assert_eq!(
+            parse_param_attr(&attr).expect("parse"),
+            Some(ParamKind::Cookie("session_id".to_string()))
+        );
+    }
+
+    #[test]
+    fn parse_if_match_param() {
+        let attr: syn::Attribute = syn::parse_quote! { #[if_match] };
+        assert_eq!(
+            parse_param_attr(&attr).expect("parse"),
+            Some(ParamKind::IfMatch)
+        );
+    }
+
     #[test]
     fn parse_duration_seconds() {
         assert_eq!(
@@ -503,11 +1238,11 @@ mod tests {
     fn parse_duration_minutes() {
         assert_eq!(
             parse_duration_string("1m"),
-            Some(std::time::Duration::from_secs(60))
+            Some(std::time::Duration::from_mins(1))
         );
         assert_eq!(
             parse_duration_string("5m"),
-            Some(std::time::Duration::from_secs(300))
+            Some(std::time::Duration::from_mins(5))
         );
     }
 
@@ -550,6 +1285,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn extract_placeholders_expressions() {
+        assert_eq!(
+            extract_path_placeholders("/files{/segments*}{?q,page}"),
+            vec!["segments".to_string(), "q".to_string(), "page".to_string()]
+        );
+    }
+
     #[test]
     fn extract_placeholders_none() {
         assert!(extract_path_placeholders("/health").is_empty());
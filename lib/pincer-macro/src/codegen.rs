@@ -4,20 +4,52 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Ident, Type, Visibility};
 
-use crate::attrs::{CollectionFormat, MethodParam, ParamKind};
+use crate::attrs::{BodyFormat, CollectionFormat, HttpMethod, MethodParam, ParamKind, PathOptions};
+use crate::uri_template::{Expression, Operator};
+
+/// Attributes of the trait carried over to the generated structs.
+pub struct ClientAttrs<'a> {
+    /// Doc comments of the trait, added to the client struct docs.
+    pub docs: &'a [syn::Attribute],
+    /// Attributes from `#[pincer_client_attrs(...)]`, applied to the client
+    /// and builder structs.
+    pub extra: &'a [syn::Meta],
+}
 
 /// Generate the client struct and builder for a trait-based API.
+///
+/// `method_names` are the method names as string literals, preceded by the
+/// method's `#[cfg]` attributes.
 pub fn generate_client_struct(
     vis: &Visibility,
+    trait_name: &Ident,
+    attrs: &ClientAttrs<'_>,
     client_name: &Ident,
     builder_name: &Ident,
     base_url: &str,
+    method_names: &[TokenStream],
 ) -> TokenStream {
+    let extra = attrs.extra;
+    let client_docs = generated_docs(
+        &format!("Client for the [`{trait_name}`] API, generated by `#[pincer]`."),
+        attrs.docs,
+        &format!("let client = {builder_name}::default().build()?;"),
+    );
+    let builder = generate_client_builder(
+        vis,
+        extra,
+        client_name,
+        builder_name,
+        base_url,
+        method_names,
+    );
     quote! {
-        /// Generated client struct implementing the API trait.
+        #client_docs
+        #(#[#extra])*
         #vis struct #client_name {
             client: ::pincer::HyperClient,
             base_url: ::pincer::url::Url,
+            timeouts: ::std::collections::HashMap<String, ::std::time::Duration>,
         }
 
         impl #client_name {
@@ -28,11 +60,41 @@ pub fn generate_client_struct(
             }
         }
 
-        /// Builder for the client struct.
+        #builder
+    }
+}
+
+/// Generate the builder of a client struct.
+fn generate_client_builder(
+    vis: &Visibility,
+    extra: &[syn::Meta],
+    client_name: &Ident,
+    builder_name: &Ident,
+    base_url: &str,
+    method_names: &[TokenStream],
+) -> TokenStream {
+    let builder_docs = generated_docs(
+        &format!("Builder for [`{client_name}`]."),
+        &[],
+        &format!(
+            "let client = {builder_name}::default()\n    .base_url(\"{base_url}\")\n    .configure_client(|b| b.with_retry(3))\n    .build()?;"
+        ),
+    );
+    let timeout_for_example = doc_example(&format!(
+        "let client = {builder_name}::default()\n    .timeout_for(\"slow_endpoint\", Duration::from_secs(120))\n    .build()?;"
+    ));
+    let configure_client_example = doc_example(&format!(
+        "let client = {builder_name}::default()\n    .configure_client(|b| b.with_retry(3).with_logging())\n    .build()?;"
+    ));
+    let base_url_doc = format!("Set a custom base URL (default: `{base_url}`).");
+    quote! {
+        #builder_docs
+        #(#[#extra])*
         #vis struct #builder_name {
             base_url: Option<String>,
             client: Option<::pincer::HyperClient>,
             client_builder: ::pincer::HyperClientBuilder,
+            timeouts: ::std::collections::HashMap<String, ::std::time::Duration>,
         }
 
         impl Default for #builder_name {
@@ -41,12 +103,13 @@ pub fn generate_client_struct(
                     base_url: None,
                     client: None,
                     client_builder: ::pincer::HyperClient::builder(),
+                    timeouts: ::std::collections::HashMap::new(),
                 }
             }
         }
 
         impl #builder_name {
-            /// Set a custom base URL (default: #base_url).
+            #[doc = #base_url_doc]
             #[must_use]
             pub fn base_url(mut self, url: impl Into<String>) -> Self {
                 self.base_url = Some(url.into());
@@ -62,15 +125,26 @@ pub fn generate_client_struct(
                 self
             }
 
-            /// Configure the underlying HTTP client builder.
+            /// Set the client certificate presented for mutual TLS.
             ///
-            /// # Example
-            ///
-            /// ```ignore
-            /// let client = MyApi::client()
-            ///     .configure_client(|b| b.with_retry(3).with_logging())
-            ///     .build()?;
-            /// ```
+            /// Each generated client can use its own identity.
+            /// Note: Ignored if a custom client is set via `client`.
+            #[must_use]
+            pub fn identity(mut self, identity: ::pincer::Identity) -> Self {
+                self.client_builder = self.client_builder.identity(identity);
+                self
+            }
+
+            /// Set the timeout of a method, overriding its `#[timeout]` attribute.
+            #timeout_for_example
+            #[must_use]
+            pub fn timeout_for(mut self, method: impl Into<String>, timeout: ::std::time::Duration) -> Self {
+                self.timeouts.insert(method.into(), timeout);
+                self
+            }
+
+            /// Configure the underlying HTTP client builder.
+            #configure_client_example
             #[must_use]
             pub fn configure_client<F>(mut self, f: F) -> Self
             where
@@ -81,80 +155,293 @@ pub fn generate_client_struct(
             }
 
             /// Build the client.
+            ///
+            /// Fails if the base URL is invalid, or if `timeout_for` names an
+            /// unknown method.
             pub fn build(self) -> ::pincer::Result<#client_name> {
                 let base_url = self.base_url.unwrap_or_else(|| #base_url.to_string());
                 let base_url = ::pincer::url::Url::parse(&base_url)
                     .map_err(::pincer::Error::InvalidUrl)?;
 
+                let methods: &[&str] = &[#(#method_names),*];
+                if let Some(method) = self.timeouts.keys().find(|method| !methods.contains(&method.as_str())) {
+                    return Err(::pincer::Error::invalid_request(format!(
+                        "timeout_for: unknown method `{method}`"
+                    )));
+                }
+
                 // If a custom client was provided, use it; otherwise build from the builder
                 let client = self.client.unwrap_or_else(|| self.client_builder.build());
 
-                Ok(#client_name { client, base_url })
+                Ok(#client_name {
+                    client,
+                    base_url,
+                    timeouts: self.timeouts,
+                })
             }
         }
     }
 }
 
+/// Generate the docs of a generated type: a summary line, the docs of the
+/// trait if any, then an example.
+fn generated_docs(summary: &str, trait_docs: &[syn::Attribute], example: &str) -> TokenStream {
+    let separator = (!trait_docs.is_empty()).then(|| quote! { #[doc = ""] });
+    let example = doc_example(example);
+    quote! {
+        #[doc = #summary]
+        #separator
+        #(#trait_docs)*
+        #example
+    }
+}
+
+/// Generate an `# Example` doc section showing `code`.
+fn doc_example(code: &str) -> TokenStream {
+    let lines = code.lines().map(|line| format!(" {line}"));
+    quote! {
+        #[doc = ""]
+        #[doc = " # Example"]
+        #[doc = ""]
+        #[doc = " ```ignore"]
+        #(#[doc = #lines])*
+        #[doc = " ```"]
+    }
+}
+
 /// Generate URL building code with path parameter substitution.
 ///
 /// Uses percent-encoding to properly encode path parameter values,
 /// ensuring special characters like spaces, `&`, `?`, `/` are handled correctly.
 pub fn generate_url_code(path_template: &str, params: &[MethodParam]) -> TokenStream {
-    // Find path parameters and generate substitutions
-    let path_params: Vec<_> = params
+    let path_code = generate_path_code(path_template, params);
+    quote! {
+        #path_code
+        let url = self.base_url.join(path.as_ref())
+            .map_err(::pincer::Error::InvalidUrl)?;
+    }
+}
+
+/// A piece of a path template, split at expansion time.
+#[derive(Debug, PartialEq, Eq)]
+enum PathSegment<'a> {
+    /// Literal text, copied as-is.
+    Literal(&'a str),
+    /// Placeholder of the path parameter with this key.
+    Param(&'a str),
+    /// URI template expression with at least one path parameter, e.g. `{?q,page}`.
+    Expression(Expression<'a>),
+}
+
+/// Split a path template into literals and the expressions of `keys`.
+///
+/// Placeholders with no matching parameter are kept as literal text.
+fn split_path_template<'a>(path_template: &'a str, keys: &[String]) -> Vec<PathSegment<'a>> {
+    let mut segments = Vec::new();
+    let mut literal_start = 0;
+    let mut rest = path_template;
+    let mut offset = 0;
+
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        let expression = Expression::parse(&rest[open + 1..open + close]);
+        let placeholder_start = offset + open;
+        let placeholder_end = placeholder_start + close + 1;
+
+        let is_key = |name: &str| keys.iter().any(|k| k == name);
+        if expression
+            .variables
+            .iter()
+            .any(|variable| is_key(variable.name))
+        {
+            if literal_start < placeholder_start {
+                segments.push(PathSegment::Literal(
+                    &path_template[literal_start..placeholder_start],
+                ));
+            }
+            segments.push(match expression.variables.as_slice() {
+                [variable] if expression.is_simple() => PathSegment::Param(variable.name),
+                _ => PathSegment::Expression(expression),
+            });
+            literal_start = placeholder_end;
+        }
+        rest = &path_template[placeholder_end..];
+        offset = placeholder_end;
+    }
+
+    if literal_start < path_template.len() {
+        segments.push(PathSegment::Literal(&path_template[literal_start..]));
+    }
+    segments
+}
+
+/// A path parameter: its placeholder key, name, type and options.
+type PathParam<'a> = (String, &'a Ident, &'a Type, &'a PathOptions);
+
+/// Generate code binding `path` to the path with parameters substituted.
+///
+/// The template is split at expansion time, so the generated code only
+/// appends literals and encoded values to one pre-sized `String` (or uses the
+/// template as a `&str` when it has no parameters).
+///
+/// Besides `{name}` placeholders, RFC 6570 expressions are expanded, e.g.
+/// `{/segments*}` or `{?q,page}`: `Option` parameters are left out when
+/// `None`, and `Vec` (or slice) parameters expand to lists.
+pub fn generate_path_code(path_template: &str, params: &[MethodParam]) -> TokenStream {
+    let path_params: Vec<PathParam<'_>> = params
         .iter()
         .filter_map(|p| match &p.kind {
-            ParamKind::Path(alias) => {
-                let name = &p.name;
-                let param_name = p.name.to_string();
-                let key = alias.as_deref().unwrap_or(&param_name);
-                Some((key.to_string(), name.clone()))
+            ParamKind::Path(options) => {
+                let key = options.alias.clone().unwrap_or_else(|| p.name.to_string());
+                Some((key, &p.name, &p.ty, options))
             }
             _ => None,
         })
         .collect();
+    let keys: Vec<String> = path_params.iter().map(|(key, ..)| key.clone()).collect();
+    let segments = split_path_template(path_template, &keys);
+
+    if segments
+        .iter()
+        .all(|segment| matches!(segment, PathSegment::Literal(_)))
+    {
+        return quote! {
+            let path: &str = #path_template;
+        };
+    }
 
-    // Build the path with substitutions using percent-encoding
-    // Use PATH_SEGMENT encoding which allows unreserved characters (- _ . ~)
-    // but encodes special chars like spaces, &, ?, /, etc.
-    let mut path_expr = quote! {
-        // Define the path segment encoding set (encodes all except unreserved + sub-delims)
-        // Unreserved: A-Z a-z 0-9 - . _ ~
-        const PATH_SEGMENT_ENCODE_SET: &::pincer::percent_encoding::AsciiSet =
-            &::pincer::percent_encoding::CONTROLS
-                .add(b' ')
-                .add(b'"')
-                .add(b'#')
-                .add(b'<')
-                .add(b'>')
-                .add(b'`')
-                .add(b'?')
-                .add(b'{')
-                .add(b'}')
-                .add(b'/')
-                .add(b'\\')
-                .add(b'%');
-        let mut path = #path_template.to_string();
+    let literal_len: usize = segments
+        .iter()
+        .map(|segment| match segment {
+            PathSegment::Literal(literal) => literal.len(),
+            PathSegment::Param(_) | PathSegment::Expression(_) => 16,
+        })
+        .sum();
+    let find = |key: &str| path_params.iter().find(|(k, ..)| k == key);
+    let pushes = segments.iter().map(|segment| match segment {
+        PathSegment::Literal(literal) => quote! {
+            path.push_str(#literal);
+        },
+        PathSegment::Param(key) => match find(key) {
+            Some((_, name, ty, options)) if !is_option_type(ty) && !is_list_type(ty) => {
+                let value = param_to_string(&quote! { #name }, options.time_format.as_deref());
+                if options.raw {
+                    quote! {
+                        path.push_str(&#value);
+                    }
+                } else {
+                    quote! {
+                        path.extend(::pincer::percent_encoding::utf8_percent_encode(
+                            &#value,
+                            ::pincer::PATH_SEGMENT_ENCODE_SET,
+                        ));
+                    }
+                }
+            }
+            _ => generate_expression_code(&Expression::parse(key), &find),
+        },
+        PathSegment::Expression(expression) => generate_expression_code(expression, &find),
+    });
+
+    quote! {
+        let mut path = ::std::string::String::with_capacity(#literal_len);
+        #(#pushes)*
+    }
+}
+
+/// Generate code appending the expansion of a URI template `expression` to
+/// `path`, with the path parameters returned by `find`.
+fn generate_expression_code<'a>(
+    expression: &Expression<'_>,
+    find: &impl Fn(&str) -> Option<&'a PathParam<'a>>,
+) -> TokenStream {
+    let operator = expression.operator;
+    let encode_set = match operator {
+        Operator::Reserved | Operator::Fragment => quote! { ::pincer::RESERVED_ENCODE_SET },
+        Operator::PathParameter | Operator::Query | Operator::QueryContinuation => {
+            quote! { ::pincer::QUERY_COMPONENT_ENCODE_SET }
+        }
+        Operator::Simple | Operator::Label | Operator::PathSegment => {
+            quote! { ::pincer::PATH_SEGMENT_ENCODE_SET }
+        }
     };
 
-    for (key, name) in &path_params {
-        let placeholder = format!("{{{key}}}");
-        path_expr = quote! {
-            #path_expr
-            path = path.replace(
-                #placeholder,
-                &::pincer::percent_encoding::utf8_percent_encode(
-                    &#name.to_string(),
-                    PATH_SEGMENT_ENCODE_SET,
-                ).to_string()
-            );
+    let pushes = expression.variables.iter().filter_map(|variable| {
+        let (_, name, ty, options) = find(variable.name)?;
+        let time_format = &options.time_format.as_deref();
+        // Raw values are already percent-encoded
+        let encode = |value: &TokenStream| {
+            if options.raw {
+                value.clone()
+            } else {
+                quote! {
+                    ::pincer::percent_encoding::utf8_percent_encode(&#value, #encode_set)
+                        .to_string()
+                }
+            }
         };
-    }
+        let var_name = variable.name;
+        let empty = format!("{var_name}{}", operator.if_empty());
+        // Push the expansion of a value, `name=value` for named operators
+        let push = |value: TokenStream| {
+            if operator.is_named() {
+                quote! {
+                    let value = #value;
+                    parts.push(if value.is_empty() {
+                        #empty.to_string()
+                    } else {
+                        ::std::format!("{}={}", #var_name, value)
+                    });
+                }
+            } else {
+                quote! { parts.push(#value); }
+            }
+        };
+        let item = encode(&param_to_string(&quote! { item }, *time_format));
+
+        Some(if is_list_type(ty) {
+            if variable.explode {
+                let push = push(item);
+                quote! {
+                    for item in #name.iter() {
+                        #push
+                    }
+                }
+            } else {
+                let push = push(quote! {
+                    #name.iter().map(|item| #item).collect::<::std::vec::Vec<_>>().join(",")
+                });
+                quote! {
+                    if !#name.is_empty() {
+                        #push
+                    }
+                }
+            }
+        } else if is_option_type(ty) {
+            let push = push(encode(&param_to_string(&quote! { value }, *time_format)));
+            quote! {
+                if let Some(value) = &#name {
+                    #push
+                }
+            }
+        } else {
+            push(encode(&param_to_string(&quote! { #name }, *time_format)))
+        })
+    });
 
+    let first = operator.first();
+    let separator = operator.separator();
     quote! {
-        #path_expr
-        let url = self.base_url.join(&path)
-            .map_err(::pincer::Error::InvalidUrl)?;
+        {
+            let mut parts: ::std::vec::Vec<::std::string::String> = ::std::vec::Vec::new();
+            #(#pushes)*
+            if !parts.is_empty() {
+                path.push_str(#first);
+                path.push_str(&parts.join(#separator));
+            }
+        }
     }
 }
 
@@ -174,7 +461,7 @@ pub fn generate_query_code(params: &[MethodParam]) -> TokenStream {
                 let name = &p.name;
                 let param_name = p.name.to_string();
                 let key = options.alias.as_deref().unwrap_or(&param_name).to_string();
-                Some((key, name.clone(), p.ty.clone(), options.format))
+                Some((key, name.clone(), p.ty.clone(), options))
             }
             _ => None,
         })
@@ -186,17 +473,57 @@ pub fn generate_query_code(params: &[MethodParam]) -> TokenStream {
 
     let mut append_statements = Vec::new();
 
-    for (key, name, ty, format) in &query_params {
-        if is_option_type(ty) {
+    for (key, name, ty, options) in &query_params {
+        let time_format = options.time_format.as_deref();
+        if options.format == CollectionFormat::DeepObject {
+            // Object or map: `key[field]=value` pairs, method call syntax
+            // derefs both values and references
+            let pairs = quote! {
+                ::pincer::deep_object_pairs(#key, {
+                    use ::pincer::ToQueryPairs as _;
+                    value.to_query_pairs()
+                })
+            };
+            append_statements.push(if is_option_type(ty) {
+                quote! {
+                    if let Some(value) = #name {
+                        for (key, value) in #pairs {
+                            query.append_pair(&key, &value);
+                        }
+                    }
+                }
+            } else {
+                quote! {
+                    {
+                        let value = &#name;
+                        for (key, value) in #pairs {
+                            query.append_pair(&key, &value);
+                        }
+                    }
+                }
+            });
+        } else if is_option_type(ty) {
             // Option<T>: skip if None
+            let value = param_to_string(&quote! { value }, time_format);
             append_statements.push(quote! {
                 if let Some(value) = #name {
-                    query.append_pair(#key, &value.to_string());
+                    query.append_pair(#key, &#value);
                 }
             });
         } else if is_vec_type(ty) {
             // Vec<T>: use the specified collection format
-            append_statements.push(generate_vec_query_code(key, name, *format));
+            append_statements.push(generate_vec_query_code(
+                key,
+                name,
+                options.format,
+                time_format,
+            ));
+        } else if time_format.is_some() {
+            // Timestamp, not a struct deriving Query
+            let value = param_to_string(&quote! { #name }, time_format);
+            append_statements.push(quote! {
+                query.append_pair(#key, &#value);
+            });
         } else if is_struct_type(ty) {
             // Struct type: use ToQueryPairs trait (requires #[derive(Query)] on the struct)
             append_statements.push(quote! {
@@ -221,14 +548,31 @@ pub fn generate_query_code(params: &[MethodParam]) -> TokenStream {
     }
 }
 
+/// Generate code converting the parameter `value` to a string, with its
+/// timestamp format if any.
+fn param_to_string(value: &TokenStream, time_format: Option<&str>) -> TokenStream {
+    if let Some(format) = time_format {
+        // `clone` derefs references, `format_timestamp` takes the time by value
+        quote! { ::pincer::format_timestamp(#value.clone(), #format) }
+    } else {
+        quote! { ::std::string::ToString::to_string(&#value) }
+    }
+}
+
 /// Generate code for serializing a Vec<T> query parameter with the given format.
-fn generate_vec_query_code(key: &str, name: &Ident, format: CollectionFormat) -> TokenStream {
+fn generate_vec_query_code(
+    key: &str,
+    name: &Ident,
+    format: CollectionFormat,
+    time_format: Option<&str>,
+) -> TokenStream {
+    let item = param_to_string(&quote! { item }, time_format);
     match format.separator() {
         None => {
             // Multi format: repeated parameters ?tags=a&tags=b&tags=c
             quote! {
                 for item in #name {
-                    query.append_pair(#key, &item.to_string());
+                    query.append_pair(#key, &#item);
                 }
             }
         }
@@ -236,7 +580,7 @@ fn generate_vec_query_code(key: &str, name: &Ident, format: CollectionFormat) ->
             // Separated format: ?tags=a,b,c or ?tags=a|b|c etc.
             quote! {
                 if !#name.is_empty() {
-                    let value = #name.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(#sep);
+                    let value = #name.iter().map(|item| #item).collect::<Vec<_>>().join(#sep);
                     query.append_pair(#key, &value);
                 }
             }
@@ -247,18 +591,23 @@ fn generate_vec_query_code(key: &str, name: &Ident, format: CollectionFormat) ->
 /// Generate headers code.
 ///
 /// Supports:
-/// - Static headers (User-Agent, Accept)
+/// - Static headers (User-Agent, Accept, from `#[accept(...)]` or the response format)
 /// - Trait-level headers (from `#[headers(...)]` on the trait)
-/// - Single header: `#[header("Authorization")] token: &str`
+/// - Single header: `#[header("Authorization")] token: &str`, or any `Display`
+///   type; `Option<T>` headers are skipped if `None`
+/// - Cookie: `#[cookie("session_id")] session: &str` (merged into one `Cookie` header)
+/// - Accept-Language: `#[accept_language] languages: &[&str]`
+/// - If-Match: `#[if_match] etag: &EntityTag`
 /// - Header map: `#[headers] extra: HashMap<String, String>`
 pub fn generate_headers_code(
     params: &[MethodParam],
     user_agent: &str,
+    accept: &str,
     trait_headers: &[(String, String)],
 ) -> TokenStream {
     let mut headers = quote! {
         .header("User-Agent", #user_agent)
-        .header("Accept", "application/json")
+        .header("Accept", #accept)
     };
 
     // Add trait-level headers
@@ -269,13 +618,56 @@ pub fn generate_headers_code(
         };
     }
 
-    // First add individual headers
+    // First add individual headers, formatted with `Display`
     for param in params {
         if let ParamKind::Header(header_name) = &param.kind {
+            let name = &param.name;
+            headers = if is_option_type(&param.ty) {
+                // Option<T>: skip if None
+                let value = param_to_string(&quote! { value }, None);
+                quote! {
+                    #headers
+                    .headers(#name.map(|value| (#header_name, #value)))
+                }
+            } else {
+                let value = param_to_string(&quote! { #name }, None);
+                quote! {
+                    #headers
+                    .header(#header_name, #value)
+                }
+            };
+        }
+    }
+
+    // Then the cookies, in declaration order
+    for param in params {
+        if let ParamKind::Cookie(cookie_name) = &param.kind {
             let name = &param.name;
             headers = quote! {
                 #headers
-                .header(#header_name, #name)
+                .cookie(#cookie_name, #name)
+            };
+        }
+    }
+
+    // Then the Accept-Language fallback chain
+    for param in params {
+        if matches!(param.kind, ParamKind::AcceptLanguage) {
+            let name = &param.name;
+            headers = quote! {
+                #headers
+                .accept_language(#name)
+            };
+        }
+    }
+
+    // Then the If-Match precondition
+    for param in params {
+        if matches!(param.kind, ParamKind::IfMatch) {
+            let name = &param.name;
+            headers = quote! {
+                #headers
+                .if_match(::std::borrow::Borrow::<::pincer::EntityTag>::borrow(&#name))
             };
         }
     }
@@ -341,10 +733,9 @@ fn generate_multipart_pre_body_code(
             form_parts.push(quote! {
                 for (i, part) in #name.into_iter().enumerate() {
                     let part_name = format!("{}[{}]", #field_name, i);
-                    let named_part = ::pincer::Part::new(part_name, part.data().clone())
-                        .with_content_type(part.content_type().unwrap_or("application/octet-stream"));
-                    let named_part = if let Some(filename) = part.filename() {
-                        named_part.with_filename(filename)
+                    let named_part = part.with_name(part_name);
+                    let named_part = if named_part.content_type().is_none() {
+                        named_part.with_content_type("application/octet-stream")
                     } else {
                         named_part
                     };
@@ -355,11 +746,9 @@ fn generate_multipart_pre_body_code(
             // Single Part - set the name from the attribute or param name
             form_parts.push(quote! {
                 {
-                    let part = &#name;
-                    let named_part = ::pincer::Part::new(#field_name, part.data().clone())
-                        .with_content_type(part.content_type().unwrap_or("application/octet-stream"));
-                    let named_part = if let Some(filename) = part.filename() {
-                        named_part.with_filename(filename)
+                    let named_part = ::pincer::Part::clone(&#name).with_name(#field_name);
+                    let named_part = if named_part.content_type().is_none() {
+                        named_part.with_content_type("application/octet-stream")
                     } else {
                         named_part
                     };
@@ -372,12 +761,17 @@ fn generate_multipart_pre_body_code(
     quote! {
         let mut __multipart_form = ::pincer::Form::new();
         #(#form_parts)*
-        let (__multipart_content_type, __multipart_body) = __multipart_form.into_body();
+        let (__multipart_content_type, __multipart_body) = __multipart_form.into_body()?;
     }
 }
 
 /// Generate body code.
-pub fn generate_body_code(params: &[MethodParam]) -> TokenStream {
+pub fn generate_body_code(
+    params: &[MethodParam],
+    json: Option<&syn::Path>,
+    xml: Option<&syn::Path>,
+    cbor: Option<&syn::Path>,
+) -> TokenStream {
     // Check for multipart params first (they take precedence)
     if has_multipart_params(params) {
         return quote! {
@@ -389,9 +783,20 @@ pub fn generate_body_code(params: &[MethodParam]) -> TokenStream {
     // Check for other body types
     for param in params {
         match &param.kind {
-            ParamKind::Body => {
+            ParamKind::Body(options) => {
                 let name = &param.name;
-                return quote! { .json(#name)? };
+                let value = options.serialize_with.as_ref().map_or_else(
+                    || quote! { #name },
+                    |serialize_with| serialize_with_value(name, &param.ty, serialize_with),
+                );
+                return match options.format {
+                    BodyFormat::Json => json.map_or_else(
+                        || quote! { .json(#value)? },
+                        |codec| quote! { .json_with(&#codec, #value)? },
+                    ),
+                    BodyFormat::Xml => quote! { .xml_with(&#xml, #value)? },
+                    BodyFormat::Cbor => quote! { .cbor_with(&#cbor, #value)? },
+                };
             }
             ParamKind::Form => {
                 let name = &param.name;
@@ -404,6 +809,27 @@ pub fn generate_body_code(params: &[MethodParam]) -> TokenStream {
     quote! {}
 }
 
+/// Generate a value serializing the body parameter `name` with a serde
+/// `serialize_with` function.
+fn serialize_with_value(name: &Ident, ty: &Type, serialize_with: &syn::Path) -> TokenStream {
+    quote! {
+        &{
+            struct __PincerSerializeWith<'a, T: ?Sized>(&'a T);
+
+            impl ::pincer::serde::Serialize for __PincerSerializeWith<'_, #ty> {
+                fn serialize<S: ::pincer::serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> ::std::result::Result<S::Ok, S::Error> {
+                    #serialize_with(self.0, serializer)
+                }
+            }
+
+            __PincerSerializeWith(&#name)
+        }
+    }
+}
+
 /// Check if a type is `Option<T>`.
 fn is_option_type(ty: &Type) -> bool {
     matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|seg| seg.ident == "Option"))
@@ -418,6 +844,12 @@ pub enum ReturnTypeKind {
     RawResponse,
     /// Unit type: `Result<()>`
     Unit,
+    /// Advertised capabilities: `Result<AllowedMethods>`
+    AllowedMethods,
+    /// Newline-delimited JSON stream: `Result<JsonLines<T>>`
+    JsonLines,
+    /// Raw body: `Result<Bytes>`
+    Bytes,
 }
 
 /// Analyze the return type to determine how to handle the response.
@@ -425,8 +857,14 @@ pub enum ReturnTypeKind {
 /// Extracts the inner type from `Result<T>` or `Result<Option<T>>` and determines:
 /// - `RawResponse`: If the type is `Response<_>` or `Response<Bytes>`
 /// - `Unit`: If the type is `()`
+/// - `AllowedMethods`: If the type is `AllowedMethods` on an `OPTIONS` method
+/// - `JsonLines`: If the type is `JsonLines<T>`
+/// - `Bytes`: If the type is `Bytes`
 /// - `Json`: Everything else (default - deserialize JSON)
-pub fn analyze_return_type(return_type: &syn::ReturnType) -> ReturnTypeKind {
+pub fn analyze_return_type(
+    return_type: &syn::ReturnType,
+    http_method: HttpMethod,
+) -> ReturnTypeKind {
     let ty = match return_type {
         syn::ReturnType::Default => return ReturnTypeKind::Unit,
         syn::ReturnType::Type(_, ty) => ty.as_ref(),
@@ -448,9 +886,35 @@ pub fn analyze_return_type(return_type: &syn::ReturnType) -> ReturnTypeKind {
         return ReturnTypeKind::RawResponse;
     }
 
+    // Check for AllowedMethods type, only read from `OPTIONS` headers
+    if http_method == HttpMethod::Options && is_allowed_methods_type(inner) {
+        return ReturnTypeKind::AllowedMethods;
+    }
+
+    // Check for JsonLines<T> type
+    if is_json_lines_type(inner) {
+        return ReturnTypeKind::JsonLines;
+    }
+
+    // Check for Bytes type
+    if is_bytes_type(inner) {
+        return ReturnTypeKind::Bytes;
+    }
+
     ReturnTypeKind::Json
 }
 
+/// Check if a return type is `Result<bool>`, the return type of `#[exists]`
+/// methods.
+pub fn is_bool_result(return_type: &syn::ReturnType) -> bool {
+    let syn::ReturnType::Type(_, ty) = return_type else {
+        return false;
+    };
+    unwrap_result_type(ty).is_some_and(
+        |inner| matches!(inner, Type::Path(type_path) if type_path.qself.is_none() && type_path.path.is_ident("bool")),
+    )
+}
+
 /// Check if a type is the unit type `()`.
 pub fn is_unit_type(ty: &Type) -> bool {
     matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty())
@@ -466,6 +930,21 @@ fn is_response_type(ty: &Type) -> bool {
     false
 }
 
+/// Check if a type is `AllowedMethods` (capabilities of an `OPTIONS` response).
+fn is_allowed_methods_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|seg| seg.ident == "AllowedMethods"))
+}
+
+/// Check if a type is `JsonLines<T>` (newline-delimited JSON stream).
+fn is_json_lines_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|seg| seg.ident == "JsonLines"))
+}
+
+/// Check if a type is `Bytes` (raw response body).
+fn is_bytes_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|seg| seg.ident == "Bytes" && seg.arguments.is_none()))
+}
+
 /// Unwrap `Result<T>` to get `T`, returns None if not a Result.
 fn unwrap_result_type(ty: &Type) -> Option<&Type> {
     if let Type::Path(type_path) = ty
@@ -497,6 +976,16 @@ fn is_vec_type(ty: &Type) -> bool {
     matches!(ty, Type::Path(type_path) if type_path.path.segments.last().is_some_and(|seg| seg.ident == "Vec"))
 }
 
+/// Check if a type is a list: `Vec<T>`, `&Vec<T>` or `&[T]`.
+fn is_list_type(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(type_ref) => {
+            matches!(*type_ref.elem, Type::Slice(_)) || is_vec_type(&type_ref.elem)
+        }
+        _ => is_vec_type(ty),
+    }
+}
+
 /// Generate a generic wrapper struct for the wrapper mode.
 ///
 /// This generates a struct that wraps any `PincerClient` implementation:
@@ -504,37 +993,68 @@ fn is_vec_type(ty: &Type) -> bool {
 /// pub struct GitHubApiClient<C> {
 ///     client: C,
 ///     base_url: Url,
+///     headers: HeaderMap,
+///     user_agent: Option<HeaderValue>,
 /// }
 /// ```
+/// and a `GitHubApiClientBuilder<C>` to set the base URL and default headers.
 pub fn generate_wrapper_struct(
     vis: &Visibility,
+    trait_name: &Ident,
+    attrs: &ClientAttrs<'_>,
     client_name: &Ident,
+    builder_name: &Ident,
     base_url: &str,
 ) -> TokenStream {
+    let extra = attrs.extra;
+    let client_docs = generated_docs(
+        &format!(
+            "Client for the [`{trait_name}`] API over any `PincerClient`, generated by `#[pincer]`."
+        ),
+        attrs.docs,
+        &format!(
+            "let client = {client_name}::new(ApiClient::new(HyperClient::new(), \"{base_url}\")?)?;"
+        ),
+    );
+    let builder = generate_wrapper_builder(vis, extra, client_name, builder_name, base_url);
     quote! {
-        /// Generated generic client wrapper implementing the API trait.
-        ///
-        /// Wraps any `PincerClient` implementation to provide the API.
+        #client_docs
+        #(#[#extra])*
         #vis struct #client_name<C> {
             client: C,
             base_url: ::pincer::url::Url,
+            headers: ::pincer::header::HeaderMap,
+            user_agent: Option<::pincer::header::HeaderValue>,
         }
 
         impl<C> #client_name<C> {
             /// Create a new client wrapper with the default base URL.
+            ///
+            /// # Errors
+            ///
+            /// Returns an error if the default base URL is invalid, which the
+            /// macro already rejects at compile time.
+            pub fn new(client: C) -> ::pincer::Result<Self> {
+                let base_url = ::pincer::url::Url::parse(#base_url)
+                    .map_err(::pincer::Error::InvalidUrl)?;
+                Ok(Self::with_base_url(client, base_url))
+            }
+
+            /// Create a new client wrapper with a custom base URL.
             #[must_use]
-            pub fn new(client: C) -> Self {
+            pub fn with_base_url(client: C, base_url: ::pincer::url::Url) -> Self {
                 Self {
                     client,
-                    base_url: ::pincer::url::Url::parse(#base_url)
-                        .expect("invalid base URL in macro"),
+                    base_url,
+                    headers: ::pincer::header::HeaderMap::new(),
+                    user_agent: None,
                 }
             }
 
-            /// Create a new client wrapper with a custom base URL.
+            /// Create a builder for the client wrapper.
             #[must_use]
-            pub fn with_base_url(client: C, base_url: ::pincer::url::Url) -> Self {
-                Self { client, base_url }
+            pub fn builder() -> #builder_name<C> {
+                #builder_name::default()
             }
 
             /// Get the base URL.
@@ -561,6 +1081,8 @@ pub fn generate_wrapper_struct(
                 Self {
                     client: self.client.clone(),
                     base_url: self.base_url.clone(),
+                    headers: self.headers.clone(),
+                    user_agent: self.user_agent.clone(),
                 }
             }
         }
@@ -568,14 +1090,168 @@ pub fn generate_wrapper_struct(
         impl<C: ::pincer::PincerClient> ::pincer::PincerClient for #client_name<C> {
             fn execute(
                 &self,
-                request: ::pincer::Request<::bytes::Bytes>,
+                mut request: ::pincer::Request<::bytes::Bytes>,
             ) -> impl ::std::future::Future<Output = ::pincer::Result<::pincer::Response<::bytes::Bytes>>> + Send {
+                for (name, value) in &self.headers {
+                    if !request.headers().contains_key(name) {
+                        request.headers_mut().insert(name.clone(), value.clone());
+                    }
+                }
+                if let Some(user_agent) = &self.user_agent {
+                    request.headers_mut().insert(::pincer::header::USER_AGENT, user_agent.clone());
+                }
                 self.client.execute(request)
             }
 
             fn base_url(&self) -> &::pincer::url::Url {
                 &self.base_url
             }
+
+            fn runtime(&self) -> Option<&dyn ::pincer::Runtime> {
+                ::pincer::PincerClient::runtime(&self.client)
+            }
+        }
+
+        #builder
+    }
+}
+
+/// Generate the `health()` and `watch_health()` methods of a client probing `path`.
+///
+/// `wrapper` clients are generic over their `PincerClient`, the others use
+/// their `HyperClient` and base URL.
+pub fn generate_health_methods(client_name: &Ident, path: &str, wrapper: bool) -> TokenStream {
+    let (impl_header, client) = if wrapper {
+        (
+            quote! { impl<C: ::pincer::PincerClient + 'static> #client_name<C> },
+            quote! { self.clone() },
+        )
+    } else {
+        (
+            quote! { impl #client_name },
+            quote! { ::pincer::ApiClient::with_url(self.client.clone(), self.base_url.clone()) },
+        )
+    };
+    quote! {
+        #impl_header {
+            #[doc = concat!("Probe the health endpoint `", #path, "`.")]
+            ///
+            /// Returns `Ok(true)` for a 2xx response, `Ok(false)` for any other status.
+            pub async fn health(&self) -> ::pincer::Result<bool> {
+                ::pincer::probe_health(&#client, #path).await
+            }
+
+            /// Probe the health endpoint every `interval` in a background task.
+            #[must_use]
+            pub fn watch_health(&self, interval: ::std::time::Duration) -> ::pincer::HealthWatcher {
+                ::pincer::HealthWatcher::spawn(#client, #path, interval)
+            }
+        }
+    }
+}
+
+/// Generate the builder of a wrapper struct.
+fn generate_wrapper_builder(
+    vis: &Visibility,
+    extra: &[syn::Meta],
+    client_name: &Ident,
+    builder_name: &Ident,
+    base_url: &str,
+) -> TokenStream {
+    let builder_docs = generated_docs(
+        &format!("Builder for [`{client_name}`]."),
+        &[],
+        &format!(
+            "let client = {client_name}::builder()\n    .client(HyperClient::new())\n    .base_url(\"{base_url}\")\n    .header(\"x-team\", \"core\")\n    .build()?;"
+        ),
+    );
+    let base_url_doc = format!("Set a custom base URL (default: `{base_url}`).");
+    quote! {
+        #builder_docs
+        #(#[#extra])*
+        #vis struct #builder_name<C> {
+            client: Option<C>,
+            base_url: Option<String>,
+            headers: Vec<(String, String)>,
+            user_agent: Option<String>,
+        }
+
+        impl<C> Default for #builder_name<C> {
+            fn default() -> Self {
+                Self {
+                    client: None,
+                    base_url: None,
+                    headers: Vec::new(),
+                    user_agent: None,
+                }
+            }
+        }
+
+        impl<C> #builder_name<C> {
+            /// Set the wrapped client (required).
+            #[must_use]
+            pub fn client(mut self, client: C) -> Self {
+                self.client = Some(client);
+                self
+            }
+
+            #[doc = #base_url_doc]
+            #[must_use]
+            pub fn base_url(mut self, url: impl Into<String>) -> Self {
+                self.base_url = Some(url.into());
+                self
+            }
+
+            /// Add a default header, sent unless the request already has it.
+            #[must_use]
+            pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+                self.headers.push((name.into(), value.into()));
+                self
+            }
+
+            /// Replace the `User-Agent` header of every request.
+            #[must_use]
+            pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+                self.user_agent = Some(user_agent.into());
+                self
+            }
+
+            /// Build the client wrapper.
+            ///
+            /// Fails if no client was set, or if the base URL or a header is
+            /// invalid.
+            pub fn build(self) -> ::pincer::Result<#client_name<C>> {
+                let client = self
+                    .client
+                    .ok_or_else(|| ::pincer::Error::invalid_request("missing client"))?;
+                let base_url = self.base_url.unwrap_or_else(|| #base_url.to_string());
+                let base_url = ::pincer::url::Url::parse(&base_url)
+                    .map_err(::pincer::Error::InvalidUrl)?;
+
+                let invalid_header = |err: &dyn ::std::fmt::Display| {
+                    ::pincer::Error::invalid_request(format!("invalid header: {err}"))
+                };
+                let mut headers = ::pincer::header::HeaderMap::new();
+                for (name, value) in self.headers {
+                    let name = ::pincer::header::HeaderName::try_from(name)
+                        .map_err(|err| invalid_header(&err))?;
+                    let value = ::pincer::header::HeaderValue::try_from(value)
+                        .map_err(|err| invalid_header(&err))?;
+                    headers.append(name, value);
+                }
+                let user_agent = self
+                    .user_agent
+                    .map(::pincer::header::HeaderValue::try_from)
+                    .transpose()
+                    .map_err(|err| invalid_header(&err))?;
+
+                Ok(#client_name {
+                    client,
+                    base_url,
+                    headers,
+                    user_agent,
+                })
+            }
         }
     }
 }
@@ -611,6 +1287,82 @@ fn is_struct_type(ty: &Type) -> bool {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_client_struct_docs() {
+        let trait_docs: Vec<syn::Attribute> =
+            vec![syn::parse_quote! { #[doc = " User directory."] }];
+        let tokens = generate_client_struct(
+            &syn::parse_quote! { pub },
+            &syn::parse_quote! { UserApi },
+            &ClientAttrs {
+                docs: &trait_docs,
+                extra: &[syn::parse_quote! { derive(Debug) }],
+            },
+            &syn::parse_quote! { UserApiClient },
+            &syn::parse_quote! { UserApiClientBuilder },
+            "https://api.example.com",
+            &[],
+        )
+        .to_string();
+
+        assert!(tokens.contains("Client for the [`UserApi`] API"));
+        assert!(tokens.contains(" User directory."));
+        assert!(tokens.contains("let client = UserApiClientBuilder::default().build()?;"));
+        assert!(tokens.contains("Builder for [`UserApiClient`]."));
+        assert!(!tokens.contains("MyApi"));
+        assert_eq!(tokens.matches("# [derive (Debug)]").count(), 2);
+    }
+
+    #[test]
+    fn test_split_path_template() {
+        let keys = ["owner".to_string(), "repo".to_string()];
+        assert_eq!(
+            split_path_template("/repos/{owner}/{repo}/issues", &keys),
+            [
+                PathSegment::Literal("/repos/"),
+                PathSegment::Param("owner"),
+                PathSegment::Literal("/"),
+                PathSegment::Param("repo"),
+                PathSegment::Literal("/issues"),
+            ]
+        );
+        assert_eq!(
+            split_path_template("{owner}{repo}", &keys),
+            [PathSegment::Param("owner"), PathSegment::Param("repo")]
+        );
+    }
+
+    #[test]
+    fn test_split_path_template_unknown_placeholder() {
+        let keys = ["id".to_string()];
+        assert_eq!(
+            split_path_template("/a/{other}/{id}", &keys),
+            [
+                PathSegment::Literal("/a/{other}/"),
+                PathSegment::Param("id")
+            ]
+        );
+        assert_eq!(
+            split_path_template("/a/{unclosed", &keys),
+            [PathSegment::Literal("/a/{unclosed")]
+        );
+    }
+
+    #[test]
+    fn test_split_path_template_expressions() {
+        let keys = ["segments".to_string(), "q".to_string()];
+        assert_eq!(
+            split_path_template("/files{/segments*}/raw{?q,page}{&other}", &keys),
+            [
+                PathSegment::Literal("/files"),
+                PathSegment::Expression(Expression::parse("/segments*")),
+                PathSegment::Literal("/raw"),
+                PathSegment::Expression(Expression::parse("?q,page")),
+                PathSegment::Literal("{&other}"),
+            ]
+        );
+    }
+
     #[test]
     fn test_is_option_type() {
         let ty: Type = syn::parse_quote!(Option<String>);
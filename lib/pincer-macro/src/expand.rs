@@ -2,16 +2,18 @@
 
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
+use syn::visit_mut::{self, VisitMut};
 use syn::{FnArg, Ident, ItemTrait, Pat, TraitItem, TraitItemFn, parse2};
 
 use crate::attrs::{
-    HttpMethod, MethodAttrs, MethodOptions, MethodParam, ParamKind, PincerMode,
-    extract_path_placeholders, parse_method_options, parse_param_attr, parse_trait_headers,
+    BodyFormat, BodyOptions, Deprecated, HttpMethod, MethodAttrs, MethodOptions, MethodParam,
+    ParamKind, PathOptions, PincerMode, ResponseFormat, extract_path_placeholders,
+    parse_client_attrs, parse_method_options, parse_param_attr, parse_trait_headers,
 };
 use crate::codegen::{
-    ReturnTypeKind, analyze_return_type, generate_body_code, generate_client_struct,
-    generate_headers_code, generate_pre_body_code, generate_query_code, generate_url_code,
-    generate_wrapper_struct,
+    ClientAttrs, ReturnTypeKind, analyze_return_type, generate_body_code, generate_client_struct,
+    generate_headers_code, generate_health_methods, generate_path_code, generate_pre_body_code,
+    generate_query_code, generate_url_code, generate_wrapper_struct, is_bool_result,
 };
 
 /// Default user agent string for pincer clients.
@@ -23,6 +25,9 @@ const PINCER_PARAM_ATTRS: &[&str] = &[
     "query",
     "header",
     "headers",
+    "cookie",
+    "accept_language",
+    "if_match",
     "body",
     "form",
     "multipart",
@@ -33,7 +38,24 @@ const PINCER_PARAM_ATTRS: &[&str] = &[
 pub struct PincerArgs {
     pub url: Option<String>,
     pub user_agent: Option<String>,
+    pub name: Option<String>,
     pub mode: PincerMode,
+    /// Types implementing the trait in `impl_for(...)` mode.
+    pub impl_for: Vec<syn::Type>,
+    /// Generate the object-safe `{Trait}Dyn` companion trait.
+    pub dyn_trait: bool,
+    /// JSON codec of the bodies, `None` for the default (`serde_json`).
+    pub json: Option<syn::Path>,
+    /// XML codec of the bodies, required by XML bodies and responses.
+    /// `"quick_xml"` selects `pincer::QuickXml`.
+    pub xml: Option<syn::Path>,
+    /// CBOR codec of the bodies, required by CBOR bodies and responses.
+    /// `"ciborium"` selects `pincer::Ciborium`.
+    pub cbor: Option<syn::Path>,
+    /// Path of the health endpoint, probed by the generated `health()` method.
+    pub health: Option<String>,
+    /// Maximum length of the request URLs, checked by the request functions.
+    pub max_url_length: Option<usize>,
 }
 
 impl PincerArgs {
@@ -42,6 +64,20 @@ impl PincerArgs {
     pub fn user_agent(&self) -> &str {
         self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT)
     }
+
+    /// Generate the health probe methods of the client, if `health` is set.
+    fn health_methods(&self, client_name: &Ident, wrapper: bool) -> Option<TokenStream> {
+        self.health
+            .as_deref()
+            .map(|path| generate_health_methods(client_name, path, wrapper))
+    }
+
+    /// Generate the request extension carrying the client name, if set.
+    fn client_name_extension(&self) -> TokenStream {
+        self.name.as_ref().map_or_else(TokenStream::new, |name| {
+            quote! { .extension(::pincer::ClientName::new(#name)) }
+        })
+    }
 }
 
 /// Parse the pincer attribute arguments.
@@ -51,17 +87,65 @@ fn parse_pincer_args(attr: TokenStream) -> syn::Result<PincerArgs> {
     let parser = syn::meta::parser(|meta| {
         if meta.path.is_ident("url") {
             let value: syn::LitStr = meta.value()?.parse()?;
+            validate_base_url(&value)?;
             args.url = Some(value.value());
             Ok(())
         } else if meta.path.is_ident("user_agent") {
             let value: syn::LitStr = meta.value()?.parse()?;
             args.user_agent = Some(value.value());
             Ok(())
+        } else if meta.path.is_ident("name") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            args.name = Some(value.value());
+            Ok(())
+        } else if meta.path.is_ident("json") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            if value.value() != "serde_json" {
+                args.json = Some(value.parse().map_err(|_| {
+                    syn::Error::new(
+                        value.span(),
+                        "invalid `json`: expected \"serde_json\" or the path of a `BodyCodec` value",
+                    )
+                })?);
+            }
+            Ok(())
+        } else if meta.path.is_ident("xml") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            let quick_xml = syn::parse_quote! { ::pincer::QuickXml };
+            args.xml = Some(parse_codec(&value, "xml", "quick_xml", quick_xml)?);
+            Ok(())
+        } else if meta.path.is_ident("cbor") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            let ciborium = syn::parse_quote! { ::pincer::Ciborium };
+            args.cbor = Some(parse_codec(&value, "cbor", "ciborium", ciborium)?);
+            Ok(())
+        } else if meta.path.is_ident("health") {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            if !value.value().starts_with('/') {
+                return Err(syn::Error::new(
+                    value.span(),
+                    "invalid `health`: expected a path starting with `/`",
+                ));
+            }
+            args.health = Some(value.value());
+            Ok(())
+        } else if meta.path.is_ident("max_url_length") {
+            let value: syn::LitInt = meta.value()?.parse()?;
+            args.max_url_length = Some(value.base10_parse()?);
+            Ok(())
+        } else if meta.path.is_ident("dyn_trait") {
+            args.dyn_trait = true;
+            Ok(())
         } else if meta.path.is_ident("mode") {
             let value: syn::LitStr = meta.value()?.parse()?;
+            if let Some(types) = parse_impl_for(&value)? {
+                args.mode = PincerMode::ImplFor;
+                args.impl_for = types;
+                return Ok(());
+            }
             args.mode = PincerMode::parse(&value.value()).ok_or_else(|| {
                 meta.error(format!(
-                    "unknown mode: \"{}\". Expected: \"full\", \"wrapper\", or \"impl_only\"",
+                    "unknown mode: \"{}\". Expected: \"full\", \"wrapper\", \"impl_only\", or \"impl_for(Type, ...)\"",
                     value.value()
                 ))
             })?;
@@ -73,17 +157,87 @@ fn parse_pincer_args(attr: TokenStream) -> syn::Result<PincerArgs> {
 
     syn::parse::Parser::parse2(parser, attr)?;
 
-    // URL is required for full and wrapper modes, optional for impl_only
-    if args.url.is_none() && args.mode != PincerMode::ImplOnly {
+    // URL is required for full and wrapper modes, optional for impl_only and impl_for
+    if args.url.is_none() && matches!(args.mode, PincerMode::Full | PincerMode::Wrapper) {
         return Err(syn::Error::new(
             proc_macro2::Span::call_site(),
             "missing `url` attribute (required for full and wrapper modes)",
         ));
     }
 
+    if args.health.is_some() && !matches!(args.mode, PincerMode::Full | PincerMode::Wrapper) {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`health` requires full or wrapper mode, which generate a client",
+        ));
+    }
+
     Ok(args)
 }
 
+/// Parse the codec of the `arg` attribute: `builtin` selects the `codec` of
+/// pincer, other values are the path of a `BodyCodec` value.
+fn parse_codec(
+    value: &syn::LitStr,
+    arg: &str,
+    builtin: &str,
+    codec: syn::Path,
+) -> syn::Result<syn::Path> {
+    if value.value() == builtin {
+        return Ok(codec);
+    }
+    value.parse().map_err(|_| {
+        syn::Error::new(
+            value.span(),
+            format!("invalid `{arg}`: expected \"{builtin}\" or the path of a `BodyCodec` value"),
+        )
+    })
+}
+
+/// Check that the `url` attribute is a valid base URL.
+///
+/// Generated code parses it again at runtime and joins method paths onto it,
+/// so this is what guarantees that `new()` and the builders never fail on
+/// the default URL.
+fn validate_base_url(value: &syn::LitStr) -> syn::Result<()> {
+    let url = url::Url::parse(&value.value())
+        .map_err(|err| syn::Error::new(value.span(), format!("invalid `url`: {err}")))?;
+    if url.cannot_be_a_base() {
+        return Err(syn::Error::new(
+            value.span(),
+            "invalid `url`: must be a base URL such as `https://api.example.com`",
+        ));
+    }
+    Ok(())
+}
+
+/// Parse the types of an `impl_for(Type, ...)` mode.
+///
+/// Returns `None` if the mode is not `impl_for`.
+fn parse_impl_for(value: &syn::LitStr) -> syn::Result<Option<Vec<syn::Type>>> {
+    let mode = value.value();
+    let Some(types) = mode
+        .trim()
+        .strip_prefix("impl_for(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    else {
+        return Ok(None);
+    };
+
+    let parser = syn::punctuated::Punctuated::<syn::Type, syn::Token![,]>::parse_terminated;
+    let types: Vec<syn::Type> = syn::parse::Parser::parse_str(parser, types)
+        .map_err(|err| syn::Error::new(value.span(), format!("invalid impl_for type: {err}")))?
+        .into_iter()
+        .collect();
+    if types.is_empty() {
+        return Err(syn::Error::new(
+            value.span(),
+            "impl_for requires at least one type",
+        ));
+    }
+    Ok(Some(types))
+}
+
 /// Information about a parsed trait method.
 pub struct TraitMethodInfo {
     /// The method signature.
@@ -96,6 +250,8 @@ pub struct TraitMethodInfo {
     pub params: Vec<MethodParam>,
     /// Documentation attributes.
     pub docs: Vec<syn::Attribute>,
+    /// `#[cfg(...)]` attributes, repeated on everything generated per method.
+    pub cfgs: Vec<syn::Attribute>,
     /// Method-level options (`not_found_as_none`, timeout, etc.).
     pub options: MethodOptions,
 }
@@ -109,8 +265,30 @@ pub fn expand_pincer_trait(attr: TokenStream, item: TokenStream) -> syn::Result<
     let vis = &trait_def.vis;
 
     let trait_headers = parse_trait_headers(&trait_def.attrs)?;
-    let methods = extract_trait_methods(&trait_def)?;
-    let clean_trait = generate_clean_trait(vis, trait_name, &methods, &trait_def);
+    let trait_docs: Vec<_> = trait_def
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .cloned()
+        .collect();
+    let extra_attrs = parse_client_attrs(&trait_def.attrs)?;
+    let client_attrs = ClientAttrs {
+        docs: &trait_docs,
+        extra: &extra_attrs,
+    };
+    let methods = extract_trait_methods(&trait_def, &args)?;
+    let mut clean_trait =
+        generate_clean_trait(vis, trait_name, &methods, &trait_def, args.dyn_trait);
+    if args.dyn_trait {
+        clean_trait.extend(generate_dyn_trait(vis, trait_name, &methods));
+    }
+    clean_trait.extend(generate_requests_struct(
+        vis,
+        trait_name,
+        &methods,
+        &args,
+        &trait_headers,
+    ));
 
     match args.mode {
         PincerMode::Full => {
@@ -122,42 +300,64 @@ pub fn expand_pincer_trait(attr: TokenStream, item: TokenStream) -> syn::Result<
                 .as_ref()
                 .ok_or_else(|| syn::Error::new(trait_name.span(), "URL required for full mode"))?;
 
-            let client_and_builder =
-                generate_client_struct(vis, &client_name, &builder_name, base_url);
-            let trait_impl =
-                generate_trait_impl(trait_name, &client_name, &methods, &args, &trait_headers);
+            let method_names: Vec<TokenStream> = methods
+                .iter()
+                .map(|m| {
+                    let cfgs = &m.cfgs;
+                    let name = m.sig.ident.to_string();
+                    quote! { #(#cfgs)* #name }
+                })
+                .collect();
+            let client_and_builder = generate_client_struct(
+                vis,
+                trait_name,
+                &client_attrs,
+                &client_name,
+                &builder_name,
+                base_url,
+                &method_names,
+            );
+            let trait_impl = generate_trait_impl(trait_name, &client_name, &methods, &args);
+            let health_methods = args.health_methods(&client_name, false);
 
             Ok(quote! {
                 #clean_trait
                 #client_and_builder
+                #health_methods
                 #trait_impl
             })
         }
         PincerMode::Wrapper => {
             // Wrapper mode: generate generic wrapper struct
             let client_name = format_ident!("{}Client", trait_name);
+            let builder_name = format_ident!("{}ClientBuilder", trait_name);
             let base_url = args.url.as_ref().ok_or_else(|| {
                 syn::Error::new(trait_name.span(), "URL required for wrapper mode")
             })?;
 
-            let wrapper_struct = generate_wrapper_struct(vis, &client_name, base_url);
-            let wrapper_impl = generate_wrapper_trait_impl(
+            let wrapper_struct = generate_wrapper_struct(
+                vis,
                 trait_name,
+                &client_attrs,
                 &client_name,
-                &methods,
-                &args,
-                &trait_headers,
+                &builder_name,
+                base_url,
             );
+            let wrapper_impl =
+                generate_wrapper_trait_impl(trait_name, &client_name, &methods, &args);
+            let health_methods = args.health_methods(&client_name, true);
 
             Ok(quote! {
                 #clean_trait
                 #wrapper_struct
+                #health_methods
                 #wrapper_impl
             })
         }
-        PincerMode::ImplOnly => {
-            // Impl-only mode: generate blanket impl for PincerClient
-            let blanket_impl = generate_blanket_impl(trait_name, &methods, &args, &trait_headers);
+        PincerMode::ImplOnly | PincerMode::ImplFor => {
+            // Impl-only mode: generate blanket impl for PincerClient,
+            // or impls for the listed types only
+            let blanket_impl = generate_blanket_impl(trait_name, &methods, &args);
 
             Ok(quote! {
                 #clean_trait
@@ -168,7 +368,10 @@ pub fn expand_pincer_trait(attr: TokenStream, item: TokenStream) -> syn::Result<
 }
 
 /// Extract methods from a trait definition.
-fn extract_trait_methods(trait_def: &ItemTrait) -> syn::Result<Vec<TraitMethodInfo>> {
+fn extract_trait_methods(
+    trait_def: &ItemTrait,
+    args: &PincerArgs,
+) -> syn::Result<Vec<TraitMethodInfo>> {
     let mut methods = Vec::new();
 
     for item in &trait_def.items {
@@ -184,9 +387,48 @@ fn extract_trait_methods(trait_def: &ItemTrait) -> syn::Result<Vec<TraitMethodIn
                     .filter(|a| a.path().is_ident("doc"))
                     .cloned()
                     .collect();
+                let cfgs = method
+                    .attrs
+                    .iter()
+                    .filter(|a| a.path().is_ident("cfg"))
+                    .cloned()
+                    .collect();
 
                 // Parse method-level options (not_found_as_none, timeout, etc.)
-                let options = parse_method_options(&method.attrs)?;
+                let mut options = parse_method_options(&method.attrs)?;
+                match analyze_return_type(&method.sig.output, http_method) {
+                    ReturnTypeKind::JsonLines => {
+                        if args.mode != PincerMode::Full {
+                            return Err(syn::Error::new_spanned(
+                                &method.sig.output,
+                                "`JsonLines` return types are only supported in full mode",
+                            ));
+                        }
+                        options
+                            .accept
+                            .get_or_insert_with(|| "application/x-ndjson".to_string());
+                    }
+                    ReturnTypeKind::Bytes => {
+                        options.accept.get_or_insert_with(|| "*/*".to_string());
+                    }
+                    _ => {}
+                }
+                if options.exists {
+                    check_exists(&method.sig, http_method, &options)?;
+                }
+                if options.post_fallback && http_method != HttpMethod::Get {
+                    return Err(syn::Error::new_spanned(
+                        &method.sig,
+                        "`#[post_fallback]` is only supported on `#[get]` methods",
+                    ));
+                }
+                if options.post_fallback && args.max_url_length.is_none() {
+                    return Err(syn::Error::new_spanned(
+                        &method.sig,
+                        "`#[post_fallback]` requires `max_url_length` in `#[pincer(...)]`",
+                    ));
+                }
+                check_codecs(&method.sig, &params, &options, args)?;
 
                 methods.push(TraitMethodInfo {
                     sig: method.sig.clone(),
@@ -194,6 +436,7 @@ fn extract_trait_methods(trait_def: &ItemTrait) -> syn::Result<Vec<TraitMethodIn
                     path,
                     params,
                     docs,
+                    cfgs,
                     options,
                 });
             }
@@ -203,6 +446,71 @@ fn extract_trait_methods(trait_def: &ItemTrait) -> syn::Result<Vec<TraitMethodIn
     Ok(methods)
 }
 
+/// Check an `#[exists]` method is a `#[head]` method returning
+/// `pincer::Result<bool>`.
+fn check_exists(
+    sig: &syn::Signature,
+    http_method: HttpMethod,
+    options: &MethodOptions,
+) -> syn::Result<()> {
+    if http_method != HttpMethod::Head {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "`#[exists]` is only supported on `#[head]` methods",
+        ));
+    }
+    if options.not_found_as_none {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "`#[exists]` already returns `false` on 404, remove `#[not_found_as_none]`",
+        ));
+    }
+    if !is_bool_result(&sig.output) {
+        return Err(syn::Error::new_spanned(
+            &sig.output,
+            "`#[exists]` methods must return `pincer::Result<bool>`",
+        ));
+    }
+    Ok(())
+}
+
+/// Check the API has an XML (or CBOR) codec if the method sends or reads XML
+/// (or CBOR).
+fn check_codecs(
+    sig: &syn::Signature,
+    params: &[MethodParam],
+    options: &MethodOptions,
+    args: &PincerArgs,
+) -> syn::Result<()> {
+    let uses = |body_format, response_format| {
+        options.response_format == response_format
+            || params.iter().any(
+                |param| matches!(&param.kind, ParamKind::Body(body) if body.format == body_format),
+            )
+    };
+    if args.xml.is_none() && uses(BodyFormat::Xml, ResponseFormat::Xml) {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "XML bodies require a codec: add `xml = \"quick_xml\"` (with the `xml` feature) or `xml = \"path::to::Codec\"` to `#[pincer(...)]`",
+        ));
+    }
+    if (options.deny_unknown_fields || options.pointer.is_some())
+        && (options.response_format != ResponseFormat::Json || args.json.is_some())
+    {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "`deny_unknown_fields` and `pointer` are only supported with JSON responses and the default JSON codec",
+        ));
+    }
+    if args.cbor.is_none() && uses(BodyFormat::Cbor, ResponseFormat::Cbor) {
+        return Err(syn::Error::new_spanned(
+            sig,
+            "CBOR bodies require a codec: add `cbor = \"ciborium\"` (with the `cbor` feature) or `cbor = \"path::to::Codec\"` to `#[pincer(...)]`",
+        ));
+    }
+    Ok(())
+}
+
 /// Find and parse HTTP method attribute from a method's attributes.
 fn find_http_attribute(attrs: &[syn::Attribute]) -> syn::Result<Option<(HttpMethod, String)>> {
     for attr in attrs {
@@ -270,6 +578,16 @@ fn parse_attr_path(attr: &syn::Attribute) -> syn::Result<String> {
     }
 }
 
+/// Kind of a parameter given by its explicit attribute, if any.
+fn explicit_param_kind(pat_type: &syn::PatType) -> syn::Result<Option<ParamKind>> {
+    for attr in &pat_type.attrs {
+        if let Some(kind) = parse_param_attr(attr)? {
+            return Ok(Some(kind));
+        }
+    }
+    Ok(None)
+}
+
 /// Parse method parameters from a trait method.
 ///
 /// Parameters are classified as follows:
@@ -298,7 +616,7 @@ fn parse_trait_method_params(
             let ty = (*pat_type.ty).clone();
 
             // Check for explicit attribute first
-            if let Some(kind) = pat_type.attrs.iter().find_map(parse_param_attr) {
+            if let Some(kind) = explicit_param_kind(pat_type)? {
                 params.push(MethodParam { name, ty, kind });
                 continue;
             }
@@ -309,7 +627,7 @@ fn parse_trait_method_params(
                 params.push(MethodParam {
                     name,
                     ty,
-                    kind: ParamKind::Path(None),
+                    kind: ParamKind::Path(PathOptions::default()),
                 });
                 continue;
             }
@@ -328,7 +646,7 @@ fn parse_trait_method_params(
                 params.push(MethodParam {
                     name,
                     ty,
-                    kind: ParamKind::Body,
+                    kind: ParamKind::Body(BodyOptions::default()),
                 });
             }
         }
@@ -368,11 +686,16 @@ fn parse_trait_method_params(
 }
 
 /// Generate a clean trait without pincer-specific attributes.
+///
+/// With `send_futures`, methods return `impl Future + Send` instead of being
+/// `async fn`, so that generic code (the `{Trait}Dyn` blanket impl) can rely
+/// on the futures being `Send`. `async fn` implementations still satisfy it.
 fn generate_clean_trait(
     vis: &syn::Visibility,
     name: &Ident,
     methods: &[TraitMethodInfo],
     original: &ItemTrait,
+    send_futures: bool,
 ) -> TokenStream {
     // Copy non-pincer attributes from original trait
     let trait_attrs: Vec<_> = original
@@ -388,9 +711,20 @@ fn generate_clean_trait(
         .iter()
         .map(|m| {
             let docs = &m.docs;
-            let sig = strip_pincer_attrs_from_sig(&m.sig);
+            let cfgs = &m.cfgs;
+            let mut sig = strip_pincer_attrs_from_sig(&m.sig);
+            if send_futures {
+                let output = return_type(&sig);
+                sig.asyncness = None;
+                sig.output = syn::parse_quote! {
+                    -> impl ::std::future::Future<Output = #output> + Send
+                };
+            }
+            let deprecated = m.options.deprecated.as_ref().map(|d| &d.attr);
             quote! {
                 #(#docs)*
+                #(#cfgs)*
+                #deprecated
                 #sig;
             }
         })
@@ -405,6 +739,126 @@ fn generate_clean_trait(
     }
 }
 
+/// Lifetime given to the borrows of a `{Trait}Dyn` method.
+const DYN_LIFETIME: &str = "'pincer";
+
+/// Generate the object-safe `{Trait}Dyn` companion trait.
+///
+/// Its methods return boxed futures, and it is implemented for every
+/// implementor of the trait, so services can hold an `Arc<dyn {Trait}Dyn>`.
+fn generate_dyn_trait(
+    vis: &syn::Visibility,
+    name: &Ident,
+    methods: &[TraitMethodInfo],
+) -> TokenStream {
+    let dyn_name = format_ident!("{}Dyn", name);
+    let lifetime = syn::Lifetime::new(DYN_LIFETIME, proc_macro2::Span::call_site());
+    let doc = format!(
+        "Object-safe version of [`{name}`], implemented for every implementor of it.\n\n\
+         Use `Arc<dyn {dyn_name}>` to choose the implementation at runtime, \
+         e.g. a mock in tests. With both traits in scope, call the methods of a \
+         concrete type fully qualified, e.g. `<T as {name}>::method(&client)`."
+    );
+
+    let (signatures, impls): (Vec<_>, Vec<_>) = methods
+        .iter()
+        .map(|m| {
+            let docs = &m.docs;
+            let cfgs = &m.cfgs;
+            let method_name = &m.sig.ident;
+            let mut elided = ElidedLifetimes(lifetime.clone());
+
+            let (arg_names, arg_types): (Vec<_>, Vec<_>) = m
+                .sig
+                .inputs
+                .iter()
+                .filter_map(|arg| match arg {
+                    FnArg::Typed(pat_type) => Some(pat_type),
+                    FnArg::Receiver(_) => None,
+                })
+                .enumerate()
+                .map(|(index, pat_type)| {
+                    let arg_name = match pat_type.pat.as_ref() {
+                        Pat::Ident(pat_ident) => pat_ident.ident.clone(),
+                        _ => format_ident!("__pincer_arg{}", index),
+                    };
+                    let mut ty = (*pat_type.ty).clone();
+                    elided.visit_type_mut(&mut ty);
+                    (arg_name, ty)
+                })
+                .unzip();
+
+            let mut output = return_type(&m.sig);
+            elided.visit_type_mut(&mut output);
+
+            let signature = quote! {
+                fn #method_name<#lifetime>(
+                    &#lifetime self,
+                    #(#arg_names: #arg_types),*
+                ) -> ::std::pin::Pin<Box<dyn ::std::future::Future<Output = #output> + Send + #lifetime>>
+            };
+            let deprecated = m.options.deprecated.as_ref().map(|d| &d.attr);
+            let method_impl = quote! {
+                #(#cfgs)*
+                #[allow(deprecated)]
+                #signature {
+                    Box::pin(<__PincerT as #name>::#method_name(self, #(#arg_names),*))
+                }
+            };
+            (
+                quote! { #(#docs)* #(#cfgs)* #deprecated #signature; },
+                method_impl,
+            )
+        })
+        .unzip();
+
+    quote! {
+        #[doc = #doc]
+        #vis trait #dyn_name: Send + Sync {
+            #(#signatures)*
+        }
+
+        impl<__PincerT: #name + Send + Sync> #dyn_name for __PincerT {
+            #(#impls)*
+        }
+    }
+}
+
+/// Return type of a method, `()` if omitted.
+fn return_type(sig: &syn::Signature) -> syn::Type {
+    match &sig.output {
+        syn::ReturnType::Default => syn::parse_quote! { () },
+        syn::ReturnType::Type(_, ty) => (**ty).clone(),
+    }
+}
+
+/// Give a lifetime to every elided lifetime of a type.
+struct ElidedLifetimes(syn::Lifetime);
+
+impl VisitMut for ElidedLifetimes {
+    fn visit_type_reference_mut(&mut self, reference: &mut syn::TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(self.0.clone());
+        }
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.0.clone();
+        }
+    }
+
+    // Elided lifetimes of `fn(&T)` and `Fn(&T)` are higher-ranked, keep them
+    fn visit_type_bare_fn_mut(&mut self, _: &mut syn::TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments_mut(
+        &mut self,
+        _: &mut syn::ParenthesizedGenericArguments,
+    ) {
+    }
+}
+
 /// Check if an attribute is a pincer parameter attribute.
 fn is_pincer_param_attr(attr: &syn::Attribute) -> bool {
     let path = attr.path();
@@ -435,28 +889,18 @@ fn generate_trait_impl(
     client_name: &Ident,
     methods: &[TraitMethodInfo],
     args: &PincerArgs,
-    trait_headers: &[(String, String)],
 ) -> TokenStream {
-    let user_agent = args.user_agent();
-
     let method_impls: Vec<_> = methods
         .iter()
         .map(|m| {
             let sig = strip_pincer_attrs_from_sig(&m.sig);
-            let attrs = MethodAttrs::new(m.http_method, m.path.clone());
-            let return_type_kind = analyze_return_type(&m.sig.output);
-            let method_name = m.sig.ident.to_string();
-            let body = generate_method_body(
-                &attrs,
-                &m.params,
-                user_agent,
-                &m.options,
-                trait_headers,
-                return_type_kind,
-                &method_name,
-            );
+            let body = generate_method_body(trait_name, m, args);
 
+            let docs = &m.docs;
+            let cfgs = &m.cfgs;
             quote! {
+                #(#docs)*
+                #(#cfgs)*
                 #sig {
                     #body
                 }
@@ -482,28 +926,20 @@ fn generate_wrapper_trait_impl(
     client_name: &Ident,
     methods: &[TraitMethodInfo],
     args: &PincerArgs,
-    trait_headers: &[(String, String)],
 ) -> TokenStream {
-    let user_agent = args.user_agent();
-
     let method_impls: Vec<_> = methods
         .iter()
         .map(|m| {
             let sig = strip_pincer_attrs_from_sig(&m.sig);
-            let attrs = MethodAttrs::new(m.http_method, m.path.clone());
-            let return_type_kind = analyze_return_type(&m.sig.output);
-            let method_name = m.sig.ident.to_string();
-            let body = generate_method_body(
-                &attrs,
-                &m.params,
-                user_agent,
-                &m.options,
-                trait_headers,
-                return_type_kind,
-                &method_name,
-            );
+            // Goes through the wrapper's `PincerClient` impl, which applies
+            // the default headers
+            let body = generate_blanket_method_body(trait_name, m, args);
 
+            let docs = &m.docs;
+            let cfgs = &m.cfgs;
             quote! {
+                #(#docs)*
+                #(#cfgs)*
                 #sig {
                     #body
                 }
@@ -524,32 +960,25 @@ fn generate_wrapper_trait_impl(
 /// ```ignore
 /// impl<T: PincerClient> GitHubApi for T { ... }
 /// ```
+///
+/// In `impl_for` mode, one implementation is generated per listed type
+/// instead.
 fn generate_blanket_impl(
     trait_name: &Ident,
     methods: &[TraitMethodInfo],
     args: &PincerArgs,
-    trait_headers: &[(String, String)],
 ) -> TokenStream {
-    let user_agent = args.user_agent();
-
     let method_impls: Vec<_> = methods
         .iter()
         .map(|m| {
             let sig = strip_pincer_attrs_from_sig(&m.sig);
-            let attrs = MethodAttrs::new(m.http_method, m.path.clone());
-            let return_type_kind = analyze_return_type(&m.sig.output);
-            let method_name = m.sig.ident.to_string();
-            let body = generate_blanket_method_body(
-                &attrs,
-                &m.params,
-                user_agent,
-                &m.options,
-                trait_headers,
-                return_type_kind,
-                &method_name,
-            );
+            let body = generate_blanket_method_body(trait_name, m, args);
 
+            let docs = &m.docs;
+            let cfgs = &m.cfgs;
             quote! {
+                #(#docs)*
+                #(#cfgs)*
                 #sig {
                     #body
                 }
@@ -557,6 +986,17 @@ fn generate_blanket_impl(
         })
         .collect();
 
+    if args.mode == PincerMode::ImplFor {
+        let impls = args.impl_for.iter().map(|target| {
+            quote! {
+                impl #trait_name for #target {
+                    #(#method_impls)*
+                }
+            }
+        });
+        return quote! { #(#impls)* };
+    }
+
     quote! {
         impl<__PincerT: ::pincer::PincerClient> #trait_name for __PincerT {
             #(#method_impls)*
@@ -564,195 +1004,331 @@ fn generate_blanket_impl(
     }
 }
 
-/// Generate the body of a method implementation for blanket impls.
+/// Generate the `{Trait}Requests` struct, with one function per method
+/// building its request without sending it.
 ///
-/// This is similar to `generate_method_body` but uses `PincerClient` trait methods
-/// instead of direct field access, enabling blanket implementations for any `T: PincerClient`.
-fn generate_blanket_method_body(
-    attrs: &MethodAttrs,
-    params: &[MethodParam],
-    user_agent: &str,
-    options: &MethodOptions,
+/// Every generated implementation of the trait builds its requests through
+/// these functions, so other transports get exactly the same requests.
+fn generate_requests_struct(
+    vis: &syn::Visibility,
+    trait_name: &Ident,
+    methods: &[TraitMethodInfo],
+    args: &PincerArgs,
     trait_headers: &[(String, String)],
-    return_type_kind: ReturnTypeKind,
-    method_name: &str,
 ) -> TokenStream {
-    let method_ident = format_ident!("{}", attrs.method.as_str());
-    let path_template = &attrs.path;
-    let url_code = generate_blanket_url_code(&attrs.path, params);
-    let query_code = generate_query_code(params);
-    let headers_code = generate_headers_code(params, user_agent, trait_headers);
-    let pre_body_code = generate_pre_body_code(params);
-    let body_code = generate_body_code(params);
-    let param_metadata_code = generate_parameter_metadata_code(method_name, params);
-
-    // Generate execute code with optional per-method timeout
-    let execute_code = if let Some(timeout) = options.timeout {
-        let secs = timeout.as_secs();
-        let nanos = timeout.subsec_nanos();
+    let requests_name = requests_name(trait_name);
+    let summary = format!(
+        "Requests of the [`{trait_name}`] API, built without being sent, generated by `#[pincer]`."
+    );
+    let request_fns = methods.iter().map(|m| {
+        let method_ident = &m.sig.ident;
+        let generics = &m.sig.generics;
+        let where_clause = &m.sig.generics.where_clause;
+        let inputs = request_fn_inputs(&m.sig);
+        let body = generate_request_body(m, args, trait_headers);
+        let cfgs = &m.cfgs;
+        let doc = format!(" Build the request of [`{trait_name}::{method_ident}`].");
         quote! {
-            let response = ::tokio::time::timeout(
-                ::std::time::Duration::new(#secs, #nanos),
-                ::pincer::PincerClient::execute(self, request)
-            ).await.map_err(|_| ::pincer::Error::Timeout)??;
+            #[doc = #doc]
+            #(#cfgs)*
+            #vis fn #method_ident #generics (
+                base_url: &::pincer::url::Url,
+                #(#inputs),*
+            ) -> ::pincer::Result<::pincer::Request> #where_clause {
+                #body
+            }
         }
-    } else {
-        quote! {
-            let response = ::pincer::PincerClient::execute(self, request).await?;
+    });
+
+    quote! {
+        #[doc = #summary]
+        #[derive(Debug, Clone, Copy)]
+        #vis struct #requests_name;
+
+        impl #requests_name {
+            #(#request_fns)*
         }
-    };
+    }
+}
 
-    // Generate response handling based on return type and options
-    let response_handling = generate_response_handling(options, return_type_kind);
+/// Name of the `{Trait}Requests` struct.
+fn requests_name(trait_name: &Ident) -> Ident {
+    format_ident!("{}Requests", trait_name)
+}
+
+/// Typed inputs of a method, without pincer attributes, to forward to its
+/// request function.
+fn request_fn_inputs(sig: &syn::Signature) -> Vec<syn::PatType> {
+    sig.inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(pat_type) if matches!(pat_type.pat.as_ref(), Pat::Ident(_)) => {
+                let mut pat_type = pat_type.clone();
+                pat_type.attrs.retain(|attr| !is_pincer_param_attr(attr));
+                Some(pat_type)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Generate the body of a request function.
+fn generate_request_body(
+    method: &TraitMethodInfo,
+    args: &PincerArgs,
+    trait_headers: &[(String, String)],
+) -> TokenStream {
+    let params = &method.params;
+    let method_name = &method.sig.ident.to_string();
+    let method_ident = format_ident!("{}", method.http_method.as_str());
+    let path_template = &method.path;
+    let path_code = generate_path_code(path_template, params);
+    let query_code = generate_query_code(params);
+    let headers_code = generate_headers_code(
+        params,
+        args.user_agent(),
+        method.options.accept(),
+        trait_headers,
+    );
+    let client_name_code = args.client_name_extension();
+    // Nobody reads the body of a successful unit response
+    let discard_body_code = (analyze_return_type(&method.sig.output, method.http_method)
+        == ReturnTypeKind::Unit)
+        .then(|| quote! { .extension(::pincer::DiscardBody) });
+    let post_fallback_code = method
+        .options
+        .post_fallback
+        .then(|| quote! { .extension(::pincer::PostFallback) });
+    let url_length_code = args
+        .max_url_length
+        .map(|max| quote! { .and_then(|request| ::pincer::check_url_length(request, #max)) });
+    let max_body_code = method
+        .options
+        .max_body
+        .map(|limit| quote! { .extension(::pincer::InspectHead::max_body(#limit)) });
+    let pre_body_code = generate_pre_body_code(params);
+    let body_code = generate_body_code(
+        params,
+        args.json.as_ref(),
+        args.xml.as_ref(),
+        args.cbor.as_ref(),
+    );
+    let param_metadata_code =
+        generate_parameter_metadata_code(method_name, params, method.options.deprecated.as_ref());
 
     quote! {
-        #url_code
+        #path_code
+        let url = base_url.join(path.as_ref())
+            .map_err(::pincer::Error::InvalidUrl)?;
         #query_code
         #pre_body_code
 
-        let request = ::pincer::Request::builder(
+        ::pincer::Request::builder(
             ::pincer::Method::#method_ident,
             url,
         )
         #headers_code
         #body_code
         .extension(::pincer::PathTemplate::new(#path_template))
+        #client_name_code
+        #discard_body_code
+        #post_fallback_code
+        #max_body_code
         #param_metadata_code
-        .build();
-
-        #execute_code
-        #response_handling
+        .try_build()
+        #url_length_code
     }
 }
 
-/// Generate URL building code for blanket impls using `PincerClient::base_url()`.
-fn generate_blanket_url_code(path_template: &str, params: &[MethodParam]) -> TokenStream {
-    // Find path parameters and generate substitutions
-    let path_params: Vec<_> = params
-        .iter()
-        .filter_map(|p| match &p.kind {
-            ParamKind::Path(alias) => {
-                let name = &p.name;
-                let param_name = p.name.to_string();
-                let key = alias.as_deref().unwrap_or(&param_name);
-                Some((key.to_string(), name.clone()))
-            }
+/// Generate the call of the request function of a method, with the base URL
+/// expression `base_url`.
+fn generate_request_call(
+    trait_name: &Ident,
+    method: &TraitMethodInfo,
+    base_url: &TokenStream,
+) -> TokenStream {
+    let requests_name = requests_name(trait_name);
+    let method_ident = &method.sig.ident;
+    let names = request_fn_inputs(&method.sig)
+        .into_iter()
+        .filter_map(|pat_type| match *pat_type.pat {
+            Pat::Ident(pat_ident) => Some(pat_ident.ident),
             _ => None,
-        })
-        .collect();
-
-    // Build the path with substitutions using percent-encoding
-    let mut path_expr = quote! {
-        const PATH_SEGMENT_ENCODE_SET: &::pincer::percent_encoding::AsciiSet =
-            &::pincer::percent_encoding::CONTROLS
-                .add(b' ')
-                .add(b'"')
-                .add(b'#')
-                .add(b'<')
-                .add(b'>')
-                .add(b'`')
-                .add(b'?')
-                .add(b'{')
-                .add(b'}')
-                .add(b'/')
-                .add(b'\\')
-                .add(b'%');
-        let mut path = #path_template.to_string();
-    };
-
-    for (key, name) in &path_params {
-        let placeholder = format!("{{{key}}}");
-        path_expr = quote! {
-            #path_expr
-            path = path.replace(
-                #placeholder,
-                &::pincer::percent_encoding::utf8_percent_encode(
-                    &#name.to_string(),
-                    PATH_SEGMENT_ENCODE_SET,
-                ).to_string()
-            );
-        };
-    }
-
+        });
     quote! {
-        #path_expr
-        let url = ::pincer::PincerClient::base_url(self).join(&path)
-            .map_err(::pincer::Error::InvalidUrl)?;
+        let request = #requests_name::#method_ident(#base_url, #(#names),*)?;
     }
 }
 
-/// Generate the body of a method implementation.
-fn generate_method_body(
-    attrs: &MethodAttrs,
-    params: &[MethodParam],
-    user_agent: &str,
-    options: &MethodOptions,
-    trait_headers: &[(String, String)],
-    return_type_kind: ReturnTypeKind,
-    method_name: &str,
+/// Generate the body of a method implementation for blanket impls.
+///
+/// This is similar to `generate_method_body` but uses `PincerClient` trait methods
+/// instead of direct field access, enabling blanket implementations for any `T: PincerClient`.
+fn generate_blanket_method_body(
+    trait_name: &Ident,
+    method: &TraitMethodInfo,
+    args: &PincerArgs,
 ) -> TokenStream {
-    let method_ident = format_ident!("{}", attrs.method.as_str());
-    let path_template = &attrs.path;
-    let url_code = generate_url_code(&attrs.path, params);
-    let query_code = generate_query_code(params);
-    let headers_code = generate_headers_code(params, user_agent, trait_headers);
-    let pre_body_code = generate_pre_body_code(params);
-    let body_code = generate_body_code(params);
-    let param_metadata_code = generate_parameter_metadata_code(method_name, params);
+    let options = &method.options;
+    let return_type_kind = analyze_return_type(&method.sig.output, method.http_method);
+    let request_code = generate_request_call(
+        trait_name,
+        method,
+        &quote! { ::pincer::PincerClient::base_url(self) },
+    );
 
     // Generate execute code with optional per-method timeout
     let execute_code = if let Some(timeout) = options.timeout {
         let secs = timeout.as_secs();
         let nanos = timeout.subsec_nanos();
         quote! {
-            let response = ::tokio::time::timeout(
+            let runtime = ::pincer::runtime_or_default(::pincer::PincerClient::runtime(self));
+            let response = ::pincer::timeout(
+                runtime,
                 ::std::time::Duration::new(#secs, #nanos),
-                self.client.execute(request)
-            ).await.map_err(|_| ::pincer::Error::Timeout)??;
+                ::pincer::PincerClient::execute(self, request)
+            ).await??;
         }
     } else {
         quote! {
-            let response = self.client.execute(request).await?;
+            let response = ::pincer::PincerClient::execute(self, request).await?;
         }
     };
 
     // Generate response handling based on return type and options
-    let response_handling = generate_response_handling(options, return_type_kind);
+    let response_handling = generate_response_handling(options, return_type_kind, args);
 
     quote! {
-        #url_code
-        #query_code
-        #pre_body_code
+        #request_code
+        #execute_code
+        #response_handling
+    }
+}
 
-        let request = ::pincer::Request::builder(
-            ::pincer::Method::#method_ident,
-            url,
-        )
-        #headers_code
-        #body_code
-        .extension(::pincer::PathTemplate::new(#path_template))
-        #param_metadata_code
-        .build();
+/// Generate the body of a method implementation.
+///
+/// The timeout configured on the client builder (`timeout_for`) overrides the
+/// `#[timeout]` attribute.
+fn generate_method_body(
+    trait_name: &Ident,
+    method: &TraitMethodInfo,
+    args: &PincerArgs,
+) -> TokenStream {
+    let options = &method.options;
+    let return_type_kind = analyze_return_type(&method.sig.output, method.http_method);
+    let method_name = &method.sig.ident.to_string();
+    let request_code = generate_request_call(trait_name, method, &quote! { &self.base_url });
+
+    // Generate execute code with the per-method timeout
+    let default_timeout = options.timeout.map_or_else(
+        || quote! { None },
+        |timeout| {
+            let secs = timeout.as_secs();
+            let nanos = timeout.subsec_nanos();
+            quote! { Some(::std::time::Duration::new(#secs, #nanos)) }
+        },
+    );
+    // A streamed response is bounded by the timeout until its headers
+    let execute = if return_type_kind == ReturnTypeKind::JsonLines {
+        quote! { ::pincer::HttpClientStreaming::execute_streaming(&self.client, request) }
+    } else {
+        quote! { self.client.execute(request) }
+    };
+    let execute_code = quote! {
+        let timeout = self.timeouts.get(#method_name).copied().or(#default_timeout);
+        let response = match timeout {
+            Some(timeout) => {
+                let runtime = ::pincer::runtime_or_default(::pincer::HttpClient::runtime(&self.client));
+                ::pincer::timeout(runtime, timeout, #execute).await??
+            }
+            None => #execute.await?,
+        };
+    };
+
+    // Generate response handling based on return type and options
+    let response_handling = generate_response_handling(options, return_type_kind, args);
 
+    quote! {
+        #request_code
         #execute_code
         #response_handling
     }
 }
 
+/// Generate response handling code of a `JsonLines` return type, the error
+/// responses being buffered to be decoded.
+fn generate_streaming_response_handling(options: &MethodOptions) -> TokenStream {
+    let not_found = options.not_found_as_none.then(|| {
+        quote! {
+            if response.status() == 404 {
+                return Ok(None);
+            }
+        }
+    });
+    let lines = if options.not_found_as_none {
+        quote! { Ok(Some(response.json_lines())) }
+    } else {
+        quote! { Ok(response.json_lines()) }
+    };
+    quote! {
+        #not_found
+        if !response.is_success() {
+            return Err(::pincer::Error::from_response(response.collect().await?));
+        }
+        #lines
+    }
+}
+
+/// Generate the expression deserializing the response body.
+fn generate_decode(options: &MethodOptions, args: &PincerArgs) -> TokenStream {
+    match options.response_format {
+        ResponseFormat::Csv { delimiter } => quote! { response.csv_with_delimiter(#delimiter) },
+        // The pointer and strict options are checked by `check_codecs`
+        ResponseFormat::Json => match (&options.pointer, options.deny_unknown_fields, &args.json) {
+            (Some(pointer), false, _) => quote! { response.json_at(#pointer) },
+            (Some(pointer), true, _) => quote! { response.json_at_strict(#pointer) },
+            (None, true, _) => quote! { response.json_strict() },
+            (None, false, Some(codec)) => quote! { response.json_with(&#codec) },
+            (None, false, None) => quote! { response.json() },
+        },
+        // Checked by `check_codecs`
+        ResponseFormat::Xml => {
+            let codec = &args.xml;
+            quote! { response.xml_with(&#codec) }
+        }
+        ResponseFormat::Cbor => {
+            let codec = &args.cbor;
+            quote! { response.cbor_with(&#codec) }
+        }
+        ResponseFormat::Yaml => quote! { response.yaml() },
+        ResponseFormat::Toml => quote! { response.toml() },
+    }
+}
+
 /// Generate response handling code based on return type kind and method options.
 fn generate_response_handling(
     options: &MethodOptions,
     return_type_kind: ReturnTypeKind,
+    args: &PincerArgs,
 ) -> TokenStream {
+    let decode = generate_decode(options, args);
+    // Existence check: Result<bool>
+    if options.exists {
+        return quote! {
+            if response.status() == 404 {
+                return Ok(false);
+            }
+            if !response.is_success() {
+                return Err(::pincer::Error::from_response(response));
+            }
+            Ok(true)
+        };
+    }
     match (return_type_kind, options.not_found_as_none) {
         // Unit return type: Result<()> - just check for success
         (ReturnTypeKind::Unit, false) => quote! {
             if !response.is_success() {
-                return Err(::pincer::Error::http_with_body(
-                    response.status(),
-                    format!("HTTP error: {}", response.status()),
-                    response.into_body(),
-                ));
+                return Err(::pincer::Error::from_response(response));
             }
             Ok(())
         },
@@ -762,11 +1338,7 @@ fn generate_response_handling(
                 return Ok(None);
             }
             if !response.is_success() {
-                return Err(::pincer::Error::http_with_body(
-                    response.status(),
-                    format!("HTTP error: {}", response.status()),
-                    response.into_body(),
-                ));
+                return Err(::pincer::Error::from_response(response));
             }
             Ok(Some(()))
         },
@@ -781,30 +1353,58 @@ fn generate_response_handling(
             }
             Ok(Some(response))
         },
+        // Advertised capabilities: Result<AllowedMethods> - read from headers
+        (ReturnTypeKind::AllowedMethods, false) => quote! {
+            if !response.is_success() {
+                return Err(::pincer::Error::from_response(response));
+            }
+            Ok(response.allowed_methods())
+        },
+        // Advertised capabilities with not_found_as_none: Result<Option<AllowedMethods>>
+        (ReturnTypeKind::AllowedMethods, true) => quote! {
+            if response.status() == 404 {
+                return Ok(None);
+            }
+            if !response.is_success() {
+                return Err(::pincer::Error::from_response(response));
+            }
+            Ok(Some(response.allowed_methods()))
+        },
         // JSON: Result<T> - deserialize JSON (default behavior)
         (ReturnTypeKind::Json, false) => quote! {
             if !response.is_success() {
-                return Err(::pincer::Error::http_with_body(
-                    response.status(),
-                    format!("HTTP error: {}", response.status()),
-                    response.into_body(),
-                ));
+                return Err(::pincer::Error::from_response(response));
             }
-            response.json()
+            #decode
         },
+        // Raw body: Result<Bytes> - return the body without the response
+        (ReturnTypeKind::Bytes, false) => quote! {
+            if !response.is_success() {
+                return Err(::pincer::Error::from_response(response));
+            }
+            Ok(response.into_body())
+        },
+        // Raw body with not_found_as_none: Result<Option<Bytes>>
+        (ReturnTypeKind::Bytes, true) => quote! {
+            if response.status() == 404 {
+                return Ok(None);
+            }
+            if !response.is_success() {
+                return Err(::pincer::Error::from_response(response));
+            }
+            Ok(Some(response.into_body()))
+        },
+        // Newline-delimited JSON stream: Result<JsonLines<T>> (full mode only)
+        (ReturnTypeKind::JsonLines, _) => generate_streaming_response_handling(options),
         // JSON with not_found_as_none: Result<Option<T>>
         (ReturnTypeKind::Json, true) => quote! {
             if response.status() == 404 {
                 return Ok(None);
             }
             if !response.is_success() {
-                return Err(::pincer::Error::http_with_body(
-                    response.status(),
-                    format!("HTTP error: {}", response.status()),
-                    response.into_body(),
-                ));
+                return Err(::pincer::Error::from_response(response));
             }
-            response.json().map(Some)
+            #decode.map(Some)
         },
     }
 }
@@ -867,13 +1467,18 @@ fn generate_standalone_method(
     let method_name = fn_name.to_string();
     let url_code = generate_url_code(&attrs.path, &params);
     let query_code = generate_query_code(&params);
-    let headers_code = generate_headers_code(&params, DEFAULT_USER_AGENT, &[]);
+    let options = parse_method_options(&method_fn.attrs)?;
+    check_codecs(sig, &params, &options, &PincerArgs::default())?;
+    let headers_code = generate_headers_code(&params, DEFAULT_USER_AGENT, options.accept(), &[]);
     let pre_body_code = generate_pre_body_code(&params);
-    let body_code = generate_body_code(&params);
-    let param_metadata_code = generate_parameter_metadata_code(&method_name, &params);
+    let body_code = generate_body_code(&params, None, None, None);
+    let deprecated = options.deprecated.as_ref().map(|d| &d.attr);
+    let param_metadata_code =
+        generate_parameter_metadata_code(&method_name, &params, options.deprecated.as_ref());
     let method_ident = format_ident!("{}", attrs.method.as_str());
 
     Ok(quote! {
+        #deprecated
         #vis #asyncness fn #fn_name(#clean_inputs) #output {
             #url_code
             #query_code
@@ -887,15 +1492,12 @@ fn generate_standalone_method(
             #body_code
             .extension(::pincer::PathTemplate::new(#path_template))
             #param_metadata_code
-            .build();
+            .try_build()?;
 
             let response = self.client.execute(request).await?;
 
             if !response.is_success() {
-                return Err(::pincer::Error::http(
-                    response.status(),
-                    format!("HTTP error: {}", response.status()),
-                ));
+                return Err(::pincer::Error::from_response(response));
             }
 
             response.json()
@@ -924,7 +1526,7 @@ fn parse_method_params(
             let ty = (*pat_type.ty).clone();
 
             // Check for explicit attribute first
-            if let Some(kind) = pat_type.attrs.iter().find_map(parse_param_attr) {
+            if let Some(kind) = explicit_param_kind(pat_type)? {
                 params.push(MethodParam { name, ty, kind });
                 continue;
             }
@@ -935,7 +1537,7 @@ fn parse_method_params(
                 params.push(MethodParam {
                     name,
                     ty,
-                    kind: ParamKind::Path(None),
+                    kind: ParamKind::Path(PathOptions::default()),
                 });
                 continue;
             }
@@ -954,7 +1556,7 @@ fn parse_method_params(
                 params.push(MethodParam {
                     name,
                     ty,
-                    kind: ParamKind::Body,
+                    kind: ParamKind::Body(BodyOptions::default()),
                 });
             }
         }
@@ -1011,7 +1613,18 @@ fn strip_pincer_attrs(
 }
 
 /// Generate parameter metadata code for injection into request extensions.
-fn generate_parameter_metadata_code(method_name: &str, params: &[MethodParam]) -> TokenStream {
+fn generate_parameter_metadata_code(
+    method_name: &str,
+    params: &[MethodParam],
+    deprecated: Option<&Deprecated>,
+) -> TokenStream {
+    let deprecated = deprecated.map_or_else(
+        || quote! { None },
+        |deprecated| {
+            let deprecation = deprecated.metadata();
+            quote! { Some(#deprecation) }
+        },
+    );
     let param_metas: Vec<_> = params
         .iter()
         .map(|p| {
@@ -1035,6 +1648,7 @@ fn generate_parameter_metadata_code(method_name: &str, params: &[MethodParam]) -
             .extension(::pincer::ParameterMetadata {
                 method_name: #method_name,
                 parameters: &[],
+                deprecated: #deprecated,
             })
         }
     } else {
@@ -1044,6 +1658,7 @@ fn generate_parameter_metadata_code(method_name: &str, params: &[MethodParam]) -
                 parameters: &[
                     #(#param_metas),*
                 ],
+                deprecated: #deprecated,
             })
         }
     }
@@ -1054,8 +1669,14 @@ fn param_kind_to_location(kind: &ParamKind) -> TokenStream {
     match kind {
         ParamKind::Path(_) => quote! { ::pincer::ParamLocation::Path },
         ParamKind::Query(_) => quote! { ::pincer::ParamLocation::Query },
-        ParamKind::Header(_) | ParamKind::Headers => quote! { ::pincer::ParamLocation::Header },
-        ParamKind::Body => quote! { ::pincer::ParamLocation::Body },
+        ParamKind::Header(_)
+        | ParamKind::Headers
+        | ParamKind::AcceptLanguage
+        | ParamKind::IfMatch => {
+            quote! { ::pincer::ParamLocation::Header }
+        }
+        ParamKind::Cookie(_) => quote! { ::pincer::ParamLocation::Cookie },
+        ParamKind::Body(_) => quote! { ::pincer::ParamLocation::Body },
         ParamKind::Form | ParamKind::Multipart(_) => quote! { ::pincer::ParamLocation::Form },
     }
 }
@@ -1099,6 +1720,135 @@ mod tests {
         assert_eq!(args.user_agent(), "my-app/1.0");
     }
 
+    #[test]
+    fn parse_pincer_args_with_name() {
+        let attr: TokenStream = quote! { url = "https://api.example.com", name = "github" };
+        let args = parse_pincer_args(attr).expect("parse");
+        assert_eq!(args.name, Some("github".to_string()));
+        assert!(
+            args.client_name_extension()
+                .to_string()
+                .contains("ClientName :: new (\"github\")")
+        );
+
+        let args = parse_pincer_args(quote! { url = "https://api.example.com" }).expect("parse");
+        assert!(args.client_name_extension().is_empty());
+    }
+
+    #[test]
+    fn parse_pincer_args_json_codec() {
+        let args =
+            parse_pincer_args(quote! { url = "https://api.example.com", json = "serde_json" })
+                .expect("parse");
+        assert!(args.json.is_none());
+
+        let args =
+            parse_pincer_args(quote! { url = "https://api.example.com", json = "codecs::Simd" })
+                .expect("parse");
+        let codec = args.json.expect("codec");
+        assert_eq!(quote! { #codec }.to_string(), "codecs :: Simd");
+
+        assert!(
+            parse_pincer_args(quote! { url = "https://api.example.com", json = "simd json" })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_pincer_args_xml_codec() {
+        let args =
+            parse_pincer_args(quote! { url = "https://api.example.com", xml = "codecs::Xml" })
+                .expect("parse");
+        let codec = args.xml.expect("codec");
+        assert_eq!(quote! { #codec }.to_string(), "codecs :: Xml");
+
+        let args = parse_pincer_args(quote! { url = "https://api.example.com", xml = "quick_xml" })
+            .expect("parse");
+        let codec = args.xml.expect("codec");
+        assert_eq!(quote! { #codec }.to_string(), ":: pincer :: QuickXml");
+
+        assert!(
+            parse_pincer_args(quote! { url = "https://api.example.com", xml = "quick xml" })
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn parse_pincer_args_cbor_codec() {
+        let args =
+            parse_pincer_args(quote! { url = "https://api.example.com", cbor = "codecs::Cbor" })
+                .expect("parse");
+        let codec = args.cbor.expect("codec");
+        assert_eq!(quote! { #codec }.to_string(), "codecs :: Cbor");
+
+        let args = parse_pincer_args(quote! { url = "https://api.example.com", cbor = "ciborium" })
+            .expect("parse");
+        let codec = args.cbor.expect("codec");
+        assert_eq!(quote! { #codec }.to_string(), ":: pincer :: Ciborium");
+    }
+
+    #[test]
+    fn parse_pincer_args_health() {
+        let args =
+            parse_pincer_args(quote! { url = "https://api.example.com", health = "/healthz" })
+                .expect("parse");
+        assert_eq!(args.health.as_deref(), Some("/healthz"));
+
+        assert!(
+            parse_pincer_args(quote! { url = "https://api.example.com", health = "healthz" })
+                .is_err()
+        );
+        assert!(parse_pincer_args(quote! { mode = "impl_only", health = "/healthz" }).is_err());
+    }
+
+    #[test]
+    fn dyn_trait_gives_lifetimes_to_borrows() {
+        let args = parse_pincer_args(quote! { url = "https://api.example.com", dyn_trait })
+            .expect("parse");
+        assert!(args.dyn_trait);
+
+        let mut ty: syn::Type = syn::parse_quote! { (&str, Option<Cow<'_, str>>, fn(&u8)) };
+        let lifetime = syn::Lifetime::new(DYN_LIFETIME, proc_macro2::Span::call_site());
+        ElidedLifetimes(lifetime).visit_type_mut(&mut ty);
+        let expected: syn::Type =
+            syn::parse_quote! { (&'pincer str, Option<Cow<'pincer, str>>, fn(&u8)) };
+        assert_eq!(ty, expected);
+    }
+
+    #[test]
+    fn parse_pincer_args_impl_for() {
+        let attr: TokenStream =
+            quote! { mode = "impl_for(MyClient, pincer::ApiClient<pincer::HyperClient>)" };
+        let args = parse_pincer_args(attr).expect("parse");
+        assert_eq!(args.mode, PincerMode::ImplFor);
+        let types: Vec<String> = args
+            .impl_for
+            .iter()
+            .map(|ty| quote!(#ty).to_string())
+            .collect();
+        assert_eq!(
+            types,
+            ["MyClient", "pincer :: ApiClient < pincer :: HyperClient >"]
+        );
+    }
+
+    #[test]
+    fn parse_pincer_args_impl_for_invalid() {
+        assert!(parse_pincer_args(quote! { mode = "impl_for()" }).is_err());
+        assert!(parse_pincer_args(quote! { mode = "impl_for(1 + 2)" }).is_err());
+    }
+
+    #[test]
+    fn parse_pincer_args_invalid_url() {
+        let result = parse_pincer_args(quote! { url = "not a url" });
+        let err = result.err().expect("invalid url");
+        assert!(err.to_string().contains("invalid `url`"));
+
+        let result = parse_pincer_args(quote! { url = "mailto:team@example.com" });
+        let err = result.err().expect("not a base url");
+        assert!(err.to_string().contains("must be a base URL"));
+    }
+
     #[test]
     fn parse_pincer_args_missing_url() {
         let attr: TokenStream = quote! { user_agent = "my-app/1.0" };
@@ -1106,6 +1856,107 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn expand_exists_checks() {
+        let expand = |method: TokenStream| {
+            expand_pincer_trait(
+                quote! { url = "https://api.example.com" },
+                quote! { pub trait Api { #method } },
+            )
+        };
+
+        assert!(
+            expand(quote! {
+                #[head("/users/{id}")]
+                #[exists]
+                async fn user_exists(&self, #[path] id: u64) -> pincer::Result<bool>;
+            })
+            .is_ok()
+        );
+
+        let err = expand(quote! {
+            #[head("/users/{id}")]
+            #[exists]
+            async fn user_exists(&self, #[path] id: u64) -> pincer::Result<()>;
+        })
+        .expect_err("not bool");
+        assert!(err.to_string().contains("`pincer::Result<bool>`"), "{err}");
+
+        let err = expand(quote! {
+            #[head("/users/{id}")]
+            #[exists]
+            #[not_found_as_none]
+            async fn user_exists(&self, #[path] id: u64) -> pincer::Result<Option<bool>>;
+        })
+        .expect_err("not_found_as_none");
+        assert!(err.to_string().contains("`#[not_found_as_none]`"), "{err}");
+
+        let err = expand(quote! {
+            #[get("/users/{id}")]
+            #[exists]
+            async fn user_exists(&self, #[path] id: u64) -> pincer::Result<bool>;
+        })
+        .expect_err("not head");
+        assert!(err.to_string().contains("`#[head]`"), "{err}");
+    }
+
+    #[test]
+    fn expand_allowed_methods_only_for_options() {
+        let expand = |method: TokenStream| {
+            expand_pincer_trait(
+                quote! { url = "https://api.example.com" },
+                quote! { pub trait Api { #method } },
+            )
+            .expect("expand")
+            .to_string()
+        };
+
+        let output = expand(quote! {
+            #[options("/users")]
+            async fn users_options(&self) -> pincer::Result<AllowedMethods>;
+        });
+        assert!(output.contains("allowed_methods ()"), "{output}");
+
+        let output = expand(quote! {
+            #[get("/allowed-methods")]
+            async fn allowed_methods(&self) -> pincer::Result<AllowedMethods>;
+        });
+        assert!(
+            !output.contains("response . allowed_methods ()"),
+            "{output}"
+        );
+        assert!(output.contains("response . json"), "{output}");
+    }
+
+    #[test]
+    fn expand_max_url_length() {
+        let method = quote! {
+            #[get("/search")]
+            #[post_fallback]
+            async fn search(&self, #[query] q: &str) -> pincer::Result<()>;
+        };
+        let output = expand_pincer_trait(
+            quote! { url = "https://api.example.com", max_url_length = 2048 },
+            quote! { pub trait Api { #method } },
+        )
+        .expect("expand")
+        .to_string();
+        assert!(
+            output.contains(":: pincer :: check_url_length (request , 2048usize)"),
+            "{output}"
+        );
+
+        let err = expand_pincer_trait(
+            quote! { url = "https://api.example.com" },
+            quote! { pub trait Api { #method } },
+        )
+        .expect_err("post_fallback without max_url_length");
+        assert!(
+            err.to_string().contains("requires `max_url_length`"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn expand_custom_http_get() {
         let attr: TokenStream = quote! { "GET /users/{id}" };
@@ -4,7 +4,7 @@
 //! - `#[pincer]` - Mark a trait as a pincer HTTP client
 //! - `#[get]`, `#[post]`, `#[put]`, `#[delete]`, `#[patch]`, `#[head]`, `#[options]` - HTTP method attributes
 //! - `#[http("VERB /path")]` - Custom HTTP method attribute for extensibility
-//! - `#[path]`, `#[query]`, `#[header]`, `#[body]`, `#[form]` - Parameter attributes
+//! - `#[path]`, `#[query]`, `#[header]`, `#[cookie]`, `#[body]`, `#[form]` - Parameter attributes
 //! - `#[derive(Query)]` - Derive macro for struct-based query parameters
 //!
 //! # Example
@@ -27,6 +27,7 @@ mod attrs;
 mod codegen;
 mod expand;
 mod query_derive;
+mod uri_template;
 
 use proc_macro::TokenStream;
 
@@ -38,11 +39,150 @@ use crate::attrs::HttpMethod;
 /// - A clean trait (without pincer attributes)
 /// - A client struct implementing the trait (e.g., `GitHubApiClient`)
 /// - A builder struct for constructing the client (e.g., `GitHubApiClientBuilder`)
+/// - A requests struct building each method's request without sending it
+///   (e.g., `GitHubApiRequests::get_user(&base_url, "octocat")`), to reuse
+///   with other transports
 ///
 /// # Attributes
 ///
 /// - `url` (required): The base URL for the client
 /// - `user_agent` (optional): Custom User-Agent header
+/// - `max_url_length` (optional): Maximum length of the request URLs, in
+///   bytes, e.g. `max_url_length = 8192`: the request functions fail with
+///   `Error::UrlTooLong` over it, since some gateways silently truncate long
+///   query strings
+/// - `name` (optional): Client name added to requests as a `ClientName`
+///   extension, to tell clients apart in logs and metrics
+/// - `mode` (optional): What to generate
+///   - `"full"` (default): client struct and builder
+///   - `"wrapper"`: generic client struct over any `PincerClient`
+///   - `"impl_only"`: blanket impl of the trait for every `PincerClient`
+///   - `"impl_for(MyClient, ApiClient<HyperClient>)"`: impls for the listed
+///     types only, to avoid method ambiguity between several traits
+/// - `json` (optional): `BodyCodec` used for JSON bodies, the path of a
+///   constant or unit struct, e.g. `json = "STRICT_JSON"` with
+///   `const STRICT_JSON: SerdeJson = SerdeJson::new().big_integers_as_strings(true);`
+///   (default: `"serde_json"`). The responses of a custom codec are decoded
+///   with its own options only: the `strict_json` setting of the client does
+///   not apply to them, set `deny_unknown_fields` on the codec instead.
+/// - `dyn_trait` (optional): Also generate `GitHubApiDyn`, an object-safe
+///   version of the trait implemented for every implementor, to use as
+///   `Arc<dyn GitHubApiDyn>`. Trait methods then return `impl Future + Send`.
+///   The generated impl forwards with fully qualified calls. Calling a method
+///   directly on a client type is ambiguous when both traits are in scope, so
+///   call it through `Arc<dyn GitHubApiDyn>` or fully qualified, e.g.
+///   `<GitHubApiClient as GitHubApi>::get_user(&client, "octocat")`.
+/// - `xml` (optional): `BodyCodec` used for XML bodies: `"quick_xml"` for
+///   `pincer::QuickXml` (requires the `xml` feature), or the path of a
+///   constant or unit struct. Required by the methods below sending or
+///   reading XML.
+/// - `cbor` (optional): `BodyCodec` used for CBOR bodies: `"ciborium"` for
+///   `pincer::Ciborium` (requires the `cbor` feature), or the path of a
+///   constant or unit struct. Required by the methods sending or reading CBOR.
+/// - `health` (optional, full and wrapper modes): Path of the health endpoint,
+///   e.g. `health = "/healthz"`. The client gets `health()`, returning
+///   `Ok(true)` on 2xx, and `watch_health(interval)`, returning a
+///   `HealthWatcher` probing it in the background.
+///
+/// A body parameter can be serialized with a serde `serialize_with` function:
+/// `#[body(serialize_with = "my_serializer")] user: &User`.
+///
+/// An XML body is marked with `#[body(format = "xml")]`. A method reading an
+/// XML response is marked with `#[response(xml)]`, or with an XML `Accept`
+/// header: `#[accept("application/xml")]`. Both use the API's `xml` codec.
+/// CBOR works the same with `#[body(format = "cbor")]`, `#[response(cbor)]`
+/// or `#[accept("application/cbor")]`, and the API's `cbor` codec.
+///
+/// A method marked with `#[response(deny_unknown_fields)]` fails on the JSON
+/// fields unknown to its return type, to surface the drift of an API
+/// contract. A whole client decodes strictly with the `strict_json` setting
+/// of its configuration, which `PINCER_STRICT_JSON` overrides, e.g. strict
+/// in tests while lenient in production. This setting does not apply to the
+/// APIs with a custom `json` codec.
+///
+/// A method marked with `#[response(pointer = "/data/items")]` returns the
+/// value at this JSON pointer of the response, without structs wrapping it.
+///
+/// A method returning `Vec<T>` from a CSV export is marked with
+/// `#[response(csv)]`, or `#[response(csv, delimiter = ';')]`, which require
+/// the `csv` feature of pincer. The first record is the header.
+///
+/// A method reading YAML or TOML, e.g. configuration files served over HTTP,
+/// is marked with `#[response(yaml)]` or `#[response(toml)]`, which require
+/// the `yaml` or `toml` feature of pincer.
+///
+/// With the `streaming` feature, a method of a full-mode client can return
+/// `pincer::Result<JsonLines<T>>`: the newline-delimited JSON body is
+/// deserialized line by line as it arrives, instead of being buffered. The
+/// `Accept` header defaults to `application/x-ndjson`. Lines are limited to
+/// `pincer::DEFAULT_MAX_LINE_LENGTH` bytes, see `JsonLines::max_line_length`.
+/// Such a method sends its request with `HttpClientStreaming::execute_streaming`,
+/// which bypasses the middleware of the client (auth, retries, logging, ...):
+/// only the connection pool is shared with the other methods.
+///
+/// A `#[head]` method marked with `#[exists]` returns `pincer::Result<bool>`:
+/// `true` on 2xx, `false` on 404, and an error for any other status.
+///
+/// An `#[options]` method can return `pincer::Result<AllowedMethods>` to read
+/// the `Allow` and `Access-Control-*` headers instead of `()`. Other methods
+/// returning a type named `AllowedMethods` decode it from the JSON body.
+///
+/// A method returning `pincer::Result<bytes::Bytes>` gets the raw body, e.g.
+/// for binary downloads, and an error for a non-2xx status. The `Accept`
+/// header defaults to `*/*`.
+///
+/// A method returning `pincer::Result<()>` does not download the body of a
+/// successful response: its request carries the `DiscardBody` extension.
+///
+/// Path parameters are percent-encoded, except `#[path(raw)]` ones, inserted
+/// verbatim for values already percent-encoded (e.g. an identifier holding
+/// `%2F`); the caller is then responsible for their encoding. An alias and
+/// options combine, e.g. `#[path("object_id", raw)]`, and an unknown option
+/// is a compile error.
+///
+/// Path templates accept RFC 6570 expressions besides `{name}`, e.g.
+/// `#[get("/files{/segments*}{.ext}{?q,page}")]`, with the `+`, `#`, `.`,
+/// `/`, `;`, `?` and `&` operators and the `*` explode modifier (not prefix
+/// modifiers). Their variables are path parameters: an `Option` is left out
+/// when `None`, and a `Vec` or slice expands to a list.
+///
+/// A method marked with `#[max_body(1048576)]` fails without downloading a
+/// successful response whose `Content-Length` is over this many bytes; a body
+/// without `Content-Length` is cut at the limit, failing its decoding. Its
+/// request carries the `InspectHead::max_body` extension. Error bodies keep
+/// the error body limit of the client.
+///
+/// A `#[get]` method marked with `#[post_fallback]` is built as a `POST`,
+/// with its query string in a form body, when its URL is over the
+/// `max_url_length` of the API, which it requires: its request carries the
+/// `PostFallback` extension. The check is done by the request functions, so
+/// the middleware sees the request as it is sent.
+///
+/// A `#[query]` or `#[path]` parameter of a timestamp type, `SystemTime` or
+/// any type convertible into one (e.g. `chrono::DateTime<Utc>`), takes a
+/// `format`: `#[query(format = "rfc3339")]`, `"unix"`, `"unix_millis"` or a
+/// `strftime`-like pattern such as `#[path(format = "%Y-%m-%d")]`, applied by
+/// `pincer::format_timestamp` instead of `Display`. `rfc3339` is to the
+/// second, dropping the fraction of the second: keep it with a pattern such
+/// as `"%Y-%m-%dT%H:%M:%S.%3fZ"`. An unknown `format` is a compile error.
+///
+/// A `#[query(format = "deep_object")]` parameter, a type deriving `Query` or
+/// a map, is encoded in the `deepObject` style of `OpenAPI`:
+/// `?filter[status]=active&filter[type]=user`.
+///
+/// A `#[header("X-Request-Id")]` parameter is any `Display` type, e.g. a
+/// number; an `Option` header is left out when `None`.
+///
+/// # Generated struct attributes
+///
+/// `#[pincer_client_attrs(...)]`, placed after `#[pincer]`, lists attributes
+/// applied to the generated client and builder structs:
+///
+/// ```ignore
+/// #[pincer(url = "https://api.github.com")]
+/// #[pincer_client_attrs(derive(Debug), must_use)]
+/// pub trait GitHubApi { ... }
+/// ```
 ///
 /// # Example
 ///
@@ -190,13 +330,34 @@ pub fn http(attr: TokenStream, item: TokenStream) -> TokenStream {
         .into()
 }
 
-/// Derive the `ToQueryPairs` trait for a struct.
+/// Derive the `ToQueryPairs` trait for a struct or an enum.
 ///
 /// This generates a method to convert the struct into query parameter pairs.
+/// Generic structs are supported, with the bounds their fields need (e.g.
+/// `T: Display` for a field of type `T`).
+///
+/// `Option<T>` fields are skipped when `None`, `Vec<T>` fields follow their
+/// collection format, and `HashMap` / `BTreeMap` fields add one pair per entry,
+/// keyed by the entry key instead of the field name.
+///
+/// # Enums
+///
+/// An enum of fieldless variants implements `Display` instead, with the
+/// variant names renamed by `rename_all` (like serde) or `#[query(rename = "...")]`
+/// on a variant, to be used as the value of a field (e.g. `sort=name_asc`).
+/// The value is also returned by its generated `as_query_value` method. An
+/// enum implementing `Display` itself is marked with `#[query(no_display)]`,
+/// and its fields with `#[query(serialize_with = "Sort::as_query_value")]`.
+///
+/// An enum with struct variants implements `ToQueryPairs` with the fields of
+/// the current variant, renamed by `rename_all`; unit variants add no pairs.
+/// Tuple variants are not supported.
 ///
 /// # Struct Attributes
 ///
 /// - `#[query(rename_all = "camelCase")]` - Rename all fields using a case convention
+/// - `#[query(no_display)]` - Do not implement `Display` for an enum of fieldless
+///   variants, e.g. already implementing it for humans
 ///
 /// Supported case conventions:
 /// - `lowercase`, `UPPERCASE`
@@ -209,6 +370,18 @@ pub fn http(attr: TokenStream, item: TokenStream) -> TokenStream {
 /// - `#[query(skip_none)]` - Skip the field if it's `None` (default for `Option<T>`)
 /// - `#[query(rename = "name")]` - Use a different name in the query string (overrides `rename_all`)
 /// - `#[query(format = "csv")]` - Collection format for `Vec<T>` (csv, ssv, pipes, multi)
+/// - `#[query(format = "deep_object")]` - Pairs of a nested `ToQueryPairs` type or map
+///   (or `Option` of it) under the field name, `filter[status]=active`
+/// - `#[query(format = "rfc3339")]` - Timestamp format of a `SystemTime` value (or items),
+///   like the method parameters: `rfc3339`, `unix`, `unix_millis` or a `strftime`-like pattern
+/// - `#[query(flatten)]` - Add the pairs of a nested `ToQueryPairs` type (or `Option` of it),
+///   like `#[serde(flatten)]`; `rename` and `rename_all` do not apply to its keys
+/// - `#[query(skip)]` - Never add the field to the query string
+/// - `#[query(serialize_with = "path::to::fn")]` - Convert the value with a
+///   `fn(&T) -> String` (or `&str`) instead of `ToString` (`T` is the inner type of an `Option`,
+///   or the item type of a `Vec`), e.g. for epoch millis or base64 tokens
+/// - `#[query(skip_if = "path::to::fn")]` - Skip the field when a `fn(&Field) -> bool`
+///   returns `true`, like `#[serde(skip_serializing_if)]`
 ///
 /// # Example
 ///
@@ -225,6 +398,19 @@ pub fn http(attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     #[query(format = "csv")]
 ///     tag_list: Vec<String>,     // becomes "tagList"
 /// }
+///
+/// #[derive(Query)]
+/// #[query(rename_all = "snake_case")]
+/// enum Sort {
+///     NameAsc,                   // becomes "name_asc"
+///     NameDesc,
+/// }
+///
+/// #[derive(Query)]
+/// enum Filter {
+///     All,                       // no pairs
+///     Range { from: u32, to: u32 },
+/// }
 /// ```
 #[proc_macro_derive(Query, attributes(query))]
 pub fn derive_query(input: TokenStream) -> TokenStream {
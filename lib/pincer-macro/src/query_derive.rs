@@ -1,14 +1,20 @@
 //! Query derive macro implementation.
 
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
 use syn::{DeriveInput, Fields, Type, parse2};
 
+use crate::attrs::is_timestamp_format;
+
 /// Struct-level options parsed from `#[query(...)]` attributes.
 #[derive(Debug, Clone, Default)]
 struct QueryStructOptions {
     /// Rename all fields using the given case convention.
     rename_all: Option<RenameRule>,
+    /// Do not implement `Display` for an enum of fieldless variants, which
+    /// implements it itself.
+    no_display: bool,
 }
 
 /// Case conversion rules for `rename_all`.
@@ -49,6 +55,14 @@ impl RenameRule {
         }
     }
 
+    /// Apply the rename rule to a `PascalCase` variant name, like serde.
+    fn apply_to_variant(self, name: &str) -> String {
+        match self {
+            Self::LowerCase | Self::UpperCase | Self::PascalCase => self.apply(name),
+            _ => self.apply(&to_snake_case(name)),
+        }
+    }
+
     /// Apply the rename rule to a field name.
     fn apply(self, name: &str) -> String {
         match self {
@@ -114,70 +128,243 @@ struct QueryFieldOptions {
     skip_none: bool,
     /// Rename the field in query string
     rename: Option<String>,
-    /// Collection format for Vec<T> fields
+    /// Collection format for Vec<T> fields, `deep_object` for nested values,
+    /// or timestamp format of the value
     format: Option<String>,
+    /// Add the pairs of a nested `ToQueryPairs` value to the parent
+    flatten: bool,
+    /// Never add this field to the query string
+    skip: bool,
+    /// Function converting the value to a string, instead of `ToString`
+    serialize_with: Option<syn::Path>,
+    /// Predicate skipping the field when it returns `true`
+    skip_if: Option<syn::Path>,
 }
 
 /// Expand the `#[derive(Query)]` macro.
 pub fn expand_query_derive(input: TokenStream) -> syn::Result<TokenStream> {
     let input: DeriveInput = parse2(input)?;
-    let name = &input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
     // Parse struct-level options
     let struct_options = parse_query_struct_options(&input.attrs)?;
 
-    // Only support structs with named fields
-    let fields = match &input.data {
+    match &input.data {
         syn::Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => expand_struct(&input, &struct_options, fields),
+            _ => Err(syn::Error::new_spanned(
+                &input,
+                "Query derive only supports structs with named fields",
+            )),
+        },
+        syn::Data::Enum(data) => expand_enum(&input, &struct_options, data),
+        syn::Data::Union(_) => Err(syn::Error::new_spanned(
+            &input,
+            "Query derive only supports structs and enums",
+        )),
+    }
+}
+
+/// Generate `ToQueryPairs` for a struct with named fields.
+fn expand_struct(
+    input: &DeriveInput,
+    struct_options: &QueryStructOptions,
+    fields: &syn::FieldsNamed,
+) -> syn::Result<TokenStream> {
+    let field_handlers = generate_field_handlers(&fields.named, struct_options, |field_name| {
+        quote! { self.#field_name }
+    })?;
+
+    Ok(impl_to_query_pairs(
+        input,
+        &quote! {
+            #(#field_handlers)*
+        },
+    ))
+}
+
+/// Generate the trait implementations of an enum.
+///
+/// An enum of fieldless variants gets an `as_query_value` method returning
+/// the (renamed) variant names, and implements `Display` with it (unless
+/// `no_display`), to be used as a field value. Otherwise, it implements
+/// `ToQueryPairs` with the fields of its struct variants, unit variants
+/// adding no pairs.
+fn expand_enum(
+    input: &DeriveInput,
+    struct_options: &QueryStructOptions,
+    data: &syn::DataEnum,
+) -> syn::Result<TokenStream> {
+    if data
+        .variants
+        .iter()
+        .all(|variant| matches!(variant.fields, Fields::Unit))
+    {
+        return expand_fieldless_enum(input, struct_options, data);
+    }
+
+    let mut arms = Vec::new();
+    for variant in &data.variants {
+        let variant_name = &variant.ident;
+        let fields = match &variant.fields {
             Fields::Named(fields) => &fields.named,
-            _ => {
+            Fields::Unit => {
+                arms.push(quote! { Self::#variant_name => {} });
+                continue;
+            }
+            Fields::Unnamed(_) => {
                 return Err(syn::Error::new_spanned(
-                    &input,
-                    "Query derive only supports structs with named fields",
+                    variant,
+                    "Query derive only supports unit and struct variants",
                 ));
             }
+        };
+
+        // Bind the fields to prefixed names, not to shadow `pairs`
+        let mut bindings = Vec::new();
+        for field in fields {
+            let Some(field_name) = field.ident.as_ref() else {
+                continue;
+            };
+            if !parse_query_field_options(&field.attrs)?.skip {
+                let binding = binding_name(field_name);
+                bindings.push(quote! { #field_name: #binding });
+            }
+        }
+        let field_handlers = generate_field_handlers(fields, struct_options, |field_name| {
+            let binding = binding_name(field_name);
+            quote! { (*#binding) }
+        })?;
+        arms.push(quote! {
+            Self::#variant_name { #(#bindings,)* .. } => {
+                #(#field_handlers)*
+            }
+        });
+    }
+
+    Ok(impl_to_query_pairs(
+        input,
+        &quote! {
+            match self {
+                #(#arms)*
+            }
         },
-        _ => {
-            return Err(syn::Error::new_spanned(
-                &input,
-                "Query derive only supports structs",
-            ));
+    ))
+}
+
+/// Generate `as_query_value` and `Display` for an enum of fieldless variants.
+fn expand_fieldless_enum(
+    input: &DeriveInput,
+    struct_options: &QueryStructOptions,
+    data: &syn::DataEnum,
+) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut arms = Vec::new();
+    for variant in &data.variants {
+        let variant_name = &variant.ident;
+        let options = parse_query_field_options(&variant.attrs)?;
+        // Determine the value: explicit rename > rename_all > variant name
+        let value = if let Some(rename) = options.rename {
+            rename
+        } else if let Some(rule) = struct_options.rename_all {
+            rule.apply_to_variant(&variant_name.to_string())
+        } else {
+            variant_name.to_string()
+        };
+        arms.push(quote! { Self::#variant_name => #value, });
+    }
+
+    let display = (!struct_options.no_display).then(|| {
+        quote! {
+            impl #impl_generics ::std::fmt::Display for #name #ty_generics #where_clause {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    f.write_str(self.as_query_value())
+                }
+            }
         }
-    };
+    });
 
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Value of the variant in a query string.
+            #[must_use]
+            pub const fn as_query_value(&self) -> &'static str {
+                match *self {
+                    #(#arms)*
+                }
+            }
+        }
+
+        #display
+    })
+}
+
+/// Name of the binding of a field of an enum variant.
+fn binding_name(field_name: &syn::Ident) -> syn::Ident {
+    format_ident!("__query_{}", field_name.unraw())
+}
+
+/// Generate `ToQueryPairs` adding pairs with `body`.
+fn impl_to_query_pairs(input: &DeriveInput, body: &TokenStream) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::pincer::ToQueryPairs for #name #ty_generics #where_clause {
+            fn to_query_pairs(&self) -> ::std::vec::Vec<(::std::string::String, ::std::string::String)> {
+                let mut pairs = ::std::vec::Vec::new();
+                #body
+                pairs
+            }
+        }
+    }
+}
+
+/// Generate the code adding the pairs of `fields`, each accessed with the
+/// place expression returned by `access`.
+fn generate_field_handlers<'a>(
+    fields: impl IntoIterator<Item = &'a syn::Field>,
+    struct_options: &QueryStructOptions,
+    access: impl Fn(&syn::Ident) -> TokenStream,
+) -> syn::Result<Vec<TokenStream>> {
     let mut field_handlers = Vec::new();
 
     for field in fields {
-        // Safe: we've already verified this is a struct with named fields
+        // Safe: we've already verified the fields are named
         let Some(field_name) = field.ident.as_ref() else {
             continue;
         };
         let field_ty = &field.ty;
         let options = parse_query_field_options(&field.attrs)?;
+        if options.skip {
+            continue;
+        }
 
         // Determine the key: explicit rename > rename_all > field name
         let key = if let Some(ref rename) = options.rename {
             rename.clone()
         } else if let Some(rule) = struct_options.rename_all {
-            rule.apply(&field_name.to_string())
+            rule.apply(&field_name.unraw().to_string())
         } else {
-            field_name.to_string()
+            field_name.unraw().to_string()
         };
 
-        let handler = generate_field_handler(field_name, field_ty, &key, &options);
+        let field = access(field_name);
+        let handler = generate_field_handler(&field, field_ty, &key, &options);
+        let handler = if let Some(ref skip_if) = options.skip_if {
+            quote! {
+                if !#skip_if(&#field) {
+                    #handler
+                }
+            }
+        } else {
+            handler
+        };
         field_handlers.push(handler);
     }
 
-    Ok(quote! {
-        impl #impl_generics ::pincer::ToQueryPairs for #name #ty_generics #where_clause {
-            fn to_query_pairs(&self) -> ::std::vec::Vec<(::std::string::String, ::std::string::String)> {
-                let mut pairs = ::std::vec::Vec::new();
-                #(#field_handlers)*
-                pairs
-            }
-        }
-    })
+    Ok(field_handlers)
 }
 
 /// Parse struct-level options from `#[query(...)]` attributes.
@@ -204,6 +391,8 @@ fn parse_query_struct_options(attrs: &[syn::Attribute]) -> syn::Result<QueryStru
                     )
                 })?;
                 options.rename_all = Some(rule);
+            } else if meta.path.is_ident("no_display") {
+                options.no_display = true;
             }
             Ok(())
         })?;
@@ -229,7 +418,40 @@ fn parse_query_field_options(attrs: &[syn::Attribute]) -> syn::Result<QueryField
                 options.rename = Some(value.value());
             } else if meta.path.is_ident("format") {
                 let value: syn::LitStr = meta.value()?.parse()?;
-                options.format = Some(value.value());
+                let format = value.value();
+                let known = matches!(
+                    format.as_str(),
+                    "csv"
+                        | "comma"
+                        | "ssv"
+                        | "space"
+                        | "pipes"
+                        | "pipe"
+                        | "multi"
+                        | "deep_object"
+                        | "deepObject"
+                );
+                if !known && !is_timestamp_format(&format) {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        format!(
+                            "unknown query format \"{format}\": expected csv, ssv, pipes, \
+                             multi, deep_object, rfc3339, unix, unix_millis \
+                             or a strftime-like pattern"
+                        ),
+                    ));
+                }
+                options.format = Some(format);
+            } else if meta.path.is_ident("flatten") {
+                options.flatten = true;
+            } else if meta.path.is_ident("skip") {
+                options.skip = true;
+            } else if meta.path.is_ident("serialize_with") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                options.serialize_with = Some(value.parse()?);
+            } else if meta.path.is_ident("skip_if") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                options.skip_if = Some(value.parse()?);
             }
             Ok(())
         })?;
@@ -240,64 +462,112 @@ fn parse_query_field_options(attrs: &[syn::Attribute]) -> syn::Result<QueryField
 
 /// Generate code for handling a single field.
 fn generate_field_handler(
-    field_name: &syn::Ident,
+    field: &TokenStream,
     field_ty: &Type,
     key: &str,
     options: &QueryFieldOptions,
 ) -> TokenStream {
     let is_option = is_option_type(field_ty);
     let is_vec = is_vec_type(field_ty);
+    let is_map = is_map_type(field_ty);
+    let is_deep_object = matches!(
+        options.format.as_deref(),
+        Some("deep_object" | "deepObject")
+    );
+    // Convert a reference to a value (or an item) to a string
+    let time_format = options
+        .format
+        .as_deref()
+        .filter(|format| is_timestamp_format(format));
+    let to_string = |value: TokenStream| {
+        if let Some(ref serialize_with) = options.serialize_with {
+            quote! { ::std::string::String::from(#serialize_with(#value)) }
+        } else if let Some(format) = time_format {
+            quote! { ::pincer::format_timestamp((#value).clone(), #format) }
+        } else {
+            quote! { (#value).to_string() }
+        }
+    };
 
-    if is_option {
+    if options.flatten {
+        // Nested ToQueryPairs: its own keys, the field key is unused
+        if is_option {
+            quote! {
+                if let Some(ref value) = #field {
+                    pairs.extend(::pincer::ToQueryPairs::to_query_pairs(value));
+                }
+            }
+        } else {
+            quote! {
+                pairs.extend(::pincer::ToQueryPairs::to_query_pairs(&#field));
+            }
+        }
+    } else if is_deep_object {
+        // Nested ToQueryPairs or map: its pairs under the field key, `key[field]`
+        if is_option {
+            quote! {
+                if let Some(ref value) = #field {
+                    pairs.extend(::pincer::deep_object_pairs(
+                        #key,
+                        ::pincer::ToQueryPairs::to_query_pairs(value),
+                    ));
+                }
+            }
+        } else {
+            quote! {
+                pairs.extend(::pincer::deep_object_pairs(
+                    #key,
+                    ::pincer::ToQueryPairs::to_query_pairs(&#field),
+                ));
+            }
+        }
+    } else if is_option {
         // Option<T>: skip if None (skip_none is default behavior for Option)
+        let value = to_string(quote! { value });
+        quote! {
+            if let Some(ref value) = #field {
+                pairs.push((#key.to_string(), #value));
+            }
+        }
+    } else if is_map {
+        // HashMap / BTreeMap: one pair per entry, the field key is unused
+        let value = to_string(quote! { value });
         quote! {
-            if let Some(ref value) = self.#field_name {
-                pairs.push((#key.to_string(), value.to_string()));
+            for (key, value) in &#field {
+                pairs.push((key.to_string(), #value));
             }
         }
     } else if is_vec {
-        let format = options.format.as_deref().unwrap_or("multi");
-        match format {
-            "csv" | "comma" => quote! {
-                if !self.#field_name.is_empty() {
-                    let value = self.#field_name.iter()
-                        .map(|x| x.to_string())
-                        .collect::<::std::vec::Vec<_>>()
-                        .join(",");
-                    pairs.push((#key.to_string(), value));
-                }
-            },
-            "ssv" | "space" => quote! {
-                if !self.#field_name.is_empty() {
-                    let value = self.#field_name.iter()
-                        .map(|x| x.to_string())
-                        .collect::<::std::vec::Vec<_>>()
-                        .join(" ");
-                    pairs.push((#key.to_string(), value));
-                }
-            },
-            "pipes" | "pipe" => quote! {
-                if !self.#field_name.is_empty() {
-                    let value = self.#field_name.iter()
-                        .map(|x| x.to_string())
+        let item = to_string(quote! { item });
+        let separator = match options.format.as_deref() {
+            Some("csv" | "comma") => Some(","),
+            Some("ssv" | "space") => Some(" "),
+            Some("pipes" | "pipe") => Some("|"),
+            _ => None,
+        };
+        if let Some(separator) = separator {
+            quote! {
+                if !#field.is_empty() {
+                    let value = #field.iter()
+                        .map(|item| #item)
                         .collect::<::std::vec::Vec<_>>()
-                        .join("|");
+                        .join(#separator);
                     pairs.push((#key.to_string(), value));
                 }
-            },
-            _ => {
-                // "multi" - repeated parameters
-                quote! {
-                    for item in &self.#field_name {
-                        pairs.push((#key.to_string(), item.to_string()));
-                    }
+            }
+        } else {
+            // "multi" - repeated parameters
+            quote! {
+                for item in &#field {
+                    pairs.push((#key.to_string(), #item));
                 }
             }
         }
     } else {
         // Simple type
+        let value = to_string(quote! { &#field });
         quote! {
-            pairs.push((#key.to_string(), self.#field_name.to_string()));
+            pairs.push((#key.to_string(), #value));
         }
     }
 }
@@ -315,3 +585,10 @@ fn is_vec_type(ty: &Type) -> bool {
         if type_path.path.segments.last()
             .is_some_and(|seg| seg.ident == "Vec"))
 }
+
+/// Check if a type is `HashMap<K, V>` or `BTreeMap<K, V>`.
+fn is_map_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path)
+        if type_path.path.segments.last()
+            .is_some_and(|seg| seg.ident == "HashMap" || seg.ident == "BTreeMap"))
+}
@@ -0,0 +1,191 @@
+//! RFC 6570 URI template expressions of path templates.
+//!
+//! Besides simple `{name}` placeholders, path templates accept expressions
+//! with an operator and several variables, e.g. `{/segments*}` or `{?q,page}`.
+//! Prefix modifiers (`{name:3}`) are not supported.
+
+/// Operator of an expression, deciding its prefix, separator and encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operator {
+    /// `{name}`: comma-separated values
+    Simple,
+    /// `{+name}`: like simple, keeping reserved characters such as `/`
+    Reserved,
+    /// `{#name}`: fragment, `#` then reserved expansion
+    Fragment,
+    /// `{.name}`: labels, `.a.b`
+    Label,
+    /// `{/name}`: path segments, `/a/b`
+    PathSegment,
+    /// `{;name}`: path parameters, `;name=a`
+    PathParameter,
+    /// `{?name}`: query, `?name=a&other=b`
+    Query,
+    /// `{&name}`: query continuation, `&name=a`
+    QueryContinuation,
+}
+
+impl Operator {
+    /// Parse the operator at the start of an expression.
+    fn parse(expression: &str) -> (Self, &str) {
+        let operator = match expression.chars().next() {
+            Some('+') => Self::Reserved,
+            Some('#') => Self::Fragment,
+            Some('.') => Self::Label,
+            Some('/') => Self::PathSegment,
+            Some(';') => Self::PathParameter,
+            Some('?') => Self::Query,
+            Some('&') => Self::QueryContinuation,
+            _ => return (Self::Simple, expression),
+        };
+        (operator, expression.get(1..).unwrap_or_default())
+    }
+
+    /// Prefix of a non-empty expansion.
+    pub(crate) const fn first(self) -> &'static str {
+        match self {
+            Self::Simple | Self::Reserved => "",
+            Self::Fragment => "#",
+            Self::Label => ".",
+            Self::PathSegment => "/",
+            Self::PathParameter => ";",
+            Self::Query => "?",
+            Self::QueryContinuation => "&",
+        }
+    }
+
+    /// Separator of the values.
+    pub(crate) const fn separator(self) -> &'static str {
+        match self {
+            Self::Simple | Self::Reserved | Self::Fragment => ",",
+            Self::Label => ".",
+            Self::PathSegment => "/",
+            Self::PathParameter => ";",
+            Self::Query | Self::QueryContinuation => "&",
+        }
+    }
+
+    /// Whether values are prefixed with `name=`.
+    pub(crate) const fn is_named(self) -> bool {
+        matches!(
+            self,
+            Self::PathParameter | Self::Query | Self::QueryContinuation
+        )
+    }
+
+    /// Expansion of a named variable with an empty value.
+    pub(crate) const fn if_empty(self) -> &'static str {
+        match self {
+            Self::Query | Self::QueryContinuation => "=",
+            _ => "",
+        }
+    }
+}
+
+/// A variable of an expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Variable<'a> {
+    pub(crate) name: &'a str,
+    /// Explode modifier (`*`): list items as separate values.
+    pub(crate) explode: bool,
+}
+
+/// An expression between braces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Expression<'a> {
+    pub(crate) operator: Operator,
+    pub(crate) variables: Vec<Variable<'a>>,
+}
+
+impl<'a> Expression<'a> {
+    /// Parse the content of an expression, without the braces.
+    pub(crate) fn parse(expression: &'a str) -> Self {
+        let (operator, variables) = Operator::parse(expression);
+        let variables = variables
+            .split(',')
+            .filter(|name| !name.is_empty())
+            .map(|name| match name.strip_suffix('*') {
+                Some(name) => Variable {
+                    name,
+                    explode: true,
+                },
+                None => Variable {
+                    name,
+                    explode: false,
+                },
+            })
+            .collect();
+        Self {
+            operator,
+            variables,
+        }
+    }
+
+    /// Whether the expression is a simple `{name}` placeholder.
+    pub(crate) fn is_simple(&self) -> bool {
+        self.operator == Operator::Simple
+            && matches!(self.variables.as_slice(), [variable] if !variable.explode)
+    }
+}
+
+/// Names of the variables of the expressions of a template.
+pub(crate) fn variable_names(template: &str) -> Vec<String> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(expression, _)| expression))
+        .flat_map(|expression| {
+            Expression::parse(expression)
+                .variables
+                .into_iter()
+                .map(|variable| variable.name.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_expressions() {
+        assert_eq!(
+            Expression::parse("id"),
+            Expression {
+                operator: Operator::Simple,
+                variables: vec![Variable {
+                    name: "id",
+                    explode: false
+                }],
+            }
+        );
+        assert!(Expression::parse("id").is_simple());
+        assert_eq!(
+            Expression::parse("?q,tags*"),
+            Expression {
+                operator: Operator::Query,
+                variables: vec![
+                    Variable {
+                        name: "q",
+                        explode: false
+                    },
+                    Variable {
+                        name: "tags",
+                        explode: true
+                    },
+                ],
+            }
+        );
+        assert!(!Expression::parse("/segments*").is_simple());
+        assert!(!Expression::parse("a,b").is_simple());
+    }
+
+    #[test]
+    fn variable_names_of_a_template() {
+        assert_eq!(
+            variable_names("/files{/segments*}/{name}{.ext}{?q,page}{&debug}"),
+            ["segments", "name", "ext", "q", "page", "debug"]
+        );
+        assert!(variable_names("/health").is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+//! Client hot path benchmarks.
+//!
+//! ```sh
+//! cargo bench -p pincer --bench client -- --save-baseline main
+//! # after a change, reports the changes against the baseline
+//! cargo bench -p pincer --bench client -- --baseline main
+//! ```
+//!
+//! End-to-end benchmarks run against a local hyper echo server, so they
+//! include the loopback round trip.
+
+#![allow(missing_docs, clippy::expect_used)]
+
+use std::net::SocketAddr;
+
+use bytes::Bytes;
+use criterion::{Criterion, criterion_group, criterion_main};
+use http_body_util::{BodyExt, Full};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use pincer::middleware::{BearerAuthLayer, LoggingLayer, RetryLayer, RetryPolicy};
+use pincer::prelude::*;
+use pincer::{HyperClient, Method, Query, Request, Response, ToQueryPairs};
+use tokio::net::TcpListener;
+use tower::{Service, ServiceBuilder, ServiceExt};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Item {
+    id: u64,
+    name: String,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Query)]
+pub struct Search {
+    q: String,
+    page: Option<u32>,
+    #[query(rename = "per_page")]
+    limit: u32,
+    #[query(format = "csv")]
+    tags: Vec<String>,
+}
+
+#[pincer(url = "http://localhost")]
+pub trait EchoApi {
+    #[get("/items/{id}")]
+    async fn get_item(&self, #[path] id: u64, #[query] search: &Search) -> pincer::Result<Item>;
+
+    #[post("/items")]
+    async fn create_item(&self, #[body] item: &Item) -> pincer::Result<Item>;
+}
+
+fn item() -> Item {
+    Item {
+        id: 42,
+        name: "hello world".to_string(),
+        tags: vec!["rust".to_string(), "http".to_string()],
+    }
+}
+
+fn search() -> Search {
+    Search {
+        q: "rust & http".to_string(),
+        page: Some(2),
+        limit: 50,
+        tags: vec!["a".to_string(), "b c".to_string()],
+    }
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("runtime")
+}
+
+fn bench_request_build(c: &mut Criterion) {
+    let url: url::Url = "https://api.example.com/items/42".parse().expect("url");
+    let item = item();
+
+    let mut group = c.benchmark_group("request_build");
+    group.bench_function("get", |b| {
+        b.iter(|| {
+            Request::<Bytes>::builder(Method::Get, url.clone())
+                .header("accept", "application/json")
+                .header("x-request-id", "b5c1d0fe")
+                .query("page", "2")
+                .build()
+        });
+    });
+    group.bench_function("post_json", |b| {
+        b.iter(|| {
+            Request::builder(Method::Post, url.clone())
+                .header("accept", "application/json")
+                .json(&item)
+                .expect("json")
+                .build()
+        });
+    });
+    let pool = pincer::BufferPool::new();
+    group.bench_function("post_json_pooled", |b| {
+        b.iter(|| {
+            Request::builder(Method::Post, url.clone())
+                .header("accept", "application/json")
+                .json_pooled(&item, &pool)
+                .expect("json")
+                .build()
+        });
+    });
+    group.finish();
+}
+
+fn bench_query(c: &mut Criterion) {
+    let search = search();
+
+    let mut group = c.benchmark_group("query");
+    group.bench_function("derive_pairs", |b| b.iter(|| search.to_query_pairs()));
+    group.bench_function("serde_string", |b| {
+        b.iter(|| pincer::to_query_string(&search).expect("query"));
+    });
+    group.finish();
+}
+
+fn bench_middleware(c: &mut Criterion) {
+    let runtime = runtime();
+    let ok = tower::service_fn(|_request: Request<Bytes>| async {
+        Ok::<_, pincer::Error>(Response::new(200, http::HeaderMap::new(), Bytes::new()))
+    });
+    let url: url::Url = "https://api.example.com/items".parse().expect("url");
+    let request = || Request::<Bytes>::builder(Method::Get, url.clone()).build();
+
+    let mut group = c.benchmark_group("middleware");
+    group.bench_function("none", |b| {
+        b.to_async(&runtime).iter(|| ok.oneshot(request()));
+    });
+
+    let stack = ServiceBuilder::new()
+        .layer(LoggingLayer::new())
+        .layer(RetryLayer::new(RetryPolicy::new(3)))
+        .layer(BearerAuthLayer::new("token"))
+        .service(ok);
+    group.bench_function("logging_retry_auth", |b| {
+        b.to_async(&runtime).iter(|| {
+            let mut stack = stack.clone();
+            let request = request();
+            async move {
+                stack.ready().await.expect("ready");
+                stack.call(request).await
+            }
+        });
+    });
+    group.finish();
+}
+
+async fn echo_server() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+    let addr = listener.local_addr().expect("local addr");
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let service = service_fn(|request: hyper::Request<hyper::body::Incoming>| async {
+                    let body = request.into_body().collect().await?.to_bytes();
+                    let body = if body.is_empty() {
+                        serde_json::to_vec(&item())
+                            .map(Bytes::from)
+                            .unwrap_or_default()
+                    } else {
+                        body
+                    };
+                    let response = hyper::Response::builder()
+                        .header("content-type", "application/json")
+                        .body(Full::new(body))
+                        .unwrap_or_default();
+                    Ok::<_, hyper::Error>(response)
+                });
+                let _ = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await;
+            });
+        }
+    });
+    addr
+}
+
+fn bench_end_to_end(c: &mut Criterion) {
+    let runtime = runtime();
+    let addr = runtime.block_on(echo_server());
+    let base_url = format!("http://{addr}");
+    let client = |http: HyperClient| {
+        EchoApiClientBuilder::default()
+            .base_url(&base_url)
+            .client(http)
+            .build()
+            .expect("client")
+    };
+    let search = search();
+    let item = item();
+
+    let mut group = c.benchmark_group("end_to_end");
+    let bare = client(HyperClient::new());
+    group.bench_function("get", |b| {
+        b.to_async(&runtime).iter(|| bare.get_item(42, &search));
+    });
+    group.bench_function("post_json", |b| {
+        b.to_async(&runtime).iter(|| bare.create_item(&item));
+    });
+
+    let layered = client(
+        HyperClient::builder()
+            .with_logging()
+            .with_retry(3)
+            .with_bearer_auth("token")
+            .build(),
+    );
+    group.bench_function("get_with_middleware", |b| {
+        b.to_async(&runtime).iter(|| layered.get_item(42, &search));
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_request_build,
+    bench_query,
+    bench_middleware,
+    bench_end_to_end
+);
+criterion_main!(benches);
@@ -0,0 +1,114 @@
+//! Request-build hot path of generated clients.
+//!
+//! Measures the `{Trait}Requests` functions, which every generated client
+//! calls to build its requests: path template expansion and encoding, query
+//! serialization, headers and body, without any I/O.
+//!
+//! ```sh
+//! cargo bench -p pincer --bench request_build -- --save-baseline main
+//! # after a change
+//! cargo bench -p pincer --bench request_build -- --baseline main
+//! ```
+
+#![allow(missing_docs, clippy::expect_used)]
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use pincer::Query;
+use pincer::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Item {
+    id: u64,
+    name: String,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Query)]
+pub struct Search {
+    q: String,
+    page: Option<u32>,
+    #[query(rename = "per_page")]
+    limit: u32,
+    #[query(format = "csv")]
+    tags: Vec<String>,
+}
+
+#[pincer(url = "https://api.example.com/v1/")]
+pub trait CatalogApi {
+    #[get("/items")]
+    async fn list_items(&self) -> pincer::Result<Vec<Item>>;
+
+    #[get("/items/{id}")]
+    async fn get_item(&self, #[path] id: u64) -> pincer::Result<Item>;
+
+    #[get("/orgs/{org}/repos/{repo}/items/{id}")]
+    async fn get_nested_item(
+        &self,
+        #[path] org: &str,
+        #[path] repo: &str,
+        #[path] id: u64,
+        #[query] search: &Search,
+        #[header("x-request-id")] request_id: &str,
+    ) -> pincer::Result<Item>;
+
+    #[post("/orgs/{org}/items")]
+    async fn create_item(&self, #[path] org: &str, #[body] item: &Item) -> pincer::Result<Item>;
+}
+
+fn item() -> Item {
+    Item {
+        id: 42,
+        name: "hello world".to_string(),
+        tags: vec!["rust".to_string(), "http".to_string()],
+    }
+}
+
+fn search() -> Search {
+    Search {
+        q: "rust & http".to_string(),
+        page: Some(2),
+        limit: 50,
+        tags: vec!["a".to_string(), "b c".to_string()],
+    }
+}
+
+fn bench_generated_requests(c: &mut Criterion) {
+    let base_url: url::Url = "https://api.example.com/v1/".parse().expect("url");
+    let search = search();
+    let item = item();
+
+    let mut group = c.benchmark_group("generated_request");
+    group.bench_function("static_path", |b| {
+        b.iter(|| CatalogApiRequests::list_items(black_box(&base_url)));
+    });
+    group.bench_function("one_path_param", |b| {
+        b.iter(|| CatalogApiRequests::get_item(black_box(&base_url), black_box(42)));
+    });
+    group.bench_function("encoded_path_query_header", |b| {
+        b.iter(|| {
+            CatalogApiRequests::get_nested_item(
+                black_box(&base_url),
+                black_box("rust lang"),
+                black_box("pincer/core"),
+                black_box(42),
+                black_box(&search),
+                black_box("b5c1d0fe"),
+            )
+        });
+    });
+    group.bench_function("json_body", |b| {
+        b.iter(|| {
+            CatalogApiRequests::create_item(
+                black_box(&base_url),
+                black_box("rust-lang"),
+                black_box(&item),
+            )
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_generated_requests);
+criterion_main!(benches);
@@ -73,6 +73,48 @@
 //! ) -> pincer::Result<()>;
 //! ```
 //!
+//! Cookies with `#[cookie("name")]`, merged into one `Cookie` header with
+//! values percent-encoded where needed:
+//!
+//! ```ignore
+//! #[get("/cart")]
+//! async fn get_cart(
+//!     &self,
+//!     #[cookie("session_id")] session: &str,
+//!     #[cookie("currency")] currency: &str,
+//! ) -> pincer::Result<Cart>;
+//!
+//! // Sends: Cookie: session_id=abc123; currency=EUR
+//! ```
+//!
+//! Language fallback chains with `#[accept_language]`, rendered with
+//! decreasing quality values:
+//!
+//! ```ignore
+//! #[get("/articles/{id}")]
+//! async fn get_article(
+//!     &self,
+//!     #[path] id: u64,
+//!     #[accept_language] languages: &[&str],
+//! ) -> pincer::Result<Article>;
+//!
+//! // ["fr-CH", "fr", "en"] sends: Accept-Language: fr-CH, fr;q=0.9, en;q=0.8
+//! ```
+//!
+//! Optimistic concurrency with `#[if_match]`: the update only applies if the
+//! resource still has the entity tag, otherwise it fails with
+//! `Error::PreconditionFailed` (`412`):
+//!
+//! ```ignore
+//! #[put("/documents/{id}")]
+//! async fn update_document(
+//!     &self,
+//!     #[path] id: u64,
+//!     #[if_match] etag: &EntityTag,
+//!     #[body] document: &Document,
+//! ) -> pincer::Result<Document>;
+//! ```
+//!
 //! ## Request Bodies
 //!
 //! ### JSON Body
@@ -40,6 +40,31 @@
 //! async fn delete_user(&self, #[path] id: u64) -> pincer::Result<()>;
 //! ```
 //!
+//! ### Existence Check
+//!
+//! Mark a `HEAD` method with `#[exists]` to get `true` on 2xx and `false` on 404
+//! (other statuses are still errors):
+//!
+//! ```ignore
+//! #[head("/users/{id}")]
+//! #[exists]
+//! async fn user_exists(&self, #[path] id: u64) -> pincer::Result<bool>;
+//! ```
+//!
+//! ### Allowed Methods
+//!
+//! Return [`AllowedMethods`][crate::AllowedMethods] from an `OPTIONS` method to
+//! read the `Allow` and `Access-Control-*` headers:
+//!
+//! ```ignore
+//! #[options("/users")]
+//! async fn users_options(&self) -> pincer::Result<AllowedMethods>;
+//!
+//! // Usage:
+//! let allowed = client.users_options().await?;
+//! if allowed.allows(Method::Delete) { /* ... */ }
+//! ```
+//!
 //! ## Error Handling
 //!
 //! ### Default Behavior
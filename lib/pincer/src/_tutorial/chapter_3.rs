@@ -34,6 +34,8 @@
 //! | `middleware-logging` | `.with_logging()` | Log requests/responses |
 //! | `middleware-bearer-auth` | `.with_bearer_auth(token)` | Add Bearer token |
 //! | `middleware-concurrency` | `.with_concurrency_limit(n)` | Limit concurrent requests |
+//! | `middleware-load-shed` | `.with_load_shed()` | Fail fast when at capacity |
+//! | `middleware-buffer` | `.with_buffer(n)` | Queue requests when at capacity |
 //!
 //! ## Middleware Order
 //!
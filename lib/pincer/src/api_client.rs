@@ -8,7 +8,7 @@ use std::future::Future;
 use bytes::Bytes;
 use url::Url;
 
-use crate::{Error, HttpClient, PincerClient, Request, Response, Result};
+use crate::{Error, HttpClient, PincerClient, Request, Response, Result, Runtime};
 
 /// Generic API client wrapper.
 ///
@@ -111,6 +111,10 @@ where
     fn base_url(&self) -> &Url {
         &self.base_url
     }
+
+    fn runtime(&self) -> Option<&dyn Runtime> {
+        self.client.runtime()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,388 @@
+//! Raw wire capture for protocol debugging.
+//!
+//! A [`WireCapture`] configured on the client receives the bytes of every
+//! connection as hyper writes and reads them, before TLS encryption and after
+//! decryption. HTTP/1.1 traffic is readable as is; HTTP/2 traffic is binary
+//! (HPACK-compressed headers).
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use pincer_core::{REDACTED, Redactor};
+
+/// Default number of bytes captured per connection and direction (64 KiB).
+const DEFAULT_MAX_BYTES: usize = 64 * 1024;
+
+/// Headers redacted by default.
+const DEFAULT_REDACTED_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+];
+
+/// Identifier of the next captured connection.
+static NEXT_CONNECTION: AtomicU64 = AtomicU64::new(1);
+
+/// Direction of captured bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WireDirection {
+    /// Bytes written to the server.
+    Sent,
+    /// Bytes read from the server.
+    Received,
+}
+
+/// Bytes captured on a connection, after redaction.
+#[derive(Debug)]
+pub struct WireChunk<'a> {
+    connection: u64,
+    direction: WireDirection,
+    bytes: &'a [u8],
+    truncated: bool,
+}
+
+impl WireChunk<'_> {
+    /// Identifier of the connection, unique in the process.
+    #[must_use]
+    pub const fn connection(&self) -> u64 {
+        self.connection
+    }
+
+    /// Direction of the bytes.
+    #[must_use]
+    pub const fn direction(&self) -> WireDirection {
+        self.direction
+    }
+
+    /// Captured bytes.
+    #[must_use]
+    pub const fn bytes(&self) -> &[u8] {
+        self.bytes
+    }
+
+    /// Whether the size limit was reached with this chunk.
+    ///
+    /// Nothing more is captured in this direction of the connection.
+    #[must_use]
+    pub const fn truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+type Sink = dyn Fn(&WireChunk<'_>) + Send + Sync;
+type Redaction = dyn Fn(WireDirection, &mut Vec<u8>) + Send + Sync;
+
+/// Wire capture configuration.
+///
+/// Captures at most 64 KiB per connection and direction by default, see
+/// [`max_bytes`](Self::max_bytes). The values of the `Authorization`,
+/// `Proxy-Authorization`, `Cookie` and `Set-Cookie` headers are replaced by
+/// `REDACTED` in HTTP/1.1 traffic; other headers can be added with
+/// [`redact_header`](Self::redact_header), and any other redaction done with
+/// [`redact_with`](Self::redact_with). The sensitive query parameters of
+/// request lines are redacted with a [`Redactor`], see
+/// [`with_redactor`](Self::with_redactor). Header redaction works on each
+/// chunk, so a line split across two writes is not redacted.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::{HyperClient, WireCapture};
+///
+/// let capture = WireCapture::new(|chunk| {
+///     eprintln!("{:?}: {}", chunk.direction(), String::from_utf8_lossy(chunk.bytes()));
+/// })
+/// .redact_header("x-api-key");
+///
+/// let client = HyperClient::builder().wire_capture(capture).build();
+/// ```
+#[derive(Clone)]
+pub struct WireCapture {
+    sink: Arc<Sink>,
+    max_bytes: usize,
+    redacted_headers: Vec<String>,
+    redactor: Arc<Redactor>,
+    redactions: Vec<Arc<Redaction>>,
+}
+
+impl fmt::Debug for WireCapture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WireCapture")
+            .field("max_bytes", &self.max_bytes)
+            .field("redacted_headers", &self.redacted_headers)
+            .field("redactor", &self.redactor)
+            .field("redactions", &self.redactions.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl WireCapture {
+    /// Capture bytes to `sink`.
+    ///
+    /// The sink is called on the connection's I/O path, so it should be fast
+    /// (e.g. push to a channel or a buffered writer).
+    #[must_use]
+    pub fn new(sink: impl Fn(&WireChunk<'_>) + Send + Sync + 'static) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            max_bytes: DEFAULT_MAX_BYTES,
+            redacted_headers: DEFAULT_REDACTED_HEADERS
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            redactor: Arc::default(),
+            redactions: Vec::new(),
+        }
+    }
+
+    /// Capture bytes as `TRACE` events of the `pincer::wire` target.
+    #[must_use]
+    pub fn tracing() -> Self {
+        Self::new(|chunk| {
+            tracing::trace!(
+                target: "pincer::wire",
+                connection = chunk.connection(),
+                direction = ?chunk.direction(),
+                truncated = chunk.truncated(),
+                bytes = %chunk.bytes().escape_ascii(),
+            );
+        })
+    }
+
+    /// Set the number of bytes captured per connection and direction.
+    #[must_use]
+    pub const fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Also redact the value of the `name` header (case-insensitive).
+    #[must_use]
+    pub fn redact_header(mut self, name: impl Into<String>) -> Self {
+        self.redacted_headers.push(name.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Set the redactor of the query of request lines.
+    ///
+    /// Defaults to [`Redactor::default()`].
+    #[must_use]
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Arc::new(redactor);
+        self
+    }
+
+    /// Add a redaction hook, run on every chunk after header redaction.
+    #[must_use]
+    pub fn redact_with(
+        mut self,
+        redaction: impl Fn(WireDirection, &mut Vec<u8>) + Send + Sync + 'static,
+    ) -> Self {
+        self.redactions.push(Arc::new(redaction));
+        self
+    }
+
+    /// Start capturing a new connection.
+    pub(crate) fn connection(&self) -> ConnectionCapture {
+        ConnectionCapture {
+            capture: self.clone(),
+            connection: NEXT_CONNECTION.fetch_add(1, Ordering::Relaxed),
+            sent: 0,
+            received: 0,
+        }
+    }
+
+    /// Replace the values of the redacted headers in `bytes`.
+    fn redact_headers(&self, bytes: &[u8]) -> Vec<u8> {
+        let mut redacted = Vec::with_capacity(bytes.len());
+        for line in bytes.split_inclusive(|&b| b == b'\n') {
+            let header = line
+                .iter()
+                .position(|&b| b == b':')
+                .and_then(|colon| line.split_at_checked(colon + 1));
+            match header {
+                Some((name, value))
+                    if self.is_redacted(name.strip_suffix(b":").unwrap_or(name)) =>
+                {
+                    let eol = value
+                        .iter()
+                        .position(|&b| b == b'\r' || b == b'\n')
+                        .unwrap_or(value.len());
+                    let (_, line_end) = value.split_at_checked(eol).unwrap_or_default();
+                    redacted.extend_from_slice(name);
+                    redacted.push(b' ');
+                    redacted.extend_from_slice(REDACTED.as_bytes());
+                    redacted.extend_from_slice(line_end);
+                }
+                _ => match self.redact_request_line(line) {
+                    Some(line) => redacted.extend_from_slice(line.as_bytes()),
+                    None => redacted.extend_from_slice(line),
+                },
+            }
+        }
+        redacted
+    }
+
+    /// Redact the query of an HTTP/1 request line, e.g.
+    /// `GET /items?token=abc HTTP/1.1`, if it has sensitive parameters.
+    fn redact_request_line(&self, line: &[u8]) -> Option<String> {
+        let line = std::str::from_utf8(line).ok()?;
+        let (method, rest) = line.split_once(' ')?;
+        let (target, version) = rest.split_once(' ')?;
+        if !version.starts_with("HTTP/1.") {
+            return None;
+        }
+        let (path, query) = target.split_once('?')?;
+        match self.redactor.redact_query(query) {
+            std::borrow::Cow::Owned(query) => Some(format!("{method} {path}?{query} {version}")),
+            std::borrow::Cow::Borrowed(_) => None,
+        }
+    }
+
+    fn is_redacted(&self, name: &[u8]) -> bool {
+        self.redacted_headers
+            .iter()
+            .any(|header| header.as_bytes().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Capture state of one connection.
+pub(crate) struct ConnectionCapture {
+    capture: WireCapture,
+    connection: u64,
+    sent: usize,
+    received: usize,
+}
+
+impl ConnectionCapture {
+    /// Capture `bytes` transferred in `direction`, within the size limit.
+    pub(crate) fn record(&mut self, direction: WireDirection, bytes: &[u8]) {
+        let max_bytes = self.capture.max_bytes;
+        let captured = match direction {
+            WireDirection::Sent => &mut self.sent,
+            WireDirection::Received => &mut self.received,
+        };
+        let available = max_bytes.saturating_sub(*captured);
+        if available == 0 || bytes.is_empty() {
+            return;
+        }
+
+        let truncated = bytes.len() >= available;
+        let bytes = bytes.get(..available).unwrap_or(bytes);
+        *captured += bytes.len();
+
+        let mut redacted = self.capture.redact_headers(bytes);
+        for redaction in &self.capture.redactions {
+            redaction(direction, &mut redacted);
+        }
+        (self.capture.sink)(&WireChunk {
+            connection: self.connection,
+            direction,
+            bytes: &redacted,
+            truncated,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    /// Captured bytes and truncation flags.
+    type Chunks = Arc<Mutex<Vec<(Vec<u8>, bool)>>>;
+
+    fn collect(capture: impl FnOnce(WireCapture) -> WireCapture) -> (ConnectionCapture, Chunks) {
+        let chunks = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&chunks);
+        let capture = capture(WireCapture::new(move |chunk| {
+            sink.lock()
+                .expect("lock")
+                .push((chunk.bytes().to_vec(), chunk.truncated()));
+        }));
+        (capture.connection(), chunks)
+    }
+
+    #[test]
+    fn redacts_headers() {
+        let (mut connection, chunks) = collect(|capture| capture.redact_header("X-Api-Key"));
+        connection.record(
+            WireDirection::Sent,
+            b"GET / HTTP/1.1\r\nAuthorization: Bearer secret\r\nx-api-key: key\r\naccept: */*\r\n\r\n",
+        );
+
+        let chunks = chunks.lock().expect("lock");
+        assert_eq!(
+            *chunks,
+            vec![(
+                b"GET / HTTP/1.1\r\nAuthorization: REDACTED\r\nx-api-key: REDACTED\r\naccept: */*\r\n\r\n"
+                    .to_vec(),
+                false
+            )]
+        );
+    }
+
+    #[test]
+    fn redacts_query_of_request_lines() {
+        let (mut connection, chunks) = collect(|capture| capture);
+        connection.record(
+            WireDirection::Sent,
+            b"GET /items?api_key=abc&page=2 HTTP/1.1\r\nhost: example.com\r\n\r\n",
+        );
+        connection.record(WireDirection::Sent, b"GET /items?page=2 HTTP/1.1\r\n\r\n");
+
+        let chunks = chunks.lock().expect("lock");
+        assert_eq!(
+            *chunks,
+            vec![
+                (
+                    b"GET /items?api_key=REDACTED&page=2 HTTP/1.1\r\nhost: example.com\r\n\r\n"
+                        .to_vec(),
+                    false
+                ),
+                (b"GET /items?page=2 HTTP/1.1\r\n\r\n".to_vec(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn limits_bytes_per_direction() {
+        let (mut connection, chunks) = collect(|capture| capture.max_bytes(4));
+        connection.record(WireDirection::Sent, b"abc");
+        connection.record(WireDirection::Sent, b"def");
+        connection.record(WireDirection::Sent, b"ghi");
+        connection.record(WireDirection::Received, b"xyz");
+
+        let chunks = chunks.lock().expect("lock");
+        assert_eq!(
+            *chunks,
+            vec![
+                (b"abc".to_vec(), false),
+                (b"d".to_vec(), true),
+                (b"xyz".to_vec(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn runs_redaction_hooks() {
+        let (mut connection, chunks) = collect(|capture| {
+            capture.redact_with(|direction, bytes| {
+                if direction == WireDirection::Received {
+                    bytes.make_ascii_uppercase();
+                }
+            })
+        });
+        connection.record(WireDirection::Sent, b"ping");
+        connection.record(WireDirection::Received, b"pong");
+
+        let chunks = chunks.lock().expect("lock");
+        assert_eq!(
+            *chunks,
+            vec![(b"ping".to_vec(), false), (b"PONG".to_vec(), false)]
+        );
+    }
+}
@@ -1,46 +1,62 @@
 //! HTTP client implementation using hyper-util.
 
-use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
-use bytes::Bytes;
-use http_body_util::{BodyExt, Full};
-use hyper_rustls::HttpsConnector;
+use bytes::{Bytes, BytesMut};
+use http_body_util::{BodyExt, Full, combinators::UnsyncBoxBody};
+use hyper::body::Incoming;
 use hyper_util::{
-    client::legacy::{Client, connect::HttpConnector},
+    client::legacy::{
+        Builder, Client,
+        connect::{Connect, HttpInfo},
+    },
     rt::TokioExecutor,
 };
 use tower::Layer;
-use tower::util::BoxCloneService;
+use tower::util::{BoxCloneSyncService, ServiceExt};
 use tower_service::Service;
 
 use crate::{
-    Error, Request, Response, Result,
+    BodyAction, ConnectionHints, ConnectionInfo, DiscardBody, Error, Identity, InspectHead,
+    Request, Response, Result, TruncatedBody,
     config::{ClientConfig, ClientConfigBuilder},
-    connector::https_connector,
+    connector::InspectingConnector,
+    middleware::{ConditionalLayer, MapLayer},
 };
 
 // Feature-gated imports for streaming
 #[cfg(feature = "streaming")]
-use futures_util::TryStreamExt;
+use futures_util::{StreamExt, TryStreamExt};
 #[cfg(feature = "streaming")]
-use http_body_util::BodyStream;
+use http_body_util::{BodyStream, StreamBody};
 #[cfg(feature = "streaming")]
-use pincer_core::StreamingBody;
+use hyper::body::Frame;
+#[cfg(feature = "streaming")]
+use pincer_core::{StreamingBody, Trailers, TrailersSender};
 
 // Feature-gated imports for middleware
+#[cfg(feature = "middleware-auth-refresh")]
+use crate::middleware::AuthRefreshLayer;
 #[cfg(feature = "middleware-basic-auth")]
 use crate::middleware::BasicAuthLayer;
 #[cfg(feature = "middleware-bearer-auth")]
 use crate::middleware::BearerAuthLayer;
+#[cfg(feature = "middleware-buffer")]
+use crate::middleware::BufferLayer;
+#[cfg(feature = "middleware-cache")]
+use crate::middleware::CacheLayer;
+#[cfg(feature = "middleware-cookies")]
+use crate::middleware::CookieJarLayer;
 #[cfg(feature = "middleware-decompression")]
 use crate::middleware::DecompressionLayer;
 #[cfg(feature = "middleware-follow-redirect")]
 use crate::middleware::FollowRedirectLayer;
+#[cfg(feature = "middleware-load-shed")]
+use crate::middleware::LoadShedLayer;
 #[cfg(feature = "middleware-logging")]
 use crate::middleware::LoggingLayer;
 #[cfg(feature = "middleware-metrics")]
@@ -49,8 +65,14 @@ use crate::middleware::MetricsLayer;
 use crate::middleware::RateLimitLayer;
 #[cfg(feature = "middleware-retry")]
 use crate::middleware::RetryPolicy;
+#[cfg(feature = "middleware-access-log")]
+use crate::middleware::{AccessLogLayer, AccessLogSink};
 #[cfg(feature = "middleware-circuit-breaker")]
 use crate::middleware::{CircuitBreakerConfig, CircuitBreakerLayer};
+#[cfg(feature = "middleware-jws")]
+use crate::middleware::{JwsLayer, JwtClaims, JwtLayer, SigningKey};
+#[cfg(feature = "middleware-token-auth")]
+use crate::middleware::{TokenAuthLayer, TokenCache};
 #[cfg(feature = "middleware-concurrency")]
 use tower::limit::ConcurrencyLimitLayer;
 #[cfg(feature = "middleware-retry")]
@@ -63,118 +85,234 @@ use tower::retry::RetryLayer;
 /// Type-erased service for middleware composition.
 ///
 /// This type allows storing and composing arbitrary Tower layers without
-/// exposing complex generic types to users.
-pub type BoxedService = BoxCloneService<Request<Bytes>, Response<Bytes>, Error>;
+/// exposing complex generic types to users. It is `Sync`, so the client can
+/// share it between tasks and clone it per request without locking.
+pub type BoxedService = BoxCloneSyncService<Request<Bytes>, Response<Bytes>, Error>;
 
 /// Future type for Tower Service implementation.
 pub type ServiceFuture = Pin<Box<dyn Future<Output = Result<Response<Bytes>>> + Send + 'static>>;
 
-/// Thread-safe wrapper for `BoxedService`.
-///
-/// This wrapper uses a Mutex to make the service Sync, which is required
-/// by the `HttpClient` trait.
-#[derive(Clone)]
-struct SyncService {
-    inner: Arc<Mutex<BoxedService>>,
-}
+// ============================================================================
+// Raw Client (internal, used for direct hyper access)
+// ============================================================================
 
-impl SyncService {
-    fn new(service: BoxedService) -> Self {
-        Self {
-            inner: Arc::new(Mutex::new(service)),
-        }
-    }
+/// Body sent by the underlying client, either buffered or streamed.
+type HyperBody = UnsyncBoxBody<Bytes, Error>;
 
-    fn call(&self, request: Request<Bytes>) -> ServiceFuture {
-        // Lock, clone the service, and release the lock immediately
-        let mut service = self
-            .inner
-            .lock()
-            .unwrap_or_else(std::sync::PoisonError::into_inner)
-            .clone();
+/// Underlying hyper-util client.
+type HyperInner = Client<InspectingConnector, HyperBody>;
 
-        Box::pin(async move { service.call(request).await })
+/// Smallest maximum size of the HTTP/1 headers accepted by hyper.
+const MIN_HTTP1_MAX_HEADER_SIZE: usize = 8 * 1024;
+
+/// Hyper-util client builder with the HTTP/1 options of `config`.
+fn client_builder(config: &ClientConfig) -> Builder {
+    let mut builder = Client::builder(TokioExecutor::new());
+    builder
+        .http1_title_case_headers(config.http1_title_case_headers)
+        .http1_allow_obsolete_multiline_headers_in_responses(
+            config.http1_allow_obsolete_multiline_headers,
+        );
+    if let Some(max) = config.http1_max_header_size {
+        // hyper panics below its minimum buffer size
+        builder.http1_max_buf_size(max.max(MIN_HTTP1_MAX_HEADER_SIZE));
     }
+    builder
 }
 
-// ============================================================================
-// Raw Client (internal, used for direct hyper access)
-// ============================================================================
-
 /// Raw HTTP client using hyper-util (internal implementation).
 #[derive(Clone)]
 struct RawHyperClient {
-    inner: Client<HttpsConnector<HttpConnector>, Full<Bytes>>,
+    inner: HyperInner,
+    /// Client sharing the connector of `inner`, without pooling.
+    fresh: HyperInner,
     config: ClientConfig,
 }
 
 impl RawHyperClient {
     fn new(config: ClientConfig) -> Self {
-        let connector = https_connector();
+        let connector = InspectingConnector::new(&config);
 
-        let inner = Client::builder(TokioExecutor::new())
+        let inner = client_builder(&config)
             .pool_idle_timeout(config.pool_idle_timeout)
             .pool_max_idle_per_host(config.pool_idle_per_host)
+            .build(connector.clone());
+        let fresh = client_builder(&config)
+            .pool_max_idle_per_host(0)
             .build(connector);
 
-        Self { inner, config }
+        Self {
+            inner,
+            fresh,
+            config,
+        }
+    }
+
+    /// Select the client honoring the request [`ConnectionHints`].
+    ///
+    /// Requests needing a fresh connection go through a client without
+    /// pooling, so they never share connections with other requests; it
+    /// shares the connector, and so the TLS configuration, of the pooled one.
+    /// Requests pinned to an address get a dedicated connector.
+    fn client_for(&self, hints: Option<&ConnectionHints>) -> HyperInner {
+        let Some(hints) = hints.filter(|hints| hints.is_fresh_connection()) else {
+            return self.inner.clone();
+        };
+
+        match hints.pinned_addr() {
+            Some(addr) => client_builder(&self.config)
+                .pool_max_idle_per_host(0)
+                .build(InspectingConnector::pinned(&self.config, addr)),
+            None => self.fresh.clone(),
+        }
+    }
+
+    /// Prepare the hyper request and the client sending it.
+    fn prepare(&self, request: Request<Bytes>) -> Result<(HyperInner, http::Request<HyperBody>)> {
+        let hints = request.extensions().get::<ConnectionHints>().copied();
+        let client = self.client_for(hints.as_ref());
+
+        let mut hyper_request = Self::build_hyper_request(request)?;
+        if hints.is_some_and(|hints| hints.is_keep_alive_disabled()) {
+            hyper_request.headers_mut().insert(
+                http::header::CONNECTION,
+                http::HeaderValue::from_static("close"),
+            );
+        }
+
+        Ok((client, hyper_request))
     }
 
     /// Build a hyper request from a pincer request.
-    fn build_hyper_request(request: Request<Bytes>) -> Result<http::Request<Full<Bytes>>> {
+    fn build_hyper_request(request: Request<Bytes>) -> Result<http::Request<HyperBody>> {
+        let http_request = Self::into_http_request(request)?;
+        Ok(http_request.map(|body| body.map_err(|never| match never {}).boxed_unsync()))
+    }
+
+    /// Convert a pincer request into an `http` request with a buffered body.
+    fn into_http_request(request: Request<Bytes>) -> Result<http::Request<Full<Bytes>>> {
         let (method, url, headers, body, extensions) = request.into_parts();
 
-        let mut builder = http::Request::builder()
+        let builder = http::Request::builder()
             .method(http::Method::from(method))
             .uri(url.as_str());
 
-        for (name, value) in &headers {
-            builder = builder.header(name.as_str(), value.as_str());
-        }
-
         let body = body.map_or_else(Full::default, Full::new);
         let mut http_request = builder
             .body(body)
             .map_err(|e| Error::invalid_request(e.to_string()))?;
 
-        // Transfer extensions to the http::Request
+        // Transfer headers and extensions to the http::Request
+        *http_request.headers_mut() = headers;
         *http_request.extensions_mut() = extensions;
 
         Ok(http_request)
     }
 
-    /// Extract response headers as a `HashMap`.
-    fn extract_headers(headers: &http::HeaderMap) -> HashMap<String, String> {
-        headers
-            .iter()
-            .filter_map(|(name, value)| {
-                value
-                    .to_str()
-                    .ok()
-                    .map(|v| (name.to_string(), v.to_string()))
-            })
-            .collect()
+    async fn execute(&self, request: Request<Bytes>) -> Result<Response<Bytes>> {
+        let retry = self.stale_retry(&request);
+        let (client, hyper_request) = self.prepare(request)?;
+        let timeout = self.config.request_timeout(hyper_request.method(), false);
+        self.send(&client, hyper_request, retry, timeout).await
     }
 
-    async fn execute(&self, request: Request<Bytes>) -> Result<Response<Bytes>> {
-        let hyper_request = Self::build_hyper_request(request)?;
+    /// Parts of `request` to send it again on a fresh connection if its
+    /// pooled connection turns out to be stale, see [`is_stale_connection`].
+    ///
+    /// Only idempotent requests are retried, and not the ones already on a
+    /// fresh or pinned connection.
+    fn stale_retry(&self, request: &Request<Bytes>) -> Option<StaleRetry> {
+        let hints = request.extensions().get::<ConnectionHints>().copied();
+        if !self.config.retry_on_connection_failure
+            || !request.method().is_idempotent()
+            || hints.is_some_and(|hints| hints.is_fresh_connection())
+        {
+            return None;
+        }
+
+        Some(StaleRetry {
+            method: request.method(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request.body().cloned(),
+            hints: hints.unwrap_or_default().fresh_connection(),
+        })
+    }
 
-        let response = tokio::time::timeout(self.config.timeout, self.inner.request(hyper_request))
+    /// Send a prepared request and buffer the response body.
+    ///
+    /// If the request fails on a stale connection, the `retry` request is
+    /// sent once, within the same timeout.
+    async fn send(
+        &self,
+        client: &HyperInner,
+        hyper_request: http::Request<HyperBody>,
+        retry: Option<StaleRetry>,
+        timeout: Duration,
+    ) -> Result<Response<Bytes>> {
+        let policy = BodyPolicy::of(hyper_request.extensions(), &self.config);
+        let attempts = async {
+            match (client.request(hyper_request).await, retry) {
+                (Err(err), Some(retry)) if is_stale_connection(&err) => {
+                    tracing::debug!(error = %err, "retrying on a fresh connection");
+                    let (client, hyper_request) = self.prepare(retry.into_request())?;
+                    client
+                        .request(hyper_request)
+                        .await
+                        .map_err(Self::map_hyper_error)
+                }
+                (result, _) => result.map_err(Self::map_hyper_error),
+            }
+        };
+        let response = tokio::time::timeout(timeout, attempts)
             .await
-            .map_err(|_| Error::Timeout)?
-            .map_err(Self::map_hyper_error)?;
+            .map_err(|_| Error::Timeout)??;
+        Self::buffer_response(response, policy).await
+    }
 
-        let status = response.status().as_u16();
-        let response_headers = Self::extract_headers(response.headers());
+    /// Buffer the body of a hyper response into a pincer response, as much
+    /// of it as the request `policy` reads.
+    async fn buffer_response(
+        response: http::Response<Incoming>,
+        policy: BodyPolicy,
+    ) -> Result<Response<Bytes>> {
+        let (parts, body) = response.into_parts();
+
+        let (body, truncated) = match policy.action(parts.status, &parts.headers)? {
+            BodyAction::Collect => {
+                let body = body
+                    .collect()
+                    .await
+                    .map_err(|e| Error::connection(e.to_string()))?
+                    .to_bytes();
+                (body, false)
+            }
+            BodyAction::Limit(limit) => collect_limited(body, limit).await?,
+            BodyAction::Discard => {
+                drain_small(body, &parts.headers).await;
+                (Bytes::new(), false)
+            }
+        };
 
-        let body = response
-            .into_body()
-            .collect()
-            .await
-            .map_err(|e| Error::connection(e.to_string()))?
-            .to_bytes();
+        let mut response = Response::new(parts.status.as_u16(), parts.headers, body);
+        *response.extensions_mut() = Self::response_extensions(parts.extensions, parts.version);
+        if truncated {
+            response.extensions_mut().insert(TruncatedBody);
+        }
+        Ok(response)
+    }
 
-        Ok(Response::new(status, response_headers, body))
+    /// Extensions of a pincer response, recording the HTTP version and addresses.
+    fn response_extensions(
+        mut extensions: http::Extensions,
+        version: http::Version,
+    ) -> http::Extensions {
+        extensions.insert(version);
+        if let Some(info) = extensions.get::<HttpInfo>() {
+            let connection = ConnectionInfo::new(info.remote_addr(), info.local_addr());
+            extensions.insert(connection);
+        }
+        extensions
     }
 
     #[allow(clippy::needless_pass_by_value)]
@@ -198,29 +336,192 @@ impl RawHyperClient {
         &self,
         request: Request<Bytes>,
     ) -> Result<pincer_core::StreamingResponse> {
-        let hyper_request = Self::build_hyper_request(request)?;
+        let (client, hyper_request) = self.prepare(request)?;
+        let timeout = self.config.request_timeout(hyper_request.method(), true);
 
-        let response = tokio::time::timeout(self.config.timeout, self.inner.request(hyper_request))
+        let response = tokio::time::timeout(timeout, client.request(hyper_request))
             .await
             .map_err(|_| Error::Timeout)?
             .map_err(Self::map_hyper_error)?;
 
-        let status = response.status().as_u16();
-        let response_headers = Self::extract_headers(response.headers());
-
-        let body_stream = BodyStream::new(response.into_body());
-        let streaming_body: StreamingBody = Box::pin(
-            body_stream
-                .map_ok(|frame| frame.into_data().unwrap_or_default())
-                .map_err(|e| Error::connection(e.to_string())),
-        );
+        let (parts, body) = response.into_parts();
+        let (sender, trailers) = Trailers::channel();
+
+        let mut response = pincer_core::StreamingResponse::new(
+            parts.status.as_u16(),
+            parts.headers,
+            Self::streaming_body(body, sender),
+        )
+        .with_trailers(trailers);
+        *response.extensions_mut() = Self::response_extensions(parts.extensions, parts.version);
+        Ok(response)
+    }
 
-        Ok(pincer_core::StreamingResponse::new(
-            status,
-            response_headers,
-            streaming_body,
+    /// Stream the data frames of `body`, sending its trailers frame to `trailers`.
+    #[cfg(feature = "streaming")]
+    fn streaming_body(body: Incoming, trailers: TrailersSender) -> StreamingBody {
+        let state = (BodyStream::new(body), Some(trailers));
+        Box::pin(futures_util::stream::unfold(
+            state,
+            |(mut frames, mut trailers)| async move {
+                loop {
+                    // The end of the body drops the sender, without trailers
+                    match frames.next().await? {
+                        Ok(frame) => match frame.into_data() {
+                            Ok(data) => return Some((Ok(data), (frames, trailers))),
+                            Err(frame) => {
+                                if let (Ok(map), Some(sender)) =
+                                    (frame.into_trailers(), trailers.take())
+                                {
+                                    sender.send(map);
+                                }
+                            }
+                        },
+                        Err(err) => {
+                            let err = Error::connection(err.to_string());
+                            return Some((Err(err), (frames, trailers)));
+                        }
+                    }
+                }
+            },
         ))
     }
+
+    /// Execute a request whose body is sent as it is produced.
+    #[cfg(feature = "streaming")]
+    async fn execute_upload(
+        &self,
+        request: Request<Bytes>,
+        body: StreamingBody,
+    ) -> Result<Response<Bytes>> {
+        let (client, mut hyper_request) = self.prepare(request)?;
+        *hyper_request.body_mut() = StreamBody::new(body.map_ok(Frame::data)).boxed_unsync();
+        let timeout = self.config.request_timeout(hyper_request.method(), true);
+        self.send(&client, hyper_request, None, timeout).await
+    }
+}
+
+/// Request sent again on a fresh connection when its pooled connection was
+/// stale.
+///
+/// Only the parts sent on the wire are kept: the body is shared, not copied,
+/// and the request is only rebuilt if the retry happens.
+struct StaleRetry {
+    method: crate::Method,
+    url: url::Url,
+    headers: http::HeaderMap,
+    body: Option<Bytes>,
+    hints: ConnectionHints,
+}
+
+impl StaleRetry {
+    fn into_request(self) -> Request<Bytes> {
+        let mut extensions = http::Extensions::new();
+        extensions.insert(self.hints);
+        Request::from_parts(self.method, self.url, self.headers, self.body, extensions)
+    }
+}
+
+/// Whether a request failed because the server had closed its pooled
+/// connection, e.g. on its idle timeout, while the request was being sent.
+///
+/// hyper reports it as "connection closed before message completed", or as
+/// a connection reset when writing to the closed socket.
+fn is_stale_connection(err: &hyper_util::client::legacy::Error) -> bool {
+    if err.is_connect() {
+        return false;
+    }
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        if err
+            .downcast_ref::<hyper::Error>()
+            .is_some_and(hyper::Error::is_incomplete_message)
+        {
+            return true;
+        }
+        if let Some(err) = err.downcast_ref::<std::io::Error>() {
+            return matches!(
+                err.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+            );
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// How much of a response body to read, from the request extensions.
+struct BodyPolicy {
+    /// Drop the body of a successful response, from [`DiscardBody`].
+    discard: bool,
+    inspect: Option<InspectHead>,
+    /// Maximum length of an error body, from [`ClientConfig::error_body_limit`].
+    error_body_limit: usize,
+}
+
+impl BodyPolicy {
+    fn of(extensions: &http::Extensions, config: &ClientConfig) -> Self {
+        Self {
+            discard: extensions.get::<DiscardBody>().is_some(),
+            inspect: extensions.get::<InspectHead>().cloned(),
+            error_body_limit: config.error_body_limit,
+        }
+    }
+
+    /// Decide what to do with the body of a response, before reading it.
+    fn action(&self, status: http::StatusCode, headers: &http::HeaderMap) -> Result<BodyAction> {
+        let action = match &self.inspect {
+            Some(inspect) => inspect.inspect(status.as_u16(), headers)?,
+            None => BodyAction::Collect,
+        };
+        if self.discard && status.is_success() {
+            return Ok(BodyAction::Discard);
+        }
+        let is_error = status.is_client_error() || status.is_server_error();
+        if is_error && action == BodyAction::Collect && self.error_body_limit < usize::MAX {
+            return Ok(BodyAction::Limit(self.error_body_limit));
+        }
+        Ok(action)
+    }
+}
+
+/// Largest `Content-Length` of a discarded body still read, so that its
+/// HTTP/1.1 connection goes back to the pool instead of being closed.
+const DISCARD_DRAIN_LIMIT: u64 = 16 * 1024;
+
+/// Read and drop `body` if its `Content-Length` is at most
+/// [`DISCARD_DRAIN_LIMIT`], otherwise drop it unread.
+///
+/// A failure to read is ignored: the connection is closed, as for an unread body.
+async fn drain_small(mut body: Incoming, headers: &http::HeaderMap) {
+    let length = headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+    if length.is_some_and(|length| length <= DISCARD_DRAIN_LIMIT) {
+        while let Some(Ok(_)) = body.frame().await {}
+    }
+}
+
+/// Read at most `limit` bytes of `body`, dropping the rest.
+///
+/// Returns whether the body was truncated.
+async fn collect_limited(mut body: Incoming, limit: usize) -> Result<(Bytes, bool)> {
+    let mut collected = BytesMut::new();
+    while let Some(frame) = body.frame().await {
+        let frame = frame.map_err(|e| Error::connection(e.to_string()))?;
+        let Ok(data) = frame.into_data() else {
+            continue;
+        };
+        let remaining = limit - collected.len();
+        if data.len() > remaining {
+            collected.extend_from_slice(&data.slice(..remaining));
+            return Ok((collected.freeze(), true));
+        }
+        collected.extend_from_slice(&data);
+    }
+    Ok((collected.freeze(), false))
 }
 
 impl Service<Request<Bytes>> for RawHyperClient {
@@ -238,6 +539,56 @@ impl Service<Request<Bytes>> for RawHyperClient {
     }
 }
 
+/// Client around a hyper-util client configured by the caller (see
+/// [`HyperClient::from_hyper`]).
+struct ExternalHyperClient<Conn> {
+    inner: Client<Conn, Full<Bytes>>,
+    config: ClientConfig,
+}
+
+impl<Conn: Clone> Clone for ExternalHyperClient<Conn> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+impl<Conn> ExternalHyperClient<Conn>
+where
+    Conn: Connect + Clone + Send + Sync + 'static,
+{
+    async fn execute(&self, request: Request<Bytes>) -> Result<Response<Bytes>> {
+        let http_request = RawHyperClient::into_http_request(request)?;
+        let timeout = self.config.request_timeout(http_request.method(), false);
+        let policy = BodyPolicy::of(http_request.extensions(), &self.config);
+        let response = tokio::time::timeout(timeout, self.inner.request(http_request))
+            .await
+            .map_err(|_| Error::Timeout)?
+            .map_err(RawHyperClient::map_hyper_error)?;
+        RawHyperClient::buffer_response(response, policy).await
+    }
+}
+
+impl<Conn> Service<Request<Bytes>> for ExternalHyperClient<Conn>
+where
+    Conn: Connect + Clone + Send + Sync + 'static,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send + 'static>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let client = self.clone();
+        Box::pin(async move { client.execute(request).await })
+    }
+}
+
 // ============================================================================
 // Public Client
 // ============================================================================
@@ -261,7 +612,9 @@ impl Service<Request<Bytes>> for RawHyperClient {
 /// ```
 #[derive(Clone)]
 pub struct HyperClient {
-    service: SyncService,
+    service: BoxedService,
+    /// Client at the bottom of the middleware stack, for requests bypassing it.
+    raw: RawHyperClient,
     config: ClientConfig,
 }
 
@@ -285,7 +638,42 @@ impl HyperClient {
     pub fn with_config(config: ClientConfig) -> Self {
         let raw = RawHyperClient::new(config.clone());
         Self {
-            service: SyncService::new(BoxCloneService::new(raw)),
+            service: BoxCloneSyncService::new(raw.clone()),
+            raw,
+            config,
+        }
+    }
+
+    /// Create a client sending requests with an existing hyper-util client,
+    /// keeping its connector (proxies, custom DNS, Unix sockets, ...).
+    ///
+    /// Only the request timeouts of `config` apply: connection settings
+    /// (TLS, pool, connect timeout) are those of `client`, and
+    /// [`ConnectionHints`] are ignored. Streaming requests do not go through
+    /// `client` but through a connector built from `config`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use hyper_util::client::legacy::Client;
+    /// use hyper_util::rt::TokioExecutor;
+    /// use pincer::{ClientConfig, HyperClient};
+    ///
+    /// let hyper = Client::builder(TokioExecutor::new()).build(my_connector);
+    /// let client = HyperClient::from_hyper(hyper, ClientConfig::default());
+    /// ```
+    #[must_use]
+    pub fn from_hyper<Conn>(client: Client<Conn, Full<Bytes>>, config: ClientConfig) -> Self
+    where
+        Conn: Connect + Clone + Send + Sync + 'static,
+    {
+        let external = ExternalHyperClient {
+            inner: client,
+            config: config.clone(),
+        };
+        Self {
+            service: BoxCloneSyncService::new(external),
+            raw: RawHyperClient::new(config.clone()),
             config,
         }
     }
@@ -296,9 +684,10 @@ impl HyperClient {
     }
 
     /// Create a client with a pre-configured service (used by builder).
-    fn with_service(service: BoxedService, config: ClientConfig) -> Self {
+    fn with_service(service: BoxedService, raw: RawHyperClient, config: ClientConfig) -> Self {
         Self {
-            service: SyncService::new(service),
+            service,
+            raw,
             config,
         }
     }
@@ -314,6 +703,60 @@ impl HyperClient {
     pub const fn config(&self) -> &ClientConfig {
         &self.config
     }
+
+    /// Open `connections` connections to the host of `base_url` ahead of the
+    /// first request, to avoid latency spikes at startup.
+    ///
+    /// Resolves the host, loads the TLS configuration and completes the
+    /// handshakes by sending concurrent `HEAD` requests to `base_url`. The
+    /// connections then stay idle in the pool, up to `pool_idle_per_host`;
+    /// an HTTP/2 server multiplexes them over a single connection.
+    ///
+    /// The requests bypass the middleware, so they are not retried, logged
+    /// or counted by rate limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `base_url` is invalid or a connection fails.
+    /// The response status is ignored.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let client = HyperClient::new();
+    /// client.warmup("https://api.example.com", 4).await?;
+    /// ```
+    pub async fn warmup(&self, base_url: impl AsRef<str>, connections: usize) -> Result<()> {
+        let url = url::Url::parse(base_url.as_ref()).map_err(Error::InvalidUrl)?;
+        let mut tasks = tokio::task::JoinSet::new();
+        for _ in 0..connections {
+            let request = Request::builder(crate::Method::Head, url.clone()).build();
+            tasks.spawn(self.raw.clone().oneshot(request));
+        }
+        while let Some(result) = tasks.join_next().await {
+            result.map_err(|e| Error::connection(e.to_string()))??;
+        }
+        Ok(())
+    }
+
+    /// Stream the items of every page, within the
+    /// [`paginate_options`](HyperClientBuilder::paginate_options) of the client.
+    ///
+    /// See [`paginate::stream_with`](crate::paginate::stream_with).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let repos = api.inner().paginate(|cursor| api.list_repos(cursor));
+    /// ```
+    #[cfg(feature = "paginate")]
+    pub fn paginate<T, F, Fut>(&self, fetch: F) -> impl futures_util::Stream<Item = Result<T>>
+    where
+        F: FnMut(Option<String>) -> Fut,
+        Fut: Future<Output = Result<crate::Page<T>>>,
+    {
+        crate::paginate::stream_with(self.config.paginate_options, fetch)
+    }
 }
 
 impl Default for HyperClient {
@@ -324,23 +767,101 @@ impl Default for HyperClient {
 
 impl pincer_core::HttpClient for HyperClient {
     async fn execute(&self, request: Request<Bytes>) -> Result<Response<Bytes>> {
-        self.service.call(request).await
+        let request = encode_json_body(request, self.config.json_codec)?;
+        let response = self.service.clone().oneshot(request).await?;
+        Ok(mark_response(
+            response,
+            self.config.strict_json,
+            self.config.json_codec,
+            self.config.error_body_preview_len,
+        ))
+    }
+}
+
+/// Re-encode the JSON body of `request` with the encoding options of `codec`,
+/// unless they are the defaults.
+///
+/// This runs before the middleware, so that signing layers see the final body.
+fn encode_json_body(
+    mut request: Request<Bytes>,
+    codec: crate::SerdeJson,
+) -> Result<Request<Bytes>> {
+    if codec.is_default_encoding() {
+        return Ok(request);
+    }
+    let is_json = request
+        .header("content-type")
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|media_type| media_type.trim().to_ascii_lowercase())
+        .is_some_and(|media_type| {
+            media_type == "application/json" || media_type.ends_with("+json")
+        });
+    if let Some(body) = request.body_mut().as_mut().filter(|_| is_json) {
+        *body = codec.transcode(body)?;
+    }
+    Ok(request)
+}
+
+/// Mark `response` to be decoded strictly if `strict`, see [`StrictJson`](crate::StrictJson),
+/// with the client JSON codec unless it is the default, and with the error
+/// body preview length, see [`mark_preview_len`].
+fn mark_response(
+    mut response: Response<Bytes>,
+    strict: bool,
+    json_codec: crate::SerdeJson,
+    preview_len: usize,
+) -> Response<Bytes> {
+    if strict {
+        response.extensions_mut().insert(crate::StrictJson);
+    }
+    if json_codec != crate::SerdeJson::new() {
+        response.extensions_mut().insert(json_codec);
+    }
+    mark_preview_len(response.extensions_mut(), preview_len);
+    response
+}
+
+/// Set the error body preview length in response `extensions`, unless it is
+/// the default, see [`BodyPreviewLen`](crate::BodyPreviewLen).
+fn mark_preview_len(extensions: &mut http::Extensions, len: usize) {
+    if len != crate::DEFAULT_BODY_PREVIEW_LEN {
+        extensions.insert(crate::BodyPreviewLen(len));
     }
 }
 
 /// Streaming HTTP client implementation.
 ///
 /// Note: Streaming bypasses middleware since we need to return the raw hyper response
-/// body. Middleware is applied to the buffered `execute()` method.
+/// body, and streamed uploads bypass it as well since their body cannot be replayed.
+/// Middleware (auth, retries, logging, ...) is applied to the buffered `execute()`
+/// method only. Both still share the connection pool of buffered requests, except
+/// for clients created with [`HyperClient::from_hyper`].
 #[cfg(feature = "streaming")]
 impl pincer_core::HttpClientStreaming for HyperClient {
     async fn execute_streaming(
         &self,
         request: Request<Bytes>,
     ) -> Result<pincer_core::StreamingResponse> {
-        // Create a raw client with the same config to bypass middleware
-        let raw_client = RawHyperClient::new(self.config.clone());
-        raw_client.execute_streaming(request).await
+        let mut response = self.raw.execute_streaming(request).await?;
+        mark_preview_len(
+            response.extensions_mut(),
+            self.config.error_body_preview_len,
+        );
+        Ok(response)
+    }
+
+    async fn execute_upload(
+        &self,
+        request: Request<Bytes>,
+        body: StreamingBody,
+    ) -> Result<Response<Bytes>> {
+        let response = self.raw.execute_upload(request, body).await?;
+        Ok(mark_response(
+            response,
+            self.config.strict_json,
+            self.config.json_codec,
+            self.config.error_body_preview_len,
+        ))
     }
 }
 
@@ -353,13 +874,37 @@ impl Service<Request<Bytes>> for HyperClient {
     type Error = Error;
     type Future = ServiceFuture;
 
-    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
-        // SyncService is always ready (the underlying service is polled when called)
-        Poll::Ready(Ok(()))
+    /// Readiness of the middleware stack.
+    ///
+    /// Layers such as concurrency and rate limits report `Pending` while they
+    /// have no capacity, so `ServiceExt::ready()`, load-shedding and `Buffer`
+    /// see the actual capacity of the client.
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.service.poll_ready(cx)
     }
 
     fn call(&mut self, request: Request<Bytes>) -> Self::Future {
-        self.service.call(request)
+        // Use the service that was polled ready (it may hold a permit) and
+        // keep a fresh clone for the next poll_ready
+        let fresh = self.service.clone();
+        let mut ready = std::mem::replace(&mut self.service, fresh);
+        let (strict, json_codec, preview_len) = (
+            self.config.strict_json,
+            self.config.json_codec,
+            self.config.error_body_preview_len,
+        );
+        let response = match encode_json_body(request, json_codec) {
+            Ok(request) => ready.call(request),
+            Err(err) => return Box::pin(std::future::ready(Err(err))),
+        };
+        Box::pin(async move {
+            Ok(mark_response(
+                response.await?,
+                strict,
+                json_codec,
+                preview_len,
+            ))
+        })
     }
 }
 
@@ -390,19 +935,47 @@ pub struct HyperClientBuilder {
     config: ClientConfigBuilder,
     layers: Vec<Arc<dyn Fn(BoxedService) -> BoxedService + Send + Sync>>,
     use_defaults: bool,
+    #[cfg(feature = "middleware-retry")]
+    with_retry: bool,
 }
 
 impl std::fmt::Debug for HyperClientBuilder {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("HyperClientBuilder")
+        let mut debug = f.debug_struct("HyperClientBuilder");
+        debug
             .field("config", &self.config)
             .field("layers_count", &self.layers.len())
-            .field("use_defaults", &self.use_defaults)
-            .finish()
+            .field("use_defaults", &self.use_defaults);
+        #[cfg(feature = "middleware-retry")]
+        debug.field("with_retry", &self.with_retry);
+        debug.finish()
     }
 }
 
 impl HyperClientBuilder {
+    /// Create a builder whose settings are overridden by environment variables.
+    ///
+    /// `PINCER_HTTP_TIMEOUT` and `PINCER_CONNECT_TIMEOUT` override the
+    /// timeouts, and `PINCER_RETRY_MAX` the maximum number of retries of
+    /// [`with_retry`](Self::with_retry), see [`ClientConfigBuilder::from_env`].
+    /// Without `with_retry`, `PINCER_RETRY_MAX` adds the retry middleware
+    /// around the connection, inside the layers added with `layer`, so a
+    /// `RetryLayer` added by hand should be replaced with `with_retry`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // PINCER_RETRY_MAX=5 makes it retry 5 times
+    /// let client = HyperClientBuilder::from_env().with_retry(3).build();
+    /// ```
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self {
+            config: ClientConfigBuilder::from_env(),
+            ..Self::default()
+        }
+    }
+
     // ========================================================================
     // Core Configuration
     // ========================================================================
@@ -414,7 +987,30 @@ impl HyperClientBuilder {
         self
     }
 
-    /// Set the connection timeout.
+    /// Set the timeout of reads (`GET`, `HEAD`), overriding `timeout`.
+    #[must_use]
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.config = self.config.read_timeout(timeout);
+        self
+    }
+
+    /// Set the timeout of writes (`POST`, `PUT`, `PATCH`, `DELETE`), overriding `timeout`.
+    #[must_use]
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.config = self.config.write_timeout(timeout);
+        self
+    }
+
+    /// Set the timeout of streaming requests (streamed responses and uploads).
+    ///
+    /// It bounds the time until the response headers are received.
+    #[must_use]
+    pub fn streaming_timeout(mut self, timeout: Duration) -> Self {
+        self.config = self.config.streaming_timeout(timeout);
+        self
+    }
+
+    /// Set the timeout of each TCP connection attempt, DNS resolution excluded.
     #[must_use]
     pub fn connect_timeout(mut self, timeout: Duration) -> Self {
         self.config = self.config.connect_timeout(timeout);
@@ -435,6 +1031,231 @@ impl HyperClientBuilder {
         self
     }
 
+    /// Set whether to retry idempotent requests once, on a fresh connection,
+    /// when their pooled connection was already closed by the server
+    /// (default: `true`), see [`ClientConfigBuilder::retry_on_connection_failure`].
+    #[must_use]
+    pub fn retry_on_connection_failure(mut self, retry: bool) -> Self {
+        self.config = self.config.retry_on_connection_failure(retry);
+        self
+    }
+
+    /// Fail on unknown fields when deserializing JSON responses, to detect
+    /// the drift of an API contract, e.g. in tests.
+    ///
+    /// `PINCER_STRICT_JSON` overrides it with [`from_env`](Self::from_env),
+    /// so it can be disabled in production.
+    ///
+    /// The methods of an API with a custom `json` codec decode with this
+    /// codec only, and are not affected.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let client = HyperClient::builder().strict_json(cfg!(test)).build();
+    /// ```
+    #[must_use]
+    pub fn strict_json(mut self, strict: bool) -> Self {
+        self.config = self.config.strict_json(strict);
+        self
+    }
+
+    /// Set the JSON codec of the client: responses are decoded with its
+    /// decoding options, and JSON request bodies re-encoded with its encoding
+    /// options, see [`SerdeJson`](crate::SerdeJson).
+    ///
+    /// It applies to every API using the client, while `#[pincer(json = "...")]`
+    /// selects the codec of one API.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::SerdeJson;
+    ///
+    /// let client = HyperClient::builder()
+    ///     .json_codec(SerdeJson::new().pretty(cfg!(debug_assertions)))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn json_codec(mut self, codec: crate::SerdeJson) -> Self {
+        self.config = self.config.json_codec(codec);
+        self
+    }
+
+    /// Set the maximum number of bytes read of an error response body
+    /// (default: [`DEFAULT_ERROR_BODY_LIMIT`](crate::DEFAULT_ERROR_BODY_LIMIT)).
+    ///
+    /// The rest of the body is dropped unread, so a server answering a 500
+    /// with a huge page cannot exhaust the memory; the response is marked
+    /// with [`TruncatedBody`]. Use `usize::MAX` for no limit.
+    #[must_use]
+    pub fn error_body_limit(mut self, limit: usize) -> Self {
+        self.config = self.config.error_body_limit(limit);
+        self
+    }
+
+    /// Set how many error body bytes are previewed when displaying HTTP
+    /// errors (default: [`DEFAULT_BODY_PREVIEW_LEN`](crate::DEFAULT_BODY_PREVIEW_LEN)).
+    ///
+    /// Use `0` to disable the preview, e.g. when error bodies may contain
+    /// sensitive data. The body itself stays available through
+    /// [`Error::body`].
+    #[must_use]
+    pub fn error_body_preview_len(mut self, len: usize) -> Self {
+        self.config = self.config.error_body_preview_len(len);
+        self
+    }
+
+    /// Only speak HTTP/1.1, without negotiating HTTP/2 over TLS.
+    ///
+    /// With the other HTTP/1 options, this helps talking to old or embedded
+    /// servers with a sloppy HTTP stack.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let client = HyperClient::builder()
+    ///     .http1_only(true)
+    ///     .http1_title_case_headers(true)
+    ///     .http1_allow_obsolete_multiline_headers(true)
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn http1_only(mut self, only: bool) -> Self {
+        self.config = self.config.http1_only(only);
+        self
+    }
+
+    /// Write HTTP/1 header names in title case (`Content-Type`), for servers
+    /// matching them case-sensitively.
+    #[must_use]
+    pub fn http1_title_case_headers(mut self, title_case: bool) -> Self {
+        self.config = self.config.http1_title_case_headers(title_case);
+        self
+    }
+
+    /// Accept obsolete multiline (folded) headers in HTTP/1 responses,
+    /// instead of failing to parse them.
+    #[must_use]
+    pub fn http1_allow_obsolete_multiline_headers(mut self, allow: bool) -> Self {
+        self.config = self.config.http1_allow_obsolete_multiline_headers(allow);
+        self
+    }
+
+    /// Set the maximum size of the headers of HTTP/1 responses, in bytes;
+    /// values below 8 KiB are raised to 8 KiB.
+    #[must_use]
+    pub fn http1_max_header_size(mut self, max: usize) -> Self {
+        self.config = self.config.http1_max_header_size(max);
+        self
+    }
+
+    /// Set the client certificate presented for mutual TLS.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let identity = Identity::from_pem(std::fs::read("client.pem")?)?;
+    /// let client = HyperClient::builder().identity(identity).build();
+    /// ```
+    #[must_use]
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.config = self.config.identity(identity);
+        self
+    }
+
+    /// Resolve host names through a TTL-aware DNS cache.
+    ///
+    /// Requires the `dns-cache` feature.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::{DnsCache, HyperClient};
+    ///
+    /// let dns = DnsCache::new()?;
+    /// let client = HyperClient::builder().dns_cache(dns.clone()).build();
+    /// ```
+    #[cfg(feature = "dns-cache")]
+    #[must_use]
+    pub fn dns_cache(mut self, cache: crate::DnsCache) -> Self {
+        self.config = self.config.dns_cache(cache);
+        self
+    }
+
+    /// Set the limits of the pagination streams of [`HyperClient::paginate`],
+    /// e.g. safety caps shared by every paginated method of the application.
+    ///
+    /// Requires the `paginate` feature.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::paginate::PaginateOptions;
+    ///
+    /// let client = HyperClient::builder()
+    ///     .paginate_options(PaginateOptions::new().max_pages(200).fail_on_limit())
+    ///     .build();
+    /// ```
+    #[cfg(feature = "paginate")]
+    #[must_use]
+    pub fn paginate_options(mut self, options: crate::paginate::PaginateOptions) -> Self {
+        self.config = self.config.paginate_options(options);
+        self
+    }
+
+    /// Capture the raw bytes of every connection, for protocol debugging.
+    ///
+    /// See [`WireCapture`](crate::WireCapture) for the size limits and redaction.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::{HyperClient, WireCapture};
+    ///
+    /// let client = HyperClient::builder()
+    ///     .wire_capture(WireCapture::tracing().max_bytes(4096))
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn wire_capture(mut self, capture: crate::WireCapture) -> Self {
+        self.config = self.config.wire_capture(capture);
+        self
+    }
+
+    /// Set the `Accept-Language` of the requests that do not set one.
+    ///
+    /// Takes a fallback chain of language tags, rendered with decreasing
+    /// quality values, see [`AcceptLanguage`](crate::AcceptLanguage). Applied
+    /// as a layer, at this position of the middleware stack. Languages that
+    /// are not valid header values are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Accept-Language: fr-CH, fr;q=0.9, en;q=0.8
+    /// let client = HyperClient::builder()
+    ///     .default_locale(["fr-CH", "fr", "en"])
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn default_locale(self, languages: impl Into<crate::AcceptLanguage>) -> Self {
+        let languages = languages.into();
+        let Some(value) = (!languages.is_empty())
+            .then(|| http::HeaderValue::try_from(languages.to_string()).ok())
+            .flatten()
+        else {
+            return self;
+        };
+        self.map_request(move |mut request| {
+            request
+                .headers_mut()
+                .entry(http::header::ACCEPT_LANGUAGE)
+                .or_insert_with(|| value.clone());
+            request
+        })
+    }
+
     // ========================================================================
     // Generic Middleware API (always available)
     // ========================================================================
@@ -461,11 +1282,12 @@ impl HyperClientBuilder {
         L::Service: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error>
             + Clone
             + Send
+            + Sync
             + 'static,
         <L::Service as Service<Request<Bytes>>>::Future: Send,
     {
         self.layers.push(Arc::new(move |service| {
-            BoxCloneService::new(layer.layer(service))
+            BoxCloneSyncService::new(layer.layer(service))
         }));
         self
     }
@@ -480,12 +1302,84 @@ impl HyperClientBuilder {
         L::Service: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error>
             + Clone
             + Send
+            + Sync
             + 'static,
         <L::Service as Service<Request<Bytes>>>::Future: Send,
     {
         self.layer(layer)
     }
 
+    /// Add a Tower layer applied only to the requests matching `predicate`.
+    ///
+    /// The predicate is evaluated on each request; other requests skip the
+    /// layer. See [`ConditionalLayer`](crate::middleware::ConditionalLayer).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::middleware::{RetryLayer, RetryPolicy};
+    /// use pincer::{HyperClient, Method};
+    ///
+    /// let client = HyperClient::builder()
+    ///     .layer_if(
+    ///         |request| request.method() == Method::Get,
+    ///         RetryLayer::new(RetryPolicy::new(3)),
+    ///     )
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn layer_if<L>(
+        self,
+        predicate: impl Fn(&Request<Bytes>) -> bool + Send + Sync + 'static,
+        layer: L,
+    ) -> Self
+    where
+        L: Layer<BoxedService> + Send + Sync + 'static,
+        L::Service: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        <L::Service as Service<Request<Bytes>>>::Future: Send,
+    {
+        self.layer(ConditionalLayer::new(predicate, layer))
+    }
+
+    /// Transform each request with `f`, see [`MapLayer`](crate::middleware::MapLayer).
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let client = HyperClient::builder()
+    ///     .map_request(|mut request| {
+    ///         request.headers_mut().insert("x-tenant", "acme".parse().expect("header"));
+    ///         request
+    ///     })
+    ///     .build();
+    /// ```
+    #[must_use]
+    pub fn map_request(
+        self,
+        f: impl Fn(Request<Bytes>) -> Request<Bytes> + Send + Sync + 'static,
+    ) -> Self {
+        self.layer(MapLayer::new().map_request(f))
+    }
+
+    /// Transform each response with `f`, see [`MapLayer`](crate::middleware::MapLayer).
+    #[must_use]
+    pub fn map_response(
+        self,
+        f: impl Fn(Response<Bytes>) -> Response<Bytes> + Send + Sync + 'static,
+    ) -> Self {
+        self.layer(MapLayer::new().map_response(f))
+    }
+
+    /// Transform each error with `f`, see [`MapLayer`](crate::middleware::MapLayer).
+    #[must_use]
+    pub fn map_error(self, f: impl Fn(Error) -> Error + Send + Sync + 'static) -> Self {
+        self.layer(MapLayer::new().map_error(f))
+    }
+
     // ========================================================================
     // Defaults Control
     // ========================================================================
@@ -527,7 +1421,9 @@ impl HyperClientBuilder {
     /// ```
     #[cfg(feature = "middleware-retry")]
     #[must_use]
-    pub fn with_retry(self, max_retries: u32) -> Self {
+    pub fn with_retry(mut self, max_retries: u32) -> Self {
+        let max_retries = self.config.max_retries_override().unwrap_or(max_retries);
+        self.with_retry = true;
         self.layer(RetryLayer::new(RetryPolicy::new(max_retries)))
     }
 
@@ -561,6 +1457,87 @@ impl HyperClientBuilder {
         self.layer(BasicAuthLayer::new(username, password))
     }
 
+    /// Add access token authentication middleware, with the tokens of `cache`.
+    ///
+    /// Share the cache between the clients using the same credentials: a
+    /// `401` response in one client refreshes the token for all of them.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let tokens = Arc::new(TokenCache::new(fetch_token));
+    /// let client = HyperClient::builder()
+    ///     .with_token_auth(Arc::clone(&tokens))
+    ///     .build();
+    /// ```
+    #[cfg(feature = "middleware-token-auth")]
+    #[must_use]
+    pub fn with_token_auth(self, cache: Arc<TokenCache>) -> Self {
+        self.layer(TokenAuthLayer::new(cache))
+    }
+
+    /// Add credential refresh middleware: on `401`, `refresh` returns the new
+    /// `Authorization` value, and the request is retried once with it.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let client = HyperClient::builder()
+    ///     .with_auth_refresh(move || {
+    ///         let auth = auth.clone();
+    ///         async move { Ok(format!("Bearer {}", auth.login().await?.token)) }
+    ///     })
+    ///     .build();
+    /// ```
+    #[cfg(feature = "middleware-auth-refresh")]
+    #[must_use]
+    pub fn with_auth_refresh<F, Fut>(self, refresh: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String>> + Send + 'static,
+    {
+        self.layer(AuthRefreshLayer::new(refresh))
+    }
+
+    /// Sign request bodies as detached JWS in the `x-jws-signature` header.
+    ///
+    /// Use [`JwsLayer`] directly for attached signatures or RFC 7797 payloads.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::middleware::{JwsAlgorithm, SigningKey};
+    ///
+    /// let key = SigningKey::from_pem(JwsAlgorithm::Ps256, pem)?;
+    /// let client = HyperClient::builder()
+    ///     .with_jws_signing(key)
+    ///     .build();
+    /// ```
+    #[cfg(feature = "middleware-jws")]
+    #[must_use]
+    pub fn with_jws_signing(self, key: SigningKey) -> Self {
+        self.layer(JwsLayer::new(key))
+    }
+
+    /// Attach a freshly minted JWT assertion as `Authorization: Bearer <jwt>`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::middleware::{JwsAlgorithm, JwtClaims, SigningKey};
+    ///
+    /// let key = SigningKey::from_pem(JwsAlgorithm::Rs256, pem)?;
+    /// let claims = JwtClaims::new().issuer("svc@example.com").audience("https://api.example.com/");
+    /// let client = HyperClient::builder()
+    ///     .with_jwt_assertion(key, claims)
+    ///     .build();
+    /// ```
+    #[cfg(feature = "middleware-jws")]
+    #[must_use]
+    pub fn with_jwt_assertion(self, key: SigningKey, claims: JwtClaims) -> Self {
+        self.layer(JwtLayer::new(key, claims))
+    }
+
     /// Add request/response logging.
     ///
     /// # Example
@@ -598,6 +1575,44 @@ impl HyperClientBuilder {
         self.layer(ConcurrencyLimitLayer::new(max))
     }
 
+    /// Reject requests with [`Error::Overloaded`] instead of waiting while
+    /// the layers added before are at capacity.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let client = HyperClient::builder()
+    ///     .with_concurrency_limit(10)
+    ///     .with_load_shed()
+    ///     .build();
+    /// ```
+    #[cfg(feature = "middleware-load-shed")]
+    #[must_use]
+    pub fn with_load_shed(self) -> Self {
+        self.layer(LoadShedLayer::new())
+    }
+
+    /// Queue up to `bound` requests in front of the layers added before.
+    ///
+    /// Requests are processed by a background task, so `build()` must then
+    /// be called within a Tokio runtime. Add a
+    /// [`BufferLayer`](crate::middleware::BufferLayer) with its own runtime
+    /// for another runtime.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let client = HyperClient::builder()
+    ///     .with_concurrency_limit(10)
+    ///     .with_buffer(100)
+    ///     .build();
+    /// ```
+    #[cfg(feature = "middleware-buffer")]
+    #[must_use]
+    pub fn with_buffer(self, bound: usize) -> Self {
+        self.layer(BufferLayer::new(bound))
+    }
+
     /// Add rate limiting (requests per second).
     ///
     /// # Example
@@ -690,6 +1705,31 @@ impl HyperClientBuilder {
         self.layer(MetricsLayer::new())
     }
 
+    /// Add structured access log middleware.
+    ///
+    /// Records one entry per request to `sink` (e.g., [`TracingSink`], a
+    /// [`JsonFileSink`] or a closure). Add it after `with_retry()`, so that
+    /// it wraps the retry layer and the entries carry the retry count.
+    ///
+    /// [`TracingSink`]: crate::middleware::TracingSink
+    /// [`JsonFileSink`]: crate::middleware::JsonFileSink
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::middleware::TracingSink;
+    ///
+    /// let client = HyperClient::builder()
+    ///     .with_retry(3)
+    ///     .with_access_log(TracingSink)
+    ///     .build();
+    /// ```
+    #[cfg(feature = "middleware-access-log")]
+    #[must_use]
+    pub fn with_access_log(self, sink: impl AccessLogSink) -> Self {
+        self.layer(AccessLogLayer::new(sink))
+    }
+
     /// Add follow redirect middleware.
     ///
     /// This middleware automatically follows HTTP redirects (301, 302, 303, 307, 308).
@@ -723,6 +1763,58 @@ impl HyperClientBuilder {
         self.layer(FollowRedirectLayer::with_max_redirects(max_redirects))
     }
 
+    /// Add response cache middleware, keyed by method and URL.
+    ///
+    /// Use [`layer`](Self::layer) with a [`CacheLayer`] to customize the cache
+    /// keys.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let client = HyperClient::builder()
+    ///     .with_cache()
+    ///     .build();
+    /// ```
+    #[cfg(feature = "middleware-cache")]
+    #[must_use]
+    pub fn with_cache(self) -> Self {
+        self.layer(CacheLayer::new())
+    }
+
+    /// Add cookie jar middleware, keeping the cookies of a session.
+    ///
+    /// Cookies set by responses are sent back on the matching requests.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let client = HyperClient::builder()
+    ///     .with_cookie_jar()
+    ///     .build();
+    /// ```
+    #[cfg(feature = "middleware-cookies")]
+    #[must_use]
+    pub fn with_cookie_jar(self) -> Self {
+        self.layer(CookieJarLayer::new())
+    }
+
+    /// Add cookie jar middleware using `store`, to read, seed or clear the
+    /// cookies, or share them between clients.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let cookies = Arc::new(CookieStore::new());
+    /// let client = HyperClient::builder()
+    ///     .with_cookie_store(Arc::clone(&cookies))
+    ///     .build();
+    /// ```
+    #[cfg(feature = "middleware-cookies")]
+    #[must_use]
+    pub fn with_cookie_store(self, store: Arc<crate::CookieStore>) -> Self {
+        self.layer(CookieJarLayer::with_store(store))
+    }
+
     /// Add automatic response decompression middleware.
     ///
     /// This middleware adds the `Accept-Encoding` header to requests and
@@ -749,26 +1841,39 @@ impl HyperClientBuilder {
     /// Build the client with all configured middleware.
     #[must_use]
     pub fn build(self) -> HyperClient {
+        #[cfg(feature = "middleware-retry")]
+        let max_retries_override = self
+            .config
+            .max_retries_override()
+            .filter(|_| !self.with_retry);
         let config = self.config.build();
         let base_client = HyperClient::with_config_raw(config.clone());
 
         // Start with base service
-        let mut service: BoxedService = BoxCloneService::new(base_client);
+        let mut service: BoxedService = BoxCloneSyncService::new(base_client.clone());
 
         // Apply default layers if enabled
         if self.use_defaults {
             #[cfg(feature = "middleware-logging")]
             {
-                service = BoxCloneService::new(LoggingLayer::new().layer(service));
+                service = BoxCloneSyncService::new(LoggingLayer::new().layer(service));
             }
         }
 
+        // PINCER_RETRY_MAX retries even if `with_retry` was not called
+        #[cfg(feature = "middleware-retry")]
+        if let Some(max_retries) = max_retries_override {
+            service = BoxCloneSyncService::new(
+                RetryLayer::new(RetryPolicy::new(max_retries)).layer(service),
+            );
+        }
+
         // Apply user layers in order (first added = outermost)
         for layer_fn in self.layers {
             service = layer_fn(service);
         }
 
-        HyperClient::with_service(service, config)
+        HyperClient::with_service(service, base_client, config)
     }
 }
 
@@ -785,11 +1890,11 @@ mod tests {
     #[test]
     fn client_builder() {
         let client = HyperClient::builder()
-            .timeout(std::time::Duration::from_secs(60))
+            .timeout(std::time::Duration::from_mins(1))
             .pool_idle_per_host(16)
             .build();
 
-        assert_eq!(client.config().timeout, std::time::Duration::from_secs(60));
+        assert_eq!(client.config().timeout, std::time::Duration::from_mins(1));
         assert_eq!(client.config().pool_idle_per_host, 16);
     }
 
@@ -805,4 +1910,111 @@ mod tests {
         let debug = format!("{client:?}");
         assert!(debug.contains("HyperClient"));
     }
+
+    #[test]
+    fn stale_retry_shares_the_body() {
+        use pincer_core::Method;
+
+        let url = url::Url::parse("http://localhost/items").expect("url");
+        let body = Bytes::from_static(b"{}");
+        let raw = RawHyperClient::new(ClientConfig::default());
+
+        let post = Request::builder(Method::Post, url.clone()).build();
+        assert!(raw.stale_retry(&post).is_none());
+
+        let put = Request::builder(Method::Put, url)
+            .header("x-id", "1")
+            .body(body.clone())
+            .build();
+        let retry = raw.stale_retry(&put).expect("retry").into_request();
+        assert_eq!(retry.header("x-id"), Some("1"));
+        assert_eq!(retry.body().map(|b| b.as_ptr()), Some(body.as_ptr()));
+        let hints = retry.extensions().get::<ConnectionHints>().expect("hints");
+        assert!(hints.is_fresh_connection());
+        assert!(raw.stale_retry(&retry).is_none());
+    }
+
+    #[cfg(feature = "middleware-retry")]
+    #[tokio::test]
+    async fn retry_max_env_override() {
+        use pincer_core::{HttpClient, Method};
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+        let url = url::Url::parse(&mock_server.uri()).expect("url");
+        let from_env = || HyperClientBuilder {
+            config: ClientConfigBuilder::from_env_with(|name| {
+                (name == "PINCER_RETRY_MAX").then(|| "2".to_string())
+            }),
+            ..HyperClientBuilder::default()
+        };
+
+        // Overrides the number of retries of `with_retry`
+        let client = from_env().with_retry(5).build();
+        let response = client
+            .execute(Request::builder(Method::Get, url.clone()).build())
+            .await
+            .expect("response");
+        assert_eq!(response.status(), 503);
+        let requests = mock_server.received_requests().await.expect("requests");
+        assert_eq!(requests.len(), 3);
+
+        // Adds the retry middleware without `with_retry`
+        mock_server.reset().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+        let client = from_env().build();
+        client
+            .execute(Request::builder(Method::Get, url).build())
+            .await
+            .expect("response");
+        let requests = mock_server.received_requests().await.expect("requests");
+        assert_eq!(requests.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn reuses_the_connection_of_small_discarded_bodies() {
+        use pincer_core::{HttpClient, Method};
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .and(path("/small"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![b'x'; 1024]))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/large"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![b'x'; 1024 * 1024]))
+            .mount(&mock_server)
+            .await;
+        let url = url::Url::parse(&mock_server.uri()).expect("url");
+        let client = HyperClient::new();
+        let delete = |path: &str| {
+            let request = Request::builder(Method::Delete, url.join(path).expect("url"))
+                .extension(DiscardBody)
+                .build();
+            client.execute(request)
+        };
+
+        let first = delete("/small").await.expect("response");
+        assert!(first.body().is_empty());
+        let second = delete("/small").await.expect("response");
+        assert!(first.local_addr().is_some());
+        assert_eq!(second.local_addr(), first.local_addr());
+
+        // The connection of a large body is closed instead
+        let large = delete("/large").await.expect("response");
+        assert_eq!(large.local_addr(), first.local_addr());
+        let next = delete("/small").await.expect("response");
+        assert_ne!(next.local_addr(), first.local_addr());
+    }
 }
@@ -0,0 +1,256 @@
+//! Registry of the API clients of an application.
+//!
+//! A [`ClientRegistry`] shares one [`HyperClient`] (connection pool and
+//! middleware) between the clients of several APIs, stores the built clients
+//! by name, and shuts them all down together.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use bytes::Bytes;
+use tokio::sync::Notify;
+
+use crate::{
+    ApiClient, ClientName, Error, HttpClient, HyperClient, Request, RequestScope, Response, Result,
+    Scoped,
+};
+
+/// Registry of named API clients sharing one [`HyperClient`].
+///
+/// Clients created with [`api`](Self::api) tag their requests with their
+/// [`ClientName`] (unless the generated client already set one), so logging,
+/// metrics and access logs are scoped per API, and stop with
+/// [`shutdown`](Self::shutdown).
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::{ClientRegistry, HyperClient};
+///
+/// let registry = ClientRegistry::new(HyperClient::builder().with_metrics().build());
+/// registry.register("github", registry.api("github", "https://api.github.com")?);
+/// let billing = BillingApiClient::builder().client(registry.http().clone()).build()?;
+/// registry.register("billing", billing);
+///
+/// // Later, anywhere the registry is shared
+/// let github: ApiClient<RegisteredClient> = registry.get("github").expect("github");
+///
+/// // On exit: wait up to 5s for in-flight requests, then abort them
+/// registry.shutdown(Duration::from_secs(5)).await;
+/// ```
+#[derive(Debug)]
+pub struct ClientRegistry {
+    http: HyperClient,
+    scope: RequestScope,
+    state: Arc<State>,
+    clients: Mutex<HashMap<&'static str, Arc<dyn Any + Send + Sync>>>,
+}
+
+/// Shutdown state shared by the clients of a registry.
+#[derive(Debug, Default)]
+struct State {
+    closed: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+impl ClientRegistry {
+    /// Create a registry sharing `http` between its clients.
+    #[must_use]
+    pub fn new(http: HyperClient) -> Self {
+        Self {
+            http,
+            scope: RequestScope::new(),
+            state: Arc::default(),
+            clients: Mutex::default(),
+        }
+    }
+
+    /// Get the shared HTTP client, e.g. for `mode = "wrapper"` clients.
+    ///
+    /// Requests sent through it directly are not tracked by
+    /// [`shutdown`](Self::shutdown).
+    #[must_use]
+    pub const fn http(&self) -> &HyperClient {
+        &self.http
+    }
+
+    /// Create a client of the API at `base_url`, named `name`.
+    ///
+    /// The client shares the HTTP client of the registry and stops with it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the base URL is invalid.
+    pub fn api(
+        &self,
+        name: &'static str,
+        base_url: impl AsRef<str>,
+    ) -> Result<ApiClient<RegisteredClient>> {
+        let client = RegisteredClient {
+            client: self.scope.attach(self.http.clone()),
+            name: ClientName::new(name),
+            state: Arc::clone(&self.state),
+        };
+        ApiClient::new(client, base_url)
+    }
+
+    /// Store a built client under `name`, replacing any client with that name.
+    pub fn register<T>(&self, name: &'static str, client: T)
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.clients().insert(name, Arc::new(client));
+    }
+
+    /// Get a clone of the client stored under `name`.
+    ///
+    /// Returns `None` if there is no such client, or it is not a `T`.
+    #[must_use]
+    pub fn get<T>(&self, name: &str) -> Option<T>
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.clients().get(name)?.downcast_ref::<T>().cloned()
+    }
+
+    /// Names of the stored clients, sorted.
+    #[must_use]
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self.clients().keys().copied().collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Number of requests in flight across the registry clients.
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.state.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Returns `true` once [`shutdown`](Self::shutdown) was called.
+    #[must_use]
+    pub fn is_shut_down(&self) -> bool {
+        self.state.closed.load(Ordering::SeqCst)
+    }
+
+    /// Stop the registry clients.
+    ///
+    /// New requests fail with [`Error::Cancelled`] right away. Requests in
+    /// flight get up to `grace` to complete, then are aborted.
+    pub async fn shutdown(&self, grace: Duration) {
+        self.state.closed.store(true, Ordering::SeqCst);
+        let drained = async {
+            loop {
+                let idle = self.state.idle.notified();
+                if self.in_flight() == 0 {
+                    break;
+                }
+                idle.await;
+            }
+        };
+        let _ = tokio::time::timeout(grace, drained).await;
+        self.scope.cancel();
+    }
+
+    fn clients(&self) -> MutexGuard<'_, HashMap<&'static str, Arc<dyn Any + Send + Sync>>> {
+        self.clients.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// HTTP client of a [`ClientRegistry`] API.
+#[derive(Debug, Clone)]
+pub struct RegisteredClient {
+    client: Scoped<HyperClient>,
+    name: ClientName,
+    state: Arc<State>,
+}
+
+impl RegisteredClient {
+    /// Get the name of the client.
+    #[must_use]
+    pub const fn name(&self) -> ClientName {
+        self.name
+    }
+}
+
+impl HttpClient for RegisteredClient {
+    fn execute(
+        &self,
+        mut request: Request<Bytes>,
+    ) -> impl Future<Output = Result<Response<Bytes>>> + Send {
+        let client = self.client.clone();
+        let state = Arc::clone(&self.state);
+        if request.extensions().get::<ClientName>().is_none() {
+            request.extensions_mut().insert(self.name);
+        }
+        async move {
+            if state.closed.load(Ordering::SeqCst) {
+                return Err(Error::Cancelled);
+            }
+            let _guard = InFlight::start(state);
+            client.execute(request).await
+        }
+    }
+}
+
+/// Counts a request in flight until dropped.
+struct InFlight(Arc<State>);
+
+impl InFlight {
+    fn start(state: Arc<State>) -> Self {
+        state.in_flight.fetch_add(1, Ordering::SeqCst);
+        Self(state)
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PincerClient;
+
+    #[test]
+    fn stores_clients_by_name_and_type() {
+        let registry = ClientRegistry::new(HyperClient::new());
+        let github = registry
+            .api("github", "https://api.github.com")
+            .expect("client");
+        registry.register("github", github);
+        registry.register("answer", 42_u32);
+
+        let github: ApiClient<RegisteredClient> = registry.get("github").expect("github");
+        assert_eq!(github.base_url().as_str(), "https://api.github.com/");
+        assert_eq!(github.inner().name().as_str(), "github");
+        assert_eq!(registry.get::<u32>("answer"), Some(42));
+        assert_eq!(registry.get::<u64>("answer"), None);
+        assert_eq!(registry.names(), ["answer", "github"]);
+    }
+
+    #[tokio::test]
+    async fn shutdown_rejects_new_requests() {
+        let registry = ClientRegistry::new(HyperClient::new());
+        let client = registry.api("local", "http://127.0.0.1:9").expect("client");
+
+        registry.shutdown(Duration::from_secs(1)).await;
+        assert!(registry.is_shut_down());
+
+        let request = Request::builder(crate::Method::Get, client.base_url().clone()).build();
+        let err = PincerClient::execute(&client, request)
+            .await
+            .expect_err("cancelled");
+        assert!(err.is_cancelled());
+        assert_eq!(registry.in_flight(), 0);
+    }
+}
@@ -1,30 +1,106 @@
 //! Client configuration types.
+//!
+//! [`ClientConfigBuilder::from_env`] reads overrides from environment
+//! variables, so deployments can tune a client without a new release:
+//! - `PINCER_HTTP_TIMEOUT` - request timeout
+//! - `PINCER_CONNECT_TIMEOUT` - connection timeout
+//! - `PINCER_RETRY_MAX` - maximum number of retries of `with_retry`, which
+//!   also enables retries on a `HyperClientBuilder` without `with_retry`
+//! - `PINCER_STRICT_JSON` - fail on unknown JSON fields (`true` or `false`)
+//!
+//! Durations are given as `500ms`, `30s`, `2m` or a number of seconds.
+//! Malformed values are ignored with a warning.
 
 use std::time::Duration;
 
+use crate::{DEFAULT_BODY_PREVIEW_LEN, DEFAULT_ERROR_BODY_LIMIT, Identity, WireCapture};
+
 /// Configuration for the HTTP client.
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone)]
 pub struct ClientConfig {
     /// Request timeout duration.
     pub timeout: Duration,
-    /// Connection timeout duration.
+    /// Timeout of reads (`GET`, `HEAD`), overriding `timeout`.
+    pub read_timeout: Option<Duration>,
+    /// Timeout of writes (`POST`, `PUT`, `PATCH`, `DELETE`), overriding `timeout`.
+    pub write_timeout: Option<Duration>,
+    /// Timeout of streaming requests until the response headers are received,
+    /// overriding the method class timeouts.
+    pub streaming_timeout: Option<Duration>,
+    /// Timeout of each TCP connection attempt.
+    ///
+    /// Host name resolution is not included: it is bounded by the resolver,
+    /// e.g. [`DnsCache::with_lookup_timeout`](crate::DnsCache) with the
+    /// `dns-cache` feature.
     pub connect_timeout: Duration,
     /// Maximum idle connections per host.
     pub pool_idle_per_host: usize,
     /// Idle connection timeout.
     pub pool_idle_timeout: Duration,
-    /// Whether to retry on connection errors.
+    /// Whether to retry idempotent requests once, on a fresh connection,
+    /// when their pooled connection was already closed by the server.
     pub retry_on_connection_failure: bool,
+    /// Client certificate presented for mutual TLS.
+    pub identity: Option<Identity>,
+    /// DNS cache used to resolve host names (system resolver if `None`).
+    #[cfg(feature = "dns-cache")]
+    pub dns_cache: Option<crate::DnsCache>,
+    /// Capture of the raw bytes of every connection, for debugging.
+    pub wire_capture: Option<WireCapture>,
+    /// Whether JSON responses fail on unknown fields, see
+    /// [`StrictJson`](crate::StrictJson).
+    pub strict_json: bool,
+    /// JSON codec decoding responses and re-encoding JSON request bodies,
+    /// see [`SerdeJson`](crate::SerdeJson).
+    pub json_codec: crate::SerdeJson,
+    /// Maximum number of bytes read of an error response body, the rest is
+    /// dropped (`usize::MAX` for no limit).
+    pub error_body_limit: usize,
+    /// Number of error body bytes previewed when displaying HTTP errors,
+    /// see [`BodyPreviewLen`](crate::BodyPreviewLen) (`0` for none).
+    pub error_body_preview_len: usize,
+    /// Whether to only speak HTTP/1.1, without negotiating HTTP/2 over TLS.
+    pub http1_only: bool,
+    /// Whether to write HTTP/1 header names in title case (`Content-Type`),
+    /// for servers matching them case-sensitively.
+    pub http1_title_case_headers: bool,
+    /// Whether to accept obsolete multiline (folded) headers in HTTP/1
+    /// responses, joining their lines with a space.
+    pub http1_allow_obsolete_multiline_headers: bool,
+    /// Maximum size of the buffer reading HTTP/1 responses, bounding the size
+    /// of their headers (hyper default, about 400 KiB, if `None`).
+    pub http1_max_header_size: Option<usize>,
+    /// Limits of the pagination streams of [`HyperClient::paginate`](crate::HyperClient::paginate).
+    #[cfg(feature = "paginate")]
+    pub paginate_options: crate::paginate::PaginateOptions,
 }
 
 impl Default for ClientConfig {
     fn default() -> Self {
         Self {
             timeout: Duration::from_secs(30),
+            read_timeout: None,
+            write_timeout: None,
+            streaming_timeout: None,
             connect_timeout: Duration::from_secs(10),
             pool_idle_per_host: 32,
             pool_idle_timeout: Duration::from_secs(90),
             retry_on_connection_failure: true,
+            identity: None,
+            #[cfg(feature = "dns-cache")]
+            dns_cache: None,
+            wire_capture: None,
+            strict_json: false,
+            json_codec: crate::SerdeJson::new(),
+            error_body_limit: DEFAULT_ERROR_BODY_LIMIT,
+            error_body_preview_len: DEFAULT_BODY_PREVIEW_LEN,
+            http1_only: false,
+            http1_title_case_headers: false,
+            http1_allow_obsolete_multiline_headers: false,
+            http1_max_header_size: None,
+            #[cfg(feature = "paginate")]
+            paginate_options: crate::paginate::PaginateOptions::new(),
         }
     }
 }
@@ -35,19 +111,137 @@ impl ClientConfig {
     pub fn builder() -> ClientConfigBuilder {
         ClientConfigBuilder::default()
     }
+
+    /// Timeout of a request with the given method.
+    pub(crate) fn request_timeout(&self, method: &http::Method, streaming: bool) -> Duration {
+        let class_timeout = match *method {
+            http::Method::GET | http::Method::HEAD => self.read_timeout,
+            http::Method::POST | http::Method::PUT | http::Method::PATCH | http::Method::DELETE => {
+                self.write_timeout
+            }
+            _ => None,
+        };
+
+        streaming
+            .then_some(self.streaming_timeout)
+            .flatten()
+            .or(class_timeout)
+            .unwrap_or(self.timeout)
+    }
 }
 
 /// Builder for [`ClientConfig`].
 #[derive(Debug, Clone, Default)]
 pub struct ClientConfigBuilder {
     timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    streaming_timeout: Option<Duration>,
     connect_timeout: Option<Duration>,
     pool_idle_per_host: Option<usize>,
     pool_idle_timeout: Option<Duration>,
     retry_on_connection_failure: Option<bool>,
+    identity: Option<Identity>,
+    #[cfg(feature = "dns-cache")]
+    dns_cache: Option<crate::DnsCache>,
+    wire_capture: Option<WireCapture>,
+    strict_json: Option<bool>,
+    json_codec: Option<crate::SerdeJson>,
+    error_body_limit: Option<usize>,
+    error_body_preview_len: Option<usize>,
+    http1_only: Option<bool>,
+    http1_title_case_headers: Option<bool>,
+    http1_allow_obsolete_multiline_headers: Option<bool>,
+    http1_max_header_size: Option<usize>,
+    #[cfg(feature = "paginate")]
+    paginate_options: Option<crate::paginate::PaginateOptions>,
+    env: EnvOverrides,
+}
+
+/// Values read from environment variables, overriding the ones set in code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct EnvOverrides {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    max_retries: Option<u32>,
+    strict_json: Option<bool>,
+}
+
+impl EnvOverrides {
+    /// Read the overrides with `var`, the value of a variable if set.
+    fn read(var: impl Fn(&str) -> Option<String>) -> Self {
+        Self {
+            timeout: read_var(&var, "PINCER_HTTP_TIMEOUT", parse_duration),
+            connect_timeout: read_var(&var, "PINCER_CONNECT_TIMEOUT", parse_duration),
+            max_retries: read_var(&var, "PINCER_RETRY_MAX", |value| value.trim().parse().ok()),
+            strict_json: read_var(&var, "PINCER_STRICT_JSON", parse_bool),
+        }
+    }
+}
+
+/// Read and parse the variable `name`, warning if its value is invalid.
+fn read_var<T>(
+    var: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<T> {
+    let value = var(name)?;
+    let parsed = parse(&value);
+    if parsed.is_none() {
+        tracing::warn!(name, value, "ignoring invalid environment override");
+    }
+    parsed
+}
+
+/// Parse `500ms`, `30s`, `2m` or a number of seconds.
+fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Some(millis) = value.strip_suffix("ms") {
+        return millis.trim().parse().ok().map(Duration::from_millis);
+    }
+    if let Some(secs) = value.strip_suffix('s') {
+        return secs.trim().parse().ok().map(Duration::from_secs);
+    }
+    if let Some(mins) = value.strip_suffix('m') {
+        return mins.trim().parse().ok().map(Duration::from_mins);
+    }
+    value.parse().ok().map(Duration::from_secs)
+}
+
+/// Parse `true`/`false`, `1`/`0`, `yes`/`no` or `on`/`off`.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
 }
 
 impl ClientConfigBuilder {
+    /// Create a builder whose values are overridden by environment variables
+    /// (see the [module docs](self)).
+    ///
+    /// Overrides win over the values set in code, so they can be changed in
+    /// the field.
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::from_env_with(|name| std::env::var(name).ok())
+    }
+
+    /// Create a builder with overrides read by `var` instead of from the environment.
+    pub(crate) fn from_env_with(var: impl Fn(&str) -> Option<String>) -> Self {
+        Self {
+            env: EnvOverrides::read(var),
+            ..Self::default()
+        }
+    }
+
+    /// Maximum number of retries set by `PINCER_RETRY_MAX`, if any.
+    #[cfg_attr(not(feature = "middleware-retry"), allow(dead_code))]
+    pub(crate) const fn max_retries_override(&self) -> Option<u32> {
+        self.env.max_retries
+    }
+
     /// Set the request timeout.
     #[must_use]
     pub const fn timeout(mut self, timeout: Duration) -> Self {
@@ -55,7 +249,31 @@ impl ClientConfigBuilder {
         self
     }
 
-    /// Set the connection timeout.
+    /// Set the timeout of reads (`GET`, `HEAD`).
+    #[must_use]
+    pub const fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout of writes (`POST`, `PUT`, `PATCH`, `DELETE`).
+    #[must_use]
+    pub const fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout of streaming requests (streamed responses and uploads).
+    ///
+    /// It bounds the time until the response headers are received, not the
+    /// reading of a streamed body.
+    #[must_use]
+    pub const fn streaming_timeout(mut self, timeout: Duration) -> Self {
+        self.streaming_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout of each TCP connection attempt, DNS resolution excluded.
     #[must_use]
     pub const fn connect_timeout(mut self, timeout: Duration) -> Self {
         self.connect_timeout = Some(timeout);
@@ -76,20 +294,129 @@ impl ClientConfigBuilder {
         self
     }
 
-    /// Set whether to retry on connection failures.
+    /// Set whether to retry idempotent requests once, on a fresh connection,
+    /// when their pooled connection was already closed by the server
+    /// (default: `true`).
+    ///
+    /// This happens when a server closes idle connections sooner than the
+    /// pool; the request fails with "connection closed before message
+    /// completed". It is independent of the retry middleware.
     #[must_use]
     pub const fn retry_on_connection_failure(mut self, retry: bool) -> Self {
         self.retry_on_connection_failure = Some(retry);
         self
     }
 
+    /// Set the client certificate presented for mutual TLS.
+    #[must_use]
+    pub fn identity(mut self, identity: Identity) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Set the DNS cache used to resolve host names.
+    #[cfg(feature = "dns-cache")]
+    #[must_use]
+    pub fn dns_cache(mut self, cache: crate::DnsCache) -> Self {
+        self.dns_cache = Some(cache);
+        self
+    }
+
+    /// Capture the raw bytes of every connection.
+    #[must_use]
+    pub fn wire_capture(mut self, capture: WireCapture) -> Self {
+        self.wire_capture = Some(capture);
+        self
+    }
+
+    /// Set whether JSON responses fail on unknown fields, to detect the drift
+    /// of an API contract, e.g. in tests.
+    #[must_use]
+    pub const fn strict_json(mut self, strict: bool) -> Self {
+        self.strict_json = Some(strict);
+        self
+    }
+
+    /// Set the JSON codec of the client (default: `serde_json` defaults).
+    #[must_use]
+    pub const fn json_codec(mut self, codec: crate::SerdeJson) -> Self {
+        self.json_codec = Some(codec);
+        self
+    }
+
+    /// Set the maximum number of bytes read of an error response body
+    /// (default: [`DEFAULT_ERROR_BODY_LIMIT`]).
+    #[must_use]
+    pub const fn error_body_limit(mut self, limit: usize) -> Self {
+        self.error_body_limit = Some(limit);
+        self
+    }
+
+    /// Set how many error body bytes are previewed when displaying HTTP
+    /// errors (default: [`DEFAULT_BODY_PREVIEW_LEN`]).
+    #[must_use]
+    pub const fn error_body_preview_len(mut self, len: usize) -> Self {
+        self.error_body_preview_len = Some(len);
+        self
+    }
+
+    /// Set whether to only speak HTTP/1.1, e.g. for servers failing the
+    /// HTTP/2 negotiation.
+    #[must_use]
+    pub const fn http1_only(mut self, only: bool) -> Self {
+        self.http1_only = Some(only);
+        self
+    }
+
+    /// Set whether to write HTTP/1 header names in title case.
+    #[must_use]
+    pub const fn http1_title_case_headers(mut self, title_case: bool) -> Self {
+        self.http1_title_case_headers = Some(title_case);
+        self
+    }
+
+    /// Set whether to accept obsolete multiline headers in HTTP/1 responses.
+    #[must_use]
+    pub const fn http1_allow_obsolete_multiline_headers(mut self, allow: bool) -> Self {
+        self.http1_allow_obsolete_multiline_headers = Some(allow);
+        self
+    }
+
+    /// Set the maximum size of the headers of HTTP/1 responses, in bytes, at
+    /// least 8 KiB.
+    #[must_use]
+    pub const fn http1_max_header_size(mut self, max: usize) -> Self {
+        self.http1_max_header_size = Some(max);
+        self
+    }
+
+    /// Set the limits of the pagination streams of the client (default:
+    /// [`PaginateOptions::new`](crate::paginate::PaginateOptions::new)).
+    #[cfg(feature = "paginate")]
+    #[must_use]
+    pub const fn paginate_options(mut self, options: crate::paginate::PaginateOptions) -> Self {
+        self.paginate_options = Some(options);
+        self
+    }
+
     /// Build the configuration.
     #[must_use]
     pub fn build(self) -> ClientConfig {
         let defaults = ClientConfig::default();
         ClientConfig {
-            timeout: self.timeout.unwrap_or(defaults.timeout),
-            connect_timeout: self.connect_timeout.unwrap_or(defaults.connect_timeout),
+            timeout: self
+                .env
+                .timeout
+                .or(self.timeout)
+                .unwrap_or(defaults.timeout),
+            read_timeout: self.read_timeout,
+            write_timeout: self.write_timeout,
+            streaming_timeout: self.streaming_timeout,
+            connect_timeout: self
+                .env
+                .connect_timeout
+                .or(self.connect_timeout)
+                .unwrap_or(defaults.connect_timeout),
             pool_idle_per_host: self
                 .pool_idle_per_host
                 .unwrap_or(defaults.pool_idle_per_host),
@@ -97,6 +424,32 @@ impl ClientConfigBuilder {
             retry_on_connection_failure: self
                 .retry_on_connection_failure
                 .unwrap_or(defaults.retry_on_connection_failure),
+            identity: self.identity,
+            #[cfg(feature = "dns-cache")]
+            dns_cache: self.dns_cache,
+            wire_capture: self.wire_capture,
+            strict_json: self
+                .env
+                .strict_json
+                .or(self.strict_json)
+                .unwrap_or(defaults.strict_json),
+            json_codec: self.json_codec.unwrap_or(defaults.json_codec),
+            error_body_limit: self.error_body_limit.unwrap_or(defaults.error_body_limit),
+            error_body_preview_len: self
+                .error_body_preview_len
+                .unwrap_or(defaults.error_body_preview_len),
+            http1_only: self.http1_only.unwrap_or(defaults.http1_only),
+            http1_title_case_headers: self
+                .http1_title_case_headers
+                .unwrap_or(defaults.http1_title_case_headers),
+            http1_allow_obsolete_multiline_headers: self
+                .http1_allow_obsolete_multiline_headers
+                .unwrap_or(defaults.http1_allow_obsolete_multiline_headers),
+            http1_max_header_size: self
+                .http1_max_header_size
+                .or(defaults.http1_max_header_size),
+            #[cfg(feature = "paginate")]
+            paginate_options: self.paginate_options.unwrap_or(defaults.paginate_options),
         }
     }
 }
@@ -111,18 +464,121 @@ mod tests {
         assert_eq!(config.timeout, Duration::from_secs(30));
         assert_eq!(config.connect_timeout, Duration::from_secs(10));
         assert_eq!(config.pool_idle_per_host, 32);
+        assert_eq!(config.error_body_limit, 64 * 1024);
+        assert_eq!(config.error_body_preview_len, 256);
+        assert_eq!(config.json_codec, crate::SerdeJson::new());
+        assert!(!config.http1_only);
+        assert_eq!(config.http1_max_header_size, None);
+        assert!(config.identity.is_none());
     }
 
     #[test]
     fn builder_overrides() {
         let config = ClientConfig::builder()
-            .timeout(Duration::from_secs(60))
+            .timeout(Duration::from_mins(1))
             .connect_timeout(Duration::from_secs(5))
             .pool_idle_per_host(16)
+            .error_body_limit(1024)
+            .error_body_preview_len(0)
+            .json_codec(crate::SerdeJson::new().pretty(true))
+            .http1_only(true)
+            .http1_title_case_headers(true)
+            .http1_max_header_size(16 * 1024)
             .build();
 
-        assert_eq!(config.timeout, Duration::from_secs(60));
+        assert_eq!(config.timeout, Duration::from_mins(1));
         assert_eq!(config.connect_timeout, Duration::from_secs(5));
         assert_eq!(config.pool_idle_per_host, 16);
+        assert_eq!(config.error_body_limit, 1024);
+        assert_eq!(config.error_body_preview_len, 0);
+        assert_eq!(config.json_codec, crate::SerdeJson::new().pretty(true));
+        assert!(config.http1_only);
+        assert!(config.http1_title_case_headers);
+        assert!(!config.http1_allow_obsolete_multiline_headers);
+        assert_eq!(config.http1_max_header_size, Some(16 * 1024));
+    }
+
+    #[test]
+    fn env_overrides_win_over_code() {
+        let env = |name: &str| match name {
+            "PINCER_HTTP_TIMEOUT" => Some("500ms".to_string()),
+            "PINCER_CONNECT_TIMEOUT" => Some("soon".to_string()),
+            "PINCER_RETRY_MAX" => Some(" 5 ".to_string()),
+            _ => None,
+        };
+        let builder = ClientConfigBuilder::from_env_with(env)
+            .timeout(Duration::from_secs(30))
+            .connect_timeout(Duration::from_secs(5));
+        assert_eq!(builder.max_retries_override(), Some(5));
+
+        let config = builder.build();
+        assert_eq!(config.timeout, Duration::from_millis(500));
+        assert_eq!(config.connect_timeout, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn strict_json_env_override() {
+        let config = ClientConfig::builder().strict_json(true).build();
+        assert!(config.strict_json);
+
+        let env = |name: &str| (name == "PINCER_STRICT_JSON").then(|| "off".to_string());
+        let config = ClientConfigBuilder::from_env_with(env)
+            .strict_json(true)
+            .build();
+        assert!(!config.strict_json);
+        assert_eq!(parse_bool(" Yes "), Some(true));
+        assert_eq!(parse_bool("maybe"), None);
+    }
+
+    #[test]
+    fn parse_env_durations() {
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("45s"), Some(Duration::from_secs(45)));
+        assert_eq!(parse_duration("2m"), Some(Duration::from_mins(2)));
+        assert_eq!(parse_duration("10"), Some(Duration::from_secs(10)));
+        assert_eq!(parse_duration("1h"), None);
+    }
+
+    #[test]
+    fn request_timeout_per_method_class() {
+        let config = ClientConfig::builder()
+            .timeout(Duration::from_secs(30))
+            .read_timeout(Duration::from_secs(5))
+            .write_timeout(Duration::from_mins(2))
+            .build();
+
+        assert_eq!(
+            config.request_timeout(&http::Method::GET, false),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            config.request_timeout(&http::Method::DELETE, false),
+            Duration::from_mins(2)
+        );
+        assert_eq!(
+            config.request_timeout(&http::Method::OPTIONS, false),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn request_timeout_streaming() {
+        let config = ClientConfig::builder()
+            .read_timeout(Duration::from_secs(5))
+            .streaming_timeout(Duration::from_mins(10))
+            .build();
+
+        assert_eq!(
+            config.request_timeout(&http::Method::GET, true),
+            Duration::from_mins(10)
+        );
+
+        let config = ClientConfig::builder()
+            .read_timeout(Duration::from_secs(5))
+            .build();
+        assert_eq!(
+            config.request_timeout(&http::Method::GET, true),
+            Duration::from_secs(5)
+        );
     }
 }
@@ -1,28 +1,303 @@
 //! HTTPS connector using rustls.
 
-use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
-use hyper_util::client::legacy::connect::HttpConnector;
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll, ready};
+
+use hyper::Uri;
+use hyper::rt::{Read, ReadBufCursor, Write};
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder, MaybeHttpsStream};
+use hyper_util::client::legacy::connect::dns::{GaiResolver, Name};
+use hyper_util::client::legacy::connect::{Connected, Connection, HttpConnector};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpStream;
+use tower_service::Service;
+
+use crate::capture::ConnectionCapture;
+use crate::{ClientConfig, TlsInfo, WireCapture, WireDirection};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Host name resolver used by the connector.
+#[derive(Clone)]
+pub(crate) enum Resolver {
+    /// System resolver (`getaddrinfo`).
+    System(GaiResolver),
+    /// Always resolves to the same address.
+    Static(IpAddr),
+    /// TTL-aware cache.
+    #[cfg(feature = "dns-cache")]
+    Cached(crate::DnsCache),
+}
+
+impl Resolver {
+    fn new(config: &ClientConfig) -> Self {
+        #[cfg(feature = "dns-cache")]
+        if let Some(cache) = &config.dns_cache {
+            return Self::Cached(cache.clone());
+        }
+        #[cfg(not(feature = "dns-cache"))]
+        let _ = config;
+
+        Self::System(GaiResolver::new())
+    }
+}
+
+impl Service<Name> for Resolver {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self {
+            Self::System(resolver) => resolver.poll_ready(cx).map_err(Into::into),
+            Self::Static(_) => Poll::Ready(Ok(())),
+            #[cfg(feature = "dns-cache")]
+            Self::Cached(_) => Poll::Ready(Ok(())),
+        }
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        match self {
+            Self::System(resolver) => {
+                let resolving = resolver.call(name);
+                Box::pin(async move { Ok(resolving.await?.collect::<Vec<_>>().into_iter()) })
+            }
+            Self::Static(addr) => {
+                let addrs = vec![SocketAddr::new(*addr, 0)];
+                Box::pin(async move { Ok(addrs.into_iter()) })
+            }
+            #[cfg(feature = "dns-cache")]
+            Self::Cached(cache) => {
+                let cache = cache.clone();
+                Box::pin(async move {
+                    let addrs = cache.resolve(name.as_str()).await?;
+                    Ok(addrs
+                        .into_iter()
+                        .map(|ip| SocketAddr::new(ip, 0))
+                        .collect::<Vec<_>>()
+                        .into_iter())
+                })
+            }
+        }
+    }
+}
+
+/// Mozilla root certificates, loaded on first use and shared by all clients.
+fn root_store() -> Arc<rustls::RootCertStore> {
+    static ROOTS: OnceLock<Arc<rustls::RootCertStore>> = OnceLock::new();
+    Arc::clone(
+        ROOTS.get_or_init(|| Arc::new(webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect())),
+    )
+}
 
 /// Create an HTTPS connector with rustls.
 ///
-/// This connector supports both HTTP/1.1 and HTTP/2, with TLS enabled
-/// using the Mozilla root certificates.
+/// This connector supports both HTTP/1.1 and HTTP/2, or only HTTP/1.1 if
+/// [`http1_only`](ClientConfig::http1_only), with TLS enabled using the
+/// Mozilla root certificates. If the configuration carries an
+/// [`Identity`](crate::Identity), it is presented for mutual TLS.
 #[must_use]
-pub fn https_connector() -> HttpsConnector<HttpConnector> {
-    // Build rustls client config with webpki roots
-    let root_store: rustls::RootCertStore =
-        webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect();
+pub(crate) fn https_connector(
+    config: &ClientConfig,
+    resolver: Resolver,
+) -> HttpsConnector<HttpConnector<Resolver>> {
+    let tls_config = rustls::ClientConfig::builder().with_root_certificates(root_store());
+    let tls_config = match &config.identity {
+        Some(identity) => tls_config.with_client_cert_resolver(identity.resolver()),
+        None => tls_config.with_no_client_auth(),
+    };
 
-    let tls_config = rustls::ClientConfig::builder()
-        .with_root_certificates(root_store)
-        .with_no_client_auth();
+    let mut http = HttpConnector::new_with_resolver(resolver);
+    http.enforce_http(false);
+    http.set_connect_timeout(Some(config.connect_timeout));
 
-    HttpsConnectorBuilder::new()
+    let builder = HttpsConnectorBuilder::new()
         .with_tls_config(tls_config)
         .https_or_http()
-        .enable_http1()
-        .enable_http2()
-        .build()
+        .enable_http1();
+    if config.http1_only {
+        builder.wrap_connector(http)
+    } else {
+        builder.enable_http2().wrap_connector(http)
+    }
+}
+
+/// Connector exposing negotiated TLS parameters to responses.
+///
+/// Wraps the HTTPS connector so that each connection reports a [`TlsInfo`]
+/// as connection metadata, which hyper copies into response extensions, and
+/// tees the connection bytes to the configured [`WireCapture`].
+///
+/// The TLS configuration is built on the first connection, so creating a
+/// client stays cheap on cold starts.
+#[derive(Clone)]
+pub(crate) struct InspectingConnector {
+    inner: Arc<OnceLock<HttpsConnector<HttpConnector<Resolver>>>>,
+    config: ClientConfig,
+    resolver: Resolver,
+    capture: Option<WireCapture>,
+}
+
+impl InspectingConnector {
+    pub(crate) fn new(config: &ClientConfig) -> Self {
+        Self::with_resolver(config, Resolver::new(config))
+    }
+
+    /// Connector always connecting to `addr`, whatever the URL host.
+    pub(crate) fn pinned(config: &ClientConfig, addr: IpAddr) -> Self {
+        Self::with_resolver(config, Resolver::Static(addr))
+    }
+
+    fn with_resolver(config: &ClientConfig, resolver: Resolver) -> Self {
+        Self {
+            inner: Arc::default(),
+            config: config.clone(),
+            resolver,
+            capture: config.wire_capture.clone(),
+        }
+    }
+
+    /// The HTTPS connector, built on first use.
+    fn connector(&self) -> &HttpsConnector<HttpConnector<Resolver>> {
+        self.inner
+            .get_or_init(|| https_connector(&self.config, self.resolver.clone()))
+    }
+}
+
+impl Service<Uri> for InspectingConnector {
+    type Response = InspectingStream;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.connector().clone().poll_ready(cx)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let connecting = self.connector().clone().call(uri);
+        let capture = self.capture.as_ref().map(WireCapture::connection);
+        Box::pin(async move { Ok(InspectingStream::new(connecting.await?, capture)) })
+    }
+}
+
+/// Connection stream carrying the TLS parameters captured after the handshake.
+pub(crate) struct InspectingStream {
+    inner: MaybeHttpsStream<TokioIo<TcpStream>>,
+    tls: Option<TlsInfo>,
+    capture: Option<ConnectionCapture>,
+    /// Read buffer of captured connections.
+    scratch: Vec<u8>,
+}
+
+/// Size of the read buffer of captured connections.
+const CAPTURE_READ_SIZE: usize = 16 * 1024;
+
+impl InspectingStream {
+    fn new(
+        inner: MaybeHttpsStream<TokioIo<TcpStream>>,
+        capture: Option<ConnectionCapture>,
+    ) -> Self {
+        let tls = match &inner {
+            MaybeHttpsStream::Https(stream) => {
+                let (_, connection) = stream.inner().get_ref();
+                Some(TlsInfo::from_connection(connection))
+            }
+            MaybeHttpsStream::Http(_) => None,
+        };
+        Self {
+            inner,
+            tls,
+            capture,
+            scratch: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, direction: WireDirection, bytes: &[u8]) {
+        if let Some(capture) = &mut self.capture {
+            capture.record(direction, bytes);
+        }
+    }
+}
+
+impl Connection for InspectingStream {
+    fn connected(&self) -> Connected {
+        let connected = self.inner.connected();
+        match &self.tls {
+            Some(tls) => connected.extra(tls.clone()),
+            None => connected,
+        }
+    }
+}
+
+impl Read for InspectingStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        mut buf: ReadBufCursor<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.capture.is_none() {
+            return Pin::new(&mut self.inner).poll_read(cx, buf);
+        }
+
+        // Read through a buffer of our own to see the bytes
+        let this = &mut *self;
+        this.scratch
+            .resize(buf.remaining().min(CAPTURE_READ_SIZE), 0);
+        let mut scratch = hyper::rt::ReadBuf::new(&mut this.scratch);
+        ready!(Pin::new(&mut this.inner).poll_read(cx, scratch.unfilled()))?;
+        let filled = scratch.filled();
+        buf.put_slice(filled);
+        if let Some(capture) = &mut this.capture {
+            capture.record(WireDirection::Received, filled);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Write for InspectingStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let written = ready!(Pin::new(&mut self.inner).poll_write(cx, buf))?;
+        self.record(WireDirection::Sent, buf.get(..written).unwrap_or(buf));
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let written = ready!(Pin::new(&mut self.inner).poll_write_vectored(cx, bufs))?;
+        let mut remaining = written;
+        for buf in bufs {
+            if remaining == 0 {
+                break;
+            }
+            let len = buf.len().min(remaining);
+            self.record(WireDirection::Sent, buf.get(..len).unwrap_or_default());
+            remaining -= len;
+        }
+        Poll::Ready(Ok(written))
+    }
 }
 
 #[cfg(test)]
@@ -31,7 +306,27 @@ mod tests {
 
     #[test]
     fn creates_connector() {
-        let _connector = https_connector();
+        let config = ClientConfig::default();
+        let _connector = https_connector(&config, Resolver::new(&config));
         // Just verify it compiles and doesn't panic
     }
+
+    #[test]
+    fn builds_tls_config_on_first_use() {
+        let connector = InspectingConnector::new(&ClientConfig::default());
+        let clone = connector.clone();
+        assert!(connector.inner.get().is_none());
+
+        let _ = clone.connector();
+        assert!(connector.inner.get().is_some());
+    }
+
+    #[test]
+    fn creates_connector_with_identity() {
+        let identity =
+            crate::Identity::from_pem(include_str!("../tests/fixtures/client-identity.pem"))
+                .expect("valid identity");
+        let config = ClientConfig::builder().identity(identity).build();
+        let _connector = https_connector(&config, Resolver::new(&config));
+    }
 }
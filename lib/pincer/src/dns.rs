@@ -0,0 +1,412 @@
+//! TTL-aware DNS cache (requires `dns-cache` feature).
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::{Duration, Instant};
+
+use futures_util::FutureExt;
+use futures_util::future::{BoxFuture, Shared};
+use hickory_resolver::TokioResolver;
+
+use crate::{Error, Result};
+
+/// Default upper bound for how long a record is cached.
+const DEFAULT_MAX_TTL: Duration = Duration::from_mins(5);
+
+/// Default upper bound for a single resolution.
+const DEFAULT_LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Maximum fraction of the TTL removed by jitter (in percent).
+const JITTER_PERCENT: u32 = 10;
+
+/// Resolution in flight, shared by every caller missing the same host.
+type PendingLookup = Shared<BoxFuture<'static, std::result::Result<Vec<IpAddr>, String>>>;
+
+/// Snapshot of DNS cache counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DnsCacheStats {
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that required a resolution.
+    pub misses: u64,
+    /// Lookups that joined a resolution already in flight for the same host.
+    pub coalesced: u64,
+    /// Resolutions that failed or timed out.
+    pub errors: u64,
+}
+
+/// DNS cache honoring record TTLs.
+///
+/// Resolved addresses are kept until their TTL (capped by
+/// [`with_max_ttl`](Self::with_max_ttl)) expires. Each entry expires up to
+/// 10% early, so clients sharing the same records do not all re-resolve at
+/// the same instant, while failovers are still picked up promptly.
+/// Concurrent misses for the same host share a single resolution.
+///
+/// Resolution is bounded by its own timeout (5 seconds by default, see
+/// [`with_lookup_timeout`](Self::with_lookup_timeout)): the client
+/// `connect_timeout` only covers establishing the TCP connection.
+///
+/// Clones share the same cache and counters, so a handle kept aside can be
+/// used to read [`stats`](Self::stats).
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::{DnsCache, HyperClient};
+///
+/// let dns = DnsCache::new()?.with_lookup_timeout(Duration::from_secs(2));
+/// let client = HyperClient::builder().dns_cache(dns.clone()).build();
+///
+/// // later
+/// tracing::info!(hits = dns.stats().hits, "dns cache");
+/// ```
+#[derive(Clone)]
+pub struct DnsCache {
+    inner: Arc<Inner>,
+    lookup_timeout: Duration,
+}
+
+struct Inner {
+    resolver: TokioResolver,
+    max_ttl: Duration,
+    entries: Mutex<HashMap<String, Entry>>,
+    pending: Mutex<HashMap<String, PendingLookup>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    coalesced: AtomicU64,
+    errors: AtomicU64,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    addrs: Vec<IpAddr>,
+    expires_at: Instant,
+}
+
+impl std::fmt::Debug for DnsCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DnsCache")
+            .field("max_ttl", &self.inner.max_ttl)
+            .field("lookup_timeout", &self.lookup_timeout)
+            .field("stats", &self.stats())
+            .finish_non_exhaustive()
+    }
+}
+
+impl DnsCache {
+    /// Create a cache using the system DNS configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system DNS configuration cannot be read.
+    pub fn new() -> Result<Self> {
+        Self::with_max_ttl(DEFAULT_MAX_TTL)
+    }
+
+    /// Create a cache keeping records at most `max_ttl`, whatever their TTL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system DNS configuration cannot be read.
+    pub fn with_max_ttl(max_ttl: Duration) -> Result<Self> {
+        let mut builder = TokioResolver::builder_tokio()
+            .map_err(|e| Error::connection(format!("failed to read DNS configuration: {e}")))?;
+        // Records are cached here, so re-resolution really reaches the resolver
+        builder.options_mut().cache_size = 0;
+        let resolver = builder
+            .build()
+            .map_err(|e| Error::connection(format!("failed to create DNS resolver: {e}")))?;
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                resolver,
+                max_ttl,
+                entries: Mutex::new(HashMap::new()),
+                pending: Mutex::new(HashMap::new()),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+                coalesced: AtomicU64::new(0),
+                errors: AtomicU64::new(0),
+            }),
+            lookup_timeout: DEFAULT_LOOKUP_TIMEOUT,
+        })
+    }
+
+    /// Set the maximum duration of a single resolution.
+    #[must_use]
+    pub const fn with_lookup_timeout(mut self, timeout: Duration) -> Self {
+        self.lookup_timeout = timeout;
+        self
+    }
+
+    /// Current counters.
+    #[must_use]
+    pub fn stats(&self) -> DnsCacheStats {
+        DnsCacheStats {
+            hits: self.inner.hits.load(Ordering::Relaxed),
+            misses: self.inner.misses.load(Ordering::Relaxed),
+            coalesced: self.inner.coalesced.load(Ordering::Relaxed),
+            errors: self.inner.errors.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Drop every cached record.
+    pub fn clear(&self) {
+        self.inner.entries().clear();
+    }
+
+    /// Resolve a host name, using the cache when the record is still valid.
+    pub(crate) async fn resolve(&self, host: &str) -> Result<Vec<IpAddr>> {
+        let resolver = self.inner.resolver.clone();
+        self.resolve_with(host, move |host| async move {
+            let lookup = resolver
+                .lookup_ip(host.as_str())
+                .await
+                .map_err(|e| format!("failed to resolve {host}: {e}"))?;
+            let ttl = lookup
+                .valid_until()
+                .saturating_duration_since(Instant::now());
+            Ok((lookup.iter().collect(), ttl))
+        })
+        .await
+    }
+
+    /// Resolve a host name with `lookup`, unless it is cached or already being resolved.
+    async fn resolve_with<F, Fut>(&self, host: &str, lookup: F) -> Result<Vec<IpAddr>>
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = std::result::Result<(Vec<IpAddr>, Duration), String>> + Send + 'static,
+    {
+        let pending = {
+            let mut pending = self.inner.pending();
+            if let Some(addrs) = self.inner.cached(host, Instant::now()) {
+                self.inner.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(addrs);
+            }
+            if let Some(lookup) = pending.get(host) {
+                self.inner.coalesced.fetch_add(1, Ordering::Relaxed);
+                lookup.clone()
+            } else {
+                self.inner.misses.fetch_add(1, Ordering::Relaxed);
+                let lookup = self.spawn_lookup(host, lookup(host.to_string()));
+                pending.insert(host.to_string(), lookup.clone());
+                lookup
+            }
+        };
+
+        pending.await.map_err(Error::connection)
+    }
+
+    /// Wrap a resolution so its outcome is cached and it leaves the pending set when done.
+    ///
+    /// The resolution only holds a weak reference to the cache, so an
+    /// abandoned lookup never keeps the cache alive.
+    fn spawn_lookup<Fut>(&self, host: &str, lookup: Fut) -> PendingLookup
+    where
+        Fut: Future<Output = std::result::Result<(Vec<IpAddr>, Duration), String>> + Send + 'static,
+    {
+        let inner: Weak<Inner> = Arc::downgrade(&self.inner);
+        let host = host.to_string();
+        let timeout = self.lookup_timeout;
+
+        async move {
+            let result = match tokio::time::timeout(timeout, lookup).await {
+                Ok(result) => result,
+                Err(_) => Err(format!("resolving {host} timed out after {timeout:?}")),
+            };
+            let Some(inner) = inner.upgrade() else {
+                return result.map(|(addrs, _)| addrs);
+            };
+            inner.pending().remove(&host);
+
+            match result {
+                Ok((addrs, ttl)) => {
+                    tracing::debug!(
+                        host,
+                        ttl_secs = ttl.as_secs(),
+                        addrs = addrs.len(),
+                        "resolved host"
+                    );
+                    inner.store(&host, addrs.clone(), ttl, Instant::now());
+                    Ok(addrs)
+                }
+                Err(err) => {
+                    inner.errors.fetch_add(1, Ordering::Relaxed);
+                    Err(err)
+                }
+            }
+        }
+        .boxed()
+        .shared()
+    }
+}
+
+impl Inner {
+    fn entries(&self) -> MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn pending(&self) -> MutexGuard<'_, HashMap<String, PendingLookup>> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn cached(&self, host: &str, now: Instant) -> Option<Vec<IpAddr>> {
+        let mut entries = self.entries();
+        match entries.get(host) {
+            Some(entry) if entry.expires_at > now => Some(entry.addrs.clone()),
+            Some(_) => {
+                entries.remove(host);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn store(&self, host: &str, addrs: Vec<IpAddr>, ttl: Duration, now: Instant) {
+        let ttl = jittered(ttl.min(self.max_ttl), random_percent());
+        if ttl.is_zero() || addrs.is_empty() {
+            return;
+        }
+        self.entries().insert(
+            host.to_string(),
+            Entry {
+                addrs,
+                expires_at: now + ttl,
+            },
+        );
+    }
+}
+
+/// Shorten `ttl` by `percent` of the maximum jitter.
+fn jittered(ttl: Duration, percent: u32) -> Duration {
+    ttl.saturating_sub(ttl * (percent % 100) * JITTER_PERCENT / 10_000)
+}
+
+/// Random value in `0..100`.
+fn random_percent() -> u32 {
+    let random = std::collections::hash_map::RandomState::new().hash_one(Instant::now());
+    u32::try_from(random % 100).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    const ADDR: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+
+    #[test]
+    fn jitter_removes_at_most_ten_percent() {
+        let ttl = Duration::from_secs(100);
+        assert_eq!(jittered(ttl, 0), ttl);
+        assert_eq!(jittered(ttl, 50), Duration::from_secs(95));
+        assert_eq!(jittered(ttl, 99), Duration::from_millis(90_100));
+    }
+
+    #[tokio::test]
+    async fn cache_honors_ttl() {
+        let cache = DnsCache::new().expect("dns cache");
+        let now = Instant::now();
+        cache
+            .inner
+            .store("example.com", vec![ADDR], Duration::from_mins(1), now);
+
+        assert_eq!(cache.inner.cached("example.com", now), Some(vec![ADDR]));
+        assert_eq!(
+            cache
+                .inner
+                .cached("example.com", now + Duration::from_secs(61)),
+            None
+        );
+        assert_eq!(cache.inner.cached("example.com", now), None);
+    }
+
+    #[tokio::test]
+    async fn cache_caps_ttl() {
+        let cache = DnsCache::with_max_ttl(Duration::from_secs(1)).expect("dns cache");
+        let now = Instant::now();
+        cache
+            .inner
+            .store("example.com", vec![ADDR], Duration::from_hours(1), now);
+
+        assert_eq!(
+            cache
+                .inner
+                .cached("example.com", now + Duration::from_secs(2)),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn cache_skips_zero_ttl() {
+        let cache = DnsCache::new().expect("dns cache");
+        let now = Instant::now();
+        cache
+            .inner
+            .store("example.com", vec![ADDR], Duration::ZERO, now);
+
+        assert_eq!(cache.inner.cached("example.com", now), None);
+        assert_eq!(cache.stats(), DnsCacheStats::default());
+    }
+
+    #[tokio::test]
+    async fn concurrent_misses_are_coalesced() {
+        let cache = DnsCache::new().expect("dns cache");
+        let calls = Arc::new(AtomicU64::new(0));
+        let lookup = |calls: Arc<AtomicU64>| {
+            move |_host: String| async move {
+                calls.fetch_add(1, Ordering::Relaxed);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok((vec![ADDR], Duration::from_mins(1)))
+            }
+        };
+
+        let (first, second) = tokio::join!(
+            cache.resolve_with("example.com", lookup(Arc::clone(&calls))),
+            cache.resolve_with("example.com", lookup(Arc::clone(&calls))),
+        );
+        assert_eq!(first.expect("first"), vec![ADDR]);
+        assert_eq!(second.expect("second"), vec![ADDR]);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        let third = cache
+            .resolve_with("example.com", lookup(Arc::clone(&calls)))
+            .await;
+        assert_eq!(third.expect("third"), vec![ADDR]);
+        assert_eq!(
+            cache.stats(),
+            DnsCacheStats {
+                hits: 1,
+                misses: 1,
+                coalesced: 1,
+                errors: 0,
+            }
+        );
+        assert!(cache.inner.pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn lookup_timeout_bounds_resolution() {
+        let cache = DnsCache::new()
+            .expect("dns cache")
+            .with_lookup_timeout(Duration::from_millis(10));
+
+        let err = cache
+            .resolve_with("example.com", |_host| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok((vec![ADDR], Duration::from_mins(1)))
+            })
+            .await
+            .expect_err("lookup should time out");
+
+        assert!(err.is_connection());
+        assert_eq!(cache.stats().errors, 1);
+        assert!(cache.inner.pending().is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+//! Actor-style client handle.
+//!
+//! A [`ClientHandle`] sends requests over a bounded channel to a single driver
+//! task that executes them one at a time. Callers wait for room in the queue,
+//! which gives natural backpressure, and the driver is the single place where
+//! requests are sent, for environments with strict task budgets.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::{Error, HttpClient, Request, Response, Result, runtime_or_default};
+
+/// A request queued for the driver, with the channel to send its response.
+type Job = (Request<Bytes>, oneshot::Sender<Result<Response<Bytes>>>);
+
+/// Cloneable handle to a client driven by a dedicated task.
+///
+/// Requests are executed in order, one at a time. Spawn several handles to
+/// run requests in parallel. The driver task stops once every handle is
+/// dropped and the queue is drained.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::{ApiClient, ClientHandle, HyperClient};
+///
+/// // At most 16 requests waiting, callers wait beyond that
+/// let handle = ClientHandle::spawn(HyperClient::new(), 16);
+/// let api = ApiClient::new(handle, "https://api.example.com")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientHandle {
+    sender: mpsc::Sender<Job>,
+}
+
+impl ClientHandle {
+    /// Spawn the driver task of `client` on its [`Runtime`](crate::Runtime),
+    /// with room for `capacity` queued requests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero, or if `client` does not name a runtime
+    /// and this is called outside a Tokio runtime.
+    #[must_use]
+    pub fn spawn<C>(client: C, capacity: usize) -> Self
+    where
+        C: HttpClient + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let client = Arc::new(client);
+        let driver = Box::pin(drive(Arc::clone(&client), receiver));
+        runtime_or_default(client.runtime()).spawn(driver);
+        Self { sender }
+    }
+
+    /// Number of requests that can be queued without waiting.
+    #[must_use]
+    pub fn available(&self) -> usize {
+        self.sender.capacity()
+    }
+
+    /// Returns `true` if the driver task has stopped.
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+impl HttpClient for ClientHandle {
+    fn execute(
+        &self,
+        request: Request<Bytes>,
+    ) -> impl Future<Output = Result<Response<Bytes>>> + Send {
+        let sender = self.sender.clone();
+        async move {
+            let (reply, response) = oneshot::channel();
+            sender
+                .send((request, reply))
+                .await
+                .map_err(|_| driver_stopped())?;
+            response.await.map_err(|_| driver_stopped())?
+        }
+    }
+}
+
+/// Execute the queued requests until every handle is dropped.
+async fn drive<C: HttpClient>(client: Arc<C>, mut receiver: mpsc::Receiver<Job>) {
+    while let Some((request, reply)) = receiver.recv().await {
+        // The caller gave up while the request was queued
+        if reply.is_closed() {
+            continue;
+        }
+        let response = client.execute(request).await;
+        let _ = reply.send(response);
+    }
+}
+
+fn driver_stopped() -> Error {
+    Error::connection("client handle driver stopped")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use http::HeaderMap;
+    use tokio::sync::Semaphore;
+
+    use super::*;
+    use crate::{Method, Runtime, Sleep};
+
+    /// Client answering with the number of requests executed so far.
+    #[derive(Debug, Clone, Default)]
+    struct CountingClient(Arc<AtomicUsize>);
+
+    impl HttpClient for CountingClient {
+        async fn execute(&self, _request: Request<Bytes>) -> Result<Response<Bytes>> {
+            let count = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(Response::new(
+                200,
+                HeaderMap::new(),
+                Bytes::from(count.to_string()),
+            ))
+        }
+    }
+
+    /// Client counting the requests it started, answering once a permit is
+    /// available.
+    #[derive(Debug, Clone)]
+    struct GatedClient {
+        started: Arc<AtomicUsize>,
+        gate: Arc<Semaphore>,
+    }
+
+    impl HttpClient for GatedClient {
+        async fn execute(&self, _request: Request<Bytes>) -> Result<Response<Bytes>> {
+            self.started.fetch_add(1, Ordering::SeqCst);
+            let _permit = self.gate.acquire().await.map_err(|_| Error::Timeout)?;
+            Ok(Response::new(200, HeaderMap::new(), Bytes::new()))
+        }
+    }
+
+    /// Runtime dropping the spawned tasks, as when it shuts down.
+    struct StoppedRuntime;
+
+    impl Runtime for StoppedRuntime {
+        fn sleep(&self, _duration: Duration) -> Sleep {
+            Box::pin(std::future::pending())
+        }
+
+        fn spawn(&self, _future: Pin<Box<dyn Future<Output = ()> + Send>>) {}
+    }
+
+    /// Client naming the [`StoppedRuntime`].
+    #[derive(Debug, Clone, Default)]
+    struct StoppedClient;
+
+    impl HttpClient for StoppedClient {
+        async fn execute(&self, _request: Request<Bytes>) -> Result<Response<Bytes>> {
+            Ok(Response::new(200, HeaderMap::new(), Bytes::new()))
+        }
+
+        fn runtime(&self) -> Option<&dyn Runtime> {
+            Some(&StoppedRuntime)
+        }
+    }
+
+    fn request() -> Request<Bytes> {
+        Request::builder(Method::Get, "https://example.com".parse().expect("url")).build()
+    }
+
+    #[tokio::test]
+    async fn executes_requests_on_the_driver_task() {
+        let handle = ClientHandle::spawn(CountingClient::default(), 2);
+        assert_eq!(handle.available(), 2);
+
+        let first = handle.execute(request()).await.expect("first");
+        let second = handle.clone().execute(request()).await.expect("second");
+        assert_eq!(first.body().as_ref(), b"1");
+        assert_eq!(second.body().as_ref(), b"2");
+        assert!(!handle.is_closed());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn callers_wait_for_room_in_the_queue() {
+        let client = GatedClient {
+            started: Arc::default(),
+            gate: Arc::new(Semaphore::new(0)),
+        };
+        let handle = ClientHandle::spawn(client.clone(), 1);
+        let send = || {
+            let handle = handle.clone();
+            tokio::spawn(async move { handle.execute(request()).await })
+        };
+
+        // The driver waits on the first request, the second one fills the queue
+        let first = send();
+        while client.started.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+        let second = send();
+        while handle.available() > 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let waiting = tokio::time::timeout(Duration::from_secs(1), handle.execute(request())).await;
+        assert!(waiting.is_err(), "no room in the queue");
+
+        client.gate.add_permits(2);
+        for task in [first, second] {
+            let response = task.await.expect("task").expect("response");
+            assert_eq!(response.status(), 200);
+        }
+        assert_eq!(client.started.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn fails_once_the_driver_stopped() {
+        let handle = ClientHandle::spawn(StoppedClient, 1);
+        assert!(handle.is_closed());
+
+        let err = handle.execute(request()).await.expect_err("stopped");
+        assert!(err.is_connection());
+        assert!(err.to_string().contains("driver stopped"), "{err}");
+    }
+}
@@ -0,0 +1,217 @@
+//! Health checks of an API.
+//!
+//! [`probe_health`] calls the health endpoint of an API once; a
+//! [`HealthWatcher`] calls it periodically in a background task and exposes
+//! the latest status, e.g. to fail over to another region or open a circuit
+//! before requests start failing.
+//!
+//! Clients generated with `#[pincer(health = "/healthz")]` provide `health()`
+//! and `watch_health(interval)` methods built on these.
+
+use std::future::{Future, poll_fn};
+use std::pin::{Pin, pin};
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::Duration;
+
+use tokio::sync::{oneshot, watch};
+
+use crate::{Error, Method, PincerClient, Request, Result, runtime_or_default};
+
+/// Status reported by a [`HealthWatcher`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HealthStatus {
+    /// No probe has completed yet.
+    Unknown,
+    /// The last probe got a 2xx response.
+    Healthy,
+    /// The last probe failed or got a non-2xx response.
+    Unhealthy,
+}
+
+/// Call the health endpoint at `path`, relative to the client base URL.
+///
+/// Returns `Ok(true)` for a 2xx response, `Ok(false)` for any other status.
+///
+/// # Errors
+///
+/// Returns an error if the request could not be sent (connection, timeout).
+pub async fn probe_health<C: PincerClient>(client: &C, path: &str) -> Result<bool> {
+    let url = client.base_url().join(path).map_err(Error::InvalidUrl)?;
+    let request = Request::builder(Method::Get, url).build();
+    let response = client.execute(request).await?;
+    Ok(response.is_success())
+}
+
+/// Background task probing the health endpoint of an API.
+///
+/// The task stops when the watcher is dropped.
+///
+/// # Example
+///
+/// ```ignore
+/// let client = UserApiClientBuilder::default().build()?;
+/// let mut health = client.watch_health(Duration::from_secs(10));
+///
+/// // Feed failover decisions
+/// let status = health.changed().await;
+/// if status == HealthStatus::Unhealthy {
+///     switch_to_backup_region();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct HealthWatcher {
+    status: watch::Receiver<HealthStatus>,
+    /// Stops the task when dropped.
+    _stop: oneshot::Sender<()>,
+}
+
+impl HealthWatcher {
+    /// Probe `path` with `client` every `interval`, starting right away, in
+    /// a task spawned on the client [`Runtime`](crate::Runtime).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `client` does not name a runtime and this is called outside
+    /// a Tokio runtime.
+    #[must_use]
+    pub fn spawn<C>(client: C, path: &'static str, interval: Duration) -> Self
+    where
+        C: PincerClient + 'static,
+    {
+        let (sender, status) = watch::channel(HealthStatus::Unknown);
+        let (stop, stopped) = oneshot::channel();
+        let client = Arc::new(client);
+        let probes = {
+            let client = Arc::clone(&client);
+            async move {
+                loop {
+                    let status = match probe_health(client.as_ref(), path).await {
+                        Ok(true) => HealthStatus::Healthy,
+                        Ok(false) | Err(_) => HealthStatus::Unhealthy,
+                    };
+                    sender.send_if_modified(|current| {
+                        let changed = *current != status;
+                        *current = status;
+                        changed
+                    });
+                    runtime_or_default(client.runtime()).sleep(interval).await;
+                }
+            }
+        };
+        runtime_or_default(client.runtime()).spawn(Box::pin(until_stopped(probes, stopped)));
+        Self {
+            status,
+            _stop: stop,
+        }
+    }
+
+    /// Latest status.
+    #[must_use]
+    pub fn status(&self) -> HealthStatus {
+        *self.status.borrow()
+    }
+
+    /// Returns `true` if the last probe succeeded.
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.status() == HealthStatus::Healthy
+    }
+
+    /// Wait for the status to change, and return the new status.
+    pub async fn changed(&mut self) -> HealthStatus {
+        // The sender lives as long as the task, which `self` keeps running
+        let _ = self.status.changed().await;
+        *self.status.borrow_and_update()
+    }
+
+    /// Subscribe to the status changes, e.g. from another task.
+    #[must_use]
+    pub fn subscribe(&self) -> watch::Receiver<HealthStatus> {
+        self.status.clone()
+    }
+}
+
+/// Run `future` until the sender of `stopped` is dropped.
+async fn until_stopped(future: impl Future<Output = ()>, mut stopped: oneshot::Receiver<()>) {
+    let mut future = pin!(future);
+    poll_fn(|cx| {
+        if Pin::new(&mut stopped).poll(cx).is_ready() {
+            return Poll::Ready(());
+        }
+        future.as_mut().poll(cx)
+    })
+    .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU16, Ordering};
+
+    use bytes::Bytes;
+    use http::HeaderMap;
+    use url::Url;
+
+    use super::*;
+    use crate::Response;
+
+    /// Client answering every request with a configurable status.
+    #[derive(Debug, Clone)]
+    struct StatusClient {
+        base_url: Url,
+        status: Arc<AtomicU16>,
+    }
+
+    impl PincerClient for StatusClient {
+        async fn execute(&self, request: Request<Bytes>) -> Result<Response<Bytes>> {
+            assert_eq!(request.url().path(), "/healthz");
+            let status = self.status.load(Ordering::SeqCst);
+            Ok(Response::new(status, HeaderMap::new(), Bytes::new()))
+        }
+
+        fn base_url(&self) -> &Url {
+            &self.base_url
+        }
+    }
+
+    fn client(status: u16) -> StatusClient {
+        StatusClient {
+            base_url: "https://api.example.com".parse().expect("url"),
+            status: Arc::new(AtomicU16::new(status)),
+        }
+    }
+
+    #[tokio::test]
+    async fn probe_reports_status() {
+        assert!(probe_health(&client(204), "/healthz").await.expect("probe"));
+        assert!(!probe_health(&client(503), "/healthz").await.expect("probe"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn watcher_follows_status_changes() {
+        let client = client(200);
+        let mut watcher = HealthWatcher::spawn(client.clone(), "/healthz", Duration::from_secs(5));
+        assert_eq!(watcher.changed().await, HealthStatus::Healthy);
+        assert!(watcher.is_healthy());
+
+        client.status.store(503, Ordering::SeqCst);
+        assert_eq!(watcher.changed().await, HealthStatus::Unhealthy);
+        assert_eq!(
+            watcher.subscribe().borrow().to_owned(),
+            HealthStatus::Unhealthy
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn dropping_the_watcher_stops_the_task() {
+        let client = client(200);
+        let mut watcher = HealthWatcher::spawn(client.clone(), "/healthz", Duration::from_secs(5));
+        assert_eq!(watcher.changed().await, HealthStatus::Healthy);
+        assert_eq!(Arc::strong_count(&client.status), 2);
+
+        drop(watcher);
+        tokio::task::yield_now().await;
+        assert_eq!(Arc::strong_count(&client.status), 1);
+    }
+}
@@ -0,0 +1,109 @@
+//! Per-request connection hints.
+
+use std::net::IpAddr;
+
+/// Connection handling hints for a single request.
+///
+/// Attach as a request extension to bypass the connection pool, disable
+/// keep-alive, or pin the request to a specific remote address. This is
+/// mostly useful to debug load balancers and sticky-session issues.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::{ConnectionHints, Method, Request};
+///
+/// let request = Request::builder(Method::Get, url)
+///     .extension(
+///         ConnectionHints::new()
+///             .fresh_connection()
+///             .remote_addr("10.0.0.12".parse()?),
+///     )
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConnectionHints {
+    fresh_connection: bool,
+    keep_alive: Option<bool>,
+    remote_addr: Option<IpAddr>,
+}
+
+impl ConnectionHints {
+    /// Create hints that leave connection handling unchanged.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            fresh_connection: false,
+            keep_alive: None,
+            remote_addr: None,
+        }
+    }
+
+    /// Open a new connection instead of reusing a pooled one.
+    ///
+    /// The connection is not returned to the pool afterwards.
+    #[must_use]
+    pub const fn fresh_connection(mut self) -> Self {
+        self.fresh_connection = true;
+        self
+    }
+
+    /// Ask the server to close the connection after the response
+    /// (`Connection: close`).
+    #[must_use]
+    pub const fn disable_keep_alive(mut self) -> Self {
+        self.keep_alive = Some(false);
+        self
+    }
+
+    /// Connect to this address instead of resolving the URL host.
+    ///
+    /// The port, TLS server name and `Host` header still come from the URL.
+    /// Implies a fresh connection.
+    #[must_use]
+    pub const fn remote_addr(mut self, addr: IpAddr) -> Self {
+        self.remote_addr = Some(addr);
+        self
+    }
+
+    /// Returns `true` if the request must use a new connection.
+    #[must_use]
+    pub const fn is_fresh_connection(&self) -> bool {
+        self.fresh_connection || self.remote_addr.is_some()
+    }
+
+    /// Returns `true` if keep-alive is disabled for this request.
+    #[must_use]
+    pub const fn is_keep_alive_disabled(&self) -> bool {
+        matches!(self.keep_alive, Some(false))
+    }
+
+    /// Address the request is pinned to, if any.
+    #[must_use]
+    pub const fn pinned_addr(&self) -> Option<IpAddr> {
+        self.remote_addr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    #[test]
+    fn default_hints_change_nothing() {
+        let hints = ConnectionHints::default();
+        assert!(!hints.is_fresh_connection());
+        assert!(!hints.is_keep_alive_disabled());
+        assert_eq!(hints.pinned_addr(), None);
+    }
+
+    #[test]
+    fn pinned_addr_implies_fresh_connection() {
+        let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        let hints = ConnectionHints::new().remote_addr(addr);
+        assert!(hints.is_fresh_connection());
+        assert_eq!(hints.pinned_addr(), Some(addr));
+    }
+}
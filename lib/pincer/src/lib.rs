@@ -27,40 +27,102 @@
 
 pub mod _tutorial;
 mod api_client;
+mod capture;
 mod client;
+mod client_registry;
 mod config;
 mod connector;
+#[cfg(feature = "dns-cache")]
+mod dns;
+mod handle;
+mod health;
+mod hints;
 pub mod middleware;
+#[cfg(feature = "paginate")]
+pub mod paginate;
 pub mod prelude;
+mod runtime;
+mod scope;
+mod tls;
+#[cfg(feature = "upload")]
+pub mod upload;
 
 // Re-export client types
 pub use api_client::ApiClient;
+pub use capture::{WireCapture, WireChunk, WireDirection};
 pub use client::{HyperClient, HyperClientBuilder, ServiceFuture};
+pub use client_registry::{ClientRegistry, RegisteredClient};
 pub use config::{ClientConfig, ClientConfigBuilder};
+#[cfg(feature = "dns-cache")]
+pub use dns::{DnsCache, DnsCacheStats};
+pub use handle::ClientHandle;
+pub use health::{HealthStatus, HealthWatcher, probe_health};
+pub use hints::ConnectionHints;
+pub use runtime::{TokioRuntime, runtime_or_default};
+pub use scope::{RequestScope, Scoped};
+pub use tls::{Identity, TlsInfo};
 
 // Re-export tower for middleware composition
 pub use tower;
 
 // Re-export core types
 pub use pincer_core::{
-    ContentType, DefaultErrorDecoder, Error, ErrorDecoder, Form, HttpClient, HttpClientExt, Method,
-    ParamLocation, ParamMeta, ParameterMetadata, Part, PathTemplate, PincerClient, Request,
-    RequestBuilder, Response, Result, ToQueryPairs, from_json, to_form, to_json, to_query_string,
+    AcceptLanguage, AllowedMethods, BodyAction, BodyCodec, BodyPreviewLen, BoxResponseFuture,
+    BufferPool, CallContext, ClientName, ConnectionInfo, ContentType, CookieStore,
+    DEFAULT_BODY_PREVIEW_LEN, DEFAULT_ERROR_BODY_LIMIT, DefaultErrorDecoder, Deprecation,
+    DiscardBody, DynPincerClient, DynamicClient, Endpoint, EndpointRegistry, EndpointSpec,
+    EntityTag, Error, ErrorDecoder, Form, HttpClient, HttpClientExt, InspectHead, Method, Page,
+    ParamLocation, ParamMeta, ParameterMetadata, Part, PathTemplate, PincerClient, PostFallback,
+    RateLimitInfo, Redactor, Request, RequestBuilder, Response, Result, Runtime, SerdeJson, Sleep,
+    StrictJson, ToQueryPairs, TruncatedBody, check_url_length, deep_object_pairs, format_timestamp,
+    from_json, from_json_at, from_json_strict, timeout, to_form, to_json, to_json_canonical,
+    to_query_string,
 };
 
+#[cfg(feature = "cbor")]
+pub use pincer_core::Ciborium;
+#[cfg(all(feature = "csv", feature = "streaming"))]
+pub use pincer_core::CsvRecords;
+#[cfg(feature = "xml")]
+pub use pincer_core::QuickXml;
+#[cfg(feature = "csv")]
+pub use pincer_core::from_csv;
+#[cfg(feature = "toml")]
+pub use pincer_core::from_toml;
+#[cfg(feature = "yaml")]
+pub use pincer_core::from_yaml;
+
 // Re-export http types for status codes and headers
 pub use pincer_core::{StatusCode, header};
 
 // Note: Form and Part are re-exported from pincer_core at the crate root
 
+// Re-export response body decoding (codecs are feature-gated)
+pub use pincer_core::encoding;
+
+// Re-export webhook verification (feature-gated)
+#[cfg(feature = "webhook")]
+pub use pincer_core::webhook;
+
+// Re-export the HTML parser of `Response::html` (feature-gated)
+#[cfg(feature = "format-html")]
+pub use pincer_core::scraper;
+
 // Re-export streaming types (feature-gated)
 #[cfg(feature = "streaming")]
-pub use pincer_core::{HttpClientStreaming, StreamingBody, StreamingResponse};
+pub use pincer_core::{
+    DEFAULT_MAX_LINE_LENGTH, HttpClientStreaming, JsonLines, StreamingBody, StreamingResponse,
+    TextLines, Trailers, TrailersSender,
+};
 
 // Re-export crates for macro-generated code
 pub use percent_encoding;
+pub use serde;
 pub use serde_html_form;
 pub use url;
 
+#[doc(hidden)]
+pub use pincer_core::{PATH_SEGMENT_ENCODE_SET, QUERY_COMPONENT_ENCODE_SET, RESERVED_ENCODE_SET};
+
 // Re-export macros
 pub use pincer_macro::{Query, delete, get, head, http, options, patch, pincer, post, put};
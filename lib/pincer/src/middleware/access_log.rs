@@ -0,0 +1,445 @@
+//! Structured access log middleware.
+//!
+//! [`AccessLogLayer`] records one [`AccessLogEntry`] per request, with the
+//! same fields whatever the outcome, and hands it to an [`AccessLogSink`]:
+//! - [`TracingSink`] - one `tracing` event per request
+//! - [`JsonFileSink`] - one JSON object per line, appended to a file
+//! - any `Fn(&AccessLogEntry)` closure
+//!
+//! The retry count is read from the [`RetryCount`] response extension, so the
+//! retry layer must be inside the access log layer: add it to the builder
+//! before the access log layer, as later layers wrap earlier ones.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use tower::{Layer, Service};
+
+use super::RetryCount;
+use crate::{ClientName, Error, Method, PathTemplate, Redactor, Request, Response, Result};
+
+/// Default header carrying the request id.
+pub const DEFAULT_REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// One request, as recorded by [`AccessLogLayer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessLogEntry {
+    /// When the request was sent.
+    pub timestamp: SystemTime,
+    /// Request method.
+    pub method: Method,
+    /// Name of the `#[pincer]` client, if set with `name = "..."`.
+    pub client: Option<&'static str>,
+    /// Path template of the `#[pincer]` method, if any.
+    pub path_template: Option<&'static str>,
+    /// Request URL, with sensitive values redacted.
+    pub url: String,
+    /// Response status, `None` if the request failed.
+    pub status: Option<u16>,
+    /// Error message, if the request failed.
+    pub error: Option<String>,
+    /// Size of the response body.
+    pub bytes_in: usize,
+    /// Size of the request body.
+    pub bytes_out: usize,
+    /// Time until the response was received.
+    pub duration: Duration,
+    /// Number of retries before the response.
+    pub retries: u32,
+    /// Request id, from the request or else the response headers.
+    pub request_id: Option<String>,
+}
+
+impl AccessLogEntry {
+    /// Render the entry as a JSON object.
+    ///
+    /// The timestamp is in milliseconds since the Unix epoch.
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "timestamp_ms": millis(self.timestamp.duration_since(UNIX_EPOCH).unwrap_or_default()),
+            "method": self.method.to_string(),
+            "client": self.client,
+            "path_template": self.path_template,
+            "url": self.url,
+            "status": self.status,
+            "error": self.error,
+            "bytes_in": self.bytes_in,
+            "bytes_out": self.bytes_out,
+            "duration_ms": millis(self.duration),
+            "retries": self.retries,
+            "request_id": self.request_id,
+        })
+    }
+}
+
+/// Destination of access log entries.
+///
+/// Implemented by closures taking an `&AccessLogEntry`.
+pub trait AccessLogSink: Send + Sync + 'static {
+    /// Record one entry.
+    fn record(&self, entry: &AccessLogEntry);
+}
+
+impl<F> AccessLogSink for F
+where
+    F: Fn(&AccessLogEntry) + Send + Sync + 'static,
+{
+    fn record(&self, entry: &AccessLogEntry) {
+        self(entry);
+    }
+}
+
+/// Sink emitting one `tracing` event per entry (target `pincer::access_log`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingSink;
+
+impl AccessLogSink for TracingSink {
+    fn record(&self, entry: &AccessLogEntry) {
+        tracing::info!(
+            target: "pincer::access_log",
+            method = %entry.method,
+            client = entry.client,
+            path_template = entry.path_template,
+            url = %entry.url,
+            status = entry.status,
+            error = entry.error.as_deref(),
+            bytes_in = entry.bytes_in,
+            bytes_out = entry.bytes_out,
+            duration_ms = millis(entry.duration),
+            retries = entry.retries,
+            request_id = entry.request_id.as_deref(),
+            "access"
+        );
+    }
+}
+
+/// Sink appending one JSON object per line to a file.
+#[derive(Debug)]
+pub struct JsonFileSink {
+    file: Mutex<File>,
+}
+
+impl JsonFileSink {
+    /// Open `path` for appending, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl AccessLogSink for JsonFileSink {
+    fn record(&self, entry: &AccessLogEntry) {
+        let mut line = entry.to_json().to_string();
+        line.push('\n');
+
+        let Ok(mut file) = self.file.lock() else {
+            return;
+        };
+        if let Err(err) = file.write_all(line.as_bytes()) {
+            tracing::warn!(error = %err, "failed to write access log entry");
+        }
+    }
+}
+
+/// Layer that records a structured access log entry per request.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::middleware::{AccessLogLayer, JsonFileSink};
+///
+/// let client = HyperClient::builder()
+///     .with_retry(3)
+///     .layer(AccessLogLayer::new(JsonFileSink::open("access.log")?))
+///     .build();
+///
+/// // Or with a callback
+/// let layer = AccessLogLayer::new(|entry: &AccessLogEntry| {
+///     pipeline.send(entry.to_json());
+/// });
+/// ```
+#[derive(Clone)]
+pub struct AccessLogLayer {
+    sink: Arc<dyn AccessLogSink>,
+    redactor: Arc<Redactor>,
+    request_id_header: Arc<str>,
+}
+
+impl fmt::Debug for AccessLogLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccessLogLayer")
+            .field("redactor", &self.redactor)
+            .field("request_id_header", &self.request_id_header)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AccessLogLayer {
+    /// Create an access log layer recording to `sink`.
+    #[must_use]
+    pub fn new(sink: impl AccessLogSink) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            redactor: Arc::default(),
+            request_id_header: Arc::from(DEFAULT_REQUEST_ID_HEADER),
+        }
+    }
+
+    /// Create an access log layer emitting `tracing` events.
+    #[must_use]
+    pub fn tracing() -> Self {
+        Self::new(TracingSink)
+    }
+
+    /// Set the redactor used when rendering URLs.
+    ///
+    /// Defaults to [`Redactor::default()`].
+    #[must_use]
+    pub fn with_redactor(mut self, redactor: Redactor) -> Self {
+        self.redactor = Arc::new(redactor);
+        self
+    }
+
+    /// Set the header carrying the request id (default: `x-request-id`).
+    #[must_use]
+    pub fn with_request_id_header(mut self, name: &str) -> Self {
+        self.request_id_header = Arc::from(name);
+        self
+    }
+}
+
+impl<S> Layer<S> for AccessLogLayer {
+    type Service = AccessLog<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AccessLog {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service that records a structured access log entry per request.
+#[derive(Debug, Clone)]
+pub struct AccessLog<S> {
+    inner: S,
+    layer: AccessLogLayer,
+}
+
+impl<S> Service<Request<Bytes>> for AccessLog<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let mut entry = AccessLogEntry {
+            timestamp: SystemTime::now(),
+            method: request.method(),
+            client: request
+                .extensions()
+                .get::<ClientName>()
+                .map(ClientName::as_str),
+            path_template: request
+                .extensions()
+                .get::<PathTemplate>()
+                .map(PathTemplate::as_str),
+            url: self.layer.redactor.redact_url(request.url()).into_owned(),
+            status: None,
+            error: None,
+            bytes_in: 0,
+            bytes_out: request.body().map_or(0, Bytes::len),
+            duration: Duration::ZERO,
+            retries: 0,
+            request_id: request
+                .header(&self.layer.request_id_header)
+                .map(str::to_string),
+        };
+
+        let sink = Arc::clone(&self.layer.sink);
+        let request_id_header = Arc::clone(&self.layer.request_id_header);
+        let mut inner = self.inner.clone();
+
+        Box::pin(async move {
+            let start = Instant::now();
+            let result = inner.call(request).await;
+            entry.duration = start.elapsed();
+
+            match &result {
+                Ok(response) => {
+                    entry.status = Some(response.status());
+                    entry.bytes_in = response.body().len();
+                    entry.retries = response
+                        .extensions()
+                        .get::<RetryCount>()
+                        .map_or(0, |count| count.0);
+                    if entry.request_id.is_none() {
+                        entry.request_id = response.header(&request_id_header).map(str::to_string);
+                    }
+                }
+                Err(err) => entry.error = Some(err.to_string()),
+            }
+
+            sink.record(&entry);
+            result
+        })
+    }
+}
+
+/// Saturating conversion to milliseconds.
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future;
+
+    use http::{HeaderMap, HeaderName, HeaderValue};
+
+    use super::*;
+
+    /// Mock service returning a fixed response.
+    #[derive(Clone)]
+    struct MockService {
+        status: u16,
+    }
+
+    impl Service<Request<Bytes>> for MockService {
+        type Response = Response<Bytes>;
+        type Error = Error;
+        type Future = future::Ready<Result<Response<Bytes>>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _request: Request<Bytes>) -> Self::Future {
+            let headers = HeaderMap::from_iter([(
+                HeaderName::from_static("x-request-id"),
+                HeaderValue::from_static("req-42"),
+            )]);
+            let mut response = Response::new(self.status, headers, Bytes::from_static(b"hello"));
+            response.extensions_mut().insert(RetryCount(2));
+            future::ready(Ok(response))
+        }
+    }
+
+    fn capture() -> (Arc<Mutex<Vec<AccessLogEntry>>>, AccessLogLayer) {
+        let entries = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&entries);
+        let layer = AccessLogLayer::new(move |entry: &AccessLogEntry| {
+            sink.lock().expect("lock").push(entry.clone());
+        });
+        (entries, layer)
+    }
+
+    #[tokio::test]
+    async fn records_one_entry_per_request() {
+        let (entries, layer) = capture();
+        let mut service = layer.layer(MockService { status: 201 });
+
+        let request = Request::builder(
+            Method::Post,
+            url::Url::parse("https://api.example.com/items?token=abc").expect("url"),
+        )
+        .body(Bytes::from_static(b"{}"))
+        .extension(PathTemplate::new("/items"))
+        .extension(ClientName::new("inventory"))
+        .build();
+        service.call(request).await.expect("response");
+
+        let entries = entries.lock().expect("lock");
+        assert_eq!(entries.len(), 1);
+        let entry = entries.first().expect("entry");
+        assert_eq!(entry.method, Method::Post);
+        assert_eq!(entry.client, Some("inventory"));
+        assert_eq!(entry.path_template, Some("/items"));
+        assert_eq!(entry.url, "https://api.example.com/items?token=REDACTED");
+        assert_eq!(entry.status, Some(201));
+        assert_eq!(entry.bytes_out, 2);
+        assert_eq!(entry.bytes_in, 5);
+        assert_eq!(entry.retries, 2);
+        assert_eq!(entry.request_id.as_deref(), Some("req-42"));
+    }
+
+    #[tokio::test]
+    async fn request_id_from_request_first() {
+        let (entries, layer) = capture();
+        let mut service = layer.layer(MockService { status: 200 });
+
+        let request = Request::builder(
+            Method::Get,
+            url::Url::parse("https://api.example.com/").expect("url"),
+        )
+        .header("x-request-id", "client-1")
+        .build();
+        service.call(request).await.expect("response");
+
+        let entries = entries.lock().expect("lock");
+        assert_eq!(
+            entries
+                .first()
+                .and_then(|entry| entry.request_id.as_deref()),
+            Some("client-1")
+        );
+    }
+
+    #[test]
+    fn entry_to_json() {
+        let entry = AccessLogEntry {
+            timestamp: UNIX_EPOCH + Duration::from_secs(1),
+            method: Method::Get,
+            client: None,
+            path_template: None,
+            url: "https://api.example.com/".to_string(),
+            status: Some(200),
+            error: None,
+            bytes_in: 10,
+            bytes_out: 0,
+            duration: Duration::from_millis(25),
+            retries: 0,
+            request_id: None,
+        };
+
+        assert_eq!(
+            entry.to_json(),
+            serde_json::json!({
+                "timestamp_ms": 1000,
+                "method": "GET",
+                "client": null,
+                "path_template": null,
+                "url": "https://api.example.com/",
+                "status": 200,
+                "error": null,
+                "bytes_in": 10,
+                "bytes_out": 0,
+                "duration_ms": 25,
+                "retries": 0,
+                "request_id": null,
+            })
+        );
+    }
+}
@@ -0,0 +1,315 @@
+//! Credential refresh middleware.
+//!
+//! On a `401 Unauthorized` response, refreshes the credential with a
+//! user-supplied async callback (e.g. logging in again to get a new session
+//! token), and retries the request once with the new credential.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use http::header::{AUTHORIZATION, HeaderName, HeaderValue};
+use tower::{Layer, Service};
+
+use crate::{Error, Request, Response, Result};
+
+/// Marker extension of a request retried after a credential refresh.
+///
+/// A request carrying it is not refreshed and retried again, so a rejected
+/// new credential returns the `401` response instead of looping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthRefreshed;
+
+/// Future refreshing the credential.
+type RefreshFuture = Pin<Box<dyn Future<Output = Result<String>> + Send>>;
+
+/// Current credential, with the number of refreshes producing it.
+#[derive(Debug, Default)]
+struct Credential {
+    value: Option<HeaderValue>,
+    generation: u64,
+}
+
+/// Credential state shared by the services of a layer.
+#[derive(Debug)]
+struct State {
+    credential: Mutex<Credential>,
+    /// Held while refreshing, so concurrent `401`s wait for a single refresh.
+    refreshing: tokio::sync::Mutex<()>,
+}
+
+/// Layer refreshing the credential on `401 Unauthorized` and retrying once.
+///
+/// The refresh callback returns the new value of the credential header
+/// (`Authorization` by default), set on every later request. Concurrent
+/// requests rejected with the same credential wait for a single refresh.
+/// The retried request is marked with [`AuthRefreshed`], and is not retried
+/// again. An error of the callback is returned instead of the `401` response.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::middleware::AuthRefreshLayer;
+///
+/// let layer = AuthRefreshLayer::new(move || {
+///     let auth = auth.clone();
+///     async move {
+///         let session = auth.login(&credentials).await?;
+///         Ok(format!("Bearer {}", session.token))
+///     }
+/// });
+///
+/// let client = HyperClient::builder().layer(layer).build();
+/// ```
+#[derive(Clone)]
+pub struct AuthRefreshLayer {
+    refresh: Arc<dyn Fn() -> RefreshFuture + Send + Sync>,
+    header: HeaderName,
+    state: Arc<State>,
+}
+
+impl fmt::Debug for AuthRefreshLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthRefreshLayer")
+            .field("header", &self.header)
+            .field("generation", &self.lock().generation)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuthRefreshLayer {
+    /// Create a layer refreshing the `Authorization` header with `refresh`.
+    pub fn new<F, Fut>(refresh: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String>> + Send + 'static,
+    {
+        Self {
+            refresh: Arc::new(move || Box::pin(refresh())),
+            header: AUTHORIZATION,
+            state: Arc::new(State {
+                credential: Mutex::default(),
+                refreshing: tokio::sync::Mutex::new(()),
+            }),
+        }
+    }
+
+    /// Refresh the `header` header instead of `Authorization`, e.g. a
+    /// session token header.
+    #[must_use]
+    pub fn header(mut self, header: HeaderName) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Set the initial credential, before the first refresh.
+    ///
+    /// Without it, requests keep their own credential until a `401`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` is not a valid header value.
+    pub fn credential(self, value: &str) -> Result<Self> {
+        let mut value = HeaderValue::try_from(value)
+            .map_err(|err| Error::invalid_request(format!("invalid credential: {err}")))?;
+        value.set_sensitive(true);
+        self.lock().value = Some(value);
+        Ok(self)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Credential> {
+        self.state
+            .credential
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Get the current credential and its generation.
+    fn current(&self) -> (Option<HeaderValue>, u64) {
+        let credential = self.lock();
+        (credential.value.clone(), credential.generation)
+    }
+
+    /// Refresh the credential, unless it was already refreshed since `generation`.
+    async fn refresh(&self, generation: u64) -> Result<()> {
+        let _refreshing = self.state.refreshing.lock().await;
+        if self.lock().generation != generation {
+            return Ok(());
+        }
+        let value = (self.refresh)().await?;
+        let mut value = HeaderValue::try_from(value).map_err(|err| {
+            Error::invalid_request(format!("invalid refreshed credential: {err}"))
+        })?;
+        value.set_sensitive(true);
+
+        let mut credential = self.lock();
+        credential.value = Some(value);
+        credential.generation += 1;
+        Ok(())
+    }
+}
+
+impl<S> Layer<S> for AuthRefreshLayer {
+    type Service = AuthRefresh<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthRefresh {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service refreshing the credential on `401 Unauthorized` and retrying once.
+#[derive(Debug, Clone)]
+pub struct AuthRefresh<S> {
+    inner: S,
+    layer: AuthRefreshLayer,
+}
+
+impl<S> AuthRefresh<S> {
+    /// Create an auth refresh service wrapping the given service.
+    pub const fn new(inner: S, layer: AuthRefreshLayer) -> Self {
+        Self { inner, layer }
+    }
+}
+
+impl<S> Service<Request<Bytes>> for AuthRefresh<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Bytes>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let layer = self.layer.clone();
+        Box::pin(async move {
+            let (value, generation) = layer.current();
+            if let Some(value) = value {
+                request.headers_mut().insert(layer.header.clone(), value);
+            }
+            if request.extensions().get::<AuthRefreshed>().is_some() {
+                return inner.call(request).await;
+            }
+
+            let mut retry = request.clone();
+            let response = inner.call(request).await?;
+            if response.status() != 401 {
+                return Ok(response);
+            }
+
+            layer.refresh(generation).await?;
+            if let (Some(value), _) = layer.current() {
+                retry.headers_mut().insert(layer.header.clone(), value);
+            }
+            retry.extensions_mut().insert(AuthRefreshed);
+            inner.call(retry).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use http::HeaderMap;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::Method;
+
+    fn request() -> Request<Bytes> {
+        Request::builder(
+            Method::Get,
+            "https://api.example.com/".parse().expect("url"),
+        )
+        .header("authorization", "Bearer expired")
+        .build()
+    }
+
+    /// Layer refreshing to `Bearer session-<n>`.
+    fn layer(refreshes: Arc<AtomicUsize>) -> AuthRefreshLayer {
+        AuthRefreshLayer::new(move || {
+            let count = refreshes.fetch_add(1, Ordering::SeqCst);
+            async move {
+                tokio::task::yield_now().await;
+                Ok(format!("Bearer session-{count}"))
+            }
+        })
+    }
+
+    /// Accept the `valid` credential, and echo the `Authorization` header.
+    fn server(
+        valid: &'static str,
+    ) -> impl Service<
+        Request<Bytes>,
+        Response = Response<Bytes>,
+        Error = Error,
+        Future = impl Future<Output = Result<Response<Bytes>>> + Send,
+    > + Clone
+    + Send
+    + 'static {
+        tower::service_fn(move |request: Request<Bytes>| async move {
+            let authorization = request.header("authorization").unwrap_or_default();
+            let status = if authorization == valid { 200 } else { 401 };
+            Ok::<_, Error>(Response::new(
+                status,
+                HeaderMap::new(),
+                Bytes::from(authorization.to_string()),
+            ))
+        })
+    }
+
+    #[tokio::test]
+    async fn refreshes_and_retries_once() {
+        let refreshes = Arc::new(AtomicUsize::new(0));
+        let service = layer(Arc::clone(&refreshes)).layer(server("Bearer session-0"));
+
+        let response = service.clone().oneshot(request()).await.expect("response");
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.body().as_ref(), b"Bearer session-0");
+
+        // The refreshed credential is used by the next requests
+        let response = service.oneshot(request()).await.expect("response");
+        assert_eq!(response.status(), 200);
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn returns_unauthorized_after_one_retry() {
+        let refreshes = Arc::new(AtomicUsize::new(0));
+        let service = layer(Arc::clone(&refreshes)).layer(server("Bearer never"));
+
+        let response = service.oneshot(request()).await.expect("response");
+        assert_eq!(response.status(), 401);
+        assert_eq!(response.body().as_ref(), b"Bearer session-0");
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn refreshes_once_for_concurrent_requests() {
+        let refreshes = Arc::new(AtomicUsize::new(0));
+        let service = layer(Arc::clone(&refreshes))
+            .credential("Bearer expired")
+            .expect("credential")
+            .layer(server("Bearer session-0"));
+
+        let (first, second) = tokio::join!(
+            service.clone().oneshot(request()),
+            service.oneshot(request())
+        );
+        assert_eq!(first.expect("response").status(), 200);
+        assert_eq!(second.expect("response").status(), 200);
+        assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    }
+}
@@ -10,6 +10,7 @@ use std::task::{Context, Poll};
 
 use base64::Engine;
 use bytes::Bytes;
+use http::header::{AUTHORIZATION, HeaderValue};
 use tower::{Layer, Service};
 
 use crate::{Error, Request, Response, Result};
@@ -88,10 +89,15 @@ where
     }
 
     fn call(&mut self, mut request: Request<Bytes>) -> Self::Future {
-        request.headers_mut().insert(
-            "Authorization".to_string(),
-            format!("Basic {}", self.encoded_credentials),
-        );
+        let mut value = match HeaderValue::try_from(format!("Basic {}", self.encoded_credentials)) {
+            Ok(value) => value,
+            Err(err) => {
+                let err = Error::invalid_request(format!("invalid basic auth credentials: {err}"));
+                return Box::pin(async move { Err(err) });
+            }
+        };
+        value.set_sensitive(true);
+        request.headers_mut().insert(AUTHORIZATION, value);
 
         let mut inner = self.inner.clone();
         Box::pin(async move { inner.call(request).await })
@@ -9,6 +9,7 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 
 use bytes::Bytes;
+use http::header::{AUTHORIZATION, HeaderValue};
 use tower::{Layer, Service};
 
 use crate::{Error, Request, Response, Result};
@@ -81,10 +82,15 @@ where
     }
 
     fn call(&mut self, mut request: Request<Bytes>) -> Self::Future {
-        request.headers_mut().insert(
-            "Authorization".to_string(),
-            format!("Bearer {}", self.token),
-        );
+        let mut value = match HeaderValue::try_from(format!("Bearer {}", self.token)) {
+            Ok(value) => value,
+            Err(err) => {
+                let err = Error::invalid_request(format!("invalid bearer token: {err}"));
+                return Box::pin(async move { Err(err) });
+            }
+        };
+        value.set_sensitive(true);
+        request.headers_mut().insert(AUTHORIZATION, value);
 
         let mut inner = self.inner.clone();
         Box::pin(async move { inner.call(request).await })
@@ -0,0 +1,193 @@
+//! Request buffering middleware.
+//!
+//! Queues requests in a bounded channel drained by a background task that
+//! owns the inner service, so callers do not wait on the inner service's
+//! readiness until the queue is full. This is tower's `Buffer` with pincer's
+//! error type.
+//!
+//! The worker task is spawned on the Tokio runtime, so the layer must be
+//! applied (the client built) within a Tokio runtime, unless another
+//! [`Runtime`] is set with [`BufferLayer::runtime`].
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use tower::{BoxError, Layer, Service};
+
+use crate::{Error, Request, Response, Result, Runtime, runtime_or_default};
+
+/// Layer that buffers requests in front of the inner service.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::HyperClient;
+///
+/// // Up to 10 requests in flight, 100 more queued
+/// let client = HyperClient::builder()
+///     .with_concurrency_limit(10)
+///     .with_buffer(100)
+///     .build();
+/// ```
+#[derive(Clone)]
+pub struct BufferLayer {
+    bound: usize,
+    runtime: Option<Arc<dyn Runtime>>,
+}
+
+impl std::fmt::Debug for BufferLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferLayer")
+            .field("bound", &self.bound)
+            .field("runtime", &self.runtime.as_ref().map(|_| "..."))
+            .finish()
+    }
+}
+
+impl BufferLayer {
+    /// Create a buffer layer queueing up to `bound` requests.
+    #[must_use]
+    pub const fn new(bound: usize) -> Self {
+        Self {
+            bound,
+            runtime: None,
+        }
+    }
+
+    /// Spawn the worker task on `runtime` instead of the Tokio runtime.
+    #[must_use]
+    pub fn runtime(mut self, runtime: Arc<dyn Runtime>) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+}
+
+impl<S> Layer<S> for BufferLayer
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Service = Buffer<S::Future>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let (inner, worker) = tower::buffer::Buffer::pair(inner, self.bound);
+        runtime_or_default(self.runtime.as_deref()).spawn(Box::pin(worker));
+        Buffer { inner }
+    }
+}
+
+/// Service that buffers requests in front of the inner service.
+///
+/// `F` is the future type of the buffered service.
+#[derive(Debug)]
+pub struct Buffer<F> {
+    inner: tower::buffer::Buffer<Request<Bytes>, F>,
+}
+
+impl<F: Send + 'static> Clone for Buffer<F> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<F> Service<Request<Bytes>> for Buffer<F>
+where
+    F: Future<Output = Result<Response<Bytes>>> + Send + 'static,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx).map_err(from_box_error)
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let response = self.inner.call(request);
+        Box::pin(async move { response.await.map_err(from_box_error) })
+    }
+}
+
+/// Recover the inner service's error, or report the failed worker.
+fn from_box_error(err: BoxError) -> Error {
+    match err.downcast::<Error>() {
+        Ok(err) => *err,
+        Err(err) => Error::connection(format!("request buffer failed: {err}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use http::HeaderMap;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::{Method, Sleep, TokioRuntime};
+
+    /// Tokio runtime counting the spawned tasks.
+    #[derive(Debug, Default)]
+    struct CountingRuntime(AtomicUsize);
+
+    impl Runtime for CountingRuntime {
+        fn sleep(&self, duration: Duration) -> Sleep {
+            TokioRuntime.sleep(duration)
+        }
+
+        fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            TokioRuntime.spawn(future);
+        }
+    }
+
+    fn request() -> Request<Bytes> {
+        Request::builder(
+            Method::Get,
+            "https://api.example.com/".parse().expect("url"),
+        )
+        .build()
+    }
+
+    #[tokio::test]
+    async fn forwards_responses_and_errors() {
+        let service = tower::service_fn(|request: Request<Bytes>| async move {
+            match request.header("x-fail") {
+                Some(_) => Err(Error::Timeout),
+                None => Ok(Response::new(200, HeaderMap::new(), Bytes::new())),
+            }
+        });
+        let service = BufferLayer::new(4).layer(service);
+
+        let response = service.clone().oneshot(request()).await.expect("response");
+        assert_eq!(response.status(), 200);
+
+        let mut failing = request();
+        failing
+            .headers_mut()
+            .insert("x-fail", "1".parse().expect("value"));
+        let err = service.oneshot(failing).await.expect_err("error");
+        assert!(err.is_timeout());
+    }
+
+    #[tokio::test]
+    async fn spawns_the_worker_on_the_runtime() {
+        let runtime = Arc::new(CountingRuntime::default());
+        let service = tower::service_fn(|_request: Request<Bytes>| async {
+            Ok(Response::new(204, HeaderMap::new(), Bytes::new()))
+        });
+        let service = BufferLayer::new(1)
+            .runtime(Arc::clone(&runtime) as Arc<dyn Runtime>)
+            .layer(service);
+        assert_eq!(runtime.0.load(Ordering::SeqCst), 1);
+
+        let response = service.oneshot(request()).await.expect("response");
+        assert_eq!(response.status(), 204);
+    }
+}
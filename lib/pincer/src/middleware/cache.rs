@@ -0,0 +1,692 @@
+//! Response cache middleware.
+//!
+//! Keeps the responses of `GET` requests in memory for the freshness lifetime
+//! of their `Cache-Control: max-age` directive, and answers the same requests
+//! from memory while they are fresh. Requests are matched on the key computed
+//! by a [`CacheKey`]: by default the method, URL and hashed credentials
+//! (`Authorization` and `Cookie`), see [`DefaultCacheKey`] to add headers or
+//! ignore query parameters. Responses with `Cache-Control: private` are not
+//! cached.
+//!
+//! A response with a `Vary` header is stored per value of the listed request
+//! headers, and only served to requests with the same values, e.g. one
+//! variant per `Accept-Language`. A response with `Vary: *` is not cached.
+
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::header::{
+    AUTHORIZATION, CACHE_CONTROL, COOKIE, HeaderMap, HeaderName, HeaderValue, VARY,
+};
+use sha2::{Digest, Sha256};
+use tokio::time::Instant;
+use tower::{Layer, Service};
+
+use crate::{DiscardBody, Error, Method, Request, Response, Result};
+
+/// Default maximum number of responses kept by a [`CacheLayer`].
+pub const DEFAULT_MAX_CACHE_ENTRIES: usize = 1000;
+
+/// Largest freshness lifetime, in seconds: greater `max-age` values are
+/// clamped to it (RFC 9111 section 1.2.2).
+const MAX_FRESHNESS_SECS: u64 = 1 << 31;
+
+/// Computes the key under which the response to a request is cached.
+///
+/// Two requests with the same key get the same cached response, so the key
+/// must include everything the response depends on: e.g. a hash of the
+/// `Authorization` header for per-user responses, to not serve them to other
+/// users.
+///
+/// Implemented for closures, e.g. `|request: &Request<Bytes>| Some(request.url().path().to_string())`.
+pub trait CacheKey: Send + Sync + 'static {
+    /// Compute the key of `request`, or `None` to not cache it.
+    fn key(&self, request: &Request<Bytes>) -> Option<String>;
+}
+
+impl<F> CacheKey for F
+where
+    F: Fn(&Request<Bytes>) -> Option<String> + Send + Sync + 'static,
+{
+    fn key(&self, request: &Request<Bytes>) -> Option<String> {
+        self(request)
+    }
+}
+
+/// Default [`CacheKey`]: the method and URL of the request, with selected
+/// headers and without volatile query parameters.
+///
+/// The `Authorization` and `Cookie` headers are always hashed into the key,
+/// so a response is only served to requests with the same credentials.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::header::{self, HeaderName};
+/// use pincer::middleware::DefaultCacheKey;
+///
+/// let key = DefaultCacheKey::new()
+///     .header(header::ACCEPT_LANGUAGE)
+///     .hashed_header(HeaderName::from_static("x-api-key"))
+///     .ignore_query_param("_ts");
+/// ```
+#[derive(Debug, Clone)]
+pub struct DefaultCacheKey {
+    headers: Vec<HeaderName>,
+    hashed_headers: Vec<HeaderName>,
+    ignored_params: Vec<String>,
+}
+
+impl Default for DefaultCacheKey {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DefaultCacheKey {
+    /// Create a key of the method, URL and hashed credentials.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            headers: Vec::new(),
+            hashed_headers: vec![AUTHORIZATION, COOKIE],
+            ignored_params: Vec::new(),
+        }
+    }
+
+    /// Add the value of the `name` header to the key, e.g. `Accept-Language`.
+    #[must_use]
+    pub fn header(mut self, name: HeaderName) -> Self {
+        self.headers.push(name);
+        self
+    }
+
+    /// Add the SHA-256 hash of the `name` header to the key, e.g.
+    /// `Authorization`, so secrets are not kept in the cache keys.
+    #[must_use]
+    pub fn hashed_header(mut self, name: HeaderName) -> Self {
+        if !self.hashed_headers.contains(&name) {
+            self.hashed_headers.push(name);
+        }
+        self
+    }
+
+    /// Leave the `name` query parameter out of the key, e.g. a timestamp or
+    /// tracking parameter that does not change the response.
+    #[must_use]
+    pub fn ignore_query_param(mut self, name: impl Into<String>) -> Self {
+        self.ignored_params.push(name.into());
+        self
+    }
+}
+
+impl CacheKey for DefaultCacheKey {
+    fn key(&self, request: &Request<Bytes>) -> Option<String> {
+        let mut url = request.url().clone();
+        if !self.ignored_params.is_empty() {
+            let pairs: Vec<(String, String)> = url
+                .query_pairs()
+                .filter(|(name, _)| !self.ignored_params.iter().any(|ignored| ignored == name))
+                .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                .collect();
+            if pairs.is_empty() {
+                url.set_query(None);
+            } else {
+                url.query_pairs_mut().clear().extend_pairs(pairs);
+            }
+        }
+
+        let mut key = format!("{} {url}", request.method());
+        let headers = request.headers();
+        for name in &self.headers {
+            let value = headers.get(name).map(HeaderValue::as_bytes);
+            let value = String::from_utf8_lossy(value.unwrap_or_default());
+            let _ = write!(key, "\n{name}: {value}");
+        }
+        for name in &self.hashed_headers {
+            let value = headers.get(name).map(HeaderValue::as_bytes);
+            let digest = Sha256::digest(value.unwrap_or_default());
+            let _ = write!(key, "\n{name}: ");
+            for byte in digest {
+                let _ = write!(key, "{byte:02x}");
+            }
+        }
+        Some(key)
+    }
+}
+
+/// A cached response.
+#[derive(Debug)]
+struct Entry {
+    response: Response<Bytes>,
+    expires: Instant,
+    /// Request headers listed by `Vary`, with their values when it was stored.
+    vary: Vec<(HeaderName, Option<HeaderValue>)>,
+}
+
+impl Entry {
+    /// Returns `true` if the response can be served to a request with `headers`.
+    fn matches(&self, headers: &HeaderMap) -> bool {
+        self.vary
+            .iter()
+            .all(|(name, value)| headers.get(name) == value.as_ref())
+    }
+}
+
+/// Variants of the responses, by cache key.
+type Entries = Arc<Mutex<HashMap<String, Vec<Entry>>>>;
+
+/// Layer caching the responses of `GET` requests in memory.
+///
+/// Only `200 OK` responses with a positive `Cache-Control: max-age` (and no
+/// `no-store`, `no-cache` or `private`) are cached. Requests with
+/// `Cache-Control: no-cache` or `no-store` bypass the cache.
+///
+/// Clones of the layer share the cached responses.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::header;
+/// use pincer::middleware::{CacheLayer, DefaultCacheKey};
+///
+/// let cache = CacheLayer::new()
+///     .key(DefaultCacheKey::new().header(header::ACCEPT_LANGUAGE))
+///     .max_entries(500);
+/// let client = HyperClient::builder().layer(cache.clone()).build();
+///
+/// // Custom key: cache by path only
+/// let cache = CacheLayer::new().key(|request: &Request<Bytes>| {
+///     Some(request.url().path().to_string())
+/// });
+/// ```
+#[derive(Clone)]
+pub struct CacheLayer {
+    key: Arc<dyn CacheKey>,
+    entries: Entries,
+    max_entries: usize,
+}
+
+impl fmt::Debug for CacheLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CacheLayer")
+            .field("max_entries", &self.max_entries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for CacheLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CacheLayer {
+    /// Create a cache keyed by [`DefaultCacheKey::new`], keeping at most
+    /// [`DEFAULT_MAX_CACHE_ENTRIES`] responses.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            key: Arc::new(DefaultCacheKey::new()),
+            entries: Arc::default(),
+            max_entries: DEFAULT_MAX_CACHE_ENTRIES,
+        }
+    }
+
+    /// Set how cache keys are computed.
+    #[must_use]
+    pub fn key(mut self, key: impl CacheKey) -> Self {
+        self.key = Arc::new(key);
+        self
+    }
+
+    /// Set the maximum number of cached responses.
+    ///
+    /// When full, the response closest to expiry is evicted.
+    #[must_use]
+    pub const fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Number of cached responses, including the expired ones not evicted yet.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        lock(&self.entries).values().map(Vec::len).sum()
+    }
+
+    /// Returns `true` if no response is cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove every cached response.
+    pub fn clear(&self) {
+        lock(&self.entries).clear();
+    }
+}
+
+impl<S> Layer<S> for CacheLayer {
+    type Service = Cache<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Cache {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service answering `GET` requests from cached responses while fresh.
+#[derive(Debug, Clone)]
+pub struct Cache<S> {
+    inner: S,
+    layer: CacheLayer,
+}
+
+fn lock(entries: &Entries) -> MutexGuard<'_, HashMap<String, Vec<Entry>>> {
+    entries.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns `true` if `Cache-Control` has `no-store` or `no-cache`.
+fn is_no_cache(headers: &HeaderMap) -> bool {
+    cache_directives(headers).any(|directive| directive == "no-store" || directive == "no-cache")
+}
+
+/// Freshness lifetime of a response, from its `Cache-Control: max-age`,
+/// at most 2^31 seconds.
+///
+/// `None` for a response that must not be cached, including `private` ones
+/// that another user of the client could be served.
+fn freshness(headers: &HeaderMap) -> Option<Duration> {
+    if is_no_cache(headers) || cache_directives(headers).any(|directive| directive == "private") {
+        return None;
+    }
+    cache_directives(headers)
+        .find_map(|directive| directive.strip_prefix("max-age=")?.parse().ok())
+        .filter(|seconds| *seconds > 0)
+        .map(|seconds: u64| Duration::from_secs(seconds.min(MAX_FRESHNESS_SECS)))
+}
+
+/// Request headers listed by the `Vary` headers, `None` for `Vary: *`.
+fn vary_names(headers: &HeaderMap) -> Option<Vec<HeaderName>> {
+    let mut names = Vec::new();
+    let values = headers
+        .get_all(VARY)
+        .into_iter()
+        .filter_map(|value| value.to_str().ok());
+    for name in values.flat_map(|value| value.split(',')).map(str::trim) {
+        if name == "*" {
+            return None;
+        }
+        if let Ok(name) = HeaderName::try_from(name)
+            && !names.contains(&name)
+        {
+            names.push(name);
+        }
+    }
+    Some(names)
+}
+
+fn cache_directives(headers: &HeaderMap) -> impl Iterator<Item = String> + '_ {
+    headers
+        .get_all(CACHE_CONTROL)
+        .into_iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|directive| directive.trim().to_ascii_lowercase())
+}
+
+/// Store `response` to a request with `headers` under `key`, evicting the
+/// response closest to expiry if full.
+fn store(layer: &CacheLayer, key: String, headers: &HeaderMap, response: &Response<Bytes>) {
+    let Some(lifetime) = freshness(response.headers()) else {
+        return;
+    };
+    let Some(names) = vary_names(response.headers()) else {
+        return;
+    };
+    let vary: Vec<_> = names
+        .into_iter()
+        .map(|name| {
+            let value = headers.get(&name).cloned();
+            (name, value)
+        })
+        .collect();
+    if layer.max_entries == 0 {
+        return;
+    }
+
+    let now = Instant::now();
+    let mut entries = lock(&layer.entries);
+    if let Some(variants) = entries.get_mut(&key) {
+        variants.retain(|entry| entry.vary != vary);
+    }
+    if entries.values().map(Vec::len).sum::<usize>() >= layer.max_entries {
+        for variants in entries.values_mut() {
+            variants.retain(|entry| entry.expires > now);
+        }
+        entries.retain(|_, variants| !variants.is_empty());
+    }
+    if entries.values().map(Vec::len).sum::<usize>() >= layer.max_entries {
+        let oldest = entries
+            .iter()
+            .flat_map(|(key, variants)| variants.iter().map(move |entry| (key, entry.expires)))
+            .min_by_key(|(_, expires)| *expires);
+        if let Some((oldest, expires)) = oldest.map(|(key, expires)| (key.clone(), expires)) {
+            if let Some(variants) = entries.get_mut(&oldest) {
+                variants.retain(|entry| entry.expires != expires);
+            }
+            entries.retain(|_, variants| !variants.is_empty());
+        }
+    }
+    entries.entry(key).or_default().push(Entry {
+        response: response.clone(),
+        expires: now + lifetime,
+        vary,
+    });
+}
+
+impl<S> Service<Request<Bytes>> for Cache<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let key = (request.method() == Method::Get && !is_no_cache(request.headers()))
+            .then(|| self.layer.key.key(&request))
+            .flatten();
+        let Some(key) = key else {
+            return Box::pin(async move { inner.call(request).await });
+        };
+
+        {
+            let mut entries = lock(&self.layer.entries);
+            if let Some(variants) = entries.get_mut(&key) {
+                let now = Instant::now();
+                variants.retain(|entry| entry.expires > now);
+                let cached = variants
+                    .iter()
+                    .find(|entry| entry.matches(request.headers()))
+                    .map(|entry| entry.response.clone());
+                if let Some(response) = cached {
+                    return Box::pin(async move { Ok(response) });
+                }
+                if variants.is_empty() {
+                    entries.remove(&key);
+                }
+            }
+        }
+
+        let layer = self.layer.clone();
+        let headers = request.headers().clone();
+        // The response of a discarded body is empty, not worth caching
+        let discard = request.extensions().get::<DiscardBody>().is_some();
+        Box::pin(async move {
+            let response = inner.call(request).await?;
+            if response.status() == 200 && !discard {
+                store(&layer, key, &headers, &response);
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use http::header::ACCEPT_LANGUAGE;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn request(url: &str) -> Request<Bytes> {
+        Request::builder(Method::Get, url.parse().expect("url")).build()
+    }
+
+    /// Service counting its calls, answering with `Cache-Control: max-age=60`.
+    fn counting(
+        calls: Arc<AtomicUsize>,
+    ) -> tower::util::ServiceFn<
+        impl Fn(Request<Bytes>) -> std::future::Ready<Result<Response<Bytes>>> + Clone,
+    > {
+        tower::service_fn(move |_request: Request<Bytes>| {
+            let count = calls.fetch_add(1, Ordering::SeqCst);
+            let mut headers = HeaderMap::new();
+            headers.insert(CACHE_CONTROL, "max-age=60".parse().expect("value"));
+            std::future::ready(Ok(Response::new(
+                200,
+                headers,
+                Bytes::from(count.to_string()),
+            )))
+        })
+    }
+
+    #[test]
+    fn default_key_includes_selected_headers() {
+        let key = DefaultCacheKey::new()
+            .header(ACCEPT_LANGUAGE)
+            .hashed_header(AUTHORIZATION)
+            .ignore_query_param("_ts");
+        let request = Request::builder(
+            Method::Get,
+            "https://api.example.com/items?page=2&_ts=123"
+                .parse()
+                .expect("url"),
+        )
+        .header("accept-language", "fr")
+        .header("authorization", "Bearer secret")
+        .build();
+
+        let key = key.key(&request).expect("key");
+        assert!(key.starts_with("GET https://api.example.com/items?page=2\naccept-language: fr\n"));
+        assert!(!key.contains("secret"));
+        assert_eq!(key.matches("authorization: ").count(), 1);
+        assert!(key.contains("\ncookie: "));
+    }
+
+    #[tokio::test]
+    async fn does_not_share_responses_between_users() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = CacheLayer::new();
+        let service = layer.layer(counting(Arc::clone(&calls)));
+        let as_user = |credentials: &str| {
+            Request::builder(
+                Method::Get,
+                "https://api.example.com/me".parse().expect("url"),
+            )
+            .header("authorization", credentials)
+            .build()
+        };
+
+        for (credentials, body) in [
+            ("Bearer alice", "0"),
+            ("Bearer bob", "1"),
+            ("Bearer alice", "0"),
+        ] {
+            let response = service
+                .clone()
+                .oneshot(as_user(credentials))
+                .await
+                .expect("response");
+            assert_eq!(response.body().as_ref(), body.as_bytes());
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(layer.len(), 2);
+    }
+
+    #[test]
+    fn does_not_store_private_responses() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, "private, max-age=60".parse().expect("value"));
+        assert_eq!(freshness(&headers), None);
+
+        headers.insert(CACHE_CONTROL, "public, max-age=60".parse().expect("value"));
+        assert_eq!(freshness(&headers), Some(Duration::from_mins(1)));
+    }
+
+    #[tokio::test]
+    async fn clamps_huge_max_age() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CACHE_CONTROL,
+            format!("max-age={}", u64::MAX).parse().expect("value"),
+        );
+        assert_eq!(
+            freshness(&headers),
+            Some(Duration::from_secs(MAX_FRESHNESS_SECS))
+        );
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = CacheLayer::new();
+        let service = layer.layer(tower::service_fn({
+            let calls = Arc::clone(&calls);
+            let headers = headers.clone();
+            move |_request: Request<Bytes>| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                std::future::ready(Ok(Response::new(200, headers.clone(), Bytes::new())))
+            }
+        }));
+        for _ in 0..2 {
+            service
+                .clone()
+                .oneshot(request("https://api.example.com/items"))
+                .await
+                .expect("response");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn serves_fresh_responses_from_cache() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = CacheLayer::new();
+        let service = layer.layer(counting(Arc::clone(&calls)));
+
+        for _ in 0..2 {
+            let response = service
+                .clone()
+                .oneshot(request("https://api.example.com/items"))
+                .await
+                .expect("response");
+            assert_eq!(response.body().as_ref(), b"0");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        tokio::time::advance(Duration::from_mins(1)).await;
+        let response = service
+            .oneshot(request("https://api.example.com/items"))
+            .await
+            .expect("response");
+        assert_eq!(response.body().as_ref(), b"1");
+    }
+
+    #[tokio::test]
+    async fn custom_key_selects_cached_requests() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = CacheLayer::new().key(|request: &Request<Bytes>| {
+            (request.url().path() != "/live").then(|| request.url().path().to_string())
+        });
+        let service = layer.layer(counting(Arc::clone(&calls)));
+
+        for url in [
+            "https://api.example.com/items?a=1",
+            "https://api.example.com/items?a=2",
+            "https://api.example.com/live",
+            "https://api.example.com/live",
+        ] {
+            service
+                .clone()
+                .oneshot(request(url))
+                .await
+                .expect("response");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(layer.len(), 1);
+    }
+    /// Service answering with the given `Vary` header and the request language.
+    fn varying(
+        vary: &'static str,
+        calls: Arc<AtomicUsize>,
+    ) -> tower::util::ServiceFn<
+        impl Fn(Request<Bytes>) -> std::future::Ready<Result<Response<Bytes>>> + Clone,
+    > {
+        tower::service_fn(move |request: Request<Bytes>| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            let mut headers = HeaderMap::new();
+            headers.insert(CACHE_CONTROL, "max-age=60".parse().expect("value"));
+            headers.insert(VARY, vary.parse().expect("value"));
+            let body = Bytes::from(request.header("accept-language").unwrap_or("-").to_string());
+            std::future::ready(Ok(Response::new(200, headers, body)))
+        })
+    }
+
+    fn localized(language: &str) -> Request<Bytes> {
+        Request::builder(
+            Method::Get,
+            "https://api.example.com/items".parse().expect("url"),
+        )
+        .header("accept-language", language)
+        .build()
+    }
+
+    #[tokio::test]
+    async fn stores_a_variant_per_vary_header_value() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = CacheLayer::new();
+        let service = layer.layer(varying(
+            "Accept-Encoding, accept-language",
+            Arc::clone(&calls),
+        ));
+
+        for language in ["fr", "en", "fr", "en"] {
+            let response = service
+                .clone()
+                .oneshot(localized(language))
+                .await
+                .expect("response");
+            assert_eq!(response.body().as_ref(), language.as_bytes());
+        }
+        let response = service
+            .oneshot(request("https://api.example.com/items"))
+            .await
+            .expect("response");
+        assert_eq!(response.body().as_ref(), b"-");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(layer.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_cache_vary_star() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let layer = CacheLayer::new();
+        let service = layer.layer(varying("*", Arc::clone(&calls)));
+
+        for _ in 0..2 {
+            service
+                .clone()
+                .oneshot(localized("fr"))
+                .await
+                .expect("response");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(layer.is_empty());
+    }
+}
@@ -0,0 +1,290 @@
+//! Call context propagation middleware.
+//!
+//! Attaches the task-local [`CallContext`] (set with [`with_call_context`])
+//! to requests that do not carry one, and optionally stamps its fields as
+//! headers, so multi-tenant services set the tenant once per task instead of
+//! on every call. Layers further down the stack read the
+//! [`CallContext`] extension.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use http::header::{HeaderName, HeaderValue};
+use tower::{Layer, Service};
+
+use crate::{CallContext, Error, Request, Response, Result};
+
+tokio::task_local! {
+    static CURRENT: CallContext;
+}
+
+/// Run `future` with `context` as the task-local call context.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::CallContext;
+/// use pincer::middleware::with_call_context;
+///
+/// let context = CallContext::new().with_tenant_id("acme");
+/// let user = with_call_context(context, client.get_user(42)).await?;
+/// ```
+pub async fn with_call_context<F: Future>(context: CallContext, future: F) -> F::Output {
+    CURRENT.scope(context, future).await
+}
+
+/// Get the task-local call context, if any.
+#[must_use]
+pub fn current_call_context() -> Option<CallContext> {
+    CURRENT.try_with(Clone::clone).ok()
+}
+
+/// Header names a [`CallContextLayer`] stamps the context fields to.
+#[derive(Debug, Clone, Default)]
+struct Headers {
+    tenant_id: Option<Arc<str>>,
+    user_id: Option<Arc<str>>,
+    locale: Option<Arc<str>>,
+    feature_flags: Option<Arc<str>>,
+}
+
+/// Layer propagating the [`CallContext`] to requests.
+///
+/// A context already in the request extensions wins over the task-local
+/// one, and headers already set on the request are left unchanged. No header
+/// is stamped unless configured.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::middleware::CallContextLayer;
+///
+/// let layer = CallContextLayer::new()
+///     .with_tenant_header("x-tenant-id")
+///     .with_locale_header("accept-language");
+///
+/// let client = HyperClient::builder().layer(layer).build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CallContextLayer {
+    headers: Headers,
+}
+
+impl CallContextLayer {
+    /// Create a layer attaching the context without stamping headers.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stamp the tenant id to the `name` header.
+    #[must_use]
+    pub fn with_tenant_header(mut self, name: &str) -> Self {
+        self.headers.tenant_id = Some(Arc::from(name));
+        self
+    }
+
+    /// Stamp the user id to the `name` header.
+    #[must_use]
+    pub fn with_user_header(mut self, name: &str) -> Self {
+        self.headers.user_id = Some(Arc::from(name));
+        self
+    }
+
+    /// Stamp the locale to the `name` header (e.g. `accept-language`).
+    #[must_use]
+    pub fn with_locale_header(mut self, name: &str) -> Self {
+        self.headers.locale = Some(Arc::from(name));
+        self
+    }
+
+    /// Stamp the feature flags, comma-separated, to the `name` header.
+    #[must_use]
+    pub fn with_feature_flags_header(mut self, name: &str) -> Self {
+        self.headers.feature_flags = Some(Arc::from(name));
+        self
+    }
+}
+
+impl<S> Layer<S> for CallContextLayer {
+    type Service = CallContextService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CallContextService {
+            inner,
+            headers: Arc::new(self.headers.clone()),
+        }
+    }
+}
+
+/// Service propagating the [`CallContext`] to requests.
+#[derive(Debug, Clone)]
+pub struct CallContextService<S> {
+    inner: S,
+    headers: Arc<Headers>,
+}
+
+impl<S> CallContextService<S> {
+    /// Stamp the configured headers from `context`.
+    fn stamp(&self, request: &mut Request<Bytes>, context: &CallContext) -> Result<()> {
+        let feature_flags = context.feature_flags().collect::<Vec<_>>().join(",");
+        let fields = [
+            (&self.headers.tenant_id, context.tenant_id()),
+            (&self.headers.user_id, context.user_id()),
+            (&self.headers.locale, context.locale()),
+            (
+                &self.headers.feature_flags,
+                Some(feature_flags.as_str()).filter(|flags| !flags.is_empty()),
+            ),
+        ];
+        for (name, value) in fields {
+            let (Some(name), Some(value)) = (name, value) else {
+                continue;
+            };
+            let name = HeaderName::try_from(&**name).map_err(|err| invalid_header(&err))?;
+            if request.headers().contains_key(&name) {
+                continue;
+            }
+            let value = HeaderValue::try_from(value).map_err(|err| invalid_header(&err))?;
+            request.headers_mut().insert(name, value);
+        }
+        Ok(())
+    }
+}
+
+impl<S> Service<Request<Bytes>> for CallContextService<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Bytes>) -> Self::Future {
+        let context = match request.extensions().get::<CallContext>() {
+            Some(context) => Some(context.clone()),
+            None => current_call_context(),
+        };
+        if let Some(context) = context {
+            if let Err(err) = self.stamp(&mut request, &context) {
+                return Box::pin(async move { Err(err) });
+            }
+            request.extensions_mut().insert(context);
+        }
+
+        let mut inner = self.inner.clone();
+        Box::pin(async move { inner.call(request).await })
+    }
+}
+
+fn invalid_header(err: &impl std::fmt::Display) -> Error {
+    Error::invalid_request(format!("invalid call context header: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderMap;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::Method;
+
+    fn request() -> Request<Bytes> {
+        Request::builder(
+            Method::Get,
+            "https://api.example.com/".parse().expect("url"),
+        )
+        .build()
+    }
+
+    /// Echo the request headers, and the tenant of the context extension.
+    fn echo() -> impl Service<
+        Request<Bytes>,
+        Response = Response<Bytes>,
+        Error = Error,
+        Future = impl Future<Output = Result<Response<Bytes>>> + Send,
+    > + Clone
+    + Send
+    + 'static {
+        tower::service_fn(|request: Request<Bytes>| async move {
+            let tenant = request
+                .extensions()
+                .get::<CallContext>()
+                .and_then(CallContext::tenant_id)
+                .unwrap_or_default()
+                .to_string();
+            Ok::<_, Error>(Response::new(
+                200,
+                request.headers().clone(),
+                Bytes::from(tenant),
+            ))
+        })
+    }
+
+    #[tokio::test]
+    async fn propagates_task_local_context() {
+        let layer = CallContextLayer::new()
+            .with_tenant_header("x-tenant-id")
+            .with_user_header("x-user-id")
+            .with_feature_flags_header("x-features");
+        let context = CallContext::new()
+            .with_tenant_id("acme")
+            .with_feature_flag("beta")
+            .with_feature_flag("alpha");
+
+        let response = with_call_context(context, layer.layer(echo()).oneshot(request()))
+            .await
+            .expect("response");
+        assert_eq!(response.body().as_ref(), b"acme");
+        assert_eq!(response.header("x-tenant-id"), Some("acme"));
+        assert_eq!(response.header("x-user-id"), None);
+        assert_eq!(response.header("x-features"), Some("alpha,beta"));
+
+        // Outside of a scope, requests are left unchanged
+        let response = layer
+            .layer(echo())
+            .oneshot(request())
+            .await
+            .expect("response");
+        assert!(response.body().is_empty());
+        assert_eq!(response.headers(), &HeaderMap::new());
+    }
+
+    #[tokio::test]
+    async fn request_context_and_headers_win() {
+        let layer = CallContextLayer::new().with_tenant_header("x-tenant-id");
+        let mut request = request();
+        request
+            .extensions_mut()
+            .insert(CallContext::new().with_tenant_id("globex"));
+        let service = layer.layer(echo());
+
+        let response = with_call_context(
+            CallContext::new().with_tenant_id("acme"),
+            service.clone().oneshot(request),
+        )
+        .await
+        .expect("response");
+        assert_eq!(response.header("x-tenant-id"), Some("globex"));
+
+        let mut request = self::request();
+        request
+            .headers_mut()
+            .insert("x-tenant-id", HeaderValue::from_static("initech"));
+        let response = with_call_context(
+            CallContext::new().with_tenant_id("acme"),
+            service.oneshot(request),
+        )
+        .await
+        .expect("response");
+        assert_eq!(response.header("x-tenant-id"), Some("initech"));
+    }
+}
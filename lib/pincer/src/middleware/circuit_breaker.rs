@@ -289,7 +289,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use http::HeaderMap;
     use std::pin::Pin;
     use std::sync::atomic::{AtomicU32, Ordering};
 
@@ -346,7 +346,7 @@ mod tests {
                 if should_error {
                     Err(Error::connection("mock error"))
                 } else {
-                    Ok(Response::new(status, HashMap::new(), Bytes::new()))
+                    Ok(Response::new(status, HeaderMap::new(), Bytes::new()))
                 }
             })
         }
@@ -369,11 +369,11 @@ mod tests {
     fn circuit_breaker_config_builder() {
         let config = CircuitBreakerConfig::default()
             .with_failure_threshold(10)
-            .with_open_duration(Duration::from_secs(60))
+            .with_open_duration(Duration::from_mins(1))
             .with_success_threshold(3);
 
         assert_eq!(config.failure_threshold, 10);
-        assert_eq!(config.open_duration, Duration::from_secs(60));
+        assert_eq!(config.open_duration, Duration::from_mins(1));
         assert_eq!(config.success_threshold, 3);
     }
 
@@ -432,7 +432,7 @@ mod tests {
         let mock = MockService::with_error();
         let config = CircuitBreakerConfig::default()
             .with_failure_threshold(3)
-            .with_open_duration(Duration::from_secs(60));
+            .with_open_duration(Duration::from_mins(1));
         let layer = CircuitBreakerLayer::new(config);
         let mut service = layer.layer(mock.clone());
 
@@ -459,7 +459,7 @@ mod tests {
         let mock = MockService::new(500);
         let config = CircuitBreakerConfig::default()
             .with_failure_threshold(2)
-            .with_open_duration(Duration::from_secs(60));
+            .with_open_duration(Duration::from_mins(1));
         let layer = CircuitBreakerLayer::new(config);
         let mut service = layer.layer(mock.clone());
 
@@ -532,7 +532,7 @@ mod tests {
                     if should_fail {
                         Err(Error::connection("mock error"))
                     } else {
-                        Ok(Response::new(200, HashMap::new(), Bytes::new()))
+                        Ok(Response::new(200, HeaderMap::new(), Bytes::new()))
                     }
                 })
             }
@@ -587,7 +587,7 @@ mod tests {
                     if should_fail {
                         Err(Error::connection("mock error"))
                     } else {
-                        Ok(Response::new(200, HashMap::new(), Bytes::new()))
+                        Ok(Response::new(200, HeaderMap::new(), Bytes::new()))
                     }
                 })
             }
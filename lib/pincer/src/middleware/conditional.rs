@@ -0,0 +1,180 @@
+//! Conditional middleware.
+//!
+//! Applies a layer only to the requests matching a predicate, evaluated at
+//! call time on the request (method, host, path template, extensions), so
+//! e.g. retries can be limited to `GET`s or authentication to one host of a
+//! multi-host client. Other requests go straight to the inner service.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use tower::{Layer, Service, ServiceExt};
+
+use crate::{Error, Request, Response, Result};
+
+type Predicate = dyn Fn(&Request<Bytes>) -> bool + Send + Sync;
+
+/// Layer applying `L` only to the requests matching a predicate.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::middleware::{ConditionalLayer, RetryLayer, RetryPolicy};
+/// use pincer::{Method, PathTemplate};
+///
+/// // Retry reads only
+/// let retry_reads = ConditionalLayer::new(
+///     |request| request.method() == Method::Get,
+///     RetryLayer::new(RetryPolicy::new(3)),
+/// );
+///
+/// // Authenticate the calls to one host only
+/// let client = HyperClient::builder()
+///     .layer_if(
+///         |request| request.url().host_str() == Some("api.example.com"),
+///         BearerAuthLayer::new("token"),
+///     )
+///     .build();
+///
+/// // Skip an endpoint, by path template
+/// let not_health = |request: &Request<Bytes>| {
+///     request
+///         .extensions()
+///         .get::<PathTemplate>()
+///         .is_none_or(|template| template.as_str() != "/health")
+/// };
+/// ```
+#[derive(Clone)]
+pub struct ConditionalLayer<L> {
+    predicate: Arc<Predicate>,
+    layer: L,
+}
+
+impl<L: fmt::Debug> fmt::Debug for ConditionalLayer<L> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConditionalLayer")
+            .field("layer", &self.layer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<L> ConditionalLayer<L> {
+    /// Apply `layer` to the requests for which `predicate` returns `true`.
+    pub fn new(
+        predicate: impl Fn(&Request<Bytes>) -> bool + Send + Sync + 'static,
+        layer: L,
+    ) -> Self {
+        Self {
+            predicate: Arc::new(predicate),
+            layer,
+        }
+    }
+}
+
+impl<S: Clone, L: Layer<S>> Layer<S> for ConditionalLayer<L> {
+    type Service = Conditional<S, L::Service>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Conditional {
+            layered: self.layer.layer(inner.clone()),
+            inner,
+            predicate: Arc::clone(&self.predicate),
+        }
+    }
+}
+
+/// Service sending the requests matching a predicate through a layered service.
+///
+/// Always reports ready: the selected service is only polled for readiness
+/// once the request is known, when it is called.
+#[derive(Clone)]
+pub struct Conditional<S, T> {
+    inner: S,
+    layered: T,
+    predicate: Arc<Predicate>,
+}
+
+impl<S: fmt::Debug, T: fmt::Debug> fmt::Debug for Conditional<S, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Conditional")
+            .field("inner", &self.inner)
+            .field("layered", &self.layered)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, T> Service<Request<Bytes>> for Conditional<S, T>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error> + Clone + Send + 'static,
+    S::Future: Send,
+    T: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error> + Clone + Send + 'static,
+    T::Future: Send,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        if (self.predicate)(&request) {
+            Box::pin(self.layered.clone().oneshot(request))
+        } else {
+            Box::pin(self.inner.clone().oneshot(request))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderMap;
+
+    use super::*;
+    use crate::Method;
+    use crate::middleware::MapLayer;
+
+    fn request(method: Method) -> Request<Bytes> {
+        Request::builder(method, "https://api.example.com/".parse().expect("url")).build()
+    }
+
+    #[tokio::test]
+    async fn applies_layer_to_matching_requests() {
+        let service = tower::service_fn(|request: Request<Bytes>| async move {
+            let status = if request.header("x-layered").is_some() {
+                200
+            } else {
+                204
+            };
+            Ok::<_, Error>(Response::new(status, HeaderMap::new(), Bytes::new()))
+        });
+        let layer = ConditionalLayer::new(
+            |request| request.method() == Method::Get,
+            MapLayer::new().map_request(|mut request| {
+                request
+                    .headers_mut()
+                    .insert("x-layered", "1".parse().expect("value"));
+                request
+            }),
+        );
+        let service = layer.layer(service);
+
+        let response = service
+            .clone()
+            .oneshot(request(Method::Get))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), 200);
+
+        let response = service
+            .oneshot(request(Method::Post))
+            .await
+            .expect("response");
+        assert_eq!(response.status(), 204);
+    }
+}
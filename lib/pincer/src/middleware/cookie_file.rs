@@ -0,0 +1,293 @@
+//! Encrypted cookie persistence.
+//!
+//! Saves the cookies of a [`CookieStore`] to a file encrypted with
+//! AES-256-GCM, and loads them back on startup, so command line tools keep
+//! their login session between runs.
+
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::CookieStore;
+
+/// Header of the cookie files, with the format version.
+const MAGIC: &[u8] = b"pincer-cookies-v1\n";
+
+/// Cookie file encrypted at rest with AES-256-GCM.
+///
+/// The file holds the unexpired cookies of a [`CookieStore`], serialized as
+/// JSON and sealed with a 256-bit key, e.g. read from the OS keychain. It is
+/// written atomically, readable by its owner only on Unix.
+///
+/// Use it with [`CookieJarLayer::persistent`](super::CookieJarLayer::persistent)
+/// to load the cookies on startup and save them after each response setting
+/// cookies.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::middleware::{CookieFile, CookieJarLayer};
+///
+/// let key = keyring.cookie_key()?; // [u8; 32]
+/// let file = CookieFile::new(config_dir.join("cookies"), key);
+/// let client = HyperClient::builder()
+///     .layer(CookieJarLayer::persistent(file)?)
+///     .build();
+/// ```
+pub struct CookieFile {
+    path: PathBuf,
+    key: [u8; 32],
+    /// Held while writing, so concurrent saves do not interleave.
+    write: Mutex<()>,
+}
+
+impl fmt::Debug for CookieFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CookieFile")
+            .field("path", &self.path)
+            .field("key", &"[REDACTED]")
+            .finish_non_exhaustive()
+    }
+}
+
+impl CookieFile {
+    /// Create a cookie file at `path`, encrypted with `key`.
+    pub fn new(path: impl Into<PathBuf>, key: [u8; 32]) -> Self {
+        Self {
+            path: path.into(),
+            key,
+            write: Mutex::new(()),
+        }
+    }
+
+    /// Generate a random key, to store e.g. in the OS keychain.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system random number generator fails.
+    pub fn generate_key() -> io::Result<[u8; 32]> {
+        let mut key = [0; 32];
+        SystemRandom::new()
+            .fill(&mut key)
+            .map_err(|_| io::Error::other("failed to generate a cookie file key"))?;
+        Ok(key)
+    }
+
+    /// Get the path of the file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Add the cookies of the file to `store`.
+    ///
+    /// Returns the number of cookies loaded, `0` if the file does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, or was not encrypted
+    /// with this key.
+    pub fn load(&self, store: &CookieStore) -> io::Result<usize> {
+        let mut sealed = match fs::read(&self.path) {
+            Ok(sealed) => sealed,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+        let json = self.open(&mut sealed)?;
+        store
+            .load_json(json)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Write the cookies of `store` to the file, replacing it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, store: &CookieStore) -> io::Result<()> {
+        let json = store
+            .to_json()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let sealed = self.seal(json)?;
+
+        let _write = self.write.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut temp = OsString::from(&self.path);
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+        let mut file = create_private(&temp)?;
+        file.write_all(&sealed)?;
+        file.sync_all()?;
+        fs::rename(&temp, &self.path)
+    }
+
+    fn key(&self) -> io::Result<LessSafeKey> {
+        let key = UnboundKey::new(&AES_256_GCM, &self.key)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid cookie file key"))?;
+        Ok(LessSafeKey::new(key))
+    }
+
+    /// Encrypt `json` as `MAGIC || nonce || ciphertext || tag`.
+    fn seal(&self, mut json: Vec<u8>) -> io::Result<Vec<u8>> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| io::Error::other("failed to generate a cookie file nonce"))?;
+        self.key()?
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(MAGIC),
+                &mut json,
+            )
+            .map_err(|_| io::Error::other("failed to encrypt the cookies"))?;
+
+        let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + json.len());
+        sealed.extend_from_slice(MAGIC);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&json);
+        Ok(sealed)
+    }
+
+    /// Decrypt the content of a file written by [`seal`](Self::seal).
+    fn open<'a>(&self, sealed: &'a mut [u8]) -> io::Result<&'a [u8]> {
+        let invalid = |message| io::Error::new(io::ErrorKind::InvalidData, message);
+        let rest = sealed
+            .strip_prefix(MAGIC)
+            .ok_or_else(|| invalid("not a cookie file"))?;
+        let nonce = rest
+            .get(..NONCE_LEN)
+            .ok_or_else(|| invalid("truncated cookie file"))?;
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| invalid("truncated cookie file"))?;
+
+        let ciphertext = sealed
+            .get_mut(MAGIC.len() + NONCE_LEN..)
+            .ok_or_else(|| invalid("truncated cookie file"))?;
+        let json = self
+            .key()?
+            .open_in_place(nonce, Aad::from(MAGIC), ciphertext)
+            .map_err(|_| invalid("cookie file not encrypted with this key, or corrupted"))?;
+        Ok(json)
+    }
+}
+
+/// Create `path` for writing, readable by its owner only on Unix.
+fn create_private(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use http::HeaderMap;
+    use http::header::SET_COOKIE;
+    use tower::{Layer, ServiceExt};
+
+    use super::*;
+    use crate::middleware::CookieJarLayer;
+    use crate::{Error, Method, Request, Response};
+
+    fn url(url: &str) -> url::Url {
+        url.parse().expect("url")
+    }
+
+    /// A cookie file in the temporary directory, removed on drop.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("pincer-{name}-{}.cookies", std::process::id()));
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn round_trips_encrypted_cookies() {
+        let temp = TempFile::new("round-trip");
+        let key = CookieFile::generate_key().expect("key");
+        let origin = url("https://api.example.com/");
+        let store = CookieStore::new();
+        store.insert(&origin, "session=abc; Secure; HttpOnly");
+
+        let file = CookieFile::new(&temp.0, key);
+        file.save(&store).expect("save");
+        let content = fs::read(&temp.0).expect("read");
+        assert!(content.starts_with(MAGIC));
+        assert!(!content.windows(3).any(|window| window == b"abc"));
+
+        let loaded = CookieStore::new();
+        assert_eq!(file.load(&loaded).expect("load"), 1);
+        assert_eq!(loaded.get(&origin, "session"), Some("abc".to_string()));
+    }
+
+    #[test]
+    fn rejects_another_key() {
+        let temp = TempFile::new("another-key");
+        let store = CookieStore::new();
+        store.insert(&url("https://api.example.com/"), "session=abc");
+        CookieFile::new(&temp.0, [1; 32])
+            .save(&store)
+            .expect("save");
+
+        let err = CookieFile::new(&temp.0, [2; 32])
+            .load(&CookieStore::new())
+            .expect_err("wrong key");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn loads_nothing_without_a_file() {
+        let temp = TempFile::new("missing");
+        let file = CookieFile::new(&temp.0, [0; 32]);
+        assert_eq!(file.load(&CookieStore::new()).expect("load"), 0);
+    }
+
+    #[tokio::test]
+    async fn cookie_jar_keeps_the_session_between_runs() {
+        let temp = TempFile::new("jar");
+        let key = CookieFile::generate_key().expect("key");
+        let login = tower::service_fn(|_request: Request<Bytes>| async {
+            let mut headers = HeaderMap::new();
+            headers.insert(SET_COOKIE, "session=abc; Path=/".parse().expect("value"));
+            Ok::<_, Error>(Response::new(200, headers, Bytes::new()))
+        });
+        let request = || {
+            Request::builder(
+                Method::Post,
+                "https://api.example.com/login".parse().expect("url"),
+            )
+            .build()
+        };
+
+        let layer = CookieJarLayer::persistent(CookieFile::new(&temp.0, key)).expect("layer");
+        layer
+            .layer(login)
+            .oneshot(request())
+            .await
+            .expect("response");
+
+        let layer = CookieJarLayer::persistent(CookieFile::new(&temp.0, key)).expect("layer");
+        assert_eq!(
+            layer
+                .store()
+                .get(&url("https://api.example.com/"), "session"),
+            Some("abc".to_string())
+        );
+    }
+}
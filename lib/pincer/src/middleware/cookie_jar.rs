@@ -0,0 +1,247 @@
+//! Cookie jar middleware.
+//!
+//! Stores the cookies set by responses in a [`CookieStore`] and sends them
+//! back on the next requests they match, for session-based APIs.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+#[cfg(feature = "middleware-cookie-persistence")]
+use http::header::SET_COOKIE;
+use http::header::{COOKIE, HeaderValue};
+use tower::{Layer, Service};
+
+#[cfg(feature = "middleware-cookie-persistence")]
+use super::CookieFile;
+use crate::{CookieStore, Error, Request, Response, Result};
+
+/// Layer that keeps the cookies of a session.
+///
+/// Cookies already set on a request (e.g. with a `#[cookie]` parameter) take
+/// precedence over the stored cookies with the same name.
+///
+/// Place it outside of [`FollowRedirectLayer`](super::FollowRedirectLayer)
+/// to only keep the cookies of final responses, inside to also send them on
+/// redirects.
+///
+/// # Example
+///
+/// ```ignore
+/// use std::sync::Arc;
+///
+/// use pincer::CookieStore;
+/// use pincer::middleware::CookieJarLayer;
+///
+/// let cookies = Arc::new(CookieStore::new());
+/// let client = HyperClient::builder()
+///     .layer(CookieJarLayer::with_store(Arc::clone(&cookies)))
+///     .build();
+///
+/// client.login(&credentials).await?; // Set-Cookie: session=...
+/// client.orders().await?; // Cookie: session=...
+/// cookies.clear(); // log out
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CookieJarLayer {
+    store: Arc<CookieStore>,
+    #[cfg(feature = "middleware-cookie-persistence")]
+    file: Option<Arc<CookieFile>>,
+}
+
+impl CookieJarLayer {
+    /// Create a layer with an empty cookie store.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a layer using `store`, e.g. shared with other clients.
+    #[must_use]
+    pub const fn with_store(store: Arc<CookieStore>) -> Self {
+        Self {
+            store,
+            #[cfg(feature = "middleware-cookie-persistence")]
+            file: None,
+        }
+    }
+
+    /// Create a layer loading the cookies of `file`, and saving them to it
+    /// after each response setting cookies.
+    ///
+    /// The file is written on the blocking thread pool of the Tokio runtime
+    /// before the response is returned. A failure to save is logged, the
+    /// response is returned anyway.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the existing file cannot be loaded.
+    #[cfg(feature = "middleware-cookie-persistence")]
+    pub fn persistent(file: CookieFile) -> std::io::Result<Self> {
+        let store = Arc::new(CookieStore::new());
+        file.load(&store)?;
+        Ok(Self {
+            store,
+            file: Some(Arc::new(file)),
+        })
+    }
+
+    /// Get the cookie store.
+    #[must_use]
+    pub const fn store(&self) -> &Arc<CookieStore> {
+        &self.store
+    }
+}
+
+impl<S> Layer<S> for CookieJarLayer {
+    type Service = CookieJar<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CookieJar {
+            inner,
+            store: Arc::clone(&self.store),
+            #[cfg(feature = "middleware-cookie-persistence")]
+            file: self.file.clone(),
+        }
+    }
+}
+
+/// Service that keeps the cookies of a session.
+#[derive(Debug, Clone)]
+pub struct CookieJar<S> {
+    inner: S,
+    store: Arc<CookieStore>,
+    #[cfg(feature = "middleware-cookie-persistence")]
+    file: Option<Arc<CookieFile>>,
+}
+
+impl<S> CookieJar<S> {
+    /// Create a cookie jar service wrapping the given service.
+    pub fn new(inner: S, store: Arc<CookieStore>) -> Self {
+        Self {
+            inner,
+            store,
+            #[cfg(feature = "middleware-cookie-persistence")]
+            file: None,
+        }
+    }
+}
+
+/// Add the stored cookies not already set to the `Cookie` header of `request`.
+fn add_cookies(request: &mut Request<Bytes>, stored: &[(String, String)]) {
+    let current = request.header("cookie").unwrap_or_default().to_string();
+    let names: HashSet<&str> = current
+        .split(';')
+        .filter_map(|pair| pair.split_once('=').map(|(name, _)| name.trim()))
+        .collect();
+    let pairs = stored
+        .iter()
+        .filter(|(name, _)| !names.contains(name.as_str()))
+        .map(|(name, value)| format!("{name}={value}"));
+    let cookies = std::iter::once(current.clone())
+        .filter(|current| !current.is_empty())
+        .chain(pairs)
+        .collect::<Vec<_>>()
+        .join("; ");
+    if cookies != current
+        && let Ok(value) = HeaderValue::try_from(cookies)
+    {
+        request.headers_mut().insert(COOKIE, value);
+    }
+}
+
+impl<S> Service<Request<Bytes>> for CookieJar<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Bytes>) -> Self::Future {
+        let url = request.url().clone();
+        add_cookies(&mut request, &self.store.cookies(&url));
+
+        let mut inner = self.inner.clone();
+        let store = Arc::clone(&self.store);
+        #[cfg(feature = "middleware-cookie-persistence")]
+        let file = self.file.clone();
+        Box::pin(async move {
+            let response = inner.call(request).await?;
+            store.store(&url, response.headers());
+            #[cfg(feature = "middleware-cookie-persistence")]
+            if let Some(file) = file
+                && response.headers().contains_key(SET_COOKIE)
+                && let Err(err) = save(Arc::clone(&file), store).await
+            {
+                tracing::warn!(error = %err, path = %file.path().display(), "failed to save cookies");
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Save the cookies of `store` to `file` on the blocking thread pool of the
+/// Tokio runtime, not to block the executor with the file I/O, or in place
+/// outside of a Tokio runtime.
+#[cfg(feature = "middleware-cookie-persistence")]
+async fn save(file: Arc<CookieFile>, store: Arc<CookieStore>) -> std::io::Result<()> {
+    match tokio::runtime::Handle::try_current() {
+        Ok(runtime) => runtime
+            .spawn_blocking(move || file.save(&store))
+            .await
+            .unwrap_or_else(|err| Err(std::io::Error::other(err))),
+        Err(_) => file.save(&store),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderMap;
+    use http::header::SET_COOKIE;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::Method;
+
+    #[tokio::test]
+    async fn replays_cookies_set_by_responses() {
+        let service = tower::service_fn(|request: Request<Bytes>| async move {
+            let mut headers = HeaderMap::new();
+            if request.url().path() == "/login" {
+                headers.append(SET_COOKIE, "session=abc; Path=/".parse().expect("value"));
+                headers.append(SET_COOKIE, "theme=dark; Path=/".parse().expect("value"));
+            }
+            let body = Bytes::from(request.header("cookie").unwrap_or_default().to_string());
+            Ok::<_, Error>(Response::new(200, headers, body))
+        });
+        let layer = CookieJarLayer::new();
+        let service = layer.layer(service);
+
+        let login = Request::builder(
+            Method::Post,
+            "https://api.example.com/login".parse().expect("url"),
+        )
+        .build();
+        let response = service.clone().oneshot(login).await.expect("response");
+        assert_eq!(response.body().as_ref(), b"");
+        assert_eq!(layer.store().len(), 2);
+
+        let orders = Request::builder(
+            Method::Get,
+            "https://api.example.com/orders".parse().expect("url"),
+        )
+        .cookie("theme", "light")
+        .build();
+        let response = service.oneshot(orders).await.expect("response");
+        assert_eq!(response.body().as_ref(), b"theme=light; session=abc");
+    }
+}
@@ -4,17 +4,33 @@
 //! compressed with gzip, deflate, br (brotli), or zstd.
 //!
 //! It adds the `Accept-Encoding` header to requests and decompresses responses
-//! based on their `Content-Encoding` header.
+//! based on their `Content-Encoding` header. A compressed body shorter than
+//! its `Content-Length` fails with [`Error::TruncatedBody`] instead of being
+//! decompressed into a silently short body.
+//!
+//! With the `middleware-metrics` feature, the compressed and decompressed
+//! sizes are recorded by the following counters, labeled by `encoding`:
+//! - `http_client_decompression_compressed_bytes_total`
+//! - `http_client_decompression_decompressed_bytes_total`
 
 use std::future::Future;
-use std::io::Read;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use bytes::Bytes;
+use http::HeaderMap;
+use http::header::{ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, HeaderValue};
 use tower::{Layer, Service};
 
-use crate::{Error, Request, Response, Result};
+use pincer_core::encoding;
+
+use crate::{DiscardBody, Error, Request, Response, Result, TruncatedBody};
+
+/// Metric names.
+#[cfg(feature = "middleware-metrics")]
+const METRIC_COMPRESSED_BYTES: &str = "http_client_decompression_compressed_bytes_total";
+#[cfg(feature = "middleware-metrics")]
+const METRIC_DECOMPRESSED_BYTES: &str = "http_client_decompression_decompressed_bytes_total";
 
 /// Layer that enables automatic response decompression.
 ///
@@ -63,44 +79,36 @@ impl<S> Decompression<S> {
     }
 }
 
+/// Check that `body` is as long as the `Content-Length` of `headers`, if any.
+fn check_length(headers: &HeaderMap, body: &Bytes) -> Result<()> {
+    let expected = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    let actual = body.len() as u64;
+    match expected {
+        Some(expected) if actual < expected => Err(Error::TruncatedBody { expected, actual }),
+        _ => Ok(()),
+    }
+}
+
+/// Record the compressed and decompressed sizes of a body.
+#[cfg(feature = "middleware-metrics")]
+fn record_sizes(encoding: &str, compressed: usize, decompressed: usize) {
+    let labels = [("encoding", encoding.trim().to_ascii_lowercase())];
+    metrics::counter!(METRIC_COMPRESSED_BYTES, &labels).increment(compressed as u64);
+    metrics::counter!(METRIC_DECOMPRESSED_BYTES, &labels).increment(decompressed as u64);
+}
+
 /// Decompress bytes based on encoding.
+///
+/// Codecs are shared with [`Response::body_decoded`]. Unknown encodings are
+/// returned as-is.
 fn decompress(encoding: &str, body: Bytes) -> Result<Bytes> {
-    let result = match encoding {
-        "gzip" | "x-gzip" => {
-            let mut decoder = flate2::read::GzDecoder::new(body.as_ref());
-            let mut decompressed = Vec::new();
-            decoder
-                .read_to_end(&mut decompressed)
-                .map_err(|e| Error::InvalidRequest(format!("gzip decompression failed: {e}")))?;
-            Bytes::from(decompressed)
-        }
-        "deflate" => {
-            let mut decoder = flate2::read::DeflateDecoder::new(body.as_ref());
-            let mut decompressed = Vec::new();
-            decoder
-                .read_to_end(&mut decompressed)
-                .map_err(|e| Error::InvalidRequest(format!("deflate decompression failed: {e}")))?;
-            Bytes::from(decompressed)
-        }
-        "br" => {
-            let mut decompressed = Vec::new();
-            brotli::BrotliDecompress(&mut body.as_ref(), &mut decompressed)
-                .map_err(|e| Error::InvalidRequest(format!("brotli decompression failed: {e}")))?;
-            Bytes::from(decompressed)
-        }
-        "zstd" => {
-            let decompressed = zstd::decode_all(body.as_ref())
-                .map_err(|e| Error::InvalidRequest(format!("zstd decompression failed: {e}")))?;
-            Bytes::from(decompressed)
-        }
-        "identity" | "" => body,
-        _ => {
-            // Unknown encoding, return as-is
-            body
-        }
-    };
-
-    Ok(result)
+    if !encoding::is_supported(encoding) {
+        return Ok(body);
+    }
+    encoding::decode(encoding, body)
 }
 
 impl<S> Service<Request<Bytes>> for Decompression<S>
@@ -118,37 +126,48 @@ where
 
     fn call(&mut self, mut request: Request<Bytes>) -> Self::Future {
         // Add Accept-Encoding header if not present
-        if !request.headers().contains_key("accept-encoding") {
+        if !request.headers().contains_key(ACCEPT_ENCODING) {
             request.headers_mut().insert(
-                "accept-encoding".to_string(),
-                "gzip, deflate, br, zstd".to_string(),
+                ACCEPT_ENCODING,
+                HeaderValue::from_static("gzip, deflate, br, zstd"),
             );
         }
 
         let mut inner = self.inner.clone();
+        let discard = request.extensions().get::<DiscardBody>().is_some();
 
         Box::pin(async move {
             let response = inner.call(request).await?;
 
             // Check for Content-Encoding header
             let encoding = response
-                .headers()
-                .get("content-encoding")
-                .cloned()
-                .unwrap_or_default();
+                .header("content-encoding")
+                .unwrap_or_default()
+                .to_string();
 
             if encoding.is_empty() || encoding == "identity" {
                 return Ok(response);
             }
+            // Nothing to decompress in a discarded body, nor in a truncated one
+            if discard && response.is_success()
+                || response.extensions().get::<TruncatedBody>().is_some()
+            {
+                return Ok(response);
+            }
 
             // Decompress the body
             let (status, mut headers, body) = response.into_parts();
+            check_length(&headers, &body)?;
+            #[cfg(feature = "middleware-metrics")]
+            let compressed = body.len();
             let decompressed = decompress(&encoding, body)?;
+            #[cfg(feature = "middleware-metrics")]
+            record_sizes(&encoding, compressed, decompressed.len());
 
             // Remove Content-Encoding header since we've decompressed
-            headers.remove("content-encoding");
+            headers.remove(CONTENT_ENCODING);
             // Update Content-Length to reflect decompressed size
-            headers.insert("content-length".to_string(), decompressed.len().to_string());
+            headers.insert(CONTENT_LENGTH, HeaderValue::from(decompressed.len()));
 
             Ok(Response::new(status, headers, decompressed))
         })
@@ -191,6 +210,19 @@ mod tests {
         assert_eq!(result, body);
     }
 
+    #[test]
+    fn rejects_bodies_shorter_than_content_length() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(11));
+        check_length(&headers, &Bytes::from("hello world")).expect("complete body");
+
+        let err = check_length(&headers, &Bytes::from("hello")).expect_err("truncated");
+        assert!(err.is_truncated_body());
+        assert_eq!(err.to_string(), "truncated body: expected 11 bytes, got 5");
+
+        check_length(&HeaderMap::new(), &Bytes::from("hello")).expect("no length");
+    }
+
     #[test]
     fn decompress_gzip() {
         use flate2::Compression;
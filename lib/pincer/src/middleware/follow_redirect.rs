@@ -158,13 +158,9 @@ where
                 }
 
                 // Extract Location header
-                let location = response
-                    .headers()
-                    .get("location")
-                    .or_else(|| response.headers().get("Location"))
-                    .ok_or_else(|| {
-                        Error::InvalidRedirect("redirect response missing Location header".into())
-                    })?;
+                let location = response.header("location").ok_or_else(|| {
+                    Error::InvalidRedirect("redirect response missing Location header".into())
+                })?;
 
                 // Resolve the redirect URL
                 let current_url = current_request.url();
@@ -0,0 +1,152 @@
+//! Load shedding middleware.
+//!
+//! Rejects requests immediately with [`Error::Overloaded`] when the inner
+//! service is not ready (for example a concurrency or rate limit is reached),
+//! instead of waiting for capacity. This is tower's `LoadShed` with pincer's
+//! error type.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use tower::{Layer, Service};
+
+use crate::{Error, Request, Response, Result};
+
+/// Layer that sheds requests while the inner service is not ready.
+///
+/// Place it outside the limiting layer, i.e. add it after the limit:
+///
+/// ```ignore
+/// use pincer::HyperClient;
+///
+/// let client = HyperClient::builder()
+///     .with_concurrency_limit(10)
+///     .with_load_shed()
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoadShedLayer;
+
+impl LoadShedLayer {
+    /// Create a new load shedding layer.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for LoadShedLayer {
+    type Service = LoadShed<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        LoadShed {
+            inner,
+            is_ready: false,
+        }
+    }
+}
+
+/// Service that sheds requests while the inner service is not ready.
+///
+/// Always reports ready; a request is only forwarded if the inner service was
+/// ready when polled, and fails with [`Error::Overloaded`] otherwise.
+#[derive(Debug)]
+pub struct LoadShed<S> {
+    inner: S,
+    is_ready: bool,
+}
+
+impl<S: Clone> Clone for LoadShed<S> {
+    fn clone(&self) -> Self {
+        // Readiness belongs to the polled instance, not to its clones
+        Self {
+            inner: self.inner.clone(),
+            is_ready: false,
+        }
+    }
+}
+
+impl<S> Service<Request<Bytes>> for LoadShed<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.is_ready = match self.inner.poll_ready(cx) {
+            Poll::Ready(Ok(())) => true,
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => false,
+        };
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        if std::mem::take(&mut self.is_ready) {
+            Box::pin(self.inner.call(request))
+        } else {
+            Box::pin(std::future::ready(Err(Error::Overloaded)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderMap;
+    use tower::ServiceExt;
+    use tower::limit::ConcurrencyLimitLayer;
+
+    use super::*;
+    use crate::Method;
+
+    fn request() -> Request<Bytes> {
+        Request::builder(
+            Method::Get,
+            "https://api.example.com/".parse().expect("url"),
+        )
+        .build()
+    }
+
+    #[tokio::test]
+    async fn forwards_when_ready() {
+        let service = tower::service_fn(|_request: Request<Bytes>| async {
+            Ok::<_, Error>(Response::new(200, HeaderMap::new(), Bytes::new()))
+        });
+        let service = LoadShedLayer::new().layer(service);
+
+        let response = service.oneshot(request()).await.expect("response");
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn sheds_when_not_ready() {
+        let (release, released) = tokio::sync::oneshot::channel::<()>();
+        let released = std::sync::Arc::new(tokio::sync::Mutex::new(Some(released)));
+        let service = tower::service_fn(move |_request: Request<Bytes>| {
+            let released = std::sync::Arc::clone(&released);
+            async move {
+                if let Some(released) = released.lock().await.take() {
+                    let _ = released.await;
+                }
+                Ok::<_, Error>(Response::new(200, HeaderMap::new(), Bytes::new()))
+            }
+        });
+        let service = LoadShedLayer::new().layer(ConcurrencyLimitLayer::new(1).layer(service));
+
+        // Hold the only permit
+        let in_flight = tokio::spawn(service.clone().oneshot(request()));
+        tokio::task::yield_now().await;
+
+        let err = service.clone().oneshot(request()).await.expect_err("shed");
+        assert!(err.is_overloaded());
+
+        release.send(()).expect("release");
+        assert!(in_flight.await.expect("task").is_ok());
+        assert!(service.oneshot(request()).await.is_ok());
+    }
+}
@@ -1,17 +1,33 @@
 //! Request/response logging middleware.
 //!
 //! This middleware logs HTTP requests and responses using the `tracing` crate.
+//!
+//! High-traffic clients can enable sampling with [`LoggingLayer::with_sample_rate`]:
+//! only one in every N successful requests is logged, while failures are
+//! always logged.
+//!
+//! URLs are rendered through a [`Redactor`], so sensitive query parameters
+//! such as `?api_key=...` never land in logs.
+//!
+//! [`LoggingLayer::with_slow_threshold`] emits a warning with the request
+//! context whenever a request takes longer than the threshold, whatever the
+//! sampling rate, to catch latency degradations before they become timeouts.
+//!
+//! The request span carries the client name and the tenant of the
+//! [`CallContext`], when set.
 
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::LazyLock;
 use std::task::{Context, Poll};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use bytes::Bytes;
 use tower::{Layer, Service};
 use tracing::{Instrument, Level, debug, info, span, warn};
 
-use crate::{Error, Request, Response, Result};
+use super::sampling::Sampler;
+use crate::{CallContext, ClientName, Error, PathTemplate, Redactor, Request, Response, Result};
 
 /// Layer that adds request/response logging.
 ///
@@ -25,9 +41,29 @@ use crate::{Error, Request, Response, Result};
 ///     .layer(LoggingLayer::new())
 ///     .service(client);
 /// ```
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy)]
 pub struct LoggingLayer {
     level: LogLevel,
+    sample_rate: u32,
+    redactor: &'static Redactor,
+    slow_threshold: Option<Duration>,
+}
+
+/// Redactor of the layers without a custom one.
+fn default_redactor() -> &'static Redactor {
+    static DEFAULT: LazyLock<Redactor> = LazyLock::new(Redactor::default);
+    &DEFAULT
+}
+
+impl Default for LoggingLayer {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::default(),
+            sample_rate: 0,
+            redactor: default_redactor(),
+            slow_threshold: None,
+        }
+    }
 }
 
 /// Log level for the logging middleware.
@@ -52,8 +88,70 @@ impl LoggingLayer {
     pub fn debug() -> Self {
         Self {
             level: LogLevel::Debug,
+            ..Self::default()
         }
     }
+
+    /// Log only one in every `rate` successful requests.
+    ///
+    /// Requests that fail (error or non-2xx response) are always logged.
+    /// A rate of `0` or `1` logs every request.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use pincer::middleware::LoggingLayer;
+    ///
+    /// // Log 1% of successful requests
+    /// let layer = LoggingLayer::new().with_sample_rate(100);
+    /// ```
+    #[must_use]
+    pub const fn with_sample_rate(mut self, rate: u32) -> Self {
+        self.sample_rate = rate;
+        self
+    }
+
+    /// Set the redactor used when rendering URLs.
+    ///
+    /// Defaults to [`Redactor::default()`]. The redactor is borrowed for the
+    /// life of the program, e.g. from a static, so the layer stays `Copy`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use std::sync::LazyLock;
+    /// use pincer::{Redactor, middleware::LoggingLayer};
+    ///
+    /// static REDACTOR: LazyLock<Redactor> =
+    ///     LazyLock::new(|| Redactor::default().with_pattern("sid"));
+    ///
+    /// let layer = LoggingLayer::new().with_redactor(&REDACTOR);
+    /// ```
+    #[must_use]
+    pub const fn with_redactor(mut self, redactor: &'static Redactor) -> Self {
+        self.redactor = redactor;
+        self
+    }
+
+    /// Warn about requests taking longer than `threshold`.
+    ///
+    /// The warning carries the method, URL, path template, headers (with
+    /// sensitive values redacted) and outcome of the request. It is emitted
+    /// for every slow request, even when sampling skips it.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use std::time::Duration;
+    /// use pincer::middleware::LoggingLayer;
+    ///
+    /// let layer = LoggingLayer::new().with_slow_threshold(Duration::from_secs(2));
+    /// ```
+    #[must_use]
+    pub const fn with_slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = Some(threshold);
+        self
+    }
 }
 
 impl<S> Layer<S> for LoggingLayer {
@@ -63,6 +161,9 @@ impl<S> Layer<S> for LoggingLayer {
         Logging {
             inner,
             level: self.level,
+            sampler: Sampler::new(self.sample_rate),
+            redactor: self.redactor,
+            slow_threshold: self.slow_threshold,
         }
     }
 }
@@ -72,6 +173,9 @@ impl<S> Layer<S> for LoggingLayer {
 pub struct Logging<S> {
     inner: S,
     level: LogLevel,
+    sampler: Sampler,
+    redactor: &'static Redactor,
+    slow_threshold: Option<Duration>,
 }
 
 impl<S> Logging<S> {
@@ -80,6 +184,9 @@ impl<S> Logging<S> {
         Self {
             inner,
             level: LogLevel::Info,
+            sampler: Sampler::new(1),
+            redactor: default_redactor(),
+            slow_threshold: None,
         }
     }
 }
@@ -99,27 +206,41 @@ where
 
     fn call(&mut self, request: Request<Bytes>) -> Self::Future {
         let method = request.method();
-        let url = request.url().to_string();
+        let url = self.redactor.redact_url(request.url()).into_owned();
         let level = self.level;
+        let sampled = self.sampler.sample();
+        let slow_threshold = self.slow_threshold;
+        // Request context is captured only when it may be reported
+        let slow_context = slow_threshold.map(|_| SlowContext::new(&request, self.redactor));
 
-        let span = span!(Level::INFO, "http_request", %method, %url);
+        let client = request
+            .extensions()
+            .get::<ClientName>()
+            .map(ClientName::as_str);
+        let tenant = request
+            .extensions()
+            .get::<CallContext>()
+            .and_then(CallContext::tenant_id);
+        let span = span!(Level::INFO, "http_request", %method, %url, client, tenant);
 
         let mut inner = self.inner.clone();
         Box::pin(
             async move {
                 let start = Instant::now();
 
-                match level {
-                    LogLevel::Debug => {
-                        debug!(
-                            method = %method,
-                            url = %url,
-                            headers = ?request.headers(),
-                            "sending request"
-                        );
-                    }
-                    LogLevel::Info => {
-                        info!(method = %method, url = %url, "sending request");
+                if sampled {
+                    match level {
+                        LogLevel::Debug => {
+                            debug!(
+                                method = %method,
+                                url = %url,
+                                headers = ?request.headers(),
+                                "sending request"
+                            );
+                        }
+                        LogLevel::Info => {
+                            info!(method = %method, url = %url, "sending request");
+                        }
                     }
                 }
 
@@ -133,7 +254,9 @@ where
                     Ok(response) => {
                         let status = response.status();
                         if response.is_success() {
-                            info!(status, elapsed_ms, "request completed");
+                            if sampled {
+                                info!(status, elapsed_ms, "request completed");
+                            }
                         } else {
                             warn!(status, elapsed_ms, "request failed with HTTP error");
                         }
@@ -143,6 +266,12 @@ where
                     }
                 }
 
+                if let (Some(threshold), Some(context)) = (slow_threshold, slow_context)
+                    && elapsed > threshold
+                {
+                    context.warn(&result, elapsed_ms, threshold);
+                }
+
                 result
             }
             .instrument(span),
@@ -150,6 +279,63 @@ where
     }
 }
 
+/// Request context reported for slow requests.
+struct SlowContext {
+    path_template: Option<&'static str>,
+    headers: Vec<(String, String)>,
+}
+
+impl SlowContext {
+    fn new(request: &Request<Bytes>, redactor: &Redactor) -> Self {
+        let mut headers: Vec<(String, String)> = request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = if redactor.is_sensitive(name.as_str()) {
+                    pincer_core::REDACTED.to_string()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+                (name.to_string(), value)
+            })
+            .collect();
+        headers.sort();
+
+        Self {
+            path_template: request
+                .extensions()
+                .get::<PathTemplate>()
+                .map(PathTemplate::as_str),
+            headers,
+        }
+    }
+
+    fn warn(&self, result: &Result<Response<Bytes>>, elapsed_ms: u64, threshold: Duration) {
+        let threshold_ms = u64::try_from(threshold.as_millis()).unwrap_or(u64::MAX);
+        let path_template = self.path_template.unwrap_or_default();
+        let headers = &self.headers;
+
+        match result {
+            Ok(response) => warn!(
+                status = response.status(),
+                elapsed_ms,
+                threshold_ms,
+                path_template,
+                ?headers,
+                "slow request"
+            ),
+            Err(err) => warn!(
+                error = %err,
+                elapsed_ms,
+                threshold_ms,
+                path_template,
+                ?headers,
+                "slow request"
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,4 +351,147 @@ mod tests {
         let layer = LoggingLayer::debug();
         assert!(matches!(layer.level, LogLevel::Debug));
     }
+
+    #[test]
+    fn logging_layer_sample_rate() {
+        let layer = LoggingLayer::debug().with_sample_rate(10);
+        assert!(matches!(layer.level, LogLevel::Debug));
+        assert_eq!(layer.sample_rate, 10);
+    }
+
+    #[test]
+    fn logging_layer_is_copy() {
+        fn assert_copy<T: Copy>() {}
+        assert_copy::<LoggingLayer>();
+    }
+
+    #[test]
+    fn logging_layer_redactor() {
+        static NONE: Redactor = Redactor::none();
+
+        let layer = LoggingLayer::new();
+        assert_eq!(*layer.redactor, Redactor::default());
+
+        let layer = layer.with_redactor(&NONE);
+        assert!(layer.redactor.patterns().is_empty());
+    }
+
+    #[test]
+    fn logging_layer_slow_threshold() {
+        let layer = LoggingLayer::new();
+        assert!(layer.slow_threshold.is_none());
+
+        let layer = layer.with_slow_threshold(Duration::from_secs(2));
+        assert_eq!(layer.slow_threshold, Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn slow_context_redacts_headers() {
+        let request = Request::builder(
+            crate::Method::Get,
+            url::Url::parse("https://api.example.com/users/1").expect("url"),
+        )
+        .header("Authorization", "Bearer secret")
+        .header("Accept", "application/json")
+        .extension(PathTemplate::new("/users/{id}"))
+        .build();
+
+        let context = SlowContext::new(&request, &Redactor::default());
+
+        assert_eq!(context.path_template, Some("/users/{id}"));
+        assert_eq!(
+            context.headers,
+            [
+                ("accept".to_string(), "application/json".to_string()),
+                ("authorization".to_string(), "REDACTED".to_string()),
+            ]
+        );
+    }
+
+    /// Subscriber counting the events of each message.
+    #[derive(Default)]
+    struct EventCounter(std::sync::Mutex<std::collections::HashMap<String, usize>>);
+
+    impl EventCounter {
+        fn count(&self, message: &str) -> usize {
+            let counts = self
+                .0
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            counts.get(message).copied().unwrap_or_default()
+        }
+    }
+
+    impl tracing::Subscriber for EventCounter {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            struct Message(String);
+            impl tracing::field::Visit for Message {
+                fn record_debug(
+                    &mut self,
+                    field: &tracing::field::Field,
+                    value: &dyn std::fmt::Debug,
+                ) {
+                    if field.name() == "message" {
+                        self.0 = format!("{value:?}");
+                    }
+                }
+            }
+
+            let mut message = Message(String::new());
+            event.record(&mut message);
+            let mut counts = self
+                .0
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            *counts.entry(message.0).or_default() += 1;
+        }
+
+        fn enter(&self, _span: &tracing::span::Id) {}
+
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    #[tokio::test]
+    async fn logging_service_samples_successes_only() {
+        use tower::ServiceExt;
+
+        let counter = std::sync::Arc::new(EventCounter::default());
+        let _guard = tracing::subscriber::set_default(std::sync::Arc::clone(&counter));
+
+        let calls = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+        let inner = tower::service_fn(move |_request: Request<Bytes>| {
+            // Every fourth request fails
+            let call = calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let status = if call % 4 == 3 { 500 } else { 200 };
+            async move { Ok::<_, Error>(Response::new(status, http::HeaderMap::new(), Bytes::new())) }
+        });
+        let service = LoggingLayer::new().with_sample_rate(3).layer(inner);
+
+        for _ in 0..8 {
+            let request = Request::builder(
+                crate::Method::Get,
+                url::Url::parse("https://api.example.com/users").expect("url"),
+            )
+            .build();
+            let _ = service.clone().oneshot(request).await.expect("response");
+        }
+
+        // Requests 1, 4 and 7 are sampled; request 4 fails
+        assert_eq!(counter.count("sending request"), 3);
+        assert_eq!(counter.count("request completed"), 2);
+        // Failures are always logged: requests 4 and 8
+        assert_eq!(counter.count("request failed with HTTP error"), 2);
+    }
 }
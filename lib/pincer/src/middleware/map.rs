@@ -0,0 +1,226 @@
+//! Request, response and error transformation middleware.
+//!
+//! Applies plain functions to the requests, responses and errors going
+//! through the stack, for the small rewrites that do not deserve a
+//! hand-written `Service` (adding a header, rewriting a URL, tagging errors).
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use tower::{Layer, Service};
+
+use crate::{Error, Request, Response, Result};
+
+type RequestHook = dyn Fn(Request<Bytes>) -> Request<Bytes> + Send + Sync;
+type ResponseHook = dyn Fn(Response<Bytes>) -> Response<Bytes> + Send + Sync;
+type ErrorHook = dyn Fn(Error) -> Error + Send + Sync;
+
+/// Transformation functions of a [`MapLayer`].
+#[derive(Clone, Default)]
+struct Hooks {
+    request: Option<Arc<RequestHook>>,
+    response: Option<Arc<ResponseHook>>,
+    error: Option<Arc<ErrorHook>>,
+}
+
+/// Layer transforming requests, responses and errors with functions.
+///
+/// Each function is optional; the ones not set leave their value unchanged.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::middleware::MapLayer;
+///
+/// let layer = MapLayer::new()
+///     .map_request(|mut request| {
+///         request.headers_mut().insert("x-tenant", "acme".parse().expect("header"));
+///         request
+///     })
+///     .map_error(|err| {
+///         tracing::warn!(%err, "request failed");
+///         err
+///     });
+///
+/// let client = HyperClient::builder().layer(layer).build();
+/// ```
+#[derive(Clone, Default)]
+pub struct MapLayer {
+    hooks: Hooks,
+}
+
+impl fmt::Debug for MapLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MapLayer")
+            .field("map_request", &self.hooks.request.is_some())
+            .field("map_response", &self.hooks.response.is_some())
+            .field("map_error", &self.hooks.error.is_some())
+            .finish()
+    }
+}
+
+impl MapLayer {
+    /// Create a layer leaving everything unchanged.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Transform each request before it is sent.
+    #[must_use]
+    pub fn map_request(
+        mut self,
+        f: impl Fn(Request<Bytes>) -> Request<Bytes> + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks.request = Some(Arc::new(f));
+        self
+    }
+
+    /// Transform each response received.
+    #[must_use]
+    pub fn map_response(
+        mut self,
+        f: impl Fn(Response<Bytes>) -> Response<Bytes> + Send + Sync + 'static,
+    ) -> Self {
+        self.hooks.response = Some(Arc::new(f));
+        self
+    }
+
+    /// Transform each error returned by the inner service.
+    #[must_use]
+    pub fn map_error(mut self, f: impl Fn(Error) -> Error + Send + Sync + 'static) -> Self {
+        self.hooks.error = Some(Arc::new(f));
+        self
+    }
+}
+
+impl<S> Layer<S> for MapLayer {
+    type Service = Map<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Map {
+            inner,
+            hooks: Arc::new(self.hooks.clone()),
+        }
+    }
+}
+
+/// Service transforming requests, responses and errors with functions.
+#[derive(Clone)]
+pub struct Map<S> {
+    inner: S,
+    hooks: Arc<Hooks>,
+}
+
+impl<S: fmt::Debug> fmt::Debug for Map<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Map")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S> Service<Request<Bytes>> for Map<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let request = match &self.hooks.request {
+            Some(map_request) => map_request(request),
+            None => request,
+        };
+        let response = self.inner.call(request);
+        let hooks = Arc::clone(&self.hooks);
+        Box::pin(async move {
+            match response.await {
+                Ok(response) => Ok(match &hooks.response {
+                    Some(map_response) => map_response(response),
+                    None => response,
+                }),
+                Err(err) => Err(match &hooks.error {
+                    Some(map_error) => map_error(err),
+                    None => err,
+                }),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderMap;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::Method;
+
+    fn request() -> Request<Bytes> {
+        Request::builder(
+            Method::Get,
+            "https://api.example.com/".parse().expect("url"),
+        )
+        .build()
+    }
+
+    #[tokio::test]
+    async fn maps_requests_and_responses() {
+        let service = tower::service_fn(|request: Request<Bytes>| async move {
+            let status = if request.header("x-tenant") == Some("acme") {
+                200
+            } else {
+                400
+            };
+            Ok::<_, Error>(Response::new(status, HeaderMap::new(), Bytes::new()))
+        });
+        let layer = MapLayer::new()
+            .map_request(|mut request| {
+                request
+                    .headers_mut()
+                    .insert("x-tenant", "acme".parse().expect("value"));
+                request
+            })
+            .map_response(|response| {
+                let (status, headers, _) = response.into_parts();
+                Response::new(status, headers, Bytes::from_static(b"mapped"))
+            });
+
+        let response = layer
+            .layer(service)
+            .oneshot(request())
+            .await
+            .expect("response");
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.body().as_ref(), b"mapped");
+    }
+
+    #[tokio::test]
+    async fn maps_errors() {
+        let service = tower::service_fn(|_request: Request<Bytes>| async {
+            Err::<Response<Bytes>, _>(Error::Timeout)
+        });
+        let layer = MapLayer::new().map_error(|err| Error::connection(format!("upstream: {err}")));
+
+        let err = layer
+            .layer(service)
+            .oneshot(request())
+            .await
+            .expect_err("error");
+        assert_eq!(
+            err.to_string(),
+            "connection error: upstream: request timeout"
+        );
+    }
+}
@@ -2,6 +2,10 @@
 //!
 //! This middleware records HTTP request/response metrics using the `metrics` crate,
 //! which allows integration with various metrics backends (Prometheus, `StatsD`, etc.).
+//!
+//! Counters and gauges are always recorded. The duration histogram can be
+//! sampled with [`MetricsLayer::with_histogram_sample_rate`] to reduce
+//! backend load on high-traffic clients.
 
 use std::future::Future;
 use std::pin::Pin;
@@ -11,11 +15,15 @@ use std::time::Instant;
 use bytes::Bytes;
 use tower::{Layer, Service};
 
-use crate::{Error, Request, Response, Result};
+use super::sampling::Sampler;
+use metrics::Label;
+
+use crate::{ClientName, Error, Request, Response, Result};
 
 /// Labels used for metrics.
 const LABEL_METHOD: &str = "method";
 const LABEL_STATUS: &str = "status";
+const LABEL_CLIENT: &str = "client";
 
 /// Metric names.
 const METRIC_REQUESTS_TOTAL: &str = "http_client_requests_total";
@@ -29,6 +37,9 @@ const METRIC_REQUESTS_IN_FLIGHT: &str = "http_client_requests_in_flight";
 /// - `http_client_request_duration_seconds` (histogram): Request duration in seconds
 /// - `http_client_requests_in_flight` (gauge): Number of requests currently in flight
 ///
+/// Requests sent by a named client (`#[pincer(name = "...")]`) also carry a
+/// `client` label.
+///
 /// # Example
 ///
 /// ```ignore
@@ -38,14 +49,24 @@ const METRIC_REQUESTS_IN_FLIGHT: &str = "http_client_requests_in_flight";
 /// ```
 #[derive(Debug, Clone, Copy, Default)]
 pub struct MetricsLayer {
-    _private: (),
+    histogram_sample_rate: u32,
 }
 
 impl MetricsLayer {
     /// Create a new metrics layer.
     #[must_use]
     pub fn new() -> Self {
-        Self { _private: () }
+        Self::default()
+    }
+
+    /// Record the duration histogram for only one in every `rate` requests.
+    ///
+    /// Request counters and the in-flight gauge are not affected.
+    /// A rate of `0` or `1` records every request.
+    #[must_use]
+    pub const fn with_histogram_sample_rate(mut self, rate: u32) -> Self {
+        self.histogram_sample_rate = rate;
+        self
     }
 }
 
@@ -53,7 +74,10 @@ impl<S> Layer<S> for MetricsLayer {
     type Service = Metrics<S>;
 
     fn layer(&self, inner: S) -> Self::Service {
-        Metrics { inner }
+        Metrics {
+            inner,
+            histogram_sampler: Sampler::new(self.histogram_sample_rate),
+        }
     }
 }
 
@@ -61,12 +85,16 @@ impl<S> Layer<S> for MetricsLayer {
 #[derive(Debug, Clone)]
 pub struct Metrics<S> {
     inner: S,
+    histogram_sampler: Sampler,
 }
 
 impl<S> Metrics<S> {
     /// Create a new metrics service wrapping the given service.
     pub fn new(inner: S) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            histogram_sampler: Sampler::new(1),
+        }
     }
 }
 
@@ -85,22 +113,32 @@ where
 
     fn call(&mut self, request: Request<Bytes>) -> Self::Future {
         let method = request.method().to_string();
+        let client: Vec<Label> = request
+            .extensions()
+            .get::<ClientName>()
+            .map(|client| Label::new(LABEL_CLIENT, client.as_str()))
+            .into_iter()
+            .collect();
         let start = Instant::now();
+        let record_duration = self.histogram_sampler.sample();
         let mut inner = self.inner.clone();
 
         // Increment in-flight gauge
-        metrics::gauge!(METRIC_REQUESTS_IN_FLIGHT).increment(1.0);
+        metrics::gauge!(METRIC_REQUESTS_IN_FLIGHT, client.iter()).increment(1.0);
 
         Box::pin(async move {
             let result = inner.call(request).await;
 
             // Decrement in-flight gauge
-            metrics::gauge!(METRIC_REQUESTS_IN_FLIGHT).decrement(1.0);
+            metrics::gauge!(METRIC_REQUESTS_IN_FLIGHT, client.iter()).decrement(1.0);
 
             // Record duration
-            let duration = start.elapsed().as_secs_f64();
-            metrics::histogram!(METRIC_REQUEST_DURATION, LABEL_METHOD => method.clone())
-                .record(duration);
+            if record_duration {
+                let duration = start.elapsed().as_secs_f64();
+                let mut labels = client.clone();
+                labels.push(Label::new(LABEL_METHOD, method.clone()));
+                metrics::histogram!(METRIC_REQUEST_DURATION, labels).record(duration);
+            }
 
             // Record request count with status
             let status = match &result {
@@ -108,12 +146,10 @@ where
                 Err(_) => "error".to_string(),
             };
 
-            metrics::counter!(
-                METRIC_REQUESTS_TOTAL,
-                LABEL_METHOD => method,
-                LABEL_STATUS => status
-            )
-            .increment(1);
+            let mut labels = client;
+            labels.push(Label::new(LABEL_METHOD, method));
+            labels.push(Label::new(LABEL_STATUS, status));
+            metrics::counter!(METRIC_REQUESTS_TOTAL, labels).increment(1);
 
             result
         })
@@ -122,7 +158,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use http::HeaderMap;
     use std::sync::Arc;
     use std::sync::atomic::{AtomicU32, Ordering};
 
@@ -179,7 +215,7 @@ mod tests {
                 if should_error {
                     Err(Error::connection("mock error"))
                 } else {
-                    Ok(Response::new(status, HashMap::new(), Bytes::new()))
+                    Ok(Response::new(status, HeaderMap::new(), Bytes::new()))
                 }
             })
         }
@@ -260,6 +296,90 @@ mod tests {
         assert_eq!(mock.call_count(), 5);
     }
 
+    /// Recorder counting the recorded histogram values.
+    #[derive(Default)]
+    struct HistogramCounter(Arc<AtomicU32>);
+
+    impl metrics::HistogramFn for HistogramCounter {
+        fn record(&self, _value: f64) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    impl metrics::Recorder for HistogramCounter {
+        fn describe_counter(
+            &self,
+            _key: metrics::KeyName,
+            _unit: Option<metrics::Unit>,
+            _description: metrics::SharedString,
+        ) {
+        }
+
+        fn describe_gauge(
+            &self,
+            _key: metrics::KeyName,
+            _unit: Option<metrics::Unit>,
+            _description: metrics::SharedString,
+        ) {
+        }
+
+        fn describe_histogram(
+            &self,
+            _key: metrics::KeyName,
+            _unit: Option<metrics::Unit>,
+            _description: metrics::SharedString,
+        ) {
+        }
+
+        fn register_counter(
+            &self,
+            _key: &metrics::Key,
+            _metadata: &metrics::Metadata<'_>,
+        ) -> metrics::Counter {
+            metrics::Counter::noop()
+        }
+
+        fn register_gauge(
+            &self,
+            _key: &metrics::Key,
+            _metadata: &metrics::Metadata<'_>,
+        ) -> metrics::Gauge {
+            metrics::Gauge::noop()
+        }
+
+        fn register_histogram(
+            &self,
+            key: &metrics::Key,
+            _metadata: &metrics::Metadata<'_>,
+        ) -> metrics::Histogram {
+            if key.name() == METRIC_REQUEST_DURATION {
+                metrics::Histogram::from_arc(Arc::new(Self(Arc::clone(&self.0))))
+            } else {
+                metrics::Histogram::noop()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn metrics_service_histogram_sampling() {
+        let recorder = HistogramCounter::default();
+        let _guard = metrics::set_default_local_recorder(&recorder);
+
+        let mock = MockService::new(200);
+        let layer = MetricsLayer::new().with_histogram_sample_rate(3);
+        let mut service = layer.layer(mock.clone());
+
+        for _ in 0..7 {
+            let request = create_request();
+            let result = service.ready().await.expect("ready").call(request).await;
+            assert!(result.is_ok());
+        }
+
+        assert_eq!(mock.call_count(), 7);
+        // Requests 1, 4 and 7 record their duration
+        assert_eq!(recorder.0.load(Ordering::SeqCst), 3);
+    }
+
     #[test]
     fn metrics_new() {
         let inner = MockService::new(200);
@@ -14,12 +14,21 @@
 //! | `middleware-logging` | `.with_logging()` helper |
 //! | `middleware-bearer-auth` | `.with_bearer_auth()` helper |
 //! | `middleware-basic-auth` | `.with_basic_auth()` helper |
+//! | `middleware-token-auth` | `.with_token_auth()` helper |
+//! | `middleware-auth-refresh` | `.with_auth_refresh()` helper |
+//! | `middleware-jws` | `.with_jws_signing()` / `.with_jwt_assertion()` helpers |
 //! | `middleware-concurrency` | `.with_concurrency_limit()` helper |
+//! | `middleware-cache` | `.with_cache()` helper |
+//! | `middleware-cookies` | `.with_cookie_jar()` / `.with_cookie_store()` helpers |
+//! | `middleware-cookie-persistence` | `CookieFile` encrypted cookie persistence |
+//! | `middleware-load-shed` | `.with_load_shed()` helper |
+//! | `middleware-buffer` | `.with_buffer()` helper |
 //! | `middleware-rate-limit` | `.with_rate_limit()` helper |
 //! | `middleware-circuit-breaker` | `.with_circuit_breaker()` helper |
 //! | `middleware-metrics` | `.with_metrics()` helper |
+//! | `middleware-access-log` | `.with_access_log()` helper |
 //! | `middleware-core` | Core middleware bundle |
-//! | `middleware-resilience` | Rate limit + circuit breaker |
+//! | `middleware-resilience` | Rate limit, circuit breaker, load shed and buffer |
 //! | `middleware-full` | All middleware |
 //!
 //! # Available Layers
@@ -28,11 +37,26 @@
 //!
 //! - [`BearerAuthLayer`] - Adds `Authorization: Bearer <token>` header
 //! - [`BasicAuthLayer`] - Adds `Authorization: Basic <base64>` header
+//! - [`TokenAuthLayer`] - Adds an access token cached in a shared [`TokenCache`]
+//! - [`AuthRefreshLayer`] - Refreshes the credential on `401` and retries once
+//! - [`JwsLayer`] - Signs request bodies as JWS (detached or attached)
+//! - [`JwtLayer`] - Attaches a freshly minted JWT assertion
+//! - [`CacheLayer`] - Caches fresh `GET` responses in memory, keyed by a [`CacheKey`]
+//! - [`CookieJarLayer`] - Stores response cookies and replays them on matching requests,
+//!   optionally persisted to an encrypted `CookieFile`
+//! - [`CallContextLayer`] - Propagates the tenant/user [`CallContext`](crate::CallContext) to requests
+//! - [`TaskLocalHeaderLayer`] - Stamps headers read from a tokio task-local on requests
 //! - [`LoggingLayer`] - Logs requests/responses using `tracing`
+//! - [`MapLayer`] - Transforms requests, responses and errors with functions
+//! - [`ConditionalLayer`] - Applies a layer only to requests matching a predicate
+//! - [`RouteLayer`] - Applies a middleware stack per path template pattern
 //! - [`RetryPolicy`] - Configurable retry policy for [`RetryLayer`]
 //! - [`RateLimitLayer`] - Limits request rate using token bucket algorithm
 //! - [`CircuitBreakerLayer`] - Implements circuit breaker pattern for fault tolerance
+//! - [`LoadShedLayer`] - Rejects requests while the inner service is at capacity
+//! - [`BufferLayer`] - Queues requests in front of the inner service
 //! - [`MetricsLayer`] - Records HTTP metrics (counters, histograms)
+//! - [`AccessLogLayer`] - Records one structured access log entry per request
 //!
 //! ## Tower Layers (always available)
 //!
@@ -65,40 +89,94 @@
 //! compatible with pincer's Request/Response types as they work with `http::Request`.
 //! For these features, consider using the raw hyper client or implementing custom adapters.
 
+#[cfg(feature = "middleware-access-log")]
+mod access_log;
+#[cfg(feature = "middleware-auth-refresh")]
+mod auth_refresh;
 #[cfg(feature = "middleware-basic-auth")]
 mod basic_auth;
 mod bearer_auth;
+#[cfg(feature = "middleware-buffer")]
+mod buffer;
+#[cfg(feature = "middleware-cache")]
+mod cache;
+mod call_context;
 #[cfg(feature = "middleware-circuit-breaker")]
 mod circuit_breaker;
+mod conditional;
+#[cfg(feature = "middleware-cookie-persistence")]
+mod cookie_file;
+#[cfg(feature = "middleware-cookies")]
+mod cookie_jar;
 #[cfg(feature = "middleware-decompression")]
 mod decompression;
 #[cfg(feature = "middleware-follow-redirect")]
 mod follow_redirect;
+#[cfg(feature = "middleware-jws")]
+mod jws;
+#[cfg(feature = "middleware-load-shed")]
+mod load_shed;
 mod logging;
+mod map;
 #[cfg(feature = "middleware-metrics")]
 mod metrics;
 #[cfg(feature = "middleware-rate-limit")]
 mod rate_limit;
 mod retry;
+mod route;
+mod sampling;
+mod task_local_header;
+#[cfg(feature = "middleware-token-auth")]
+mod token_auth;
 
 // Custom middleware (always available)
+#[cfg(feature = "middleware-access-log")]
+pub use access_log::{
+    AccessLog, AccessLogEntry, AccessLogLayer, AccessLogSink, DEFAULT_REQUEST_ID_HEADER,
+    JsonFileSink, TracingSink,
+};
+#[cfg(feature = "middleware-auth-refresh")]
+pub use auth_refresh::{AuthRefresh, AuthRefreshLayer, AuthRefreshed};
 #[cfg(feature = "middleware-basic-auth")]
 pub use basic_auth::{BasicAuth, BasicAuthLayer};
 pub use bearer_auth::{BearerAuth, BearerAuthLayer};
+#[cfg(feature = "middleware-buffer")]
+pub use buffer::{Buffer, BufferLayer};
+#[cfg(feature = "middleware-cache")]
+pub use cache::{Cache, CacheKey, CacheLayer, DEFAULT_MAX_CACHE_ENTRIES, DefaultCacheKey};
+pub use call_context::{
+    CallContextLayer, CallContextService, current_call_context, with_call_context,
+};
 #[cfg(feature = "middleware-circuit-breaker")]
 pub use circuit_breaker::{
     CircuitBreaker, CircuitBreakerConfig, CircuitBreakerLayer, CircuitState,
 };
+pub use conditional::{Conditional, ConditionalLayer};
+#[cfg(feature = "middleware-cookie-persistence")]
+pub use cookie_file::CookieFile;
+#[cfg(feature = "middleware-cookies")]
+pub use cookie_jar::{CookieJar, CookieJarLayer};
 #[cfg(feature = "middleware-decompression")]
 pub use decompression::{Decompression, DecompressionLayer};
 #[cfg(feature = "middleware-follow-redirect")]
 pub use follow_redirect::{DEFAULT_MAX_REDIRECTS, FollowRedirect, FollowRedirectLayer};
+#[cfg(feature = "middleware-jws")]
+pub use jws::{
+    DEFAULT_JWS_HEADER, Jws, JwsAlgorithm, JwsLayer, JwsMode, Jwt, JwtClaims, JwtLayer, SigningKey,
+};
+#[cfg(feature = "middleware-load-shed")]
+pub use load_shed::{LoadShed, LoadShedLayer};
 pub use logging::{LogLevel, Logging, LoggingLayer};
+pub use map::{Map, MapLayer};
 #[cfg(feature = "middleware-metrics")]
 pub use metrics::{Metrics, MetricsLayer};
 #[cfg(feature = "middleware-rate-limit")]
 pub use rate_limit::{RateLimit, RateLimitLayer};
-pub use retry::RetryPolicy;
+pub use retry::{RetryCount, RetryPolicy};
+pub use route::{RouteLayer, Router};
+pub use task_local_header::{TaskLocalHeader, TaskLocalHeaderLayer};
+#[cfg(feature = "middleware-token-auth")]
+pub use token_auth::{DEFAULT_REFRESH_MARGIN, Token, TokenAuth, TokenAuthLayer, TokenCache};
 
 // Re-export tower types for convenience (always available)
 pub use tower::{Layer, ServiceBuilder};
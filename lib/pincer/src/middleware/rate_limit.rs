@@ -131,7 +131,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use http::HeaderMap;
     use std::pin::Pin;
     use std::sync::atomic::{AtomicU32, Ordering};
     use std::time::Instant;
@@ -174,7 +174,7 @@ mod tests {
             self.call_count.fetch_add(1, Ordering::SeqCst);
             let status = self.status;
 
-            Box::pin(async move { Ok(Response::new(status, HashMap::new(), Bytes::new())) })
+            Box::pin(async move { Ok(Response::new(status, HeaderMap::new(), Bytes::new())) })
         }
     }
 
@@ -2,6 +2,9 @@
 //!
 //! This module provides a simple retry policy for HTTP requests that can be
 //! customized based on response status codes and error types.
+//!
+//! The final response carries a [`RetryCount`] extension with the number of
+//! retries it took.
 
 use std::future;
 
@@ -29,18 +32,40 @@ use crate::{Error, Request, Response};
 /// ```
 #[derive(Debug, Clone)]
 pub struct RetryPolicy {
+    max_retries: u32,
     remaining: u32,
 }
 
+/// Number of retries performed before a response was received.
+///
+/// Inserted in response extensions by [`RetryPolicy`].
+///
+/// # Example
+///
+/// ```ignore
+/// let retries = response.extensions().get::<RetryCount>().map_or(0, |count| count.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RetryCount(pub u32);
+
 impl RetryPolicy {
     /// Create a new retry policy with the given maximum number of retries.
     #[must_use]
     pub fn new(max_retries: u32) -> Self {
         Self {
+            max_retries,
             remaining: max_retries,
         }
     }
 
+    /// Record the number of retries on the final response.
+    fn record_retries(&self, result: &mut Result<Response<Bytes>, Error>) {
+        if let Ok(response) = result {
+            let retries = self.max_retries - self.remaining;
+            response.extensions_mut().insert(RetryCount(retries));
+        }
+    }
+
     /// Returns `true` if the response should be retried.
     fn should_retry_response(response: &Response<Bytes>) -> bool {
         let status = response.status();
@@ -64,6 +89,7 @@ impl Policy<Request<Bytes>, Response<Bytes>, Error> for RetryPolicy {
         result: &mut Result<Response<Bytes>, Error>,
     ) -> Option<Self::Future> {
         if self.remaining == 0 {
+            self.record_retries(result);
             return None;
         }
 
@@ -76,6 +102,7 @@ impl Policy<Request<Bytes>, Response<Bytes>, Error> for RetryPolicy {
             self.remaining -= 1;
             Some(future::ready(()))
         } else {
+            self.record_retries(result);
             None
         }
     }
@@ -88,7 +115,7 @@ impl Policy<Request<Bytes>, Response<Bytes>, Error> for RetryPolicy {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use http::HeaderMap;
 
     use super::*;
 
@@ -100,31 +127,31 @@ mod tests {
 
     #[test]
     fn should_retry_5xx_response() {
-        let response = Response::new(500, HashMap::default(), Bytes::new());
+        let response = Response::new(500, HeaderMap::new(), Bytes::new());
         assert!(RetryPolicy::should_retry_response(&response));
 
-        let response = Response::new(503, HashMap::default(), Bytes::new());
+        let response = Response::new(503, HeaderMap::new(), Bytes::new());
         assert!(RetryPolicy::should_retry_response(&response));
     }
 
     #[test]
     fn should_retry_429_response() {
-        let response = Response::new(429, HashMap::default(), Bytes::new());
+        let response = Response::new(429, HeaderMap::new(), Bytes::new());
         assert!(RetryPolicy::should_retry_response(&response));
     }
 
     #[test]
     fn should_not_retry_4xx_response() {
-        let response = Response::new(400, HashMap::default(), Bytes::new());
+        let response = Response::new(400, HeaderMap::new(), Bytes::new());
         assert!(!RetryPolicy::should_retry_response(&response));
 
-        let response = Response::new(404, HashMap::default(), Bytes::new());
+        let response = Response::new(404, HeaderMap::new(), Bytes::new());
         assert!(!RetryPolicy::should_retry_response(&response));
     }
 
     #[test]
     fn should_not_retry_2xx_response() {
-        let response = Response::new(200, HashMap::default(), Bytes::new());
+        let response = Response::new(200, HeaderMap::new(), Bytes::new());
         assert!(!RetryPolicy::should_retry_response(&response));
     }
 
@@ -139,4 +166,26 @@ mod tests {
         let error = Error::Timeout;
         assert!(RetryPolicy::should_retry_error(&error));
     }
+
+    #[test]
+    fn final_response_records_retry_count() {
+        let mut policy = RetryPolicy::new(3);
+        let mut request = Request::builder(
+            crate::Method::Get,
+            url::Url::parse("https://example.com").expect("url"),
+        )
+        .build();
+
+        let mut failed = Ok(Response::new(503, HeaderMap::new(), Bytes::new()));
+        assert!(policy.retry(&mut request, &mut failed).is_some());
+
+        let mut succeeded = Ok(Response::new(200, HeaderMap::new(), Bytes::new()));
+        assert!(policy.retry(&mut request, &mut succeeded).is_none());
+
+        let response = succeeded.expect("response");
+        assert_eq!(
+            response.extensions().get::<RetryCount>(),
+            Some(&RetryCount(1))
+        );
+    }
 }
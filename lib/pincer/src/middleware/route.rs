@@ -0,0 +1,317 @@
+//! Middleware scoped by path template.
+//!
+//! Routes each request through the middleware stack of the first pattern
+//! matching its [`PathTemplate`], so e.g. long-poll endpoints get other
+//! timeouts and retries than the CRUD endpoints of the same client. Requests
+//! matching no pattern go straight to the inner service.
+//!
+//! Patterns are matched segment by segment against the path template
+//! (`/users/{id}`), or the URL path for requests without a template:
+//! - a literal segment matches itself (`{id}` matches the `{id}` template segment)
+//! - `*` matches any single segment
+//! - `**` matches any number of segments, including none
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use tower::util::BoxCloneSyncService;
+use tower::{Layer, Service, ServiceExt};
+
+use crate::client::BoxedService;
+use crate::{Error, PathTemplate, Request, Response, Result};
+
+type Stack = dyn Fn(BoxedService) -> BoxedService + Send + Sync;
+
+/// Segment of a route pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    /// `*`
+    Any,
+    /// `**`
+    Rest,
+}
+
+/// Parsed route pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pattern {
+    source: String,
+    segments: Vec<Segment>,
+}
+
+impl Pattern {
+    fn parse(source: &str) -> Self {
+        let segments = split(source)
+            .map(|segment| match segment {
+                "*" => Segment::Any,
+                "**" => Segment::Rest,
+                literal => Segment::Literal(literal.to_string()),
+            })
+            .collect();
+        Self {
+            source: source.to_string(),
+            segments,
+        }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        let path: Vec<&str> = split(path).collect();
+        matches_segments(&self.segments, &path)
+    }
+}
+
+/// Non-empty segments of a path.
+fn split(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|segment| !segment.is_empty())
+}
+
+fn matches_segments(pattern: &[Segment], path: &[&str]) -> bool {
+    match (pattern.split_first(), path.split_first()) {
+        (None, None) => true,
+        (Some((Segment::Rest, rest)), _) => (0..=path.len()).any(|skip| {
+            path.get(skip..)
+                .is_some_and(|path| matches_segments(rest, path))
+        }),
+        (Some((Segment::Any, rest)), Some((_, path))) => matches_segments(rest, path),
+        (Some((Segment::Literal(literal), rest)), Some((segment, path))) => {
+            literal == segment && matches_segments(rest, path)
+        }
+        _ => false,
+    }
+}
+
+/// Layer applying a different middleware stack per path template pattern.
+///
+/// Routes are tried in the order they were added and the first match wins.
+///
+/// # Example
+///
+/// ```ignore
+/// use std::time::Duration;
+/// use pincer::middleware::{RetryLayer, RetryPolicy, RouteLayer, ServiceBuilder};
+/// use tower::timeout::TimeoutLayer;
+///
+/// let routes = RouteLayer::new()
+///     // Long polls: generous timeout, no retries
+///     .route("/events/**", TimeoutLayer::new(Duration::from_secs(300)))
+///     // Everything else: short timeout and retries
+///     .route(
+///         "/**",
+///         ServiceBuilder::new()
+///             .layer(RetryLayer::new(RetryPolicy::new(3)))
+///             .layer(TimeoutLayer::new(Duration::from_secs(10))),
+///     );
+///
+/// let client = HyperClient::builder().layer(routes).build();
+/// ```
+#[derive(Clone, Default)]
+pub struct RouteLayer {
+    routes: Vec<(Pattern, Arc<Stack>)>,
+}
+
+impl fmt::Debug for RouteLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RouteLayer")
+            .field(
+                "routes",
+                &self
+                    .routes
+                    .iter()
+                    .map(|(pattern, _)| &pattern.source)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl RouteLayer {
+    /// Create a router without routes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `layer` to the requests whose path template matches `pattern`.
+    #[must_use]
+    pub fn route<L>(mut self, pattern: &str, layer: L) -> Self
+    where
+        L: Layer<BoxedService> + Send + Sync + 'static,
+        L::Service: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error>
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        <L::Service as Service<Request<Bytes>>>::Future: Send,
+    {
+        let stack = move |service| BoxCloneSyncService::new(layer.layer(service));
+        self.routes.push((Pattern::parse(pattern), Arc::new(stack)));
+        self
+    }
+}
+
+impl Layer<BoxedService> for RouteLayer {
+    type Service = Router;
+
+    fn layer(&self, inner: BoxedService) -> Self::Service {
+        let routes = self
+            .routes
+            .iter()
+            .map(|(pattern, stack)| (pattern.clone(), stack(inner.clone())))
+            .collect();
+        Router {
+            routes: Arc::new(routes),
+            fallback: inner,
+        }
+    }
+}
+
+/// Service routing requests to the middleware stack of their path template.
+///
+/// Always reports ready: the selected stack is only polled for readiness
+/// once the request is known, when it is called.
+#[derive(Clone)]
+pub struct Router {
+    routes: Arc<Vec<(Pattern, BoxedService)>>,
+    fallback: BoxedService,
+}
+
+impl fmt::Debug for Router {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router")
+            .field(
+                "routes",
+                &self
+                    .routes
+                    .iter()
+                    .map(|(pattern, _)| &pattern.source)
+                    .collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl Service<Request<Bytes>> for Router {
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Bytes>) -> Self::Future {
+        let path = request
+            .extensions()
+            .get::<PathTemplate>()
+            .map_or_else(|| request.url().path(), |template| template.as_str());
+        let service = self
+            .routes
+            .iter()
+            .find(|(pattern, _)| pattern.matches(path))
+            .map_or(&self.fallback, |(_, service)| service)
+            .clone();
+        Box::pin(service.oneshot(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderMap;
+
+    use super::*;
+    use crate::Method;
+    use crate::middleware::MapLayer;
+
+    #[test]
+    fn pattern_matching() {
+        let matches = |pattern: &str, path: &str| Pattern::parse(pattern).matches(path);
+
+        assert!(matches("/users/{id}", "/users/{id}"));
+        assert!(!matches("/users/{id}", "/users/{id}/posts"));
+        assert!(matches("/users/*", "/users/{id}"));
+        assert!(!matches("/users/*", "/users"));
+        assert!(matches("/users/**", "/users"));
+        assert!(matches("/users/**", "/users/{id}/posts"));
+        assert!(matches("/**/posts", "/users/{id}/posts"));
+        assert!(!matches("/**/posts", "/users/{id}"));
+        assert!(matches("/**", "/"));
+    }
+
+    fn tag(value: &'static str) -> MapLayer {
+        MapLayer::new().map_request(move |mut request| {
+            request
+                .headers_mut()
+                .insert("x-route", value.parse().expect("value"));
+            request
+        })
+    }
+
+    async fn route_of(router: &Router, request: Request<Bytes>) -> Option<String> {
+        let response = router.clone().oneshot(request).await.expect("response");
+        response.header("x-route").map(ToString::to_string)
+    }
+
+    #[tokio::test]
+    async fn routes_by_path_template() {
+        let echo = tower::service_fn(|request: Request<Bytes>| async move {
+            let mut headers = HeaderMap::new();
+            if let Some(route) = request.headers().get("x-route") {
+                headers.insert("x-route", route.clone());
+            }
+            Ok::<_, Error>(Response::new(200, headers, Bytes::new()))
+        });
+        let router = RouteLayer::new()
+            .route("/events/**", tag("events"))
+            .route("/users/*", tag("users"))
+            .layer(BoxCloneSyncService::new(echo));
+
+        let request = |template: &'static str, url: &str| {
+            Request::builder(Method::Get, url.parse().expect("url"))
+                .extension(PathTemplate::new(template))
+                .build()
+        };
+
+        assert_eq!(
+            route_of(
+                &router,
+                request(
+                    "/events/{topic}/poll",
+                    "https://api.example.com/events/a/poll"
+                )
+            )
+            .await,
+            Some("events".to_string())
+        );
+        assert_eq!(
+            route_of(
+                &router,
+                request("/users/{id}", "https://api.example.com/users/1")
+            )
+            .await,
+            Some("users".to_string())
+        );
+        assert_eq!(
+            route_of(
+                &router,
+                request("/health", "https://api.example.com/health")
+            )
+            .await,
+            None
+        );
+
+        // Without a template, the URL path is matched
+        let untemplated = Request::builder(
+            Method::Get,
+            "https://api.example.com/events/b".parse().expect("url"),
+        )
+        .build();
+        assert_eq!(
+            route_of(&router, untemplated).await,
+            Some("events".to_string())
+        );
+    }
+}
@@ -0,0 +1,65 @@
+//! Deterministic 1-in-N sampling shared by observability middleware.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counter-based sampler selecting one event out of every `rate`.
+///
+/// A rate of `0` or `1` samples every event. Clones share the same counter,
+/// so all clones of a service contribute to the same sampling sequence.
+#[derive(Debug, Clone)]
+pub(crate) struct Sampler {
+    rate: u64,
+    counter: Arc<AtomicU64>,
+}
+
+impl Sampler {
+    pub(crate) fn new(rate: u32) -> Self {
+        Self {
+            rate: u64::from(rate.max(1)),
+            counter: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Returns `true` if the current event should be recorded.
+    pub(crate) fn sample(&self) -> bool {
+        self.rate == 1
+            || self
+                .counter
+                .fetch_add(1, Ordering::Relaxed)
+                .is_multiple_of(self.rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampler_rate_one_samples_everything() {
+        let sampler = Sampler::new(1);
+        assert!((0..10).all(|_| sampler.sample()));
+    }
+
+    #[test]
+    fn sampler_rate_zero_samples_everything() {
+        let sampler = Sampler::new(0);
+        assert!((0..10).all(|_| sampler.sample()));
+    }
+
+    #[test]
+    fn sampler_one_in_n() {
+        let sampler = Sampler::new(4);
+        let hits = (0..12).filter(|_| sampler.sample()).count();
+        assert_eq!(hits, 3);
+    }
+
+    #[test]
+    fn sampler_clones_share_counter() {
+        let sampler = Sampler::new(2);
+        let clone = sampler.clone();
+        assert!(sampler.sample());
+        assert!(!clone.sample());
+        assert!(sampler.sample());
+    }
+}
@@ -0,0 +1,303 @@
+//! Task-local header forwarding middleware.
+//!
+//! Reads a tokio task-local, typically set by the server middleware handling
+//! the incoming request (request id, auth token...), and stamps headers from
+//! it on every request sent in that task, so services forward their context
+//! without threading it through every call.
+//!
+//! The task-local is read when a request reaches the layer, so the layer
+//! must run in the caller's task. Behind a
+//! [`BufferLayer`](super::BufferLayer) or a
+//! [`ClientHandle`](crate::ClientHandle), requests are sent from a background
+//! task where the value is not set: add the layer after `with_buffer()`, and
+//! to the client a handle drives rather than behind the handle.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use http::header::{CONNECTION, CONTENT_LENGTH, HOST, TE, TRAILER, TRANSFER_ENCODING, UPGRADE};
+use http::{HeaderMap, HeaderName};
+use tokio::task::LocalKey;
+use tower::{Layer, Service};
+
+use crate::{Error, Request, Response, Result};
+
+/// Read the headers to stamp from the task-local, `None` outside of a scope.
+type ReadHeaders = Arc<dyn Fn() -> Option<HeaderMap> + Send + Sync>;
+
+/// Layer stamping headers read from a tokio task-local on requests.
+///
+/// Headers already set on a request are left unchanged, unless
+/// [`overwrite`](Self::overwrite) is set. Requests sent outside of a scope of
+/// the task-local are left unchanged.
+///
+/// # Example
+///
+/// ```ignore
+/// use http::HeaderMap;
+/// use pincer::middleware::TaskLocalHeaderLayer;
+///
+/// tokio::task_local! {
+///     static INCOMING: RequestInfo;
+/// }
+///
+/// let layer = TaskLocalHeaderLayer::new(&INCOMING, |info: &RequestInfo| {
+///     let mut headers = HeaderMap::new();
+///     headers.insert("x-request-id", info.request_id.clone());
+///     headers
+/// });
+/// let client = HyperClient::builder().layer(layer).build();
+///
+/// // In the server handler
+/// INCOMING.scope(info, async { client.get_user(42).await }).await?;
+/// ```
+#[derive(Clone)]
+pub struct TaskLocalHeaderLayer {
+    read: ReadHeaders,
+    overwrite: bool,
+}
+
+impl fmt::Debug for TaskLocalHeaderLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaskLocalHeaderLayer")
+            .field("overwrite", &self.overwrite)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TaskLocalHeaderLayer {
+    /// Create a layer stamping the headers built by `headers` from the value
+    /// of the task-local `key`.
+    pub fn new<T: 'static>(
+        key: &'static LocalKey<T>,
+        headers: impl Fn(&T) -> HeaderMap + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            read: Arc::new(move || key.try_with(&headers).ok()),
+            overwrite: false,
+        }
+    }
+
+    /// Create a layer stamping the headers of the task-local `key`, e.g. the
+    /// headers of the incoming request to forward.
+    ///
+    /// Headers describing the incoming connection and body are not
+    /// forwarded: `Host`, `Content-Length`, the hop-by-hop headers
+    /// (`Connection`, `Keep-Alive`, `Transfer-Encoding`...) and the headers
+    /// listed by `Connection`. Use [`new`](Self::new) to forward an
+    /// allow-list of headers instead.
+    #[must_use]
+    pub fn forward(key: &'static LocalKey<HeaderMap>) -> Self {
+        Self::new(key, forwarded_headers)
+    }
+
+    /// Replace the headers already set on requests.
+    #[must_use]
+    pub const fn overwrite(mut self) -> Self {
+        self.overwrite = true;
+        self
+    }
+}
+
+impl<S> Layer<S> for TaskLocalHeaderLayer {
+    type Service = TaskLocalHeader<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TaskLocalHeader {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service stamping headers read from a tokio task-local on requests.
+#[derive(Debug, Clone)]
+pub struct TaskLocalHeader<S> {
+    inner: S,
+    layer: TaskLocalHeaderLayer,
+}
+
+impl<S> TaskLocalHeader<S> {
+    /// Create a task-local header service wrapping the given service.
+    pub const fn new(inner: S, layer: TaskLocalHeaderLayer) -> Self {
+        Self { inner, layer }
+    }
+}
+
+/// Headers that only apply to the incoming connection or message framing.
+const NOT_FORWARDED: [HeaderName; 9] = [
+    HOST,
+    CONNECTION,
+    HeaderName::from_static("keep-alive"),
+    HeaderName::from_static("proxy-connection"),
+    TE,
+    TRAILER,
+    TRANSFER_ENCODING,
+    UPGRADE,
+    CONTENT_LENGTH,
+];
+
+/// The headers of an incoming request that can be forwarded.
+fn forwarded_headers(incoming: &HeaderMap) -> HeaderMap {
+    let listed: Vec<HeaderName> = incoming
+        .get_all(CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::try_from(name.trim()).ok())
+        .collect();
+    let mut headers = incoming.clone();
+    for name in NOT_FORWARDED.iter().chain(&listed) {
+        headers.remove(name);
+    }
+    headers
+}
+
+/// Stamp `headers` on `request`, keeping the request headers unless `overwrite`.
+fn stamp(request: &mut Request<Bytes>, headers: &HeaderMap, overwrite: bool) {
+    for name in headers.keys() {
+        if request.headers().contains_key(name) {
+            if !overwrite {
+                continue;
+            }
+            request.headers_mut().remove(name);
+        }
+        for value in headers.get_all(name) {
+            request.headers_mut().append(name.clone(), value.clone());
+        }
+    }
+}
+
+impl<S> Service<Request<Bytes>> for TaskLocalHeader<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Bytes>) -> Self::Future {
+        if let Some(headers) = (self.layer.read)() {
+            stamp(&mut request, &headers, self.layer.overwrite);
+        }
+
+        let mut inner = self.inner.clone();
+        Box::pin(async move { inner.call(request).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header::HeaderValue;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::Method;
+
+    tokio::task_local! {
+        static INCOMING: HeaderMap;
+        static REQUEST_ID: u64;
+    }
+
+    fn request() -> Request<Bytes> {
+        Request::builder(
+            Method::Get,
+            "https://api.example.com/".parse().expect("url"),
+        )
+        .header("x-tenant-id", "globex")
+        .build()
+    }
+
+    /// Echo the request headers.
+    fn echo() -> impl Service<
+        Request<Bytes>,
+        Response = Response<Bytes>,
+        Error = Error,
+        Future = impl Future<Output = Result<Response<Bytes>>> + Send,
+    > + Clone
+    + Send
+    + 'static {
+        tower::service_fn(|request: Request<Bytes>| async move {
+            Ok::<_, Error>(Response::new(200, request.headers().clone(), Bytes::new()))
+        })
+    }
+
+    fn incoming() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-tenant-id", HeaderValue::from_static("acme"));
+        headers.append("x-trace", HeaderValue::from_static("a"));
+        headers.append("x-trace", HeaderValue::from_static("b"));
+        headers
+    }
+
+    #[tokio::test]
+    async fn forwards_task_local_headers() {
+        let service = TaskLocalHeaderLayer::forward(&INCOMING).layer(echo());
+
+        let response = INCOMING
+            .scope(incoming(), service.clone().oneshot(request()))
+            .await
+            .expect("response");
+        assert_eq!(response.header("x-tenant-id"), Some("globex"));
+        let traces: Vec<_> = response.headers().get_all("x-trace").iter().collect();
+        assert_eq!(traces, ["a", "b"]);
+
+        // Outside of a scope, requests are left unchanged
+        let response = service.oneshot(request()).await.expect("response");
+        assert_eq!(response.headers().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_forward_connection_headers() {
+        let mut headers = incoming();
+        for (name, value) in [
+            ("host", "internal.example.com"),
+            ("content-length", "42"),
+            ("connection", "keep-alive, x-hop"),
+            ("keep-alive", "timeout=5"),
+            ("transfer-encoding", "chunked"),
+            ("x-hop", "1"),
+        ] {
+            headers.insert(
+                HeaderName::from_static(name),
+                HeaderValue::from_static(value),
+            );
+        }
+
+        let service = TaskLocalHeaderLayer::forward(&INCOMING).layer(echo());
+        let response = INCOMING
+            .scope(headers, service.oneshot(request()))
+            .await
+            .expect("response");
+        let mut names: Vec<_> = response.headers().keys().map(HeaderName::as_str).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["x-tenant-id", "x-trace"]);
+    }
+
+    #[tokio::test]
+    async fn builds_headers_from_task_local_value() {
+        let layer = TaskLocalHeaderLayer::new(&REQUEST_ID, |id: &u64| {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-request-id", HeaderValue::from(*id));
+            headers.insert("x-tenant-id", HeaderValue::from_static("acme"));
+            headers
+        })
+        .overwrite();
+
+        let response = REQUEST_ID
+            .scope(42, layer.layer(echo()).oneshot(request()))
+            .await
+            .expect("response");
+        assert_eq!(response.header("x-request-id"), Some("42"));
+        assert_eq!(response.header("x-tenant-id"), Some("acme"));
+    }
+}
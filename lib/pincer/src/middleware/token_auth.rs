@@ -0,0 +1,377 @@
+//! Token authentication middleware.
+//!
+//! Adds an `Authorization: Bearer <token>` header with an access token
+//! fetched on demand (e.g. an OAuth 2.0 client credentials exchange) and
+//! cached in a [`TokenCache`] until it expires. The cache is shared behind an `Arc`
+//! by every client using the same credentials: a `401 Unauthorized` seen by
+//! one of them invalidates the token for all of them.
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::header::{AUTHORIZATION, HeaderValue};
+use tokio::time::Instant;
+use tower::{Layer, Service};
+
+use crate::{Error, Request, Response, Result};
+
+/// Default margin before expiry at which a cached token is refreshed.
+pub const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(30);
+
+/// An access token, with its expiry.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Token {
+    value: Arc<str>,
+    expires_at: Option<Instant>,
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("value", &"[REDACTED]")
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+impl Token {
+    /// Create a token that never expires.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: Arc::from(value.into()),
+            expires_at: None,
+        }
+    }
+
+    /// Set the lifetime of the token, e.g. the `expires_in` of an OAuth 2.0
+    /// token response.
+    #[must_use]
+    pub fn expires_in(mut self, lifetime: Duration) -> Self {
+        self.expires_at = Some(Instant::now() + lifetime);
+        self
+    }
+
+    /// Get the token value.
+    #[must_use]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Get the instant the token expires at, if known.
+    #[must_use]
+    pub const fn expires_at(&self) -> Option<Instant> {
+        self.expires_at
+    }
+
+    /// Returns `true` if the token expires within `margin`.
+    fn expires_within(&self, margin: Duration) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Instant::now() + margin)
+    }
+}
+
+/// Future fetching a new token.
+type FetchFuture = Pin<Box<dyn Future<Output = Result<Token>> + Send>>;
+
+/// Access tokens fetched on demand and cached until they expire.
+///
+/// Concurrent requests needing a new token wait for a single fetch. Share the
+/// cache with an `Arc` between the clients using the same credentials, so
+/// they share the token and its refreshes.
+///
+/// # Example
+///
+/// ```ignore
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// use pincer::middleware::{Token, TokenAuthLayer, TokenCache};
+///
+/// let tokens = Arc::new(TokenCache::new(move || {
+///     let auth = auth.clone();
+///     async move {
+///         let response = auth.client_credentials().await?;
+///         Ok(Token::new(response.access_token).expires_in(Duration::from_secs(response.expires_in)))
+///     }
+/// }));
+///
+/// let users = HyperClient::builder()
+///     .layer(TokenAuthLayer::new(Arc::clone(&tokens)))
+///     .build();
+/// let orders = HyperClient::builder()
+///     .layer(TokenAuthLayer::new(Arc::clone(&tokens)))
+///     .build();
+///
+/// tokens.invalidate(); // e.g. after rotating the credentials
+/// ```
+pub struct TokenCache {
+    fetch: Box<dyn Fn() -> FetchFuture + Send + Sync>,
+    token: Mutex<Option<Token>>,
+    /// Held while fetching, so concurrent requests wait for a single fetch.
+    refresh: tokio::sync::Mutex<()>,
+    refresh_margin: Duration,
+}
+
+impl fmt::Debug for TokenCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenCache")
+            .field("token", &*self.lock())
+            .field("refresh_margin", &self.refresh_margin)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TokenCache {
+    /// Create a cache fetching tokens with `fetch`.
+    pub fn new<F, Fut>(fetch: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Token>> + Send + 'static,
+    {
+        Self {
+            fetch: Box::new(move || Box::pin(fetch())),
+            token: Mutex::new(None),
+            refresh: tokio::sync::Mutex::new(()),
+            refresh_margin: DEFAULT_REFRESH_MARGIN,
+        }
+    }
+
+    /// Set the margin before expiry at which a token is refreshed (default:
+    /// [`DEFAULT_REFRESH_MARGIN`]), so it does not expire in flight.
+    #[must_use]
+    pub const fn refresh_margin(mut self, margin: Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// Get the cached token, fetching a new one if none is cached or it is
+    /// about to expire.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the fetch.
+    pub async fn token(&self) -> Result<Token> {
+        if let Some(token) = self.cached() {
+            return Ok(token);
+        }
+        let _refresh = self.refresh.lock().await;
+        // Fetched by another request while waiting
+        if let Some(token) = self.cached() {
+            return Ok(token);
+        }
+        let token = (self.fetch)().await?;
+        *self.lock() = Some(token.clone());
+        Ok(token)
+    }
+
+    /// Drop the cached token, the next request fetches a new one.
+    pub fn invalidate(&self) {
+        *self.lock() = None;
+    }
+
+    /// Drop the cached token if it is `rejected`, e.g. by a `401` response.
+    ///
+    /// A token already refreshed by another request is kept.
+    pub fn invalidate_token(&self, rejected: &Token) {
+        let mut token = self.lock();
+        if token.as_ref() == Some(rejected) {
+            *token = None;
+        }
+    }
+
+    fn cached(&self) -> Option<Token> {
+        self.lock()
+            .as_ref()
+            .filter(|token| !token.expires_within(self.refresh_margin))
+            .cloned()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<Token>> {
+        self.token.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Layer that adds a cached access token to requests.
+///
+/// A `401 Unauthorized` response invalidates the token in the shared
+/// [`TokenCache`], the next request fetches a new one.
+#[derive(Debug, Clone)]
+pub struct TokenAuthLayer {
+    cache: Arc<TokenCache>,
+}
+
+impl TokenAuthLayer {
+    /// Create a layer using the tokens of `cache`.
+    #[must_use]
+    pub const fn new(cache: Arc<TokenCache>) -> Self {
+        Self { cache }
+    }
+
+    /// Get the token cache.
+    #[must_use]
+    pub const fn cache(&self) -> &Arc<TokenCache> {
+        &self.cache
+    }
+}
+
+impl<S> Layer<S> for TokenAuthLayer {
+    type Service = TokenAuth<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TokenAuth {
+            inner,
+            cache: Arc::clone(&self.cache),
+        }
+    }
+}
+
+/// Service that adds a cached access token to requests.
+#[derive(Debug, Clone)]
+pub struct TokenAuth<S> {
+    inner: S,
+    cache: Arc<TokenCache>,
+}
+
+impl<S> TokenAuth<S> {
+    /// Create a token auth service wrapping the given service.
+    pub const fn new(inner: S, cache: Arc<TokenCache>) -> Self {
+        Self { inner, cache }
+    }
+}
+
+impl<S> Service<Request<Bytes>> for TokenAuth<S>
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>, Error = Error> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response<Bytes>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Bytes>) -> Self::Future {
+        let mut inner = self.inner.clone();
+        let cache = Arc::clone(&self.cache);
+        Box::pin(async move {
+            let token = cache.token().await?;
+            let mut value = HeaderValue::try_from(format!("Bearer {}", token.value()))
+                .map_err(|err| Error::invalid_request(format!("invalid access token: {err}")))?;
+            value.set_sensitive(true);
+            request.headers_mut().insert(AUTHORIZATION, value);
+
+            let response = inner.call(request).await?;
+            if response.status() == 401 {
+                cache.invalidate_token(&token);
+            }
+            Ok(response)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use http::HeaderMap;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::Method;
+
+    fn request() -> Request<Bytes> {
+        Request::builder(
+            Method::Get,
+            "https://api.example.com/".parse().expect("url"),
+        )
+        .build()
+    }
+
+    /// Cache fetching `token-<n>` tokens valid for a minute.
+    fn cache(fetches: Arc<AtomicUsize>) -> TokenCache {
+        TokenCache::new(move || {
+            let count = fetches.fetch_add(1, Ordering::SeqCst);
+            async move {
+                tokio::task::yield_now().await;
+                Ok(Token::new(format!("token-{count}")).expires_in(Duration::from_mins(1)))
+            }
+        })
+    }
+
+    /// Answer `401` to `token-0`, and echo the `Authorization` header.
+    fn echo() -> impl Service<
+        Request<Bytes>,
+        Response = Response<Bytes>,
+        Error = Error,
+        Future = impl Future<Output = Result<Response<Bytes>>> + Send,
+    > + Clone
+    + Send
+    + 'static {
+        tower::service_fn(|request: Request<Bytes>| async move {
+            let authorization = request.header("authorization").unwrap_or_default();
+            let status = if authorization == "Bearer token-0" {
+                401
+            } else {
+                200
+            };
+            Ok::<_, Error>(Response::new(
+                status,
+                HeaderMap::new(),
+                Bytes::from(authorization.to_string()),
+            ))
+        })
+    }
+
+    #[tokio::test]
+    async fn fetches_a_single_token_concurrently() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let cache = cache(Arc::clone(&fetches));
+
+        let (first, second) = tokio::join!(cache.token(), cache.token());
+        assert_eq!(first.expect("token").value(), "token-0");
+        assert_eq!(second.expect("token").value(), "token-0");
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        cache.invalidate();
+        assert_eq!(cache.token().await.expect("token").value(), "token-1");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refreshes_tokens_before_expiry() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let cache = cache(Arc::clone(&fetches)).refresh_margin(Duration::from_secs(10));
+
+        assert_eq!(cache.token().await.expect("token").value(), "token-0");
+        tokio::time::advance(Duration::from_secs(45)).await;
+        assert_eq!(cache.token().await.expect("token").value(), "token-0");
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert_eq!(cache.token().await.expect("token").value(), "token-1");
+    }
+
+    #[tokio::test]
+    async fn unauthorized_invalidates_the_shared_token() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let cache = Arc::new(cache(Arc::clone(&fetches)));
+        let users = TokenAuthLayer::new(Arc::clone(&cache)).layer(echo());
+        let orders = TokenAuthLayer::new(Arc::clone(&cache)).layer(echo());
+
+        let response = users.oneshot(request()).await.expect("response");
+        assert_eq!(response.status(), 401);
+
+        let response = orders.clone().oneshot(request()).await.expect("response");
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.body().as_ref(), b"Bearer token-1");
+
+        // A stale rejection keeps the refreshed token
+        cache.invalidate_token(&Token::new("token-0"));
+        orders.oneshot(request()).await.expect("response");
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,501 @@
+//! Stream the items of a paginated API (requires `paginate` feature).
+//!
+//! [`stream`] repeatedly calls a fetch function with the cursor of the next
+//! page, and yields the items of each [`Page`] until the last one. Transient
+//! failures of a page are retried, and the number of pages and items is
+//! capped, so a server that never stops returning cursors cannot turn the
+//! loop into an infinite one.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use std::pin::pin;
+//!
+//! use futures_util::TryStreamExt;
+//! use pincer::paginate::{self, PaginateOptions};
+//!
+//! let mut repos = pin!(paginate::stream(|cursor| client.list_repos(cursor)));
+//! while let Some(repo) = repos.try_next().await? {
+//!     println!("{}", repo.name);
+//! }
+//!
+//! // First 50 items, at most 5 pages
+//! let options = PaginateOptions::new().max_items(50).max_pages(5);
+//! let repos: Vec<Repo> = paginate::stream_with(options, |cursor| client.list_repos(cursor))
+//!     .try_collect()
+//!     .await?;
+//!
+//! // Safety caps set once on the client, failing when reached
+//! let http = HyperClient::builder()
+//!     .paginate_options(
+//!         PaginateOptions::new()
+//!             .max_pages(200)
+//!             .max_duration(Duration::from_mins(5))
+//!             .fail_on_limit(),
+//!     )
+//!     .build();
+//! let client = ApiClientBuilder::default().client(http).build()?;
+//! let repos = client.inner().paginate(|cursor| client.list_repos(cursor));
+//! ```
+
+use std::future::Future;
+use std::time::Duration;
+
+use futures_util::{Stream, stream};
+use tokio::time::Instant;
+
+use crate::{Error, Page, Result};
+
+/// Default maximum number of pages fetched by a stream.
+pub const DEFAULT_MAX_PAGES: usize = 1_000;
+
+/// Default number of retries for a failed page.
+pub const DEFAULT_PAGE_RETRIES: u32 = 2;
+
+/// Delay before the first page retry, doubled on each attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// Limits of a pagination stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaginateOptions {
+    max_pages: Option<usize>,
+    max_items: Option<usize>,
+    max_duration: Option<Duration>,
+    fail_on_limit: bool,
+    page_retries: u32,
+}
+
+impl Default for PaginateOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PaginateOptions {
+    /// Create options with at most 1000 pages, no item or time limit and 2
+    /// retries per page.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            max_pages: Some(DEFAULT_MAX_PAGES),
+            max_items: None,
+            max_duration: None,
+            fail_on_limit: false,
+            page_retries: DEFAULT_PAGE_RETRIES,
+        }
+    }
+
+    /// Stop after fetching `max_pages` pages.
+    #[must_use]
+    pub const fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Remove the page limit, relying on the server to end the pagination.
+    #[must_use]
+    pub const fn unlimited_pages(mut self) -> Self {
+        self.max_pages = None;
+        self
+    }
+
+    /// Stop after yielding `max_items` items.
+    #[must_use]
+    pub const fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// Stop fetching pages once `max_duration` has elapsed since the first page
+    /// was requested.
+    #[must_use]
+    pub const fn max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// End the stream with [`Error::PaginationLimit`] when a limit is reached
+    /// while the server still has items, instead of ending it silently.
+    #[must_use]
+    pub const fn fail_on_limit(mut self) -> Self {
+        self.fail_on_limit = true;
+        self
+    }
+
+    /// Set how many times a failed page is retried.
+    ///
+    /// Only connection errors, timeouts, `429` and `5xx` responses are retried.
+    #[must_use]
+    pub const fn page_retries(mut self, retries: u32) -> Self {
+        self.page_retries = retries;
+        self
+    }
+}
+
+/// Stream the items of every page, with [`PaginateOptions::new`].
+///
+/// `fetch` is called with `None` for the first page, then with the
+/// [`next_cursor`](Page::next_cursor) of the previous page.
+///
+/// See [`stream_with`], and [`HyperClient::paginate`](crate::HyperClient::paginate)
+/// for the options configured on a client.
+pub fn stream<T, F, Fut>(fetch: F) -> impl Stream<Item = Result<T>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Page<T>>>,
+{
+    stream_with(PaginateOptions::default(), fetch)
+}
+
+/// Stream the items of every page, within the given limits.
+///
+/// The stream ends after the last page, or once a limit is reached. It yields
+/// an error (and then ends) if a page still fails after its retries, if the
+/// server returns the cursor that was just requested, or if a limit is
+/// reached with [`PaginateOptions::fail_on_limit`].
+pub fn stream_with<T, F, Fut>(options: PaginateOptions, fetch: F) -> impl Stream<Item = Result<T>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Page<T>>>,
+{
+    let state = Paginator {
+        fetch,
+        options,
+        cursor: None,
+        items: Vec::new().into_iter(),
+        pages: 0,
+        yielded: 0,
+        started: None,
+        done: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        let item = state.next_item().await?;
+        Some((item, state))
+    })
+}
+
+struct Paginator<F, T> {
+    fetch: F,
+    options: PaginateOptions,
+    cursor: Option<String>,
+    items: std::vec::IntoIter<T>,
+    pages: usize,
+    yielded: usize,
+    started: Option<Instant>,
+    done: bool,
+}
+
+impl<F, Fut, T> Paginator<F, T>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Page<T>>>,
+{
+    async fn next_item(&mut self) -> Option<Result<T>> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        loop {
+            if let Some(max) = self.options.max_items.filter(|max| self.yielded >= *max) {
+                let more = self.items.len() > 0 || !self.done;
+                return self.limit_reached(more, format!("{max} items"));
+            }
+            if let Some(item) = self.items.next() {
+                self.yielded += 1;
+                return Some(Ok(item));
+            }
+            if self.done {
+                return None;
+            }
+            if let Some(max) = self.options.max_pages.filter(|max| self.pages >= *max) {
+                tracing::debug!(pages = self.pages, "pagination stopped at max pages");
+                return self.limit_reached(true, format!("{max} pages"));
+            }
+            if let Some(max) = self
+                .options
+                .max_duration
+                .filter(|max| started.elapsed() >= *max)
+            {
+                tracing::debug!(pages = self.pages, "pagination stopped at max duration");
+                return self.limit_reached(true, format!("{max:?}"));
+            }
+            if let Err(err) = self.next_page().await {
+                self.done = true;
+                return Some(Err(err));
+            }
+        }
+    }
+
+    /// End the stream at a limit, with an error if configured and the server
+    /// has `more` items.
+    fn limit_reached(&mut self, more: bool, limit: String) -> Option<Result<T>> {
+        let fail = self.options.fail_on_limit && more;
+        self.done = true;
+        self.items = Vec::new().into_iter();
+        fail.then(|| Err(Error::PaginationLimit(limit)))
+    }
+
+    async fn next_page(&mut self) -> Result<()> {
+        let page = self.fetch_page().await?;
+        self.pages += 1;
+
+        let next = page.next_cursor().map(str::to_string);
+        if next.is_some() && next == self.cursor {
+            return Err(Error::invalid_request(format!(
+                "pagination cursor {:?} repeated by the server",
+                self.cursor.as_deref().unwrap_or_default()
+            )));
+        }
+
+        self.done = next.is_none();
+        self.cursor = next;
+        self.items = page.into_items().into_iter();
+        Ok(())
+    }
+
+    async fn fetch_page(&mut self) -> Result<Page<T>> {
+        let mut attempt = 0;
+
+        loop {
+            match (self.fetch)(self.cursor.clone()).await {
+                Ok(page) => return Ok(page),
+                Err(err) if attempt < self.options.page_retries && is_transient(&err) => {
+                    let delay = RETRY_BASE_DELAY * 2u32.saturating_pow(attempt);
+                    tracing::debug!(cursor = ?self.cursor, attempt, error = %err, "retrying page");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Returns `true` if fetching the page again may succeed.
+fn is_transient(error: &Error) -> bool {
+    error.is_connection()
+        || error.is_timeout()
+        || error
+            .status()
+            .is_some_and(|status| status >= 500 || status == 429)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use futures_util::{StreamExt, TryStreamExt};
+
+    use super::*;
+
+    /// Pages `0..count`, each with two items, linked by their index.
+    fn fetch_pages(
+        count: usize,
+        calls: &Mutex<Vec<Option<String>>>,
+    ) -> impl FnMut(Option<String>) -> std::future::Ready<Result<Page<usize>>> + '_ {
+        move |cursor| {
+            calls.lock().expect("lock").push(cursor.clone());
+            let index: usize = cursor.map_or(0, |cursor| cursor.parse().expect("cursor"));
+            let page = Page::new(vec![index * 2, index * 2 + 1]);
+            let page = if index + 1 < count {
+                page.with_next_cursor((index + 1).to_string())
+            } else {
+                page
+            };
+            std::future::ready(Ok(page))
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_yields_every_page() {
+        let calls = Mutex::new(Vec::new());
+
+        let items: Vec<usize> = stream(fetch_pages(3, &calls))
+            .try_collect()
+            .await
+            .expect("items");
+
+        assert_eq!(items, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(
+            *calls.lock().expect("lock"),
+            [None, Some("1".to_string()), Some("2".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_stops_at_max_items() {
+        let calls = Mutex::new(Vec::new());
+        let options = PaginateOptions::new().max_items(3);
+
+        let items: Vec<usize> = stream_with(options, fetch_pages(10, &calls))
+            .try_collect()
+            .await
+            .expect("items");
+
+        assert_eq!(items, [0, 1, 2]);
+        assert_eq!(calls.lock().expect("lock").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn stream_stops_at_max_pages() {
+        let calls = Mutex::new(Vec::new());
+        let options = PaginateOptions::new().max_pages(2);
+
+        let items: Vec<usize> = stream_with(options, fetch_pages(10, &calls))
+            .try_collect()
+            .await
+            .expect("items");
+
+        assert_eq!(items, [0, 1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn stream_fails_at_limit_with_remaining_pages() {
+        let calls = Mutex::new(Vec::new());
+        let options = PaginateOptions::new().max_pages(2).fail_on_limit();
+
+        let results: Vec<Result<usize>> = stream_with(options, fetch_pages(10, &calls))
+            .collect()
+            .await;
+
+        assert_eq!(results.len(), 5);
+        let err = results
+            .last()
+            .and_then(|result| result.as_ref().err())
+            .expect("limit error");
+        assert!(err.is_pagination_limit());
+        assert!(err.to_string().contains("2 pages"));
+
+        // The last page fits in the limit: no error
+        let calls = Mutex::new(Vec::new());
+        let options = PaginateOptions::new().max_items(4).fail_on_limit();
+        let items: Vec<usize> = stream_with(options, fetch_pages(2, &calls))
+            .try_collect()
+            .await
+            .expect("items");
+        assert_eq!(items, [0, 1, 2, 3]);
+
+        // The last page has items past the limit
+        let calls = Mutex::new(Vec::new());
+        let options = PaginateOptions::new().max_items(3).fail_on_limit();
+        let results: Vec<Result<usize>> =
+            stream_with(options, fetch_pages(2, &calls)).collect().await;
+        assert_eq!(results.len(), 4);
+        assert!(
+            results
+                .last()
+                .and_then(|result| result.as_ref().err())
+                .is_some_and(|err| err.to_string().contains("3 items"))
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stream_stops_at_max_duration() {
+        let options = PaginateOptions::new()
+            .max_pages(usize::MAX)
+            .max_duration(Duration::from_secs(10))
+            .fail_on_limit();
+        let mut cursor = 0_u32;
+
+        let results: Vec<Result<u32>> = stream_with(options, |_cursor| {
+            cursor += 1;
+            let page = Page::new(vec![cursor]).with_next_cursor(cursor.to_string());
+            async move {
+                tokio::time::sleep(Duration::from_secs(3)).await;
+                Ok(page)
+            }
+        })
+        .collect()
+        .await;
+
+        assert_eq!(results.len(), 5);
+        assert!(
+            results
+                .last()
+                .and_then(|result| result.as_ref().err())
+                .is_some_and(Error::is_pagination_limit)
+        );
+    }
+
+    #[test]
+    fn default_options() {
+        let options = PaginateOptions::default();
+        assert_eq!(options, PaginateOptions::new());
+        assert_eq!(options.max_pages, Some(DEFAULT_MAX_PAGES));
+        assert_eq!(options.max_duration, None);
+        assert!(!options.fail_on_limit);
+    }
+
+    #[tokio::test]
+    async fn client_default_options() {
+        let calls = Mutex::new(Vec::new());
+        let client = crate::HyperClient::builder()
+            .paginate_options(PaginateOptions::new().max_pages(2))
+            .build();
+        assert_eq!(
+            client.config().paginate_options,
+            PaginateOptions::new().max_pages(2)
+        );
+
+        let items: Vec<usize> = client
+            .paginate(fetch_pages(10, &calls))
+            .try_collect()
+            .await
+            .expect("items");
+
+        assert_eq!(items, [0, 1, 2, 3]);
+        assert_eq!(calls.lock().expect("lock").len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stream_retries_transient_failures() {
+        let attempts = AtomicU32::new(0);
+
+        let items: Vec<u32> = stream(|_cursor| {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt < 2 {
+                    Err(Error::http(503, "unavailable"))
+                } else {
+                    Ok(Page::new(vec![attempt]))
+                }
+            }
+        })
+        .try_collect()
+        .await
+        .expect("items");
+
+        assert_eq!(items, [2]);
+    }
+
+    #[tokio::test]
+    async fn stream_ends_after_permanent_failure() {
+        let attempts = AtomicU32::new(0);
+
+        let results: Vec<Result<u32>> = stream(|_cursor| {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            std::future::ready(Err(Error::http(404, "not found")))
+        })
+        .collect()
+        .await;
+
+        assert_eq!(results.len(), 1);
+        assert!(results.iter().all(Result::is_err));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn stream_rejects_repeated_cursor() {
+        let results: Vec<Result<u32>> =
+            stream(|_cursor| std::future::ready(Ok(Page::new(vec![1]).with_next_cursor("same"))))
+                .collect()
+                .await;
+
+        assert_eq!(results.len(), 2);
+        assert!(results.first().is_some_and(Result::is_ok));
+        let err = results
+            .last()
+            .and_then(|result| result.as_ref().err())
+            .expect("repeated cursor error");
+        assert!(err.to_string().contains("same"));
+    }
+}
@@ -0,0 +1,39 @@
+//! Tokio implementation of [`Runtime`].
+
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::{Runtime, Sleep};
+
+/// The Tokio runtime, used by clients that do not name their own runtime.
+///
+/// Must be used from within a Tokio runtime with timers enabled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioRuntime;
+
+/// The runtime named by a client, or [`TokioRuntime`] if it names none.
+///
+/// Generated clients and the background tasks of pincer (client handles,
+/// health watchers, request buffers) spawn and sleep on this runtime.
+///
+/// # Example
+///
+/// ```ignore
+/// let runtime = pincer::runtime_or_default(HttpClient::runtime(&client));
+/// pincer::timeout(runtime, Duration::from_secs(5), client.execute(request)).await??;
+/// ```
+#[must_use]
+pub fn runtime_or_default(runtime: Option<&dyn Runtime>) -> &dyn Runtime {
+    runtime.unwrap_or(&TokioRuntime)
+}
+
+impl Runtime for TokioRuntime {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) {
+        tokio::spawn(future);
+    }
+}
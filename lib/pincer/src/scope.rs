@@ -0,0 +1,199 @@
+//! Request scopes, for structured concurrency.
+//!
+//! A [`RequestScope`] ties the requests of the clients attached to it to its
+//! lifetime: when the scope is dropped or cancelled, their in-flight requests
+//! are aborted and fail with [`Error::Cancelled`]. This makes request-scoped
+//! fan-out (e.g., in a server handler) deterministic, even when the futures
+//! were spawned on a `JoinSet` or detached tasks.
+
+use std::future::{Future, poll_fn};
+use std::pin::pin;
+use std::task::Poll;
+
+use bytes::Bytes;
+use tokio::sync::watch;
+use url::Url;
+
+use crate::{Error, HttpClient, PincerClient, Request, Response, Result, Runtime};
+
+/// Cancellation scope for the requests of attached clients.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::{ApiClient, RequestScope};
+///
+/// async fn handler(api: ApiClient<HyperClient>) -> pincer::Result<()> {
+///     let scope = RequestScope::new();
+///     let client = UserApiClient::new(scope.attach(api));
+///
+///     let mut tasks = tokio::task::JoinSet::new();
+///     for id in 1..=10 {
+///         let client = client.clone();
+///         tasks.spawn(async move { client.get_user(id).await });
+///     }
+///     // ...
+///     Ok(())
+/// } // `scope` is dropped: outstanding requests fail with `Error::Cancelled`
+/// ```
+#[derive(Debug)]
+pub struct RequestScope {
+    cancelled: watch::Sender<bool>,
+}
+
+impl RequestScope {
+    /// Create a new, active scope.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            cancelled: watch::Sender::new(false),
+        }
+    }
+
+    /// Attach a client to this scope.
+    ///
+    /// The returned client is cheap to clone when `client` is.
+    #[must_use]
+    pub fn attach<C>(&self, client: C) -> Scoped<C> {
+        Scoped {
+            client,
+            cancelled: self.cancelled.subscribe(),
+        }
+    }
+
+    /// Abort the in-flight and future requests of the attached clients.
+    pub fn cancel(&self) {
+        self.cancelled.send_replace(true);
+    }
+
+    /// Returns `true` if the scope was cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled.borrow()
+    }
+}
+
+impl Default for RequestScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A client attached to a [`RequestScope`].
+///
+/// Implements [`HttpClient`] and [`PincerClient`] when the inner client does.
+#[derive(Debug, Clone)]
+pub struct Scoped<C> {
+    client: C,
+    cancelled: watch::Receiver<bool>,
+}
+
+impl<C> Scoped<C> {
+    /// Get the inner client.
+    #[must_use]
+    pub fn inner(&self) -> &C {
+        &self.client
+    }
+
+    /// Returns `true` if the scope was cancelled or dropped.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        *self.cancelled.borrow() || self.cancelled.has_changed().is_err()
+    }
+
+    /// Run `request`, unless the scope ends first.
+    async fn run(
+        &self,
+        request: impl Future<Output = Result<Response<Bytes>>>,
+    ) -> Result<Response<Bytes>> {
+        let mut receiver = self.cancelled.clone();
+        // Resolves when the scope is cancelled, or dropped (the sender is closed)
+        let mut cancelled = pin!(async move {
+            let _ = receiver.wait_for(|cancelled| *cancelled).await;
+        });
+        let mut request = pin!(request);
+        poll_fn(|cx| {
+            if cancelled.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(Err(Error::Cancelled));
+            }
+            request.as_mut().poll(cx)
+        })
+        .await
+    }
+}
+
+impl<C: HttpClient> HttpClient for Scoped<C> {
+    fn execute(
+        &self,
+        request: Request<Bytes>,
+    ) -> impl Future<Output = Result<Response<Bytes>>> + Send {
+        self.run(self.client.execute(request))
+    }
+
+    fn runtime(&self) -> Option<&dyn Runtime> {
+        self.client.runtime()
+    }
+}
+
+impl<C: PincerClient> PincerClient for Scoped<C> {
+    fn execute(
+        &self,
+        request: Request<Bytes>,
+    ) -> impl Future<Output = Result<Response<Bytes>>> + Send {
+        self.run(self.client.execute(request))
+    }
+
+    fn base_url(&self) -> &Url {
+        self.client.base_url()
+    }
+
+    fn runtime(&self) -> Option<&dyn Runtime> {
+        PincerClient::runtime(&self.client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+
+    /// Client whose requests never complete.
+    #[derive(Debug, Clone)]
+    struct PendingClient;
+
+    impl HttpClient for PendingClient {
+        async fn execute(&self, _request: Request<Bytes>) -> Result<Response<Bytes>> {
+            std::future::pending().await
+        }
+    }
+
+    fn request() -> Request<Bytes> {
+        Request::builder(Method::Get, "https://example.com".parse().expect("url")).build()
+    }
+
+    #[tokio::test]
+    async fn dropping_the_scope_aborts_in_flight_requests() {
+        let scope = RequestScope::new();
+        let client = scope.attach(PendingClient);
+        let task = tokio::spawn(async move { client.execute(request()).await });
+
+        tokio::task::yield_now().await;
+        drop(scope);
+
+        let result = task.await.expect("task");
+        assert!(result.expect_err("cancelled").is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn cancelled_scope_rejects_new_requests() {
+        let scope = RequestScope::new();
+        let client = scope.attach(PendingClient);
+        assert!(!client.is_cancelled());
+
+        scope.cancel();
+        assert!(scope.is_cancelled());
+        assert!(client.is_cancelled());
+        let err = client.execute(request()).await.expect_err("cancelled");
+        assert!(err.is_cancelled());
+    }
+}
@@ -0,0 +1,215 @@
+//! TLS client identity for mutual TLS and negotiated session details.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use rustls::client::ResolvesClientCert;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::sign::CertifiedKey;
+use rustls::{ClientConnection, SignatureScheme};
+
+use crate::{Error, Result};
+
+/// Client certificate chain and private key used for mutual TLS.
+///
+/// Each [`HyperClient`](crate::HyperClient) can carry its own identity, so a
+/// single process can talk to several mutually-authenticated services.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::{HyperClient, Identity};
+///
+/// let identity = Identity::from_pem(std::fs::read("client.pem")?)?;
+/// let client = HyperClient::builder().identity(identity).build();
+/// ```
+#[derive(Clone)]
+pub struct Identity {
+    key: Arc<CertifiedKey>,
+}
+
+impl std::fmt::Debug for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Identity")
+            .field("certificates", &self.key.cert.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Identity {
+    /// Load an identity from a PEM bundle containing the certificate chain and private key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no certificate or private key is found, or if the
+    /// key does not match the leaf certificate.
+    pub fn from_pem(pem: impl AsRef<[u8]>) -> Result<Self> {
+        Self::from_pem_parts(pem.as_ref(), pem.as_ref())
+    }
+
+    /// Load an identity from separate PEM certificate chain and private key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no certificate or private key is found, or if the
+    /// key does not match the leaf certificate.
+    pub fn from_pem_parts(cert_pem: impl AsRef<[u8]>, key_pem: impl AsRef<[u8]>) -> Result<Self> {
+        let certs = CertificateDer::pem_slice_iter(cert_pem.as_ref())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::tls(format!("invalid PEM certificate: {e}")))?;
+        if certs.is_empty() {
+            return Err(Error::tls("no certificate found in PEM"));
+        }
+
+        let key = PrivateKeyDer::from_pem_slice(key_pem.as_ref())
+            .map_err(|e| Error::tls(format!("invalid PEM private key: {e}")))?;
+
+        let provider = rustls::crypto::ring::default_provider();
+        let key = CertifiedKey::from_der(certs, key, &provider)
+            .map_err(|e| Error::tls(format!("invalid client identity: {e}")))?;
+
+        Ok(Self { key: Arc::new(key) })
+    }
+
+    /// Certificate resolver presenting this identity during handshakes.
+    pub(crate) fn resolver(&self) -> Arc<dyn ResolvesClientCert> {
+        Arc::new(IdentityResolver(Arc::clone(&self.key)))
+    }
+}
+
+/// Always presents the same certificate chain.
+#[derive(Debug)]
+struct IdentityResolver(Arc<CertifiedKey>);
+
+impl ResolvesClientCert for IdentityResolver {
+    fn resolve(
+        &self,
+        _root_hint_subjects: &[&[u8]],
+        _sigschemes: &[SignatureScheme],
+    ) -> Option<Arc<CertifiedKey>> {
+        Some(Arc::clone(&self.0))
+    }
+
+    fn has_certs(&self) -> bool {
+        true
+    }
+}
+
+/// Parameters negotiated during the TLS handshake.
+///
+/// Inserted into the extensions of every response received over HTTPS,
+/// which makes it available for compliance logging or for debugging
+/// handshake issues. Plain HTTP responses carry no `TlsInfo`.
+///
+/// # Example
+///
+/// ```ignore
+/// use pincer::TlsInfo;
+///
+/// let response = client.execute(request).await?;
+/// if let Some(tls) = response.extensions().get::<TlsInfo>() {
+///     tracing::info!(version = tls.protocol_version(), cipher = tls.cipher_suite());
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsInfo {
+    protocol_version: Option<String>,
+    cipher_suite: Option<String>,
+    alpn_protocol: Option<String>,
+    peer_certificates: Vec<Bytes>,
+}
+
+impl TlsInfo {
+    /// Capture the negotiated parameters of an established connection.
+    pub(crate) fn from_connection(connection: &ClientConnection) -> Self {
+        Self {
+            protocol_version: connection.protocol_version().map(|version| {
+                version
+                    .as_str()
+                    .map_or_else(|| format!("{version:?}"), String::from)
+            }),
+            cipher_suite: connection.negotiated_cipher_suite().map(|suite| {
+                let suite = suite.suite();
+                suite
+                    .as_str()
+                    .map_or_else(|| format!("{suite:?}"), String::from)
+            }),
+            alpn_protocol: connection
+                .alpn_protocol()
+                .map(|alpn| String::from_utf8_lossy(alpn).into_owned()),
+            peer_certificates: connection
+                .peer_certificates()
+                .unwrap_or_default()
+                .iter()
+                .map(|cert| Bytes::copy_from_slice(cert))
+                .collect(),
+        }
+    }
+
+    /// Negotiated protocol version (e.g., `TLSv1_3`).
+    #[must_use]
+    pub fn protocol_version(&self) -> Option<&str> {
+        self.protocol_version.as_deref()
+    }
+
+    /// Negotiated cipher suite (e.g., `TLS13_AES_128_GCM_SHA256`).
+    #[must_use]
+    pub fn cipher_suite(&self) -> Option<&str> {
+        self.cipher_suite.as_deref()
+    }
+
+    /// Protocol selected through ALPN (e.g., `h2`).
+    #[must_use]
+    pub fn alpn_protocol(&self) -> Option<&str> {
+        self.alpn_protocol.as_deref()
+    }
+
+    /// DER-encoded certificate chain presented by the server, leaf first.
+    #[must_use]
+    pub fn peer_certificates(&self) -> &[Bytes] {
+        &self.peer_certificates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDENTITY_PEM: &str = include_str!("../tests/fixtures/client-identity.pem");
+
+    #[test]
+    fn identity_from_pem_bundle() {
+        let identity = Identity::from_pem(IDENTITY_PEM).expect("valid identity");
+        assert!(format!("{identity:?}").contains("certificates: 1"));
+        assert!(identity.resolver().has_certs());
+    }
+
+    #[test]
+    fn identity_requires_certificate() {
+        let err = Identity::from_pem("").expect_err("empty PEM");
+        assert!(err.to_string().contains("no certificate"));
+    }
+
+    #[test]
+    fn tls_info_before_handshake() {
+        let config = rustls::ClientConfig::builder()
+            .with_root_certificates(rustls::RootCertStore::empty())
+            .with_no_client_auth();
+        let name = "example.com".try_into().expect("server name");
+        let connection = ClientConnection::new(Arc::new(config), name).expect("connection");
+
+        let info = TlsInfo::from_connection(&connection);
+        assert_eq!(info.protocol_version(), None);
+        assert_eq!(info.cipher_suite(), None);
+        assert_eq!(info.alpn_protocol(), None);
+        assert!(info.peer_certificates().is_empty());
+    }
+
+    #[test]
+    fn identity_rejects_garbage_key() {
+        let pem = "-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----\n";
+        let err = Identity::from_pem(pem).expect_err("missing key");
+        assert!(matches!(err, Error::Tls(_)));
+    }
+}
@@ -0,0 +1,317 @@
+//! Chunked upload orchestration (requires `upload` feature).
+//!
+//! Large bodies are often rejected with `413 Payload Too Large` by endpoints
+//! that also offer a chunked or resumable protocol. [`ChunkedUploader`] first
+//! sends the request as-is, and on `413` switches to the configured
+//! [`ChunkedProtocol`], uploading the body in chunks.
+//!
+//! Built-in protocols:
+//! - [`Tus`] - [tus](https://tus.io) resumable uploads (sequential chunks)
+//!
+//! For S3-style multipart uploads driven by your own API methods, see
+//! [`MultipartUpload`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use pincer::upload::{ChunkedUploader, Tus};
+//!
+//! let tus = Tus::new(client.clone(), "https://uploads.example.com/files/".parse()?);
+//! let uploader = ChunkedUploader::new(tus).chunk_size(8 * 1024 * 1024);
+//!
+//! let response = uploader.send(&client, request).await?;
+//! ```
+
+mod multipart;
+mod tus;
+
+use std::future::Future;
+
+use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt, stream};
+
+use crate::{Error, HttpClient, Request, Response, Result};
+
+pub use multipart::{CompletedPart, MultipartHooks, MultipartUpload};
+pub use tus::Tus;
+
+/// Status code of a request rejected for being too large.
+const PAYLOAD_TOO_LARGE: u16 = 413;
+
+/// Default chunk size: 5 MiB, the minimum part size of S3 multipart uploads.
+pub const DEFAULT_CHUNK_SIZE: usize = 5 * 1024 * 1024;
+
+/// Default number of chunks uploaded concurrently.
+pub const DEFAULT_PARALLELISM: usize = 4;
+
+/// A slice of the body uploaded as one request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// Zero-based position of the chunk.
+    pub index: usize,
+    /// Offset of the first byte of the chunk in the whole body.
+    pub offset: u64,
+    /// Chunk content.
+    pub data: Bytes,
+}
+
+/// Protocol uploading a body in several requests.
+pub trait ChunkedProtocol: Send + Sync {
+    /// State shared by every request of one upload (e.g., upload URL or id).
+    type Session: Send + Sync;
+    /// Result of uploading one chunk (e.g., `ETag` of an S3 part).
+    type Receipt: Send;
+
+    /// Largest number of chunks this protocol accepts concurrently.
+    ///
+    /// `None` means no limit besides the uploader parallelism.
+    fn max_parallelism(&self) -> Option<usize> {
+        None
+    }
+
+    /// Start an upload of `total_len` bytes.
+    fn start(&self, total_len: u64) -> impl Future<Output = Result<Self::Session>> + Send;
+
+    /// Upload one chunk.
+    fn upload_chunk(
+        &self,
+        session: &Self::Session,
+        chunk: Chunk,
+    ) -> impl Future<Output = Result<Self::Receipt>> + Send;
+
+    /// Complete the upload once every chunk has been uploaded.
+    ///
+    /// Receipts are given in chunk order.
+    fn complete(
+        &self,
+        session: Self::Session,
+        receipts: Vec<Self::Receipt>,
+    ) -> impl Future<Output = Result<Response<Bytes>>> + Send;
+
+    /// Abort an upload after a failure.
+    ///
+    /// The default implementation does nothing.
+    fn abort(&self, session: Self::Session) -> impl Future<Output = Result<()>> + Send {
+        let _ = session;
+        async { Ok(()) }
+    }
+}
+
+/// Upload driver falling back to a [`ChunkedProtocol`] on `413` responses.
+#[derive(Debug, Clone)]
+pub struct ChunkedUploader<P> {
+    protocol: P,
+    chunk_size: usize,
+    parallelism: usize,
+}
+
+impl<P: ChunkedProtocol> ChunkedUploader<P> {
+    /// Create an uploader with the default chunk size and parallelism.
+    #[must_use]
+    pub const fn new(protocol: P) -> Self {
+        Self {
+            protocol,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            parallelism: DEFAULT_PARALLELISM,
+        }
+    }
+
+    /// Set the chunk size in bytes (at least 1).
+    #[must_use]
+    pub const fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = if chunk_size == 0 { 1 } else { chunk_size };
+        self
+    }
+
+    /// Set how many chunks are uploaded concurrently (at least 1).
+    ///
+    /// Capped by the protocol [`max_parallelism`](ChunkedProtocol::max_parallelism).
+    #[must_use]
+    pub const fn parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = if parallelism == 0 { 1 } else { parallelism };
+        self
+    }
+
+    /// Get the protocol.
+    #[must_use]
+    pub const fn protocol(&self) -> &P {
+        &self.protocol
+    }
+
+    /// Send `request`, switching to a chunked upload of its body on `413`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails for another reason than its
+    /// size, or if the chunked upload fails.
+    pub async fn send<C: HttpClient>(
+        &self,
+        client: &C,
+        request: Request<Bytes>,
+    ) -> Result<Response<Bytes>> {
+        let body = request.body().cloned().unwrap_or_default();
+
+        match client.execute(request).await {
+            Ok(response) if response.status() == PAYLOAD_TOO_LARGE => {}
+            Err(err) if err.status() == Some(PAYLOAD_TOO_LARGE) => {}
+            other => return other,
+        }
+
+        tracing::debug!(
+            len = body.len(),
+            chunk_size = self.chunk_size,
+            "payload too large, switching to chunked upload"
+        );
+        self.upload(body).await
+    }
+
+    /// Upload `body` in chunks.
+    ///
+    /// The upload is aborted if any chunk fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if starting, uploading a chunk, or completing fails.
+    pub async fn upload(&self, body: Bytes) -> Result<Response<Bytes>> {
+        let total_len = u64::try_from(body.len())
+            .map_err(|_| Error::invalid_request("upload body too large"))?;
+        let session = self.protocol.start(total_len).await?;
+
+        let uploaded = stream::iter(chunks(&body, self.chunk_size))
+            .map(|chunk| self.protocol.upload_chunk(&session, chunk))
+            .buffered(self.effective_parallelism())
+            .try_collect::<Vec<_>>()
+            .await;
+
+        match uploaded {
+            Ok(receipts) => self.protocol.complete(session, receipts).await,
+            Err(err) => {
+                if let Err(abort_err) = self.protocol.abort(session).await {
+                    tracing::warn!(error = %abort_err, "failed to abort chunked upload");
+                }
+                Err(err)
+            }
+        }
+    }
+
+    fn effective_parallelism(&self) -> usize {
+        self.protocol
+            .max_parallelism()
+            .map_or(self.parallelism, |max| self.parallelism.min(max.max(1)))
+    }
+}
+
+/// Split `body` into chunks of at most `chunk_size` bytes.
+///
+/// An empty body still yields a single empty chunk.
+fn chunks(body: &Bytes, chunk_size: usize) -> Vec<Chunk> {
+    if body.is_empty() {
+        return vec![Chunk {
+            index: 0,
+            offset: 0,
+            data: Bytes::new(),
+        }];
+    }
+
+    (0..body.len())
+        .step_by(chunk_size)
+        .enumerate()
+        .map(|(index, start)| Chunk {
+            index,
+            offset: start as u64,
+            data: body.slice(start..body.len().min(start + chunk_size)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use http::HeaderMap;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn chunks_split_body() {
+        let body = Bytes::from_static(b"abcdefgh");
+        let chunks = chunks(&body, 3);
+
+        let data: Vec<&[u8]> = chunks.iter().map(|chunk| chunk.data.as_ref()).collect();
+        assert_eq!(data, [&b"abc"[..], b"def", b"gh"]);
+        let offsets: Vec<u64> = chunks.iter().map(|chunk| chunk.offset).collect();
+        assert_eq!(offsets, [0, 3, 6]);
+    }
+
+    #[test]
+    fn chunks_empty_body() {
+        let chunks = chunks(&Bytes::new(), 3);
+        assert_eq!(chunks.len(), 1);
+    }
+
+    /// Records uploaded chunks, failing on a given index.
+    #[derive(Default)]
+    struct Recorder {
+        fail_at: Option<usize>,
+        uploaded: Mutex<Vec<usize>>,
+        aborted: Mutex<bool>,
+    }
+
+    impl ChunkedProtocol for Recorder {
+        type Session = ();
+        type Receipt = usize;
+
+        async fn start(&self, _total_len: u64) -> Result<()> {
+            Ok(())
+        }
+
+        async fn upload_chunk(&self, _session: &(), chunk: Chunk) -> Result<usize> {
+            if self.fail_at == Some(chunk.index) {
+                return Err(Error::connection("chunk failed"));
+            }
+            self.uploaded.lock().expect("lock").push(chunk.index);
+            Ok(chunk.index)
+        }
+
+        async fn complete(&self, _session: (), receipts: Vec<usize>) -> Result<Response<Bytes>> {
+            Ok(Response::new(
+                200,
+                HeaderMap::new(),
+                Bytes::from(format!("{receipts:?}")),
+            ))
+        }
+
+        async fn abort(&self, _session: ()) -> Result<()> {
+            *self.aborted.lock().expect("lock") = true;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn upload_completes_with_ordered_receipts() {
+        let uploader = ChunkedUploader::new(Recorder::default()).chunk_size(2);
+
+        let response = uploader
+            .upload(Bytes::from_static(b"abcdefg"))
+            .await
+            .expect("upload");
+
+        assert_eq!(response.body().as_ref(), b"[0, 1, 2, 3]");
+    }
+
+    #[tokio::test]
+    async fn upload_aborts_on_failure() {
+        let recorder = Recorder {
+            fail_at: Some(1),
+            ..Recorder::default()
+        };
+        let uploader = ChunkedUploader::new(recorder).chunk_size(2).parallelism(1);
+
+        let err = uploader
+            .upload(Bytes::from_static(b"abcdefg"))
+            .await
+            .expect_err("chunk failure");
+
+        assert!(err.is_connection());
+        assert!(*uploader.protocol().aborted.lock().expect("lock"));
+    }
+}
@@ -0,0 +1,299 @@
+//! S3-style multipart upload driver.
+
+use std::future::Future;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt, stream};
+
+use super::{Chunk, DEFAULT_CHUNK_SIZE, DEFAULT_PARALLELISM, chunks};
+use crate::Result;
+
+/// Maximum number of parts in an S3 multipart upload.
+const MAX_PARTS: usize = 10_000;
+
+/// Default number of retries for a failed part.
+const DEFAULT_PART_RETRIES: u32 = 3;
+
+/// Delay before the first part retry, doubled on each attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// A part uploaded successfully.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedPart {
+    /// One-based part number.
+    pub part_number: u32,
+    /// `ETag` returned for the part.
+    pub etag: String,
+}
+
+/// Requests of a multipart upload, usually delegating to `#[pincer]` methods.
+///
+/// # Example
+///
+/// ```ignore
+/// struct BucketUpload<'a> {
+///     api: &'a StorageClient,
+///     key: String,
+/// }
+///
+/// impl MultipartHooks for BucketUpload<'_> {
+///     type Output = CompleteResult;
+///
+///     async fn initiate(&self) -> pincer::Result<String> {
+///         Ok(self.api.create_upload(&self.key).await?.upload_id)
+///     }
+///
+///     async fn upload_part(&self, upload_id: &str, part_number: u32, data: Bytes) -> pincer::Result<String> {
+///         self.api.upload_part(&self.key, upload_id, part_number, data).await
+///     }
+///
+///     async fn complete(&self, upload_id: &str, parts: Vec<CompletedPart>) -> pincer::Result<CompleteResult> {
+///         self.api.complete_upload(&self.key, upload_id, parts.into()).await
+///     }
+///
+///     async fn abort(&self, upload_id: &str) -> pincer::Result<()> {
+///         self.api.abort_upload(&self.key, upload_id).await
+///     }
+/// }
+/// ```
+pub trait MultipartHooks: Send + Sync {
+    /// Result of completing the upload.
+    type Output: Send;
+
+    /// Initiate the upload and return its id.
+    fn initiate(&self) -> impl Future<Output = Result<String>> + Send;
+
+    /// Upload one part and return its `ETag`.
+    fn upload_part(
+        &self,
+        upload_id: &str,
+        part_number: u32,
+        data: Bytes,
+    ) -> impl Future<Output = Result<String>> + Send;
+
+    /// Complete the upload. Parts are given in part number order.
+    fn complete(
+        &self,
+        upload_id: &str,
+        parts: Vec<CompletedPart>,
+    ) -> impl Future<Output = Result<Self::Output>> + Send;
+
+    /// Abort the upload, discarding uploaded parts.
+    fn abort(&self, upload_id: &str) -> impl Future<Output = Result<()>> + Send;
+}
+
+/// Driver for S3-style multipart uploads.
+///
+/// Splits the body into parts (growing the part size if needed to stay under
+/// 10 000 parts), uploads them in parallel, retries failed parts with an
+/// exponential backoff, and aborts the upload if a part keeps failing.
+#[derive(Debug, Clone)]
+pub struct MultipartUpload<H> {
+    hooks: H,
+    part_size: usize,
+    parallelism: usize,
+    part_retries: u32,
+}
+
+impl<H: MultipartHooks> MultipartUpload<H> {
+    /// Create a driver with 5 MiB parts, 4 parallel uploads and 3 retries per part.
+    #[must_use]
+    pub const fn new(hooks: H) -> Self {
+        Self {
+            hooks,
+            part_size: DEFAULT_CHUNK_SIZE,
+            parallelism: DEFAULT_PARALLELISM,
+            part_retries: DEFAULT_PART_RETRIES,
+        }
+    }
+
+    /// Set the part size in bytes (at least 1).
+    ///
+    /// S3 requires at least 5 MiB for every part but the last.
+    #[must_use]
+    pub const fn part_size(mut self, part_size: usize) -> Self {
+        self.part_size = if part_size == 0 { 1 } else { part_size };
+        self
+    }
+
+    /// Set how many parts are uploaded concurrently (at least 1).
+    #[must_use]
+    pub const fn parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = if parallelism == 0 { 1 } else { parallelism };
+        self
+    }
+
+    /// Set how many times a failed part is retried.
+    #[must_use]
+    pub const fn part_retries(mut self, retries: u32) -> Self {
+        self.part_retries = retries;
+        self
+    }
+
+    /// Get the hooks.
+    #[must_use]
+    pub const fn hooks(&self) -> &H {
+        &self.hooks
+    }
+
+    /// Upload `body` and complete the upload.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the failing step. If a part still fails after
+    /// its retries, the upload is aborted before returning.
+    pub async fn upload(&self, body: Bytes) -> Result<H::Output> {
+        let upload_id = self.hooks.initiate().await?;
+
+        let parts = stream::iter(chunks(&body, self.effective_part_size(body.len())))
+            .map(|chunk| self.upload_part(&upload_id, chunk))
+            .buffered(self.parallelism)
+            .try_collect::<Vec<_>>()
+            .await;
+
+        match parts {
+            Ok(parts) => self.hooks.complete(&upload_id, parts).await,
+            Err(err) => {
+                if let Err(abort_err) = self.hooks.abort(&upload_id).await {
+                    tracing::warn!(upload_id, error = %abort_err, "failed to abort multipart upload");
+                }
+                Err(err)
+            }
+        }
+    }
+
+    async fn upload_part(&self, upload_id: &str, chunk: Chunk) -> Result<CompletedPart> {
+        let part_number = u32::try_from(chunk.index + 1).unwrap_or(u32::MAX);
+        let mut attempt = 0;
+
+        loop {
+            match self
+                .hooks
+                .upload_part(upload_id, part_number, chunk.data.clone())
+                .await
+            {
+                Ok(etag) => return Ok(CompletedPart { part_number, etag }),
+                Err(err) if attempt < self.part_retries => {
+                    let delay = RETRY_BASE_DELAY * 2u32.saturating_pow(attempt);
+                    tracing::debug!(upload_id, part_number, attempt, error = %err, "retrying part");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Part size respecting the maximum number of parts.
+    fn effective_part_size(&self, len: usize) -> usize {
+        self.part_size.max(len.div_ceil(MAX_PARTS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+    use crate::Error;
+
+    #[derive(Default)]
+    struct Recorder {
+        failures_before_success: u32,
+        attempts: AtomicU32,
+        aborted: Mutex<Option<String>>,
+    }
+
+    impl MultipartHooks for Recorder {
+        type Output = Vec<CompletedPart>;
+
+        async fn initiate(&self) -> Result<String> {
+            Ok("upload-1".to_string())
+        }
+
+        async fn upload_part(
+            &self,
+            _upload_id: &str,
+            part_number: u32,
+            data: Bytes,
+        ) -> Result<String> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures_before_success {
+                return Err(Error::connection("reset"));
+            }
+            Ok(format!("etag-{part_number}-{}", data.len()))
+        }
+
+        async fn complete(
+            &self,
+            _upload_id: &str,
+            parts: Vec<CompletedPart>,
+        ) -> Result<Self::Output> {
+            Ok(parts)
+        }
+
+        async fn abort(&self, upload_id: &str) -> Result<()> {
+            *self.aborted.lock().expect("lock") = Some(upload_id.to_string());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn upload_numbers_parts_in_order() {
+        let upload = MultipartUpload::new(Recorder::default()).part_size(4);
+
+        let parts = upload
+            .upload(Bytes::from_static(b"0123456789"))
+            .await
+            .expect("upload");
+
+        let etags: Vec<&str> = parts.iter().map(|part| part.etag.as_str()).collect();
+        assert_eq!(etags, ["etag-1-4", "etag-2-4", "etag-3-2"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn upload_retries_failed_parts() {
+        let recorder = Recorder {
+            failures_before_success: 2,
+            ..Recorder::default()
+        };
+        let upload = MultipartUpload::new(recorder).part_size(4).parallelism(1);
+
+        let parts = upload
+            .upload(Bytes::from_static(b"0123"))
+            .await
+            .expect("upload");
+
+        assert_eq!(parts.len(), 1);
+        assert_eq!(upload.hooks().attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn upload_aborts_after_retries() {
+        let recorder = Recorder {
+            failures_before_success: u32::MAX,
+            ..Recorder::default()
+        };
+        let upload = MultipartUpload::new(recorder).part_retries(1);
+
+        let err = upload
+            .upload(Bytes::from_static(b"0123"))
+            .await
+            .expect_err("part failure");
+
+        assert!(err.is_connection());
+        assert_eq!(upload.hooks().attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            upload.hooks().aborted.lock().expect("lock").as_deref(),
+            Some("upload-1")
+        );
+    }
+
+    #[test]
+    fn part_size_grows_to_respect_max_parts() {
+        let upload = MultipartUpload::new(Recorder::default()).part_size(1);
+        assert_eq!(upload.effective_part_size(25_000), 3);
+        assert_eq!(upload.effective_part_size(10), 1);
+    }
+}
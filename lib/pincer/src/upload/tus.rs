@@ -0,0 +1,100 @@
+//! tus resumable upload protocol (core protocol and creation extension).
+
+use bytes::Bytes;
+use url::Url;
+
+use super::{Chunk, ChunkedProtocol};
+use crate::{Error, HttpClient, Method, Request, Response, Result};
+
+/// Protocol version sent in `Tus-Resumable` headers.
+const TUS_VERSION: &str = "1.0.0";
+
+/// [tus](https://tus.io/protocols/resumable-upload) upload protocol.
+///
+/// Creates the upload with a `POST` to the creation endpoint, then sends
+/// each chunk with a `PATCH` at its offset. Chunks are sent sequentially, as
+/// the core protocol requires. Aborting issues a `DELETE` (termination
+/// extension).
+#[derive(Debug, Clone)]
+pub struct Tus<C> {
+    client: C,
+    endpoint: Url,
+}
+
+impl<C: HttpClient> Tus<C> {
+    /// Create a tus protocol using `endpoint` as the creation URL.
+    #[must_use]
+    pub const fn new(client: C, endpoint: Url) -> Self {
+        Self { client, endpoint }
+    }
+
+    /// Get the creation URL.
+    #[must_use]
+    pub const fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    async fn send(&self, request: Request<Bytes>, expected: u16) -> Result<Response<Bytes>> {
+        let response = self.client.execute(request).await?;
+        if response.status() == expected {
+            return Ok(response);
+        }
+        Err(Error::http_with_body(
+            response.status(),
+            format!("unexpected tus response status {}", response.status()),
+            response.into_body(),
+        ))
+    }
+}
+
+impl<C: HttpClient> ChunkedProtocol for Tus<C> {
+    /// Upload URL returned by the creation request.
+    type Session = Url;
+    /// Response of the `PATCH` request.
+    type Receipt = Response<Bytes>;
+
+    fn max_parallelism(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    async fn start(&self, total_len: u64) -> Result<Url> {
+        let request = Request::builder(Method::Post, self.endpoint.clone())
+            .header("Tus-Resumable", TUS_VERSION)
+            .header("Upload-Length", total_len.to_string())
+            .build();
+        let response = self.send(request, 201).await?;
+
+        let location = response
+            .header("location")
+            .ok_or_else(|| Error::invalid_request("tus creation response without Location"))?;
+        Ok(self.endpoint.join(location)?)
+    }
+
+    async fn upload_chunk(&self, session: &Url, chunk: Chunk) -> Result<Response<Bytes>> {
+        let request = Request::builder(Method::Patch, session.clone())
+            .header("Tus-Resumable", TUS_VERSION)
+            .header("Upload-Offset", chunk.offset.to_string())
+            .header("Content-Type", "application/offset+octet-stream")
+            .body(chunk.data)
+            .build();
+        self.send(request, 204).await
+    }
+
+    async fn complete(
+        &self,
+        session: Url,
+        receipts: Vec<Response<Bytes>>,
+    ) -> Result<Response<Bytes>> {
+        receipts
+            .into_iter()
+            .last()
+            .ok_or_else(|| Error::invalid_request(format!("tus upload {session} sent no chunk")))
+    }
+
+    async fn abort(&self, session: Url) -> Result<()> {
+        let request = Request::builder(Method::Delete, session)
+            .header("Tus-Resumable", TUS_VERSION)
+            .build();
+        self.send(request, 204).await.map(|_| ())
+    }
+}
@@ -4,7 +4,7 @@ use pincer::{HttpClient, HyperClient, Method, Request};
 use serde::{Deserialize, Serialize};
 use wiremock::{
     Mock, MockServer, ResponseTemplate,
-    matchers::{body_json, header, method, path},
+    matchers::{body_json, header, headers, method, path},
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -176,6 +176,34 @@ async fn test_timeout() {
     assert!(err.is_timeout(), "Expected timeout error, got: {err}");
 }
 
+#[tokio::test]
+async fn test_timeout_per_method_class() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(path("/slow"))
+        .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(300)))
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::builder()
+        .read_timeout(std::time::Duration::from_millis(100))
+        .write_timeout(std::time::Duration::from_secs(5))
+        .build();
+
+    let url = url::Url::parse(&format!("{}/slow", mock_server.uri())).expect("url");
+
+    let read = Request::builder(Method::Get, url.clone()).build();
+    let err = client
+        .execute(read)
+        .await
+        .expect_err("read should time out");
+    assert!(err.is_timeout(), "Expected timeout error, got: {err}");
+
+    let write = Request::builder(Method::Post, url).build();
+    let response = client.execute(write).await.expect("write within timeout");
+    assert!(response.is_success());
+}
+
 #[tokio::test]
 async fn test_connection_error() {
     let client = HyperClient::new();
@@ -261,3 +289,614 @@ async fn test_delete_request() {
     assert!(response.is_success());
     assert_eq!(response.status(), 204);
 }
+
+#[tokio::test]
+async fn test_response_connection_extensions() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/plain"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::new();
+    let url = url::Url::parse(&format!("{}/plain", mock_server.uri())).expect("url");
+    let request = Request::builder(Method::Get, url).build();
+
+    let response = client.execute(request).await.expect("response");
+
+    // Connection metadata is forwarded, but plain HTTP carries no TLS details
+    let info = response
+        .extensions()
+        .get::<hyper_util::client::legacy::connect::HttpInfo>()
+        .expect("connection info");
+    assert_eq!(info.remote_addr(), *mock_server.address());
+    assert!(response.extensions().get::<pincer::TlsInfo>().is_none());
+
+    assert_eq!(response.http_version(), Some(http::Version::HTTP_11));
+    assert_eq!(response.remote_addr(), Some(*mock_server.address()));
+    assert_eq!(response.local_addr(), Some(info.local_addr()));
+}
+
+#[tokio::test]
+async fn test_connection_hints_pin_remote_addr() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/pinned"))
+        .and(header("connection", "close"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::new();
+    // The host does not resolve: the request only succeeds if pinned
+    let url = url::Url::parse(&format!(
+        "http://pinned.invalid:{}/pinned",
+        mock_server.address().port()
+    ))
+    .expect("url");
+    let hints = pincer::ConnectionHints::new()
+        .remote_addr(mock_server.address().ip())
+        .disable_keep_alive();
+    let request = Request::builder(Method::Get, url).extension(hints).build();
+
+    let response = client.execute(request).await.expect("response");
+
+    assert!(response.is_success());
+}
+
+#[tokio::test]
+async fn test_error_body_limit() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/failing"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("x".repeat(100_000)))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/large"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("y".repeat(100_000)))
+        .mount(&mock_server)
+        .await;
+    let request = |route: &str| {
+        let url = url::Url::parse(&format!("{}{route}", mock_server.uri())).expect("url");
+        Request::builder(Method::Get, url).build()
+    };
+
+    let client = HyperClient::new();
+    let response = client.execute(request("/failing")).await.expect("response");
+    assert_eq!(response.body().len(), pincer::DEFAULT_ERROR_BODY_LIMIT);
+    assert!(
+        response
+            .extensions()
+            .get::<pincer::TruncatedBody>()
+            .is_some()
+    );
+    let response = client.execute(request("/large")).await.expect("response");
+    assert_eq!(response.body().len(), 100_000);
+    assert!(
+        response
+            .extensions()
+            .get::<pincer::TruncatedBody>()
+            .is_none()
+    );
+
+    let client = HyperClient::builder().error_body_limit(10).build();
+    let err =
+        pincer::Error::from_response(client.execute(request("/failing")).await.expect("response"));
+    assert_eq!(err.body().map(AsRef::as_ref), Some(b"xxxxxxxxxx".as_ref()));
+}
+
+#[tokio::test]
+async fn test_error_body_preview_len() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(401).set_body_string("token=secret"))
+        .mount(&mock_server)
+        .await;
+    let url = url::Url::parse(&mock_server.uri()).expect("url");
+    let error = |client: HyperClient| {
+        let request = Request::builder(Method::Get, url.clone()).build();
+        async move { pincer::Error::from_response(client.execute(request).await.expect("response")) }
+    };
+
+    let err = error(HyperClient::new()).await;
+    assert_eq!(
+        err.to_string(),
+        "HTTP error 401: HTTP error: 401 (body: token=secret)"
+    );
+
+    let err = error(HyperClient::builder().error_body_preview_len(0).build()).await;
+    assert_eq!(err.to_string(), "HTTP error 401: HTTP error: 401");
+    assert_eq!(err.body_preview(), None);
+    assert_eq!(
+        err.body().map(AsRef::as_ref),
+        Some(b"token=secret".as_ref())
+    );
+}
+
+#[tokio::test]
+async fn test_http1_compatibility_options() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // wiremock normalizes header names and rejects folded headers, answer
+    // like an old embedded server instead
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.expect("accept");
+        let mut request = [0; 1024];
+        let read = socket.read(&mut request).await.expect("read");
+        socket
+            .write_all(
+                b"HTTP/1.1 200 OK\r\n\
+                  X-Firmware: 1.2\r\n\
+                  \tbuild 42\r\n\
+                  Content-Length: 2\r\n\
+                  Connection: close\r\n\r\n\
+                  ok",
+            )
+            .await
+            .expect("write");
+        String::from_utf8_lossy(request.get(..read).unwrap_or_default()).into_owned()
+    });
+
+    let client = HyperClient::builder()
+        .http1_only(true)
+        .http1_title_case_headers(true)
+        .http1_allow_obsolete_multiline_headers(true)
+        .http1_max_header_size(16 * 1024)
+        .build();
+    let url = url::Url::parse(&format!("http://{addr}/status")).expect("url");
+    let request = Request::builder(Method::Get, url)
+        .header("x-device-id", "42")
+        .build();
+    let response = client.execute(request).await.expect("response");
+
+    assert_eq!(
+        response
+            .headers()
+            .get("x-firmware")
+            .and_then(|v| v.to_str().ok()),
+        Some("1.2 build 42")
+    );
+    let request = server.await.expect("server");
+    assert!(request.contains("\r\nX-Device-Id: 42\r\n"), "{request}");
+}
+
+/// Start a server answering the first request of each connection, then
+/// closing it on the next request, like a server dropping idle connections.
+async fn start_stale_connection_server() -> std::io::Result<std::net::SocketAddr> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            tokio::spawn(async move {
+                let mut request = [0; 1024];
+                let _ = socket.read(&mut request).await;
+                let _ = socket
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                    .await;
+                let _ = socket.read(&mut request).await;
+            });
+        }
+    });
+    Ok(addr)
+}
+
+#[tokio::test]
+async fn test_stale_connection_retry() {
+    let addr = start_stale_connection_server().await.expect("server");
+    let url = url::Url::parse(&format!("http://{addr}/status")).expect("url");
+    let request = |method| Request::builder(method, url.clone()).build();
+
+    let client = HyperClient::new();
+    for _ in 0..3 {
+        let response = client
+            .execute(request(Method::Get))
+            .await
+            .expect("response");
+        assert_eq!(response.body().as_ref(), b"ok");
+    }
+    // The last request left its connection in the pool, but a POST is not
+    // idempotent
+    let err = client
+        .execute(request(Method::Post))
+        .await
+        .expect_err("stale connection");
+    assert!(matches!(err, pincer::Error::Connection(_)), "{err}");
+
+    let client = HyperClient::builder()
+        .retry_on_connection_failure(false)
+        .build();
+    let _ = client
+        .execute(request(Method::Get))
+        .await
+        .expect("response");
+    client
+        .execute(request(Method::Get))
+        .await
+        .expect_err("stale connection");
+}
+
+#[tokio::test]
+async fn test_inspect_head_before_the_body() {
+    use pincer::{BodyAction, InspectHead};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/failing"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("x".repeat(10_000)))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/export"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("y".repeat(10_000)))
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::new();
+    let inspect = InspectHead::new(|status, headers| {
+        let length = headers
+            .get(http::header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok()?.parse::<u64>().ok());
+        match length {
+            _ if status >= 400 => Ok(BodyAction::Limit(100)),
+            Some(length) if length > 1000 => {
+                Err(pincer::Error::invalid_request("export too large"))
+            }
+            _ => Ok(BodyAction::Collect),
+        }
+    });
+    let request = |route: &str| {
+        let url = url::Url::parse(&format!("{}{route}", mock_server.uri())).expect("url");
+        Request::builder(Method::Get, url)
+            .extension(inspect.clone())
+            .build()
+    };
+
+    let response = client.execute(request("/failing")).await.expect("response");
+    assert_eq!(response.status(), 500);
+    assert_eq!(response.body().as_ref(), "x".repeat(100).as_bytes());
+
+    let err = client
+        .execute(request("/export"))
+        .await
+        .expect_err("too large");
+    assert!(err.to_string().contains("export too large"), "{err}");
+}
+
+#[tokio::test]
+async fn test_wire_capture() {
+    use std::sync::{Arc, Mutex};
+
+    use pincer::{WireCapture, WireDirection};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/captured"))
+        .and(header("authorization", "Bearer secret"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("captured body"))
+        .mount(&mock_server)
+        .await;
+
+    let chunks = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&chunks);
+    let capture = WireCapture::new(move |chunk| {
+        sink.lock()
+            .expect("lock")
+            .push((chunk.direction(), chunk.bytes().to_vec()));
+    });
+    let client = HyperClient::builder().wire_capture(capture).build();
+    let url = url::Url::parse(&format!("{}/captured", mock_server.uri())).expect("url");
+    let request = Request::builder(Method::Get, url)
+        .header("authorization", "Bearer secret")
+        .build();
+
+    let response = client.execute(request).await.expect("response");
+    assert!(response.is_success());
+
+    let chunks = chunks.lock().expect("lock");
+    let wire = |direction| {
+        chunks
+            .iter()
+            .filter(|(d, _)| *d == direction)
+            .flat_map(|(_, bytes)| bytes.iter().copied())
+            .collect::<Vec<u8>>()
+    };
+    let sent = String::from_utf8(wire(WireDirection::Sent)).expect("utf-8");
+    assert!(sent.starts_with("GET /captured HTTP/1.1\r\n"), "{sent}");
+    assert!(sent.contains("authorization: REDACTED\r\n"), "{sent}");
+    assert!(!sent.contains("secret"), "{sent}");
+    let received = String::from_utf8(wire(WireDirection::Received)).expect("utf-8");
+    assert!(received.starts_with("HTTP/1.1 200 OK\r\n"), "{received}");
+    assert!(received.ends_with("captured body"), "{received}");
+}
+
+#[tokio::test]
+async fn test_default_locale() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/greeting"))
+        .and(headers("accept-language", vec!["de-CH", "de;q=0.9"]))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-language", "de"))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/greeting"))
+        .and(header("accept-language", "it"))
+        .respond_with(ResponseTemplate::new(200).insert_header("content-language", "it"))
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::builder()
+        .default_locale(["de-CH", "de"])
+        .build();
+    let url = url::Url::parse(&format!("{}/greeting", mock_server.uri())).expect("url");
+
+    let request = Request::builder(Method::Get, url.clone()).build();
+    let response = client.execute(request).await.expect("response");
+    assert_eq!(response.content_language(), vec!["de"]);
+
+    // The request locale wins over the default one
+    let request = Request::builder(Method::Get, url)
+        .accept_language("it")
+        .build();
+    let response = client.execute(request).await.expect("response");
+    assert_eq!(response.content_language(), vec!["it"]);
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_streaming_multipart_upload() {
+    use pincer::{Form, HttpClientStreaming, Part};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(wiremock::matchers::body_string_contains("streamed content"))
+        .respond_with(ResponseTemplate::new(201))
+        .mount(&mock_server)
+        .await;
+
+    let form = Form::new().text("name", "report").part(
+        Part::reader("file", std::io::Cursor::new("streamed content")).with_filename("r.txt"),
+    );
+    let (content_type, body) = form.into_stream();
+
+    let client = HyperClient::new();
+    let url = url::Url::parse(&format!("{}/upload", mock_server.uri())).expect("url");
+    let request = Request::builder(Method::Post, url)
+        .header("content-type", content_type)
+        .build();
+
+    let response = client
+        .execute_upload(request, body)
+        .await
+        .expect("response");
+
+    assert_eq!(response.status(), 201);
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_streaming_upload_reuses_pooled_connection() {
+    use pincer::{Form, HttpClientStreaming};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(path("/upload"))
+        .respond_with(ResponseTemplate::new(201))
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::new();
+    let url = url::Url::parse(&format!("{}/upload", mock_server.uri())).expect("url");
+
+    let response = client
+        .execute(Request::builder(Method::Get, url.clone()).build())
+        .await
+        .expect("response");
+    let pooled = response.local_addr().expect("local address");
+
+    let (content_type, body) = Form::new().text("name", "report").into_stream();
+    let request = Request::builder(Method::Post, url)
+        .header("content-type", content_type)
+        .build();
+    let response = client
+        .execute_upload(request, body)
+        .await
+        .expect("response");
+
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.local_addr(), Some(pooled));
+}
+
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_streaming_response_trailers() {
+    use futures_util::StreamExt;
+    use pincer::HttpClientStreaming;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // wiremock cannot send trailers, answer with a raw chunked response
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.expect("accept");
+        let mut request = [0; 1024];
+        let _ = socket.read(&mut request).await.expect("read");
+        socket
+            .write_all(
+                b"HTTP/1.1 200 OK\r\n\
+                  Transfer-Encoding: chunked\r\n\
+                  Trailer: x-checksum\r\n\r\n\
+                  5\r\nhello\r\n0\r\n\
+                  x-checksum: abc123\r\n\r\n",
+            )
+            .await
+            .expect("write");
+    });
+
+    let client = HyperClient::new();
+    let url = url::Url::parse(&format!("http://{addr}/object")).expect("url");
+    let request = Request::builder(Method::Get, url)
+        .header("te", "trailers")
+        .build();
+    let response = client.execute_streaming(request).await.expect("response");
+
+    let trailers = response.trailers();
+    let mut body = response.into_body();
+    let mut content = Vec::new();
+    while let Some(chunk) = body.next().await {
+        content.extend_from_slice(&chunk.expect("chunk"));
+    }
+    assert_eq!(content, b"hello");
+
+    let trailers = trailers.await.expect("trailers");
+    assert_eq!(
+        trailers.get("x-checksum").and_then(|v| v.to_str().ok()),
+        Some("abc123")
+    );
+}
+
+#[cfg(feature = "upload")]
+#[tokio::test]
+async fn test_chunked_upload_fallback_on_413() {
+    use pincer::upload::{ChunkedUploader, Tus};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("PUT"))
+        .and(path("/upload"))
+        .respond_with(ResponseTemplate::new(413))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/files"))
+        .and(header("upload-length", "10"))
+        .respond_with(ResponseTemplate::new(201).insert_header("Location", "/files/abc"))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("PATCH"))
+        .and(path("/files/abc"))
+        .and(header("content-type", "application/offset+octet-stream"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::new();
+    let endpoint = url::Url::parse(&format!("{}/files", mock_server.uri())).expect("url");
+    let uploader = ChunkedUploader::new(Tus::new(client.clone(), endpoint)).chunk_size(4);
+
+    let url = url::Url::parse(&format!("{}/upload", mock_server.uri())).expect("url");
+    let request = Request::builder(Method::Put, url)
+        .body(bytes::Bytes::from_static(b"0123456789"))
+        .build();
+
+    let response = uploader.send(&client, request).await.expect("response");
+
+    assert_eq!(response.status(), 204);
+}
+
+#[tokio::test]
+async fn test_dynamic_client_call() {
+    let mock_server = MockServer::start().await;
+
+    let user = User {
+        id: 5,
+        name: "Eve".to_string(),
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/users/5"))
+        .and(wiremock::matchers::query_param("fields", "name"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&user))
+        .mount(&mock_server)
+        .await;
+
+    let registry = pincer::EndpointRegistry::from_json(
+        br#"{"getUser": {"method": "GET", "path": "/users/{id}"}}"#,
+    )
+    .expect("registry");
+    let api = pincer::ApiClient::new(HyperClient::new(), mock_server.uri()).expect("client");
+    let client = pincer::DynamicClient::new(api, registry);
+
+    let response = client
+        .call("getUser", [("id", "5"), ("fields", "name")])
+        .await
+        .expect("call");
+    assert_eq!(response.json::<User>().expect("json"), user);
+
+    let err = client
+        .call("getUser", [("id", "6")])
+        .await
+        .expect_err("not found");
+    assert!(err.is_client_error(), "{err}");
+}
+
+#[tokio::test]
+async fn test_from_hyper_client() {
+    use hyper_util::client::legacy::{Client, connect::HttpConnector};
+    use hyper_util::rt::TokioExecutor;
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/echo"))
+        .and(body_json(serde_json::json!({"name": "Alice"})))
+        .respond_with(ResponseTemplate::new(201).insert_header("x-served-by", "mock"))
+        .mount(&mock_server)
+        .await;
+
+    let hyper = Client::builder(TokioExecutor::new()).build(HttpConnector::new());
+    let client = HyperClient::from_hyper(hyper, pincer::ClientConfig::default());
+
+    let url = url::Url::parse(&format!("{}/echo", mock_server.uri())).expect("url");
+    let request = Request::builder(Method::Post, url)
+        .json(&serde_json::json!({"name": "Alice"}))
+        .expect("json")
+        .build();
+
+    let response = client.execute(request).await.expect("response");
+    assert_eq!(response.status(), 201);
+    assert_eq!(response.header("x-served-by"), Some("mock"));
+    assert!(response.extensions().get::<http::Version>().is_some());
+}
+
+#[tokio::test]
+async fn test_warmup_opens_connections() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("HEAD"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::new();
+    client.warmup(mock_server.uri(), 3).await.expect("warmup");
+
+    let err = client
+        .warmup("http://127.0.0.1:1", 1)
+        .await
+        .expect_err("unreachable host");
+    assert!(err.is_connection(), "Expected connection error, got: {err}");
+}
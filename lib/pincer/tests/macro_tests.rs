@@ -9,7 +9,7 @@ use wiremock::{
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-struct User {
+pub struct User {
     id: u64,
     name: String,
 }
@@ -109,12 +109,29 @@ async fn test_http_method_attribute() {
     assert_eq!(result, users);
 }
 
+#[test]
+fn test_requests_build_without_sending() {
+    let base_url: pincer::url::Url = "https://api.example.com".parse().expect("url");
+
+    let request = UserApiRequests::get_user(&base_url, 42).expect("request");
+    assert_eq!(request.method(), pincer::Method::Get);
+    assert_eq!(request.url().as_str(), "https://api.example.com/users/42");
+    assert_eq!(request.header("accept"), Some("application/json"));
+    assert_eq!(
+        request
+            .extensions()
+            .get::<pincer::PathTemplate>()
+            .map(pincer::PathTemplate::as_str),
+        Some("/users/{id}")
+    );
+}
+
 // ============================================================================
 // Tests for new features: path encoding, Vec<T> query, struct query
 // ============================================================================
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-struct SearchResult {
+pub struct SearchResult {
     query: String,
     count: u32,
 }
@@ -560,6 +577,98 @@ async fn test_timeout_attribute() {
     assert!(err.is_timeout(), "should be timeout error");
 }
 
+#[tokio::test]
+async fn test_timeout_for_overrides_attribute() {
+    let mock_server = MockServer::start().await;
+
+    let user = User {
+        id: 1,
+        name: "Slow User".to_string(),
+    };
+
+    // Delay within the 1s attribute timeout, but above the runtime override
+    Mock::given(method("GET"))
+        .and(path("/slow"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(&user)
+                .set_delay(std::time::Duration::from_millis(500)),
+        )
+        .mount(&mock_server)
+        .await;
+
+    let client = ErrorHandlingApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .timeout_for("slow_endpoint", std::time::Duration::from_millis(100))
+        .build()
+        .expect("build client");
+
+    let err = client.slow_endpoint().await.expect_err("should timeout");
+    assert!(err.is_timeout(), "should be timeout error");
+}
+
+#[test]
+fn test_timeout_for_unknown_method() {
+    let result = ErrorHandlingApiClientBuilder::default()
+        .timeout_for("missing", std::time::Duration::from_secs(1))
+        .build();
+
+    let err = result.err().expect("unknown method should fail");
+    assert!(err.to_string().contains("missing"));
+}
+
+/// Runtime whose timers fire immediately.
+struct ExpiredRuntime;
+
+impl pincer::Runtime for ExpiredRuntime {
+    fn sleep(&self, _duration: std::time::Duration) -> pincer::Sleep {
+        Box::pin(std::future::ready(()))
+    }
+
+    fn spawn(&self, _future: std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>>) {}
+}
+
+/// Client whose requests never complete, with its own runtime
+#[derive(Clone)]
+struct PendingClient {
+    base_url: pincer::url::Url,
+}
+
+impl pincer::PincerClient for PendingClient {
+    async fn execute(
+        &self,
+        _request: pincer::Request<bytes::Bytes>,
+    ) -> pincer::Result<pincer::Response<bytes::Bytes>> {
+        std::future::pending().await
+    }
+
+    fn base_url(&self) -> &pincer::url::Url {
+        &self.base_url
+    }
+
+    fn runtime(&self) -> Option<&dyn pincer::Runtime> {
+        Some(&ExpiredRuntime)
+    }
+}
+
+#[pincer(mode = "impl_for(PendingClient)")]
+pub trait SlowApi {
+    #[get("/slow")]
+    #[timeout("60s")]
+    async fn slow_endpoint(&self) -> pincer::Result<User>;
+}
+
+#[tokio::test]
+async fn test_timeout_uses_client_runtime() {
+    let client = PendingClient {
+        base_url: "http://localhost:9999".parse().expect("url"),
+    };
+
+    // The runtime of the client fires the 60s timer immediately
+    let err = client.slow_endpoint().await.expect_err("should timeout");
+    assert!(err.is_timeout(), "should be timeout error");
+}
+
 #[tokio::test]
 async fn test_error_body_preserved_for_decode_body() {
     let mock_server = MockServer::start().await;
@@ -612,6 +721,15 @@ pub trait ReturnTypesApi {
         #[path] id: u64,
     ) -> pincer::Result<Option<pincer::Response<bytes::Bytes>>>;
 
+    /// Test raw body return Result<Bytes>
+    #[get("/files/{id}")]
+    async fn download(&self, #[path] id: u64) -> pincer::Result<bytes::Bytes>;
+
+    /// Test raw body with `not_found_as_none`
+    #[get("/files/{id}")]
+    #[not_found_as_none]
+    async fn download_optional(&self, #[path] id: u64) -> pincer::Result<Option<bytes::Bytes>>;
+
     /// Test unit return type Result<()>
     #[delete("/items/{id}")]
     async fn delete_item(&self, #[path] id: u64) -> pincer::Result<()>;
@@ -691,12 +809,18 @@ async fn test_raw_response_with_not_found_as_none() {
 }
 
 #[tokio::test]
-async fn test_unit_return_type() {
+async fn test_bytes_return_type() {
     let mock_server = MockServer::start().await;
 
-    Mock::given(method("DELETE"))
-        .and(path("/items/42"))
-        .respond_with(ResponseTemplate::new(204))
+    Mock::given(method("GET"))
+        .and(path("/files/1"))
+        .and(header("accept", "*/*"))
+        .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![0x89, b'P', b'N', b'G']))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/files/2"))
+        .respond_with(ResponseTemplate::new(404))
         .mount(&mock_server)
         .await;
 
@@ -705,17 +829,22 @@ async fn test_unit_return_type() {
         .build()
         .expect("build client");
 
-    // Should succeed with no return value
-    client.delete_item(42).await.expect("should succeed");
+    let body = client.download(1).await.expect("should succeed");
+    assert_eq!(body.as_ref(), [0x89, b'P', b'N', b'G']);
+
+    let err = client.download(2).await.expect_err("should fail");
+    assert!(err.is_not_found());
+    let body = client.download_optional(2).await.expect("should not error");
+    assert!(body.is_none());
 }
 
 #[tokio::test]
-async fn test_unit_return_type_error_on_failure() {
+async fn test_unit_return_type() {
     let mock_server = MockServer::start().await;
 
     Mock::given(method("DELETE"))
         .and(path("/items/42"))
-        .respond_with(ResponseTemplate::new(500).set_body_string("Server Error"))
+        .respond_with(ResponseTemplate::new(204))
         .mount(&mock_server)
         .await;
 
@@ -724,746 +853,2729 @@ async fn test_unit_return_type_error_on_failure() {
         .build()
         .expect("build client");
 
-    let result = client.delete_item(42).await;
-    let err = result.expect_err("should error on 500");
-    assert!(err.is_server_error());
+    // Should succeed with no return value
+    client.delete_item(42).await.expect("should succeed");
+}
+
+#[pincer(url = "http://localhost:9999")]
+pub trait TimestampApi {
+    #[get("/reports/{day}")]
+    async fn report(
+        &self,
+        #[path(format = "%Y-%m-%d")] day: std::time::SystemTime,
+        #[query(format = "rfc3339")] since: &std::time::SystemTime,
+        #[query(format = "unix")] until: Option<std::time::SystemTime>,
+    ) -> pincer::Result<()>;
 }
 
 #[tokio::test]
-async fn test_unit_return_type_with_not_found_as_none() {
+async fn test_timestamp_params() {
     let mock_server = MockServer::start().await;
 
-    // 404 should return Ok(None)
-    Mock::given(method("DELETE"))
-        .and(path("/items/999/soft"))
-        .respond_with(ResponseTemplate::new(404))
-        .mount(&mock_server)
-        .await;
-
-    // 204 should return Ok(Some(()))
-    Mock::given(method("DELETE"))
-        .and(path("/items/42/soft"))
+    Mock::given(method("GET"))
+        .and(path("/reports/2023-11-14"))
+        .and(query_param("since", "2023-11-14T22:13:20Z"))
+        .and(query_param("until", "1700000000"))
         .respond_with(ResponseTemplate::new(204))
+        .expect(1)
         .mount(&mock_server)
         .await;
 
-    let client = ReturnTypesApiClientBuilder::default()
+    let client = TimestampApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
-    // 404 returns None
-    let result = client
-        .soft_delete_item(999)
+    let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+    client
+        .report(time, &time, Some(time))
         .await
-        .expect("should not error");
-    assert!(result.is_none());
-
-    // 204 returns Some(())
-    let result = client.soft_delete_item(42).await.expect("should succeed");
-    assert!(result.is_some());
+        .expect("should succeed");
 }
 
-// ============================================================================
-// Tests for collection format query parameters
-// ============================================================================
+#[derive(Debug, Clone, Query)]
+pub struct ChargeFilter {
+    status: String,
+    #[query(format = "deep_object")]
+    created: Option<std::collections::BTreeMap<String, u64>>,
+}
 
 #[pincer(url = "http://localhost:9999")]
-pub trait CollectionFormatApi {
-    /// Default format (multi): ?tags=a&tags=b&tags=c
-    #[get("/search")]
-    async fn search_multi(&self, #[query] tags: Vec<String>) -> pincer::Result<SearchResult>;
-
-    /// CSV format: ?tags=a,b,c
-    #[get("/search-csv")]
-    async fn search_csv(
-        &self,
-        #[query(format = "csv")] tags: Vec<String>,
-    ) -> pincer::Result<SearchResult>;
-
-    /// SSV (space-separated) format: ?tags=a%20b%20c
-    #[get("/search-ssv")]
-    async fn search_ssv(
-        &self,
-        #[query(format = "ssv")] tags: Vec<String>,
-    ) -> pincer::Result<SearchResult>;
-
-    /// Pipes format: ?tags=a|b|c
-    #[get("/search-pipes")]
-    async fn search_pipes(
+pub trait DeepObjectApi {
+    #[get("/charges")]
+    async fn charges(
         &self,
-        #[query(format = "pipes")] tags: Vec<String>,
-    ) -> pincer::Result<SearchResult>;
+        #[query(format = "deep_object")] filter: &ChargeFilter,
+        #[query(format = "deep_object")] metadata: Option<
+            std::collections::HashMap<String, String>,
+        >,
+    ) -> pincer::Result<()>;
 }
 
 #[tokio::test]
-async fn test_collection_format_csv() {
+async fn test_deep_object_query_params() {
     let mock_server = MockServer::start().await;
 
-    let result = SearchResult {
-        query: "csv".to_string(),
-        count: 3,
-    };
-
-    // CSV format should produce a single param with comma-separated values
     Mock::given(method("GET"))
-        .and(path("/search-csv"))
-        .and(query_param("tags", "rust,http,async"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
+        .and(path("/charges"))
+        .and(query_param("filter[status]", "paid"))
+        .and(query_param("filter[created][gte]", "1700000000"))
+        .and(query_param("metadata[order]", "42"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
         .mount(&mock_server)
         .await;
 
-    let client = CollectionFormatApiClientBuilder::default()
+    let client = DeepObjectApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
-    let response = client
-        .search_csv(vec![
-            "rust".to_string(),
-            "http".to_string(),
-            "async".to_string(),
-        ])
+    let filter = ChargeFilter {
+        status: "paid".to_string(),
+        created: Some([("gte".to_string(), 1_700_000_000)].into()),
+    };
+    let metadata = [("order".to_string(), "42".to_string())].into();
+    client
+        .charges(&filter, Some(metadata))
         .await
-        .expect("search");
-    assert_eq!(response.query, "csv");
+        .expect("should succeed");
+}
+
+#[pincer(url = "http://localhost:9999", max_url_length = 128)]
+pub trait LongQueryApi {
+    #[get("/search")]
+    #[post_fallback]
+    async fn search(&self, #[query] q: &str) -> pincer::Result<()>;
+
+    #[get("/lookup")]
+    async fn lookup(&self, #[query] q: &str) -> pincer::Result<()>;
 }
 
 #[tokio::test]
-async fn test_collection_format_ssv() {
+async fn test_long_query_post_fallback() {
     let mock_server = MockServer::start().await;
+    let q = "a".repeat(200);
 
-    let result = SearchResult {
-        query: "ssv".to_string(),
-        count: 3,
-    };
+    Mock::given(method("POST"))
+        .and(path("/search"))
+        .and(header("x-http-method-override", "GET"))
+        .and(wiremock::matchers::body_string(format!("q={q}")))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let base_url: pincer::url::Url = mock_server.uri().parse().expect("url");
+    let request = LongQueryApiRequests::search(&base_url, &q).expect("request");
+    assert_eq!(request.method(), pincer::Method::Post);
+
+    let client = LongQueryApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+
+    client.search(&q).await.expect("should fall back to POST");
+    let err = client.lookup(&q).await.expect_err("URL too long");
+    assert!(matches!(err, pincer::Error::UrlTooLong { max: 128, .. }));
+}
+
+#[pincer(url = "http://localhost:9999")]
+pub trait UriTemplateApi {
+    #[get("/files{/segments*}{.ext}{?q,page,tags}")]
+    async fn files(
+        &self,
+        segments: &[&str],
+        ext: Option<&str>,
+        q: &str,
+        page: Option<u32>,
+        tags: Vec<String>,
+    ) -> pincer::Result<()>;
+
+    #[get("/raw/{+file}{?flags*}")]
+    async fn raw(&self, file: &str, flags: &[&str]) -> pincer::Result<()>;
+}
+
+#[tokio::test]
+async fn test_uri_template_paths() {
+    let mock_server = MockServer::start().await;
 
-    // SSV format should produce a single param with space-separated values (URL encoded)
     Mock::given(method("GET"))
-        .and(path("/search-ssv"))
-        .and(query_param("tags", "rust http async"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
+        .and(path("/files/docs/a%20b.txt"))
+        .and(query_param("q", "x&y"))
+        .and(query_param("tags", "a,b"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/files"))
+        .and(query_param("q", ""))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/raw/dir/file.txt"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
         .mount(&mock_server)
         .await;
 
-    let client = CollectionFormatApiClientBuilder::default()
+    let client = UriTemplateApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
-    let response = client
-        .search_ssv(vec![
-            "rust".to_string(),
-            "http".to_string(),
-            "async".to_string(),
-        ])
+    client
+        .files(
+            &["docs", "a b"],
+            Some("txt"),
+            "x&y",
+            None,
+            vec!["a".to_string(), "b".to_string()],
+        )
         .await
-        .expect("search");
-    assert_eq!(response.query, "ssv");
+        .expect("full expansion");
+    client
+        .files(&[], None, "", None, Vec::new())
+        .await
+        .expect("empty expansion");
+    client
+        .raw("dir/file.txt", &["a", "b"])
+        .await
+        .expect("reserved expansion");
+
+    let requests = mock_server.received_requests().await.expect("requests");
+    let urls: Vec<_> = requests
+        .iter()
+        .map(|request| {
+            let url = &request.url;
+            format!("{}?{}", url.path(), url.query().unwrap_or_default())
+        })
+        .collect();
+    assert_eq!(
+        urls,
+        [
+            "/files/docs/a%20b.txt?q=x%26y&tags=a,b",
+            "/files?q=",
+            "/raw/dir/file.txt?flags=a&flags=b",
+        ]
+    );
+}
+
+#[pincer(url = "http://localhost:9999")]
+pub trait RawPathApi {
+    #[get("/objects/{id}/{name}")]
+    async fn object(&self, #[path(raw)] id: &str, name: &str) -> pincer::Result<()>;
 }
 
 #[tokio::test]
-async fn test_collection_format_pipes() {
+async fn test_raw_path_params() {
     let mock_server = MockServer::start().await;
 
-    let result = SearchResult {
-        query: "pipes".to_string(),
-        count: 3,
-    };
-
-    // Pipes format should produce a single param with pipe-separated values
     Mock::given(method("GET"))
-        .and(path("/search-pipes"))
-        .and(query_param("tags", "rust|http|async"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
         .mount(&mock_server)
         .await;
 
-    let client = CollectionFormatApiClientBuilder::default()
+    let client = RawPathApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
-    let response = client
-        .search_pipes(vec![
-            "rust".to_string(),
-            "http".to_string(),
-            "async".to_string(),
-        ])
+    client
+        .object("bucket%2Fkey", "a/b")
         .await
-        .expect("search");
-    assert_eq!(response.query, "pipes");
+        .expect("should succeed");
+
+    let requests = mock_server.received_requests().await.expect("requests");
+    let paths: Vec<_> = requests.iter().map(|request| request.url.path()).collect();
+    assert_eq!(paths, ["/objects/bucket%2Fkey/a%2Fb"]);
+}
+
+#[pincer(url = "http://localhost:9999")]
+pub trait TypedHeadersApi {
+    #[get("/traced")]
+    async fn traced(
+        &self,
+        #[header("X-Request-Id")] request_id: u64,
+        #[header("X-Trace-Id")] trace_id: Option<&str>,
+        #[header("X-Retry")] retry: Option<u32>,
+    ) -> pincer::Result<SearchResult>;
 }
 
 #[tokio::test]
-async fn test_collection_format_empty_vec() {
+async fn test_typed_and_optional_headers() {
     let mock_server = MockServer::start().await;
 
     let result = SearchResult {
-        query: "empty".to_string(),
-        count: 0,
+        query: "traced".to_string(),
+        count: 1,
     };
-
-    // Empty vec should not add any query param
     Mock::given(method("GET"))
-        .and(path("/search-csv"))
+        .and(path("/traced"))
+        .and(header("X-Request-Id", "42"))
+        .and(header("X-Trace-Id", "abc"))
         .respond_with(ResponseTemplate::new(200).set_body_json(&result))
+        .expect(1)
         .mount(&mock_server)
         .await;
 
-    let client = CollectionFormatApiClientBuilder::default()
+    let client = TypedHeadersApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
-    let response = client.search_csv(vec![]).await.expect("search");
-    assert_eq!(response.query, "empty");
+    let response = client.traced(42, Some("abc"), None).await.expect("traced");
+    assert_eq!(response.query, "traced");
+
+    let requests = mock_server.received_requests().await.expect("requests");
+    let headers = requests.first().map(|request| &request.headers);
+    assert!(headers.is_some_and(|headers| !headers.contains_key("x-retry")));
 }
 
-// ============================================================================
-// Multipart Upload Tests
-// ============================================================================
+#[tokio::test]
+async fn test_unit_return_type_error_on_failure() {
+    let mock_server = MockServer::start().await;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct UploadResult {
-    received_parts: usize,
-    total_bytes: usize,
-}
+    Mock::given(method("DELETE"))
+        .and(path("/items/42"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("Server Error"))
+        .mount(&mock_server)
+        .await;
 
-#[pincer(url = "http://localhost")]
-trait MultipartApi {
-    #[post("/upload")]
-    async fn upload_file(&self, #[multipart] file: pincer::Part) -> pincer::Result<UploadResult>;
+    let client = ReturnTypesApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
 
-    #[post("/upload-named")]
-    async fn upload_named(
-        &self,
-        #[multipart(name = "document")] file: pincer::Part,
-    ) -> pincer::Result<UploadResult>;
+    let result = client.delete_item(42).await;
+    let err = result.expect_err("should error on 500");
+    assert!(err.is_server_error());
+    // The body of an error response is still read
+    assert_eq!(
+        err.body().map(bytes::Bytes::as_ref),
+        Some(b"Server Error".as_slice())
+    );
 }
 
+#[cfg(feature = "middleware-decompression")]
 #[tokio::test]
-async fn test_multipart_upload_single_file() {
+async fn test_unit_return_type_discards_the_body() {
     let mock_server = MockServer::start().await;
 
-    let result = UploadResult {
-        received_parts: 1,
-        total_bytes: 11,
-    };
-
-    Mock::given(method("POST"))
-        .and(path("/upload"))
-        .and(wiremock::matchers::header_exists("content-type"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
+    // A corrupted gzip body would fail decompression if it was read
+    Mock::given(method("DELETE"))
+        .and(path("/items/42"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-encoding", "gzip")
+                .set_body_bytes(vec![0_u8; 64 * 1024]),
+        )
         .mount(&mock_server)
         .await;
 
-    let client = MultipartApiClientBuilder::default()
+    let base_url: pincer::url::Url = mock_server.uri().parse().expect("url");
+    let request = ReturnTypesApiRequests::delete_item(&base_url, 42).expect("request");
+    assert!(request.extensions().get::<pincer::DiscardBody>().is_some());
+
+    let client = ReturnTypesApiClientBuilder::default()
         .base_url(mock_server.uri())
+        .client(HyperClient::builder().with_decompression().build())
         .build()
         .expect("build client");
 
-    let part = pincer::Part::file("upload", "test.txt", "hello world");
-    let response = client.upload_file(part).await.expect("upload");
-    assert_eq!(response.received_parts, 1);
-    assert_eq!(response.total_bytes, 11);
+    client.delete_item(42).await.expect("should succeed");
 }
 
 #[tokio::test]
-async fn test_multipart_upload_with_custom_name() {
+async fn test_unit_return_type_with_not_found_as_none() {
     let mock_server = MockServer::start().await;
 
-    let result = UploadResult {
-        received_parts: 1,
-        total_bytes: 5,
-    };
+    // 404 should return Ok(None)
+    Mock::given(method("DELETE"))
+        .and(path("/items/999/soft"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
 
-    Mock::given(method("POST"))
-        .and(path("/upload-named"))
-        .and(wiremock::matchers::header_exists("content-type"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
+    // 204 should return Ok(Some(()))
+    Mock::given(method("DELETE"))
+        .and(path("/items/42/soft"))
+        .respond_with(ResponseTemplate::new(204))
         .mount(&mock_server)
         .await;
 
-    let client = MultipartApiClientBuilder::default()
+    let client = ReturnTypesApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
-    let part = pincer::Part::file("doc", "report.pdf", "data!");
-    let response = client.upload_named(part).await.expect("upload");
-    assert_eq!(response.received_parts, 1);
-    assert_eq!(response.total_bytes, 5);
+    // 404 returns None
+    let result = client
+        .soft_delete_item(999)
+        .await
+        .expect("should not error");
+    assert!(result.is_none());
+
+    // 204 returns Some(())
+    let result = client.soft_delete_item(42).await.expect("should succeed");
+    assert!(result.is_some());
 }
 
 // ============================================================================
-// Auto Path Detection Tests (Feature 1)
+// Tests for the maximum body length
 // ============================================================================
 
-#[pincer(url = "http://localhost")]
-trait AutoPathApi {
-    /// Path param `id` auto-detected from URL placeholder `{id}`
+#[pincer(url = "http://localhost:9999")]
+pub trait MaxBodyApi {
     #[get("/users/{id}")]
-    async fn get_user(&self, id: u64) -> pincer::Result<User>;
-
-    /// Multiple path params auto-detected
-    #[get("/repos/{owner}/{repo}")]
-    async fn get_repo(&self, owner: &str, repo: &str) -> pincer::Result<SearchResult>;
-
-    /// Explicit path alias when param name differs from placeholder
-    #[get("/users/{user_id}")]
-    async fn get_user_aliased(&self, #[path("user_id")] id: u64) -> pincer::Result<User>;
+    #[max_body(64)]
+    async fn get_user(&self, #[path] id: u64) -> pincer::Result<User>;
 }
 
 #[tokio::test]
-async fn test_auto_path_detection() {
+async fn test_max_body() {
     let mock_server = MockServer::start().await;
 
-    let user = User {
-        id: 42,
-        name: "Alice".to_string(),
-    };
-
     Mock::given(method("GET"))
-        .and(path("/users/42"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&user))
+        .and(path("/users/1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": 1,
+            "name": "Alice",
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/users/2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "id": 2,
+            "name": "x".repeat(100),
+        })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/users/3"))
+        .respond_with(ResponseTemplate::new(500).set_body_string("e".repeat(100)))
         .mount(&mock_server)
         .await;
 
-    let client = AutoPathApiClientBuilder::default()
+    let client = MaxBodyApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
-    let response = client.get_user(42).await.expect("get user");
-    assert_eq!(response.id, 42);
-    assert_eq!(response.name, "Alice");
+    let user = client.get_user(1).await.expect("small body");
+    assert_eq!(user.name, "Alice");
+    let err = client.get_user(2).await.expect_err("body over the limit");
+    assert!(
+        err.to_string().contains("over the limit of 64 bytes"),
+        "{err}"
+    );
+    // Error bodies are not limited
+    let err = client.get_user(3).await.expect_err("server error");
+    assert_eq!(err.body().map(bytes::Bytes::len), Some(100));
+}
+
+// ============================================================================
+// Tests for collection format query parameters
+// ============================================================================
+
+#[pincer(url = "http://localhost:9999")]
+pub trait CollectionFormatApi {
+    /// Default format (multi): ?tags=a&tags=b&tags=c
+    #[get("/search")]
+    async fn search_multi(&self, #[query] tags: Vec<String>) -> pincer::Result<SearchResult>;
+
+    /// CSV format: ?tags=a,b,c
+    #[get("/search-csv")]
+    async fn search_csv(
+        &self,
+        #[query(format = "csv")] tags: Vec<String>,
+    ) -> pincer::Result<SearchResult>;
+
+    /// SSV (space-separated) format: ?tags=a%20b%20c
+    #[get("/search-ssv")]
+    async fn search_ssv(
+        &self,
+        #[query(format = "ssv")] tags: Vec<String>,
+    ) -> pincer::Result<SearchResult>;
+
+    /// Pipes format: ?tags=a|b|c
+    #[get("/search-pipes")]
+    async fn search_pipes(
+        &self,
+        #[query(format = "pipes")] tags: Vec<String>,
+    ) -> pincer::Result<SearchResult>;
 }
 
 #[tokio::test]
-async fn test_auto_path_detection_multiple() {
+async fn test_collection_format_csv() {
     let mock_server = MockServer::start().await;
 
     let result = SearchResult {
-        query: "repo".to_string(),
-        count: 100,
+        query: "csv".to_string(),
+        count: 3,
     };
 
+    // CSV format should produce a single param with comma-separated values
     Mock::given(method("GET"))
-        .and(path("/repos/rust-lang/rust"))
+        .and(path("/search-csv"))
+        .and(query_param("tags", "rust,http,async"))
         .respond_with(ResponseTemplate::new(200).set_body_json(&result))
         .mount(&mock_server)
         .await;
 
-    let client = AutoPathApiClientBuilder::default()
+    let client = CollectionFormatApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
     let response = client
-        .get_repo("rust-lang", "rust")
+        .search_csv(vec![
+            "rust".to_string(),
+            "http".to_string(),
+            "async".to_string(),
+        ])
         .await
-        .expect("get repo");
-    assert_eq!(response.query, "repo");
+        .expect("search");
+    assert_eq!(response.query, "csv");
 }
 
 #[tokio::test]
-async fn test_auto_path_detection_with_alias() {
+async fn test_collection_format_ssv() {
     let mock_server = MockServer::start().await;
 
-    let user = User {
-        id: 123,
-        name: "Bob".to_string(),
+    let result = SearchResult {
+        query: "ssv".to_string(),
+        count: 3,
     };
 
+    // SSV format should produce a single param with space-separated values (URL encoded)
     Mock::given(method("GET"))
-        .and(path("/users/123"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&user))
+        .and(path("/search-ssv"))
+        .and(query_param("tags", "rust http async"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
         .mount(&mock_server)
         .await;
 
-    let client = AutoPathApiClientBuilder::default()
+    let client = CollectionFormatApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
-    let response = client.get_user_aliased(123).await.expect("get user");
-    assert_eq!(response.id, 123);
-}
-
-// ============================================================================
-// Auto Body Detection Tests (Feature 2)
-// ============================================================================
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct CreateUser {
-    name: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct UpdateUser {
-    name: String,
-}
-
-#[pincer(url = "http://localhost")]
-trait AutoBodyApi {
-    /// Body param auto-detected (no explicit #[body])
-    #[post("/users")]
-    async fn create_user(&self, user: &CreateUser) -> pincer::Result<User>;
-
-    /// Path auto-detected, body auto-detected
-    #[put("/users/{id}")]
-    async fn update_user(&self, id: u64, user: &UpdateUser) -> pincer::Result<User>;
+    let response = client
+        .search_ssv(vec![
+            "rust".to_string(),
+            "http".to_string(),
+            "async".to_string(),
+        ])
+        .await
+        .expect("search");
+    assert_eq!(response.query, "ssv");
 }
 
 #[tokio::test]
-async fn test_auto_body_detection() {
+async fn test_collection_format_pipes() {
     let mock_server = MockServer::start().await;
 
-    let created_user = User {
-        id: 1,
-        name: "Charlie".to_string(),
+    let result = SearchResult {
+        query: "pipes".to_string(),
+        count: 3,
     };
 
-    Mock::given(method("POST"))
-        .and(path("/users"))
-        .and(header("content-type", "application/json"))
-        .respond_with(ResponseTemplate::new(201).set_body_json(&created_user))
+    // Pipes format should produce a single param with pipe-separated values
+    Mock::given(method("GET"))
+        .and(path("/search-pipes"))
+        .and(query_param("tags", "rust|http|async"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
         .mount(&mock_server)
         .await;
 
-    let client = AutoBodyApiClientBuilder::default()
+    let client = CollectionFormatApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
-    let new_user = CreateUser {
-        name: "Charlie".to_string(),
-    };
-    let response = client.create_user(&new_user).await.expect("create user");
-    assert_eq!(response.id, 1);
-    assert_eq!(response.name, "Charlie");
+    let response = client
+        .search_pipes(vec![
+            "rust".to_string(),
+            "http".to_string(),
+            "async".to_string(),
+        ])
+        .await
+        .expect("search");
+    assert_eq!(response.query, "pipes");
 }
 
 #[tokio::test]
-async fn test_auto_body_with_auto_path() {
+async fn test_collection_format_empty_vec() {
     let mock_server = MockServer::start().await;
 
-    let updated_user = User {
-        id: 42,
-        name: "Updated".to_string(),
+    let result = SearchResult {
+        query: "empty".to_string(),
+        count: 0,
     };
 
-    Mock::given(method("PUT"))
-        .and(path("/users/42"))
-        .and(header("content-type", "application/json"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&updated_user))
+    // Empty vec should not add any query param
+    Mock::given(method("GET"))
+        .and(path("/search-csv"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
         .mount(&mock_server)
         .await;
 
-    let client = AutoBodyApiClientBuilder::default()
+    let client = CollectionFormatApiClientBuilder::default()
         .base_url(mock_server.uri())
         .build()
         .expect("build client");
 
-    let update = UpdateUser {
-        name: "Updated".to_string(),
-    };
-    let response = client.update_user(42, &update).await.expect("update user");
-    assert_eq!(response.id, 42);
-    assert_eq!(response.name, "Updated");
+    let response = client.search_csv(vec![]).await.expect("search");
+    assert_eq!(response.query, "empty");
 }
 
 // ============================================================================
-// Query Derive Macro Tests (Feature 3)
+// Multipart Upload Tests
 // ============================================================================
 
-#[derive(Debug, Clone, Query)]
-struct QueryDeriveTest {
-    q: String,
-    page: Option<u32>,
-    #[query(rename = "per_page")]
-    limit: u32,
-    #[query(format = "csv")]
-    tags: Vec<String>,
+#[derive(Debug, Serialize, Deserialize)]
+struct UploadResult {
+    received_parts: usize,
+    total_bytes: usize,
 }
 
-#[test]
-fn test_query_derive_basic() {
-    let params = QueryDeriveTest {
-        q: "rust".to_string(),
-        page: Some(2),
-        limit: 10,
-        tags: vec!["a".to_string(), "b".to_string()],
+#[pincer(url = "http://localhost")]
+trait MultipartApi {
+    #[post("/upload")]
+    async fn upload_file(&self, #[multipart] file: pincer::Part) -> pincer::Result<UploadResult>;
+
+    #[post("/upload-named")]
+    async fn upload_named(
+        &self,
+        #[multipart(name = "document")] file: pincer::Part,
+    ) -> pincer::Result<UploadResult>;
+}
+
+#[tokio::test]
+async fn test_multipart_upload_single_file() {
+    let mock_server = MockServer::start().await;
+
+    let result = UploadResult {
+        received_parts: 1,
+        total_bytes: 11,
     };
 
-    let pairs = params.to_query_pairs();
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(wiremock::matchers::header_exists("content-type"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
+        .mount(&mock_server)
+        .await;
 
-    assert!(pairs.contains(&("q".to_string(), "rust".to_string())));
-    assert!(pairs.contains(&("page".to_string(), "2".to_string())));
-    assert!(pairs.contains(&("per_page".to_string(), "10".to_string())));
-    assert!(pairs.contains(&("tags".to_string(), "a,b".to_string())));
+    let client = MultipartApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+
+    let part = pincer::Part::file("upload", "test.txt", "hello world");
+    let response = client.upload_file(part).await.expect("upload");
+    assert_eq!(response.received_parts, 1);
+    assert_eq!(response.total_bytes, 11);
 }
 
-#[test]
-fn test_query_derive_skip_none() {
-    let params = QueryDeriveTest {
-        q: "rust".to_string(),
-        page: None, // should be skipped
-        limit: 10,
-        tags: vec![],
+#[tokio::test]
+async fn test_multipart_upload_with_custom_name() {
+    let mock_server = MockServer::start().await;
+
+    let result = UploadResult {
+        received_parts: 1,
+        total_bytes: 5,
     };
 
-    let pairs = params.to_query_pairs();
+    Mock::given(method("POST"))
+        .and(path("/upload-named"))
+        .and(wiremock::matchers::header_exists("content-type"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
+        .mount(&mock_server)
+        .await;
 
-    assert!(pairs.contains(&("q".to_string(), "rust".to_string())));
-    assert!(!pairs.iter().any(|(k, _)| k == "page"));
-    assert!(pairs.contains(&("per_page".to_string(), "10".to_string())));
-    // empty vec doesn't add anything with csv format
-    assert!(!pairs.iter().any(|(k, _)| k == "tags"));
+    let client = MultipartApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+
+    let part = pincer::Part::file("doc", "report.pdf", "data!");
+    let response = client.upload_named(part).await.expect("upload");
+    assert_eq!(response.received_parts, 1);
+    assert_eq!(response.total_bytes, 5);
 }
 
-#[derive(Debug, Clone, Query)]
-#[allow(clippy::struct_field_names)]
-struct QueryDeriveMultiFormat {
-    #[query(format = "multi")]
-    multi_tags: Vec<String>,
-    #[query(format = "ssv")]
-    ssv_tags: Vec<String>,
-    #[query(format = "pipes")]
-    pipe_tags: Vec<String>,
+#[cfg(feature = "streaming")]
+#[tokio::test]
+async fn test_multipart_upload_rejects_reader_part() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&mock_server)
+        .await;
+
+    let client = MultipartApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+
+    let part = pincer::Part::reader("upload", std::io::Cursor::new("hello world"));
+    let err = client.upload_file(part).await.expect_err("reader part");
+    assert!(err.to_string().contains("multipart part file"));
 }
 
-#[test]
-fn test_query_derive_collection_formats() {
-    let params = QueryDeriveMultiFormat {
-        multi_tags: vec!["a".to_string(), "b".to_string()],
-        ssv_tags: vec!["c".to_string(), "d".to_string()],
-        pipe_tags: vec!["e".to_string(), "f".to_string()],
-    };
+// ============================================================================
+// Auto Path Detection Tests (Feature 1)
+// ============================================================================
 
-    let pairs = params.to_query_pairs();
+#[pincer(url = "http://localhost")]
+trait AutoPathApi {
+    /// Path param `id` auto-detected from URL placeholder `{id}`
+    #[get("/users/{id}")]
+    async fn get_user(&self, id: u64) -> pincer::Result<User>;
 
-    // Multi format produces multiple pairs
-    assert!(pairs.contains(&("multi_tags".to_string(), "a".to_string())));
-    assert!(pairs.contains(&("multi_tags".to_string(), "b".to_string())));
+    /// Multiple path params auto-detected
+    #[get("/repos/{owner}/{repo}")]
+    async fn get_repo(&self, owner: &str, repo: &str) -> pincer::Result<SearchResult>;
 
-    // SSV format produces space-separated value
-    assert!(pairs.contains(&("ssv_tags".to_string(), "c d".to_string())));
+    /// Explicit path alias when param name differs from placeholder
+    #[get("/users/{user_id}")]
+    async fn get_user_aliased(&self, #[path("user_id")] id: u64) -> pincer::Result<User>;
+}
 
-    // Pipes format produces pipe-separated value
-    assert!(pairs.contains(&("pipe_tags".to_string(), "e|f".to_string())));
+#[tokio::test]
+async fn test_auto_path_detection() {
+    let mock_server = MockServer::start().await;
+
+    let user = User {
+        id: 42,
+        name: "Alice".to_string(),
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/users/42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&user))
+        .mount(&mock_server)
+        .await;
+
+    let client = AutoPathApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+
+    let response = client.get_user(42).await.expect("get user");
+    assert_eq!(response.id, 42);
+    assert_eq!(response.name, "Alice");
 }
 
-// Test rename_all with camelCase
-#[derive(Debug, Clone, Query)]
-#[query(rename_all = "camelCase")]
-struct QueryDeriveCamelCase {
-    search_query: String,
-    page_number: u32,
-    #[query(rename = "limit")] // explicit rename overrides rename_all
-    per_page: u32,
+#[tokio::test]
+async fn test_auto_path_detection_multiple() {
+    let mock_server = MockServer::start().await;
+
+    let result = SearchResult {
+        query: "repo".to_string(),
+        count: 100,
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/repos/rust-lang/rust"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&result))
+        .mount(&mock_server)
+        .await;
+
+    let client = AutoPathApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+
+    let response = client
+        .get_repo("rust-lang", "rust")
+        .await
+        .expect("get repo");
+    assert_eq!(response.query, "repo");
 }
 
-#[test]
-fn test_query_derive_rename_all_camel_case() {
-    let params = QueryDeriveCamelCase {
-        search_query: "rust".to_string(),
-        page_number: 1,
-        per_page: 10,
+#[tokio::test]
+async fn test_auto_path_detection_with_alias() {
+    let mock_server = MockServer::start().await;
+
+    let user = User {
+        id: 123,
+        name: "Bob".to_string(),
     };
 
-    let pairs = params.to_query_pairs();
+    Mock::given(method("GET"))
+        .and(path("/users/123"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&user))
+        .mount(&mock_server)
+        .await;
+
+    let client = AutoPathApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+
+    let response = client.get_user_aliased(123).await.expect("get user");
+    assert_eq!(response.id, 123);
+}
+
+// ============================================================================
+// Auto Body Detection Tests (Feature 2)
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CreateUser {
+    name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UpdateUser {
+    name: String,
+}
+
+#[pincer(url = "http://localhost")]
+trait AutoBodyApi {
+    /// Body param auto-detected (no explicit #[body])
+    #[post("/users")]
+    async fn create_user(&self, user: &CreateUser) -> pincer::Result<User>;
+
+    /// Path auto-detected, body auto-detected
+    #[put("/users/{id}")]
+    async fn update_user(&self, id: u64, user: &UpdateUser) -> pincer::Result<User>;
+}
+
+#[tokio::test]
+async fn test_auto_body_detection() {
+    let mock_server = MockServer::start().await;
+
+    let created_user = User {
+        id: 1,
+        name: "Charlie".to_string(),
+    };
+
+    Mock::given(method("POST"))
+        .and(path("/users"))
+        .and(header("content-type", "application/json"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(&created_user))
+        .mount(&mock_server)
+        .await;
+
+    let client = AutoBodyApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+
+    let new_user = CreateUser {
+        name: "Charlie".to_string(),
+    };
+    let response = client.create_user(&new_user).await.expect("create user");
+    assert_eq!(response.id, 1);
+    assert_eq!(response.name, "Charlie");
+}
+
+#[tokio::test]
+async fn test_auto_body_with_auto_path() {
+    let mock_server = MockServer::start().await;
+
+    let updated_user = User {
+        id: 42,
+        name: "Updated".to_string(),
+    };
+
+    Mock::given(method("PUT"))
+        .and(path("/users/42"))
+        .and(header("content-type", "application/json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&updated_user))
+        .mount(&mock_server)
+        .await;
+
+    let client = AutoBodyApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+
+    let update = UpdateUser {
+        name: "Updated".to_string(),
+    };
+    let response = client.update_user(42, &update).await.expect("update user");
+    assert_eq!(response.id, 42);
+    assert_eq!(response.name, "Updated");
+}
+
+// ============================================================================
+// Query Derive Macro Tests (Feature 3)
+// ============================================================================
+
+#[derive(Debug, Clone, Query)]
+struct QueryDeriveTest {
+    q: String,
+    page: Option<u32>,
+    #[query(rename = "per_page")]
+    limit: u32,
+    #[query(format = "csv")]
+    tags: Vec<String>,
+}
+
+#[test]
+fn test_query_derive_basic() {
+    let params = QueryDeriveTest {
+        q: "rust".to_string(),
+        page: Some(2),
+        limit: 10,
+        tags: vec!["a".to_string(), "b".to_string()],
+    };
+
+    let pairs = params.to_query_pairs();
+
+    assert!(pairs.contains(&("q".to_string(), "rust".to_string())));
+    assert!(pairs.contains(&("page".to_string(), "2".to_string())));
+    assert!(pairs.contains(&("per_page".to_string(), "10".to_string())));
+    assert!(pairs.contains(&("tags".to_string(), "a,b".to_string())));
+}
+
+#[test]
+fn test_query_derive_skip_none() {
+    let params = QueryDeriveTest {
+        q: "rust".to_string(),
+        page: None, // should be skipped
+        limit: 10,
+        tags: vec![],
+    };
+
+    let pairs = params.to_query_pairs();
+
+    assert!(pairs.contains(&("q".to_string(), "rust".to_string())));
+    assert!(!pairs.iter().any(|(k, _)| k == "page"));
+    assert!(pairs.contains(&("per_page".to_string(), "10".to_string())));
+    // empty vec doesn't add anything with csv format
+    assert!(!pairs.iter().any(|(k, _)| k == "tags"));
+}
+
+#[derive(Debug, Clone, Query)]
+#[allow(clippy::struct_field_names)]
+struct QueryDeriveMultiFormat {
+    #[query(format = "multi")]
+    multi_tags: Vec<String>,
+    #[query(format = "ssv")]
+    ssv_tags: Vec<String>,
+    #[query(format = "pipes")]
+    pipe_tags: Vec<String>,
+}
+
+#[test]
+fn test_query_derive_collection_formats() {
+    let params = QueryDeriveMultiFormat {
+        multi_tags: vec!["a".to_string(), "b".to_string()],
+        ssv_tags: vec!["c".to_string(), "d".to_string()],
+        pipe_tags: vec!["e".to_string(), "f".to_string()],
+    };
+
+    let pairs = params.to_query_pairs();
+
+    // Multi format produces multiple pairs
+    assert!(pairs.contains(&("multi_tags".to_string(), "a".to_string())));
+    assert!(pairs.contains(&("multi_tags".to_string(), "b".to_string())));
+
+    // SSV format produces space-separated value
+    assert!(pairs.contains(&("ssv_tags".to_string(), "c d".to_string())));
+
+    // Pipes format produces pipe-separated value
+    assert!(pairs.contains(&("pipe_tags".to_string(), "e|f".to_string())));
+}
+
+// Test rename_all with camelCase
+#[derive(Debug, Clone, Query)]
+#[query(rename_all = "camelCase")]
+struct QueryDeriveCamelCase {
+    search_query: String,
+    page_number: u32,
+    #[query(rename = "limit")] // explicit rename overrides rename_all
+    per_page: u32,
+}
+
+#[test]
+fn test_query_derive_rename_all_camel_case() {
+    let params = QueryDeriveCamelCase {
+        search_query: "rust".to_string(),
+        page_number: 1,
+        per_page: 10,
+    };
+
+    let pairs = params.to_query_pairs();
+
+    // snake_case fields are converted to camelCase
+    assert!(pairs.contains(&("searchQuery".to_string(), "rust".to_string())));
+    assert!(pairs.contains(&("pageNumber".to_string(), "1".to_string())));
+    // explicit rename overrides rename_all
+    assert!(pairs.contains(&("limit".to_string(), "10".to_string())));
+}
+
+// Test rename_all with kebab-case
+#[derive(Debug, Clone, Query)]
+#[query(rename_all = "kebab-case")]
+struct QueryDeriveKebabCase {
+    search_query: String,
+    page_number: u32,
+}
+
+#[test]
+fn test_query_derive_rename_all_kebab_case() {
+    let params = QueryDeriveKebabCase {
+        search_query: "rust".to_string(),
+        page_number: 1,
+    };
+
+    let pairs = params.to_query_pairs();
+
+    // snake_case fields are converted to kebab-case
+    assert!(pairs.contains(&("search-query".to_string(), "rust".to_string())));
+    assert!(pairs.contains(&("page-number".to_string(), "1".to_string())));
+}
+
+// Test rename_all with SCREAMING_SNAKE_CASE
+#[derive(Debug, Clone, Query)]
+#[query(rename_all = "SCREAMING_SNAKE_CASE")]
+struct QueryDeriveScreamingSnake {
+    search_query: String,
+    page_number: u32,
+}
+
+#[test]
+fn test_query_derive_rename_all_screaming_snake() {
+    let params = QueryDeriveScreamingSnake {
+        search_query: "rust".to_string(),
+        page_number: 1,
+    };
+
+    let pairs = params.to_query_pairs();
+
+    // snake_case fields are converted to SCREAMING_SNAKE_CASE
+    assert!(pairs.contains(&("SEARCH_QUERY".to_string(), "rust".to_string())));
+    assert!(pairs.contains(&("PAGE_NUMBER".to_string(), "1".to_string())));
+}
+
+// Test flatten with a shared nested struct
+#[derive(Debug, Clone, Query)]
+struct Pagination {
+    page: u32,
+    per_page: Option<u32>,
+}
+
+#[derive(Debug, Clone, Query)]
+#[query(rename_all = "camelCase")]
+struct QueryDeriveFlatten {
+    search_query: String,
+    #[query(flatten)]
+    pagination: Pagination,
+    #[query(flatten)]
+    next: Option<Pagination>,
+}
+
+#[test]
+fn test_query_derive_flatten() {
+    let params = QueryDeriveFlatten {
+        search_query: "rust".to_string(),
+        pagination: Pagination {
+            page: 2,
+            per_page: Some(50),
+        },
+        next: None,
+    };
+
+    let pairs = params.to_query_pairs();
+
+    // nested pairs keep their own keys, in field order
+    assert_eq!(
+        pairs,
+        vec![
+            ("searchQuery".to_string(), "rust".to_string()),
+            ("page".to_string(), "2".to_string()),
+            ("per_page".to_string(), "50".to_string()),
+        ]
+    );
+
+    let params = QueryDeriveFlatten {
+        next: Some(Pagination {
+            page: 3,
+            per_page: None,
+        }),
+        ..params
+    };
+    assert!(
+        params
+            .to_query_pairs()
+            .contains(&("page".to_string(), "3".to_string()))
+    );
+}
+
+// Test deepObject fields
+#[derive(Debug, Clone, Query)]
+struct QueryDeriveDeepObject {
+    #[query(format = "deep_object")]
+    page: Pagination,
+    #[query(format = "deepObject", rename = "meta")]
+    metadata: Option<std::collections::BTreeMap<String, String>>,
+}
+
+#[test]
+fn test_query_derive_deep_object() {
+    let params = QueryDeriveDeepObject {
+        page: Pagination {
+            page: 2,
+            per_page: None,
+        },
+        metadata: Some([("a".to_string(), "1".to_string())].into()),
+    };
+
+    assert_eq!(
+        params.to_query_pairs(),
+        vec![
+            ("page[page]".to_string(), "2".to_string()),
+            ("meta[a]".to_string(), "1".to_string()),
+        ]
+    );
+
+    let params = QueryDeriveDeepObject {
+        metadata: None,
+        ..params
+    };
+    assert_eq!(params.to_query_pairs().len(), 1);
+}
+
+// Test skip, serialize_with and skip_if
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn epoch_millis(secs: &u64) -> String {
+    (secs * 1000).to_string()
+}
+
+#[allow(clippy::ref_option, clippy::trivially_copy_pass_by_ref)]
+fn is_first_page(page: &Option<u32>) -> bool {
+    *page == Some(1)
+}
+
+#[derive(Debug, Clone, Query)]
+struct QueryDeriveCustom {
+    #[query(serialize_with = "epoch_millis")]
+    since: u64,
+    #[query(serialize_with = "epoch_millis")]
+    until: Option<u64>,
+    #[query(serialize_with = "epoch_millis", format = "csv")]
+    at: Vec<u64>,
+    #[query(skip_if = "is_first_page")]
+    page: Option<u32>,
+    #[query(skip_if = "String::is_empty")]
+    cursor: String,
+    #[query(skip)]
+    #[allow(dead_code)]
+    cache_key: String,
+}
+
+#[test]
+fn test_query_derive_custom_serialization() {
+    let params = QueryDeriveCustom {
+        since: 1,
+        until: Some(2),
+        at: vec![3, 4],
+        page: Some(1),
+        cursor: String::new(),
+        cache_key: "local".to_string(),
+    };
+
+    assert_eq!(
+        params.to_query_pairs(),
+        vec![
+            ("since".to_string(), "1000".to_string()),
+            ("until".to_string(), "2000".to_string()),
+            ("at".to_string(), "3000,4000".to_string()),
+        ]
+    );
+
+    let params = QueryDeriveCustom {
+        page: Some(2),
+        cursor: "abc".to_string(),
+        ..params
+    };
+    let pairs = params.to_query_pairs();
+    assert!(pairs.contains(&("page".to_string(), "2".to_string())));
+    assert!(pairs.contains(&("cursor".to_string(), "abc".to_string())));
+}
+
+// Test generic structs and map fields
+#[derive(Debug, Clone, Query)]
+struct QueryDeriveGeneric<T: std::fmt::Display, F>
+where
+    F: std::fmt::Display,
+{
+    value: T,
+    #[query(format = "csv")]
+    fields: Vec<F>,
+    labels: std::collections::BTreeMap<String, T>,
+    #[query(serialize_with = "ToString::to_string")]
+    extra: std::collections::HashMap<&'static str, u32>,
+}
+
+#[test]
+fn test_query_derive_generics_and_maps() {
+    let params = QueryDeriveGeneric {
+        value: 42,
+        fields: vec!["id", "name"],
+        labels: [("env".to_string(), 1), ("app".to_string(), 2)].into(),
+        extra: [("limit", 10)].into(),
+    };
+
+    assert_eq!(
+        params.to_query_pairs(),
+        vec![
+            ("value".to_string(), "42".to_string()),
+            ("fields".to_string(), "id,name".to_string()),
+            // BTreeMap entries are sorted by key
+            ("app".to_string(), "2".to_string()),
+            ("env".to_string(), "1".to_string()),
+            ("limit".to_string(), "10".to_string()),
+        ]
+    );
+}
+
+// Test enums of fieldless and struct variants
+#[derive(Debug, Clone, Copy, Query)]
+#[query(rename_all = "snake_case")]
+enum QuerySort {
+    NameAsc,
+    NameDesc,
+    #[query(rename = "newest")]
+    CreatedDesc,
+}
+
+#[derive(Debug, Clone, Query)]
+#[query(rename_all = "camelCase")]
+enum QueryFilter {
+    All,
+    Range {
+        min_price: u32,
+        max_price: Option<u32>,
+    },
+    Tagged {
+        #[query(format = "csv")]
+        tags: Vec<String>,
+        #[query(skip)]
+        #[allow(dead_code)]
+        pairs: u32,
+    },
+}
+
+#[derive(Debug, Clone, Query)]
+struct QueryDeriveEnums {
+    sort: QuerySort,
+    #[query(flatten)]
+    filter: QueryFilter,
+}
+
+/// Fieldless enum with its own `Display`, for humans
+#[derive(Debug, Clone, Copy, Query)]
+#[query(rename_all = "lowercase", no_display)]
+enum QueryOrder {
+    Ascending,
+    Descending,
+}
+
+impl std::fmt::Display for QueryOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Ascending => "A to Z",
+            Self::Descending => "Z to A",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Query)]
+struct QueryDeriveOrder {
+    #[query(serialize_with = "QueryOrder::as_query_value")]
+    order: QueryOrder,
+}
+
+#[test]
+fn test_query_derive_enums() {
+    assert_eq!(QuerySort::NameAsc.to_string(), "name_asc");
+    assert_eq!(QuerySort::CreatedDesc.to_string(), "newest");
+    assert_eq!(QuerySort::CreatedDesc.as_query_value(), "newest");
+
+    assert_eq!(QueryOrder::Ascending.to_string(), "A to Z");
+    assert_eq!(QueryOrder::Descending.to_string(), "Z to A");
+    let params = QueryDeriveOrder {
+        order: QueryOrder::Descending,
+    };
+    assert_eq!(
+        params.to_query_pairs(),
+        vec![("order".to_string(), "descending".to_string())]
+    );
+
+    let params = QueryDeriveEnums {
+        sort: QuerySort::NameDesc,
+        filter: QueryFilter::Range {
+            min_price: 10,
+            max_price: None,
+        },
+    };
+    assert_eq!(
+        params.to_query_pairs(),
+        vec![
+            ("sort".to_string(), "name_desc".to_string()),
+            ("minPrice".to_string(), "10".to_string()),
+        ]
+    );
+
+    let params = QueryDeriveEnums {
+        sort: QuerySort::NameAsc,
+        filter: QueryFilter::All,
+    };
+    assert_eq!(
+        params.to_query_pairs(),
+        vec![("sort".to_string(), "name_asc".to_string())]
+    );
+
+    let filter = QueryFilter::Tagged {
+        tags: vec!["a".to_string(), "b".to_string()],
+        pairs: 0,
+    };
+    assert_eq!(
+        filter.to_query_pairs(),
+        vec![("tags".to_string(), "a,b".to_string())]
+    );
+}
+
+// Test timestamp formats
+#[derive(Debug, Clone, Query)]
+struct QueryDeriveTimestamps {
+    #[query(format = "rfc3339")]
+    since: std::time::SystemTime,
+    #[query(format = "unix_millis")]
+    until: Option<std::time::SystemTime>,
+    #[query(format = "%Y-%m-%d")]
+    days: Vec<std::time::SystemTime>,
+}
+
+#[test]
+fn test_query_derive_timestamps() {
+    let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+    let params = QueryDeriveTimestamps {
+        since: time,
+        until: Some(time),
+        days: vec![time],
+    };
+
+    assert_eq!(
+        params.to_query_pairs(),
+        vec![
+            ("since".to_string(), "2023-11-14T22:13:20Z".to_string()),
+            ("until".to_string(), "1700000000000".to_string()),
+            ("days".to_string(), "2023-11-14".to_string()),
+        ]
+    );
+}
+
+// ============================================================================
+// Wrapper Mode Tests (mode = "wrapper")
+// ============================================================================
+
+/// Test wrapper mode: generates a generic wrapper struct that works with any `PincerClient`
+#[pincer(url = "http://localhost:9999", mode = "wrapper")]
+pub trait WrapperModeApi {
+    #[get("/users/{id}")]
+    async fn get_user(&self, #[path] id: u64) -> pincer::Result<User>;
+
+    #[get("/health")]
+    async fn health(&self) -> pincer::Result<()>;
+}
+
+#[tokio::test]
+async fn test_wrapper_mode_with_api_client() {
+    let mock_server = MockServer::start().await;
+
+    let user = User {
+        id: 42,
+        name: "Alice".to_string(),
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/users/42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&user))
+        .mount(&mock_server)
+        .await;
+
+    // Create a HyperClient and wrap it with ApiClient
+    let http = pincer::HyperClient::new();
+    let api = pincer::ApiClient::new(http, mock_server.uri()).expect("api client");
+
+    // Use the wrapper struct with the ApiClient and mock server URL
+    let base_url = pincer::url::Url::parse(&mock_server.uri()).expect("parse url");
+    let client = WrapperModeApiClient::with_base_url(api, base_url);
+
+    // Use explicit trait method call to disambiguate from ImplOnlyApi blanket impl
+    let result = WrapperModeApi::get_user(&client, 42)
+        .await
+        .expect("get user");
+    assert_eq!(result.id, 42);
+    assert_eq!(result.name, "Alice");
+}
+
+#[tokio::test]
+async fn test_wrapper_mode_with_custom_base_url() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    // Create ApiClient and wrap with WrapperModeApiClient using custom base URL
+    let http = pincer::HyperClient::new();
+    let api = pincer::ApiClient::new(http, mock_server.uri()).expect("api client");
+    let base_url = pincer::url::Url::parse(&mock_server.uri()).expect("parse url");
+
+    let client = WrapperModeApiClient::with_base_url(api, base_url);
+
+    client.health().await.expect("health check");
+}
+
+#[tokio::test]
+async fn test_wrapper_mode_builder() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .and(header("x-team", "core"))
+        .and(header("user-agent", "wrapper/1.0"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let http = pincer::HyperClient::new();
+    let api = pincer::ApiClient::new(http, mock_server.uri()).expect("api client");
+    let client = WrapperModeApiClient::builder()
+        .client(api)
+        .base_url(mock_server.uri())
+        .header("x-team", "core")
+        .user_agent("wrapper/1.0")
+        .build()
+        .expect("wrapper client");
+
+    assert_eq!(
+        client.base_url().as_str(),
+        format!("{}/", mock_server.uri())
+    );
+    WrapperModeApi::health(&client).await.expect("health check");
+}
+
+#[test]
+fn test_wrapper_mode_builder_errors() {
+    let missing = WrapperModeApiClient::<pincer::HyperClient>::builder().build();
+    assert!(missing.is_err());
+
+    let invalid = WrapperModeApiClient::builder()
+        .client(pincer::HyperClient::new())
+        .base_url("not a url")
+        .build();
+    assert!(invalid.is_err());
+
+    let invalid = WrapperModeApiClient::builder()
+        .client(pincer::HyperClient::new())
+        .header("bad header", "value")
+        .build();
+    assert!(invalid.is_err());
+}
+
+#[test]
+fn test_wrapper_mode_struct_is_clone() {
+    // Verify the wrapper struct implements Clone when the inner client is Clone
+    let http = pincer::HyperClient::new();
+    let api = pincer::ApiClient::new(http, "http://localhost").expect("api client");
+    let client = WrapperModeApiClient::new(api).expect("wrapper client");
+    let _cloned = client.clone();
+}
+
+// ============================================================================
+// Impl-Only Mode Tests (mode = "impl_only")
+// ============================================================================
+
+/// Test `impl_only` mode: generates a blanket impl for any `PincerClient`
+#[pincer(mode = "impl_only")]
+pub trait ImplOnlyApi {
+    #[get("/users/{id}")]
+    async fn get_user(&self, #[path] id: u64) -> pincer::Result<User>;
+
+    #[post("/users")]
+    async fn create_user(&self, user: &CreateUser) -> pincer::Result<User>;
+}
+
+#[tokio::test]
+async fn test_impl_only_mode_with_api_client() {
+    let mock_server = MockServer::start().await;
+
+    let user = User {
+        id: 99,
+        name: "Bob".to_string(),
+    };
+
+    Mock::given(method("GET"))
+        .and(path("/users/99"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&user))
+        .mount(&mock_server)
+        .await;
+
+    // Create ApiClient - it directly implements ImplOnlyApi via blanket impl
+    let http = pincer::HyperClient::new();
+    let api = pincer::ApiClient::new(http, mock_server.uri()).expect("api client");
+
+    // ApiClient directly implements ImplOnlyApi
+    let result = api.get_user(99).await.expect("get user");
+    assert_eq!(result.id, 99);
+    assert_eq!(result.name, "Bob");
+}
+
+#[tokio::test]
+async fn test_impl_only_mode_post_request() {
+    let mock_server = MockServer::start().await;
+
+    let created_user = User {
+        id: 1,
+        name: "Charlie".to_string(),
+    };
+
+    Mock::given(method("POST"))
+        .and(path("/users"))
+        .and(header("content-type", "application/json"))
+        .respond_with(ResponseTemplate::new(201).set_body_json(&created_user))
+        .mount(&mock_server)
+        .await;
+
+    let http = pincer::HyperClient::new();
+    let api = pincer::ApiClient::new(http, mock_server.uri()).expect("api client");
+
+    let new_user = CreateUser {
+        name: "Charlie".to_string(),
+    };
+    let result = api.create_user(&new_user).await.expect("create user");
+    assert_eq!(result.id, 1);
+    assert_eq!(result.name, "Charlie");
+}
+
+/// Client answering every request with the same user, for swapping in tests
+#[derive(Clone)]
+struct StaticUserClient {
+    base_url: pincer::url::Url,
+    user: User,
+}
+
+impl pincer::PincerClient for StaticUserClient {
+    fn execute(
+        &self,
+        _request: pincer::Request<bytes::Bytes>,
+    ) -> impl std::future::Future<Output = pincer::Result<pincer::Response<bytes::Bytes>>> + Send
+    {
+        let body = pincer::to_json(&self.user);
+        async move {
+            Ok(pincer::Response::new(
+                200,
+                pincer::header::HeaderMap::new(),
+                body?,
+            ))
+        }
+    }
+
+    fn base_url(&self) -> &pincer::url::Url {
+        &self.base_url
+    }
+}
+
+#[tokio::test]
+async fn test_impl_only_mode_with_dyn_clients() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(User {
+            id: 7,
+            name: "Real".to_string(),
+        }))
+        .mount(&mock_server)
+        .await;
+
+    let real =
+        pincer::ApiClient::new(pincer::HyperClient::new(), mock_server.uri()).expect("api client");
+    let fake = StaticUserClient {
+        base_url: pincer::url::Url::parse("http://localhost").expect("url"),
+        user: User {
+            id: 7,
+            name: "Fake".to_string(),
+        },
+    };
+
+    let mut registry: std::collections::HashMap<&str, Box<dyn pincer::DynPincerClient>> =
+        std::collections::HashMap::new();
+    registry.insert("real", Box::new(real));
+    registry.insert("fake", Box::new(fake.clone()));
+
+    let client = registry.get("real").expect("real client").clone();
+    assert_eq!(client.get_user(7).await.expect("get user").name, "Real");
+    let client = registry.get("fake").expect("fake client");
+    assert_eq!(client.get_user(7).await.expect("get user").name, "Fake");
+
+    let shared: std::sync::Arc<dyn pincer::DynPincerClient> = std::sync::Arc::new(fake);
+    assert_eq!(shared.get_user(7).await.expect("get user").name, "Fake");
+    assert_eq!(
+        pincer::PincerClient::base_url(&shared).as_str(),
+        "http://localhost/"
+    );
+}
+
+// ============================================================================
+// Dyn Trait Tests (dyn_trait)
+// ============================================================================
+
+/// Test `dyn_trait`: generates the object-safe `DirectoryApiDyn`
+#[pincer(url = "http://localhost:9999", dyn_trait)]
+pub trait DirectoryApi {
+    #[get("/users/{id}")]
+    async fn get_user(&self, #[path] id: u64) -> pincer::Result<User>;
+
+    #[post("/users")]
+    async fn create_user(&self, user: &CreateUser) -> pincer::Result<User>;
+}
+
+/// Hand-written implementation, as used in tests of dependent services
+struct FakeDirectory;
+
+impl DirectoryApi for FakeDirectory {
+    async fn get_user(&self, id: u64) -> pincer::Result<User> {
+        Ok(User {
+            id,
+            name: "Fake".to_string(),
+        })
+    }
+
+    async fn create_user(&self, user: &CreateUser) -> pincer::Result<User> {
+        Ok(User {
+            id: 0,
+            name: user.name.clone(),
+        })
+    }
+}
+
+/// Service depending on the API without being generic over it
+struct Greeter {
+    directory: std::sync::Arc<dyn DirectoryApiDyn>,
+}
+
+impl Greeter {
+    async fn greet(&self, id: u64) -> pincer::Result<String> {
+        let user = self.directory.get_user(id).await?;
+        Ok(format!("Hello, {}!", user.name))
+    }
+}
+
+#[tokio::test]
+async fn test_dyn_trait_with_generated_and_fake_clients() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(User {
+            id: 3,
+            name: "Real".to_string(),
+        }))
+        .mount(&mock_server)
+        .await;
+
+    let client = DirectoryApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+    let real = Greeter {
+        directory: std::sync::Arc::new(client),
+    };
+    assert_eq!(real.greet(3).await.expect("greet"), "Hello, Real!");
+
+    let fake = Greeter {
+        directory: std::sync::Arc::new(FakeDirectory),
+    };
+    assert_eq!(fake.greet(3).await.expect("greet"), "Hello, Fake!");
+
+    let new_user = CreateUser {
+        name: "Dana".to_string(),
+    };
+    let created = fake.directory.create_user(&new_user).await.expect("create");
+    assert_eq!(created.name, "Dana");
+
+    // Futures are `Send`, so calls can be spawned
+    let directory = std::sync::Arc::clone(&fake.directory);
+    let user = tokio::spawn(async move { directory.get_user(4).await })
+        .await
+        .expect("task")
+        .expect("get user");
+    assert_eq!(user.id, 4);
+}
+
+/// Test `dyn_trait` with both traits in scope: fully qualified calls pick
+/// the trait, as plain method calls on the client are ambiguous
+#[tokio::test]
+async fn test_dyn_trait_fully_qualified_calls() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(User {
+            id: 7,
+            name: "Qualified".to_string(),
+        }))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let client = DirectoryApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+
+    let user = <DirectoryApiClient as DirectoryApi>::get_user(&client, 7)
+        .await
+        .expect("get user");
+    assert_eq!(user.name, "Qualified");
+
+    let user = <DirectoryApiClient as DirectoryApiDyn>::get_user(&client, 7)
+        .await
+        .expect("get user");
+    assert_eq!(user.name, "Qualified");
+}
+
+// ============================================================================
+// Cfg-Gated Method Tests
+// ============================================================================
+
+/// Test `#[cfg]` on methods: `compiled_out` and its missing type disappear
+/// from the trait, the client, the dyn trait and the `timeout_for` names
+#[pincer(url = "http://localhost:9999", dyn_trait)]
+pub trait GatedApi {
+    #[cfg(test)]
+    #[get("/users/{id}")]
+    async fn get_user(&self, #[path] id: u64) -> pincer::Result<User>;
+
+    #[cfg(not(test))]
+    #[get("/reports")]
+    async fn compiled_out(&self) -> pincer::Result<Report>;
+}
+
+/// Implementing the trait does not require the compiled out method
+struct FakeGated;
+
+impl GatedApi for FakeGated {
+    async fn get_user(&self, id: u64) -> pincer::Result<User> {
+        Ok(User {
+            id,
+            name: "Gated".to_string(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_cfg_gated_methods() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/5"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(User {
+            id: 5,
+            name: "Eve".to_string(),
+        }))
+        .mount(&mock_server)
+        .await;
+
+    let client = GatedApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .timeout_for("get_user", std::time::Duration::from_secs(5))
+        .build()
+        .expect("client");
+    let user = GatedApi::get_user(&client, 5).await.expect("get user");
+    assert_eq!(user.name, "Eve");
+
+    let unknown = GatedApiClientBuilder::default()
+        .timeout_for("compiled_out", std::time::Duration::from_secs(5))
+        .build();
+    assert!(unknown.is_err());
+
+    let fake: std::sync::Arc<dyn GatedApiDyn> = std::sync::Arc::new(FakeGated);
+    assert_eq!(fake.get_user(1).await.expect("get user").name, "Gated");
+}
+
+// ============================================================================
+// Deprecated Method Tests
+// ============================================================================
+
+/// Test `#[deprecated]` on methods: forwarded to the trait and recorded in
+/// the parameter metadata
+#[pincer(mode = "impl_for(MetadataClient)")]
+pub trait LegacyApi {
+    #[deprecated(since = "1.2.0", note = "use `get_user_v2`")]
+    #[get("/v1/users/{id}")]
+    async fn get_user_v1(&self, #[path] id: u64) -> pincer::Result<()>;
+
+    #[get("/v2/users/{id}")]
+    async fn get_user_v2(&self, #[path] id: u64) -> pincer::Result<()>;
+}
+
+/// Client recording the parameter metadata of each request
+#[derive(Clone)]
+struct MetadataClient {
+    base_url: pincer::url::Url,
+    metadata: std::sync::Arc<std::sync::Mutex<Vec<pincer::ParameterMetadata>>>,
+}
+
+impl pincer::PincerClient for MetadataClient {
+    fn execute(
+        &self,
+        request: pincer::Request<bytes::Bytes>,
+    ) -> impl std::future::Future<Output = pincer::Result<pincer::Response<bytes::Bytes>>> + Send
+    {
+        if let Some(metadata) = request.extensions().get::<pincer::ParameterMetadata>() {
+            self.metadata
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .push(*metadata);
+        }
+        std::future::ready(Ok(pincer::Response::new(
+            200,
+            pincer::header::HeaderMap::new(),
+            bytes::Bytes::new(),
+        )))
+    }
+
+    fn base_url(&self) -> &pincer::url::Url {
+        &self.base_url
+    }
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn test_deprecated_method_metadata() {
+    let client = MetadataClient {
+        base_url: pincer::url::Url::parse("http://localhost").expect("url"),
+        metadata: std::sync::Arc::default(),
+    };
+
+    client.get_user_v1(1).await.expect("v1");
+    client.get_user_v2(1).await.expect("v2");
+
+    let metadata = client.metadata.lock().expect("lock");
+    let deprecation = metadata
+        .first()
+        .and_then(|meta| meta.deprecated)
+        .expect("v1 is deprecated");
+    assert_eq!(deprecation.since, Some("1.2.0"));
+    assert_eq!(deprecation.note, Some("use `get_user_v2`"));
+    assert!(metadata.get(1).expect("v2 metadata").deprecated.is_none());
+}
+
+// ============================================================================
+// Generated Struct Attribute Tests (pincer_client_attrs)
+// ============================================================================
+
+/// Test `#[pincer_client_attrs]`: derives `Debug` on the client and builder
+#[pincer(url = "http://localhost:9999")]
+#[pincer_client_attrs(derive(Debug), must_use)]
+pub trait DebugApi {
+    #[get("/health")]
+    async fn health(&self) -> pincer::Result<()>;
+}
+
+/// Same for the wrapper struct and its builder
+#[pincer(url = "http://localhost:9999", mode = "wrapper")]
+#[pincer_client_attrs(derive(Debug))]
+pub trait DebugWrapperApi {
+    #[get("/health")]
+    async fn health(&self) -> pincer::Result<()>;
+}
+
+#[test]
+fn test_client_attrs_derive_debug() {
+    let builder = DebugApiClientBuilder::default();
+    assert!(format!("{builder:?}").starts_with("DebugApiClientBuilder"));
+    let client = builder.build().expect("client");
+    assert!(format!("{client:?}").starts_with("DebugApiClient"));
+
+    let wrapper = DebugWrapperApiClient::new(pincer::HyperClient::new()).expect("wrapper client");
+    assert!(format!("{wrapper:?}").starts_with("DebugWrapperApiClient"));
+    let builder = DebugWrapperApiClient::<pincer::HyperClient>::builder();
+    assert!(format!("{builder:?}").starts_with("DebugWrapperApiClientBuilder"));
+}
+
+// ============================================================================
+// JSON Codec Tests (json = "...", #[body(serialize_with = "...")])
+// ============================================================================
+
+static CODEC_CALLS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+/// Codec counting its calls, delegating to `serde_json`
+struct CountingJson;
+
+impl pincer::BodyCodec for CountingJson {
+    fn content_type(&self) -> pincer::ContentType {
+        pincer::ContentType::Json
+    }
+
+    fn encode<T: Serialize>(&self, value: &T) -> pincer::Result<bytes::Bytes> {
+        CODEC_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        pincer::BodyCodec::encode(&pincer::SerdeJson::new(), value)
+    }
+
+    fn decode<T: serde::de::DeserializeOwned>(&self, bytes: &[u8]) -> pincer::Result<T> {
+        CODEC_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        pincer::BodyCodec::decode(&pincer::SerdeJson::new(), bytes)
+    }
+}
+
+fn serialize_shouting<S: serde::Serializer>(
+    user: &CreateUser,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    CreateUser {
+        name: user.name.to_uppercase(),
+    }
+    .serialize(serializer)
+}
+
+/// Test `json` and `serialize_with`: bodies go through the selected codec
+#[pincer(url = "http://localhost:9999", json = "CountingJson")]
+pub trait CodecApi {
+    #[post("/users")]
+    async fn create_user(
+        &self,
+        #[body(serialize_with = "serialize_shouting")] user: &CreateUser,
+    ) -> pincer::Result<User>;
+}
+
+#[tokio::test]
+async fn test_json_codec_and_serialize_with() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/users"))
+        .and(header("content-type", "application/json"))
+        .and(wiremock::matchers::body_json(
+            serde_json::json!({ "name": "ALICE" }),
+        ))
+        .respond_with(ResponseTemplate::new(201).set_body_json(User {
+            id: 1,
+            name: "ALICE".to_string(),
+        }))
+        .mount(&mock_server)
+        .await;
+
+    let client = CodecApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+    let user = CreateUser {
+        name: "alice".to_string(),
+    };
+    let created = client.create_user(&user).await.expect("create user");
+    assert_eq!(created.name, "ALICE");
+    assert_eq!(CODEC_CALLS.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
+
+const STRICT_JSON: pincer::SerdeJson = pincer::SerdeJson::new().big_integers_as_strings(true);
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Ledger {
+    balance: u64,
+}
+
+#[pincer(url = "http://localhost:9999", json = "STRICT_JSON")]
+pub trait LedgerApi {
+    #[put("/ledger")]
+    async fn update(&self, #[body] ledger: &Ledger) -> pincer::Result<()>;
+}
+
+#[tokio::test]
+async fn test_configured_json_codec() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("PUT"))
+        .and(path("/ledger"))
+        .and(wiremock::matchers::body_json(
+            serde_json::json!({ "balance": "18446744073709551615" }),
+        ))
+        .respond_with(ResponseTemplate::new(204))
+        .mount(&mock_server)
+        .await;
+
+    let client = LedgerApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+    client
+        .update(&Ledger { balance: u64::MAX })
+        .await
+        .expect("update");
+}
+
+#[pincer(url = "http://localhost:9999")]
+pub trait AccountApi {
+    #[get("/ledger")]
+    async fn ledger(&self) -> pincer::Result<Ledger>;
+
+    #[put("/ledger")]
+    async fn update(&self, #[body] ledger: &Ledger) -> pincer::Result<()>;
+}
+
+#[tokio::test]
+async fn test_client_json_codec() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/ledger"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(r#"{"balance":"18446744073709551615"}"#, "application/json"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/ledger"))
+        .and(wiremock::matchers::body_string(
+            "{\n  \"balance\": \"18446744073709551615\"\n}",
+        ))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    // Responses are decoded with the decoding options of the client codec
+    let lenient = AccountApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+    assert!(lenient.ledger().await.is_err());
+
+    let codec = pincer::SerdeJson::new()
+        .big_integers_as_strings(true)
+        .pretty(true);
+    let client = AccountApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .client(pincer::HyperClient::builder().json_codec(codec).build())
+        .build()
+        .expect("client");
+    let ledger = client.ledger().await.expect("ledger");
+    assert_eq!(ledger.balance, u64::MAX);
+
+    // JSON request bodies are re-encoded with its encoding options
+    client.update(&ledger).await.expect("update");
+}
+
+#[cfg(all(feature = "yaml", feature = "toml"))]
+mod config_formats {
+    use pincer::prelude::*;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{header, method, path},
+    };
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    pub struct ServiceConfig {
+        name: String,
+        replicas: u32,
+    }
+
+    #[pincer(url = "http://localhost:9999")]
+    pub trait ConfigApi {
+        #[get("/config.yaml")]
+        #[response(yaml)]
+        async fn yaml_config(&self) -> pincer::Result<ServiceConfig>;
+
+        #[get("/config.toml")]
+        #[response(toml)]
+        async fn toml_config(&self) -> pincer::Result<ServiceConfig>;
+    }
+
+    #[tokio::test]
+    async fn test_yaml_and_toml_responses() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/config.yaml"))
+            .and(header("accept", "application/yaml"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("name: api\nreplicas: 3\n", "application/yaml"),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/config.toml"))
+            .and(header("accept", "application/toml"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("name = \"worker\"\nreplicas = 2\n", "application/toml"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = ConfigApiClientBuilder::default()
+            .base_url(mock_server.uri())
+            .build()
+            .expect("client");
+        let config = client.yaml_config().await.expect("yaml");
+        assert_eq!(
+            config,
+            ServiceConfig {
+                name: "api".to_string(),
+                replicas: 3,
+            }
+        );
+        let config = client.toml_config().await.expect("toml");
+        assert_eq!(
+            config,
+            ServiceConfig {
+                name: "worker".to_string(),
+                replicas: 2,
+            }
+        );
+    }
+}
+
+#[cfg(feature = "csv")]
+mod csv_response {
+    use pincer::prelude::*;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{method, path},
+    };
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    pub struct PageViews {
+        page: String,
+        views: Option<u64>,
+    }
+
+    #[pincer(url = "http://localhost:9999")]
+    pub trait AnalyticsApi {
+        #[get("/exports/views.csv")]
+        #[response(csv, delimiter = ';')]
+        async fn page_views(&self) -> pincer::Result<Vec<PageViews>>;
+    }
+
+    #[tokio::test]
+    async fn test_csv_response() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/exports/views.csv"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/csv")
+                    .set_body_string(
+                        "page;views\r\n/home;42\r\n\"/a;b\";7\r\n\"/multi\nline\";\"\"\r\n",
+                    ),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let client = AnalyticsApiClientBuilder::default()
+            .base_url(mock_server.uri())
+            .build()
+            .expect("client");
+        let views = client.page_views().await.expect("page views");
+        assert_eq!(
+            views,
+            vec![
+                PageViews {
+                    page: "/home".to_string(),
+                    views: Some(42),
+                },
+                PageViews {
+                    page: "/a;b".to_string(),
+                    views: Some(7),
+                },
+                PageViews {
+                    page: "/multi\nline".to_string(),
+                    views: None,
+                },
+            ]
+        );
+    }
+}
+
+#[cfg(feature = "xml")]
+mod xml_bodies {
+    use pincer::prelude::*;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_string, header, method, path},
+    };
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename = "user")]
+    pub struct NewUser {
+        name: String,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct User {
+        id: u64,
+        name: String,
+    }
+
+    #[pincer(url = "http://localhost:9999", xml = "quick_xml")]
+    pub trait XmlApi {
+        #[post("/users")]
+        #[accept("application/xml")]
+        async fn create_user(&self, #[body(format = "xml")] user: &NewUser)
+        -> pincer::Result<User>;
+
+        #[get("/users/{id}")]
+        #[response(xml)]
+        async fn get_user(&self, #[path] id: u64) -> pincer::Result<User>;
+    }
+
+    #[tokio::test]
+    async fn test_xml_bodies() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/users"))
+            .and(header("content-type", "application/xml"))
+            .and(header("accept", "application/xml"))
+            .and(body_string("<user><name>alice</name></user>"))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .set_body_string("<user><id>1</id><name>alice</name></user>"),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/users/1"))
+            .and(header("accept", "application/xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<?xml version="1.0"?><user><id>1</id><name>alice &amp; co</name></user>"#,
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let client = XmlApiClientBuilder::default()
+            .base_url(mock_server.uri())
+            .build()
+            .expect("client");
+        let user = NewUser {
+            name: "alice".to_string(),
+        };
+        let created = client.create_user(&user).await.expect("create user");
+        assert_eq!(created.id, 1);
+        let fetched = client.get_user(1).await.expect("get user");
+        assert_eq!(fetched.id, 1);
+        assert_eq!(fetched.name, "alice & co");
+    }
+}
+
+#[cfg(feature = "cbor")]
+mod cbor_bodies {
+    use pincer::prelude::*;
+    use pincer::{BodyCodec, Ciborium};
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_bytes, header, method, path},
+    };
+
+    #[derive(Debug, Serialize)]
+    pub struct NewUser {
+        name: String,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct User {
+        id: u64,
+        name: String,
+    }
+
+    #[pincer(url = "http://localhost:9999", cbor = "ciborium")]
+    pub trait CborApi {
+        #[post("/users")]
+        #[response(cbor)]
+        async fn create_user(
+            &self,
+            #[body(format = "cbor")] user: &NewUser,
+        ) -> pincer::Result<User>;
+
+        #[get("/users/{id}")]
+        #[accept("application/cbor")]
+        async fn get_user(&self, #[path] id: u64) -> pincer::Result<User>;
+    }
+
+    #[tokio::test]
+    async fn test_cbor_bodies() {
+        let mock_server = MockServer::start().await;
+        let alice = Ciborium
+            .encode(&User {
+                id: 1,
+                name: "alice".to_string(),
+            })
+            .expect("encode");
+
+        Mock::given(method("POST"))
+            .and(path("/users"))
+            .and(header("content-type", "application/cbor"))
+            .and(header("accept", "application/cbor"))
+            // {"name": "alice"}
+            .and(body_bytes(b"\xa1\x64name\x65alice".to_vec()))
+            .respond_with(ResponseTemplate::new(201).set_body_bytes(alice.to_vec()))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/users/1"))
+            .and(header("accept", "application/cbor"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(alice.to_vec()))
+            .mount(&mock_server)
+            .await;
+
+        let client = CborApiClientBuilder::default()
+            .base_url(mock_server.uri())
+            .build()
+            .expect("client");
+        let user = NewUser {
+            name: "alice".to_string(),
+        };
+        let created = client.create_user(&user).await.expect("create user");
+        assert_eq!(created.id, 1);
+        let fetched = client.get_user(1).await.expect("get user");
+        assert_eq!(fetched.name, "alice");
+    }
+}
+
+// ============================================================================
+// Tests for strict JSON decoding
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct Profile {
+    name: String,
+}
+
+#[pincer(url = "http://localhost:9999")]
+pub trait ProfileApi {
+    #[get("/profile")]
+    async fn profile(&self) -> pincer::Result<Profile>;
+
+    #[get("/profile")]
+    #[response(deny_unknown_fields)]
+    async fn profile_strict(&self) -> pincer::Result<Profile>;
+
+    #[get("/team")]
+    #[response(pointer = "/data/members")]
+    async fn members(&self) -> pincer::Result<Vec<Profile>>;
+
+    #[get("/team")]
+    #[response(pointer = "/data/members/0", deny_unknown_fields)]
+    async fn leader(&self) -> pincer::Result<Profile>;
+}
+
+#[tokio::test]
+async fn test_strict_json_decoding() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/profile"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "name": "Alice",
+            "nickname": "al",
+        })))
+        .mount(&mock_server)
+        .await;
 
-    // snake_case fields are converted to camelCase
-    assert!(pairs.contains(&("searchQuery".to_string(), "rust".to_string())));
-    assert!(pairs.contains(&("pageNumber".to_string(), "1".to_string())));
-    // explicit rename overrides rename_all
-    assert!(pairs.contains(&("limit".to_string(), "10".to_string())));
+    let lenient = ProfileApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+    assert_eq!(lenient.profile().await.expect("lenient").name, "Alice");
+    let err = lenient.profile_strict().await.expect_err("strict method");
+    assert!(
+        err.to_string().contains("'nickname': unknown field"),
+        "{err}"
+    );
+
+    let strict = ProfileApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .client(pincer::HyperClient::builder().strict_json(true).build())
+        .build()
+        .expect("build client");
+    let err = strict.profile().await.expect_err("strict client");
+    assert!(
+        err.to_string().contains("'nickname': unknown field"),
+        "{err}"
+    );
 }
 
-// Test rename_all with kebab-case
-#[derive(Debug, Clone, Query)]
-#[query(rename_all = "kebab-case")]
-struct QueryDeriveKebabCase {
-    search_query: String,
-    page_number: u32,
-}
+#[tokio::test]
+async fn test_json_pointer_response() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/team"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "data": {
+                "members": [{ "name": "Alice", "role": "lead" }, { "name": "Bob" }],
+            },
+            "meta": { "total": 2 },
+        })))
+        .mount(&mock_server)
+        .await;
 
-#[test]
-fn test_query_derive_rename_all_kebab_case() {
-    let params = QueryDeriveKebabCase {
-        search_query: "rust".to_string(),
-        page_number: 1,
+    let client = ProfileApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("build client");
+    let members = client.members().await.expect("members");
+    let names: Vec<_> = members.iter().map(|member| member.name.as_str()).collect();
+    assert_eq!(names, ["Alice", "Bob"]);
+
+    let err = client.leader().await.expect_err("strict pointer");
+    assert!(
+        err.to_string()
+            .contains("'/data/members/0.role': unknown field"),
+        "{err}"
+    );
+}
+
+#[cfg(feature = "streaming")]
+mod json_lines {
+    use futures_util::StreamExt;
+    use pincer::prelude::*;
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{header, method, path},
     };
 
-    let pairs = params.to_query_pairs();
+    use super::User;
 
-    // snake_case fields are converted to kebab-case
-    assert!(pairs.contains(&("search-query".to_string(), "rust".to_string())));
-    assert!(pairs.contains(&("page-number".to_string(), "1".to_string())));
+    #[pincer(url = "http://localhost:9999")]
+    pub trait ExportApi {
+        #[get("/export/users")]
+        async fn export_users(&self) -> pincer::Result<pincer::JsonLines<User>>;
+
+        #[get("/export/archived")]
+        #[not_found_as_none]
+        async fn export_archived(&self) -> pincer::Result<Option<pincer::JsonLines<User>>>;
+    }
+
+    #[tokio::test]
+    async fn test_json_lines_response() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/export/users"))
+            .and(header("accept", "application/x-ndjson"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(
+                    "{\"id\":1,\"name\":\"alice\"}\n{\"id\":2,\"name\":\"bob\"}\n",
+                ),
+            )
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/export/archived"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let client = ExportApiClientBuilder::default()
+            .base_url(mock_server.uri())
+            .build()
+            .expect("client");
+        let users: Vec<User> = client
+            .export_users()
+            .await
+            .expect("export")
+            .map(|user| user.expect("user"))
+            .collect()
+            .await;
+        let names: Vec<_> = users.iter().map(|user| user.name.as_str()).collect();
+        assert_eq!(names, ["alice", "bob"]);
+
+        assert!(client.export_archived().await.expect("export").is_none());
+    }
 }
 
-// Test rename_all with SCREAMING_SNAKE_CASE
-#[derive(Debug, Clone, Query)]
-#[query(rename_all = "SCREAMING_SNAKE_CASE")]
-struct QueryDeriveScreamingSnake {
-    search_query: String,
-    page_number: u32,
+#[pincer(url = "http://localhost:9999")]
+pub trait ArticlesApi {
+    #[get("/articles/{id}")]
+    async fn get_article(
+        &self,
+        #[path] id: u64,
+        #[accept_language] languages: &[&str],
+    ) -> pincer::Result<User>;
 }
 
-#[test]
-fn test_query_derive_rename_all_screaming_snake() {
-    let params = QueryDeriveScreamingSnake {
-        search_query: "rust".to_string(),
-        page_number: 1,
-    };
+#[tokio::test]
+async fn test_accept_language_param() {
+    let mock_server = MockServer::start().await;
 
-    let pairs = params.to_query_pairs();
+    Mock::given(method("GET"))
+        .and(path("/articles/1"))
+        .and(wiremock::matchers::headers(
+            "accept-language",
+            vec!["fr-CH", "fr;q=0.9", "en;q=0.8"],
+        ))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("content-language", "fr-CH")
+                .set_body_json(serde_json::json!({"id": 1, "name": "Bonjour"})),
+        )
+        .mount(&mock_server)
+        .await;
 
-    // snake_case fields are converted to SCREAMING_SNAKE_CASE
-    assert!(pairs.contains(&("SEARCH_QUERY".to_string(), "rust".to_string())));
-    assert!(pairs.contains(&("PAGE_NUMBER".to_string(), "1".to_string())));
+    let client = ArticlesApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+    let article = client
+        .get_article(1, &["fr-CH", "fr", "en"])
+        .await
+        .expect("article");
+    assert_eq!(article.name, "Bonjour");
 }
 
-// ============================================================================
-// Wrapper Mode Tests (mode = "wrapper")
-// ============================================================================
-
-/// Test wrapper mode: generates a generic wrapper struct that works with any `PincerClient`
-#[pincer(url = "http://localhost:9999", mode = "wrapper")]
-pub trait WrapperModeApi {
+#[pincer(url = "http://localhost:9999", health = "/healthz")]
+pub trait MonitoredApi {
     #[get("/users/{id}")]
     async fn get_user(&self, #[path] id: u64) -> pincer::Result<User>;
+}
 
-    #[get("/health")]
-    async fn health(&self) -> pincer::Result<()>;
+#[pincer(url = "http://localhost:9999", mode = "wrapper", health = "/status")]
+pub trait MonitoredWrapperApi {
+    #[get("/users/{id}")]
+    async fn get_user(&self, #[path] id: u64) -> pincer::Result<User>;
 }
 
 #[tokio::test]
-async fn test_wrapper_mode_with_api_client() {
+async fn test_health_probe() {
     let mock_server = MockServer::start().await;
 
-    let user = User {
-        id: 42,
-        name: "Alice".to_string(),
-    };
-
     Mock::given(method("GET"))
-        .and(path("/users/42"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&user))
+        .and(path("/healthz"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/status"))
+        .respond_with(ResponseTemplate::new(503))
         .mount(&mock_server)
         .await;
 
-    // Create a HyperClient and wrap it with ApiClient
-    let http = pincer::HyperClient::new();
-    let api = pincer::ApiClient::new(http, mock_server.uri()).expect("api client");
+    let client = MonitoredApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+    assert!(client.health().await.expect("health"));
 
-    // Use the wrapper struct with the ApiClient and mock server URL
-    let base_url = pincer::url::Url::parse(&mock_server.uri()).expect("parse url");
-    let client = WrapperModeApiClient::with_base_url(api, base_url);
+    let mut watcher = client.watch_health(std::time::Duration::from_secs(30));
+    assert_eq!(watcher.changed().await, pincer::HealthStatus::Healthy);
 
-    // Use explicit trait method call to disambiguate from ImplOnlyApi blanket impl
-    let result = WrapperModeApi::get_user(&client, 42)
-        .await
-        .expect("get user");
-    assert_eq!(result.id, 42);
-    assert_eq!(result.name, "Alice");
+    let base_url: pincer::url::Url = mock_server.uri().parse().expect("url");
+    let http = pincer::ApiClient::with_url(pincer::HyperClient::new(), base_url.clone());
+    let wrapper = MonitoredWrapperApiClient::with_base_url(http, base_url);
+    assert!(!wrapper.health().await.expect("health"));
+}
+
+#[pincer(url = "http://localhost:9999")]
+pub trait CartApi {
+    #[get("/cart")]
+    async fn get_cart(
+        &self,
+        #[cookie("session_id")] session: &str,
+        #[cookie("page")] page: u32,
+    ) -> pincer::Result<User>;
 }
 
 #[tokio::test]
-async fn test_wrapper_mode_with_custom_base_url() {
+async fn test_cookie_params() {
     let mock_server = MockServer::start().await;
 
     Mock::given(method("GET"))
-        .and(path("/health"))
-        .respond_with(ResponseTemplate::new(200))
+        .and(path("/cart"))
+        .and(header("cookie", "session_id=a%20b; page=2"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"id": 1, "name": "cart"})),
+        )
         .mount(&mock_server)
         .await;
 
-    // Create ApiClient and wrap with WrapperModeApiClient using custom base URL
-    let http = pincer::HyperClient::new();
-    let api = pincer::ApiClient::new(http, mock_server.uri()).expect("api client");
-    let base_url = pincer::url::Url::parse(&mock_server.uri()).expect("parse url");
+    let client = CartApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+    let cart = client.get_cart("a b", 2).await.expect("cart");
+    assert_eq!(cart.name, "cart");
+}
 
-    let client = WrapperModeApiClient::with_base_url(api, base_url);
+#[pincer(url = "http://localhost:9999")]
+pub trait ExistenceApi {
+    #[head("/users/{id}")]
+    #[exists]
+    async fn user_exists(&self, #[path] id: u64) -> pincer::Result<bool>;
+}
 
-    client.health().await.expect("health check");
+#[tokio::test]
+async fn test_exists_head_method() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("HEAD"))
+        .and(path("/users/1"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("HEAD"))
+        .and(path("/users/2"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("HEAD"))
+        .and(path("/users/3"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&mock_server)
+        .await;
+
+    let client = ExistenceApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+
+    assert!(client.user_exists(1).await.expect("exists"));
+    assert!(!client.user_exists(2).await.expect("missing"));
+    let err = client.user_exists(3).await.expect_err("server error");
+    assert!(err.is_server_error(), "{err}");
 }
 
-#[test]
-fn test_wrapper_mode_struct_is_clone() {
-    // Verify the wrapper struct implements Clone when the inner client is Clone
-    let http = pincer::HyperClient::new();
-    let api = pincer::ApiClient::new(http, "http://localhost").expect("api client");
-    let client = WrapperModeApiClient::new(api);
-    let _cloned = client.clone();
+#[pincer(url = "http://localhost:9999")]
+pub trait CapabilitiesApi {
+    #[options("/users")]
+    async fn users_options(&self) -> pincer::Result<pincer::AllowedMethods>;
 }
 
-// ============================================================================
-// Impl-Only Mode Tests (mode = "impl_only")
-// ============================================================================
+#[tokio::test]
+async fn test_options_allowed_methods() {
+    let mock_server = MockServer::start().await;
 
-/// Test `impl_only` mode: generates a blanket impl for any `PincerClient`
-#[pincer(mode = "impl_only")]
-pub trait ImplOnlyApi {
-    #[get("/users/{id}")]
-    async fn get_user(&self, #[path] id: u64) -> pincer::Result<User>;
+    Mock::given(method("OPTIONS"))
+        .and(path("/users"))
+        .respond_with(
+            ResponseTemplate::new(204)
+                .insert_header("allow", "GET, POST, OPTIONS")
+                .insert_header("access-control-allow-origin", "*")
+                .insert_header("access-control-max-age", "90"),
+        )
+        .mount(&mock_server)
+        .await;
 
-    #[post("/users")]
-    async fn create_user(&self, user: &CreateUser) -> pincer::Result<User>;
+    let client = CapabilitiesApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+
+    let allowed = client.users_options().await.expect("options");
+    assert_eq!(allowed.allow, ["GET", "POST", "OPTIONS"]);
+    assert!(allowed.allows(pincer::Method::Post));
+    assert!(!allowed.allows(pincer::Method::Delete));
+    assert_eq!(allowed.allow_origin.as_deref(), Some("*"));
+    assert_eq!(allowed.max_age, Some(std::time::Duration::from_secs(90)));
+}
+
+#[pincer(url = "http://localhost:9999")]
+pub trait DocumentsApi {
+    #[put("/documents/{id}")]
+    async fn update_document(
+        &self,
+        #[path] id: u64,
+        #[if_match] etag: &pincer::EntityTag,
+        #[body] document: &User,
+    ) -> pincer::Result<User>;
 }
 
 #[tokio::test]
-async fn test_impl_only_mode_with_api_client() {
+async fn test_if_match_param() {
     let mock_server = MockServer::start().await;
 
-    let user = User {
-        id: 99,
-        name: "Bob".to_string(),
-    };
-
-    Mock::given(method("GET"))
-        .and(path("/users/99"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(&user))
+    Mock::given(method("PUT"))
+        .and(path("/documents/1"))
+        .and(header("if-match", "\"v1\""))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({"id": 1, "name": "new"})),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("PUT"))
+        .and(path("/documents/1"))
+        .respond_with(ResponseTemplate::new(412).insert_header("etag", "\"v2\""))
         .mount(&mock_server)
         .await;
 
-    // Create ApiClient - it directly implements ImplOnlyApi via blanket impl
-    let http = pincer::HyperClient::new();
-    let api = pincer::ApiClient::new(http, mock_server.uri()).expect("api client");
+    let client = DocumentsApiClientBuilder::default()
+        .base_url(mock_server.uri())
+        .build()
+        .expect("client");
+    let document = User {
+        id: 1,
+        name: "new".to_string(),
+    };
 
-    // ApiClient directly implements ImplOnlyApi
-    let result = api.get_user(99).await.expect("get user");
-    assert_eq!(result.id, 99);
-    assert_eq!(result.name, "Bob");
+    let updated = client
+        .update_document(1, &pincer::EntityTag::strong("v1"), &document)
+        .await
+        .expect("update");
+    assert_eq!(updated, document);
+
+    let err = client
+        .update_document(1, &pincer::EntityTag::strong("v0"), &document)
+        .await
+        .expect_err("stale entity tag");
+    assert!(err.is_precondition_failed(), "{err}");
+    assert_eq!(err.header("etag"), Some("\"v2\""));
+}
+
+// ============================================================================
+// Impl-For Mode Tests (mode = "impl_for(...)")
+// ============================================================================
+
+/// Test `impl_for` mode: implements the trait only for the listed types
+#[pincer(mode = "impl_for(pincer::ApiClient<pincer::HyperClient>)")]
+pub trait ImplForApi {
+    #[get("/accounts/{id}")]
+    async fn get_account(&self, #[path] id: u64) -> pincer::Result<User>;
 }
 
 #[tokio::test]
-async fn test_impl_only_mode_post_request() {
+async fn test_impl_for_mode_with_api_client() {
     let mock_server = MockServer::start().await;
 
-    let created_user = User {
-        id: 1,
-        name: "Charlie".to_string(),
+    let user = User {
+        id: 7,
+        name: "Dana".to_string(),
     };
 
-    Mock::given(method("POST"))
-        .and(path("/users"))
-        .and(header("content-type", "application/json"))
-        .respond_with(ResponseTemplate::new(201).set_body_json(&created_user))
+    Mock::given(method("GET"))
+        .and(path("/accounts/7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&user))
         .mount(&mock_server)
         .await;
 
     let http = pincer::HyperClient::new();
     let api = pincer::ApiClient::new(http, mock_server.uri()).expect("api client");
 
-    let new_user = CreateUser {
-        name: "Charlie".to_string(),
-    };
-    let result = api.create_user(&new_user).await.expect("create user");
-    assert_eq!(result.id, 1);
-    assert_eq!(result.name, "Charlie");
+    let result = api.get_account(7).await.expect("get account");
+    assert_eq!(result, user);
 }
 
 // ============================================================================
@@ -1499,7 +3611,9 @@ impl pincer::PincerClient for AuthenticatedClient {
         let inner = self.inner.clone();
         async move {
             let mut req = request;
-            req.headers_mut().insert("X-API-Key".to_string(), api_key);
+            let api_key = pincer::header::HeaderValue::try_from(api_key)
+                .map_err(|e| pincer::Error::invalid_request(e.to_string()))?;
+            req.headers_mut().insert("x-api-key", api_key);
             inner.execute(req).await
         }
     }
@@ -1563,3 +3677,57 @@ async fn test_custom_pincer_client_with_wrapper_mode() {
         .expect("get user");
     assert_eq!(result.id, 2);
 }
+
+// ============================================================================
+// Client Name Tests
+// ============================================================================
+
+#[pincer(mode = "impl_only", name = "directory")]
+pub trait NamedApi {
+    #[get("/people/{id}")]
+    async fn get_person(&self, #[path] id: u64) -> pincer::Result<()>;
+}
+
+/// A `PincerClient` recording the client name of each request
+#[derive(Clone)]
+struct RecordingClient {
+    base_url: pincer::url::Url,
+    names: std::sync::Arc<std::sync::Mutex<Vec<Option<&'static str>>>>,
+}
+
+impl pincer::PincerClient for RecordingClient {
+    fn execute(
+        &self,
+        request: pincer::Request<bytes::Bytes>,
+    ) -> impl std::future::Future<Output = pincer::Result<pincer::Response<bytes::Bytes>>> + Send
+    {
+        let name = request
+            .extensions()
+            .get::<pincer::ClientName>()
+            .map(pincer::ClientName::as_str);
+        self.names
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(name);
+        std::future::ready(Ok(pincer::Response::new(
+            200,
+            pincer::header::HeaderMap::new(),
+            bytes::Bytes::new(),
+        )))
+    }
+
+    fn base_url(&self) -> &pincer::url::Url {
+        &self.base_url
+    }
+}
+
+#[tokio::test]
+async fn test_client_name_extension() {
+    let client = RecordingClient {
+        base_url: pincer::url::Url::parse("http://localhost").expect("url"),
+        names: std::sync::Arc::default(),
+    };
+
+    client.get_person(1).await.expect("get person");
+    assert_eq!(*client.names.lock().expect("lock"), [Some("directory")]);
+}
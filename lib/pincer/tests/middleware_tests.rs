@@ -488,3 +488,250 @@ async fn test_decompression_passthrough() {
     let body = response.into_body();
     assert_eq!(body.as_ref(), b"plain text");
 }
+
+// ============================================================================
+// Cookie Jar Tests
+// ============================================================================
+
+/// Test that the cookie jar replays the session cookie set at login.
+#[tokio::test]
+async fn test_cookie_jar_keeps_session() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(
+            ResponseTemplate::new(204)
+                .insert_header("set-cookie", "session=abc123; Path=/; HttpOnly"),
+        )
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/orders"))
+        .and(header("cookie", "session=abc123"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let cookies = std::sync::Arc::new(pincer::CookieStore::new());
+    let client = HyperClient::builder()
+        .with_cookie_store(std::sync::Arc::clone(&cookies))
+        .build();
+
+    let url = url::Url::parse(&format!("{}/orders", mock_server.uri())).expect("url");
+    let response = client
+        .execute(Request::builder(Method::Get, url.clone()).build())
+        .await
+        .expect("response");
+    assert_eq!(response.status(), 404);
+
+    let login = url.join("/login").expect("url");
+    client
+        .execute(Request::builder(Method::Post, login).build())
+        .await
+        .expect("login");
+    assert_eq!(cookies.get(&url, "session").as_deref(), Some("abc123"));
+
+    let response = client
+        .execute(Request::builder(Method::Get, url).build())
+        .await
+        .expect("response");
+    assert!(response.is_success());
+}
+
+/// Test that concurrent requests share one client with a readiness-based layer.
+#[tokio::test]
+async fn test_concurrent_requests_with_concurrency_limit() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/shared"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(8)
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::builder().with_concurrency_limit(2).build();
+    let url = url::Url::parse(&format!("{}/shared", mock_server.uri())).expect("url");
+
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let client = client.clone();
+            let request = Request::builder(Method::Get, url.clone()).build();
+            tokio::spawn(async move { client.execute(request).await })
+        })
+        .collect();
+
+    for task in tasks {
+        let response = task.await.expect("task").expect("response");
+        assert!(response.is_success());
+    }
+}
+
+/// Test that the client readiness reflects the concurrency limit.
+#[tokio::test]
+async fn test_poll_ready_reflects_concurrency_limit() {
+    use std::time::Duration;
+    use tower::{Service, ServiceExt};
+
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/ready"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::builder().with_concurrency_limit(1).build();
+    let url = url::Url::parse(&format!("{}/ready", mock_server.uri())).expect("url");
+
+    let mut first = client.clone();
+    first.ready().await.expect("first ready");
+
+    // The only permit is held by `first`
+    let mut second = client.clone();
+    let blocked = tokio::time::timeout(Duration::from_millis(50), second.ready()).await;
+    assert!(blocked.is_err(), "second client should not be ready");
+
+    let response = first
+        .call(Request::builder(Method::Get, url).build())
+        .await
+        .expect("response");
+    assert!(response.is_success());
+
+    // The permit is released once the call completes
+    tokio::time::timeout(Duration::from_secs(1), second.ready())
+        .await
+        .expect("second ready")
+        .expect("ready");
+}
+
+/// Test the `map_request` / `map_response` builder helpers.
+#[tokio::test]
+async fn test_map_request_and_response() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/tenant"))
+        .and(header("x-tenant", "acme"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::builder()
+        .map_request(|mut request| {
+            request
+                .headers_mut()
+                .insert("x-tenant", "acme".parse().expect("value"));
+            request
+        })
+        .map_response(|mut response| {
+            response
+                .headers_mut()
+                .insert("x-mapped", "1".parse().expect("value"));
+            response
+        })
+        .build();
+
+    let url = url::Url::parse(&format!("{}/tenant", mock_server.uri())).expect("url");
+    let response = client
+        .execute(Request::builder(Method::Get, url).build())
+        .await
+        .expect("response");
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.header("x-mapped"), Some("1"));
+}
+
+/// Test that `layer_if` only applies the layer to matching requests.
+#[tokio::test]
+async fn test_layer_if_predicate() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/admin"))
+        .and(header("Authorization", "Bearer admin-token"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/public"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::builder()
+        .layer_if(
+            |request| request.url().path().starts_with("/admin"),
+            pincer::middleware::BearerAuthLayer::new("admin-token"),
+        )
+        .build();
+
+    let url = url::Url::parse(&format!("{}/admin", mock_server.uri())).expect("url");
+    let response = client
+        .execute(Request::builder(Method::Get, url).build())
+        .await
+        .expect("response");
+    assert_eq!(response.status(), 200);
+
+    let url = url::Url::parse(&format!("{}/public", mock_server.uri())).expect("url");
+    let response = client
+        .execute(Request::builder(Method::Get, url).build())
+        .await
+        .expect("response");
+    assert_eq!(response.status(), 200);
+
+    let requests = mock_server.received_requests().await.expect("requests");
+    let public = requests
+        .iter()
+        .find(|request| request.url.path() == "/public")
+        .expect("public request");
+    assert!(!public.headers.contains_key("authorization"));
+}
+
+/// Test that route layers apply their stack to the matching path templates only.
+#[tokio::test]
+async fn test_route_layer_by_path_template() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/1"))
+        .and(header("Authorization", "Bearer users-token"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/events/poll"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&mock_server)
+        .await;
+
+    let client = HyperClient::builder()
+        .layer(pincer::middleware::RouteLayer::new().route(
+            "/users/*",
+            pincer::middleware::BearerAuthLayer::new("users-token"),
+        ))
+        .build();
+
+    let url = url::Url::parse(&format!("{}/users/1", mock_server.uri())).expect("url");
+    let request = Request::builder(Method::Get, url)
+        .extension(pincer::PathTemplate::new("/users/{id}"))
+        .build();
+    let response = client.execute(request).await.expect("response");
+    assert_eq!(response.status(), 200);
+
+    let url = url::Url::parse(&format!("{}/events/poll", mock_server.uri())).expect("url");
+    let request = Request::builder(Method::Get, url)
+        .extension(pincer::PathTemplate::new("/events/poll"))
+        .build();
+    let response = client.execute(request).await.expect("response");
+    assert_eq!(response.status(), 200);
+
+    let requests = mock_server.received_requests().await.expect("requests");
+    let events = requests
+        .iter()
+        .find(|request| request.url.path() == "/events/poll")
+        .expect("events request");
+    assert!(!events.headers.contains_key("authorization"));
+}